@@ -0,0 +1,163 @@
+// arbitrage/aliases.rs - Conversion edges between tickers of one asset, and the caveats they carry
+use std::collections::HashMap;
+use serde::Serialize;
+
+use crate::markets::{self, AliasKind, AssetAlias};
+
+/// A leg of a cycle that converts between tickers of one asset instead of trading
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConversionCaveat {
+    pub from: String,
+    pub to: String,
+    pub asset: String,
+    pub kind: AliasKind,
+    /// The conversion also moves the asset between exchanges
+    pub transfer: bool,
+    pub note: &'static str,
+}
+
+fn split_node(node: &str) -> (&str, &str) {
+    node.rsplit_once('_').unwrap_or((node, ""))
+}
+
+/// Link every pair of nodes holding different tickers of one asset
+/// (`RNDR_kraken` and `RENDER_kraken`) at the alias's conversion cost, and
+/// derivatives to their underlying (`BTC-PERP_deribit` and `BTC_deribit`).
+/// Pairs on different exchanges are linked only with `cross_exchange`.
+/// Returns how many edges were written
+pub fn link_aliases(
+    graph: &mut [Vec<f64>],
+    currencies: &HashMap<String, usize>,
+    aliases: &[AssetAlias],
+    cross_exchange: bool,
+) -> usize {
+    let links = alias_edges(graph.len(), currencies, aliases, cross_exchange);
+    for &(u, v, weight) in &links {
+        graph[u][v] = weight;
+    }
+    links.len()
+}
+
+/// Remove the edges `link_aliases` wrote under these settings, before linking
+/// under new ones. Returns how many edges were removed
+pub fn unlink_aliases(
+    graph: &mut [Vec<f64>],
+    currencies: &HashMap<String, usize>,
+    aliases: &[AssetAlias],
+    cross_exchange: bool,
+) -> usize {
+    let links = alias_edges(graph.len(), currencies, aliases, cross_exchange);
+    for &(u, v, _) in &links {
+        graph[u][v] = f64::INFINITY;
+    }
+    links.len()
+}
+
+/// (from, to, weight) of every alias edge between `currencies`
+fn alias_edges(
+    size: usize,
+    currencies: &HashMap<String, usize>,
+    aliases: &[AssetAlias],
+    cross_exchange: bool,
+) -> Vec<(usize, usize, f64)> {
+    let mut edges = Vec::new();
+    for (from, &u) in currencies {
+        let (from_asset, from_exchange) = split_node(from);
+        for (to, &v) in currencies {
+            let (to_asset, to_exchange) = split_node(to);
+            if u >= size || v >= size || (from_exchange != to_exchange && !cross_exchange) {
+                continue;
+            }
+            if let Some(link) = markets::link(aliases, from_asset, to_asset) {
+                edges.push((u, v, -(1.0 - link.conversion_cost).ln()));
+            }
+        }
+    }
+    edges
+}
+
+/// The conversion legs along `path` ("RNDR_kraken -> RENDER_kraken -> USDT_kraken")
+pub fn conversion_caveats(path: &str, aliases: &[AssetAlias]) -> Vec<ConversionCaveat> {
+    let mut nodes: Vec<&str> = path.split(" -> ").map(str::trim).collect();
+    // Paths may repeat the start node to show the cycle closing
+    if nodes.len() > 1 && nodes.first() == nodes.last() {
+        nodes.pop();
+    }
+    
+    (0..nodes.len())
+        .filter_map(|i| {
+            let (from, to) = (nodes[i], nodes[(i + 1) % nodes.len()]);
+            let ((from_asset, from_exchange), (to_asset, to_exchange)) = (split_node(from), split_node(to));
+            let link = markets::link(aliases, from_asset, to_asset)?;
+            Some(ConversionCaveat {
+                from: from.to_string(),
+                to: to.to_string(),
+                asset: link.asset.to_string(),
+                kind: link.kind,
+                transfer: from_exchange != to_exchange,
+                note: link.kind.caveat(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_aliases_link_only_allowed_pairs() {
+        let currencies: HashMap<String, usize> = ["RNDR_kraken", "RENDER_kraken", "RENDER_binance", "USD_kraken"]
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.to_string(), i))
+            .collect();
+        let mut aliases = AssetAlias::known();
+        aliases[0].conversion_cost = 0.01;
+        
+        let mut graph = vec![vec![f64::INFINITY; 4]; 4];
+        assert_eq!(link_aliases(&mut graph, &currencies, &aliases, false), 2);
+        assert!((graph[0][1] + 0.99f64.ln()).abs() < 1e-12);
+        assert!((graph[1][0] + 0.99f64.ln()).abs() < 1e-12);
+        assert!(graph[0][2].is_infinite(), "RNDR_kraken -> RENDER_binance is cross-exchange");
+        assert!(graph[1][2].is_infinite(), "same ticker on two exchanges is not a conversion");
+        
+        let mut graph = vec![vec![f64::INFINITY; 4]; 4];
+        assert_eq!(link_aliases(&mut graph, &currencies, &aliases, true), 4);
+        assert!(graph[2][0].is_finite());
+        assert!(graph[3].iter().all(|w| w.is_infinite()));
+    }
+    
+    #[test]
+    fn test_caveats_name_each_conversion_leg() {
+        let aliases = AssetAlias::known();
+        let path = "USD_kraken -> RNDR_kraken -> RENDER_binance -> USDT_binance -> USD_kraken";
+        let caveats = conversion_caveats(path, &aliases);
+        assert_eq!(caveats.len(), 1);
+        assert_eq!((caveats[0].from.as_str(), caveats[0].to.as_str()), ("RNDR_kraken", "RENDER_binance"));
+        assert_eq!((caveats[0].kind, caveats[0].transfer), (AliasKind::Rename, true));
+        
+        assert!(conversion_caveats("BTC_binance -> ETH_binance -> USDT_binance", &aliases).is_empty());
+    }
+    
+    #[test]
+    fn test_derivatives_link_to_their_underlying() {
+        let currencies: HashMap<String, usize> = ["BTC_deribit", "BTC-PERP_deribit", "BTC-27DEC24_deribit", "ETH-PERP_deribit"]
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.to_string(), i))
+            .collect();
+        
+        let mut graph = vec![vec![f64::INFINITY; 4]; 4];
+        assert_eq!(link_aliases(&mut graph, &currencies, &[], false), 4);
+        assert_eq!((graph[0][1], graph[1][0], graph[2][0]), (0.0, 0.0, 0.0));
+        assert!(graph[1][2].is_infinite(), "two derivatives aren't linked to each other");
+        assert!(graph[3].iter().all(|w| w.is_infinite()));
+        
+        let caveats = conversion_caveats("USDC_deribit -> BTC_deribit -> BTC-PERP_deribit -> USDC_deribit", &[]);
+        assert_eq!(caveats.len(), 1);
+        assert_eq!((caveats[0].asset.as_str(), caveats[0].kind), ("BTC", AliasKind::Derivative));
+        assert!(markets::underlying("BTC-1JAN25").is_some());
+        assert!(markets::underlying("BTC-27DEC24-50000-C").is_none(), "options aren't quoted");
+    }
+}
@@ -0,0 +1,205 @@
+// arbitrage/allocation.rs - Target inventory per exchange/asset from opportunity heat
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use super::heat::HeatSample;
+
+const STEPS: usize = 1000; // Capital is handed out in 0.1% increments
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllocationConfig {
+    /// Current holdings, exchange -> asset -> value in the base currency
+    pub balances: HashMap<String, HashMap<String, f64>>,
+    /// Most of total capital to keep on one exchange (counterparty risk)
+    pub max_exchange_share: f64,
+    /// Most of total capital to hold in one asset across exchanges (price risk)
+    pub max_asset_share: f64,
+    #[serde(with = "humantime_serde")]
+    pub report_interval: Duration,
+}
+
+impl Default for AllocationConfig {
+    fn default() -> Self {
+        Self {
+            balances: HashMap::new(),
+            max_exchange_share: 0.5,
+            max_asset_share: 0.5,
+            report_interval: Duration::from_secs(3600),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AllocationTarget {
+    pub exchange: String,
+    pub asset: String,
+    pub current: f64,
+    pub target: f64,
+    /// Positive to move capital in, negative to move it out
+    pub change: f64,
+    /// Profit this holding would have captured over the window
+    pub expected_profit: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AllocationReport {
+    pub window_secs: u64,
+    pub total_capital: f64,
+    /// Captured profit over the window at current and at target holdings
+    pub current_expected_profit: f64,
+    pub expected_profit: f64,
+    /// Capital no observed opportunity could use within the risk limits
+    pub unallocated: f64,
+    /// Largest moves first
+    pub targets: Vec<AllocationTarget>,
+}
+
+/// Opportunities through one (asset, exchange) node, as (profit share, volume).
+/// A cycle needs inventory at every node, so its profit is split evenly across them
+type NodeSamples = Vec<(f64, f64)>;
+
+/// Profit captured holding `amount` at a node: each opportunity pays its
+/// share on as much of its volume as the holding covers
+fn captured(samples: &NodeSamples, amount: f64) -> f64 {
+    samples.iter().map(|(share, volume)| share * amount.min(*volume)).sum()
+}
+
+/// Recommend holdings that maximize captured profit over the heat window,
+/// subject to the per-exchange and per-asset caps.
+///
+/// Captured profit is concave in each holding, so capital is handed out
+/// greedily in small increments to whichever node gains most from it.
+pub fn optimize(samples: &[HeatSample], config: &AllocationConfig, window: Duration) -> AllocationReport {
+    let mut nodes: BTreeMap<(String, String), NodeSamples> = BTreeMap::new();
+    for sample in samples {
+        let share = sample.profit / sample.nodes.len().max(1) as f64;
+        for (asset, exchange) in &sample.nodes {
+            nodes.entry((exchange.clone(), asset.clone())).or_default().push((share, sample.max_volume));
+        }
+    }
+    let mut current: BTreeMap<(String, String), f64> = BTreeMap::new();
+    for (exchange, assets) in &config.balances {
+        for (asset, value) in assets {
+            current.insert((exchange.clone(), asset.clone()), *value);
+            nodes.entry((exchange.clone(), asset.clone())).or_default();
+        }
+    }
+    
+    let total: f64 = current.values().sum();
+    let step = total / STEPS as f64;
+    let exchange_cap = total * config.max_exchange_share;
+    let asset_cap = total * config.max_asset_share;
+    
+    let mut target: BTreeMap<(String, String), f64> = nodes.keys().map(|key| (key.clone(), 0.0)).collect();
+    let mut per_exchange: HashMap<String, f64> = HashMap::new();
+    let mut per_asset: HashMap<String, f64> = HashMap::new();
+    let mut remaining = total;
+    
+    while step > 0.0 && remaining >= step / 2.0 {
+        let best = nodes
+            .iter()
+            .filter(|((exchange, asset), _)| {
+                per_exchange.get(exchange).copied().unwrap_or(0.0) + step <= exchange_cap + 1e-9
+                    && per_asset.get(asset).copied().unwrap_or(0.0) + step <= asset_cap + 1e-9
+            })
+            .map(|(key, node)| {
+                let held = target[key];
+                (key, captured(node, held + step) - captured(node, held))
+            })
+            .filter(|(_, gain)| *gain > 0.0)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((key, _)) = best else { break };
+        
+        *target.get_mut(key).unwrap() += step;
+        *per_exchange.entry(key.0.clone()).or_default() += step;
+        *per_asset.entry(key.1.clone()).or_default() += step;
+        remaining -= step;
+    }
+    
+    let mut targets: Vec<AllocationTarget> = nodes
+        .iter()
+        .map(|(key, node)| {
+            let held = current.get(key).copied().unwrap_or(0.0);
+            AllocationTarget {
+                exchange: key.0.clone(),
+                asset: key.1.clone(),
+                current: held,
+                target: target[key],
+                change: target[key] - held,
+                expected_profit: captured(node, target[key]),
+            }
+        })
+        .filter(|t| t.current > 0.0 || t.target > 0.0)
+        .collect();
+    targets.sort_by(|a, b| b.change.abs().total_cmp(&a.change.abs()));
+    
+    AllocationReport {
+        window_secs: window.as_secs(),
+        total_capital: total,
+        current_expected_profit: nodes.iter().map(|(key, node)| captured(node, current.get(key).copied().unwrap_or(0.0))).sum(),
+        expected_profit: targets.iter().map(|t| t.expected_profit).sum(),
+        unallocated: remaining.max(0.0),
+        targets,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn sample(nodes: &[(&str, &str)], profit: f64, max_volume: f64) -> HeatSample {
+        HeatSample {
+            nodes: nodes.iter().map(|(a, e)| (a.to_string(), e.to_string())).collect(),
+            profit,
+            max_volume,
+        }
+    }
+    
+    #[test]
+    fn test_moves_capital_toward_heat_within_caps() {
+        let config = AllocationConfig {
+            balances: HashMap::from([
+                ("binance".to_string(), HashMap::from([("USDT".to_string(), 1000.0)])),
+                ("coinbase".to_string(), HashMap::from([("USD".to_string(), 1000.0)])),
+            ]),
+            max_exchange_share: 0.6,
+            max_asset_share: 0.5,
+            ..AllocationConfig::default()
+        };
+        // Every opportunity runs through kraken; none touches coinbase
+        let samples = vec![
+            sample(&[("BTC", "kraken"), ("USDT", "kraken"), ("USDT", "binance")], 0.03, 5000.0),
+            sample(&[("ETH", "kraken"), ("BTC", "kraken")], 0.02, 5000.0),
+        ];
+        
+        let report = optimize(&samples, &config, Duration::from_secs(3600));
+        assert_eq!(report.total_capital, 2000.0);
+        
+        let target = |exchange: &str, asset: &str| {
+            report.targets.iter().find(|t| t.exchange == exchange && t.asset == asset).map_or(0.0, |t| t.target)
+        };
+        let kraken = target("kraken", "BTC") + target("kraken", "USDT") + target("kraken", "ETH");
+        assert!((kraken - 1200.0).abs() < 1e-6, "kraken capped at 60%, got {}", kraken);
+        assert!(target("kraken", "BTC") <= 1000.0 + 1e-6);
+        assert_eq!(target("coinbase", "USD"), 0.0);
+        assert!(report.targets.iter().any(|t| t.exchange == "coinbase" && t.change == -1000.0));
+        assert!(report.expected_profit > report.current_expected_profit);
+        assert!(report.unallocated < 1e-6);
+    }
+    
+    #[test]
+    fn test_capital_beyond_opportunity_volume_stays_unallocated() {
+        let config = AllocationConfig {
+            balances: HashMap::from([("binance".to_string(), HashMap::from([("USDT".to_string(), 1000.0)]))]),
+            max_exchange_share: 1.0,
+            max_asset_share: 1.0,
+            ..AllocationConfig::default()
+        };
+        let samples = vec![sample(&[("BTC", "binance"), ("USDT", "binance")], 0.01, 100.0)];
+        
+        let report = optimize(&samples, &config, Duration::from_secs(3600));
+        assert!((report.unallocated - 800.0).abs() < 1e-6);
+        assert!((report.expected_profit - 1.0).abs() < 1e-9);
+    }
+}
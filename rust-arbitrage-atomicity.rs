@@ -0,0 +1,121 @@
+// arbitrage/atomicity.rs - How atomically a cycle executes: one venue, funded venues, or a transfer
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+use super::fees;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AtomicityClass {
+    SingleVenue, // Every leg on one exchange
+    MultiVenue,  // Several exchanges, each leg trading inventory already there
+    Transfer,    // Some asset has to move between exchanges mid-cycle
+}
+
+impl AtomicityClass {
+    pub const ALL: [AtomicityClass; 3] = [AtomicityClass::SingleVenue, AtomicityClass::MultiVenue, AtomicityClass::Transfer];
+    
+    pub fn name(self) -> &'static str {
+        match self {
+            AtomicityClass::SingleVenue => "single_venue",
+            AtomicityClass::MultiVenue => "multi_venue",
+            AtomicityClass::Transfer => "transfer",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AtomicityConfig {
+    /// Per class; raises the detection threshold for cycles of that class,
+    /// e.g. `transfer: 0.01` to ignore transfers below 1% net
+    pub min_profit_threshold: HashMap<AtomicityClass, f64>,
+}
+
+impl AtomicityConfig {
+    /// Whether `profit` clears the threshold for `atomicity`'s class
+    pub fn admits(&self, profit: f64, atomicity: &Atomicity) -> bool {
+        self.min_profit_threshold
+            .get(&atomicity.class)
+            .is_none_or(|&threshold| profit > threshold)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Atomicity {
+    pub class: AtomicityClass,
+    /// 1 for one venue; above 0.5 across funded venues, fewer venues higher;
+    /// at most 0.25 with transfers, more transfers lower
+    pub score: f64,
+    pub venues: usize,
+    /// Legs that move an asset to an exchange it isn't held on
+    pub transfers: Vec<String>,
+}
+
+impl Atomicity {
+    /// Classify `path` given current holdings (exchange -> asset -> value).
+    /// Legs between exchanges count as transfers unless the asset is already
+    /// held where the leg arrives; without any holdings configured, every
+    /// exchange is assumed funded
+    pub fn of(path: &str, balances: &HashMap<String, HashMap<String, f64>>) -> Self {
+        let nodes = fees::path_nodes(path);
+        let mut venues: Vec<&str> = nodes.iter().map(|&(_, exchange)| exchange).collect();
+        venues.sort_unstable();
+        venues.dedup();
+        
+        let funded = |asset: &str, exchange: &str| {
+            balances.is_empty() || balances.get(exchange).and_then(|held| held.get(asset)).is_some_and(|&value| value > 0.0)
+        };
+        let transfers: Vec<String> = (0..nodes.len())
+            .filter_map(|i| {
+                let (from, to) = (nodes[i], nodes[(i + 1) % nodes.len()]);
+                (from.1 != to.1 && !funded(to.0, to.1)).then(|| format!("{}_{} -> {}_{}", from.0, from.1, to.0, to.1))
+            })
+            .collect();
+        
+        let (class, score) = if !transfers.is_empty() {
+            (AtomicityClass::Transfer, 0.25 / transfers.len() as f64)
+        } else if venues.len() > 1 {
+            (AtomicityClass::MultiVenue, 0.5 + 0.25 / (venues.len() - 1) as f64)
+        } else {
+            (AtomicityClass::SingleVenue, 1.0)
+        };
+        Self {
+            class,
+            score,
+            venues: venues.len(),
+            transfers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_single_venue_beats_funded_venues_beats_transfers() {
+        let none = HashMap::new();
+        let triangle = Atomicity::of("BTC_binance -> ETH_binance -> USDT_binance", &none);
+        assert_eq!((triangle.class, triangle.score, triangle.venues), (AtomicityClass::SingleVenue, 1.0, 1));
+        
+        let cross = "USDT_binance -> BTC_binance -> BTC_kraken -> USDT_kraken";
+        let assumed = Atomicity::of(cross, &none);
+        assert_eq!((assumed.class, assumed.score), (AtomicityClass::MultiVenue, 0.75));
+        
+        // Holdings show BTC isn't on kraken, so it has to be sent there
+        let balances = HashMap::from([
+            ("binance".to_string(), HashMap::from([("USDT".to_string(), 1000.0)])),
+            ("kraken".to_string(), HashMap::from([("USDT".to_string(), 1000.0), ("BTC".to_string(), 0.0)])),
+        ]);
+        let moved = Atomicity::of(cross, &balances);
+        assert_eq!(moved.class, AtomicityClass::Transfer);
+        assert_eq!(moved.transfers, vec!["BTC_binance -> BTC_kraken"]);
+        assert!(moved.score < assumed.score && assumed.score < triangle.score);
+        
+        let config = AtomicityConfig {
+            min_profit_threshold: HashMap::from([(AtomicityClass::Transfer, 0.01)]),
+        };
+        assert!(!config.admits(0.005, &moved));
+        assert!(config.admits(0.011, &moved) && config.admits(0.005, &assumed));
+    }
+}
@@ -0,0 +1,240 @@
+// arbitrage/basis.rs - Cash-and-carry: spot bought against a dated future sold above it
+use std::collections::HashMap;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use super::fees;
+use super::types::OpportunityKind;
+use super::quotes::QuoteBook;
+use crate::markets::{self, AssetAlias, Fees};
+
+const YEAR: Duration = Duration::from_secs(365 * 24 * 3600);
+const DAY_MS: u64 = 24 * 3600 * 1000;
+/// Deribit, Binance and OKX settle dated futures at 08:00 UTC
+const SETTLEMENT_MS: u64 = 8 * 3600 * 1000;
+const MONTHS: [&str; 12] = ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+
+/// Dated futures quoted above spot, held to expiry for the basis
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BasisConfig {
+    pub enabled: bool,
+    /// Net return to expiry, scaled to a year, a trade must reach
+    pub min_annualized_yield: f64,
+    /// Futures closer to expiry than this are skipped; annualizing a few
+    /// hours' basis blows noise up into huge yields
+    #[serde(with = "humantime_serde")]
+    pub min_time_to_expiry: Duration,
+    /// Quote assets treated as one dollar, so a USD-settled future can be
+    /// hedged with USDT or USDC spot
+    pub dollar_quotes: Vec<String>,
+}
+
+impl Default for BasisConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_annualized_yield: 0.1,
+            min_time_to_expiry: Duration::from_secs(24 * 3600),
+            dollar_quotes: vec!["USD".to_string(), "USDT".to_string(), "USDC".to_string()],
+        }
+    }
+}
+
+/// Buy spot at the ask on one venue, sell a dated future at its bid on
+/// another (or the same), and hold both until the future settles
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CarryTrade {
+    pub kind: OpportunityKind,
+    /// "USDT_binance -> BTC_binance -> BTC-27DEC24_deribit -> USD_deribit"
+    pub path: String,
+    pub future: String,
+    pub future_exchange: String,
+    pub spot: String,
+    pub spot_exchange: String,
+    pub spot_price: f64,
+    pub future_price: f64,
+    /// Future over spot, as a fraction of spot
+    pub basis: f64,
+    /// Return to expiry after taker fees on both entries and on selling the
+    /// spot at expiry; the future settles without a trade
+    pub net_return: f64,
+    /// `net_return` over a year of such trades, without compounding
+    pub annualized_yield: f64,
+    pub expires_ms: u64,
+    pub days_to_expiry: f64,
+}
+
+/// Days since the Unix epoch of a civil date (Hinnant's days_from_civil)
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let yoe = year - era * 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// When a dated future ticker ("BTC-27DEC24", "ETH-3JAN25") settles, in
+/// Unix milliseconds; None for spot assets and perpetuals
+pub fn expiry_ms(ticker: &str) -> Option<u64> {
+    markets::underlying(ticker)?;
+    let (_, contract) = ticker.split_once('-')?;
+    let n = contract.len();
+    if contract == "PERP" || n < 6 {
+        return None;
+    }
+    let day: u64 = contract[..n - 5].parse().ok()?;
+    let month = MONTHS.iter().position(|month| *month == &contract[n - 5..n - 2])? as u64 + 1;
+    let year: u64 = 2000 + contract[n - 2..].parse::<u64>().ok()?;
+    (1..=31).contains(&day).then(|| days_from_civil(year, month, day) * DAY_MS + SETTLEMENT_MS)
+}
+
+/// Every future quoted in `book` against the cheapest spot of its underlying,
+/// where holding the pair to expiry pays at least `min_annualized_yield`
+/// after `fees`, best yield first
+pub fn carry_trades(
+    book: &QuoteBook,
+    config: &BasisConfig,
+    fees: &HashMap<String, Fees>,
+    aliases: &[AssetAlias],
+    now_ms: u64,
+) -> Vec<CarryTrade> {
+    if !config.enabled {
+        return Vec::new();
+    }
+    let is_dollar = |asset: &str| config.dollar_quotes.iter().any(|quote| quote.eq_ignore_ascii_case(asset));
+    let symbols = book.symbols();
+    let exchanges = book.exchanges();
+    let node = |asset: &str, exchange: &str| format!("{}_{}", asset, exchange);
+    let mut trades = Vec::new();
+    for future in &symbols {
+        let Some((contract, future_quote)) = future.split_once('/') else {
+            continue;
+        };
+        let (Some(underlying), Some(expires_ms)) = (markets::underlying(contract), expiry_ms(contract)) else {
+            continue;
+        };
+        let to_expiry = Duration::from_millis(expires_ms.saturating_sub(now_ms));
+        if to_expiry.is_zero() || to_expiry < config.min_time_to_expiry {
+            continue;
+        }
+        // Spot pairs of the underlying in the future's quote, or in any dollar for a dollar future
+        let hedges: Vec<(&String, &str)> = symbols
+            .iter()
+            .filter_map(|spot| {
+                let (base, quote) = spot.split_once('/')?;
+                let hedges = base == underlying && (quote == future_quote || (is_dollar(quote) && is_dollar(future_quote)));
+                hedges.then_some((spot, quote))
+            })
+            .collect();
+        for future_exchange in &exchanges {
+            let Some((bid, _)) = book.top(future_exchange, future).filter(|&(bid, _)| bid > 0.0) else {
+                continue;
+            };
+            let best = hedges
+                .iter()
+                .flat_map(|&(spot, spot_quote)| {
+                    exchanges.iter().filter_map(move |spot_exchange| {
+                        let (_, ask) = book.top(spot_exchange, spot).filter(|&(_, ask)| ask > 0.0)?;
+                        Some((spot, spot_quote, spot_exchange, ask))
+                    })
+                })
+                .min_by(|a, b| a.3.total_cmp(&b.3));
+            let Some((spot, spot_quote, spot_exchange, ask)) = best else {
+                continue;
+            };
+            
+            let path = [
+                node(spot_quote, spot_exchange),
+                node(underlying, spot_exchange),
+                node(contract, future_exchange),
+                node(future_quote, future_exchange),
+            ]
+            .join(" -> ");
+            let exit = fees
+                .get(spot_exchange.as_str())
+                .map_or(1.0, |rates| fees::trade_multiplier(rates, rates.taker, (underlying, spot_exchange), spot_quote, &symbols));
+            let net_return = bid / ask * fees::fee_multiplier(&path, fees, &symbols, aliases) * exit - 1.0;
+            let annualized_yield = net_return * (YEAR.as_secs_f64() / to_expiry.as_secs_f64());
+            if net_return <= 0.0 || annualized_yield < config.min_annualized_yield {
+                continue;
+            }
+            trades.push(CarryTrade {
+                kind: OpportunityKind::Basis,
+                path,
+                future: future.clone(),
+                future_exchange: future_exchange.clone(),
+                spot: spot.clone(),
+                spot_exchange: spot_exchange.clone(),
+                spot_price: ask,
+                future_price: bid,
+                basis: bid / ask - 1.0,
+                net_return,
+                annualized_yield,
+                expires_ms,
+                days_to_expiry: to_expiry.as_secs_f64() / 86_400.0,
+            });
+        }
+    }
+    trades.sort_by(|a, b| b.annualized_yield.total_cmp(&a.annualized_yield));
+    trades
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use crate::arbitrage::types::MarketTick;
+    
+    fn quote(book: &QuoteBook, exchange: &str, symbol: &str, bid: f64, ask: f64) {
+        book.update(&MarketTick {
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            bid,
+            ask,
+            last_price: (bid + ask) / 2.0,
+            volume: 1.0,
+            timestamp: Instant::now(),
+            sequence: 0,
+        });
+    }
+    
+    #[test]
+    fn test_carries_futures_above_spot_to_expiry() {
+        // 2024-12-27 08:00 UTC
+        assert_eq!(expiry_ms("BTC-27DEC24"), Some(1_735_286_400_000));
+        assert_eq!(expiry_ms("ETH-3JAN25"), Some(1_735_891_200_000));
+        assert_eq!(expiry_ms("BTC-PERP"), None);
+        assert_eq!(expiry_ms("BTC"), None);
+        
+        let book = QuoteBook::new();
+        quote(&book, "deribit", "BTC-27DEC24/USD", 102_000.0, 102_100.0);
+        quote(&book, "deribit", "ETH-3JAN25/USD", 3_000.0, 3_001.0);
+        quote(&book, "binance", "BTC/USDT", 99_900.0, 100_000.0);
+        quote(&book, "kraken", "BTC/USD", 100_050.0, 100_100.0);
+        quote(&book, "binance", "ETH/USDT", 3_010.0, 3_011.0);
+        let fees = HashMap::from([
+            ("binance".to_string(), Fees { maker: 0.001, taker: 0.001, charged_in: None }),
+            ("deribit".to_string(), Fees { maker: 0.0, taker: 0.0005, charged_in: None }),
+        ]);
+        // 36.5 days before BTC's expiry, a tenth of a year
+        let now_ms = 1_735_286_400_000 - 73 * DAY_MS / 2;
+        
+        let trades = carry_trades(&book, &BasisConfig::default(), &fees, &[], now_ms);
+        assert_eq!(trades.len(), 1, "ETH's future trades below spot");
+        let trade = &trades[0];
+        assert_eq!(trade.path, "USDT_binance -> BTC_binance -> BTC-27DEC24_deribit -> USD_deribit");
+        assert_eq!((trade.spot_exchange.as_str(), trade.spot_price), ("binance", 100_000.0));
+        assert!((trade.basis - 0.02).abs() < 1e-12);
+        let net = 1.02 * 0.999 * 0.9995 * 0.999 - 1.0;
+        assert!((trade.net_return - net).abs() < 1e-12);
+        assert!((trade.annualized_yield - net * 10.0).abs() < 1e-9);
+        assert_eq!(serde_json::to_value(trade).unwrap()["kind"], "basis");
+        
+        // Near expiry the yield is noise; a high bar filters the rest
+        assert!(carry_trades(&book, &BasisConfig::default(), &fees, &[], 1_735_286_400_000 - DAY_MS / 2).is_empty());
+        let strict = BasisConfig { min_annualized_yield: 0.5, ..BasisConfig::default() };
+        assert!(carry_trades(&book, &strict, &fees, &[], now_ms).is_empty());
+    }
+}
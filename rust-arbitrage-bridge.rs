@@ -0,0 +1,132 @@
+// arbitrage/bridge.rs - Pairs a venue doesn't list, priced through a bridge asset
+use serde::{Deserialize, Serialize};
+
+use super::quotes::QuoteBook;
+
+/// Compare a configured pair against venues that only list it in two steps
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BridgeConfig {
+    /// Assets an unlisted pair may be converted through; empty turns it off
+    pub assets: Vec<String>,
+}
+
+impl Default for BridgeConfig {
+    fn default() -> Self {
+        Self {
+            assets: vec!["USDT".to_string(), "BTC".to_string(), "ETH".to_string()],
+        }
+    }
+}
+
+/// A spread between a venue's direct quote for a pair and another venue's
+/// rate through a bridge asset, as a path of nodes that closes on itself
+#[derive(Debug, Clone, PartialEq)]
+pub struct BridgedSpread {
+    /// "USDT_kraken -> ETH_kraken -> BTC_kraken -> BTC_binance -> USDT_binance":
+    /// both steps of the bridged conversion are legs of their own
+    pub nodes: Vec<String>,
+    /// Return before fees
+    pub gross_profit: f64,
+}
+
+impl BridgedSpread {
+    pub fn path(&self) -> String {
+        self.nodes.join(" -> ")
+    }
+}
+
+/// What one unit of `from` fetches in `to` on `exchange`, crossing the spread:
+/// the bid of `from/to`, or one over the ask of `to/from`
+fn rate(book: &QuoteBook, exchange: &str, from: &str, to: &str) -> Option<f64> {
+    if let Some((bid, _)) = book.top(exchange, &format!("{}/{}", from, to)) {
+        return (bid > 0.0).then_some(bid);
+    }
+    let (_, ask) = book.top(exchange, &format!("{}/{}", to, from))?;
+    (ask > 0.0).then(|| 1.0 / ask)
+}
+
+/// For every `symbols` pair A/C, each venue quoting it directly against each
+/// venue that doesn't but quotes A and C against one of `bridges`: buying on
+/// one and selling on the other, either way round, where that pays before fees
+pub fn bridged_spreads(book: &QuoteBook, symbols: &[String], bridges: &[String]) -> Vec<BridgedSpread> {
+    let exchanges = book.exchanges();
+    let mut spreads = Vec::new();
+    for symbol in symbols {
+        let Some((base, quote)) = symbol.split_once('/') else {
+            continue;
+        };
+        let node = |asset: &str, exchange: &str| format!("{}_{}", asset, exchange);
+        for direct in &exchanges {
+            let Some((bid, ask)) = book.top(direct, symbol).filter(|&(bid, ask)| bid > 0.0 && ask > 0.0) else {
+                continue;
+            };
+            for venue in exchanges.iter().filter(|venue| book.top(venue, symbol).is_none()) {
+                for bridge in bridges.iter().filter(|bridge| bridge.as_str() != base && bridge.as_str() != quote) {
+                    // Sell A through the bridge on the venue after buying it directly
+                    if let (Some(to_bridge), Some(to_quote)) = (rate(book, venue, base, bridge), rate(book, venue, bridge, quote)) {
+                        spreads.push(BridgedSpread {
+                            nodes: vec![node(quote, direct), node(base, direct), node(base, venue), node(bridge, venue), node(quote, venue)],
+                            gross_profit: to_bridge * to_quote / ask - 1.0,
+                        });
+                    }
+                    // Buy A through the bridge on the venue and sell it directly
+                    if let (Some(to_bridge), Some(to_base)) = (rate(book, venue, quote, bridge), rate(book, venue, bridge, base)) {
+                        spreads.push(BridgedSpread {
+                            nodes: vec![node(quote, venue), node(bridge, venue), node(base, venue), node(base, direct), node(quote, direct)],
+                            gross_profit: to_bridge * to_base * bid - 1.0,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    spreads.retain(|spread| spread.gross_profit > 0.0);
+    spreads
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use crate::arbitrage::types::MarketTick;
+    
+    fn quote(book: &QuoteBook, exchange: &str, symbol: &str, bid: f64, ask: f64) {
+        book.update(&MarketTick {
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            bid,
+            ask,
+            last_price: (bid + ask) / 2.0,
+            volume: 1.0,
+            timestamp: Instant::now(),
+            sequence: 0,
+        });
+    }
+    
+    #[test]
+    fn test_prices_unlisted_pair_through_bridge() {
+        let book = QuoteBook::new();
+        // Binance lists SOL/BTC; kraken only SOL/ETH and ETH/BTC, and ETH/BTC the other way up
+        quote(&book, "binance", "SOL/BTC", 0.00300, 0.00301);
+        quote(&book, "kraken", "SOL/ETH", 0.0620, 0.0621);
+        quote(&book, "kraken", "ETH/BTC", 0.0500, 0.0501);
+        let symbols = vec!["SOL/BTC".to_string()];
+        let bridges = vec!["ETH".to_string(), "USDT".to_string()];
+        
+        // Kraken's implied SOL/BTC bid is 0.062 x 0.05 = 0.0031, over binance's ask
+        let spreads = bridged_spreads(&book, &symbols, &bridges);
+        assert_eq!(spreads.len(), 1);
+        assert_eq!(spreads[0].path(), "BTC_binance -> SOL_binance -> SOL_kraken -> ETH_kraken -> BTC_kraken");
+        assert!((spreads[0].gross_profit - (0.0031 / 0.00301 - 1.0)).abs() < 1e-12);
+        
+        // Cheap enough through the bridge to sell on binance instead
+        quote(&book, "kraken", "SOL/ETH", 0.0580, 0.0581);
+        quote(&book, "kraken", "BTC/ETH", 19.9, 20.0);
+        let spreads = bridged_spreads(&book, &symbols, &bridges);
+        assert_eq!(spreads.len(), 1);
+        assert_eq!(spreads[0].path(), "BTC_kraken -> ETH_kraken -> SOL_kraken -> SOL_binance -> BTC_binance");
+        assert!((spreads[0].gross_profit - (19.9 / 0.0581 * 0.003 - 1.0)).abs() < 1e-12);
+        
+        assert!(bridged_spreads(&book, &symbols, &[]).is_empty());
+    }
+}
@@ -0,0 +1,171 @@
+// arbitrage/budget.rs - Per-stage latency budget enforcement
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    /// Raw exchange frame -> canonical tick
+    Parse,
+    /// `update_price` hand-off into the engine
+    Ingest,
+    /// One full detection pass
+    Detect,
+    /// Opportunity -> alert delivered
+    Alert,
+}
+
+impl PipelineStage {
+    pub const ALL: [PipelineStage; 4] = [
+        PipelineStage::Parse,
+        PipelineStage::Ingest,
+        PipelineStage::Detect,
+        PipelineStage::Alert,
+    ];
+    
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyBudgets {
+    #[serde(with = "humantime_serde")]
+    pub parse: Duration,
+    #[serde(with = "humantime_serde")]
+    pub ingest: Duration,
+    #[serde(with = "humantime_serde")]
+    pub detect: Duration,
+    #[serde(with = "humantime_serde")]
+    pub alert: Duration,
+}
+
+impl Default for LatencyBudgets {
+    fn default() -> Self {
+        Self {
+            parse: Duration::from_micros(50),
+            ingest: Duration::from_micros(100),
+            detect: Duration::from_millis(2),
+            alert: Duration::from_millis(10),
+        }
+    }
+}
+
+impl LatencyBudgets {
+    pub fn for_stage(&self, stage: PipelineStage) -> Duration {
+        match stage {
+            PipelineStage::Parse => self.parse,
+            PipelineStage::Ingest => self.ingest,
+            PipelineStage::Detect => self.detect,
+            PipelineStage::Alert => self.alert,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StageBudgetReport {
+    pub stage: PipelineStage,
+    pub budget_us: f64,
+    pub samples: u64,
+    pub violations: u64,
+    pub violation_rate: f64,
+    pub last_us: f64,
+    pub max_us: f64,
+}
+
+#[derive(Default)]
+struct StageCounters {
+    samples: AtomicU64,
+    violations: AtomicU64,
+    last_ns: AtomicU64,
+    max_ns: AtomicU64,
+}
+
+/// Lock-free per-stage latency accounting against configured budgets
+pub struct BudgetTracker {
+    budgets: LatencyBudgets,
+    stages: [StageCounters; 4],
+}
+
+impl BudgetTracker {
+    pub fn new(budgets: LatencyBudgets) -> Self {
+        Self {
+            budgets,
+            stages: Default::default(),
+        }
+    }
+    
+    /// Record one stage latency; returns false if it blew the budget
+    pub fn record(&self, stage: PipelineStage, latency: Duration) -> bool {
+        let counters = &self.stages[stage.index()];
+        let nanos = latency.as_nanos() as u64;
+        
+        counters.samples.fetch_add(1, Ordering::Relaxed);
+        counters.last_ns.store(nanos, Ordering::Relaxed);
+        counters.max_ns.fetch_max(nanos, Ordering::Relaxed);
+        
+        let budget = self.budgets.for_stage(stage);
+        if latency > budget {
+            counters.violations.fetch_add(1, Ordering::Relaxed);
+            debug!("{:?} stage took {:?}, budget {:?}", stage, latency, budget);
+            return false;
+        }
+        true
+    }
+    
+    /// Per-stage report, worst violation rate first
+    pub fn report(&self) -> Vec<StageBudgetReport> {
+        let mut reports: Vec<StageBudgetReport> = PipelineStage::ALL
+            .iter()
+            .map(|&stage| {
+                let counters = &self.stages[stage.index()];
+                let samples = counters.samples.load(Ordering::Relaxed);
+                let violations = counters.violations.load(Ordering::Relaxed);
+                
+                StageBudgetReport {
+                    stage,
+                    budget_us: self.budgets.for_stage(stage).as_nanos() as f64 / 1000.0,
+                    samples,
+                    violations,
+                    violation_rate: if samples > 0 { violations as f64 / samples as f64 } else { 0.0 },
+                    last_us: counters.last_ns.load(Ordering::Relaxed) as f64 / 1000.0,
+                    max_us: counters.max_ns.load(Ordering::Relaxed) as f64 / 1000.0,
+                }
+            })
+            .collect();
+        
+        reports.sort_by(|a, b| b.violation_rate.total_cmp(&a.violation_rate));
+        reports
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_violations_are_counted_per_stage() {
+        let tracker = BudgetTracker::new(LatencyBudgets::default());
+        
+        assert!(tracker.record(PipelineStage::Parse, Duration::from_micros(10)));
+        assert!(!tracker.record(PipelineStage::Parse, Duration::from_micros(80)));
+        assert!(tracker.record(PipelineStage::Detect, Duration::from_micros(500)));
+        
+        let report = tracker.report();
+        assert_eq!(report[0].stage, PipelineStage::Parse);
+        assert_eq!(report[0].samples, 2);
+        assert_eq!(report[0].violations, 1);
+        assert_eq!(report[0].violation_rate, 0.5);
+        assert_eq!(report[0].max_us, 80.0);
+        assert_eq!(report[0].last_us, 80.0);
+    }
+    
+    #[test]
+    fn test_untouched_stages_report_zero() {
+        let report = BudgetTracker::new(LatencyBudgets::default()).report();
+        assert_eq!(report.len(), 4);
+        assert!(report.iter().all(|r| r.samples == 0 && r.violation_rate == 0.0));
+    }
+}
@@ -0,0 +1,98 @@
+// arbitrage/cluster.rs - Grouping of correlated opportunities from one detection pass
+use std::collections::BTreeSet;
+
+use super::types::ArbitrageOpportunity;
+
+/// Near-identical opportunities collapsed behind their most profitable member
+#[derive(Debug, Clone)]
+pub struct OpportunityCluster {
+    pub representative: ArbitrageOpportunity,
+    pub members: usize,
+    nodes: BTreeSet<String>,
+}
+
+/// Greedily cluster opportunities whose node sets (currency_exchange pairs)
+/// overlap by at least `similarity` (Jaccard index, 0..=1).
+///
+/// Bellman-Ford run from every source reports the same cycle rotated and
+/// with small detours; these all describe one economic trade. With
+/// `similarity = 1.0` only exact rotations are merged.
+pub fn cluster_opportunities(
+    mut opportunities: Vec<ArbitrageOpportunity>,
+    similarity: f64,
+) -> Vec<OpportunityCluster> {
+    opportunities.sort_by(|a, b| b.profit_percentage.total_cmp(&a.profit_percentage));
+    
+    let mut clusters: Vec<OpportunityCluster> = Vec::new();
+    for opp in opportunities {
+        let nodes = path_nodes(&opp.path);
+        
+        match clusters
+            .iter_mut()
+            .find(|cluster| jaccard(&cluster.nodes, &nodes) >= similarity)
+        {
+            Some(cluster) => cluster.members += 1,
+            None => clusters.push(OpportunityCluster {
+                representative: opp,
+                members: 1,
+                nodes,
+            }),
+        }
+    }
+    
+    clusters
+}
+
+fn path_nodes(path: &str) -> BTreeSet<String> {
+    path.split(" -> ").map(|node| node.trim().to_string()).collect()
+}
+
+fn jaccard(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_rotations_collapse_to_best_representative() {
+        let clusters = cluster_opportunities(
+            vec![
+                ArbitrageOpportunity::sample("A_binance -> B_binance -> C_binance", 0.010),
+                ArbitrageOpportunity::sample("B_binance -> C_binance -> A_binance", 0.012),
+                ArbitrageOpportunity::sample("C_binance -> A_binance -> B_binance", 0.011),
+            ],
+            1.0,
+        );
+        
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].members, 3);
+        assert_eq!(clusters[0].representative.profit_percentage, 0.012);
+    }
+    
+    #[test]
+    fn test_small_detours_join_cluster_at_lower_similarity() {
+        let opps = vec![
+            ArbitrageOpportunity::sample("BTC_binance -> USDT_binance -> USDT_kraken -> BTC_kraken", 0.02),
+            ArbitrageOpportunity::sample("BTC_binance -> USDT_binance -> USDC_kraken -> USDT_kraken -> BTC_kraken", 0.015),
+            ArbitrageOpportunity::sample("ETH_coinbase -> USD_coinbase -> SOL_coinbase", 0.01),
+        ];
+        
+        assert_eq!(cluster_opportunities(opps.clone(), 1.0).len(), 3);
+        
+        let clusters = cluster_opportunities(opps, 0.6);
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].members, 2);
+        assert_eq!(clusters[1].representative.path, "ETH_coinbase -> USD_coinbase -> SOL_coinbase");
+    }
+    
+    #[test]
+    fn test_empty_input() {
+        assert!(cluster_opportunities(Vec::new(), 0.5).is_empty());
+    }
+}
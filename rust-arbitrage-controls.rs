@@ -0,0 +1,141 @@
+// arbitrage/controls.rs - Operator controls adjustable while the engine runs
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::Instant;
+
+use super::types::ArbitrageOpportunity;
+
+/// Settings operators change at runtime (bot commands, control API) without a
+/// restart; the detector reads them every pass
+pub struct RuntimeControls {
+    min_profit_bits: AtomicU64, // f64 bits
+    paused_exchanges: RwLock<BTreeSet<String>>,
+    snoozed_pairs: RwLock<HashMap<String, Instant>>, // Normalized pair -> snoozed until
+}
+
+impl RuntimeControls {
+    pub fn new(min_profit_threshold: f64) -> Self {
+        Self {
+            min_profit_bits: AtomicU64::new(min_profit_threshold.to_bits()),
+            paused_exchanges: RwLock::new(BTreeSet::new()),
+            snoozed_pairs: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    pub fn min_profit_threshold(&self) -> f64 {
+        f64::from_bits(self.min_profit_bits.load(Ordering::Relaxed))
+    }
+    
+    pub fn set_min_profit_threshold(&self, threshold: f64) {
+        self.min_profit_bits.store(threshold.to_bits(), Ordering::Relaxed);
+    }
+    
+    /// Stop alerting on opportunities that touch `exchange`; false if already paused
+    pub fn pause_exchange(&self, exchange: &str) -> bool {
+        self.paused_exchanges.write().unwrap().insert(exchange.to_lowercase())
+    }
+    
+    /// False if `exchange` wasn't paused
+    pub fn resume_exchange(&self, exchange: &str) -> bool {
+        self.paused_exchanges.write().unwrap().remove(&exchange.to_lowercase())
+    }
+    
+    pub fn paused_exchanges(&self) -> Vec<String> {
+        self.paused_exchanges.read().unwrap().iter().cloned().collect()
+    }
+    
+    /// Stop alerting on opportunities that trade `pair` ("ETH/BTC", either
+    /// order) until `until`
+    pub fn snooze_pair(&self, pair: &str, until: Instant) {
+        let (base, quote) = pair.split_once('/').unwrap_or((pair, ""));
+        let now = Instant::now();
+        let mut snoozed = self.snoozed_pairs.write().unwrap();
+        snoozed.retain(|_, expires| now < *expires);
+        snoozed.insert(pair_key(base, quote), until);
+    }
+    
+    /// Whether alerts for `opportunity` are paused or snoozed as of its detection
+    pub fn is_suppressed(&self, opportunity: &ArbitrageOpportunity) -> bool {
+        {
+            let paused = self.paused_exchanges.read().unwrap();
+            if !paused.is_empty() && opportunity.exchanges.iter().any(|e| paused.contains(&e.to_lowercase())) {
+                return true;
+            }
+        }
+        
+        let snoozed = self.snoozed_pairs.read().unwrap();
+        if snoozed.is_empty() {
+            return false;
+        }
+        path_pairs(&opportunity.path)
+            .iter()
+            .any(|pair| snoozed.get(pair).is_some_and(|&until| opportunity.detected_at < until))
+    }
+}
+
+/// Asset pairs traded along `path`, e.g. "BTC_binance -> ETH_binance -> BTC_kraken"
+/// trades BTC/ETH; transfers of one asset between exchanges aren't pairs
+pub fn path_pairs(path: &str) -> Vec<String> {
+    let assets: Vec<&str> = path
+        .split(" -> ")
+        .map(|node| node.trim().split_once('_').map_or(node.trim(), |(asset, _)| asset))
+        .collect();
+    let mut pairs = Vec::new();
+    for leg in assets.windows(2) {
+        let pair = pair_key(leg[0], leg[1]);
+        if !leg[0].eq_ignore_ascii_case(leg[1]) && !pairs.contains(&pair) {
+            pairs.push(pair);
+        }
+    }
+    pairs
+}
+
+/// Direction-independent key, so ETH/BTC and BTC/ETH snooze the same market
+fn pair_key(a: &str, b: &str) -> String {
+    let (a, b) = (a.to_uppercase(), b.to_uppercase());
+    if a <= b {
+        format!("{}/{}", a, b)
+    } else {
+        format!("{}/{}", b, a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    
+    #[test]
+    fn test_threshold_and_pause_round_trip() {
+        let controls = RuntimeControls::new(0.001);
+        controls.set_min_profit_threshold(0.002);
+        assert_eq!(controls.min_profit_threshold(), 0.002);
+        
+        let opp = ArbitrageOpportunity::sample("BTC_binance -> BTC_kraken", 0.01);
+        assert!(!controls.is_suppressed(&opp));
+        
+        assert!(controls.pause_exchange("Kraken"));
+        assert!(!controls.pause_exchange("kraken"));
+        assert!(controls.is_suppressed(&opp));
+        assert_eq!(controls.paused_exchanges(), vec!["kraken"]);
+        
+        assert!(controls.resume_exchange("kraken"));
+        assert!(!controls.is_suppressed(&opp));
+    }
+    
+    #[test]
+    fn test_snoozed_pair_suppresses_until_expiry() {
+        let controls = RuntimeControls::new(0.001);
+        let opp = ArbitrageOpportunity::sample("BTC_binance -> ETH_binance -> ETH_kraken -> BTC_kraken", 0.01);
+        assert_eq!(path_pairs(&opp.path), vec!["BTC/ETH"]);
+        
+        let detected = opp.detected_at;
+        controls.snooze_pair("usdt/btc", detected + Duration::from_secs(3600));
+        assert!(!controls.is_suppressed(&opp));
+        controls.snooze_pair("ETH/BTC", detected + Duration::from_secs(3600));
+        assert!(controls.is_suppressed(&opp));
+        controls.snooze_pair("BTC/ETH", detected);
+        assert!(!controls.is_suppressed(&opp), "the snooze ended as it was detected");
+    }
+}
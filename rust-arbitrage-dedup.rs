@@ -0,0 +1,235 @@
+// arbitrage/dedup.rs - Repeated-opportunity suppression in constant memory
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+use super::types::ArbitrageOpportunity;
+
+/// Alert an opportunity once per window rather than on every detection pass
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DedupConfig {
+    /// How long a fingerprint is remembered, at least; zero turns dedup off
+    #[serde(with = "humantime_serde")]
+    pub window: Duration,
+    /// Distinct fingerprints a window holds at `false_positive_rate`; a busier
+    /// window starts the next one early
+    pub capacity: usize,
+    /// Share of new opportunities wrongly taken for repeats at `capacity`
+    pub false_positive_rate: f64,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(60),
+            capacity: 100_000,
+            false_positive_rate: 0.001,
+        }
+    }
+}
+
+/// Same path at the same spread, to the basis point; a spread that moves
+/// alerts again
+pub fn fingerprint(opportunity: &ArbitrageOpportunity) -> (String, i64) {
+    (opportunity.path.clone(), (opportunity.profit_percentage * 10_000.0).round() as i64)
+}
+
+/// A fixed-size Bloom filter sized for `capacity` items at a target false
+/// positive rate, probed by double hashing
+struct BloomFilter {
+    bits: Vec<u64>,
+    len: u64,
+    hashes: u32,
+    items: usize,
+}
+
+impl BloomFilter {
+    fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        let n = capacity.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let len = (-n * false_positive_rate.ln() / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let hashes = ((len as f64 / n) * ln2).round().clamp(1.0, 32.0) as u32;
+        Self {
+            bits: vec![0; len.div_ceil(64) as usize],
+            len,
+            hashes,
+            items: 0,
+        }
+    }
+    
+    fn positions(&self, (h1, h2): (u64, u64)) -> impl Iterator<Item = u64> + '_ {
+        (0..self.hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.len)
+    }
+    
+    fn contains(&self, hashes: (u64, u64)) -> bool {
+        self.positions(hashes).all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+    
+    fn insert(&mut self, hashes: (u64, u64)) {
+        let positions: Vec<u64> = self.positions(hashes).collect();
+        for bit in positions {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+        self.items += 1;
+    }
+    
+    fn clear(&mut self) {
+        self.bits.fill(0);
+        self.items = 0;
+    }
+    
+    /// Chance a fresh item looks present, from how many bits are set
+    fn estimated_false_positive_rate(&self) -> f64 {
+        let set: u32 = self.bits.iter().map(|word| word.count_ones()).sum();
+        (set as f64 / self.len as f64).powi(self.hashes as i32)
+    }
+    
+    fn memory_bytes(&self) -> usize {
+        self.bits.len() * 8
+    }
+}
+
+fn hash_pair<T: Hash>(item: &T) -> (u64, u64) {
+    let mut first = DefaultHasher::new();
+    item.hash(&mut first);
+    let h1 = first.finish();
+    let mut second = DefaultHasher::new();
+    h1.hash(&mut second);
+    // Odd, so the probe sequence never collapses onto one bit
+    (h1, second.finish() | 1)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DedupReport {
+    pub enabled: bool,
+    pub window_secs: u64,
+    pub capacity: usize,
+    pub hashes: u32,
+    pub memory_bytes: usize,
+    pub target_false_positive_rate: f64,
+    /// From the bits set in both generations right now
+    pub estimated_false_positive_rate: f64,
+    pub fingerprints_in_window: usize,
+    pub suppressed: u64,
+    pub passed: u64,
+}
+
+/// Fingerprints seen in the current and previous window, each in a Bloom
+/// filter of fixed size: the oldest generation is cleared as the window
+/// turns, so memory doesn't grow with uptime and a fingerprint is forgotten
+/// one to two windows after it was last let through
+pub struct OpportunityDedup {
+    config: DedupConfig,
+    current: BloomFilter,
+    previous: BloomFilter,
+    rotated_at: Option<Instant>,
+    suppressed: u64,
+    passed: u64,
+}
+
+impl OpportunityDedup {
+    pub fn new(config: DedupConfig) -> Self {
+        let filter = || BloomFilter::new(config.capacity, config.false_positive_rate);
+        Self {
+            current: filter(),
+            previous: filter(),
+            config,
+            rotated_at: None,
+            suppressed: 0,
+            passed: 0,
+        }
+    }
+    
+    fn rotate(&mut self, now: Instant) {
+        std::mem::swap(&mut self.current, &mut self.previous);
+        self.current.clear();
+        self.rotated_at = Some(now);
+    }
+    
+    /// Whether `opportunity` repeats one let through in the last window; if
+    /// not, it is remembered from now on
+    pub fn is_repeat(&mut self, opportunity: &ArbitrageOpportunity, now: Instant) -> bool {
+        if self.config.window.is_zero() {
+            self.passed += 1;
+            return false;
+        }
+        let started = *self.rotated_at.get_or_insert(now);
+        if now.saturating_duration_since(started) >= self.config.window || self.current.items >= self.config.capacity {
+            self.rotate(now);
+        }
+        
+        let hashes = hash_pair(&fingerprint(opportunity));
+        if self.current.contains(hashes) || self.previous.contains(hashes) {
+            self.suppressed += 1;
+            return true;
+        }
+        self.current.insert(hashes);
+        self.passed += 1;
+        false
+    }
+    
+    pub fn report(&self) -> DedupReport {
+        let (current, previous) = (
+            self.current.estimated_false_positive_rate(),
+            self.previous.estimated_false_positive_rate(),
+        );
+        DedupReport {
+            enabled: !self.config.window.is_zero(),
+            window_secs: self.config.window.as_secs(),
+            capacity: self.config.capacity,
+            hashes: self.current.hashes,
+            memory_bytes: self.current.memory_bytes() + self.previous.memory_bytes(),
+            target_false_positive_rate: self.config.false_positive_rate,
+            estimated_false_positive_rate: 1.0 - (1.0 - current) * (1.0 - previous),
+            fingerprints_in_window: self.current.items + self.previous.items,
+            suppressed: self.suppressed,
+            passed: self.passed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_suppresses_repeats_until_the_window_turns() {
+        let config = DedupConfig {
+            window: Duration::from_secs(10),
+            capacity: 1000,
+            false_positive_rate: 0.01,
+        };
+        let mut dedup = OpportunityDedup::new(config.clone());
+        let start = Instant::now();
+        let btc = ArbitrageOpportunity::sample("BTC->ETH->USDT->BTC", 0.0021);
+        
+        assert!(!dedup.is_repeat(&btc, start));
+        assert!(dedup.is_repeat(&btc, start + Duration::from_secs(5)));
+        // A wider spread on the same path is news
+        assert!(!dedup.is_repeat(&ArbitrageOpportunity::sample("BTC->ETH->USDT->BTC", 0.0035), start));
+        // Remembered through the next window, gone after
+        assert!(dedup.is_repeat(&btc, start + Duration::from_secs(15)));
+        assert!(!dedup.is_repeat(&btc, start + Duration::from_secs(25)));
+        
+        let report = dedup.report();
+        assert_eq!((report.suppressed, report.passed), (2, 3));
+        let memory = report.memory_bytes;
+        
+        // Memory stays put however many fingerprints go through, and the
+        // measured false positive rate stays near the target
+        let mut false_positives = 0;
+        for i in 0..100_000 {
+            let at = start + Duration::from_millis(30_000 + i);
+            if dedup.is_repeat(&ArbitrageOpportunity::sample(&format!("PATH{}", i), 0.001), at) {
+                false_positives += 1;
+            }
+        }
+        assert_eq!(dedup.report().memory_bytes, memory);
+        assert!(false_positives < 2 * 100_000 / 100, "{} false positives", false_positives);
+        assert!(dedup.report().estimated_false_positive_rate < 2.0 * config.false_positive_rate);
+        
+        let mut off = OpportunityDedup::new(DedupConfig { window: Duration::ZERO, ..config });
+        assert!(!off.is_repeat(&btc, start) && !off.is_repeat(&btc, start));
+    }
+}
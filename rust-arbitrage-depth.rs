@@ -0,0 +1,321 @@
+// arbitrage/depth.rs - L2 order books per venue and executable cycle volume
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+use std::time::Instant;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::exchange::OrderBookUpdate;
+use super::quotes::QuoteBook;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderBookConfig {
+    /// Subscribe to L2 depth on venues that have it, alongside top of book
+    pub enabled: bool,
+    /// Levels per side to ask for; rounded up to a depth the venue offers
+    pub levels: usize,
+    /// How far past the top an opportunity may walk each book, as a fraction
+    /// of the top price, when sizing its executable volume
+    pub max_slippage: f64,
+}
+
+impl Default for OrderBookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            levels: 20,
+            max_slippage: 0.001,
+        }
+    }
+}
+
+/// Book key ordered by price; venues never send NaN prices past `apply`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Price(f64);
+
+impl Eq for Price {}
+
+impl PartialOrd for Price {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Price {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+#[derive(Debug, Default)]
+struct OrderBook {
+    bids: BTreeMap<Price, f64>,
+    asks: BTreeMap<Price, f64>,
+    sequence: Option<u64>,
+}
+
+impl OrderBook {
+    fn apply_levels(side: &mut BTreeMap<Price, f64>, levels: &[(f64, f64)]) {
+        for &(price, size) in levels {
+            if !(price.is_finite() && price > 0.0 && size.is_finite()) {
+                continue;
+            }
+            if size > 0.0 {
+                side.insert(Price(price), size);
+            } else {
+                side.remove(&Price(price));
+            }
+        }
+    }
+    
+    /// Keep the best `depth` levels of each side
+    fn truncate(&mut self, depth: usize) {
+        while self.bids.len() > depth {
+            self.bids.pop_first();
+        }
+        while self.asks.len() > depth {
+            self.asks.pop_last();
+        }
+    }
+    
+    fn best_bid(&self) -> Option<f64> {
+        self.bids.keys().next_back().map(|price| price.0)
+    }
+    
+    fn best_ask(&self) -> Option<f64> {
+        self.asks.keys().next().map(|price| price.0)
+    }
+    
+    /// Best first: bids high to low, asks low to high
+    fn side(&self, bids: bool) -> Vec<(f64, f64)> {
+        if bids {
+            self.bids.iter().rev().map(|(price, size)| (price.0, *size)).collect()
+        } else {
+            self.asks.iter().map(|(price, size)| (price.0, *size)).collect()
+        }
+    }
+}
+
+/// One side of a book as served by `/api/book`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BookView {
+    pub exchange: String,
+    pub symbol: String,
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+    pub sequence: Option<u64>,
+}
+
+/// What became of an update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookApply {
+    Applied,
+    /// Older than the book, already reflected in it
+    Stale,
+    /// A diff with no snapshot to apply it to, or one that left the book
+    /// crossed; the book is dropped until the venue sends a new snapshot
+    OutOfSync,
+}
+
+/// Every venue's L2 book, kept from snapshots and diffs
+pub struct DepthBooks {
+    max_slippage: f64,
+    books: RwLock<HashMap<(String, String), OrderBook>>,
+}
+
+impl DepthBooks {
+    pub fn new(config: &OrderBookConfig) -> Self {
+        Self {
+            max_slippage: config.max_slippage,
+            books: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    pub fn apply(&self, update: &OrderBookUpdate) -> BookApply {
+        let key = (update.exchange.clone(), update.symbol.clone());
+        let mut books = self.books.write().unwrap();
+        if update.snapshot {
+            let book = books.entry(key.clone()).or_default();
+            if let (Some(current), Some(sequence)) = (book.sequence, update.sequence) {
+                if sequence <= current {
+                    return BookApply::Stale;
+                }
+            }
+            *book = OrderBook::default();
+        }
+        let Some(book) = books.get_mut(&key) else {
+            return BookApply::OutOfSync;
+        };
+        if !update.snapshot {
+            if let (Some(current), Some(sequence)) = (book.sequence, update.sequence) {
+                if sequence <= current {
+                    return BookApply::Stale;
+                }
+            }
+        }
+        
+        OrderBook::apply_levels(&mut book.bids, &update.bids);
+        OrderBook::apply_levels(&mut book.asks, &update.asks);
+        book.sequence = update.sequence.or(book.sequence);
+        if let Some(depth) = update.depth {
+            book.truncate(depth);
+        }
+        if let (Some(bid), Some(ask)) = (book.best_bid(), book.best_ask()) {
+            if bid >= ask {
+                warn!("{} {} book crossed ({} >= {}); dropping it until the next snapshot", update.exchange, update.symbol, bid, ask);
+                books.remove(&key);
+                return BookApply::OutOfSync;
+            }
+        }
+        BookApply::Applied
+    }
+    
+    /// Forget `exchange`'s books for `symbols`, e.g. when its connection drops
+    pub fn clear(&self, exchange: &str, symbols: &[String]) {
+        self.books
+            .write()
+            .unwrap()
+            .retain(|(book_exchange, symbol), _| book_exchange != exchange || !symbols.contains(symbol));
+    }
+    
+    pub fn view(&self, exchange: &str, symbol: &str, levels: usize) -> Option<BookView> {
+        let books = self.books.read().unwrap();
+        let book = books.get(&(exchange.to_string(), symbol.to_string()))?;
+        let mut bids = book.side(true);
+        let mut asks = book.side(false);
+        bids.truncate(levels);
+        asks.truncate(levels);
+        Some(BookView {
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            bids,
+            asks,
+            sequence: book.sequence,
+        })
+    }
+    
+    /// Levels of one side of `symbol` on `exchange`, best first, from its L2
+    /// book or else the top of book in `quotes`
+    fn levels(&self, exchange: &str, symbol: &str, bids: bool, quotes: &QuoteBook) -> Option<Vec<(f64, f64)>> {
+        if let Some(book) = self.books.read().unwrap().get(&(exchange.to_string(), symbol.to_string())) {
+            let levels = book.side(bids);
+            if !levels.is_empty() {
+                return Some(levels);
+            }
+        }
+        let quote = quotes.venue_quotes(symbol, Instant::now())?.into_iter().find(|quote| quote.exchange == exchange)?;
+        Some(vec![(if bids { quote.bid } else { quote.ask }, quote.volume)])
+    }
+    
+    /// Most of the first asset in `path` ("BTC_binance -> USDT_binance ->
+    /// ...") the cycle can trade before some leg walks its book more than
+    /// `max_slippage` past the top. Transfers between venues don't limit it.
+    /// `None` if a leg's market is unknown
+    pub fn executable_volume(&self, path: &str, quotes: &QuoteBook) -> Option<f64> {
+        let nodes: Vec<(&str, &str)> = path.split(" -> ").filter_map(|node| node.split_once('_')).collect();
+        if nodes.len() < 2 {
+            return None;
+        }
+        
+        // Units of the current asset per unit of the first, at top-of-book prices
+        let mut rate = 1.0;
+        let mut volume = f64::INFINITY;
+        for i in 0..nodes.len() {
+            let (from, exchange) = nodes[i];
+            let (to, to_exchange) = nodes[(i + 1) % nodes.len()];
+            if exchange != to_exchange || from == to {
+                continue;
+            }
+            
+            // Selling `from` into the bids of from/to, or buying `to` from the asks of to/from
+            let (capacity, top_rate) = match self.levels(exchange, &format!("{}/{}", from, to), true, quotes) {
+                Some(bids) => self.capacity(&bids, true)?,
+                None => self.capacity(&self.levels(exchange, &format!("{}/{}", to, from), false, quotes)?, false)?,
+            };
+            volume = volume.min(capacity / rate);
+            rate *= top_rate;
+        }
+        volume.is_finite().then_some(volume)
+    }
+    
+    /// How much of the spent asset one side takes within `max_slippage` of
+    /// its top, and the top rate in received per spent
+    fn capacity(&self, levels: &[(f64, f64)], bids: bool) -> Option<(f64, f64)> {
+        let top = levels.first()?.0;
+        if !(top.is_finite() && top > 0.0) {
+            return None;
+        }
+        let limit = if bids { top * (1.0 - self.max_slippage) } else { top * (1.0 + self.max_slippage) };
+        let capacity = levels
+            .iter()
+            .take_while(|(price, _)| if bids { *price >= limit } else { *price <= limit })
+            // Bids take the base being sold; asks take quote, price times size
+            .map(|(price, size)| if bids { *size } else { price * size })
+            .sum();
+        Some((capacity, if bids { top } else { 1.0 / top }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn update(exchange: &str, symbol: &str, snapshot: bool, bids: &[(f64, f64)], asks: &[(f64, f64)], sequence: Option<u64>) -> OrderBookUpdate {
+        OrderBookUpdate {
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            snapshot,
+            bids: bids.to_vec(),
+            asks: asks.to_vec(),
+            sequence,
+            depth: None,
+        }
+    }
+    
+    #[test]
+    fn test_snapshot_then_diffs_and_resync() {
+        let books = DepthBooks::new(&OrderBookConfig::default());
+        
+        // A diff before any snapshot has nothing to apply to
+        assert_eq!(books.apply(&update("kraken", "BTC/USD", false, &[(99.0, 1.0)], &[], None)), BookApply::OutOfSync);
+        
+        let snapshot = update("kraken", "BTC/USD", true, &[(99.0, 1.0), (98.0, 2.0)], &[(101.0, 1.0), (102.0, 3.0)], Some(10));
+        assert_eq!(books.apply(&snapshot), BookApply::Applied);
+        assert_eq!(books.apply(&update("kraken", "BTC/USD", false, &[(99.0, 0.0), (100.0, 0.5)], &[], Some(11))), BookApply::Applied);
+        assert_eq!(books.apply(&update("kraken", "BTC/USD", false, &[(97.0, 9.0)], &[], Some(11))), BookApply::Stale);
+        let view = books.view("kraken", "BTC/USD", 10).unwrap();
+        assert_eq!(view.bids, vec![(100.0, 0.5), (98.0, 2.0)]);
+        assert_eq!((view.asks[0], view.sequence), ((101.0, 1.0), Some(11)));
+        
+        // Venues that stop sending out-of-range levels are cut back to their depth
+        let mut deep = update("kraken", "BTC/USD", false, &[(99.5, 1.0)], &[], Some(12));
+        deep.depth = Some(2);
+        books.apply(&deep);
+        assert_eq!(books.view("kraken", "BTC/USD", 10).unwrap().bids, vec![(100.0, 0.5), (99.5, 1.0)]);
+        
+        // A crossed book is dropped until the next snapshot
+        assert_eq!(books.apply(&update("kraken", "BTC/USD", false, &[(105.0, 1.0)], &[], Some(13))), BookApply::OutOfSync);
+        assert!(books.view("kraken", "BTC/USD", 10).is_none());
+        books.apply(&snapshot);
+        books.clear("kraken", &["BTC/USD".to_string()]);
+        assert!(books.view("kraken", "BTC/USD", 10).is_none());
+    }
+    
+    #[test]
+    fn test_executable_volume_walks_each_leg_within_slippage() {
+        let books = DepthBooks::new(&OrderBookConfig { max_slippage: 0.01, ..OrderBookConfig::default() });
+        let quotes = QuoteBook::new();
+        // Sell BTC for USDT: 2 BTC within 1% of 100
+        books.apply(&update("binance", "BTC/USDT", true, &[(100.0, 1.5), (99.5, 0.5), (98.0, 10.0)], &[(100.1, 1.0)], None));
+        // Buy ETH with USDT: 150.5 USDT within 1% of 10
+        books.apply(&update("binance", "ETH/USDT", true, &[(9.9, 100.0)], &[(10.0, 10.0), (10.1, 5.0), (11.0, 100.0)], None));
+        // Sell ETH for BTC: 12 ETH
+        books.apply(&update("binance", "ETH/BTC", true, &[(0.101, 12.0)], &[(0.102, 1.0)], None));
+        
+        // BTC leg allows 2 BTC; USDT leg 150.5 USDT = 1.505 BTC; ETH leg 12 ETH = 1.2 BTC
+        let volume = books.executable_volume("BTC_binance -> USDT_binance -> ETH_binance", &quotes).unwrap();
+        assert!((volume - 1.2).abs() < 1e-9, "{}", volume);
+        assert!(books.executable_volume("BTC_binance -> DOGE_binance -> ETH_binance", &quotes).is_none());
+    }
+}
@@ -1,30 +1,256 @@
 // arbitrage/engine.rs - Core arbitrage detection engine in Rust
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
 use std::sync::{Arc, Mutex, RwLock};
+use std::future::Future;
 use std::time::{Duration, Instant};
 use crossbeam::channel::{self, Receiver, Sender, TryRecvError};
+use tokio::runtime::Handle;
 use tokio::{task, time};
 use tracing::{debug, info, warn, error};
 use serde::{Deserialize, Serialize};
 
-use super::types::{ArbitrageOpportunity, MarketTick, PerformanceStats};
+use crate::bot::TelegramBotConfig;
+use crate::clock::{self, SharedClock};
+use crate::compliance::ComplianceConfig;
+use crate::config::ReloadConfig;
+use crate::display::DisplayFormat;
+use crate::exchange::curve::{self, CurveConfig};
+use crate::exchange::deribit::{self, DeribitConfig};
+use crate::exchange::discovery::DiscoveryConfig;
+use crate::exchange::dydx;
+use crate::exchange::funding::FundingConfig;
+use crate::exchange::tardis::TardisConfig;
+use crate::exchange::OrderBookUpdate;
+use crate::execution::approval::ApprovalConfig;
+use crate::execution::eod::EndOfDayConfig;
+use crate::execution::locks::LockConfig;
+use crate::execution::router::RouterConfig;
+use crate::execution::sweeper::SweepConfig;
+use crate::faults::FaultConfig;
+use crate::dex::DexConfig;
+use crate::feetiers::FeeTierConfig;
+use crate::jobs::JobsConfig;
+use crate::markets::{AssetAlias, Fees, MarketsConfig};
+use crate::notify::NotifyConfig;
+use crate::outbox::OutboxConfig;
+use crate::recording::{Recorder, RecordingConfig};
+use crate::service::ServiceConfig;
+use crate::silence::SilenceConfig;
+use crate::standby::{StandbyConfig, StandbyLink};
+use super::aliases::{self, ConversionCaveat};
+use super::allocation::{optimize, AllocationConfig, AllocationReport};
+use super::atomicity::{Atomicity, AtomicityConfig};
+use super::basis::{self, BasisConfig, CarryTrade};
+use super::bridge::{self, BridgeConfig};
+use super::budget::{BudgetTracker, LatencyBudgets, PipelineStage, StageBudgetReport};
+use super::cluster::cluster_opportunities;
+use super::dedup::{DedupConfig, DedupReport, OpportunityDedup};
+use super::fees;
+use super::funding::{FundingBook, FundingOpportunity, FundingRate};
+use super::controls::RuntimeControls;
+use super::depth::{BookApply, BookView, DepthBooks, OrderBookConfig};
+use super::fairvalue::{FairValue, FairValueConfig, TickScreen};
+use super::heat::{HeatMap, HeatReport};
+use super::leadlag::{LeadLag, LeadLagConfig, LeadLagDetector};
+use super::listings::{ListingAges, NewListing};
+use super::maker::{self, MakerConfig, MakerPlan};
+use super::history::OpportunityHistory;
+use super::hotset::HotSet;
+use super::johnson;
+use super::partition::GraphPartition;
+use super::pruning::{self, EdgePruner, EdgeSet, PruningConfig};
+use super::persistence::{SpreadClass, SpreadPersistence, StructuralSpread, StructuralSpreadCallback};
+use super::quotes::{CompositeQuote, MarketSnapshot, QuoteBook, RegionalPremium};
+use super::rollups::{RollupPage, RollupQuery, Rollups};
+use super::routes::{ConversionRoute, RouteTable};
+use super::snapshot::{GraphSnapshot, SnapshotCell};
+use super::statarb::{SpreadSignalCallback, StatArbConfig, StatArbMonitor, StatArbReport};
+use super::transfers::{self, TransferConfig, TransferPlan};
+use super::stats::StatsCollector;
+use super::ticks::{PublishedTick, TickFeed};
+use super::types::{ArbitrageOpportunity, MarketTick, OpportunityKind, PerformanceStats};
+use super::washtrade::{FeedIntegrity, WashTradingConfig};
 
 pub type OpportunityCallback = Box<dyn Fn(ArbitrageOpportunity) + Send + Sync>;
 
+/// One top-of-book update for `update_prices`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceUpdate {
+    pub exchange: String,
+    pub symbol: String,
+    pub bid: f64,
+    pub ask: f64,
+    pub volume: f64,
+}
+
+/// Messages applied before the processor publishes an epoch even if more are queued
+const MAX_MESSAGES_PER_EPOCH: usize = 1024;
+
+/// Work item for the market data processor; a batch is applied under one lock
+enum TickMessage {
+    Single(MarketTick),
+    Batch(Vec<MarketTick>),
+    /// (exchange, symbol) feeds dropped by a config reload; their quotes and
+    /// graph edges go, so detection never runs on prices that stopped updating
+    Remove(Vec<(String, String)>),
+}
+
+// Numeric guards for the log-space price graph. Rates outside this range are
+// treated as feed glitches rather than prices, which bounds every edge weight
+// by ln(1e12), about 27.6.
+const MIN_VALID_RATE: f64 = 1e-12;
+const MAX_VALID_RATE: f64 = 1e12;
+
+/// (local currency, bridge currency) pairs whose regional premiums are tracked
+const REGIONAL_MARKETS: [(&str, &str); 1] = [("KRW", "USDT")];
+
+/// Negative cycle search used by the detector
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DetectionBackend {
+    BellmanFord, // One Bellman-Ford run per source; best for dense graphs
+    Johnson,     // One super-source run plus reweighted Dijkstra; best for sparse graphs
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub exchanges: Vec<String>,
     pub symbols: Vec<String>,
+    /// Add the pairs the exchanges list in common to `symbols` at startup
+    pub discovery: DiscoveryConfig,
+    /// Net of `fees`; opportunities must clear this after paying them
     pub min_profit_threshold: f64,
+    /// Maker and taker fee per exchange; every trade in a cycle pays the taker
+    /// rate. Exchanges not listed are treated as fee-free
+    pub fees: HashMap<String, Fees>,
+    /// Withdrawal and network fees and confirmation times for moving assets
+    /// between exchanges, charged on a trade of `max_position_size`
+    pub transfers: TransferConfig,
+    /// Cycles that only pay with some legs resting as maker orders
+    pub maker_legs: MakerConfig,
+    /// Higher thresholds for cycles that can't execute on one venue
+    pub atomicity: AtomicityConfig,
+    /// Read-only account access for detecting each exchange's actual fee tier
+    pub fee_tiers: FeeTierConfig,
+    /// Uniswap v3 pools quoted as a venue, with each swap charged its gas
+    pub dex: DexConfig,
     pub max_position_size: f64,
     pub dashboard_port: u16,
+    /// Bearer token the dashboard's control routes require; without one they
+    /// only answer requests from this machine
+    pub control_token: Option<String>,
+    #[serde(with = "humantime_serde")]
     pub websocket_timeout: Duration,
+    #[serde(with = "humantime_serde")]
     pub reconnect_interval: Duration,
     pub max_reconnect_attempts: u32,
+    /// Connect to exchange sandboxes instead of production feeds
+    pub use_testnet: bool,
     pub enable_triangle_arbitrage: bool,
+    /// Also link tickers of one asset (`markets.aliases`) across exchanges
     pub enable_cross_exchange: bool,
+    /// Compare pairs against venues that only trade them through a bridge asset
+    pub bridge: BridgeConfig,
     pub thread_pool_size: usize,
     pub enable_thread_pinning: bool,
+    pub opportunity_history_size: usize,
+    /// How far back `/api/analytics/heat` aggregates alerted opportunities
+    #[serde(with = "humantime_serde")]
+    pub heat_window: Duration,
+    /// Current balances and risk limits for the capital allocation report
+    pub allocation: AllocationConfig,
+    #[serde(with = "humantime_serde")]
+    pub subscription_ack_timeout: Duration,
+    /// Split symbols over several connections when one exceeds an exchange's
+    /// stream limit; otherwise such a config is rejected at startup
+    pub auto_shard_subscriptions: bool,
+    /// Venues and data types behind the "tardis" exchange entry
+    pub tardis: TardisConfig,
+    /// Perpetuals and futures behind the "deribit" exchange entry
+    pub deribit: DeribitConfig,
+    /// Stableswap pools behind the "curve" exchange entry
+    pub curve: CurveConfig,
+    /// Perpetual funding rates hedged against `symbols` on spot
+    pub funding: FundingConfig,
+    /// Dated futures carried against spot to expiry
+    pub basis: BasisConfig,
+    #[serde(with = "humantime_serde")]
+    pub structural_spread_after: Duration,
+    #[serde(with = "humantime_serde")]
+    pub structural_blacklist_duration: Duration,
+    /// Repeats of an alerted opportunity within the window are dropped
+    pub opportunity_dedup: DedupConfig,
+    pub opportunity_cluster_similarity: f64,
+    pub compliance: ComplianceConfig,
+    pub latency_budgets: LatencyBudgets,
+    #[serde(with = "humantime_serde")]
+    pub hot_detection_interval: Duration,
+    #[serde(with = "humantime_serde")]
+    pub cold_detection_interval: Duration,
+    #[serde(with = "humantime_serde")]
+    pub hot_symbol_ttl: Duration,
+    pub gpu_detection_min_nodes: usize, // Only used with the "gpu" feature
+    #[serde(with = "humantime_serde")]
+    pub route_scan_interval: Duration,
+    pub detection_backend: DetectionBackend,
+    /// Slices of the graph by quote currency, each detected on its own thread
+    /// with its own threshold; empty detects on the whole graph
+    pub graph_partitions: Vec<GraphPartition>,
+    /// Edges far from any profitable cycle are left out of detection and
+    /// rechecked on each route scan
+    pub edge_pruning: PruningConfig,
+    pub alert_outbox: OutboxConfig,
+    /// Handlebars alert bodies by channel; "default" replaces the built-in format
+    pub alert_templates: HashMap<String, String>,
+    /// Built-in alert language by channel ("en", "zh", "es")
+    pub alert_languages: HashMap<String, String>,
+    /// Number formatting for alerts and the dashboard API
+    pub display: DisplayFormat,
+    pub notifications: NotifyConfig,
+    /// Operator commands over Telegram; disabled when unset
+    pub telegram_bot: Option<TelegramBotConfig>,
+    /// Alert only, or queue opportunities for a human to approve before execution
+    pub approval: ApprovalConfig,
+    /// What happens to an execution that overlaps one already in flight
+    pub execution_locks: LockConfig,
+    /// Order legs and their states, reloaded and reconciled on restart
+    pub order_store: PathBuf,
+    /// Cancelling legs that go stale or were in flight when their connection dropped
+    pub stale_orders: SweepConfig,
+    /// How the order router completes a partially filled leg
+    pub order_routing: RouterConfig,
+    /// Daily flattening of residual inventory back to the starting portfolio; off by default
+    pub end_of_day: EndOfDayConfig,
+    /// Artificial latency, jitter and drops for robustness testing; off by default
+    pub fault_injection: FaultConfig,
+    /// Tick and opportunity recording for `scanner debug-opportunity`; off by default
+    pub recording: RecordingConfig,
+    /// CCXT market metadata sources for the asset registry and fee schedules
+    pub markets: MarketsConfig,
+    /// Cross-venue fair value, and the bad-print check ticks pass on the way in
+    pub fair_value: FairValueConfig,
+    /// Measuring how long slow venues take to repeat fast venues' moves
+    pub lead_lag: LeadLagConfig,
+    /// Rolling spreads between correlated instruments, signalled on their z-score
+    pub stat_arb: StatArbConfig,
+    /// Feeds showing fake liquidity, and what happens to opportunities on them
+    pub wash_trading: WashTradingConfig,
+    /// L2 depth subscriptions, used to size opportunities
+    pub order_book: OrderBookConfig,
+    /// Reloading the config file while running; SIGHUP reloads regardless
+    pub config_reload: ReloadConfig,
+    /// Warm standby: a primary relays ticks and order state, a standby takes
+    /// over when the primary's heartbeat stops
+    pub standby: StandbyConfig,
+    /// systemd and Windows service integration, and restarts by handover
+    pub service: ServiceConfig,
+    /// Cron schedules for retention, fee refresh, discovery, reports and rebalancing
+    pub jobs: JobsConfig,
+    /// Operational alerts when an exchange or detector goes quiet for too long
+    pub silence: SilenceConfig,
 }
 
 impl Default for Config {
@@ -32,91 +258,285 @@ impl Default for Config {
         Self {
             exchanges: vec!["binance".to_string(), "coinbase".to_string()],
             symbols: vec!["BTC/USDT".to_string(), "ETH/USDT".to_string()],
+            discovery: DiscoveryConfig::default(),
             min_profit_threshold: 0.001,
+            fees: HashMap::new(),
+            transfers: TransferConfig::default(),
+            maker_legs: MakerConfig::default(),
+            atomicity: AtomicityConfig::default(),
+            fee_tiers: FeeTierConfig::default(),
+            dex: DexConfig::default(),
             max_position_size: 1000.0,
             dashboard_port: 8080,
+            control_token: None,
             websocket_timeout: Duration::from_secs(30),
             reconnect_interval: Duration::from_secs(5),
             max_reconnect_attempts: 10,
+            use_testnet: false,
             enable_triangle_arbitrage: true,
             enable_cross_exchange: true,
+            bridge: BridgeConfig::default(),
             thread_pool_size: num_cpus::get(),
             enable_thread_pinning: true,
+            opportunity_history_size: 1000,
+            heat_window: Duration::from_secs(24 * 3600),
+            allocation: AllocationConfig::default(),
+            subscription_ack_timeout: Duration::from_secs(10),
+            auto_shard_subscriptions: false,
+            tardis: TardisConfig::default(),
+            deribit: DeribitConfig::default(),
+            curve: CurveConfig::default(),
+            funding: FundingConfig::default(),
+            basis: BasisConfig::default(),
+            structural_spread_after: Duration::from_secs(300),
+            structural_blacklist_duration: Duration::from_secs(3600),
+            opportunity_dedup: DedupConfig::default(),
+            opportunity_cluster_similarity: 0.6,
+            compliance: ComplianceConfig::default(),
+            latency_budgets: LatencyBudgets::default(),
+            hot_detection_interval: Duration::from_millis(2),
+            cold_detection_interval: Duration::from_millis(10),
+            hot_symbol_ttl: Duration::from_secs(30),
+            gpu_detection_min_nodes: 128,
+            route_scan_interval: Duration::from_secs(5),
+            detection_backend: DetectionBackend::BellmanFord,
+            graph_partitions: Vec::new(),
+            edge_pruning: PruningConfig::default(),
+            alert_outbox: OutboxConfig::default(),
+            alert_templates: HashMap::new(),
+            alert_languages: HashMap::new(),
+            display: DisplayFormat::default(),
+            notifications: NotifyConfig::default(),
+            telegram_bot: None,
+            approval: ApprovalConfig::default(),
+            execution_locks: LockConfig::default(),
+            order_store: PathBuf::from("orders.json"),
+            stale_orders: SweepConfig::default(),
+            order_routing: RouterConfig::default(),
+            end_of_day: EndOfDayConfig::default(),
+            fault_injection: FaultConfig::default(),
+            recording: RecordingConfig::default(),
+            markets: MarketsConfig::default(),
+            fair_value: FairValueConfig::default(),
+            lead_lag: LeadLagConfig::default(),
+            stat_arb: StatArbConfig::default(),
+            wash_trading: WashTradingConfig::default(),
+            order_book: OrderBookConfig::default(),
+            config_reload: ReloadConfig::default(),
+            standby: StandbyConfig::default(),
+            service: ServiceConfig::default(),
+            jobs: JobsConfig::default(),
+            silence: SilenceConfig::default(),
         }
     }
 }
 
 pub struct ArbitrageEngine {
-    config: Config,
+    config: Arc<RwLock<Config>>,
+    config_version: Arc<AtomicU64>,  // Bumped by `apply_config`; the detector reloads on change
     
     // High-performance data structures
     price_graph: Arc<RwLock<Vec<Vec<f64>>>>,  // Adjacency matrix for currencies
     currency_map: Arc<RwLock<HashMap<String, usize>>>,  // Currency -> index mapping
+    snapshots: Arc<SnapshotCell>,  // Consistent per-epoch view for detection
+    quotes: Arc<QuoteBook>,  // Latest quote per symbol/exchange
+    funding: Arc<FundingBook>,  // Latest perpetual funding rate per exchange/symbol
+    listings: Arc<ListingAges>,  // Pairs listed since startup, held back while new
+    routes: Arc<RwLock<RouteTable>>,  // Periodic all-pairs best conversion routes
+    pruned_edges: Arc<RwLock<Arc<EdgeSet>>>,  // Skipped by detection until relevant again
     
     // Lock-free communication channels
-    tick_sender: Sender<MarketTick>,
-    tick_receiver: Arc<Mutex<Receiver<MarketTick>>>,
+    tick_sender: Sender<TickMessage>,
+    tick_receiver: Arc<Mutex<Receiver<TickMessage>>>,
+    tick_feed: Arc<TickFeed>,  // Validated ticks for external subscribers
+    tick_screen: Arc<TickScreen>,  // Drops ticks far from the other venues' fair value, flags fake liquidity
+    lead_lag: Arc<LeadLagDetector>,
+    stat_arb: Arc<StatArbMonitor>,
+    depth: Arc<DepthBooks>,
     
     // Opportunity storage and callbacks
-    opportunities: Arc<Mutex<VecDeque<ArbitrageOpportunity>>>,
-    callbacks: Arc<Mutex<Vec<OpportunityCallback>>>,
+    opportunities: Arc<OpportunityHistory>,
+    heat: Arc<HeatMap>,
+    rollups: Arc<Rollups>,
+    callbacks: Arc<RwLock<Vec<OpportunityCallback>>>,
+    structural_callbacks: Arc<RwLock<Vec<StructuralSpreadCallback>>>,
+    signal_callbacks: Arc<RwLock<Vec<SpreadSignalCallback>>>,
+    spread_persistence: Arc<Mutex<SpreadPersistence>>,
+    opportunity_dedup: Arc<Mutex<OpportunityDedup>>,
     
     // Performance monitoring
-    stats: Arc<Mutex<PerformanceStats>>,
+    stats: Arc<StatsCollector>,
+    budgets: Arc<BudgetTracker>,
+    
+    // Replay recording, if enabled
+    recorder: Option<Arc<Recorder>>,
+    
+    // Ticks forwarded to a warm standby, on a primary
+    relay: Option<Arc<StandbyLink>>,
+    
+    // Tick timestamps, detection times and TTLs; a manual clock in replays and tests
+    clock: SharedClock,
     
     // Control
+    controls: Arc<RuntimeControls>,
+    account_fees: RwLock<HashMap<String, Fees>>,  // Detected account tiers and DEX gas; override `Config::fees`, also across reloads
     is_running: Arc<std::sync::atomic::AtomicBool>,
     task_handles: Arc<Mutex<Vec<task::JoinHandle<()>>>>,
+    sequence: AtomicU64,  // Per engine, so engines sharing a process count their own ticks
+    runtime: Option<Handle>,  // Where `start` spawns; the caller's runtime when unset
 }
 
 impl ArbitrageEngine {
     pub fn new(config: Config) -> Self {
         let (tx, rx) = channel::unbounded();
         let max_currencies = 100; // Support up to 100 currencies
+        let history_size = config.opportunity_history_size;
+        let budgets = BudgetTracker::new(config.latency_budgets.clone());
+        let spread_persistence = SpreadPersistence::new(
+            config.structural_spread_after,
+            config.structural_blacklist_duration,
+        );
+        
+        let opportunity_dedup = OpportunityDedup::new(config.opportunity_dedup.clone());
+        let controls = RuntimeControls::new(config.min_profit_threshold);
+        let heat = HeatMap::new(config.heat_window, &config.symbols);
+        let tick_screen = TickScreen::new(config.fair_value.clone()).with_wash_trading(config.wash_trading.clone());
+        let lead_lag = LeadLagDetector::new(config.lead_lag.clone());
+        let stat_arb = StatArbMonitor::new(config.stat_arb.clone());
+        let depth = DepthBooks::new(&config.order_book);
         
         Self {
-            config,
+            config: Arc::new(RwLock::new(config)),
+            config_version: Arc::new(AtomicU64::new(0)),
             price_graph: Arc::new(RwLock::new(vec![vec![f64::INFINITY; max_currencies]; max_currencies])),
             currency_map: Arc::new(RwLock::new(HashMap::new())),
+            snapshots: Arc::new(SnapshotCell::new()),
+            quotes: Arc::new(QuoteBook::new()),
+            funding: Arc::new(FundingBook::new()),
+            listings: Arc::new(ListingAges::new()),
             tick_sender: tx,
             tick_receiver: Arc::new(Mutex::new(rx)),
-            opportunities: Arc::new(Mutex::new(VecDeque::new())),
-            callbacks: Arc::new(Mutex::new(Vec::new())),
-            stats: Arc::new(Mutex::new(PerformanceStats::default())),
+            tick_feed: Arc::new(TickFeed::new()),
+            tick_screen: Arc::new(tick_screen),
+            lead_lag: Arc::new(lead_lag),
+            stat_arb: Arc::new(stat_arb),
+            depth: Arc::new(depth),
+            routes: Arc::new(RwLock::new(RouteTable::default())),
+            pruned_edges: Arc::new(RwLock::new(Arc::new(EdgeSet::new()))),
+            opportunities: Arc::new(OpportunityHistory::with_capacity(history_size)),
+            heat: Arc::new(heat),
+            rollups: Arc::new(Rollups::new()),
+            callbacks: Arc::new(RwLock::new(Vec::new())),
+            structural_callbacks: Arc::new(RwLock::new(Vec::new())),
+            signal_callbacks: Arc::new(RwLock::new(Vec::new())),
+            spread_persistence: Arc::new(Mutex::new(spread_persistence)),
+            opportunity_dedup: Arc::new(Mutex::new(opportunity_dedup)),
+            stats: Arc::new(StatsCollector::new()),
+            budgets: Arc::new(budgets),
+            recorder: None,
+            relay: None,
+            clock: clock::system(),
+            controls: Arc::new(controls),
+            account_fees: RwLock::new(HashMap::new()),
             is_running: Arc::new(std::sync::atomic::AtomicBool::new(false)),
             task_handles: Arc::new(Mutex::new(Vec::new())),
+            sequence: AtomicU64::new(0),
+            runtime: None,
         }
     }
     
+    /// An engine whose `start` spawns onto `runtime` rather than the runtime
+    /// it is called from, for embedders that keep their own
+    pub fn new_in(config: Config, runtime: Handle) -> Self {
+        let mut engine = Self::new(config);
+        engine.runtime = Some(runtime);
+        engine
+    }
+    
+    /// Record every tick and alerted opportunity; call before `start`
+    pub fn with_recorder(mut self, recorder: Arc<Recorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+    
+    /// Forward every tick to standbys following this engine; call before `start`
+    pub fn with_relay(mut self, relay: Arc<StandbyLink>) -> Self {
+        self.relay = Some(relay);
+        self
+    }
+    
+    /// Read the time from `clock` instead of the system; call before `start`
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
     pub async fn start(&self) {
-        if self.is_running.swap(true, std::sync::atomic::Ordering::SeqCst) {
+        if !self.begin() {
             return; // Already running
         }
         
-        info!("Starting arbitrage engine with {} threads", self.config.thread_pool_size);
-        
-        // Initialize price graph diagonal
-        {
-            let mut graph = self.price_graph.write().unwrap();
-            for i in 0..graph.len() {
-                graph[i][i] = 0.0;
-            }
-        }
-        
         let mut handles = self.task_handles.lock().unwrap();
         
         // Start market data processing task
-        handles.push(self.spawn_market_data_processor());
+        handles.push(self.spawn(self.market_data_processor()));
         
         // Start arbitrage detection task
-        handles.push(self.spawn_arbitrage_detector());
+        handles.push(self.spawn(self.arbitrage_detector()));
+        
+        // Start slower all-pairs route planning task
+        handles.push(self.spawn(self.route_planner()));
+        
+        // Start spread sampling for mean-reversion signals
+        handles.push(self.spawn(self.spread_sampler()));
         
         // Start performance monitoring task
-        handles.push(self.spawn_performance_monitor());
+        handles.push(self.spawn(self.performance_monitor()));
         
         info!("Arbitrage engine started successfully");
     }
     
+    /// Run the engine's loops on the calling task instead of spawning them;
+    /// resolves once `stop` is called. Lets an embedder drive the engine from
+    /// its own executor without the engine owning any task
+    pub async fn run(&self) {
+        if !self.begin() {
+            return; // Already running
+        }
+        
+        tokio::join!(
+            self.market_data_processor(),
+            self.arbitrage_detector(),
+            self.route_planner(),
+            self.spread_sampler(),
+            self.performance_monitor(),
+        );
+        info!("Arbitrage engine loops finished");
+    }
+    
+    /// Mark the engine running and prepare the graph; false if it already was
+    fn begin(&self) -> bool {
+        if self.is_running.swap(true, std::sync::atomic::Ordering::SeqCst) {
+            return false;
+        }
+        
+        info!("Starting arbitrage engine with {} threads", self.config.read().unwrap().thread_pool_size);
+        
+        // Initialize price graph diagonal
+        let mut graph = self.price_graph.write().unwrap();
+        for i in 0..graph.len() {
+            graph[i][i] = 0.0;
+        }
+        true
+    }
+    
+    fn spawn(&self, task: impl Future<Output = ()> + Send + 'static) -> task::JoinHandle<()> {
+        match &self.runtime {
+            Some(runtime) => runtime.spawn(task),
+            None => task::spawn(task),
+        }
+    }
+    
     pub async fn stop(&self) {
         self.is_running.store(false, std::sync::atomic::Ordering::SeqCst);
         
@@ -145,6 +565,18 @@ impl ArbitrageEngine {
         volume: f64,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let start_time = Instant::now();
+        if let Some(recorder) = &self.recorder {
+            recorder.record_tick(exchange, symbol, bid, ask, volume);
+        }
+        if let Some(relay) = &self.relay {
+            relay.relay(vec![PriceUpdate {
+                exchange: exchange.to_string(),
+                symbol: symbol.to_string(),
+                bid,
+                ask,
+                volume,
+            }]);
+        }
         
         let tick = MarketTick {
             exchange: exchange.to_string(),
@@ -153,51 +585,212 @@ impl ArbitrageEngine {
             ask,
             last_price: (bid + ask) / 2.0,
             volume,
-            timestamp: start_time,
+            timestamp: self.clock.now(),
             sequence: self.get_next_sequence(),
         };
         
         // Send to processing thread via lock-free channel
-        self.tick_sender.send(tick).map_err(|e| {
+        self.tick_sender.send(TickMessage::Single(tick)).map_err(|e| {
             error!("Failed to send market tick: {}", e);
             e
         })?;
         
         // Update performance stats
-        let processing_time = start_time.elapsed();
-        if let Ok(mut stats) = self.stats.lock() {
-            stats.messages_processed += 1;
-            stats.update_avg_latency(processing_time.as_micros() as f64);
+        let ingest_time = start_time.elapsed();
+        self.stats.record_message(ingest_time);
+        self.budgets.record(PipelineStage::Ingest, ingest_time);
+        
+        Ok(())
+    }
+    
+    /// Apply many updates (e.g. a depth snapshot) as one unit: the processor
+    /// takes the graph locks once and the detector never sees half a batch
+    pub async fn update_prices(
+        &self,
+        updates: &[PriceUpdate],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if updates.is_empty() {
+            return Ok(());
+        }
+        
+        let start_time = Instant::now();
+        if let Some(recorder) = &self.recorder {
+            for update in updates {
+                recorder.record_tick(&update.exchange, &update.symbol, update.bid, update.ask, update.volume);
+            }
+        }
+        if let Some(relay) = &self.relay {
+            relay.relay(updates.to_vec());
+        }
+        
+        let received = self.clock.now();
+        let ticks: Vec<MarketTick> = updates
+            .iter()
+            .map(|update| MarketTick {
+                exchange: update.exchange.clone(),
+                symbol: update.symbol.clone(),
+                bid: update.bid,
+                ask: update.ask,
+                last_price: (update.bid + update.ask) / 2.0,
+                volume: update.volume,
+                timestamp: received,
+                sequence: self.get_next_sequence(),
+            })
+            .collect();
+        
+        self.tick_sender.send(TickMessage::Batch(ticks)).map_err(|e| {
+            error!("Failed to send market tick batch: {}", e);
+            e
+        })?;
+        
+        // Count every update as a message, sharing the batch ingest time
+        let ingest_time = start_time.elapsed();
+        let per_update = ingest_time / updates.len() as u32;
+        for _ in updates {
+            self.stats.record_message(per_update);
         }
+        self.budgets.record(PipelineStage::Ingest, ingest_time);
         
         Ok(())
     }
     
-    fn spawn_market_data_processor(&self) -> task::JoinHandle<()> {
+    fn market_data_processor(&self) -> impl Future<Output = ()> + Send + 'static {
         let receiver = Arc::clone(&self.tick_receiver);
         let price_graph = Arc::clone(&self.price_graph);
         let currency_map = Arc::clone(&self.currency_map);
+        let quotes = Arc::clone(&self.quotes);
+        let tick_feed = Arc::clone(&self.tick_feed);
+        let tick_screen = Arc::clone(&self.tick_screen);
+        let lead_lag = Arc::clone(&self.lead_lag);
+        let snapshots = Arc::clone(&self.snapshots);
         let is_running = Arc::clone(&self.is_running);
+        let config = Arc::clone(&self.config);
+        let config_version = Arc::clone(&self.config_version);
         
-        task::spawn(async move {
+        async move {
             info!("Market data processor started");
+            // Alias settings the graph is linked under; a reload swaps them in
+            let mut version = config_version.load(std::sync::atomic::Ordering::SeqCst);
+            let (mut asset_aliases, mut cross_exchange) = Self::alias_settings(&config);
             
             while is_running.load(std::sync::atomic::Ordering::SeqCst) {
-                let tick = match receiver.lock().unwrap().try_recv() {
-                    Ok(tick) => tick,
-                    Err(TryRecvError::Empty) => {
-                        // No data available, brief sleep to prevent busy waiting
-                        tokio::time::sleep(Duration::from_micros(100)).await;
-                        continue;
+                // Drain what's queued into one write epoch, then publish it whole
+                let known_currencies = currency_map.read().unwrap().len();
+                let mut applied = 0;
+                let mut disconnected = false;
+                while applied < MAX_MESSAGES_PER_EPOCH {
+                    // Release the receiver lock before any await below
+                    let received = receiver.lock().unwrap().try_recv();
+                    match received {
+                        Ok(message) => {
+                            Self::apply_message(message, &quotes, &tick_feed, &tick_screen, &lead_lag, &price_graph, &currency_map);
+                            applied += 1;
+                        }
+                        Err(TryRecvError::Empty) => break,
+                        Err(TryRecvError::Disconnected) => {
+                            disconnected = true;
+                            break;
+                        }
                     }
-                    Err(TryRecvError::Disconnected) => break,
-                };
+                }
+                
+                let mut relinked = false;
+                let latest = config_version.load(std::sync::atomic::Ordering::SeqCst);
+                if latest != version {
+                    version = latest;
+                    let (aliases, cross) = Self::alias_settings(&config);
+                    if (&aliases, cross) != (&asset_aliases, cross_exchange) {
+                        let mut graph = price_graph.write().unwrap();
+                        let currencies = currency_map.read().unwrap();
+                        aliases::unlink_aliases(&mut graph, &currencies, &asset_aliases, cross_exchange);
+                        aliases::link_aliases(&mut graph, &currencies, &aliases, cross);
+                        (asset_aliases, cross_exchange) = (aliases, cross);
+                        relinked = true;
+                    }
+                }
                 
-                Self::process_market_tick(tick, &price_graph, &currency_map);
+                if applied > 0 || relinked {
+                    // New nodes may be another ticker of an asset already in the graph
+                    if currency_map.read().unwrap().len() > known_currencies {
+                        let mut graph = price_graph.write().unwrap();
+                        let currencies = currency_map.read().unwrap();
+                        aliases::link_aliases(&mut graph, &currencies, &asset_aliases, cross_exchange);
+                    }
+                    let graph = price_graph.read().unwrap();
+                    let currencies = currency_map.read().unwrap();
+                    let epoch = snapshots.publish(&graph, &currencies);
+                    debug!("Published graph epoch {} ({} messages)", epoch, applied);
+                }
+                if disconnected {
+                    break;
+                }
+                if applied == 0 {
+                    // No data available, brief sleep to prevent busy waiting
+                    tokio::time::sleep(Duration::from_micros(100)).await;
+                }
             }
             
             info!("Market data processor stopped");
-        })
+        }
+    }
+    
+    fn alias_settings(config: &RwLock<Config>) -> (Vec<AssetAlias>, bool) {
+        let config = config.read().unwrap();
+        (config.markets.aliases.clone(), config.enable_cross_exchange)
+    }
+    
+    fn apply_message(
+        message: TickMessage,
+        quotes: &QuoteBook,
+        tick_feed: &TickFeed,
+        tick_screen: &TickScreen,
+        lead_lag: &LeadLagDetector,
+        price_graph: &Arc<RwLock<Vec<Vec<f64>>>>,
+        currency_map: &Arc<RwLock<HashMap<String, usize>>>,
+    ) {
+        match message {
+            TickMessage::Single(tick) => {
+                if !tick_screen.admit(&tick, quotes) {
+                    return;
+                }
+                quotes.update(&tick);
+                if Self::is_valid_tick(&tick) {
+                    tick_feed.publish(&tick);
+                    lead_lag.observe(&tick);
+                }
+                Self::process_market_tick(tick, price_graph, currency_map);
+            }
+            TickMessage::Batch(mut ticks) => {
+                ticks.retain(|tick| tick_screen.admit(tick, quotes));
+                for tick in &ticks {
+                    quotes.update(tick);
+                    if Self::is_valid_tick(tick) {
+                        tick_feed.publish(tick);
+                        lead_lag.observe(tick);
+                    }
+                }
+                Self::process_market_batch(&ticks, price_graph, currency_map);
+            }
+            TickMessage::Remove(feeds) => {
+                let mut graph = price_graph.write().unwrap();
+                let map = currency_map.read().unwrap();
+                for (exchange, symbol) in &feeds {
+                    quotes.remove(exchange, symbol);
+                    let Some((base, quote)) = Self::parse_symbol(symbol) else {
+                        continue;
+                    };
+                    let base_idx = map.get(&format!("{}_{}", base, exchange));
+                    let quote_idx = map.get(&format!("{}_{}", quote, exchange));
+                    if let (Some(&base_idx), Some(&quote_idx)) = (base_idx, quote_idx) {
+                        if base_idx < graph.len() && quote_idx < graph.len() {
+                            graph[base_idx][quote_idx] = f64::INFINITY;
+                            graph[quote_idx][base_idx] = f64::INFINITY;
+                        }
+                    }
+                }
+                info!("Dropped {} feeds removed from the configuration", feeds.len());
+            }
+        }
     }
     
     fn process_market_tick(
@@ -226,20 +819,9 @@ impl ArbitrageEngine {
             (base_idx, quote_idx)
         };
         
-        // Update price graph with log-transformed prices for Bellman-Ford
         {
             let mut graph = price_graph.write().unwrap();
-            if base_idx < graph.len() && quote_idx < graph.len() {
-                // Forward edge: base -> quote (selling base for quote)
-                if tick.bid > 0.0 {
-                    graph[base_idx][quote_idx] = -tick.bid.ln();
-                }
-                
-                // Reverse edge: quote -> base (buying base with quote)
-                if tick.ask > 0.0 {
-                    graph[quote_idx][base_idx] = -(1.0 / tick.ask).ln();
-                }
-            }
+            Self::write_tick_edges(&tick, base_idx, quote_idx, &mut graph);
         }
         
         debug!(
@@ -248,104 +830,392 @@ impl ArbitrageEngine {
         );
     }
     
-    fn spawn_arbitrage_detector(&self) -> task::JoinHandle<()> {
-        let price_graph = Arc::clone(&self.price_graph);
-        let currency_map = Arc::clone(&self.currency_map);
+    fn process_market_batch(
+        ticks: &[MarketTick],
+        price_graph: &Arc<RwLock<Vec<Vec<f64>>>>,
+        currency_map: &Arc<RwLock<HashMap<String, usize>>>,
+    ) {
+        // Same lock order as the detector (graph, then currencies) so holding
+        // both for the whole batch can't deadlock against a detection pass
+        let mut graph = price_graph.write().unwrap();
+        let mut map = currency_map.write().unwrap();
+        
+        for tick in ticks {
+            let Some((base, quote)) = Self::parse_symbol(&tick.symbol) else {
+                warn!("Invalid symbol format: {}", tick.symbol);
+                continue;
+            };
+            
+            let base_idx = Self::get_or_create_currency_index(&mut map, format!("{}_{}", base, tick.exchange));
+            let quote_idx = Self::get_or_create_currency_index(&mut map, format!("{}_{}", quote, tick.exchange));
+            Self::write_tick_edges(tick, base_idx, quote_idx, &mut graph);
+        }
+        
+        debug!("Applied batch of {} price updates", ticks.len());
+    }
+    
+    /// A tick the graph takes both edges from: a parseable symbol and usable prices
+    fn is_valid_tick(tick: &MarketTick) -> bool {
+        Self::parse_symbol(&tick.symbol).is_some()
+            && Self::rate_to_edge_weight(tick.bid).is_some()
+            && Self::rate_to_edge_weight(1.0 / tick.ask).is_some()
+    }
+    
+    pub(super) fn write_tick_edges(tick: &MarketTick, base_idx: usize, quote_idx: usize, graph: &mut [Vec<f64>]) {
+        // Update price graph with log-transformed prices for Bellman-Ford.
+        // Degenerate quotes (zero/negative/NaN/inf) remove the edge instead of
+        // writing a non-finite weight that would poison every relaxation.
+        if base_idx < graph.len() && quote_idx < graph.len() {
+            // Forward edge: base -> quote (selling base for quote)
+            graph[base_idx][quote_idx] = match Self::rate_to_edge_weight(tick.bid) {
+                Some(weight) => weight,
+                None => {
+                    warn!("Dropping degenerate bid {} for {} on {}", tick.bid, tick.symbol, tick.exchange);
+                    f64::INFINITY
+                }
+            };
+            
+            // Reverse edge: quote -> base (buying base with quote)
+            graph[quote_idx][base_idx] = match Self::rate_to_edge_weight(1.0 / tick.ask) {
+                Some(weight) => weight,
+                None => {
+                    warn!("Dropping degenerate ask {} for {} on {}", tick.ask, tick.symbol, tick.exchange);
+                    f64::INFINITY
+                }
+            };
+        }
+    }
+    
+    fn arbitrage_detector(&self) -> impl Future<Output = ()> + Send + 'static {
+        let snapshots = Arc::clone(&self.snapshots);
+        let pruned_edges = Arc::clone(&self.pruned_edges);
         let opportunities = Arc::clone(&self.opportunities);
+        let heat = Arc::clone(&self.heat);
+        let depth = Arc::clone(&self.depth);
+        let quotes = Arc::clone(&self.quotes);
+        let tick_screen = Arc::clone(&self.tick_screen);
+        let listings = Arc::clone(&self.listings);
+        let rollups = Arc::clone(&self.rollups);
         let callbacks = Arc::clone(&self.callbacks);
+        let structural_callbacks = Arc::clone(&self.structural_callbacks);
+        let spread_persistence = Arc::clone(&self.spread_persistence);
+        let opportunity_dedup = Arc::clone(&self.opportunity_dedup);
         let stats = Arc::clone(&self.stats);
+        let budgets = Arc::clone(&self.budgets);
+        let controls = Arc::clone(&self.controls);
+        let recorder = self.recorder.clone();
+        let clock = Arc::clone(&self.clock);
         let is_running = Arc::clone(&self.is_running);
-        let config = self.config.clone();
+        let shared_config = Arc::clone(&self.config);
+        let config_version = Arc::clone(&self.config_version);
+        let mut version = config_version.load(std::sync::atomic::Ordering::SeqCst);
+        let mut config = shared_config.read().unwrap().clone();
         
-        task::spawn(async move {
+        async move {
             info!("Arbitrage detector started");
-            let mut detection_interval = time::interval(Duration::from_millis(10)); // 100Hz
+            // Hot subgraphs are scanned every tick, the full graph on the cold cadence
+            let mut detection_interval = time::interval(config.hot_detection_interval);
+            let mut last_full_scan: Option<Instant> = None;
+            let mut hot_set = HotSet::new(config.hot_symbol_ttl);
             
             while is_running.load(std::sync::atomic::Ordering::SeqCst) {
                 detection_interval.tick().await;
                 
+                // Pick up a reloaded config; a changed cadence restarts its timer
+                let latest = config_version.load(std::sync::atomic::Ordering::SeqCst);
+                if latest != version {
+                    version = latest;
+                    let reloaded = shared_config.read().unwrap().clone();
+                    if reloaded.hot_detection_interval != config.hot_detection_interval {
+                        detection_interval = time::interval(reloaded.hot_detection_interval);
+                    }
+                    if reloaded.hot_symbol_ttl != config.hot_symbol_ttl {
+                        hot_set = HotSet::new(reloaded.hot_symbol_ttl);
+                    }
+                    config = reloaded;
+                    last_full_scan = None;
+                }
+                
                 let start_time = Instant::now();
+                let now = clock.now();
+                hot_set.prune(now);
+                config.min_profit_threshold = controls.min_profit_threshold();
+                
+                let full_scan = last_full_scan
+                    .map_or(true, |at| now.saturating_duration_since(at) >= config.cold_detection_interval);
+                if !full_scan && hot_set.is_empty() {
+                    continue;
+                }
+                if full_scan {
+                    last_full_scan = Some(now);
+                }
                 
-                // Find arbitrage opportunities using Bellman-Ford
-                let found_opportunities = Self::detect_arbitrage_opportunities(
-                    &price_graph,
-                    &currency_map,
+                // Find arbitrage opportunities on one consistent epoch of the graph
+                let snapshot = snapshots.load();
+                // Pruned edges wait for the route planner to find them relevant again
+                let pruned = Arc::clone(&pruned_edges.read().unwrap());
+                let snapshot = if pruned.is_empty() { snapshot } else { Arc::new(pruning::prune(&snapshot, &pruned)) };
+                let mut found_opportunities = Self::detect_arbitrage_opportunities(
+                    &snapshot,
                     &config,
+                    if full_scan { None } else { Some(&hot_set) },
+                    now,
                 );
+                if full_scan {
+                    found_opportunities.extend(Self::bridged_opportunities(&quotes, &config, now));
+                    found_opportunities.extend(Self::basis_opportunities(&quotes, &config, clock.unix_ms(), now));
+                }
+                for opp in &found_opportunities {
+                    hot_set.mark(opp, now);
+                }
                 
                 let detection_time = start_time.elapsed();
                 
                 // Process opportunities
-                for opp in found_opportunities {
-                    if opp.profit_percentage > config.min_profit_threshold {
-                        // Store opportunity
-                        {
-                            let mut opps = opportunities.lock().unwrap();
-                            opps.push_back(opp.clone());
-                            
-                            // Keep only recent opportunities (last 1000)
-                            while opps.len() > 1000 {
-                                opps.pop_front();
+                {
+                    let mut persistence = spread_persistence.lock().unwrap();
+                    persistence.prune(now);
+                    listings.prune(now, &config.discovery.new_listings);
+                    let mut dedup = opportunity_dedup.lock().unwrap();
+                    
+                    // Screen each cycle before clustering, so a rejected one
+                    // doesn't take its correlated cycles on other venues with it
+                    let mut admitted = Vec::with_capacity(found_opportunities.len());
+                    for mut opp in found_opportunities {
+                        if let Some(volume) = depth.executable_volume(&opp.path, &quotes) {
+                            opp.max_volume = volume;
+                        }
+                        // Legs on feeds that look like fake liquidity
+                        if !tick_screen.screen_opportunity(&mut opp) {
+                            continue;
+                        }
+                        // Fresh listings' spreads aren't tradable yet
+                        if !listings.admits(&opp, now, &config.discovery.new_listings) {
+                            continue;
+                        }
+                        // Detection already applied the global or partition threshold
+                        if controls.is_suppressed(&opp) {
+                            continue;
+                        }
+                        // Spreads that never close are structural, not tradable
+                        match persistence.observe(&opp, now) {
+                            SpreadClass::Transient => {}
+                            SpreadClass::Blacklisted => continue,
+                            SpreadClass::Structural(spread) => {
+                                warn!(
+                                    "Structural spread on {} persisted {}s, blacklisting for {}s",
+                                    spread.path, spread.persisted_secs, spread.blacklisted_secs
+                                );
+                                Self::notify_callbacks(&structural_callbacks, &spread);
+                                continue;
                             }
                         }
+                        // Already alerted within the dedup window
+                        if dedup.is_repeat(&opp, now) {
+                            continue;
+                        }
+                        admitted.push(opp);
+                    }
+                    
+                    // Alert once per cluster of correlated cycles
+                    for cluster in cluster_opportunities(admitted, config.opportunity_cluster_similarity) {
+                        let opp = cluster.representative;
+                        
+                        // Store opportunity (ring buffer overwrites the oldest entry)
+                        opportunities.push(opp.clone());
+                        heat.record(&opp);
+                        rollups.record_opportunity(&opp);
+                        if let Some(recorder) = &recorder {
+                            let id = recorder.record_opportunity(&opp);
+                            info!("Recorded opportunity #{} on {}", id, opp.path);
+                        }
                         
                         // Notify callbacks
-                        {
-                            let callbacks_guard = callbacks.lock().unwrap();
-                            for callback in callbacks_guard.iter() {
-                                callback(opp.clone());
-                            }
-                        }
+                        Self::notify_callbacks(&callbacks, &opp);
                         
                         // Update stats
-                        if let Ok(mut stats) = stats.lock() {
-                            stats.opportunities_found += 1;
-                        }
+                        stats.record_opportunity();
                         
                         info!(
-                            "Arbitrage opportunity: {} - {:.4}% profit",
-                            opp.path, opp.profit_percentage * 100.0
+                            "Arbitrage opportunity: {} - {:.4}% profit ({} correlated cycles, epoch {})",
+                            opp.path, opp.profit_percentage * 100.0, cluster.members, snapshot.epoch
                         );
                     }
                 }
                 
                 // Update detection latency stats
-                if let Ok(mut stats) = stats.lock() {
-                    stats.detection_latency_us = detection_time.as_micros() as f64;
-                }
+                stats.record_detection(detection_time);
+                budgets.record(PipelineStage::Detect, detection_time);
             }
             
             info!("Arbitrage detector stopped");
-        })
+        }
     }
     
-    fn detect_arbitrage_opportunities(
-        price_graph: &Arc<RwLock<Vec<Vec<f64>>>>,
-        currency_map: &Arc<RwLock<HashMap<String, usize>>>,
+    pub(super) fn detect_arbitrage_opportunities(
+        snapshot: &GraphSnapshot,
         config: &Config,
+        focus: Option<&HotSet>,
+        detected_at: Instant,
     ) -> Vec<ArbitrageOpportunity> {
-        let graph = price_graph.read().unwrap();
-        let currencies = currency_map.read().unwrap();
-        let n = currencies.len().min(graph.len());
+        let graph = &snapshot.weights;
+        let currencies = &snapshot.currencies;
+        let n = snapshot.len();
         
         if n < 3 {
             return Vec::new(); // Need at least 3 currencies for arbitrage
         }
         
-        let mut opportunities = Vec::new();
+        // Hot subgraph around recently profitable nodes, in graph indices
+        let hot = focus.map(|hot_set| hot_set.node_indices(currencies, graph, n));
+        if config.graph_partitions.is_empty() {
+            return Self::detect_in(graph, currencies, n, hot, config.min_profit_threshold, config, detected_at);
+        }
+        
+        // Partitions share no state, so each searches its slice on its own thread
+        let slices: Vec<(&str, Vec<usize>, f64)> = config
+            .graph_partitions
+            .iter()
+            .map(|partition| {
+                let mut nodes = partition.node_indices(&config.symbols, currencies, n);
+                if let Some(hot) = &hot {
+                    nodes.retain(|u| hot.binary_search(u).is_ok());
+                }
+                let threshold = partition.min_profit_threshold.unwrap_or(config.min_profit_threshold);
+                (partition.name.as_str(), nodes, threshold)
+            })
+            .filter(|(_, nodes, _)| nodes.len() >= 3)
+            .collect();
         
-        // Bellman-Ford algorithm to detect negative cycles
-        for source in 0..n {
-            if let Some(cycle) = Self::bellman_ford_negative_cycle(&graph, source, n) {
-                if let Some(opp) = Self::cycle_to_opportunity(cycle, &currencies, &graph) {
-                    if opp.profit_percentage > config.min_profit_threshold {
-                        opportunities.push(opp);
-                    }
+        std::thread::scope(|scope| {
+            let searches: Vec<_> = slices
+                .into_iter()
+                .map(|(name, nodes, threshold)| {
+                    let search = scope.spawn(move || {
+                        Self::detect_in(graph, currencies, n, Some(nodes), threshold, config, detected_at)
+                    });
+                    (name, search)
+                })
+                .collect();
+            searches
+                .into_iter()
+                .flat_map(|(name, search)| {
+                    search.join().unwrap_or_else(|_| {
+                        error!("Detection in partition {} panicked", name);
+                        Vec::new()
+                    })
+                })
+                .collect()
+        })
+    }
+    
+    /// Opportunities above `threshold` on the subgraph induced by `nodes`
+    /// (sorted graph indices), or on the whole graph
+    fn detect_in(
+        graph: &[Vec<f64>],
+        currencies: &HashMap<String, usize>,
+        n: usize,
+        nodes: Option<Vec<usize>>,
+        threshold: f64,
+        config: &Config,
+        detected_at: Instant,
+    ) -> Vec<ArbitrageOpportunity> {
+        let cycles = match nodes {
+            None => Self::negative_cycles(graph, n, config),
+            Some(nodes) => {
+                // Same search on the induced subgraph, mapped back to graph indices
+                let k = nodes.len();
+                if k < 3 {
+                    return Vec::new();
+                }
+                
+                let subgraph: Vec<Vec<f64>> = nodes
+                    .iter()
+                    .map(|&u| nodes.iter().map(|&v| graph[u][v]).collect())
+                    .collect();
+                
+                Self::negative_cycles(&subgraph, k, config)
+                    .into_iter()
+                    .map(|cycle| cycle.into_iter().map(|i| nodes[i]).collect())
+                    .collect()
+            }
+        };
+        
+        cycles
+            .into_iter()
+            .filter_map(|cycle| {
+                let taker = Self::cycle_to_opportunity(cycle.clone(), currencies, graph, config, detected_at)
+                    .filter(|opp| opp.profit_percentage > threshold);
+                if taker.is_some() || !config.maker_legs.enabled {
+                    return taker;
+                }
+                Self::maker_opportunity(&cycle, currencies, graph, threshold, config, detected_at)
+            })
+            .filter(|opp| {
+                config.atomicity.admits(opp.profit_percentage, &Atomicity::of(&opp.path, &config.allocation.balances))
+            })
+            .collect()
+    }
+    
+    /// `cycle` as a maker-leg opportunity, when resting some legs clears
+    /// `threshold` in expectation though taking every leg doesn't. Profit is
+    /// the expected profit after transfers and confidence is scaled by the
+    /// fill probability
+    fn maker_opportunity(
+        cycle: &[usize],
+        currencies: &HashMap<String, usize>,
+        graph: &[Vec<f64>],
+        threshold: f64,
+        config: &Config,
+        detected_at: Instant,
+    ) -> Option<ArbitrageOpportunity> {
+        let (path, exchanges) = Self::cycle_path(cycle, currencies);
+        let plan = maker::best_plan(cycle, graph, &path, &config.fees, &config.symbols, &config.markets.aliases, &config.maker_legs)?;
+        let profit_percentage = (1.0 + plan.expected_profit) * Self::plan_transfers(&path, config).multiplier() - 1.0;
+        if profit_percentage <= threshold {
+            return None;
+        }
+        
+        let confidence = Self::calculate_confidence(profit_percentage, cycle.len()) as f64 * plan.fill_probability;
+        let log_return: f64 = (0..cycle.len()).map(|i| graph[cycle[i]][cycle[(i + 1) % cycle.len()]]).sum();
+        Some(ArbitrageOpportunity {
+            kind: OpportunityKind::Cycle,
+            path,
+            profit_percentage,
+            gross_profit: (-log_return).exp() - 1.0,
+            max_volume: 100.0,
+            confidence: confidence as u32,
+            detected_at,
+            exchanges,
+        })
+    }
+    
+    /// Negative cycles in trade order using the configured backend
+    fn negative_cycles(graph: &[Vec<f64>], n: usize, config: &Config) -> Vec<Vec<usize>> {
+        match config.detection_backend {
+            DetectionBackend::BellmanFord => Self::candidate_sources(graph, n, config)
+                .into_iter()
+                .filter_map(|source| Self::bellman_ford_negative_cycle(graph, source, n))
+                .collect(),
+            DetectionBackend::Johnson => johnson::negative_cycles(graph, n),
+        }
+    }
+    
+    /// Sources worth a full Bellman-Ford pass; large graphs are prefiltered
+    /// on the GPU when the "gpu" feature is enabled and an adapter exists
+    #[cfg_attr(not(feature = "gpu"), allow(unused_variables))]
+    fn candidate_sources(graph: &[Vec<f64>], n: usize, config: &Config) -> Vec<usize> {
+        #[cfg(feature = "gpu")]
+        if n >= config.gpu_detection_min_nodes {
+            if let Some(relaxer) = super::gpu::shared() {
+                match relaxer.negative_cycle_sources(graph, n) {
+                    Ok(sources) => return sources,
+                    Err(e) => warn!("GPU relaxation failed, falling back to CPU: {}", e),
                 }
             }
         }
         
-        opportunities
+        (0..n).collect()
     }
     
     fn bellman_ford_negative_cycle(
@@ -413,6 +1283,9 @@ impl ArbitrageEngine {
         let cycle_start_pos = cycle.iter().position(|&x| x == node)?;
         cycle.drain(0..cycle_start_pos);
         
+        // Parent links point backwards along edges; return nodes in trade order
+        cycle.reverse();
+        
         if cycle.len() >= 3 {
             Some(cycle)
         } else {
@@ -424,6 +1297,8 @@ impl ArbitrageEngine {
         cycle: Vec<usize>,
         currencies: &HashMap<String, usize>,
         graph: &[Vec<f64>],
+        config: &Config,
+        detected_at: Instant,
     ) -> Option<ArbitrageOpportunity> {
         if cycle.len() < 3 {
             return None;
@@ -437,42 +1312,134 @@ impl ArbitrageEngine {
             total_log_return += graph[u][v];
         }
         
+        if !total_log_return.is_finite() {
+            return None;
+        }
+        
         let profit_multiplier = (-total_log_return).exp();
-        let profit_percentage = profit_multiplier - 1.0;
+        let gross_profit = profit_multiplier - 1.0;
+        
+        if !gross_profit.is_finite() || gross_profit <= 0.0 {
+            return None;
+        }
         
+        let (path, exchanges) = Self::cycle_path(&cycle, currencies);
+        Self::net_opportunity(path, exchanges, gross_profit, config, detected_at)
+    }
+    
+    /// Only cycles that still pay after every leg's taker fee, and the
+    /// transfers inventory doesn't cover, are opportunities
+    fn net_opportunity(
+        path: String,
+        exchanges: Vec<String>,
+        gross_profit: f64,
+        config: &Config,
+        detected_at: Instant,
+    ) -> Option<ArbitrageOpportunity> {
+        let aliases = &config.markets.aliases;
+        let after_fees = fees::net_profit(gross_profit, &path, &config.fees, &config.symbols, aliases);
+        let transfers = Self::plan_transfers(&path, config);
+        let profit_percentage = (1.0 + after_fees) * transfers.multiplier() - 1.0;
         if profit_percentage <= 0.0 {
             return None;
         }
         
-        // Build currency path string
-        let reverse_map: HashMap<usize, String> = currencies
+        Some(ArbitrageOpportunity {
+            kind: OpportunityKind::Cycle,
+            confidence: Self::calculate_confidence(profit_percentage, exchanges.len()),
+            path,
+            profit_percentage,
+            gross_profit,
+            max_volume: 100.0, // Until the detector sizes it against the books
+            detected_at,
+            exchanges,
+        })
+    }
+    
+    /// Configured pairs quoted directly on one venue against their rate
+    /// through `bridge.assets` on venues that don't list them
+    fn bridged_opportunities(quotes: &QuoteBook, config: &Config, detected_at: Instant) -> Vec<ArbitrageOpportunity> {
+        if config.bridge.assets.is_empty() {
+            return Vec::new();
+        }
+        bridge::bridged_spreads(quotes, &config.symbols, &config.bridge.assets)
+            .into_iter()
+            .filter_map(|spread| {
+                let exchanges = spread
+                    .nodes
+                    .iter()
+                    .filter_map(|node| node.rsplit_once('_').map(|(_, exchange)| exchange.to_string()))
+                    .collect();
+                Self::net_opportunity(spread.path(), exchanges, spread.gross_profit, config, detected_at)
+            })
+            .filter(|opp| opp.profit_percentage > config.min_profit_threshold)
+            .collect()
+    }
+    
+    /// Carry trades through streamed dated futures, as opportunities whose
+    /// profit is the net return to expiry
+    fn basis_opportunities(quotes: &QuoteBook, config: &Config, now_ms: u64, detected_at: Instant) -> Vec<ArbitrageOpportunity> {
+        basis::carry_trades(quotes, &config.basis, &config.fees, &config.markets.aliases, now_ms)
+            .into_iter()
+            .map(|trade| {
+                // One exchange per node: quote and underlying on spot, future and its quote
+                let exchanges = vec![trade.spot_exchange.clone(), trade.spot_exchange, trade.future_exchange.clone(), trade.future_exchange];
+                ArbitrageOpportunity {
+                    kind: OpportunityKind::Basis,
+                    confidence: Self::calculate_confidence(trade.net_return, exchanges.len()),
+                    path: trade.path,
+                    profit_percentage: trade.net_return,
+                    gross_profit: trade.basis,
+                    max_volume: 100.0, // Until the detector sizes it against the books
+                    detected_at,
+                    exchanges,
+                }
+            })
+            .collect()
+    }
+    
+    /// Currency path string for `cycle` and the exchange of each node
+    fn cycle_path(cycle: &[usize], currencies: &HashMap<String, usize>) -> (String, Vec<String>) {
+        let reverse_map: HashMap<usize, &String> = currencies
             .iter()
-            .map(|(k, &v)| (v, k.clone()))
+            .map(|(k, &v)| (v, k))
             .collect();
         
-        let path = cycle
+        let names: Vec<&String> = cycle
+            .iter()
+            .filter_map(|idx| reverse_map.get(idx).copied())
+            .collect();
+        let exchanges = names
             .iter()
-            .filter_map(|&idx| reverse_map.get(&idx))
-            .cloned()
-            .collect::<Vec<_>>()
-            .join(" -> ");
+            .filter_map(|name| name.rsplit('_').next().map(|s| s.to_string()))
+            .collect();
+        (names.iter().map(|name| name.as_str()).collect::<Vec<_>>().join(" -> "), exchanges)
+    }
+    
+    /// Invoke every registered callback, isolating panics so one misbehaving
+    /// subscriber can't poison the registry or take down the detector loop.
+    /// Returns the number of callbacks that panicked.
+    fn notify_callbacks<T: Clone>(
+        callbacks: &RwLock<Vec<Box<dyn Fn(T) + Send + Sync>>>,
+        event: &T,
+    ) -> usize {
+        let callbacks_guard = callbacks.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut panicked = 0;
         
-        Some(ArbitrageOpportunity {
-            path,
-            profit_percentage,
-            max_volume: 100.0, // Simplified volume estimation
-            confidence: Self::calculate_confidence(profit_percentage, cycle.len()),
-            detected_at: Instant::now(),
-            exchanges: cycle
-                .iter()
-                .filter_map(|&idx| {
-                    reverse_map.get(&idx)?
-                        .split('_')
-                        .last()
-                        .map(|s| s.to_string())
-                })
-                .collect(),
-        })
+        for (index, callback) in callbacks_guard.iter().enumerate() {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| callback(event.clone())));
+            if let Err(payload) = result {
+                panicked += 1;
+                let reason = payload
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                error!("Callback #{} panicked: {}", index, reason);
+            }
+        }
+        
+        panicked
     }
     
     fn calculate_confidence(profit: f64, path_length: usize) -> u32 {
@@ -482,45 +1449,109 @@ impl ArbitrageEngine {
         (profit_score + path_score) as u32
     }
     
-    fn spawn_performance_monitor(&self) -> task::JoinHandle<()> {
-        let stats = Arc::clone(&self.stats);
+    fn route_planner(&self) -> impl Future<Output = ()> + Send + 'static {
+        let snapshots = Arc::clone(&self.snapshots);
+        let routes = Arc::clone(&self.routes);
+        let pruned_edges = Arc::clone(&self.pruned_edges);
+        let controls = Arc::clone(&self.controls);
+        let clock = Arc::clone(&self.clock);
         let is_running = Arc::clone(&self.is_running);
+        let (scan_interval, pruning) = {
+            let config = self.config.read().unwrap();
+            (config.route_scan_interval, config.edge_pruning.clone())
+        };
         
-        task::spawn(async move {
-            let mut interval = time::interval(Duration::from_secs(10));
+        async move {
+            let mut interval = time::interval(scan_interval);
+            let mut pruner = EdgePruner::new(pruning);
             
             while is_running.load(std::sync::atomic::Ordering::SeqCst) {
                 interval.tick().await;
                 
-                if let Ok(stats) = stats.lock() {
-                    info!(
-                        "Performance: {} msgs/s, {} opps found, {:.2}μs avg latency",
-                        stats.messages_processed / 10,
-                        stats.opportunities_found,
-                        stats.avg_latency_us
-                    );
-                }
+                let start_time = Instant::now();
+                // Floyd-Warshall is O(n^3); compute on a snapshot, outside any lock
+                let snapshot = snapshots.load();
+                let table = RouteTable::compute(&snapshot.weights, &snapshot.currencies);
+                
+                // Score edges on the full graph, so pruned ones can come back
+                let pruned = pruner.observe(&snapshot.weights, &table, controls.min_profit_threshold(), clock.now());
+                let count = pruned.len();
+                *pruned_edges.write().unwrap() = Arc::new(pruned);
+                *routes.write().unwrap() = table;
+                
+                debug!("Route table rebuilt in {:?}, {} edges pruned", start_time.elapsed(), count);
             }
-        })
+        }
     }
     
-    // Utility methods
-    
-    fn get_next_sequence(&self) -> u64 {
-        static SEQUENCE: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
-        SEQUENCE.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+    fn spread_sampler(&self) -> impl Future<Output = ()> + Send + 'static {
+        let quotes = Arc::clone(&self.quotes);
+        let stat_arb = Arc::clone(&self.stat_arb);
+        let signal_callbacks = Arc::clone(&self.signal_callbacks);
+        let clock = Arc::clone(&self.clock);
+        let is_running = Arc::clone(&self.is_running);
+        
+        async move {
+            while is_running.load(std::sync::atomic::Ordering::SeqCst) {
+                // Read each pass, so a reloaded interval applies
+                time::sleep(stat_arb.sample_interval()).await;
+                
+                for signal in stat_arb.sample(&quotes, clock.unix_ms()) {
+                    debug!("Spread {} {:?} {:?} at z {:.2}", signal.spread, signal.action, signal.side, signal.z_score);
+                    Self::notify_callbacks(&signal_callbacks, &signal);
+                }
+            }
+        }
     }
     
-    fn parse_symbol(symbol: &str) -> Option<(String, String)> {
-        let parts: Vec<&str> = symbol.split('/').collect();
-        if parts.len() == 2 {
-            Some((parts[0].to_string(), parts[1].to_string()))
-        } else {
-            None
+    fn performance_monitor(&self) -> impl Future<Output = ()> + Send + 'static {
+        let stats = Arc::clone(&self.stats);
+        let is_running = Arc::clone(&self.is_running);
+        
+        async move {
+            let mut interval = time::interval(Duration::from_secs(10));
+            
+            while is_running.load(std::sync::atomic::Ordering::SeqCst) {
+                interval.tick().await;
+                
+                let snapshot = stats.snapshot();
+                info!(
+                    "Performance: {} msgs/s, {} opps found, {:.2}μs avg latency",
+                    snapshot.messages_processed / 10,
+                    snapshot.opportunities_found,
+                    snapshot.avg_latency_us
+                );
+            }
+        }
+    }
+    
+    // Utility methods
+    
+    /// Convert an exchange rate into a Bellman-Ford edge weight (`-ln(rate)`).
+    ///
+    /// Returns `None` for non-finite, non-positive or out-of-range rates, so a
+    /// single extreme quote can't dominate a cycle.
+    fn rate_to_edge_weight(rate: f64) -> Option<f64> {
+        if !rate.is_finite() || rate < MIN_VALID_RATE || rate > MAX_VALID_RATE {
+            return None;
+        }
+        Some(-rate.ln())
+    }
+    
+    fn get_next_sequence(&self) -> u64 {
+        self.sequence.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+    }
+    
+    pub(super) fn parse_symbol(symbol: &str) -> Option<(String, String)> {
+        let parts: Vec<&str> = symbol.split('/').collect();
+        if parts.len() == 2 {
+            Some((parts[0].to_string(), parts[1].to_string()))
+        } else {
+            None
         }
     }
     
-    fn get_or_create_currency_index(
+    pub(super) fn get_or_create_currency_index(
         map: &mut HashMap<String, usize>,
         currency: String,
     ) -> usize {
@@ -535,24 +1566,347 @@ impl ArbitrageEngine {
     
     // Public API methods
     
+    /// Apply a reloaded config to the running engine: detection settings take
+    /// effect on the next pass, and feeds no longer configured are dropped.
+    /// A changed `min_profit_threshold` replaces any threshold set at runtime.
+    /// Detected account fees keep precedence over the reloaded `fees`
+    pub fn apply_config(&self, mut config: Config) {
+        for (exchange, fees) in self.account_fees.read().unwrap().iter() {
+            Self::overlay_fees(&mut config.fees, exchange, *fees);
+        }
+        let removed: Vec<(String, String)> = {
+            let mut current = self.config.write().unwrap();
+            if config.min_profit_threshold != current.min_profit_threshold {
+                self.controls.set_min_profit_threshold(config.min_profit_threshold);
+            }
+            if config.fair_value != current.fair_value {
+                self.tick_screen.set_config(config.fair_value.clone());
+            }
+            if config.lead_lag != current.lead_lag {
+                self.lead_lag.set_config(config.lead_lag.clone());
+            }
+            if config.stat_arb != current.stat_arb {
+                self.stat_arb.set_config(config.stat_arb.clone());
+            }
+            if config.wash_trading != current.wash_trading {
+                self.tick_screen.set_wash_trading(config.wash_trading.clone());
+            }
+            if config.structural_spread_after != current.structural_spread_after
+                || config.structural_blacklist_duration != current.structural_blacklist_duration
+            {
+                self.spread_persistence
+                    .lock()
+                    .unwrap()
+                    .set_limits(config.structural_spread_after, config.structural_blacklist_duration);
+            }
+            if config.opportunity_dedup != current.opportunity_dedup {
+                // Resized filters start empty, so open opportunities alert once more
+                *self.opportunity_dedup.lock().unwrap() = OpportunityDedup::new(config.opportunity_dedup.clone());
+            }
+            
+            let kept = Self::feeds(&config);
+            let mut removed: Vec<(String, String)> = Self::feeds(&current).into_iter().filter(|feed| !kept.contains(feed)).collect();
+            removed.sort();
+            *current = config;
+            removed
+        };
+        self.config_version.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        
+        if !removed.is_empty() {
+            // Behind any ticks already queued for these feeds
+            if let Err(e) = self.tick_sender.send(TickMessage::Remove(removed)) {
+                error!("Failed to drop removed feeds: {}", e);
+            }
+        }
+    }
+    
+    /// Price `exchange`'s trades at the rates the account actually pays,
+    /// from the next detection pass on
+    pub fn set_account_fees(&self, exchange: &str, fees: Fees) {
+        self.account_fees.write().unwrap().insert(exchange.to_string(), fees);
+        Self::overlay_fees(&mut self.config.write().unwrap().fees, exchange, fees);
+        self.config_version.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+    
+    /// Detected rates replace the configured ones; the configured `charged_in`
+    /// stays unless the detection knows what the account pays in
+    fn overlay_fees(fees: &mut HashMap<String, Fees>, exchange: &str, detected: Fees) {
+        let charged_in = detected.charged_in.or_else(|| fees.get(exchange).and_then(|configured| configured.charged_in));
+        fees.insert(exchange.to_string(), Fees { charged_in, ..detected });
+    }
+    
+    /// Charge every swap on `venue` `cost` as a fraction of the trade, on top of
+    /// the pool fee already in its quotes. Gas is paid either way a swap goes,
+    /// so maker legs pay it too
+    pub fn set_gas_cost(&self, venue: &str, cost: f64) {
+        self.set_account_fees(venue, Fees { maker: cost, taker: cost, charged_in: None });
+    }
+    
+    /// The config in effect, reloads included
+    pub fn current_config(&self) -> Config {
+        self.config.read().unwrap().clone()
+    }
+    
+    /// (exchange, symbol) pairs `config` streams; tardis ticks carry the venue
+    /// they were relayed from, deribit adds its derivatives, dydx quotes the
+    /// perpetual on each pair's base and curve quotes its pools' pairs under
+    /// one venue per size
+    fn feeds(config: &Config) -> HashSet<(String, String)> {
+        let mut exchanges: Vec<&String> = Vec::new();
+        for exchange in &config.exchanges {
+            match exchange.as_str() {
+                "tardis" => exchanges.extend(&config.tardis.venues),
+                "curve" => {}
+                _ => exchanges.push(exchange),
+            }
+        }
+        let mut feeds: HashSet<(String, String)> = exchanges
+            .into_iter()
+            .flat_map(|exchange| {
+                config.symbols.iter().map(move |symbol| match exchange.as_str() {
+                    "dydx" => (exchange.clone(), dydx::perpetual_symbol(symbol)),
+                    _ => (exchange.clone(), symbol.clone()),
+                })
+            })
+            .collect();
+        if config.exchanges.iter().any(|exchange| exchange == "deribit") {
+            let derivatives = config.deribit.instruments.iter().filter_map(|i| deribit::instrument_symbol(i));
+            feeds.extend(derivatives.map(|symbol| ("deribit".to_string(), symbol)));
+        }
+        if config.exchanges.iter().any(|exchange| exchange == "curve") {
+            feeds.extend(curve::feeds(&config.curve, &config.symbols));
+        }
+        feeds
+    }
+    
     pub fn register_callback(&self, callback: OpportunityCallback) {
-        let mut callbacks = self.callbacks.lock().unwrap();
+        let mut callbacks = self.callbacks.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        callbacks.push(callback);
+    }
+    
+    /// Subscribe to spreads reclassified as structural (and blacklisted)
+    pub fn register_structural_callback(&self, callback: StructuralSpreadCallback) {
+        let mut callbacks = self.structural_callbacks.write().unwrap_or_else(|poisoned| poisoned.into_inner());
         callbacks.push(callback);
     }
     
+    /// Subscribe to mean-reversion signals opening and closing on spreads
+    pub fn register_signal_callback(&self, callback: SpreadSignalCallback) {
+        let mut callbacks = self.signal_callbacks.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        callbacks.push(callback);
+    }
+    
+    /// Stop alerting on `path` as if it had been found structural; false if it
+    /// was already blacklisted
+    pub async fn blacklist_path(&self, path: &str) -> bool {
+        self.spread_persistence.lock().unwrap().blacklist(path, self.clock.now())
+    }
+    
+    pub async fn get_blacklisted_paths(&self) -> Vec<String> {
+        self.spread_persistence.lock().unwrap().blacklisted_paths()
+    }
+    
     pub async fn get_recent_opportunities(&self, limit: usize) -> Vec<ArbitrageOpportunity> {
-        let opportunities = self.opportunities.lock().unwrap();
-        let start_idx = if opportunities.len() > limit {
-            opportunities.len() - limit
-        } else {
-            0
-        };
-        
-        opportunities.range(start_idx..).cloned().collect()
+        self.opportunities.recent(limit)
+    }
+    
+    /// Where alerted opportunities clustered over `heat_window`
+    pub async fn get_heat_report(&self) -> HeatReport {
+        self.heat.report()
+    }
+    
+    /// Repeat suppression so far, with the filters' size and false positive rate
+    pub async fn get_dedup_report(&self) -> DedupReport {
+        self.opportunity_dedup.lock().unwrap().report()
+    }
+    
+    /// Per-minute opportunity counts and exchange uptime, a page at a time
+    pub async fn get_rollups(&self, query: &RollupQuery) -> RollupPage {
+        self.rollups.page(query)
+    }
+    
+    /// Feeds exchange uptime into the rollups
+    pub fn record_connection(&self, exchange: &str, connected: bool) {
+        self.rollups.record_connection(exchange, connected);
+    }
+    
+    /// Target inventory per exchange/asset for the heat seen over `heat_window`
+    pub async fn get_allocation_report(&self) -> AllocationReport {
+        let allocation = self.config.read().unwrap().allocation.clone();
+        optimize(&self.heat.samples(), &allocation, self.heat.window())
+    }
+    
+    fn plan_transfers(path: &str, config: &Config) -> TransferPlan {
+        transfers::plan(path, &config.transfers, &config.allocation.balances, config.max_position_size, &config.markets.aliases)
+    }
+    
+    /// Whether `opportunity` runs on inventory or waits for transfers, and
+    /// what those cost
+    pub fn transfer_plan(&self, opportunity: &ArbitrageOpportunity) -> TransferPlan {
+        Self::plan_transfers(&opportunity.path, &self.config.read().unwrap())
+    }
+    
+    /// Legs of `opportunity` that convert between tickers of one asset rather
+    /// than trade; each needs the conversion to actually be available
+    pub fn conversion_caveats(&self, opportunity: &ArbitrageOpportunity) -> Vec<ConversionCaveat> {
+        let config = self.config.read().unwrap();
+        aliases::conversion_caveats(&opportunity.path, &config.markets.aliases)
+    }
+    
+    /// Whether `opportunity` executes on one venue, on several funded ones,
+    /// or needs a transfer, by current holdings
+    pub fn atomicity(&self, opportunity: &ArbitrageOpportunity) -> Atomicity {
+        let config = self.config.read().unwrap();
+        Atomicity::of(&opportunity.path, &config.allocation.balances)
+    }
+    
+    /// How `opportunity` trades with some legs resting as maker orders, when
+    /// maker legs are enabled and that beats taking every leg
+    pub fn maker_plan(&self, opportunity: &ArbitrageOpportunity) -> Option<MakerPlan> {
+        let config = self.config.read().unwrap();
+        if !config.maker_legs.enabled {
+            return None;
+        }
+        let snapshot = self.snapshots.load();
+        let cycle = opportunity
+            .path
+            .split(" -> ")
+            .map(|name| snapshot.currencies.get(name).copied())
+            .collect::<Option<Vec<usize>>>()?;
+        let plan = maker::best_plan(&cycle, &snapshot.weights, &opportunity.path, &config.fees, &config.symbols, &config.markets.aliases, &config.maker_legs)?;
+        (plan.expected_profit > plan.taker_profit).then_some(plan)
+    }
+    
+    /// Pairs that just became listed on every exchange; their opportunities
+    /// wait out `discovery.new_listings`
+    pub fn mark_listed(&self, symbols: &[String]) {
+        self.listings.mark(symbols, self.clock.now());
+    }
+    
+    /// Pairs listed since startup still warming up or on probation, newest first
+    pub async fn get_new_listings(&self) -> Vec<NewListing> {
+        let config = self.config.read().unwrap().discovery.new_listings.clone();
+        self.listings.report(self.clock.now(), &config)
+    }
+    
+    /// Funding rates polled from perpetual venues
+    pub fn record_funding(&self, rates: Vec<FundingRate>) {
+        self.funding.record(rates);
+    }
+    
+    /// Perpetuals paying at least `funding.min_annualized_yield`, each against
+    /// the best spot quote to hedge it, best yield first
+    pub async fn get_funding_opportunities(&self) -> Vec<FundingOpportunity> {
+        let min_yield = self.config.read().unwrap().funding.min_annualized_yield;
+        let now = self.clock.now();
+        self.funding.opportunities(|symbol| self.quotes.snapshot(symbol, now), min_yield)
+    }
+    
+    /// Dated futures above spot by at least `basis.min_annualized_yield` after
+    /// fees, each against the cheapest spot, best yield first
+    pub async fn get_basis_opportunities(&self) -> Vec<CarryTrade> {
+        let config = self.config.read().unwrap().clone();
+        basis::carry_trades(&self.quotes, &config.basis, &config.fees, &config.markets.aliases, self.clock.unix_ms())
+    }
+    
+    /// Tracked spreads furthest from their mean first, and the latest signals
+    pub async fn get_stat_arb(&self) -> StatArbReport {
+        self.stat_arb.report()
+    }
+    
+    /// Latest quote from every exchange for `symbol`, with implied cross-exchange spreads
+    pub async fn get_market_snapshot(&self, symbol: &str) -> Option<MarketSnapshot> {
+        self.quotes.snapshot(symbol, self.clock.now())
+    }
+    
+    /// Composite best bid and offer for `symbol` across every venue
+    pub async fn get_composite(&self, symbol: &str) -> Option<CompositeQuote> {
+        self.quotes.composite(symbol)
+    }
+    
+    pub async fn get_composites(&self) -> Vec<CompositeQuote> {
+        self.quotes.composites()
+    }
+    
+    /// What local-currency markets (Upbit's KRW) pay over the rest of the
+    /// world, largest premium or discount first
+    pub async fn get_regional_premiums(&self) -> Vec<RegionalPremium> {
+        let mut premiums: Vec<RegionalPremium> = REGIONAL_MARKETS
+            .iter()
+            .flat_map(|(local, bridge)| self.quotes.premiums(local, bridge))
+            .collect();
+        premiums.sort_by(|a, b| b.premium_percentage.abs().total_cmp(&a.premium_percentage.abs()));
+        premiums
+    }
+    
+    /// Depth-weighted fair value for `symbol` across venues, outliers left out
+    pub async fn get_fair_value(&self, symbol: &str) -> Option<FairValue> {
+        self.tick_screen.fair_value(symbol, &self.quotes, self.clock.now())
+    }
+    
+    /// Apply an L2 snapshot or diff to its venue's book
+    pub fn update_book(&self, update: &OrderBookUpdate) -> BookApply {
+        self.depth.apply(update)
+    }
+    
+    /// Drop `exchange`'s books for `symbols`, which can't be trusted once its feed drops
+    pub fn clear_books(&self, exchange: &str, symbols: &[String]) {
+        self.depth.clear(exchange, symbols);
+    }
+    
+    /// Top `levels` of `exchange`'s L2 book for `symbol`
+    pub async fn get_book(&self, exchange: &str, symbol: &str, levels: usize) -> Option<BookView> {
+        self.depth.view(exchange, symbol, levels)
+    }
+    
+    /// How long each venue takes to repeat another's moves, viable latency
+    /// arbitrage first
+    pub async fn get_lead_lag(&self) -> Vec<LeadLag> {
+        self.lead_lag.report()
+    }
+    
+    /// Fake liquidity signs on every feed, suspect feeds first
+    pub async fn get_feed_integrity(&self) -> Vec<FeedIntegrity> {
+        self.tick_screen.feed_integrity()
+    }
+    
+    /// Shared quote book so alerting can price opportunities against the composite
+    pub fn quote_book(&self) -> Arc<QuoteBook> {
+        Arc::clone(&self.quotes)
+    }
+    
+    /// Every validated tick from now on, as the engine applies it
+    pub fn subscribe_ticks(&self) -> tokio::sync::broadcast::Receiver<PublishedTick> {
+        self.tick_feed.subscribe()
+    }
+    
+    /// Best conversion route between two nodes (e.g. `BTC_kraken` -> `USDC_coinbase`)
+    /// from the last periodic route scan
+    pub async fn get_best_route(&self, from: &str, to: &str) -> Option<ConversionRoute> {
+        self.routes.read().unwrap().best_route(from, to)
+    }
+    
+    /// Runtime-adjustable threshold and exchange pauses
+    pub fn controls(&self) -> Arc<RuntimeControls> {
+        Arc::clone(&self.controls)
+    }
+    
+    /// The engine's time source, for anything that schedules against its timestamps
+    pub fn clock(&self) -> SharedClock {
+        Arc::clone(&self.clock)
+    }
+    
+    /// Shared budget tracker so connectors and alerting can report their stage latencies
+    pub fn budget_tracker(&self) -> Arc<BudgetTracker> {
+        Arc::clone(&self.budgets)
+    }
+    
+    pub async fn get_latency_budget_report(&self) -> Vec<StageBudgetReport> {
+        self.budgets.report()
     }
     
     pub async fn get_performance_stats(&self) -> PerformanceStats {
-        self.stats.lock().unwrap().clone()
+        self.stats.snapshot()
     }
 }
 
@@ -566,6 +1920,7 @@ impl Drop for ArbitrageEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::ManualClock;
     
     #[tokio::test]
     async fn test_engine_lifecycle() {
@@ -600,6 +1955,85 @@ mod tests {
         engine.stop().await;
     }
     
+    #[tokio::test]
+    async fn test_batch_price_update() {
+        let engine = ArbitrageEngine::new(Config::default());
+        engine.start().await;
+        
+        let update = |symbol: &str, bid: f64, ask: f64| PriceUpdate {
+            exchange: "binance".to_string(),
+            symbol: symbol.to_string(),
+            bid,
+            ask,
+            volume: 1.0,
+        };
+        engine.update_prices(&[]).await.unwrap();
+        engine
+            .update_prices(&[
+                update("BTC/USDT", 50000.0, 50001.0),
+                update("ETH/USDT", 3000.0, 3000.5),
+                update("ETH/BTC", 0.06, 0.0601),
+            ])
+            .await
+            .unwrap();
+        
+        time::sleep(Duration::from_millis(50)).await;
+        
+        {
+            let currencies = engine.currency_map.read().unwrap();
+            let graph = engine.price_graph.read().unwrap();
+            assert_eq!(currencies.len(), 3);
+            
+            let eth = currencies["ETH_binance"];
+            let btc = currencies["BTC_binance"];
+            assert!((graph[eth][btc] + f64::ln(0.06)).abs() < 1e-12);
+            assert!((graph[btc][eth] + f64::ln(1.0 / 0.0601)).abs() < 1e-12);
+        }
+        assert_eq!(engine.get_performance_stats().await.messages_processed, 3);
+        
+        // The whole batch lands in a single published epoch
+        let snapshot = engine.snapshots.load();
+        assert_eq!(snapshot.epoch, 1);
+        assert_eq!(snapshot.len(), 3);
+        
+        engine.stop().await;
+    }
+    
+    #[tokio::test]
+    async fn test_manual_clock_stamps_ticks() {
+        let clock = Arc::new(ManualClock::new(1_700_000_000_000));
+        let engine = ArbitrageEngine::new(Config::default()).with_clock(clock.clone());
+        engine.start().await;
+        
+        engine.update_price("binance", "BTC/USDT", 50000.0, 50001.0, 1.0).await.unwrap();
+        time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(engine.get_market_snapshot("BTC/USDT").await.unwrap().quotes[0].age_ms, 0);
+        
+        clock.advance(Duration::from_millis(250));
+        assert_eq!(engine.get_market_snapshot("BTC/USDT").await.unwrap().quotes[0].age_ms, 250);
+        
+        engine.stop().await;
+    }
+    
+    #[test]
+    fn test_config_durations_are_human_readable() {
+        let json = serde_json::to_value(Config::default()).unwrap();
+        assert_eq!(json["websocket_timeout"], "30s");
+        assert_eq!(json["hot_detection_interval"], "2ms");
+        assert_eq!(json["latency_budgets"]["parse"], "50us");
+        
+        let mut edited = json.clone();
+        edited["websocket_timeout"] = "1m 30s".into();
+        edited["structural_blacklist_duration"] = "2h".into();
+        let config: Config = serde_json::from_value(edited).unwrap();
+        assert_eq!(config.websocket_timeout, Duration::from_secs(90));
+        assert_eq!(config.structural_blacklist_duration, Duration::from_secs(7200));
+        
+        edited = json;
+        edited["reconnect_interval"] = "soon".into();
+        assert!(serde_json::from_value::<Config>(edited).is_err());
+    }
+    
     #[test]
     fn test_symbol_parsing() {
         assert_eq!(
@@ -609,4 +2043,590 @@ mod tests {
         
         assert_eq!(ArbitrageEngine::parse_symbol("INVALID"), None);
     }
+    
+    #[test]
+    fn test_panicking_callback_does_not_stop_other_subscribers() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        
+        let engine = ArbitrageEngine::new(Config::default());
+        let delivered = Arc::new(AtomicUsize::new(0));
+        
+        engine.register_callback(Box::new(|_| panic!("subscriber bug")));
+        let counter = Arc::clone(&delivered);
+        engine.register_callback(Box::new(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+        
+        let opp = ArbitrageOpportunity::sample("BTC_binance -> ETH_binance -> USDT_binance", 0.01);
+        
+        assert_eq!(ArbitrageEngine::notify_callbacks(&engine.callbacks, &opp), 1);
+        assert_eq!(ArbitrageEngine::notify_callbacks(&engine.callbacks, &opp), 1);
+        assert_eq!(delivered.load(Ordering::SeqCst), 2);
+        
+        // Registry must still be usable after the panics
+        engine.register_callback(Box::new(|_| {}));
+        assert_eq!(engine.callbacks.read().unwrap().len(), 3);
+    }
+    
+    #[test]
+    fn test_hot_subgraph_detection() {
+        let mut config = Config::default();
+        config.min_profit_threshold = 0.0;
+        let inf = f64::INFINITY;
+        
+        // Profitable cycle 0 -> 1 -> 2 -> 0; nodes 3 and 4 form an unrelated pair
+        let graph = vec![
+            vec![0.0, -f64::ln(0.85), inf, inf, inf],
+            vec![inf, 0.0, -f64::ln(0.90), inf, inf],
+            vec![-f64::ln(1.35), inf, 0.0, inf, inf],
+            vec![inf, inf, inf, 0.0, 0.0],
+            vec![inf, inf, inf, 0.0, 0.0],
+        ];
+        let currencies: HashMap<String, usize> = ["USD_x", "EUR_x", "GBP_x", "AAA_y", "BBB_y"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.to_string(), i))
+            .collect();
+        let snapshot = GraphSnapshot::new(1, graph, currencies);
+        
+        let full = ArbitrageEngine::detect_arbitrage_opportunities(&snapshot, &config, None, Instant::now());
+        assert!(!full.is_empty());
+        
+        let mut hot = HotSet::new(Duration::from_secs(30));
+        hot.mark(&full[0], Instant::now());
+        let focused = ArbitrageEngine::detect_arbitrage_opportunities(&snapshot, &config, Some(&hot), Instant::now());
+        assert!(!focused.is_empty());
+        
+        // A hot set far from the cycle must not find it
+        let mut cold = HotSet::new(Duration::from_secs(30));
+        cold.mark(&ArbitrageOpportunity { path: "AAA_y -> BBB_y".to_string(), ..full[0].clone() }, Instant::now());
+        assert!(ArbitrageEngine::detect_arbitrage_opportunities(&snapshot, &config, Some(&cold), Instant::now()).is_empty());
+    }
+    
+    #[test]
+    fn test_opportunities_are_net_of_fees() {
+        let mut config = Config::default();
+        config.min_profit_threshold = 0.0;
+        let inf = f64::INFINITY;
+        
+        // 0.85 * 0.90 * 1.35 = 3.275% before fees
+        let graph = vec![
+            vec![0.0, -f64::ln(0.85), inf],
+            vec![inf, 0.0, -f64::ln(0.90)],
+            vec![-f64::ln(1.35), inf, 0.0],
+        ];
+        let currencies: HashMap<String, usize> = ["USD_x", "EUR_x", "GBP_x"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.to_string(), i))
+            .collect();
+        let snapshot = GraphSnapshot::new(1, graph, currencies);
+        let detect = |taker: f64| {
+            let config = Config {
+                fees: HashMap::from([("x".to_string(), Fees { maker: 0.0, taker, charged_in: None })]),
+                ..config.clone()
+            };
+            ArbitrageEngine::detect_arbitrage_opportunities(&snapshot, &config, None, Instant::now())
+        };
+        
+        let found = detect(0.005);
+        assert!(!found.is_empty());
+        let net = 1.03275 * 0.995f64.powi(3) - 1.0;
+        assert!(found.iter().all(|opp| (opp.profit_percentage - net).abs() < 1e-9));
+        // Three 2% fees eat the whole spread
+        assert!(detect(0.02).is_empty());
+        
+        assert!(found.iter().all(|opp| (opp.gross_profit - 0.03275).abs() < 1e-9));
+        
+        // Gross is what the quotes paid when found; later fee changes leave it be
+        let engine = ArbitrageEngine::new(config);
+        engine.opportunities.push(found[0].clone());
+        engine.set_account_fees("x", Fees { maker: 0.0, taker: 0.0, charged_in: None });
+        engine.apply_config(Config::default());
+        let recorded = &engine.opportunities.recent(1)[0];
+        assert!((recorded.gross_profit - 0.03275).abs() < 1e-9);
+        assert!((recorded.profit_percentage - net).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_maker_legs_rescue_cycles_fees_make_marginal() {
+        let mut graph = vec![vec![f64::INFINITY; 3]; 3];
+        for (base, quote, bid, ask) in [(0, 2, 50000.0, 50010.0), (1, 0, 0.05983, 0.05984), (1, 2, 3000.0, 3000.3)] {
+            graph[base][quote] = -f64::ln(bid);
+            graph[quote][base] = f64::ln(ask);
+        }
+        let currencies: HashMap<String, usize> = ["BTC_binance", "ETH_binance", "USDT_binance"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.to_string(), i))
+            .collect();
+        let snapshot = GraphSnapshot::new(1, graph, currencies);
+        let mut config = Config {
+            min_profit_threshold: 0.0,
+            fees: HashMap::from([("binance".to_string(), Fees { maker: -0.0001, taker: 0.001, charged_in: None })]),
+            ..Config::default()
+        };
+        
+        // 0.25% before fees doesn't cover three taker fees
+        assert!(ArbitrageEngine::detect_arbitrage_opportunities(&snapshot, &config, None, Instant::now()).is_empty());
+        
+        config.maker_legs.enabled = true;
+        let found = ArbitrageEngine::detect_arbitrage_opportunities(&snapshot, &config, None, Instant::now());
+        assert!(!found.is_empty());
+        assert!(found.iter().all(|opp| opp.profit_percentage > 0.0 && opp.exchanges == ["binance"; 3]));
+    }
+    
+    #[tokio::test]
+    async fn test_renamed_ticker_cycles_carry_caveats() {
+        let engine = ArbitrageEngine::new(Config::default());
+        engine.start().await;
+        
+        // Kraken still quotes RNDR below RENDER; the migration converts 1:1
+        let update = |symbol: &str, bid: f64, ask: f64| PriceUpdate {
+            exchange: "kraken".to_string(),
+            symbol: symbol.to_string(),
+            bid,
+            ask,
+            volume: 1.0,
+        };
+        engine
+            .update_prices(&[update("RNDR/USD", 7.0, 7.01), update("RENDER/USD", 7.2, 7.21)])
+            .await
+            .unwrap();
+        time::sleep(Duration::from_millis(100)).await;
+        
+        let found = engine.get_recent_opportunities(10).await;
+        assert!(!found.is_empty(), "USD -> RNDR -> RENDER -> USD should pay ~2.7%");
+        let caveats = engine.conversion_caveats(&found[0]);
+        assert_eq!(caveats.len(), 1);
+        assert_eq!((caveats[0].from.as_str(), caveats[0].to.as_str()), ("RNDR_kraken", "RENDER_kraken"));
+        assert!(!caveats[0].transfer);
+        
+        engine.stop().await;
+    }
+    
+    #[tokio::test]
+    async fn test_blacklisted_cycle_leaves_its_cluster_alerting() {
+        // EUR is rich against both GBP and JPY; the two triangles share the
+        // USD and EUR nodes and cluster together
+        let config = Config { opportunity_cluster_similarity: 0.3, ..Config::default() };
+        let update = |symbol: &str, bid: f64, ask: f64| PriceUpdate {
+            exchange: "kraken".to_string(),
+            symbol: symbol.to_string(),
+            bid,
+            ask,
+            volume: 1.0,
+        };
+        let updates = [
+            update("EUR/USD", 1.10, 1.1001),
+            update("GBP/USD", 1.30, 1.3001),
+            update("JPY/USD", 0.0070, 0.0070001),
+            update("EUR/GBP", 0.87, 0.8701),
+            update("EUR/JPY", 160.0, 160.01),
+        ];
+        let alerted = |blacklisted: Option<String>| {
+            let (config, updates) = (config.clone(), updates.clone());
+            async move {
+                let engine = ArbitrageEngine::new(config);
+                if let Some(path) = &blacklisted {
+                    engine.blacklist_path(path).await;
+                }
+                engine.start().await;
+                engine.update_prices(&updates).await.unwrap();
+                time::sleep(Duration::from_millis(100)).await;
+                let found = engine.get_recent_opportunities(10).await;
+                engine.stop().await;
+                found
+            }
+        };
+        
+        let best = alerted(None).await;
+        assert_eq!(best.len(), 1, "one alert for the cluster");
+        let rest = alerted(Some(best[0].path.clone())).await;
+        assert_eq!(rest.len(), 1, "the next cycle in the cluster alerts instead");
+        assert_ne!(rest[0].path, best[0].path);
+        assert!(rest[0].profit_percentage <= best[0].profit_percentage);
+        assert_eq!(best[0].kind, OpportunityKind::Cycle);
+    }
+    
+    #[test]
+    fn test_carry_trades_are_tagged_basis() {
+        let book = QuoteBook::new();
+        for (exchange, symbol, bid, ask) in [("deribit", "BTC-27DEC24/USD", 102_000.0, 102_100.0), ("binance", "BTC/USDT", 99_900.0, 100_000.0)] {
+            book.update(&MarketTick {
+                exchange: exchange.to_string(),
+                symbol: symbol.to_string(),
+                bid,
+                ask,
+                last_price: (bid + ask) / 2.0,
+                volume: 1.0,
+                timestamp: Instant::now(),
+                sequence: 0,
+            });
+        }
+        // A tenth of a year before expiry
+        let now_ms = 1_735_286_400_000 - 3_153_600_000;
+        
+        let found = ArbitrageEngine::basis_opportunities(&book, &Config::default(), now_ms, Instant::now());
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kind, OpportunityKind::Basis);
+        assert_eq!(serde_json::to_value(&found[0]).unwrap()["kind"], "basis");
+    }
+    
+    #[test]
+    fn test_partitions_detect_separately_with_their_own_thresholds() {
+        let inf = f64::INFINITY;
+        let triangle = |rates: [f64; 3]| {
+            vec![
+                vec![0.0, -rates[0].ln(), inf],
+                vec![inf, 0.0, -rates[1].ln()],
+                vec![-rates[2].ln(), inf, 0.0],
+            ]
+        };
+        // A 3.275% USDT-quoted cycle and a 0.5% EUR-quoted one, disconnected
+        let (usdt, eur) = (triangle([0.85, 0.90, 1.35]), triangle([1.0, 1.0, 1.005]));
+        let mut graph = vec![vec![inf; 6]; 6];
+        for i in 0..3 {
+            for j in 0..3 {
+                graph[i][j] = usdt[i][j];
+                graph[i + 3][j + 3] = eur[i][j];
+            }
+        }
+        let currencies: HashMap<String, usize> = ["USDT_x", "BTC_x", "ETH_x", "EUR_x", "GBP_x", "CHF_x"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.to_string(), i))
+            .collect();
+        let snapshot = GraphSnapshot::new(1, graph, currencies);
+        
+        let partition = |name: &str, threshold: Option<f64>| GraphPartition {
+            name: name.to_lowercase(),
+            quotes: vec![name.to_string()],
+            min_profit_threshold: threshold,
+        };
+        let config = |usdt_threshold: f64| Config {
+            symbols: ["BTC/USDT", "ETH/USDT", "ETH/BTC", "GBP/EUR", "CHF/EUR"].iter().map(|s| s.to_string()).collect(),
+            min_profit_threshold: 0.001,
+            graph_partitions: vec![partition("USDT", Some(usdt_threshold)), partition("EUR", None)],
+            ..Config::default()
+        };
+        
+        let found = ArbitrageEngine::detect_arbitrage_opportunities(&snapshot, &config(0.05), None, Instant::now());
+        assert!(!found.is_empty());
+        assert!(found.iter().all(|opp| opp.path.contains("EUR_x")), "USDT cycles are under 5%: {:?}", found);
+        
+        let found = ArbitrageEngine::detect_arbitrage_opportunities(&snapshot, &config(0.01), None, Instant::now());
+        assert!(found.iter().any(|opp| opp.path.contains("USDT_x")));
+        assert!(found.iter().any(|opp| opp.path.contains("EUR_x")));
+        assert!(found.iter().all(|opp| !(opp.path.contains("USDT_x") && opp.path.contains("EUR_x"))));
+    }
+    
+    #[test]
+    fn test_detection_backends_agree() {
+        let mut config = Config::default();
+        config.min_profit_threshold = 0.0;
+        let n = 24;
+        
+        // Two sparse rings of lossy conversions, each hiding one profitable triangle
+        let mut graph = vec![vec![f64::INFINITY; n]; n];
+        for u in 0..n {
+            let base = u / 12 * 12;
+            graph[u][u] = 0.0;
+            graph[u][base + (u - base + 1) % 12] = -f64::ln(0.995);
+            graph[u][base + (u - base + 5) % 12] = -f64::ln(0.98);
+        }
+        for start in [3, 14] {
+            graph[start][start + 1] = -f64::ln(1.01);
+            graph[start + 1][start + 2] = -f64::ln(1.01);
+            graph[start + 2][start] = -f64::ln(1.01);
+        }
+        let currencies: HashMap<String, usize> = (0..n).map(|i| (format!("C{}_x", i), i)).collect();
+        let snapshot = GraphSnapshot::new(1, graph, currencies);
+        
+        let cycles = |backend| {
+            let config = Config { detection_backend: backend, ..config.clone() };
+            let mut found: Vec<Vec<String>> = ArbitrageEngine::detect_arbitrage_opportunities(&snapshot, &config, None, Instant::now())
+                .into_iter()
+                .map(|opp| {
+                    let mut nodes: Vec<String> = opp.path.split(" -> ").map(str::to_string).collect();
+                    nodes.sort();
+                    nodes
+                })
+                .collect();
+            found.sort();
+            found.dedup();
+            found
+        };
+        
+        let bellman_ford = cycles(DetectionBackend::BellmanFord);
+        assert_eq!(bellman_ford.len(), 2);
+        assert_eq!(bellman_ford, cycles(DetectionBackend::Johnson));
+    }
+    
+    #[test]
+    fn test_edge_weight_rejects_degenerate_rates() {
+        for rate in [0.0, -1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 1e-20, 1e20] {
+            assert_eq!(ArbitrageEngine::rate_to_edge_weight(rate), None, "rate {}", rate);
+        }
+        
+        // Just past the valid range is a glitch; at its edges the weight is bounded
+        assert_eq!(ArbitrageEngine::rate_to_edge_weight(MIN_VALID_RATE / 10.0), None);
+        assert_eq!(ArbitrageEngine::rate_to_edge_weight(MAX_VALID_RATE * 10.0), None);
+        let bound = MAX_VALID_RATE.ln();
+        for rate in [MIN_VALID_RATE, MAX_VALID_RATE] {
+            let weight = ArbitrageEngine::rate_to_edge_weight(rate).unwrap();
+            assert!((weight.abs() - bound).abs() < 1e-9, "rate {}", rate);
+        }
+        assert_eq!(ArbitrageEngine::rate_to_edge_weight(1.0), Some(0.0));
+    }
+    
+    #[test]
+    fn test_degenerate_tick_removes_edges() {
+        let price_graph = Arc::new(RwLock::new(vec![vec![f64::INFINITY; 4]; 4]));
+        let currency_map = Arc::new(RwLock::new(HashMap::new()));
+        let tick = |bid: f64, ask: f64| MarketTick {
+            exchange: "binance".to_string(),
+            symbol: "SHIB/USDT".to_string(),
+            bid,
+            ask,
+            last_price: (bid + ask) / 2.0,
+            volume: 1.0,
+            timestamp: Instant::now(),
+            sequence: 0,
+        };
+        
+        ArbitrageEngine::process_market_tick(tick(0.00001, 0.000011), &price_graph, &currency_map);
+        assert!(price_graph.read().unwrap()[0][1].is_finite());
+        assert!(price_graph.read().unwrap()[1][0].is_finite());
+        
+        // A bid=0 / NaN ask glitch must clear the edges rather than write inf/NaN weights
+        ArbitrageEngine::process_market_tick(tick(0.0, f64::NAN), &price_graph, &currency_map);
+        let graph = price_graph.read().unwrap();
+        assert_eq!(graph[0][1], f64::INFINITY);
+        assert_eq!(graph[1][0], f64::INFINITY);
+        assert!(graph.iter().flatten().all(|w| !w.is_nan()));
+    }
+    
+    #[tokio::test]
+    async fn test_tick_subscribers_only_see_validated_ticks() {
+        let engine = ArbitrageEngine::new(Config::default());
+        let mut ticks = engine.subscribe_ticks();
+        engine.start().await;
+        
+        engine.update_price("binance", "BTC/USDT", 0.0, 50001.0, 1.0).await.unwrap();
+        engine.update_price("binance", "BTCUSDT", 50000.0, 50001.0, 1.0).await.unwrap();
+        engine.update_price("kraken", "BTC/USDT", 50000.0, 50001.0, 1.0).await.unwrap();
+        
+        let tick = time::timeout(Duration::from_secs(1), ticks.recv()).await.unwrap().unwrap();
+        assert_eq!((tick.exchange.as_str(), tick.bid), ("kraken", 50000.0));
+        assert!(ticks.try_recv().is_err());
+        engine.stop().await;
+    }
+    
+    #[tokio::test]
+    async fn test_engines_in_one_process_number_their_own_ticks() {
+        let spawned = ArbitrageEngine::new_in(Config::default(), Handle::current());
+        let embedded = ArbitrageEngine::new(Config::default());
+        let (mut spawned_ticks, mut embedded_ticks) = (spawned.subscribe_ticks(), embedded.subscribe_ticks());
+        spawned.start().await;
+        for _ in 0..3 {
+            spawned.update_price("binance", "BTC/USDT", 50000.0, 50001.0, 1.0).await.unwrap();
+        }
+        
+        // Driven in place: the embedded engine's loops run inside this select
+        let tick = tokio::select! {
+            _ = embedded.run() => unreachable!("run only resolves after stop"),
+            tick = async {
+                embedded.update_price("kraken", "BTC/USDT", 50000.0, 50001.0, 1.0).await.unwrap();
+                time::timeout(Duration::from_secs(1), embedded_ticks.recv()).await.unwrap().unwrap()
+            } => tick,
+        };
+        assert_eq!(tick.sequence, 0);
+        assert!(embedded.task_handles.lock().unwrap().is_empty());
+        
+        let mut sequences = Vec::new();
+        for _ in 0..3 {
+            sequences.push(time::timeout(Duration::from_secs(1), spawned_ticks.recv()).await.unwrap().unwrap().sequence);
+        }
+        assert_eq!(sequences, vec![0, 1, 2]);
+        spawned.stop().await;
+    }
+    
+    #[tokio::test]
+    async fn test_reloaded_aliases_relink_the_running_graph() {
+        let engine = ArbitrageEngine::new(Config::default());
+        engine.start().await;
+        engine.update_price("binance", "RNDR/USDT", 7.0, 7.01, 1.0).await.unwrap();
+        engine.update_price("binance", "RENDER/USDT", 7.0, 7.01, 1.0).await.unwrap();
+        // Waits for the processor to give RNDR -> RENDER this weight
+        async fn settle(engine: &ArbitrageEngine, expected: f64) {
+            let rename = || {
+                let map = engine.currency_map.read().unwrap();
+                let graph = engine.price_graph.read().unwrap();
+                map.get("RNDR_binance").zip(map.get("RENDER_binance")).map(|(&u, &v)| graph[u][v])
+            };
+            for _ in 0..1000 {
+                if rename().is_some_and(|weight| weight == expected || (weight - expected).abs() < 1e-12) {
+                    return;
+                }
+                time::sleep(Duration::from_millis(1)).await;
+            }
+            panic!("rename edge never became {}: {:?}", expected, rename());
+        }
+        settle(&engine, 0.0).await;
+        
+        // Dropping the alias unlinks the tickers without a restart
+        let mut config = Config::default();
+        config.markets.aliases.retain(|alias| alias.asset != "RENDER");
+        engine.apply_config(config.clone());
+        settle(&engine, f64::INFINITY).await;
+        
+        // And a new conversion cost is picked up as well
+        config.markets.aliases.push(AssetAlias {
+            asset: "RENDER".to_string(),
+            aliases: vec!["RNDR".to_string()],
+            kind: crate::markets::AliasKind::Rename,
+            conversion_cost: 0.01,
+        });
+        engine.apply_config(config);
+        settle(&engine, -(0.99f64).ln()).await;
+        engine.stop().await;
+    }
+    
+    #[test]
+    fn test_apply_config_drops_removed_feeds() {
+        let config = Config {
+            exchanges: vec!["binance".to_string(), "kraken".to_string()],
+            symbols: vec!["BTC/USDT".to_string(), "ETH/USDT".to_string()],
+            ..Config::default()
+        };
+        let engine = ArbitrageEngine::new(config.clone());
+        for (exchange, symbol) in [("binance", "BTC/USDT"), ("binance", "ETH/USDT"), ("kraken", "ETH/USDT")] {
+            let tick = MarketTick {
+                exchange: exchange.to_string(),
+                symbol: symbol.to_string(),
+                bid: 100.0,
+                ask: 101.0,
+                last_price: 100.5,
+                volume: 1.0,
+                timestamp: Instant::now(),
+                sequence: 0,
+            };
+            ArbitrageEngine::apply_message(TickMessage::Single(tick), &engine.quotes, &engine.tick_feed, &engine.tick_screen, &engine.lead_lag, &engine.price_graph, &engine.currency_map);
+        }
+        
+        engine.apply_config(Config {
+            symbols: vec!["BTC/USDT".to_string()],
+            min_profit_threshold: 0.004,
+            ..config
+        });
+        assert_eq!(engine.controls.min_profit_threshold(), 0.004);
+        assert_eq!(engine.config_version.load(std::sync::atomic::Ordering::SeqCst), 1);
+        
+        let message = engine.tick_receiver.lock().unwrap().try_recv().unwrap();
+        let TickMessage::Remove(removed) = &message else {
+            panic!("expected a removal");
+        };
+        assert_eq!(removed, &[("binance".to_string(), "ETH/USDT".to_string()), ("kraken".to_string(), "ETH/USDT".to_string())]);
+        ArbitrageEngine::apply_message(message, &engine.quotes, &engine.tick_feed, &engine.tick_screen, &engine.lead_lag, &engine.price_graph, &engine.currency_map);
+        
+        assert!(engine.quotes.snapshot("ETH/USDT", Instant::now()).is_none());
+        assert!(engine.quotes.snapshot("BTC/USDT", Instant::now()).is_some());
+        let map = engine.currency_map.read().unwrap();
+        let graph = engine.price_graph.read().unwrap();
+        assert_eq!(graph[map["ETH_binance"]][map["USDT_binance"]], f64::INFINITY);
+        assert!(graph[map["BTC_binance"]][map["USDT_binance"]].is_finite());
+    }
+    
+    #[test]
+    fn test_non_finite_cycle_is_not_an_opportunity() {
+        let mut currencies = HashMap::new();
+        for (i, name) in ["BTC_binance", "ETH_binance", "USDT_binance"].iter().enumerate() {
+            currencies.insert(name.to_string(), i);
+        }
+        let graph = vec![
+            vec![0.0, f64::NEG_INFINITY, f64::INFINITY],
+            vec![f64::INFINITY, 0.0, -1.0],
+            vec![-1.0, f64::INFINITY, 0.0],
+        ];
+        
+        assert!(ArbitrageEngine::cycle_to_opportunity(vec![0, 1, 2], &currencies, &graph, &Config::default(), Instant::now()).is_none());
+    }
+    
+    /// Dense graph with near-fair rates and a few planted profitable triangles
+    #[cfg(feature = "gpu")]
+    fn synthetic_graph(n: usize) -> Vec<Vec<f64>> {
+        let mut seed: u64 = 0x2545_F491_4F6C_DD1D;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % 10_000) as f64 / 10_000.0
+        };
+        
+        let mut graph = vec![vec![0.0; n]; n];
+        for u in 0..n {
+            for v in 0..n {
+                if u != v {
+                    // Spread keeps every random cycle unprofitable
+                    graph[u][v] = 0.001 + next() * 0.01;
+                }
+            }
+        }
+        for start in (0..n.saturating_sub(2)).step_by(n.max(16) / 8) {
+            graph[start][start + 1] = -0.002;
+            graph[start + 1][start + 2] = -0.002;
+            graph[start + 2][start] = -0.002;
+        }
+        graph
+    }
+    
+    #[cfg(feature = "gpu")]
+    #[test]
+    fn test_gpu_sources_match_cpu() {
+        let Some(relaxer) = super::super::gpu::shared() else {
+            return; // No adapter on this machine
+        };
+        let n = 64;
+        let graph = synthetic_graph(n);
+        
+        let cpu: Vec<usize> = (0..n)
+            .filter(|&s| ArbitrageEngine::bellman_ford_negative_cycle(&graph, s, n).is_some())
+            .collect();
+        let gpu = relaxer.negative_cycle_sources(&graph, n).unwrap();
+        
+        // GPU flags nodes on a cycle; the CPU also reports sources that merely reach one
+        assert!(!gpu.is_empty());
+        assert!(gpu.iter().all(|s| cpu.contains(s)));
+    }
+    
+    /// cargo test --release --features gpu -- --ignored bench_cpu_vs_gpu --nocapture
+    #[cfg(feature = "gpu")]
+    #[test]
+    #[ignore]
+    fn bench_cpu_vs_gpu_detection() {
+        let Some(relaxer) = super::super::gpu::shared() else {
+            println!("no GPU adapter available");
+            return;
+        };
+        
+        println!("{:>6} {:>14} {:>14}", "nodes", "cpu", "gpu");
+        for n in [32, 64, 128, 256, 512] {
+            let graph = synthetic_graph(n);
+            
+            let start = Instant::now();
+            for source in 0..n {
+                let _ = ArbitrageEngine::bellman_ford_negative_cycle(&graph, source, n);
+            }
+            let cpu = start.elapsed();
+            
+            let start = Instant::now();
+            for source in relaxer.negative_cycle_sources(&graph, n).unwrap() {
+                let _ = ArbitrageEngine::bellman_ford_negative_cycle(&graph, source, n);
+            }
+            let gpu = start.elapsed();
+            
+            println!("{:>6} {:>14?} {:>14?}", n, cpu, gpu);
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,278 @@
+// arbitrage/fairvalue.rs - Depth-weighted multi-venue fair value with outlier rejection
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::quotes::{QuoteBook, VenueQuote};
+use super::types::{ArbitrageOpportunity, MarketTick};
+use super::washtrade::{FeedIntegrity, WashMonitor, WashTradingConfig};
+
+/// Scales a median absolute deviation to a standard deviation for normal data
+const MAD_SCALE: f64 = 1.4826;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FairValueConfig {
+    /// Venues further than this many (scaled) median absolute deviations from
+    /// the median mid are left out as outliers
+    pub outlier_mads: f64,
+    /// Floor on the outlier band as a fraction of the median mid, so venues
+    /// agreeing to the tick don't make every small move an outlier
+    pub min_outlier_band: f64,
+    /// Quotes older than this don't count towards the fair value
+    #[serde(with = "humantime_serde")]
+    pub max_quote_age: Duration,
+    /// Ticks further than this fraction from the other venues' fair value are
+    /// dropped as bad prints before they reach the price graph; zero turns
+    /// the check off
+    pub max_tick_deviation: f64,
+}
+
+impl Default for FairValueConfig {
+    fn default() -> Self {
+        Self {
+            outlier_mads: 3.0,
+            min_outlier_band: 0.001,
+            max_quote_age: Duration::from_secs(10),
+            max_tick_deviation: 0.1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FairValueVenue {
+    pub exchange: String,
+    pub mid: f64,
+    /// Share of the fair value; zero for outliers and stale quotes
+    pub weight: f64,
+    pub outlier: bool,
+    pub stale: bool,
+}
+
+/// One symbol's fair value and how each venue contributed to it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FairValue {
+    pub symbol: String,
+    pub fair_value: f64,
+    pub median_mid: f64,
+    pub venues: Vec<FairValueVenue>,
+    /// Ticks for the symbol dropped as bad prints since startup
+    pub rejected_ticks: u64,
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let n = sorted.len();
+    if n % 2 == 1 {
+        sorted[n / 2]
+    } else {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+    }
+}
+
+/// Mid of every venue with a usable quote, weighted by its top-of-book depth,
+/// leaving out stale quotes and venues far from the median
+pub fn fair_value(symbol: &str, quotes: &[VenueQuote], config: &FairValueConfig) -> Option<FairValue> {
+    let max_age_ms = config.max_quote_age.as_millis() as u64;
+    let usable: Vec<&VenueQuote> = quotes
+        .iter()
+        .filter(|quote| quote.bid > 0.0 && quote.ask >= quote.bid && quote.ask.is_finite())
+        .collect();
+    let mut fresh: Vec<f64> = usable
+        .iter()
+        .filter(|quote| quote.age_ms <= max_age_ms)
+        .map(|quote| (quote.bid + quote.ask) / 2.0)
+        .collect();
+    if fresh.is_empty() {
+        return None;
+    }
+    fresh.sort_by(f64::total_cmp);
+    let median_mid = median(&fresh);
+    let mut deviations: Vec<f64> = fresh.iter().map(|mid| (mid - median_mid).abs()).collect();
+    deviations.sort_by(f64::total_cmp);
+    let band = (config.outlier_mads * MAD_SCALE * median(&deviations)).max(config.min_outlier_band * median_mid);
+    
+    let mut venues: Vec<FairValueVenue> = usable
+        .iter()
+        .map(|quote| {
+            let mid = (quote.bid + quote.ask) / 2.0;
+            let stale = quote.age_ms > max_age_ms;
+            let outlier = !stale && (mid - median_mid).abs() > band;
+            FairValueVenue {
+                exchange: quote.exchange.clone(),
+                mid,
+                // A venue showing no size still counts, as a venue with the least
+                weight: if stale || outlier { 0.0 } else { quote.volume.max(f64::MIN_POSITIVE) },
+                outlier,
+                stale,
+            }
+        })
+        .collect();
+    let total: f64 = venues.iter().map(|venue| venue.weight).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    for venue in &mut venues {
+        venue.weight /= total;
+    }
+    Some(FairValue {
+        symbol: symbol.to_string(),
+        fair_value: venues.iter().map(|venue| venue.mid * venue.weight).sum(),
+        median_mid,
+        venues,
+        rejected_ticks: 0,
+    })
+}
+
+/// How far `tick` strays from the fair value of the other venues quoting its
+/// symbol, when that is more than `max_tick_deviation`. Takes two other
+/// venues to judge; a lone feed is never rejected
+pub fn anomaly(tick: &MarketTick, quotes: &[VenueQuote], config: &FairValueConfig) -> Option<f64> {
+    if config.max_tick_deviation <= 0.0 {
+        return None;
+    }
+    let others: Vec<VenueQuote> = quotes.iter().filter(|quote| quote.exchange != tick.exchange).cloned().collect();
+    let reference = fair_value(&tick.symbol, &others, config)?;
+    if reference.venues.iter().filter(|venue| venue.weight > 0.0).count() < 2 {
+        return None;
+    }
+    let deviation = ((tick.bid + tick.ask) / 2.0 / reference.fair_value - 1.0).abs();
+    (deviation > config.max_tick_deviation).then_some(deviation)
+}
+
+/// Drops bad prints before they reach the quote book and price graph, and
+/// counts them by symbol. Every tick also goes to the wash-trading monitor
+pub struct TickScreen {
+    config: RwLock<FairValueConfig>,
+    rejected: Mutex<HashMap<String, u64>>,
+    wash: WashMonitor,
+}
+
+impl TickScreen {
+    pub fn new(config: FairValueConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            rejected: Mutex::new(HashMap::new()),
+            wash: WashMonitor::new(WashTradingConfig::default()),
+        }
+    }
+    
+    pub fn with_wash_trading(mut self, config: WashTradingConfig) -> Self {
+        self.wash = WashMonitor::new(config);
+        self
+    }
+    
+    pub fn set_config(&self, config: FairValueConfig) {
+        *self.config.write().unwrap() = config;
+    }
+    
+    pub fn set_wash_trading(&self, config: WashTradingConfig) {
+        self.wash.set_config(config);
+    }
+    
+    /// Whether `tick` is close enough to the other venues in `quotes` to apply
+    pub fn admit(&self, tick: &MarketTick, quotes: &QuoteBook) -> bool {
+        let venues = quotes.venue_quotes(&tick.symbol, tick.timestamp).unwrap_or_default();
+        self.wash.observe(tick, &venues);
+        let config = self.config.read().unwrap();
+        if config.max_tick_deviation <= 0.0 || venues.is_empty() {
+            return true;
+        }
+        match anomaly(tick, &venues, &config) {
+            Some(deviation) => {
+                debug!("Dropping {} {} tick {:.2}% off the other venues' fair value", tick.exchange, tick.symbol, deviation * 100.0);
+                *self.rejected.lock().unwrap().entry(tick.symbol.clone()).or_default() += 1;
+                false
+            }
+            None => true,
+        }
+    }
+    
+    /// Fair value for `symbol` from `quotes` as of `now`, with the ticks
+    /// dropped for it so far
+    pub fn fair_value(&self, symbol: &str, quotes: &QuoteBook, now: Instant) -> Option<FairValue> {
+        let mut value = fair_value(symbol, &quotes.venue_quotes(symbol, now)?, &self.config.read().unwrap())?;
+        value.rejected_ticks = self.rejected.lock().unwrap().get(symbol).copied().unwrap_or(0);
+        Some(value)
+    }
+    
+    /// Scale or drop `opportunity` for legs trading suspect feeds; false to drop
+    pub fn screen_opportunity(&self, opportunity: &mut ArbitrageOpportunity) -> bool {
+        self.wash.screen(opportunity)
+    }
+    
+    /// Fake liquidity signs per feed, suspect feeds first
+    pub fn feed_integrity(&self) -> Vec<FeedIntegrity> {
+        self.wash.report()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn quote(exchange: &str, bid: f64, ask: f64, volume: f64, age_ms: u64) -> VenueQuote {
+        VenueQuote { exchange: exchange.to_string(), bid, ask, volume, age_ms }
+    }
+    
+    #[test]
+    fn test_fair_value_weights_depth_and_drops_outliers() {
+        let config = FairValueConfig::default();
+        let quotes = vec![
+            quote("binance", 99.9, 100.1, 3.0, 0),
+            quote("coinbase", 100.1, 100.3, 1.0, 0),
+            quote("kraken", 99.8, 100.0, 0.0, 0),
+            // Far off the others, and too old to count
+            quote("mexc", 109.0, 109.2, 50.0, 0),
+            quote("gemini", 100.0, 100.2, 50.0, 60_000),
+        ];
+        let value = fair_value("BTC/USDT", &quotes, &config).unwrap();
+        
+        assert_eq!(value.median_mid, 100.1);
+        let mexc = value.venues.iter().find(|venue| venue.exchange == "mexc").unwrap();
+        assert!(mexc.outlier && mexc.weight == 0.0);
+        assert!(value.venues.iter().find(|venue| venue.exchange == "gemini").unwrap().stale);
+        // Binance carries three quarters, coinbase a quarter, kraken next to nothing
+        assert!((value.fair_value - (100.0 * 0.75 + 100.2 * 0.25)).abs() < 1e-9);
+        
+        assert!(fair_value("BTC/USDT", &[quote("binance", 0.0, 100.0, 1.0, 0)], &config).is_none());
+    }
+    
+    #[test]
+    fn test_tick_far_from_other_venues_is_an_anomaly() {
+        let config = FairValueConfig::default();
+        let tick = |exchange: &str, bid: f64| MarketTick {
+            exchange: exchange.to_string(),
+            symbol: "BTC/USDT".to_string(),
+            bid,
+            ask: bid + 0.2,
+            last_price: bid + 0.1,
+            volume: 1.0,
+            timestamp: Instant::now(),
+            sequence: 0,
+        };
+        let quotes = vec![
+            quote("binance", 99.9, 100.1, 1.0, 0),
+            quote("coinbase", 100.0, 100.2, 1.0, 0),
+            quote("kraken", 99.9, 100.1, 1.0, 0),
+        ];
+        
+        // A decimal slip on kraken is dropped; a 5% move is left to the detector
+        assert!(anomaly(&tick("kraken", 10.0), &quotes, &config).unwrap() > 0.8);
+        assert!(anomaly(&tick("kraken", 105.0), &quotes, &config).is_none());
+        // Only one other venue to compare with
+        assert!(anomaly(&tick("kraken", 10.0), &quotes[..1], &config).is_none());
+        let off = FairValueConfig { max_tick_deviation: 0.0, ..config.clone() };
+        assert!(anomaly(&tick("kraken", 10.0), &quotes, &off).is_none());
+        
+        let book = QuoteBook::new();
+        for exchange in ["binance", "coinbase", "kraken"] {
+            book.update(&tick(exchange, 100.0));
+        }
+        let screen = TickScreen::new(config);
+        assert!(!screen.admit(&tick("kraken", 10.0), &book));
+        assert!(screen.admit(&tick("kraken", 100.1), &book));
+        let value = screen.fair_value("BTC/USDT", &book, Instant::now()).unwrap();
+        assert_eq!((value.rejected_ticks, value.venues.len()), (1, 3));
+    }
+}
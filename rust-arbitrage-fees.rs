@@ -0,0 +1,151 @@
+// arbitrage/fees.rs - Trading fees along a cycle, for net (after-fee) profit
+use std::collections::HashMap;
+
+use crate::markets::{self, AssetAlias, Fees};
+
+/// Quote currency of the pair `a` and `b` trade on, if `symbols` lists it
+fn quote_of<'a>(symbols: &'a [String], a: &str, b: &str) -> Option<&'a str> {
+    symbols.iter().find_map(|symbol| {
+        let (base, quote) = symbol.split_once('/')?;
+        ((base == a && quote == b) || (base == b && quote == a)).then_some(quote)
+    })
+}
+
+/// Fraction of notional left after paying `fees` on every trade along `path`
+/// ("BTC_binance -> ETH_binance -> USDT_binance"). Detected cycles cross the
+/// spread on each leg, so each trade pays its exchange's taker rate, scaled by
+/// the asset it's charged in: a fee added to what a buy spends costs 1/(1+f)
+/// rather than 1-f. Pairs not in `symbols` are taken as charging the asset
+/// received. Moving one asset between exchanges isn't a trade, nor is
+/// converting between tickers of one asset in `aliases` or from an asset to its
+/// derivative (the cost is on the edge). Unlisted exchanges are fee-free
+pub fn fee_multiplier(path: &str, fees: &HashMap<String, Fees>, symbols: &[String], aliases: &[AssetAlias]) -> f64 {
+    if fees.is_empty() {
+        return 1.0;
+    }
+    let nodes = path_nodes(path);
+    let mut multiplier = 1.0;
+    for (i, &node) in nodes.iter().enumerate() {
+        let next = nodes[(i + 1) % nodes.len()];
+        if !is_trade(node, next, aliases) {
+            continue;
+        }
+        if let Some(rates) = fees.get(node.1) {
+            multiplier *= trade_multiplier(rates, rates.taker, node, next.0, symbols);
+        }
+    }
+    multiplier
+}
+
+/// Share of `from` (an asset and its exchange) that arrives as `to` after a
+/// fee of `rate`, one of `fees`' rates, paid in the asset `fees` are charged in
+pub fn trade_multiplier(fees: &Fees, rate: f64, from: (&str, &str), to: &str, symbols: &[String]) -> f64 {
+    fees.asset(from.1).multiplier(rate, to, quote_of(symbols, from.0, to))
+}
+
+/// (asset, exchange) for each node of `path`, or of one node name ("BTC_binance")
+pub fn path_nodes(path: &str) -> Vec<(&str, &str)> {
+    let mut nodes: Vec<(&str, &str)> = path
+        .split(" -> ")
+        .map(|node| node.trim().rsplit_once('_').unwrap_or((node.trim(), "")))
+        .collect();
+    // Paths may repeat the start node to show the cycle closing
+    if nodes.len() > 1 && nodes.first() == nodes.last() {
+        nodes.pop();
+    }
+    nodes
+}
+
+/// Whether moving from one (asset, exchange) node to the next is a trade that
+/// pays fees, rather than a transfer, a conversion between aliased tickers
+/// or a move between an asset and its derivative
+pub fn is_trade(from: (&str, &str), to: (&str, &str), aliases: &[AssetAlias]) -> bool {
+    from.0 != to.0 && from.1 == to.1 && markets::link(aliases, from.0, to.0).is_none()
+}
+
+/// Profit after fees for a cycle that returns `gross` before fees
+pub fn net_profit(gross: f64, path: &str, fees: &HashMap<String, Fees>, symbols: &[String], aliases: &[AssetAlias]) -> f64 {
+    (1.0 + gross) * fee_multiplier(path, fees, symbols, aliases) - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markets::FeeAsset;
+    
+    fn schedule() -> HashMap<String, Fees> {
+        HashMap::from([
+            ("binance".to_string(), Fees { maker: 0.001, taker: 0.001, charged_in: None }),
+            ("kraken".to_string(), Fees { maker: 0.0016, taker: 0.0026, charged_in: None }),
+        ])
+    }
+    
+    #[test]
+    fn test_every_trade_pays_its_exchange_taker_fee() {
+        let triangle = "BTC_binance -> ETH_binance -> USDT_binance";
+        assert!((fee_multiplier(triangle, &schedule(), &[], &[]) - 0.999f64.powi(3)).abs() < 1e-12);
+        // A closing repeat of the start node is not another trade
+        let closed = "BTC_binance -> ETH_binance -> USDT_binance -> BTC_binance";
+        assert_eq!(fee_multiplier(closed, &schedule(), &[], &[]), fee_multiplier(triangle, &schedule(), &[], &[]));
+        
+        // Transfers between exchanges are free; coinbase isn't in the schedule
+        let cross = "BTC_binance -> USDT_binance -> USDT_kraken -> BTC_kraken -> BTC_coinbase -> USDT_coinbase";
+        assert!((fee_multiplier(cross, &schedule(), &[], &[]) - 0.999 * 0.9974).abs() < 1e-12);
+        assert_eq!(fee_multiplier(triangle, &HashMap::new(), &[], &[]), 1.0);
+        
+        // Converting RNDR to RENDER on one exchange is not a trade either
+        let renamed = "USDT_binance -> RNDR_binance -> RENDER_binance";
+        let aliases = AssetAlias::known();
+        assert!((fee_multiplier(renamed, &schedule(), &[], &aliases) - 0.999f64.powi(2)).abs() < 1e-12);
+        assert!((fee_multiplier(renamed, &schedule(), &[], &[]) - 0.999f64.powi(3)).abs() < 1e-12);
+    }
+    
+    #[test]
+    fn test_net_profit_pays_every_fee() {
+        let path = "BTC_binance -> ETH_binance -> USDT_binance";
+        let net = net_profit(0.002, path, &schedule(), &[], &[]);
+        assert!(net < 0.0, "0.2% gross doesn't cover three 0.1% fees: {}", net);
+        assert!((net - (1.002 * 0.999f64.powi(3) - 1.0)).abs() < 1e-12);
+        assert!(net_profit(0.005, path, &schedule(), &[], &[]) > 0.0);
+    }
+    
+    #[test]
+    fn test_fee_asset_follows_each_venues_convention() {
+        for venue in ["binance", "bitfinex", "cryptocom", "mexc"] {
+            assert_eq!(FeeAsset::convention(venue), FeeAsset::Received, "{}", venue);
+        }
+        for venue in ["coinbase", "kraken", "bitstamp", "gemini"] {
+            assert_eq!(FeeAsset::convention(venue), FeeAsset::Quote, "{}", venue);
+        }
+        
+        let symbols: Vec<String> = ["BTC/USDT", "ETH/BTC", "ETH/USDT"].iter().map(|s| s.to_string()).collect();
+        let on = |exchange: &str, path: &str| path.replace("{}", exchange);
+        let forward = "USDT_{} -> BTC_{} -> ETH_{} -> USDT_{}";
+        let backward = "USDT_{} -> ETH_{} -> BTC_{} -> USDT_{}";
+        let multiplier = |path: &str, fees: &HashMap<String, Fees>| fee_multiplier(path, fees, &symbols, &[]);
+        
+        // Binance takes the fee out of whatever each trade pays, either direction
+        assert!((multiplier(&on("binance", forward), &schedule()) - 0.999f64.powi(3)).abs() < 1e-12);
+        assert!((multiplier(&on("binance", backward), &schedule()) - 0.999f64.powi(3)).abs() < 1e-12);
+        
+        // Kraken charges USDT and BTC: on top of what the two buys spend, out of the sell
+        let kraken = 0.9974 / 1.0026f64.powi(2);
+        assert!((multiplier(&on("kraken", forward), &schedule()) - kraken).abs() < 1e-12);
+        // Backwards, ETH/BTC is a sell into BTC
+        let kraken = 0.9974f64.powi(2) / 1.0026;
+        assert!((multiplier(&on("kraken", backward), &schedule()) - kraken).abs() < 1e-12);
+        // A pair the config doesn't list is charged in what it pays out
+        assert!((fee_multiplier(&on("kraken", forward), &schedule(), &[], &[]) - 0.9974f64.powi(3)).abs() < 1e-12);
+        
+        // With the BNB discount the trades stay whole and the fee costs 25% less
+        let bnb: Fees = serde_yaml::from_str("{maker: 0.001, taker: 0.001, charged_in: {asset: token, discount: 0.25}}").unwrap();
+        assert_eq!(bnb.asset("binance"), FeeAsset::Token { discount: 0.25 });
+        let fees = HashMap::from([("binance".to_string(), bnb)]);
+        assert!((multiplier(&on("binance", forward), &fees) - 0.99925f64.powi(3)).abs() < 1e-12);
+        
+        // Set explicitly, the asset overrides the venue's convention
+        let received = Fees { charged_in: Some(FeeAsset::Received), ..schedule()["kraken"] };
+        let fees = HashMap::from([("kraken".to_string(), received)]);
+        assert!((multiplier(&on("kraken", forward), &fees) - 0.9974f64.powi(3)).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,187 @@
+// arbitrage/funding.rs - Delta-neutral spot vs perpetual funding rate opportunities
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+use serde::Serialize;
+
+use super::quotes::MarketSnapshot;
+use super::types::OpportunityKind;
+
+const YEAR: Duration = Duration::from_secs(365 * 24 * 3600);
+
+/// A perpetual's current funding rate, as a venue published it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FundingRate {
+    pub exchange: String,
+    /// Spot symbol the perpetual tracks, `BASE/QUOTE`
+    pub symbol: String,
+    /// Paid by longs to shorts each interval; negative when shorts pay
+    pub rate: f64,
+    #[serde(skip)]
+    pub interval: Duration,
+    pub next_funding_ms: Option<u64>,
+    pub mark_price: Option<f64>,
+}
+
+impl FundingRate {
+    /// The rate over a year of intervals, without compounding
+    pub fn annualized(&self) -> f64 {
+        if self.interval.is_zero() {
+            return 0.0;
+        }
+        self.rate * (YEAR.as_secs_f64() / self.interval.as_secs_f64())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FundingSide {
+    /// Positive funding: buy spot and short the perpetual to collect it
+    LongSpotShortPerp,
+    /// Negative funding: borrow and sell spot and go long the perpetual
+    ShortSpotLongPerp,
+}
+
+/// Funding collected by holding spot against an opposite perpetual position
+#[derive(Debug, Clone, Serialize)]
+pub struct FundingOpportunity {
+    pub kind: OpportunityKind,
+    pub symbol: String,
+    pub perp_exchange: String,
+    pub spot_exchange: String,
+    pub side: FundingSide,
+    pub funding_rate: f64,
+    pub interval_hours: f64,
+    /// What the position earns in a year at the current rate, before trading fees
+    pub annualized_yield: f64,
+    /// Best spot price for the side: the ask to buy, the bid to sell
+    pub spot_price: f64,
+    pub mark_price: Option<f64>,
+    /// Perpetual over spot, as a fraction of spot
+    pub basis: Option<f64>,
+    pub next_funding_ms: Option<u64>,
+}
+
+/// Latest funding rate per exchange and symbol
+pub struct FundingBook {
+    rates: RwLock<HashMap<(String, String), FundingRate>>,
+}
+
+impl FundingBook {
+    pub fn new() -> Self {
+        Self {
+            rates: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    pub fn record(&self, rates: Vec<FundingRate>) {
+        let mut current = self.rates.write().unwrap();
+        for rate in rates {
+            current.insert((rate.exchange.clone(), rate.symbol.clone()), rate);
+        }
+    }
+    
+    /// Perpetuals paying at least `min_annualized_yield` either way that can
+    /// be hedged on a spot venue in `snapshot`, best yield first
+    pub fn opportunities(
+        &self,
+        snapshot: impl Fn(&str) -> Option<MarketSnapshot>,
+        min_annualized_yield: f64,
+    ) -> Vec<FundingOpportunity> {
+        let rates = self.rates.read().unwrap();
+        let mut found: Vec<FundingOpportunity> = rates
+            .values()
+            .filter(|rate| rate.rate != 0.0 && rate.annualized().abs() >= min_annualized_yield)
+            .filter_map(|rate| {
+                let market = snapshot(&rate.symbol)?;
+                let side = if rate.rate > 0.0 { FundingSide::LongSpotShortPerp } else { FundingSide::ShortSpotLongPerp };
+                let spot = match side {
+                    FundingSide::LongSpotShortPerp => market
+                        .quotes
+                        .iter()
+                        .filter(|quote| quote.ask > 0.0)
+                        .min_by(|a, b| a.ask.total_cmp(&b.ask))
+                        .map(|quote| (quote.exchange.clone(), quote.ask)),
+                    FundingSide::ShortSpotLongPerp => market
+                        .quotes
+                        .iter()
+                        .filter(|quote| quote.bid > 0.0)
+                        .max_by(|a, b| a.bid.total_cmp(&b.bid))
+                        .map(|quote| (quote.exchange.clone(), quote.bid)),
+                };
+                let (spot_exchange, spot_price) = spot?;
+                Some(FundingOpportunity {
+                    kind: OpportunityKind::Funding,
+                    symbol: rate.symbol.clone(),
+                    perp_exchange: rate.exchange.clone(),
+                    spot_exchange,
+                    side,
+                    funding_rate: rate.rate,
+                    interval_hours: rate.interval.as_secs_f64() / 3600.0,
+                    annualized_yield: rate.annualized().abs(),
+                    spot_price,
+                    mark_price: rate.mark_price,
+                    basis: rate.mark_price.map(|mark| mark / spot_price - 1.0),
+                    next_funding_ms: rate.next_funding_ms,
+                })
+            })
+            .collect();
+        found.sort_by(|a, b| b.annualized_yield.total_cmp(&a.annualized_yield));
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::quotes::VenueQuote;
+    
+    fn rate(exchange: &str, symbol: &str, rate: f64) -> FundingRate {
+        FundingRate {
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            rate,
+            interval: Duration::from_secs(8 * 3600),
+            next_funding_ms: None,
+            mark_price: Some(101.0),
+        }
+    }
+    
+    fn market(symbol: &str) -> Option<MarketSnapshot> {
+        let quote = |exchange: &str, bid, ask| VenueQuote { exchange: exchange.to_string(), bid, ask, volume: 1.0, age_ms: 0 };
+        (symbol != "SOL/USDT").then(|| MarketSnapshot {
+            symbol: symbol.to_string(),
+            quotes: vec![quote("binance", 99.9, 100.0), quote("kraken", 99.8, 100.2)],
+            spreads: Vec::new(),
+        })
+    }
+    
+    #[test]
+    fn test_hedges_perpetual_funding_on_the_best_spot_venue() {
+        let book = FundingBook::new();
+        book.record(vec![
+            rate("bybit", "BTC/USDT", 0.0001),
+            rate("okx", "ETH/USDT", -0.0003),
+            rate("binance_futures", "DOGE/USDT", 0.00001),
+            rate("bybit", "SOL/USDT", 0.001),
+        ]);
+        // 0.01% every 8h is 10.95% a year
+        assert!((rate("bybit", "BTC/USDT", 0.0001).annualized() - 0.1095).abs() < 1e-9);
+        
+        let found = book.opportunities(market, 0.05);
+        assert_eq!(found.len(), 2, "DOGE pays too little and SOL has no spot quote");
+        assert_eq!((found[0].symbol.as_str(), found[0].side), ("ETH/USDT", FundingSide::ShortSpotLongPerp));
+        assert_eq!((found[0].spot_exchange.as_str(), found[0].spot_price), ("binance", 99.9));
+        assert!((found[0].annualized_yield - 0.3285).abs() < 1e-9);
+        assert_eq!((found[1].side, found[1].spot_price), (FundingSide::LongSpotShortPerp, 100.0));
+        assert!((found[1].basis.unwrap() - 0.01).abs() < 1e-12);
+        
+        let tagged = serde_json::to_value(&found[1]).unwrap();
+        assert_eq!(tagged["kind"], "funding");
+        assert_eq!(tagged["side"], "long_spot_short_perp");
+        
+        // A newer rate replaces the last one
+        book.record(vec![rate("bybit", "BTC/USDT", 0.0)]);
+        assert_eq!(book.opportunities(market, 0.05).len(), 1);
+    }
+}
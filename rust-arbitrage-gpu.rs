@@ -0,0 +1,205 @@
+// arbitrage/gpu.rs - wgpu backend for the all-sources relaxation step (feature "gpu")
+use std::error::Error;
+use std::sync::{mpsc, OnceLock};
+use tracing::{info, warn};
+use wgpu::util::DeviceExt;
+
+/// Stand-in for `f64::INFINITY`; WGSL storage buffers are f32 only
+const UNREACHABLE: f32 = 1.0e30;
+
+/// f32 accumulates rounding error, so tiny "cycles" below this are noise
+const NEGATIVE_EPSILON: f32 = 1.0e-6;
+
+const WORKGROUP_SIZE: u32 = 16;
+
+/// One min-plus relaxation round for every (source, target) pair at once
+const RELAX_SHADER: &str = r#"
+struct Params {
+    n: u32,
+};
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> weights: array<f32>;
+@group(0) @binding(2) var<storage, read> dist_in: array<f32>;
+@group(0) @binding(3) var<storage, read_write> dist_out: array<f32>;
+
+@compute @workgroup_size(16, 16)
+fn relax(@builtin(global_invocation_id) id: vec3<u32>) {
+    let n = params.n;
+    let source = id.y;
+    let target = id.x;
+    if (source >= n || target >= n) {
+        return;
+    }
+    
+    var best = dist_in[source * n + target];
+    for (var via: u32 = 0u; via < n; via = via + 1u) {
+        best = min(best, dist_in[source * n + via] + weights[via * n + target]);
+    }
+    dist_out[source * n + target] = best;
+}
+"#;
+
+pub struct GpuRelaxer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    layout: wgpu::BindGroupLayout,
+}
+
+impl GpuRelaxer {
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        pollster::block_on(Self::init())
+    }
+    
+    async fn init() -> Result<Self, Box<dyn Error>> {
+        let instance = wgpu::Instance::default();
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                ..Default::default()
+            })
+            .await
+            .ok_or("no GPU adapter available")?;
+        
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor::default(), None)
+            .await?;
+        
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("relax"),
+            source: wgpu::ShaderSource::Wgsl(RELAX_SHADER.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("relax"),
+            layout: None,
+            module: &module,
+            entry_point: "relax",
+        });
+        let layout = pipeline.get_bind_group_layout(0);
+        
+        info!("GPU relaxation backend on {}", adapter.get_info().name);
+        
+        Ok(Self {
+            device,
+            queue,
+            pipeline,
+            layout,
+        })
+    }
+    
+    /// Sources that lie on a negative cycle, found by running `n` min-plus
+    /// rounds from every source in parallel and checking `dist[s][s] < 0`.
+    ///
+    /// Only flags candidates; the CPU Bellman-Ford still extracts the cycle.
+    pub fn negative_cycle_sources(&self, graph: &[Vec<f64>], n: usize) -> Result<Vec<usize>, Box<dyn Error>> {
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+        
+        let mut weights = vec![UNREACHABLE; n * n];
+        for u in 0..n {
+            for v in 0..n {
+                let w = graph[u][v];
+                if w.is_finite() {
+                    weights[u * n + v] = w as f32;
+                }
+            }
+        }
+        let mut dist = weights.clone();
+        for s in 0..n {
+            dist[s * n + s] = dist[s * n + s].min(0.0);
+        }
+        
+        let size = (n * n * std::mem::size_of::<f32>()) as wgpu::BufferAddress;
+        let params = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("relax params"),
+            contents: bytemuck::cast_slice(&[n as u32, 0, 0, 0]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let weights_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("relax weights"),
+            contents: bytemuck::cast_slice(&weights),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+        let ping = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("relax ping"),
+            contents: bytemuck::cast_slice(&dist),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        });
+        let pong = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("relax pong"),
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("relax staging"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        
+        let bind = |input: &wgpu::Buffer, output: &wgpu::Buffer| {
+            self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("relax bindings"),
+                layout: &self.layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: params.as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 1, resource: weights_buffer.as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 2, resource: input.as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 3, resource: output.as_entire_binding() },
+                ],
+            })
+        };
+        let groups = [bind(&ping, &pong), bind(&pong, &ping)];
+        
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("relax") });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&self.pipeline);
+            let workgroups = (n as u32).div_ceil(WORKGROUP_SIZE);
+            // Every dispatch is its own usage scope, so rounds are ordered
+            for round in 0..n {
+                pass.set_bind_group(0, &groups[round % 2], &[]);
+                pass.dispatch_workgroups(workgroups, workgroups, 1);
+            }
+        }
+        let result = if n % 2 == 0 { &ping } else { &pong };
+        encoder.copy_buffer_to_buffer(result, 0, &staging, 0, size);
+        self.queue.submit(Some(encoder.finish()));
+        
+        let slice = staging.slice(..);
+        let (tx, rx) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |mapped| {
+            let _ = tx.send(mapped);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        rx.recv()??;
+        
+        let sources = {
+            let data = slice.get_mapped_range();
+            let dist: &[f32] = bytemuck::cast_slice(&data);
+            (0..n).filter(|&s| dist[s * n + s] < -NEGATIVE_EPSILON).collect()
+        };
+        staging.unmap();
+        
+        Ok(sources)
+    }
+}
+
+/// Process-wide relaxer, initialized on first use; `None` when no adapter
+/// is available so callers fall back to the CPU path
+pub fn shared() -> Option<&'static GpuRelaxer> {
+    static RELAXER: OnceLock<Option<GpuRelaxer>> = OnceLock::new();
+    
+    RELAXER
+        .get_or_init(|| match GpuRelaxer::new() {
+            Ok(relaxer) => Some(relaxer),
+            Err(e) => {
+                warn!("GPU detection unavailable, using CPU: {}", e);
+                None
+            }
+        })
+        .as_ref()
+}
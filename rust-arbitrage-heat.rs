@@ -0,0 +1,228 @@
+// arbitrage/heat.rs - Rolling statistics of where profitable opportunities occur
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::Serialize;
+
+use crate::execution::analytics::exchange_pair;
+use super::types::ArbitrageOpportunity;
+
+const MAX_EVENTS: usize = 100_000; // Bounds memory if the detector runs hot for a whole window
+
+struct HeatEvent {
+    at_ms: u64,
+    exchange_pair: String,
+    symbols: Vec<String>,
+    sample: HeatSample,
+}
+
+/// One opportunity within the window, as the allocation optimizer sees it
+#[derive(Debug, Clone)]
+pub struct HeatSample {
+    /// (asset, exchange) for every node of the cycle
+    pub nodes: Vec<(String, String)>,
+    pub profit: f64,
+    pub max_volume: f64,
+}
+
+/// Opportunity count and profit for one exchange pair, symbol or hour
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HeatBucket {
+    pub key: String,
+    pub opportunities: u64,
+    pub total_profit: f64,
+    pub avg_profit: f64,
+    pub max_profit: f64,
+}
+
+impl HeatBucket {
+    fn add(&mut self, profit: f64) {
+        self.opportunities += 1;
+        self.total_profit += profit;
+        self.max_profit = self.max_profit.max(profit);
+        self.avg_profit = self.total_profit / self.opportunities as f64;
+    }
+}
+
+/// Hottest first; profits are fractions as in `ArbitrageOpportunity`
+#[derive(Debug, Clone, Serialize)]
+pub struct HeatReport {
+    pub window_secs: u64,
+    pub opportunities: u64,
+    pub exchange_pairs: Vec<HeatBucket>,
+    pub symbols: Vec<HeatBucket>,
+    /// UTC hour of day, "00" to "23"
+    pub hours: Vec<HeatBucket>,
+}
+
+/// Opportunities seen over the last `window`, for deciding where to hold inventory
+pub struct HeatMap {
+    window: Duration,
+    /// Configured symbols, so legs are reported as the market that trades them
+    symbols: Vec<String>,
+    events: Mutex<VecDeque<HeatEvent>>,
+}
+
+impl HeatMap {
+    pub fn new(window: Duration, symbols: &[String]) -> Self {
+        Self {
+            window,
+            symbols: symbols.to_vec(),
+            events: Mutex::new(VecDeque::new()),
+        }
+    }
+    
+    pub fn record(&self, opportunity: &ArbitrageOpportunity) {
+        self.record_at(opportunity, unix_ms());
+    }
+    
+    fn record_at(&self, opportunity: &ArbitrageOpportunity, at_ms: u64) {
+        let nodes = cycle_nodes(&opportunity.path);
+        let event = HeatEvent {
+            at_ms,
+            exchange_pair: exchange_pair(&opportunity.exchanges),
+            symbols: self.traded_symbols(&nodes),
+            sample: HeatSample {
+                nodes: nodes.into_iter().map(|(asset, exchange)| (asset.to_string(), exchange.to_string())).collect(),
+                profit: opportunity.profit_percentage,
+                max_volume: opportunity.max_volume,
+            },
+        };
+        let mut events = self.events.lock().unwrap();
+        Self::prune(&mut events, at_ms, self.window);
+        if events.len() == MAX_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+    
+    /// Markets traded by a cycle's same-exchange legs; cross-exchange legs are transfers
+    fn traded_symbols(&self, nodes: &[(&str, &str)]) -> Vec<String> {
+        let mut symbols: Vec<String> = (0..nodes.len())
+            .filter_map(|i| {
+                let (from, from_exchange) = nodes[i];
+                let (to, to_exchange) = nodes[(i + 1) % nodes.len()];
+                (from_exchange == to_exchange && from != to).then(|| self.market_for(from, to))
+            })
+            .collect();
+        symbols.sort();
+        symbols.dedup();
+        symbols
+    }
+    
+    fn market_for(&self, a: &str, b: &str) -> String {
+        let forward = format!("{}/{}", a, b);
+        let reverse = format!("{}/{}", b, a);
+        if self.symbols.contains(&reverse) {
+            reverse
+        } else if self.symbols.contains(&forward) || a < b {
+            forward
+        } else {
+            reverse
+        }
+    }
+    
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+    
+    /// Opportunities still inside the window, oldest first
+    pub fn samples(&self) -> Vec<HeatSample> {
+        let mut events = self.events.lock().unwrap();
+        Self::prune(&mut events, unix_ms(), self.window);
+        events.iter().map(|event| event.sample.clone()).collect()
+    }
+    
+    pub fn report(&self) -> HeatReport {
+        self.report_at(unix_ms())
+    }
+    
+    fn report_at(&self, now_ms: u64) -> HeatReport {
+        let mut events = self.events.lock().unwrap();
+        Self::prune(&mut events, now_ms, self.window);
+        
+        let mut pairs: HashMap<String, HeatBucket> = HashMap::new();
+        let mut symbols: HashMap<String, HeatBucket> = HashMap::new();
+        let mut hours: Vec<HeatBucket> = (0..24).map(|h| HeatBucket { key: format!("{:02}", h), ..HeatBucket::default() }).collect();
+        
+        for event in events.iter() {
+            bucket(&mut pairs, &event.exchange_pair).add(event.sample.profit);
+            for symbol in &event.symbols {
+                bucket(&mut symbols, symbol).add(event.sample.profit);
+            }
+            hours[(event.at_ms / 3_600_000 % 24) as usize].add(event.sample.profit);
+        }
+        
+        HeatReport {
+            window_secs: self.window.as_secs(),
+            opportunities: events.len() as u64,
+            exchange_pairs: hottest(pairs),
+            symbols: hottest(symbols),
+            hours,
+        }
+    }
+    
+    fn prune(events: &mut VecDeque<HeatEvent>, now_ms: u64, window: Duration) {
+        let cutoff = now_ms.saturating_sub(window.as_millis() as u64);
+        while events.front().is_some_and(|event| event.at_ms < cutoff) {
+            events.pop_front();
+        }
+    }
+}
+
+/// "BTC_binance -> USDT_binance" as [("BTC", "binance"), ("USDT", "binance")]
+fn cycle_nodes(path: &str) -> Vec<(&str, &str)> {
+    path.split(" -> ").filter_map(|node| node.split_once('_')).collect()
+}
+
+fn bucket<'a>(buckets: &'a mut HashMap<String, HeatBucket>, key: &str) -> &'a mut HeatBucket {
+    buckets.entry(key.to_string()).or_insert_with(|| HeatBucket { key: key.to_string(), ..HeatBucket::default() })
+}
+
+/// By total profit, then count, then key for a stable order
+fn hottest(buckets: HashMap<String, HeatBucket>) -> Vec<HeatBucket> {
+    let mut buckets: Vec<HeatBucket> = buckets.into_values().collect();
+    buckets.sort_by(|a, b| {
+        b.total_profit
+            .total_cmp(&a.total_profit)
+            .then(b.opportunities.cmp(&a.opportunities))
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    buckets
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_aggregates_pairs_symbols_and_hours_over_window() {
+        let symbols = vec!["BTC/USDT".to_string(), "ETH/USDT".to_string(), "ETH/BTC".to_string()];
+        let heat = HeatMap::new(Duration::from_secs(3600), &symbols);
+        let hour = 3_600_000;
+        
+        heat.record_at(&ArbitrageOpportunity::sample("USDT_binance -> BTC_binance -> ETH_binance", 0.002), 10 * hour);
+        heat.record_at(&ArbitrageOpportunity::sample("BTC_binance -> BTC_kraken -> USDT_kraken -> USDT_binance", 0.004), 10 * hour + 5);
+        heat.record_at(&ArbitrageOpportunity::sample("BTC_kraken -> USDT_kraken -> USDT_binance -> BTC_binance", 0.006), 11 * hour);
+        
+        let report = heat.report_at(11 * hour);
+        assert_eq!(report.opportunities, 3);
+        assert_eq!(report.exchange_pairs[0].key, "binance/kraken");
+        assert_eq!(report.exchange_pairs[0].opportunities, 2);
+        assert!((report.exchange_pairs[0].avg_profit - 0.005).abs() < 1e-12);
+        
+        let symbols: Vec<(&str, u64)> = report.symbols.iter().map(|b| (b.key.as_str(), b.opportunities)).collect();
+        assert_eq!(symbols, vec![("BTC/USDT", 3), ("ETH/BTC", 1), ("ETH/USDT", 1)]);
+        assert_eq!((report.hours[10].opportunities, report.hours[11].opportunities), (2, 1));
+        
+        // The first two age out of the one-hour window
+        let report = heat.report_at(11 * hour + 6);
+        assert_eq!(report.opportunities, 1);
+        assert_eq!(report.exchange_pairs.len(), 1);
+        assert_eq!(report.hours[10].opportunities, 0);
+    }
+}
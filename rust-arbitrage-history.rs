@@ -0,0 +1,110 @@
+// arbitrage/history.rs - Fixed-capacity ring buffer of recent opportunities
+use std::sync::RwLock;
+
+use super::types::ArbitrageOpportunity;
+
+/// Pre-allocated ring buffer holding the most recent opportunities.
+///
+/// A push overwrites a single slot under a short write lock; there is no
+/// reallocation or `pop_front` shuffling, so dashboard reads never contend
+/// with the detector for longer than one slot write.
+pub struct OpportunityHistory {
+    ring: RwLock<Ring>,
+}
+
+struct Ring {
+    slots: Vec<Option<ArbitrageOpportunity>>,
+    head: usize, // Next slot to write
+    len: usize,
+}
+
+impl OpportunityHistory {
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let mut slots = Vec::with_capacity(capacity);
+        slots.resize_with(capacity, || None);
+        
+        Self {
+            ring: RwLock::new(Ring { slots, head: 0, len: 0 }),
+        }
+    }
+    
+    pub fn capacity(&self) -> usize {
+        self.ring.read().unwrap().slots.len()
+    }
+    
+    pub fn len(&self) -> usize {
+        self.ring.read().unwrap().len
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    
+    /// Append an opportunity, overwriting the oldest one when full
+    pub fn push(&self, opportunity: ArbitrageOpportunity) {
+        let mut ring = self.ring.write().unwrap();
+        let capacity = ring.slots.len();
+        let head = ring.head;
+        
+        ring.slots[head] = Some(opportunity);
+        ring.head = (head + 1) % capacity;
+        ring.len = (ring.len + 1).min(capacity);
+    }
+    
+    /// Up to `limit` most recent opportunities, oldest first
+    pub fn recent(&self, limit: usize) -> Vec<ArbitrageOpportunity> {
+        let ring = self.ring.read().unwrap();
+        let capacity = ring.slots.len();
+        let count = limit.min(ring.len);
+        let start = (ring.head + capacity - count) % capacity;
+        
+        (0..count)
+            .filter_map(|i| ring.slots[(start + i) % capacity].clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const PATH: &str = "BTC_binance -> USDT_binance -> ETH_binance";
+    
+    #[test]
+    fn test_recent_returns_oldest_first() {
+        let history = OpportunityHistory::with_capacity(4);
+        for i in 0..3 {
+            history.push(ArbitrageOpportunity::sample(PATH, i as f64));
+        }
+        
+        let profits: Vec<f64> = history.recent(10).iter().map(|o| o.profit_percentage).collect();
+        assert_eq!(profits, vec![0.0, 1.0, 2.0]);
+        
+        let profits: Vec<f64> = history.recent(2).iter().map(|o| o.profit_percentage).collect();
+        assert_eq!(profits, vec![1.0, 2.0]);
+    }
+    
+    #[test]
+    fn test_wraps_and_overwrites_oldest() {
+        let history = OpportunityHistory::with_capacity(3);
+        for i in 0..7 {
+            history.push(ArbitrageOpportunity::sample(PATH, i as f64));
+        }
+        
+        assert_eq!(history.len(), 3);
+        let profits: Vec<f64> = history.recent(3).iter().map(|o| o.profit_percentage).collect();
+        assert_eq!(profits, vec![4.0, 5.0, 6.0]);
+    }
+    
+    #[test]
+    fn test_zero_capacity_is_clamped() {
+        let history = OpportunityHistory::with_capacity(0);
+        assert_eq!(history.capacity(), 1);
+        assert!(history.is_empty());
+        
+        history.push(ArbitrageOpportunity::sample(PATH, 0.5));
+        history.push(ArbitrageOpportunity::sample(PATH, 0.7));
+        assert_eq!(history.recent(5)[0].profit_percentage, 0.7);
+    }
+}
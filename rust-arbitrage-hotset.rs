@@ -0,0 +1,106 @@
+// arbitrage/hotset.rs - Recently profitable currencies for prioritized detection
+use std::collections::{BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+
+use super::types::ArbitrageOpportunity;
+
+/// Graph nodes (currency_exchange) that recently appeared in an opportunity.
+///
+/// The detector scans the subgraph around these nodes on a fast cadence and
+/// only sweeps the full graph on the slower cold cadence.
+pub struct HotSet {
+    ttl: Duration,
+    nodes: HashMap<String, Instant>, // node -> last seen in an opportunity
+}
+
+impl HotSet {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            nodes: HashMap::new(),
+        }
+    }
+    
+    pub fn mark(&mut self, opportunity: &ArbitrageOpportunity, now: Instant) {
+        for node in opportunity.path.split(" -> ") {
+            self.nodes.insert(node.trim().to_string(), now);
+        }
+    }
+    
+    pub fn prune(&mut self, now: Instant) {
+        let ttl = self.ttl;
+        self.nodes.retain(|_, seen| now.saturating_duration_since(*seen) <= ttl);
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+    
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+    
+    /// Graph indices of hot nodes plus their direct neighbours, so cycles can
+    /// still route through one adjacent currency
+    pub fn node_indices(&self, currencies: &HashMap<String, usize>, graph: &[Vec<f64>], n: usize) -> Vec<usize> {
+        let mut selected = BTreeSet::new();
+        
+        for node in self.nodes.keys() {
+            let Some(&u) = currencies.get(node) else {
+                continue;
+            };
+            if u >= n {
+                continue;
+            }
+            selected.insert(u);
+            for v in 0..n {
+                if graph[u][v] != f64::INFINITY || graph[v][u] != f64::INFINITY {
+                    selected.insert(v);
+                }
+            }
+        }
+        
+        selected.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_nodes_expire_after_ttl() {
+        let mut hot = HotSet::new(Duration::from_secs(30));
+        let start = Instant::now();
+        hot.mark(&ArbitrageOpportunity::sample("A_x -> B_x -> C_x", 0.01), start);
+        assert_eq!(hot.len(), 3);
+        
+        hot.prune(start + Duration::from_secs(10));
+        assert_eq!(hot.len(), 3);
+        hot.prune(start + Duration::from_secs(31));
+        assert!(hot.is_empty());
+    }
+    
+    #[test]
+    fn test_node_indices_include_neighbours_only() {
+        let inf = f64::INFINITY;
+        // 0 <-> 1 connected, 2 isolated, 3 <-> 1 connected
+        let graph = vec![
+            vec![0.0, 0.1, inf, inf],
+            vec![0.1, 0.0, inf, 0.1],
+            vec![inf, inf, 0.0, inf],
+            vec![inf, 0.1, inf, 0.0],
+        ];
+        let currencies: HashMap<String, usize> = ["A_x", "B_x", "C_x", "D_x"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.to_string(), i))
+            .collect();
+        
+        let mut hot = HotSet::new(Duration::from_secs(30));
+        hot.mark(&ArbitrageOpportunity::sample("A_x", 0.01), Instant::now());
+        
+        // Diagonal zeros make every node its own neighbour; 2 stays out
+        assert_eq!(hot.node_indices(&currencies, &graph, 4), vec![0, 1]);
+    }
+}
@@ -0,0 +1,248 @@
+// arbitrage/johnson.rs - Johnson-style negative cycle search for sparse graphs
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+
+/// Reweighted edges can dip just below zero from rounding
+const REWEIGHT_TOLERANCE: f64 = 1e-12;
+
+/// Cycles must beat this log-return to count, matching f64 noise on long paths
+const CYCLE_EPSILON: f64 = 1e-12;
+
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    from: usize,
+    to: usize,
+    weight: f64,
+}
+
+#[derive(PartialEq)]
+struct Candidate {
+    cost: f64,
+    node: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    // Reversed so BinaryHeap pops the cheapest node first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost).then_with(|| self.node.cmp(&other.node))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Every negative cycle (in trade order, at least 3 nodes) found by one
+/// super-source Bellman-Ford plus reweighted Dijkstra.
+///
+/// Each Bellman-Ford pass either yields Johnson potentials or a cycle; found
+/// cycles are cut from the graph until potentials exist. Each cut edge is
+/// then closed with Dijkstra over the reweighted residual graph, recovering
+/// the best cycle through it. Cost is O(VE) per cut cycle plus O(E log V)
+/// per cut edge, which beats V full Bellman-Ford runs when E is small.
+pub fn negative_cycles(graph: &[Vec<f64>], n: usize) -> Vec<Vec<usize>> {
+    let mut edges: Vec<Edge> = Vec::new();
+    for (u, row) in graph.iter().enumerate().take(n) {
+        for (v, &weight) in row.iter().enumerate().take(n) {
+            if u != v && weight.is_finite() {
+                edges.push(Edge { from: u, to: v, weight });
+            }
+        }
+    }
+    
+    let mut cycles = Vec::new();
+    let mut seen = HashSet::new();
+    let mut cut: Vec<Edge> = Vec::new();
+    
+    let potentials = loop {
+        match potentials(&edges, n) {
+            Ok(h) => break h,
+            Err(cycle) => {
+                let on_cycle: HashSet<(usize, usize)> = (0..cycle.len())
+                    .map(|i| (cycle[i], cycle[(i + 1) % cycle.len()]))
+                    .collect();
+                edges.retain(|edge| {
+                    let keep = !on_cycle.contains(&(edge.from, edge.to));
+                    if !keep {
+                        cut.push(*edge);
+                    }
+                    keep
+                });
+                record_cycle(cycle, &mut cycles, &mut seen);
+            }
+        }
+    };
+    
+    let mut adjacency: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+    for edge in &edges {
+        let reweighted = edge.weight + potentials[edge.from] - potentials[edge.to];
+        adjacency[edge.from].push((edge.to, reweighted.max(0.0)));
+    }
+    
+    for edge in &cut {
+        let (dist, parent) = dijkstra(&adjacency, edge.to, n);
+        if !dist[edge.from].is_finite() {
+            continue;
+        }
+        
+        // Undo the reweighting to get the original path cost
+        let path_cost = dist[edge.from] - potentials[edge.to] + potentials[edge.from];
+        if edge.weight + path_cost >= -CYCLE_EPSILON {
+            continue;
+        }
+        
+        let mut path = vec![edge.from];
+        let mut node = edge.from;
+        while node != edge.to {
+            node = match parent[node] {
+                Some(p) => p,
+                None => break,
+            };
+            path.push(node);
+        }
+        path.reverse(); // edge.to ... edge.from, closed by the cut edge
+        record_cycle(path, &mut cycles, &mut seen);
+    }
+    
+    cycles
+}
+
+/// Bellman-Ford from a virtual source with zero-weight edges to every node.
+/// Returns the potentials, or a negative cycle in trade order.
+fn potentials(edges: &[Edge], n: usize) -> Result<Vec<f64>, Vec<usize>> {
+    let mut dist = vec![0.0; n];
+    let mut parent: Vec<Option<usize>> = vec![None; n];
+    
+    // n + 1 nodes including the virtual source, so n rounds must converge
+    for round in 0..=n {
+        let mut last_updated = None;
+        for edge in edges {
+            let candidate = dist[edge.from] + edge.weight;
+            if candidate < dist[edge.to] {
+                dist[edge.to] = candidate;
+                parent[edge.to] = Some(edge.from);
+                last_updated = Some(edge.to);
+            }
+        }
+        
+        let Some(mut node) = last_updated else {
+            return Ok(dist);
+        };
+        if round < n {
+            continue;
+        }
+        
+        // Walk back n steps to be sure we're on the cycle, then collect it
+        for _ in 0..n {
+            node = parent[node].expect("relaxed node has a parent");
+        }
+        let mut cycle = vec![node];
+        let mut current = parent[node].expect("cycle node has a parent");
+        while current != node {
+            cycle.push(current);
+            current = parent[current].expect("cycle node has a parent");
+        }
+        cycle.reverse();
+        return Err(cycle);
+    }
+    
+    Ok(dist)
+}
+
+fn dijkstra(adjacency: &[Vec<(usize, f64)>], source: usize, n: usize) -> (Vec<f64>, Vec<Option<usize>>) {
+    let mut dist = vec![f64::INFINITY; n];
+    let mut parent = vec![None; n];
+    let mut heap = BinaryHeap::new();
+    
+    dist[source] = 0.0;
+    heap.push(Candidate { cost: 0.0, node: source });
+    
+    while let Some(Candidate { cost, node }) = heap.pop() {
+        if cost > dist[node] + REWEIGHT_TOLERANCE {
+            continue; // Stale entry
+        }
+        for &(next, weight) in &adjacency[node] {
+            let candidate = cost + weight;
+            if candidate < dist[next] {
+                dist[next] = candidate;
+                parent[next] = Some(node);
+                heap.push(Candidate { cost: candidate, node: next });
+            }
+        }
+    }
+    
+    (dist, parent)
+}
+
+/// Keep cycles of 3+ nodes, deduplicated by rotation
+fn record_cycle(cycle: Vec<usize>, cycles: &mut Vec<Vec<usize>>, seen: &mut HashSet<Vec<usize>>) {
+    if cycle.len() < 3 {
+        return;
+    }
+    
+    let start = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap_or(0);
+    let mut canonical = cycle[start..].to_vec();
+    canonical.extend_from_slice(&cycle[..start]);
+    
+    if seen.insert(canonical) {
+        cycles.push(cycle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const INF: f64 = f64::INFINITY;
+    
+    #[test]
+    fn test_finds_triangle_in_trade_order() {
+        let graph = vec![
+            vec![0.0, -f64::ln(0.85), INF],
+            vec![INF, 0.0, -f64::ln(0.90)],
+            vec![-f64::ln(1.35), INF, 0.0],
+        ];
+        
+        let cycles = negative_cycles(&graph, 3);
+        assert_eq!(cycles.len(), 1);
+        
+        let cycle = &cycles[0];
+        let start = cycle.iter().position(|&x| x == 0).unwrap();
+        assert_eq!(cycle[(start + 1) % 3], 1);
+        assert_eq!(cycle[(start + 2) % 3], 2);
+    }
+    
+    #[test]
+    fn test_fair_graph_has_no_cycles() {
+        // Round trips lose a 0.1% fee everywhere
+        let fee = -f64::ln(0.999);
+        let graph = vec![
+            vec![0.0, fee, fee],
+            vec![fee, 0.0, fee],
+            vec![fee, fee, 0.0],
+        ];
+        
+        assert!(negative_cycles(&graph, 3).is_empty());
+    }
+    
+    #[test]
+    fn test_two_node_loops_are_cut_but_not_reported() {
+        // 0 <-> 1 is profitable but too short; 1 -> 2 -> 3 -> 1 is a real triangle
+        let graph = vec![
+            vec![0.0, -0.1, INF, INF],
+            vec![-0.1, 0.0, -0.01, INF],
+            vec![INF, INF, 0.0, -0.01],
+            vec![INF, -0.01, INF, 0.0],
+        ];
+        
+        let cycles = negative_cycles(&graph, 4);
+        assert_eq!(cycles.len(), 1);
+        let mut nodes = cycles[0].clone();
+        nodes.sort();
+        assert_eq!(nodes, vec![1, 2, 3]);
+    }
+}
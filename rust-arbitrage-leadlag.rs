@@ -0,0 +1,255 @@
+// arbitrage/leadlag.rs - Lead-lag between venues quoting the same symbol
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+use super::types::MarketTick;
+
+/// Lags kept per venue pair for the median and tail
+const LAG_SAMPLES: usize = 256;
+/// Bounds the moves waiting for followers if a symbol turns very busy
+const MAX_PENDING_MOVES: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeadLagConfig {
+    /// How far, in basis points, a venue's mid has to go from where it last
+    /// moved before the change counts as a move
+    pub move_bps: f64,
+    /// A venue moving the same way later than this isn't following
+    #[serde(with = "humantime_serde")]
+    pub max_lag: Duration,
+    /// Median lags shorter than this are inside network jitter and can't be
+    /// traded on
+    #[serde(with = "humantime_serde")]
+    pub min_lag: Duration,
+    /// Follows to see before a pair can be called viable
+    pub min_samples: u64,
+    /// Share of the leader's moves the follower has to repeat
+    pub min_follow_rate: f64,
+}
+
+impl Default for LeadLagConfig {
+    fn default() -> Self {
+        Self {
+            move_bps: 5.0,
+            max_lag: Duration::from_secs(2),
+            min_lag: Duration::from_millis(20),
+            min_samples: 30,
+            min_follow_rate: 0.6,
+        }
+    }
+}
+
+/// How one venue trails another on a symbol
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LeadLag {
+    pub symbol: String,
+    pub leader: String,
+    pub follower: String,
+    pub leader_moves: u64,
+    pub followed: u64,
+    pub follow_rate: f64,
+    pub median_lag_ms: f64,
+    pub p90_lag_ms: f64,
+    /// Followed often enough, late enough, and more than it leads the other way
+    pub viable: bool,
+}
+
+struct Move {
+    exchange: String,
+    at: Instant,
+    up: bool,
+    followers: Vec<String>,
+}
+
+#[derive(Default)]
+struct PairStats {
+    leader_moves: u64,
+    followed: u64,
+    lags_ms: VecDeque<f64>,
+}
+
+#[derive(Default)]
+struct SymbolState {
+    /// Mid each venue last moved to
+    anchors: HashMap<String, f64>,
+    moves: VecDeque<Move>,
+    /// Keyed by (leader, follower)
+    pairs: HashMap<(String, String), PairStats>,
+}
+
+/// Measures, from tick receive times, how long each venue takes to repeat
+/// another venue's moves
+pub struct LeadLagDetector {
+    config: RwLock<LeadLagConfig>,
+    symbols: Mutex<HashMap<String, SymbolState>>,
+}
+
+impl LeadLagDetector {
+    pub fn new(config: LeadLagConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            symbols: Mutex::new(HashMap::new()),
+        }
+    }
+    
+    pub fn set_config(&self, config: LeadLagConfig) {
+        *self.config.write().unwrap() = config;
+    }
+    
+    pub fn observe(&self, tick: &MarketTick) {
+        let mid = (tick.bid + tick.ask) / 2.0;
+        if !(mid.is_finite() && mid > 0.0) {
+            return;
+        }
+        let config = self.config.read().unwrap();
+        let mut symbols = self.symbols.lock().unwrap();
+        let state = symbols.entry(tick.symbol.clone()).or_default();
+        let Some(anchor) = state.anchors.insert(tick.exchange.clone(), mid) else {
+            return;
+        };
+        let change_bps = (mid / anchor - 1.0) * 10_000.0;
+        if change_bps.abs() < config.move_bps {
+            // Not a move yet; keep measuring from where the venue last moved
+            state.anchors.insert(tick.exchange.clone(), anchor);
+            return;
+        }
+        let up = change_bps > 0.0;
+        let at = tick.timestamp;
+        while state.moves.front().is_some_and(|pending| at.saturating_duration_since(pending.at) > config.max_lag) {
+            state.moves.pop_front();
+        }
+        
+        // Each other venue's latest same-way move is the one being followed;
+        // its earlier unanswered ones are covered by the same follow
+        let mut followed: Vec<(String, f64)> = Vec::new();
+        for pending in state.moves.iter_mut().rev() {
+            if pending.exchange == tick.exchange || pending.up != up || pending.followers.contains(&tick.exchange) {
+                continue;
+            }
+            pending.followers.push(tick.exchange.clone());
+            if !followed.iter().any(|(leader, _)| *leader == pending.exchange) {
+                let lag_ms = at.saturating_duration_since(pending.at).as_micros() as f64 / 1000.0;
+                followed.push((pending.exchange.clone(), lag_ms));
+            }
+        }
+        for (leader, lag_ms) in followed {
+            let pair = state.pairs.entry((leader, tick.exchange.clone())).or_default();
+            pair.followed += 1;
+            if pair.lags_ms.len() == LAG_SAMPLES {
+                pair.lags_ms.pop_front();
+            }
+            pair.lags_ms.push_back(lag_ms);
+        }
+        
+        // Every other venue quoting the symbol now has a move to follow
+        for other in state.anchors.keys().filter(|other| **other != tick.exchange) {
+            state.pairs.entry((tick.exchange.clone(), other.clone())).or_default().leader_moves += 1;
+        }
+        if state.moves.len() == MAX_PENDING_MOVES {
+            state.moves.pop_front();
+        }
+        state.moves.push_back(Move {
+            exchange: tick.exchange.clone(),
+            at,
+            up,
+            followers: Vec::new(),
+        });
+    }
+    
+    /// Every measured pair, viable ones first and longest lag first within that
+    pub fn report(&self) -> Vec<LeadLag> {
+        let config = self.config.read().unwrap();
+        let symbols = self.symbols.lock().unwrap();
+        let min_lag_ms = config.min_lag.as_secs_f64() * 1000.0;
+        let follow_rate = |pair: &PairStats| {
+            if pair.leader_moves == 0 {
+                0.0
+            } else {
+                (pair.followed as f64 / pair.leader_moves as f64).min(1.0)
+            }
+        };
+        
+        let mut report: Vec<LeadLag> = Vec::new();
+        for (symbol, state) in symbols.iter() {
+            for ((leader, follower), pair) in &state.pairs {
+                if pair.lags_ms.is_empty() {
+                    continue;
+                }
+                let mut lags: Vec<f64> = pair.lags_ms.iter().copied().collect();
+                lags.sort_by(f64::total_cmp);
+                let median_lag_ms = lags[lags.len() / 2];
+                let p90_lag_ms = lags[(lags.len() * 9 / 10).min(lags.len() - 1)];
+                let rate = follow_rate(pair);
+                let reverse = state.pairs.get(&(follower.clone(), leader.clone())).map_or(0.0, follow_rate);
+                report.push(LeadLag {
+                    symbol: symbol.clone(),
+                    leader: leader.clone(),
+                    follower: follower.clone(),
+                    leader_moves: pair.leader_moves,
+                    followed: pair.followed,
+                    follow_rate: rate,
+                    median_lag_ms,
+                    p90_lag_ms,
+                    viable: pair.followed >= config.min_samples
+                        && rate >= config.min_follow_rate
+                        && median_lag_ms >= min_lag_ms
+                        && rate > reverse,
+                });
+            }
+        }
+        report.sort_by(|a, b| {
+            b.viable
+                .cmp(&a.viable)
+                .then(b.median_lag_ms.total_cmp(&a.median_lag_ms))
+                .then_with(|| (&a.symbol, &a.leader, &a.follower).cmp(&(&b.symbol, &b.leader, &b.follower)))
+        });
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_slow_venue_repeating_fast_moves_is_viable() {
+        let detector = LeadLagDetector::new(LeadLagConfig::default());
+        let start = Instant::now();
+        let tick = |exchange: &str, mid: f64, at_ms: u64| MarketTick {
+            exchange: exchange.to_string(),
+            symbol: "BTC/USDT".to_string(),
+            bid: mid - 0.01,
+            ask: mid + 0.01,
+            last_price: mid,
+            volume: 1.0,
+            timestamp: start + Duration::from_millis(at_ms),
+            sequence: 0,
+        };
+        
+        // Binance moves first; kraken repeats it 80ms later, coinbase within 5ms
+        let mut mid = 100.0;
+        for step in 0..41u64 {
+            let at = step * 3000;
+            detector.observe(&tick("binance", mid, at));
+            detector.observe(&tick("coinbase", mid, at + 5));
+            detector.observe(&tick("kraken", mid, at + 80));
+            mid *= if step % 2 == 0 { 1.002 } else { 0.999 };
+        }
+        
+        let report = detector.report();
+        let pair = |leader: &str, follower: &str| report.iter().find(|lag| lag.leader == leader && lag.follower == follower).unwrap();
+        let kraken = pair("binance", "kraken");
+        assert!(kraken.viable);
+        assert_eq!((kraken.followed, kraken.median_lag_ms), (40, 80.0));
+        assert_eq!(report[0], *kraken);
+        // Too close to binance to trade, and kraken never leads anyone
+        assert!(!pair("binance", "coinbase").viable);
+        assert!(report.iter().all(|lag| lag.leader != "kraken"));
+        
+        // Sub-threshold wiggles aren't moves
+        detector.observe(&tick("binance", mid * 1.0001, 200_000));
+        assert_eq!(detector.report().iter().map(|lag| lag.followed).sum::<u64>(), report.iter().map(|lag| lag.followed).sum::<u64>());
+    }
+}
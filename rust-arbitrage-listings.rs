@@ -0,0 +1,163 @@
+// arbitrage/listings.rs - Newly listed pairs: a warm-up without alerts, then a stricter threshold
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+use super::fees;
+use super::types::ArbitrageOpportunity;
+
+/// Fresh listings open with wide spreads that can't be traded: deposits and
+/// withdrawals are often still closed, and books are thin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewListingConfig {
+    /// No opportunities through a pair for this long after it's listed
+    #[serde(with = "humantime_serde")]
+    pub warmup: Duration,
+    /// For this long after the warm-up, opportunities through the pair must
+    /// clear `min_profit_threshold` instead of the global one
+    #[serde(with = "humantime_serde")]
+    pub probation: Duration,
+    pub min_profit_threshold: f64,
+}
+
+impl Default for NewListingConfig {
+    fn default() -> Self {
+        Self {
+            warmup: Duration::from_secs(10 * 60),
+            probation: Duration::from_secs(2 * 3600),
+            min_profit_threshold: 0.02,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListingPhase {
+    WarmingUp, // No opportunities yet
+    Probation, // Held to the listing threshold
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NewListing {
+    pub symbol: String,
+    pub age_secs: u64,
+    pub phase: ListingPhase,
+}
+
+/// When each pair discovery found after startup was listed. Pairs known at
+/// startup have no age and are never held back
+#[derive(Default)]
+pub struct ListingAges {
+    /// Uppercase symbol -> when it was first seen listed
+    listed: RwLock<HashMap<String, Instant>>,
+}
+
+impl ListingAges {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Start the clock on `symbols`; a pair already tracked keeps its age
+    pub fn mark(&self, symbols: &[String], at: Instant) {
+        let mut listed = self.listed.write().unwrap();
+        for symbol in symbols {
+            listed.entry(symbol.to_uppercase()).or_insert(at);
+        }
+    }
+    
+    fn phase(listed_at: Instant, now: Instant, config: &NewListingConfig) -> Option<ListingPhase> {
+        let age = now.saturating_duration_since(listed_at);
+        if age < config.warmup {
+            Some(ListingPhase::WarmingUp)
+        } else if age < config.warmup + config.probation {
+            Some(ListingPhase::Probation)
+        } else {
+            None
+        }
+    }
+    
+    /// Forget pairs past their probation
+    pub fn prune(&self, now: Instant, config: &NewListingConfig) {
+        self.listed.write().unwrap().retain(|_, &mut at| Self::phase(at, now, config).is_some());
+    }
+    
+    /// Whether `opportunity` may be alerted given the pairs its trades use:
+    /// none warming up, and past the listing threshold if any is on probation
+    pub fn admits(&self, opportunity: &ArbitrageOpportunity, now: Instant, config: &NewListingConfig) -> bool {
+        let listed = self.listed.read().unwrap();
+        if listed.is_empty() {
+            return true;
+        }
+        let nodes = fees::path_nodes(&opportunity.path);
+        let phases = (0..nodes.len()).filter_map(|i| {
+            let (from, to) = (nodes[i], nodes[(i + 1) % nodes.len()]);
+            if from.1 != to.1 {
+                return None;
+            }
+            [format!("{}/{}", from.0, to.0), format!("{}/{}", to.0, from.0)]
+                .iter()
+                .find_map(|symbol| listed.get(&symbol.to_uppercase()))
+                .and_then(|&at| Self::phase(at, now, config))
+        });
+        let mut probation = false;
+        for phase in phases {
+            match phase {
+                ListingPhase::WarmingUp => return false,
+                ListingPhase::Probation => probation = true,
+            }
+        }
+        !probation || opportunity.profit_percentage > config.min_profit_threshold
+    }
+    
+    /// Pairs still warming up or on probation, newest first
+    pub fn report(&self, now: Instant, config: &NewListingConfig) -> Vec<NewListing> {
+        let mut report: Vec<NewListing> = self
+            .listed
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|(symbol, &at)| {
+                Some(NewListing {
+                    symbol: symbol.clone(),
+                    age_secs: now.saturating_duration_since(at).as_secs(),
+                    phase: Self::phase(at, now, config)?,
+                })
+            })
+            .collect();
+        report.sort_by(|a, b| a.age_secs.cmp(&b.age_secs).then_with(|| a.symbol.cmp(&b.symbol)));
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_new_pairs_warm_up_then_need_the_listing_threshold() {
+        let config = NewListingConfig::default();
+        let ages = ListingAges::new();
+        let start = Instant::now();
+        ages.mark(&["new/usdt".to_string()], start);
+        // Marking again doesn't restart the clock
+        ages.mark(&["NEW/USDT".to_string()], start + Duration::from_secs(60));
+        
+        let cross = ArbitrageOpportunity::sample("NEW_binance -> USDT_binance -> USDT_kraken -> NEW_kraken", 0.05);
+        let modest = ArbitrageOpportunity::sample("USDT_binance -> NEW_binance -> BTC_binance", 0.01);
+        let unrelated = ArbitrageOpportunity::sample("BTC_binance -> USDT_binance -> ETH_binance", 0.01);
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        
+        assert!(!ages.admits(&cross, at(5 * 60), &config));
+        assert!(ages.admits(&unrelated, at(5 * 60), &config));
+        assert_eq!(ages.report(at(5 * 60), &config), vec![NewListing { symbol: "NEW/USDT".to_string(), age_secs: 300, phase: ListingPhase::WarmingUp }]);
+        
+        assert!(ages.admits(&cross, at(30 * 60), &config));
+        assert!(!ages.admits(&modest, at(30 * 60), &config));
+        assert_eq!(ages.report(at(30 * 60), &config)[0].phase, ListingPhase::Probation);
+        
+        ages.prune(at(3 * 3600), &config);
+        assert!(ages.admits(&modest, at(3 * 3600), &config));
+        assert!(ages.report(at(3 * 3600), &config).is_empty());
+    }
+}
@@ -0,0 +1,204 @@
+// arbitrage/maker.rs - Maker-leg variant: cycles that pay once some legs rest as maker orders
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::markets::{AssetAlias, Fees};
+use super::fees;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MakerConfig {
+    /// Also report cycles that only pay with some legs posted as maker orders
+    pub enabled: bool,
+    /// Most legs of one cycle that may rest on the book
+    pub max_maker_legs: usize,
+    /// Chance a resting order at the touch fills before the cycle goes stale,
+    /// by exchange
+    pub fill_probability: HashMap<String, f64>,
+    /// For exchanges not in `fill_probability`
+    pub default_fill_probability: f64,
+}
+
+impl Default for MakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_maker_legs: 1,
+            fill_probability: HashMap::new(),
+            default_fill_probability: 0.3,
+        }
+    }
+}
+
+impl MakerConfig {
+    pub fn fill_probability(&self, exchange: &str) -> f64 {
+        self.fill_probability.get(exchange).copied().unwrap_or(self.default_fill_probability)
+    }
+}
+
+/// How to trade a cycle with some legs posted as maker orders.
+/// `maker_legs[0]` is posted first and nothing else trades until it fills;
+/// the other maker legs fall back to taking if they don't fill
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MakerPlan {
+    /// Leg `i` trades from node `i` of the path to node `i + 1`
+    pub maker_legs: Vec<usize>,
+    /// Chance the first maker leg fills and the cycle starts
+    pub fill_probability: f64,
+    /// Net of fees, if every maker leg fills
+    pub profit_if_filled: f64,
+    /// Net of fees, weighted by the fill probabilities
+    pub expected_profit: f64,
+    /// Net of fees with every leg taking, for comparison
+    pub taker_profit: f64,
+}
+
+/// What one leg returns per unit in, after fees, taking or resting at the touch
+struct Leg {
+    taker: f64,
+    /// Return and fill probability; None for transfers, conversions and
+    /// markets without a quote on the other side
+    maker: Option<(f64, f64)>,
+}
+
+/// Best plan for `cycle` (graph indices in trade order, named by `path`).
+/// A maker leg from u to v rests at the price a taker going from v to u gets,
+/// so its rate is the inverse of that edge's and it pays the maker fee. None
+/// if no leg can rest or `path` doesn't match `cycle`
+pub fn best_plan(
+    cycle: &[usize],
+    graph: &[Vec<f64>],
+    path: &str,
+    fee_schedule: &HashMap<String, Fees>,
+    symbols: &[String],
+    aliases: &[AssetAlias],
+    config: &MakerConfig,
+) -> Option<MakerPlan> {
+    let nodes = fees::path_nodes(path);
+    if cycle.len() < 3 || nodes.len() != cycle.len() {
+        return None;
+    }
+    
+    let legs: Vec<Leg> = (0..cycle.len())
+        .map(|i| {
+            let j = (i + 1) % cycle.len();
+            let (u, v) = (cycle[i], cycle[j]);
+            if !fees::is_trade(nodes[i], nodes[j], aliases) {
+                return Leg { taker: (-graph[u][v]).exp(), maker: None };
+            }
+            let rates = fee_schedule.get(nodes[i].1).cloned().unwrap_or(Fees { maker: 0.0, taker: 0.0, charged_in: None });
+            let after = |rate: f64| fees::trade_multiplier(&rates, rate, nodes[i], nodes[j].0, symbols);
+            Leg {
+                taker: (-graph[u][v]).exp() * after(rates.taker),
+                maker: graph[v][u]
+                    .is_finite()
+                    .then(|| (graph[v][u].exp() * after(rates.maker), config.fill_probability(nodes[i].1))),
+            }
+        })
+        .collect();
+    let taker_profit = legs.iter().map(|leg| leg.taker).product::<f64>() - 1.0;
+    if !taker_profit.is_finite() {
+        return None;
+    }
+    
+    let candidates: Vec<usize> = (0..legs.len()).filter(|&i| legs[i].maker.is_some()).collect();
+    let mut subsets = Vec::new();
+    collect_subsets(&candidates, config.max_maker_legs, 0, &mut Vec::new(), &mut subsets);
+    
+    let mut best: Option<MakerPlan> = None;
+    for subset in subsets {
+        // Any of the maker legs can be the one posted first
+        for &first in &subset {
+            let first_probability = legs[first].maker?.1;
+            let mut filled = 1.0;
+            let mut expected = 1.0;
+            for (i, leg) in legs.iter().enumerate() {
+                match leg.maker {
+                    Some((rate, _)) if i == first => {
+                        filled *= rate;
+                        expected *= rate;
+                    }
+                    Some((rate, probability)) if subset.contains(&i) => {
+                        filled *= rate;
+                        expected *= probability * rate + (1.0 - probability) * leg.taker;
+                    }
+                    _ => {
+                        filled *= leg.taker;
+                        expected *= leg.taker;
+                    }
+                }
+            }
+            // If the first leg never fills nothing trades, for no gain or loss
+            let expected_profit = first_probability * (expected - 1.0);
+            if best.as_ref().is_none_or(|plan| expected_profit > plan.expected_profit) {
+                let mut maker_legs = vec![first];
+                maker_legs.extend(subset.iter().copied().filter(|&i| i != first));
+                best = Some(MakerPlan {
+                    maker_legs,
+                    fill_probability: first_probability,
+                    profit_if_filled: filled - 1.0,
+                    expected_profit,
+                    taker_profit,
+                });
+            }
+        }
+    }
+    best
+}
+
+/// Every non-empty subset of `candidates[start..]` with at most `max` members
+fn collect_subsets(candidates: &[usize], max: usize, start: usize, current: &mut Vec<usize>, out: &mut Vec<Vec<usize>>) {
+    for i in start..candidates.len() {
+        if current.len() == max {
+            return;
+        }
+        current.push(candidates[i]);
+        out.push(current.clone());
+        collect_subsets(candidates, max, i + 1, current, out);
+        current.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Both edges of `base/quote`, weighted the way the engine writes them
+    fn book(graph: &mut [Vec<f64>], base: usize, quote: usize, bid: f64, ask: f64) {
+        graph[base][quote] = -bid.ln();
+        graph[quote][base] = -(1.0 / ask).ln();
+    }
+    
+    #[test]
+    fn test_maker_leg_turns_marginal_cycle_profitable() {
+        // BTC 0, ETH 1, USDT 2 on binance; 0.25% gross before fees
+        let mut graph = vec![vec![f64::INFINITY; 3]; 3];
+        book(&mut graph, 0, 2, 50000.0, 50010.0);
+        book(&mut graph, 1, 0, 0.05983, 0.05984);
+        book(&mut graph, 1, 2, 3000.0, 3000.3);
+        let cycle = [2, 0, 1];
+        let path = "USDT_binance -> BTC_binance -> ETH_binance";
+        let schedule = HashMap::from([("binance".to_string(), Fees { maker: -0.0001, taker: 0.001, charged_in: None })]);
+        let config = MakerConfig {
+            enabled: true,
+            fill_probability: HashMap::from([("binance".to_string(), 0.5)]),
+            ..MakerConfig::default()
+        };
+        
+        let plan = best_plan(&cycle, &graph, path, &schedule, &[], &[], &config).unwrap();
+        assert!(plan.taker_profit < 0.0, "three taker fees eat the spread: {}", plan.taker_profit);
+        // Resting the BTC buy at the bid improves the price the most
+        assert_eq!(plan.maker_legs, vec![0]);
+        assert_eq!(plan.fill_probability, 0.5);
+        assert!(plan.profit_if_filled > 0.0);
+        assert!((plan.expected_profit - 0.5 * plan.profit_if_filled).abs() < 1e-12);
+        
+        // A second maker leg only adds its expected improvement
+        let two = best_plan(&cycle, &graph, path, &schedule, &[], &[], &MakerConfig { max_maker_legs: 2, ..config.clone() }).unwrap();
+        assert_eq!(two.maker_legs.len(), 2);
+        assert!(two.expected_profit > plan.expected_profit && two.profit_if_filled > two.expected_profit / 0.5);
+        
+        // Transfers between exchanges can't rest
+        let cross = "USDT_binance -> BTC_kraken -> ETH_binance";
+        assert!(best_plan(&cycle, &graph, cross, &schedule, &[], &[], &config).unwrap().maker_legs.iter().all(|&leg| leg == 2));
+    }
+}
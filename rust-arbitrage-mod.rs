@@ -0,0 +1,40 @@
+// arbitrage/mod.rs - Arbitrage detection module
+pub mod aliases;
+pub mod allocation;
+pub mod atomicity;
+pub mod basis;
+pub mod bridge;
+pub mod budget;
+pub mod cluster;
+pub mod controls;
+pub mod dedup;
+pub mod depth;
+pub mod engine;
+pub mod fairvalue;
+pub mod fees;
+pub mod funding;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+pub mod heat;
+pub mod history;
+pub mod hotset;
+pub mod johnson;
+pub mod leadlag;
+pub mod listings;
+pub mod maker;
+pub mod partition;
+pub mod persistence;
+pub mod pruning;
+pub mod quotes;
+pub mod replay;
+pub mod rollups;
+pub mod routes;
+pub mod snapshot;
+pub mod stats;
+pub mod statarb;
+pub mod ticks;
+pub mod transfers;
+pub mod types;
+pub mod washtrade;
+
+pub use engine::{ArbitrageEngine, Config, DetectionBackend, PriceUpdate};
@@ -0,0 +1,73 @@
+// arbitrage/partition.rs - Independent slices of the currency graph, detected in parallel
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+
+/// The markets quoted in `quotes` and the assets they trade, detected on their
+/// own (e.g. a USDT universe apart from a BTC universe)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphPartition {
+    pub name: String,
+    pub quotes: Vec<String>,
+    /// Replaces `min_profit_threshold` for cycles found in this partition
+    #[serde(default)]
+    pub min_profit_threshold: Option<f64>,
+}
+
+impl GraphPartition {
+    /// The partition's quote currencies and every base `symbols` quotes in one of them
+    pub fn assets(&self, symbols: &[String]) -> HashSet<String> {
+        let mut assets: HashSet<String> = self.quotes.iter().cloned().collect();
+        for symbol in symbols {
+            if let Some((base, quote)) = symbol.split_once('/') {
+                if self.quotes.iter().any(|q| q == quote) {
+                    assets.insert(base.to_string());
+                }
+            }
+        }
+        assets
+    }
+    
+    /// Sorted graph indices of nodes holding one of the partition's assets, on any exchange
+    pub fn node_indices(&self, symbols: &[String], currencies: &HashMap<String, usize>, n: usize) -> Vec<usize> {
+        let assets = self.assets(symbols);
+        let mut nodes: Vec<usize> = currencies
+            .iter()
+            .filter(|(node, &i)| i < n && assets.contains(node.rsplit_once('_').map_or(node.as_str(), |(asset, _)| asset)))
+            .map(|(_, &i)| i)
+            .collect();
+        nodes.sort_unstable();
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_partition_holds_its_quotes_and_their_bases() {
+        let symbols: Vec<String> = ["BTC/USDT", "ETH/USDT", "ETH/BTC", "SOL/ETH"].iter().map(|s| s.to_string()).collect();
+        let partition = |quotes: &[&str]| GraphPartition {
+            name: quotes.join("+"),
+            quotes: quotes.iter().map(|q| q.to_string()).collect(),
+            min_profit_threshold: None,
+        };
+        
+        let mut usdt: Vec<String> = partition(&["USDT"]).assets(&symbols).into_iter().collect();
+        usdt.sort();
+        assert_eq!(usdt, vec!["BTC", "ETH", "USDT"]);
+        let mut btc: Vec<String> = partition(&["BTC"]).assets(&symbols).into_iter().collect();
+        btc.sort();
+        assert_eq!(btc, vec!["BTC", "ETH"]);
+        
+        let currencies: HashMap<String, usize> = ["BTC_binance", "USDT_binance", "SOL_binance", "ETH_kraken", "USDT_kraken"]
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.to_string(), i))
+            .collect();
+        assert_eq!(partition(&["USDT"]).node_indices(&symbols, &currencies, 5), vec![0, 1, 3, 4]);
+        assert_eq!(partition(&["ETH"]).node_indices(&symbols, &currencies, 5), vec![2, 3]);
+        // Indices past the snapshot are ignored
+        assert_eq!(partition(&["USDT"]).node_indices(&symbols, &currencies, 2), vec![0, 1]);
+    }
+}
@@ -0,0 +1,177 @@
+// arbitrage/persistence.rs - Structural spread detection and path blacklisting
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+
+use super::types::ArbitrageOpportunity;
+
+/// A path that stops being seen for longer than this starts a fresh streak
+const MAX_STREAK_GAP: Duration = Duration::from_secs(5);
+
+/// A spread that never closed; usually a withdrawal suspension or delisting
+/// rather than a tradable opportunity
+#[derive(Debug, Clone, Serialize)]
+pub struct StructuralSpread {
+    pub path: String,
+    pub exchanges: Vec<String>,
+    pub profit_percentage: f64,
+    pub persisted_secs: u64,
+    pub blacklisted_secs: u64,
+}
+
+pub type StructuralSpreadCallback = Box<dyn Fn(StructuralSpread) + Send + Sync>;
+
+#[derive(Debug)]
+pub enum SpreadClass {
+    Transient,
+    /// Crossed the persistence limit on this observation; path is now blacklisted
+    Structural(StructuralSpread),
+    Blacklisted,
+}
+
+struct Streak {
+    first_seen: Instant,
+    last_seen: Instant,
+}
+
+pub struct SpreadPersistence {
+    structural_after: Duration,
+    blacklist_for: Duration,
+    streaks: HashMap<String, Streak>,
+    blacklist: HashMap<String, Instant>, // path -> blacklisted until
+}
+
+impl SpreadPersistence {
+    pub fn new(structural_after: Duration, blacklist_for: Duration) -> Self {
+        Self {
+            structural_after,
+            blacklist_for,
+            streaks: HashMap::new(),
+            blacklist: HashMap::new(),
+        }
+    }
+    
+    /// New limits from a config reload; open streaks and blacklist entries are kept
+    pub fn set_limits(&mut self, structural_after: Duration, blacklist_for: Duration) {
+        self.structural_after = structural_after;
+        self.blacklist_for = blacklist_for;
+    }
+    
+    pub fn observe(&mut self, opportunity: &ArbitrageOpportunity, now: Instant) -> SpreadClass {
+        if let Some(&until) = self.blacklist.get(&opportunity.path) {
+            if now < until {
+                return SpreadClass::Blacklisted;
+            }
+            self.blacklist.remove(&opportunity.path);
+        }
+        
+        let streak = self.streaks.entry(opportunity.path.clone()).or_insert(Streak {
+            first_seen: now,
+            last_seen: now,
+        });
+        if now.saturating_duration_since(streak.last_seen) > MAX_STREAK_GAP {
+            streak.first_seen = now;
+        }
+        streak.last_seen = now;
+        
+        let persisted = now.saturating_duration_since(streak.first_seen);
+        if persisted < self.structural_after {
+            return SpreadClass::Transient;
+        }
+        
+        self.streaks.remove(&opportunity.path);
+        self.blacklist.insert(opportunity.path.clone(), now + self.blacklist_for);
+        
+        SpreadClass::Structural(StructuralSpread {
+            path: opportunity.path.clone(),
+            exchanges: opportunity.exchanges.clone(),
+            profit_percentage: opportunity.profit_percentage,
+            persisted_secs: persisted.as_secs(),
+            blacklisted_secs: self.blacklist_for.as_secs(),
+        })
+    }
+    
+    /// Drop closed streaks and expired blacklist entries
+    pub fn prune(&mut self, now: Instant) {
+        self.streaks
+            .retain(|_, streak| now.saturating_duration_since(streak.last_seen) <= MAX_STREAK_GAP);
+        self.blacklist.retain(|_, until| now < *until);
+    }
+    
+    /// Blacklist `path` by hand for the usual period; false if it already was
+    pub fn blacklist(&mut self, path: &str, now: Instant) -> bool {
+        self.streaks.remove(path);
+        let already = self.blacklist.get(path).is_some_and(|&until| now < until);
+        self.blacklist.insert(path.to_string(), now + self.blacklist_for);
+        !already
+    }
+    
+    pub fn blacklisted_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self.blacklist.keys().cloned().collect();
+        paths.sort();
+        paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const PATH: &str = "BTC_binance -> USDT_binance -> BTC_kraken";
+    
+    #[test]
+    fn test_persistent_spread_becomes_structural_and_blacklisted() {
+        let mut persistence = SpreadPersistence::new(Duration::from_secs(10), Duration::from_secs(60));
+        let start = Instant::now();
+        let opp = ArbitrageOpportunity::sample(PATH, 0.02);
+        
+        for second in 0..10 {
+            let class = persistence.observe(&opp, start + Duration::from_secs(second));
+            assert!(matches!(class, SpreadClass::Transient), "second {}", second);
+        }
+        
+        match persistence.observe(&opp, start + Duration::from_secs(10)) {
+            SpreadClass::Structural(spread) => assert_eq!(spread.persisted_secs, 10),
+            other => panic!("expected structural, got {:?}", other),
+        }
+        
+        assert!(matches!(persistence.observe(&opp, start + Duration::from_secs(30)), SpreadClass::Blacklisted));
+        assert_eq!(persistence.blacklisted_paths(), vec![opp.path.clone()]);
+    }
+    
+    #[test]
+    fn test_gap_resets_streak() {
+        let mut persistence = SpreadPersistence::new(Duration::from_secs(10), Duration::from_secs(60));
+        let start = Instant::now();
+        let opp = ArbitrageOpportunity::sample(PATH, 0.02);
+        
+        persistence.observe(&opp, start);
+        persistence.observe(&opp, start + Duration::from_secs(4));
+        // Spread closed for longer than the gap, then reopened
+        persistence.observe(&opp, start + Duration::from_secs(12));
+        
+        assert!(matches!(persistence.observe(&opp, start + Duration::from_secs(16)), SpreadClass::Transient));
+    }
+    
+    #[test]
+    fn test_blacklist_expires() {
+        let mut persistence = SpreadPersistence::new(Duration::ZERO, Duration::from_secs(60));
+        let start = Instant::now();
+        let opp = ArbitrageOpportunity::sample(PATH, 0.02);
+        
+        assert!(matches!(persistence.observe(&opp, start), SpreadClass::Structural(_)));
+        persistence.prune(start + Duration::from_secs(61));
+        assert!(persistence.blacklisted_paths().is_empty());
+    }
+    
+    #[test]
+    fn test_manual_blacklist() {
+        let mut persistence = SpreadPersistence::new(Duration::from_secs(10), Duration::from_secs(60));
+        let start = Instant::now();
+        let opp = ArbitrageOpportunity::sample(PATH, 0.02);
+        
+        assert!(persistence.blacklist(&opp.path, start));
+        assert!(!persistence.blacklist(&opp.path, start + Duration::from_secs(1)));
+        assert!(matches!(persistence.observe(&opp, start + Duration::from_secs(30)), SpreadClass::Blacklisted));
+    }
+}
@@ -0,0 +1,145 @@
+// arbitrage/pruning.rs - Drop edges that never come close to a profitable cycle
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+use super::routes::RouteTable;
+use super::snapshot::GraphSnapshot;
+
+/// Directed edges by graph index
+pub type EdgeSet = HashSet<(usize, usize)>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PruningConfig {
+    pub enabled: bool,
+    /// An edge is pruned once it has been irrelevant for this long
+    #[serde(with = "humantime_serde")]
+    pub window: Duration,
+    /// An edge stays relevant while the best cycle through it loses no more
+    /// than `margin` times `min_profit_threshold`
+    pub margin: f64,
+}
+
+impl Default for PruningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window: Duration::from_secs(3600),
+            margin: 10.0,
+        }
+    }
+}
+
+/// Tracks when each edge last sat on a near-profitable cycle. Scored on the
+/// route planner's full-graph pass, so a pruned edge comes back on the first
+/// pass that finds it relevant again
+pub struct EdgePruner {
+    config: PruningConfig,
+    last_relevant: HashMap<(usize, usize), Instant>, // First seen counts as relevant
+}
+
+impl EdgePruner {
+    pub fn new(config: PruningConfig) -> Self {
+        Self {
+            config,
+            last_relevant: HashMap::new(),
+        }
+    }
+    
+    /// Score every edge of `graph` against `routes` computed on it, and return
+    /// the edges irrelevant for the whole window
+    pub fn observe(&mut self, graph: &[Vec<f64>], routes: &RouteTable, threshold: f64, now: Instant) -> EdgeSet {
+        if !self.config.enabled {
+            return EdgeSet::new();
+        }
+        
+        let floor = -self.config.margin * threshold;
+        let mut seen = HashSet::new();
+        for (u, row) in graph.iter().enumerate() {
+            for (v, weight) in row.iter().enumerate() {
+                if u == v || !weight.is_finite() {
+                    continue;
+                }
+                seen.insert((u, v));
+                let relevant = routes.best_cycle_through(u, v).is_some_and(|profit| profit >= floor);
+                if relevant {
+                    self.last_relevant.insert((u, v), now);
+                } else {
+                    self.last_relevant.entry((u, v)).or_insert(now);
+                }
+            }
+        }
+        // Edges that went away (dropped feeds, degenerate quotes) start over
+        self.last_relevant.retain(|edge, _| seen.contains(edge));
+        
+        self.last_relevant
+            .iter()
+            .filter(|(_, &at)| now.saturating_duration_since(at) >= self.config.window)
+            .map(|(&edge, _)| edge)
+            .collect()
+    }
+}
+
+/// `snapshot` without `pruned` edges or the nodes left without both an edge
+/// in and an edge out, since no cycle can pass through them
+pub fn prune(snapshot: &GraphSnapshot, pruned: &EdgeSet) -> GraphSnapshot {
+    let n = snapshot.len();
+    let live = |u: usize, v: usize| u != v && snapshot.weights[u][v].is_finite() && !pruned.contains(&(u, v));
+    let kept: Vec<usize> = (0..n)
+        .filter(|&u| (0..n).any(|v| live(u, v)) && (0..n).any(|v| live(v, u)))
+        .collect();
+    
+    let weights = kept
+        .iter()
+        .map(|&u| kept.iter().map(|&v| if live(u, v) { snapshot.weights[u][v] } else { f64::INFINITY }).collect())
+        .collect();
+    let position: HashMap<usize, usize> = kept.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+    let currencies = snapshot
+        .currencies
+        .iter()
+        .filter_map(|(name, u)| Some((name.clone(), *position.get(u)?)))
+        .collect();
+    
+    GraphSnapshot::new(snapshot.epoch, weights, currencies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_edges_far_from_any_cycle_are_pruned_after_the_window() {
+        let inf = f64::INFINITY;
+        // A-B-C round trip loses 0.3%; A <-> D loses 19%
+        let graph = vec![
+            vec![inf, -f64::ln(0.999), inf, -f64::ln(0.9)],
+            vec![inf, inf, -f64::ln(0.999), inf],
+            vec![-f64::ln(0.999), inf, inf, inf],
+            vec![-f64::ln(0.9), inf, inf, inf],
+        ];
+        let currencies: HashMap<String, usize> = ["A_x", "B_x", "C_x", "D_x"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.to_string(), i))
+            .collect();
+        let routes = RouteTable::compute(&graph, &currencies);
+        let mut pruner = EdgePruner::new(PruningConfig::default());
+        
+        let start = Instant::now();
+        assert!(pruner.observe(&graph, &routes, 0.001, start).is_empty(), "new edges get a full window");
+        let later = start + Duration::from_secs(3600);
+        let pruned = pruner.observe(&graph, &routes, 0.001, later);
+        assert_eq!(pruned, HashSet::from([(0, 3), (3, 0)]));
+        
+        let snapshot = prune(&GraphSnapshot::new(7, graph.clone(), currencies.clone()), &pruned);
+        assert_eq!((snapshot.epoch, snapshot.len()), (7, 3));
+        assert!(!snapshot.currencies.contains_key("D_x"));
+        assert!(snapshot.weights[snapshot.currencies["A_x"]][snapshot.currencies["B_x"]].is_finite());
+        
+        // The background pass brings an edge back as soon as it's relevant
+        let mut tighter = graph;
+        tighter[0][3] = -f64::ln(1.11);
+        let routes = RouteTable::compute(&tighter, &currencies);
+        assert!(pruner.observe(&tighter, &routes, 0.001, later + Duration::from_secs(5)).is_empty());
+    }
+}
@@ -0,0 +1,433 @@
+// arbitrage/quotes.rs - Latest top-of-book quote per exchange and symbol
+use std::collections::{BTreeSet, HashMap};
+use std::sync::RwLock;
+use std::time::Instant;
+use serde::Serialize;
+
+use super::types::MarketTick;
+
+#[derive(Debug, Clone)]
+struct Quote {
+    bid: f64,
+    ask: f64,
+    volume: f64,
+    received_at: Instant,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VenueQuote {
+    pub exchange: String,
+    pub bid: f64,
+    pub ask: f64,
+    pub volume: f64,
+    pub age_ms: u64,
+}
+
+/// Buy on `buy_exchange` at its ask, sell on `sell_exchange` at its bid
+#[derive(Debug, Clone, Serialize)]
+pub struct CrossSpread {
+    pub buy_exchange: String,
+    pub sell_exchange: String,
+    pub buy_price: f64,
+    pub sell_price: f64,
+    pub spread_percentage: f64,
+}
+
+/// Every venue's latest quote for one symbol, read at a single instant
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketSnapshot {
+    pub symbol: String,
+    pub quotes: Vec<VenueQuote>,
+    pub spreads: Vec<CrossSpread>,
+}
+
+/// Best bid and best offer across every venue quoting a symbol, NBBO-style
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompositeQuote {
+    pub symbol: String,
+    pub bid: f64,
+    pub bid_exchange: String,
+    pub ask: f64,
+    pub ask_exchange: String,
+    pub mid: f64,
+    /// Ask over bid as a fraction of mid; negative when venues cross
+    pub spread: f64,
+    pub venues: usize,
+}
+
+/// How far one venue's quote for a pair sits from the composite
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompositeDeviation {
+    pub symbol: String,
+    pub exchange: String,
+    pub venue_mid: f64,
+    pub composite_mid: f64,
+    /// Venue mid over composite mid, in basis points
+    pub deviation_bps: f64,
+}
+
+/// An asset's price on a venue's local-currency market (BTC/KRW), converted
+/// at that venue's own rate for the bridge currency (USDT/KRW), against the
+/// other venues' composite for the bridge pair (BTC/USDT)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RegionalPremium {
+    pub asset: String,
+    pub exchange: String,
+    pub local_currency: String,
+    pub bridge_currency: String,
+    /// Local mid in the bridge currency
+    pub local_price: f64,
+    pub reference_price: f64,
+    /// Local price over reference price; positive when the region pays more
+    pub premium_percentage: f64,
+}
+
+pub struct QuoteBook {
+    quotes: RwLock<HashMap<String, HashMap<String, Quote>>>, // symbol -> exchange -> quote
+}
+
+impl QuoteBook {
+    pub fn new() -> Self {
+        Self {
+            quotes: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    pub fn update(&self, tick: &MarketTick) {
+        let mut quotes = self.quotes.write().unwrap();
+        quotes
+            .entry(tick.symbol.clone())
+            .or_default()
+            .insert(tick.exchange.clone(), Quote {
+                bid: tick.bid,
+                ask: tick.ask,
+                volume: tick.volume,
+                received_at: tick.timestamp,
+            });
+    }
+    
+    /// Forget `exchange`'s quote for `symbol`, e.g. once the feed is unsubscribed
+    pub fn remove(&self, exchange: &str, symbol: &str) {
+        let mut quotes = self.quotes.write().unwrap();
+        if let Some(venues) = quotes.get_mut(symbol) {
+            venues.remove(exchange);
+            if venues.is_empty() {
+                quotes.remove(symbol);
+            }
+        }
+    }
+    
+    /// `exchange`'s latest bid and ask for `symbol`
+    pub fn top(&self, exchange: &str, symbol: &str) -> Option<(f64, f64)> {
+        let quotes = self.quotes.read().unwrap();
+        let quote = quotes.get(symbol)?.get(exchange)?;
+        Some((quote.bid, quote.ask))
+    }
+    
+    /// Every quoted symbol, sorted
+    pub fn symbols(&self) -> Vec<String> {
+        let mut symbols: Vec<String> = self.quotes.read().unwrap().keys().cloned().collect();
+        symbols.sort();
+        symbols
+    }
+    
+    /// Every exchange with a quote, sorted
+    pub fn exchanges(&self) -> BTreeSet<String> {
+        let quotes = self.quotes.read().unwrap();
+        quotes.values().flat_map(|venues| venues.keys().cloned()).collect()
+    }
+    
+    /// Every venue's latest quote for `symbol`, by exchange name, with their ages as of `now`
+    pub fn venue_quotes(&self, symbol: &str, now: Instant) -> Option<Vec<VenueQuote>> {
+        let quotes = self.quotes.read().unwrap();
+        let venues = quotes.get(symbol)?;
+        
+        let mut venue_quotes: Vec<VenueQuote> = venues
+            .iter()
+            .map(|(exchange, quote)| VenueQuote {
+                exchange: exchange.clone(),
+                bid: quote.bid,
+                ask: quote.ask,
+                volume: quote.volume,
+                age_ms: now.saturating_duration_since(quote.received_at).as_millis() as u64,
+            })
+            .collect();
+        venue_quotes.sort_by(|a, b| a.exchange.cmp(&b.exchange));
+        Some(venue_quotes)
+    }
+    
+    /// Quotes for `symbol` with their ages as of `now`
+    pub fn snapshot(&self, symbol: &str, now: Instant) -> Option<MarketSnapshot> {
+        let venue_quotes = self.venue_quotes(symbol, now)?;
+        
+        let mut spreads = Vec::new();
+        for buy in &venue_quotes {
+            for sell in &venue_quotes {
+                if buy.exchange == sell.exchange || buy.ask <= 0.0 || sell.bid <= 0.0 {
+                    continue;
+                }
+                spreads.push(CrossSpread {
+                    buy_exchange: buy.exchange.clone(),
+                    sell_exchange: sell.exchange.clone(),
+                    buy_price: buy.ask,
+                    sell_price: sell.bid,
+                    spread_percentage: (sell.bid - buy.ask) / buy.ask,
+                });
+            }
+        }
+        spreads.sort_by(|a, b| b.spread_percentage.total_cmp(&a.spread_percentage));
+        
+        Some(MarketSnapshot {
+            symbol: symbol.to_string(),
+            quotes: venue_quotes,
+            spreads,
+        })
+    }
+    
+    /// Composite best bid and offer for `symbol` across every venue with a usable quote
+    pub fn composite(&self, symbol: &str) -> Option<CompositeQuote> {
+        let quotes = self.quotes.read().unwrap();
+        composite_of(symbol, quotes.get(symbol)?)
+    }
+    
+    /// Composite for every quoted symbol, by symbol
+    pub fn composites(&self) -> Vec<CompositeQuote> {
+        let quotes = self.quotes.read().unwrap();
+        let mut composites: Vec<CompositeQuote> = quotes
+            .iter()
+            .filter_map(|(symbol, venues)| composite_of(symbol, venues))
+            .collect();
+        composites.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        composites
+    }
+    
+    /// Each single-venue leg of `path` ("BTC_kraken -> ETH_kraken -> ...")
+    /// against the composite for the pair it trades, where another venue
+    /// quotes that pair too
+    pub fn deviations(&self, path: &str) -> Vec<CompositeDeviation> {
+        let quotes = self.quotes.read().unwrap();
+        let nodes: Vec<(&str, &str)> = path
+            .split(" -> ")
+            .filter_map(|node| node.trim().rsplit_once('_'))
+            .collect();
+        
+        let mut deviations: Vec<CompositeDeviation> = Vec::new();
+        for pair in nodes.windows(2) {
+            let ((from, exchange), (to, next_exchange)) = (pair[0], pair[1]);
+            if exchange != next_exchange {
+                continue;
+            }
+            let listed = [format!("{}/{}", from, to), format!("{}/{}", to, from)]
+                .into_iter()
+                .find(|symbol| quotes.get(symbol).is_some_and(|venues| venues.contains_key(exchange)));
+            let Some(symbol) = listed else {
+                continue;
+            };
+            if deviations.iter().any(|seen| seen.symbol == symbol && seen.exchange == exchange) {
+                continue;
+            }
+            let venues = &quotes[&symbol];
+            let venue = &venues[exchange];
+            let Some(composite) = composite_of(&symbol, venues).filter(|composite| composite.venues > 1) else {
+                continue;
+            };
+            let venue_mid = (venue.bid + venue.ask) / 2.0;
+            deviations.push(CompositeDeviation {
+                symbol,
+                exchange: exchange.to_string(),
+                venue_mid,
+                composite_mid: composite.mid,
+                deviation_bps: (venue_mid / composite.mid - 1.0) * 10_000.0,
+            });
+        }
+        deviations
+    }
+    
+    /// Every asset quoted against `local` on a venue that also quotes
+    /// `bridge`/`local`, priced against the other venues' `bridge` markets;
+    /// largest premium or discount first
+    pub fn premiums(&self, local: &str, bridge: &str) -> Vec<RegionalPremium> {
+        let quotes = self.quotes.read().unwrap();
+        let mid = |quote: &Quote| (quote.bid > 0.0 && quote.ask > 0.0).then(|| (quote.bid + quote.ask) / 2.0);
+        let suffix = format!("/{}", local);
+        let rates = quotes.get(&format!("{}/{}", bridge, local));
+        
+        let mut premiums = Vec::new();
+        for (symbol, venues) in quotes.iter() {
+            let Some(asset) = symbol.strip_suffix(&suffix).filter(|&asset| asset != bridge) else {
+                continue;
+            };
+            let reference_symbol = format!("{}/{}", asset, bridge);
+            let Some(references) = quotes.get(&reference_symbol) else {
+                continue;
+            };
+            for (exchange, quote) in venues {
+                let rate = rates.and_then(|rates| rates.get(exchange)).and_then(mid);
+                let (Some(local_mid), Some(rate)) = (mid(quote), rate) else {
+                    continue;
+                };
+                let others: HashMap<String, Quote> = references
+                    .iter()
+                    .filter(|(venue, _)| *venue != exchange)
+                    .map(|(venue, quote)| (venue.clone(), quote.clone()))
+                    .collect();
+                let Some(reference) = composite_of(&reference_symbol, &others) else {
+                    continue;
+                };
+                let local_price = local_mid / rate;
+                premiums.push(RegionalPremium {
+                    asset: asset.to_string(),
+                    exchange: exchange.clone(),
+                    local_currency: local.to_string(),
+                    bridge_currency: bridge.to_string(),
+                    local_price,
+                    reference_price: reference.mid,
+                    premium_percentage: local_price / reference.mid - 1.0,
+                });
+            }
+        }
+        premiums.sort_by(|a, b| b.premium_percentage.abs().total_cmp(&a.premium_percentage.abs()));
+        premiums
+    }
+}
+
+fn composite_of(symbol: &str, venues: &HashMap<String, Quote>) -> Option<CompositeQuote> {
+    let mut usable: Vec<(&String, &Quote)> = venues
+        .iter()
+        .filter(|(_, quote)| quote.bid > 0.0 && quote.ask > 0.0 && quote.bid.is_finite() && quote.ask.is_finite())
+        .collect();
+    // Ties go to the first exchange by name, so the composite doesn't flicker
+    usable.sort_by(|a, b| a.0.cmp(b.0));
+    let (bid_exchange, best_bid) = usable.iter().fold(None::<(&String, &Quote)>, |best, &(exchange, quote)| match best {
+        Some((_, current)) if current.bid >= quote.bid => best,
+        _ => Some((exchange, quote)),
+    })?;
+    let (ask_exchange, best_ask) = usable.iter().fold(None::<(&String, &Quote)>, |best, &(exchange, quote)| match best {
+        Some((_, current)) if current.ask <= quote.ask => best,
+        _ => Some((exchange, quote)),
+    })?;
+    let mid = (best_bid.bid + best_ask.ask) / 2.0;
+    Some(CompositeQuote {
+        symbol: symbol.to_string(),
+        bid: best_bid.bid,
+        bid_exchange: bid_exchange.clone(),
+        ask: best_ask.ask,
+        ask_exchange: ask_exchange.clone(),
+        mid,
+        spread: (best_ask.ask - best_bid.bid) / mid,
+        venues: usable.len(),
+    })
+}
+
+impl Default for QuoteBook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    
+    fn tick(exchange: &str, bid: f64, ask: f64, at: Instant) -> MarketTick {
+        quote(exchange, "BTC/USDT", bid, ask, at)
+    }
+    
+    fn quote(exchange: &str, symbol: &str, bid: f64, ask: f64, at: Instant) -> MarketTick {
+        MarketTick {
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            bid,
+            ask,
+            last_price: (bid + ask) / 2.0,
+            volume: 1.0,
+            timestamp: at,
+            sequence: 0,
+        }
+    }
+    
+    #[test]
+    fn test_snapshot_reports_all_venues_with_age() {
+        let book = QuoteBook::new();
+        let now = Instant::now();
+        book.update(&tick("binance", 50000.0, 50001.0, now - Duration::from_millis(250)));
+        book.update(&tick("kraken", 50010.0, 50012.0, now));
+        
+        let snapshot = book.snapshot("BTC/USDT", now).unwrap();
+        assert_eq!(snapshot.quotes.len(), 2);
+        assert_eq!(snapshot.quotes[0].exchange, "binance");
+        assert_eq!(snapshot.quotes[0].age_ms, 250);
+        assert_eq!(snapshot.quotes[1].age_ms, 0);
+    }
+    
+    #[test]
+    fn test_spreads_are_sorted_best_first() {
+        let book = QuoteBook::new();
+        let now = Instant::now();
+        book.update(&tick("binance", 50000.0, 50001.0, now));
+        book.update(&tick("kraken", 50101.0, 50102.0, now));
+        
+        let spreads = book.snapshot("BTC/USDT", now).unwrap().spreads;
+        assert_eq!(spreads.len(), 2);
+        assert_eq!(spreads[0].buy_exchange, "binance");
+        assert_eq!(spreads[0].sell_exchange, "kraken");
+        assert!((spreads[0].spread_percentage - 100.0 / 50001.0).abs() < 1e-12);
+        assert!(spreads[1].spread_percentage < 0.0);
+    }
+    
+    #[test]
+    fn test_composite_takes_best_bid_and_offer_across_venues() {
+        let book = QuoteBook::new();
+        let now = Instant::now();
+        book.update(&tick("binance", 50000.0, 50004.0, now));
+        book.update(&tick("kraken", 50002.0, 50006.0, now));
+        book.update(&tick("coinbase", 49990.0, 50003.0, now));
+        // A glitched venue doesn't drag the composite
+        book.update(&tick("mexc", 0.0, 50001.0, now));
+        
+        let composite = book.composite("BTC/USDT").unwrap();
+        assert_eq!((composite.bid_exchange.as_str(), composite.bid), ("kraken", 50002.0));
+        assert_eq!((composite.ask_exchange.as_str(), composite.ask), ("coinbase", 50003.0));
+        assert_eq!((composite.mid, composite.venues), (50002.5, 3));
+        assert_eq!(book.composites(), vec![composite.clone()]);
+        
+        // Kraken's mid of 50004 is 0.3 bps over the composite's; the
+        // cross-exchange leg has no single venue to compare
+        let deviations = book.deviations("USDT_kraken -> BTC_kraken -> BTC_binance -> USDT_binance");
+        assert_eq!(deviations.len(), 2);
+        assert_eq!((deviations[0].symbol.as_str(), deviations[0].exchange.as_str()), ("BTC/USDT", "kraken"));
+        assert!((deviations[0].deviation_bps - (50004.0 / 50002.5 - 1.0) * 10_000.0).abs() < 1e-9);
+        assert!(deviations[1].deviation_bps < 0.0);
+        
+        let alone = QuoteBook::new();
+        alone.update(&tick("binance", 50000.0, 50004.0, now));
+        assert!(alone.deviations("USDT_binance -> BTC_binance -> USDT_binance").is_empty());
+    }
+    
+    #[test]
+    fn test_krw_prices_compare_through_the_venues_own_usdt_rate() {
+        let book = QuoteBook::new();
+        let now = Instant::now();
+        book.update(&quote("upbit", "BTC/KRW", 71_500_000.0, 71_510_000.0, now));
+        book.update(&quote("upbit", "USDT/KRW", 1400.0, 1400.0, now));
+        // Upbit's own BTC/USDT is left out of the reference
+        book.update(&tick("upbit", 51000.0, 51010.0, now));
+        book.update(&tick("binance", 50000.0, 50000.0, now));
+        // No USDT/KRW rate on this venue
+        book.update(&quote("bithumb", "BTC/KRW", 71_000_000.0, 71_010_000.0, now));
+        
+        let premiums = book.premiums("KRW", "USDT");
+        assert_eq!(premiums.len(), 1);
+        let upbit = &premiums[0];
+        assert_eq!((upbit.asset.as_str(), upbit.exchange.as_str(), upbit.reference_price), ("BTC", "upbit", 50000.0));
+        assert!((upbit.premium_percentage - (71_505_000.0 / 1400.0 / 50000.0 - 1.0)).abs() < 1e-12);
+        assert!(upbit.premium_percentage > 0.02);
+    }
+    
+    #[test]
+    fn test_unknown_symbol() {
+        assert!(QuoteBook::new().snapshot("ETH/USDT", Instant::now()).is_none());
+        assert!(QuoteBook::new().composite("ETH/USDT").is_none());
+    }
+}
@@ -0,0 +1,253 @@
+// arbitrage/replay.rs - Offline re-detection over recorded ticks for debugging opportunities
+use std::collections::HashMap;
+use std::fmt;
+use tracing::{debug, trace};
+
+use crate::clock::{Clock, ManualClock};
+use crate::recording::{RecordedOpportunity, RecordedTick};
+use super::engine::{ArbitrageEngine, Config};
+use super::snapshot::GraphSnapshot;
+use super::types::{ArbitrageOpportunity, MarketTick};
+
+/// The live engine's graph, rebuilt one recorded tick at a time
+pub struct ReplayGraph {
+    config: Config,
+    graph: Vec<Vec<f64>>,
+    currencies: HashMap<String, usize>,
+    clock: ManualClock, // Follows the recorded tick times
+}
+
+impl ReplayGraph {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            graph: Vec::new(),
+            currencies: HashMap::new(),
+            clock: ManualClock::new(0),
+        }
+    }
+    
+    pub fn apply(&mut self, tick: &RecordedTick) {
+        self.clock.set_unix_ms(tick.at_ms);
+        let Some((base, quote)) = ArbitrageEngine::parse_symbol(&tick.symbol) else {
+            return;
+        };
+        let base_idx = ArbitrageEngine::get_or_create_currency_index(&mut self.currencies, format!("{}_{}", base, tick.exchange));
+        let quote_idx = ArbitrageEngine::get_or_create_currency_index(&mut self.currencies, format!("{}_{}", quote, tick.exchange));
+        
+        // Grow the matrix as currencies appear instead of preallocating
+        let n = self.currencies.len();
+        for row in &mut self.graph {
+            row.resize(n, f64::INFINITY);
+        }
+        while self.graph.len() < n {
+            let i = self.graph.len();
+            let mut row = vec![f64::INFINITY; n];
+            row[i] = 0.0;
+            self.graph.push(row);
+        }
+        
+        let market_tick = MarketTick {
+            exchange: tick.exchange.clone(),
+            symbol: tick.symbol.clone(),
+            bid: tick.bid,
+            ask: tick.ask,
+            last_price: (tick.bid + tick.ask) / 2.0,
+            volume: tick.volume,
+            timestamp: self.clock.now(),
+            sequence: 0,
+        };
+        ArbitrageEngine::write_tick_edges(&market_tick, base_idx, quote_idx, &mut self.graph);
+    }
+    
+    /// Profit of the cycle through `path`'s nodes, or `None` while an edge is missing
+    pub fn cycle_profit(&self, path: &str) -> Option<f64> {
+        let nodes: Vec<usize> = path
+            .split(" -> ")
+            .map(|node| self.currencies.get(node).copied())
+            .collect::<Option<_>>()?;
+        let total_log_return: f64 = (0..nodes.len())
+            .map(|i| self.graph[nodes[i]][nodes[(i + 1) % nodes.len()]])
+            .sum();
+        total_log_return.is_finite().then(|| (-total_log_return).exp() - 1.0)
+    }
+    
+    /// Full detection pass, exactly as the live detector's cold scan
+    pub fn detect(&self) -> Vec<ArbitrageOpportunity> {
+        let snapshot = GraphSnapshot::capture(0, &self.graph, &self.currencies);
+        ArbitrageEngine::detect_arbitrage_opportunities(&snapshot, &self.config, None, self.clock.now())
+    }
+}
+
+/// Same nodes in the same cyclic order, whichever node the path starts at
+fn same_cycle(a: &str, b: &str) -> bool {
+    let a: Vec<&str> = a.split(" -> ").collect();
+    let b: Vec<&str> = b.split(" -> ").collect();
+    a.len() == b.len() && (0..b.len()).any(|shift| (0..a.len()).all(|i| a[i] == b[(i + shift) % b.len()]))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EdgeChangeKind {
+    Created,
+    Destroyed,
+}
+
+/// A tick that moved the cycle across the profit threshold
+#[derive(Debug, Clone)]
+pub struct EdgeChange {
+    pub kind: EdgeChangeKind,
+    pub tick: RecordedTick,
+    pub profit_before: Option<f64>,
+    pub profit_after: Option<f64>,
+    /// Whether a full detection pass after this tick reports the cycle
+    pub detected: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct OpportunityTrace {
+    pub opportunity: RecordedOpportunity,
+    pub ticks_replayed: usize,
+    pub profit_at_detection: Option<f64>,
+    pub changes: Vec<EdgeChange>,
+}
+
+/// Replay `ticks` (the window around detection) and record every update that
+/// opened or closed `opportunity`'s cycle above `config.min_profit_threshold`
+pub fn trace_opportunity(opportunity: &RecordedOpportunity, ticks: &[RecordedTick], config: &Config) -> OpportunityTrace {
+    let mut ticks = ticks.to_vec();
+    ticks.sort_by_key(|tick| tick.at_ms); // Stable: equal timestamps keep recording order
+    
+    let mut replay = ReplayGraph::new(config.clone());
+    let mut profit = None;
+    let mut profit_at_detection = None;
+    let mut changes = Vec::new();
+    let live = |profit: Option<f64>| profit.is_some_and(|p| p > config.min_profit_threshold);
+    
+    for tick in &ticks {
+        replay.apply(tick);
+        let after = replay.cycle_profit(&opportunity.path);
+        if tick.at_ms <= opportunity.at_ms {
+            profit_at_detection = after;
+        }
+        if after == profit {
+            trace!("{} {} {} bid {} ask {}: cycle unchanged", tick.at_ms, tick.exchange, tick.symbol, tick.bid, tick.ask);
+            continue;
+        }
+        debug!(
+            "{} {} {} bid {} ask {}: cycle {} -> {}",
+            tick.at_ms, tick.exchange, tick.symbol, tick.bid, tick.ask, format_profit(profit), format_profit(after)
+        );
+        
+        let kind = match (live(profit), live(after)) {
+            (false, true) => Some(EdgeChangeKind::Created),
+            (true, false) => Some(EdgeChangeKind::Destroyed),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            let found = replay.detect();
+            debug!("Detector found {} cycles after {:?} at {}", found.len(), kind, tick.at_ms);
+            changes.push(EdgeChange {
+                kind,
+                tick: tick.clone(),
+                profit_before: profit,
+                profit_after: after,
+                detected: found.iter().any(|opp| same_cycle(&opp.path, &opportunity.path)),
+            });
+        }
+        profit = after;
+    }
+    
+    OpportunityTrace {
+        opportunity: opportunity.clone(),
+        ticks_replayed: ticks.len(),
+        profit_at_detection,
+        changes,
+    }
+}
+
+fn format_profit(profit: Option<f64>) -> String {
+    profit.map_or_else(|| "no cycle".to_string(), |p| format!("{:+.4}%", p * 100.0))
+}
+
+impl fmt::Display for OpportunityTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let opp = &self.opportunity;
+        writeln!(f, "Opportunity #{}: {}", opp.id, opp.path)?;
+        writeln!(
+            f,
+            "Recorded {} at {}; replay of {} ticks gives {}",
+            format_profit(Some(opp.profit_percentage)),
+            opp.at_ms,
+            self.ticks_replayed,
+            format_profit(self.profit_at_detection)
+        )?;
+        if self.changes.is_empty() {
+            return write!(f, "No update in the window moved the cycle across the threshold");
+        }
+        for change in &self.changes {
+            let label = match change.kind {
+                EdgeChangeKind::Created => "created",
+                EdgeChangeKind::Destroyed => "destroyed",
+            };
+            write!(
+                f,
+                "\n{:>9} at {} by {} {} bid {} ask {}: {} -> {}{}",
+                label,
+                change.tick.at_ms,
+                change.tick.exchange,
+                change.tick.symbol,
+                change.tick.bid,
+                change.tick.ask,
+                format_profit(change.profit_before),
+                format_profit(change.profit_after),
+                if change.detected { "" } else { " (not reported by the detector)" }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn tick(at_ms: u64, symbol: &str, bid: f64, ask: f64) -> RecordedTick {
+        RecordedTick {
+            at_ms,
+            exchange: "binance".to_string(),
+            symbol: symbol.to_string(),
+            bid,
+            ask,
+            volume: 1.0,
+        }
+    }
+    
+    #[test]
+    fn test_trace_finds_creating_and_destroying_ticks() {
+        let config = Config { min_profit_threshold: 0.001, ..Config::default() };
+        let opportunity = RecordedOpportunity {
+            id: 7,
+            at_ms: 1_300,
+            path: "USDT_binance -> BTC_binance -> ETH_binance".to_string(),
+            profit_percentage: 0.0101,
+            exchanges: vec!["binance".to_string()],
+        };
+        let ticks = vec![
+            tick(1_000, "BTC/USDT", 50_000.0, 50_000.0),
+            tick(1_100, "ETH/USDT", 2_500.0, 2_500.0),
+            tick(1_200, "ETH/BTC", 0.05, 0.05),
+            tick(1_300, "ETH/BTC", 0.0495, 0.0495), // ETH briefly cheap in BTC
+            tick(1_400, "ETH/BTC", 0.0496, 0.0496),
+            tick(1_500, "ETH/BTC", 0.05, 0.05),
+        ];
+        
+        let trace = trace_opportunity(&opportunity, &ticks, &config);
+        assert_eq!(trace.ticks_replayed, 6);
+        assert_eq!(trace.changes.len(), 2, "{}", trace);
+        assert_eq!((trace.changes[0].kind, trace.changes[0].tick.at_ms), (EdgeChangeKind::Created, 1_300));
+        assert!(trace.changes[0].detected);
+        assert_eq!((trace.changes[1].kind, trace.changes[1].tick.at_ms), (EdgeChangeKind::Destroyed, 1_500));
+        assert!((trace.profit_at_detection.unwrap() - 0.0101).abs() < 1e-4);
+        assert!(trace.to_string().contains("created at 1300 by binance ETH/BTC"));
+    }
+}
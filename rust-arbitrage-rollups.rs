@@ -0,0 +1,241 @@
+// arbitrage/rollups.rs - Per-minute opportunity and uptime rollups for dashboard charts
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+
+use super::types::ArbitrageOpportunity;
+
+const MINUTE_MS: u64 = 60_000;
+pub const RETENTION_MINUTES: u64 = 24 * 60;
+const DEFAULT_LIMIT: usize = 60;
+
+#[derive(Default)]
+struct Minute {
+    opportunities: u64,
+    total_profit: f64,
+    max_profit: f64,
+    /// Closed connected intervals per exchange; an open one is added when read
+    connected_ms: BTreeMap<String, u64>,
+}
+
+/// An exchange's sockets; it's up while any shard is connected
+struct Link {
+    first_seen_ms: u64,
+    sockets: usize,
+    connected_since: Option<u64>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Keyed by the minute's start, unix ms
+    minutes: BTreeMap<u64, Minute>,
+    links: BTreeMap<String, Link>,
+}
+
+impl State {
+    fn add_connected(&mut self, exchange: &str, from_ms: u64, to_ms: u64) {
+        let mut start = from_ms;
+        while start < to_ms {
+            let minute = start - start % MINUTE_MS;
+            let end = to_ms.min(minute + MINUTE_MS);
+            *self.minutes.entry(minute).or_default().connected_ms.entry(exchange.to_string()).or_default() += end - start;
+            start = end;
+        }
+    }
+    
+    fn prune(&mut self, now_ms: u64) {
+        let cutoff = floor_minute(now_ms).saturating_sub((RETENTION_MINUTES - 1) * MINUTE_MS);
+        self.minutes = self.minutes.split_off(&cutoff);
+    }
+}
+
+/// `?since_ms=&limit=`: minutes from `since_ms` forward, or the latest
+/// `limit` minutes without it
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RollupQuery {
+    pub since_ms: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+/// One minute of activity; profits are fractions as in `ArbitrageOpportunity`
+#[derive(Debug, Clone, Serialize)]
+pub struct MinuteRollup {
+    pub minute_ms: u64,
+    pub opportunities: u64,
+    pub avg_profit: f64,
+    pub max_profit: f64,
+    /// Fraction of the minute each exchange was connected, for exchanges seen by then
+    pub uptime: BTreeMap<String, f64>,
+}
+
+/// Oldest minute first; `next_since_ms` fetches the following page
+#[derive(Debug, Clone, Serialize)]
+pub struct RollupPage {
+    pub rollups: Vec<MinuteRollup>,
+    pub next_since_ms: Option<u64>,
+}
+
+/// Opportunity counts and exchange uptime, aggregated per minute as they
+/// happen so charts don't need the raw history
+pub struct Rollups {
+    state: Mutex<State>,
+}
+
+impl Rollups {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(State::default()),
+        }
+    }
+    
+    pub fn record_opportunity(&self, opportunity: &ArbitrageOpportunity) {
+        self.record_opportunity_at(opportunity, unix_ms());
+    }
+    
+    fn record_opportunity_at(&self, opportunity: &ArbitrageOpportunity, at_ms: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.prune(at_ms);
+        let minute = state.minutes.entry(floor_minute(at_ms)).or_default();
+        minute.opportunities += 1;
+        minute.total_profit += opportunity.profit_percentage;
+        minute.max_profit = minute.max_profit.max(opportunity.profit_percentage);
+    }
+    
+    pub fn record_connection(&self, exchange: &str, connected: bool) {
+        self.record_connection_at(exchange, connected, unix_ms());
+    }
+    
+    fn record_connection_at(&self, exchange: &str, connected: bool, at_ms: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.prune(at_ms);
+        let link = state.links.entry(exchange.to_string()).or_insert(Link {
+            first_seen_ms: at_ms,
+            sockets: 0,
+            connected_since: None,
+        });
+        if connected {
+            link.sockets += 1;
+            link.connected_since.get_or_insert(at_ms);
+            return;
+        }
+        link.sockets = link.sockets.saturating_sub(1);
+        if link.sockets > 0 {
+            return;
+        }
+        if let Some(since) = link.connected_since.take() {
+            state.add_connected(exchange, since, at_ms);
+        }
+    }
+    
+    pub fn page(&self, query: &RollupQuery) -> RollupPage {
+        self.page_at(query, unix_ms())
+    }
+    
+    fn page_at(&self, query: &RollupQuery, now_ms: u64) -> RollupPage {
+        let mut state = self.state.lock().unwrap();
+        state.prune(now_ms);
+        
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, RETENTION_MINUTES as usize) as u64;
+        let current = floor_minute(now_ms);
+        let oldest = current.saturating_sub((RETENTION_MINUTES - 1) * MINUTE_MS);
+        let first = match query.since_ms {
+            Some(since) => floor_minute(since).max(oldest),
+            None => current.saturating_sub((limit - 1) * MINUTE_MS).max(oldest),
+        };
+        let last = current.min(first.saturating_add((limit - 1) * MINUTE_MS));
+        
+        let mut rollups = Vec::new();
+        let mut minute_ms = first;
+        while minute_ms <= last {
+            rollups.push(Self::rollup(&state, minute_ms, now_ms));
+            minute_ms += MINUTE_MS;
+        }
+        RollupPage {
+            rollups,
+            next_since_ms: (minute_ms <= current).then_some(minute_ms),
+        }
+    }
+    
+    fn rollup(state: &State, minute_ms: u64, now_ms: u64) -> MinuteRollup {
+        let minute = state.minutes.get(&minute_ms);
+        let end = now_ms.min(minute_ms + MINUTE_MS);
+        let uptime = state
+            .links
+            .iter()
+            .filter_map(|(exchange, link)| {
+                let observed = end.saturating_sub(minute_ms.max(link.first_seen_ms));
+                if observed == 0 {
+                    return None;
+                }
+                let closed = minute.and_then(|m| m.connected_ms.get(exchange)).copied().unwrap_or_default();
+                let open = link.connected_since.map_or(0, |since| end.saturating_sub(minute_ms.max(since)));
+                Some((exchange.clone(), ((closed + open) as f64 / observed as f64).min(1.0)))
+            })
+            .collect();
+        
+        let opportunities = minute.map_or(0, |m| m.opportunities);
+        MinuteRollup {
+            minute_ms,
+            opportunities,
+            avg_profit: minute.filter(|_| opportunities > 0).map_or(0.0, |m| m.total_profit / opportunities as f64),
+            max_profit: minute.map_or(0.0, |m| m.max_profit),
+            uptime,
+        }
+    }
+}
+
+fn floor_minute(ms: u64) -> u64 {
+    ms - ms % MINUTE_MS
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const PATH: &str = "BTC_binance -> BTC_kraken -> BTC_binance";
+    
+    #[test]
+    fn test_rolls_up_minutes_and_pages_forward() {
+        let rollups = Rollups::new();
+        let t0 = 1_000 * MINUTE_MS;
+        
+        rollups.record_connection_at("binance", true, t0);
+        rollups.record_connection_at("kraken", true, t0);
+        rollups.record_opportunity_at(&ArbitrageOpportunity::sample(PATH, 0.002), t0 + 1_000);
+        rollups.record_opportunity_at(&ArbitrageOpportunity::sample(PATH, 0.004), t0 + 2_000);
+        // Kraken drops for half of the second minute
+        rollups.record_connection_at("kraken", false, t0 + MINUTE_MS);
+        rollups.record_connection_at("kraken", true, t0 + MINUTE_MS + 30_000);
+        rollups.record_opportunity_at(&ArbitrageOpportunity::sample(PATH, 0.001), t0 + 2 * MINUTE_MS);
+        
+        let now = t0 + 2 * MINUTE_MS + 30_000;
+        let page = rollups.page_at(&RollupQuery { since_ms: Some(t0), limit: Some(2) }, now);
+        assert_eq!(page.rollups.len(), 2);
+        assert_eq!(page.next_since_ms, Some(t0 + 2 * MINUTE_MS));
+        
+        let first = &page.rollups[0];
+        assert_eq!((first.minute_ms, first.opportunities), (t0, 2));
+        assert!((first.avg_profit - 0.003).abs() < 1e-12);
+        assert_eq!(first.max_profit, 0.004);
+        assert_eq!(first.uptime["kraken"], 1.0);
+        let second = &page.rollups[1];
+        assert_eq!(second.opportunities, 0);
+        assert_eq!((second.uptime["binance"], second.uptime["kraken"]), (1.0, 0.5));
+        
+        // The last page is the minute in progress, measured up to now
+        let page = rollups.page_at(&RollupQuery { since_ms: page.next_since_ms, limit: Some(2) }, now);
+        assert_eq!(page.rollups.len(), 1);
+        assert_eq!(page.rollups[0].opportunities, 1);
+        assert_eq!(page.rollups[0].uptime["kraken"], 1.0);
+        assert_eq!(page.next_since_ms, None);
+        
+        // Without a cursor it's the latest minutes
+        let latest = rollups.page_at(&RollupQuery::default(), now);
+        assert_eq!(latest.rollups.last().unwrap().minute_ms, t0 + 2 * MINUTE_MS);
+    }
+}
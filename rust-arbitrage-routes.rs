@@ -0,0 +1,176 @@
+// arbitrage/routes.rs - Best conversion routes between any two currencies (Floyd-Warshall)
+use std::collections::HashMap;
+use std::time::Instant;
+use serde::Serialize;
+
+/// Cheapest known way to turn one currency/exchange node into another
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConversionRoute {
+    pub from: String,
+    pub to: String,
+    pub path: Vec<String>,
+    pub rate: f64, // Units of `to` received per unit of `from`
+    pub hops: usize,
+}
+
+/// All-pairs shortest paths over the `-ln(rate)` graph, rebuilt periodically.
+///
+/// Pairs whose best walk runs through an arbitrage cycle have no well-defined
+/// route; those are reported as `None` rather than as an unbounded rate.
+#[derive(Default)]
+pub struct RouteTable {
+    nodes: Vec<String>,
+    index: HashMap<String, usize>,
+    dist: Vec<Vec<f64>>,
+    next: Vec<Vec<Option<usize>>>,
+    weights: Vec<Vec<f64>>,
+    computed_at: Option<Instant>,
+}
+
+impl RouteTable {
+    pub fn compute(graph: &[Vec<f64>], currencies: &HashMap<String, usize>) -> Self {
+        let n = currencies.len().min(graph.len());
+        
+        let mut nodes = vec![String::new(); n];
+        let mut index = HashMap::with_capacity(n);
+        for (name, &i) in currencies {
+            if i < n {
+                nodes[i] = name.clone();
+                index.insert(name.clone(), i);
+            }
+        }
+        
+        let weights: Vec<Vec<f64>> = graph.iter().take(n).map(|row| row[..n].to_vec()).collect();
+        let mut dist = weights.clone();
+        let mut next = vec![vec![None; n]; n];
+        for u in 0..n {
+            dist[u][u] = dist[u][u].min(0.0);
+            for v in 0..n {
+                if u == v || dist[u][v].is_finite() {
+                    next[u][v] = Some(v);
+                }
+            }
+        }
+        
+        for k in 0..n {
+            for i in 0..n {
+                if !dist[i][k].is_finite() {
+                    continue;
+                }
+                for j in 0..n {
+                    let through = dist[i][k] + dist[k][j];
+                    if through < dist[i][j] {
+                        dist[i][j] = through;
+                        next[i][j] = next[i][k];
+                    }
+                }
+            }
+        }
+        
+        Self {
+            nodes,
+            index,
+            dist,
+            next,
+            weights,
+            computed_at: Some(Instant::now()),
+        }
+    }
+    
+    pub fn best_route(&self, from: &str, to: &str) -> Option<ConversionRoute> {
+        let &source = self.index.get(from)?;
+        let &target = self.index.get(to)?;
+        if source == target || !self.dist[source][target].is_finite() {
+            return None;
+        }
+        
+        let mut path = vec![source];
+        let mut current = source;
+        while current != target {
+            current = self.next[current][target]?;
+            if path.contains(&current) {
+                return None; // Walk loops through an arbitrage cycle
+            }
+            path.push(current);
+        }
+        
+        // Recompute from the raw edges so the reported rate matches the hops
+        let log_rate: f64 = path.windows(2).map(|hop| self.weights[hop[0]][hop[1]]).sum();
+        let rate = (-log_rate).exp();
+        if !rate.is_finite() {
+            return None;
+        }
+        
+        Some(ConversionRoute {
+            from: from.to_string(),
+            to: to.to_string(),
+            hops: path.len() - 1,
+            path: path.into_iter().map(|i| self.nodes[i].clone()).collect(),
+            rate,
+        })
+    }
+    
+    /// Return of the best cycle that starts with the edge `from -> to` (graph
+    /// indices): the edge, then the cheapest way back. `None` without one
+    pub fn best_cycle_through(&self, from: usize, to: usize) -> Option<f64> {
+        let edge = *self.weights.get(from)?.get(to)?;
+        let back = *self.dist.get(to)?.get(from)?;
+        let total = edge + back;
+        total.is_finite().then(|| (-total).exp() - 1.0)
+    }
+    
+    pub fn computed_at(&self) -> Option<Instant> {
+        self.computed_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn currencies(names: &[&str]) -> HashMap<String, usize> {
+        names.iter().enumerate().map(|(i, name)| (name.to_string(), i)).collect()
+    }
+    
+    #[test]
+    fn test_indirect_route_beats_direct_quote() {
+        let inf = f64::INFINITY;
+        // BTC -> USDC direct at 49000, via USDT at 50000 * 0.999
+        let graph = vec![
+            vec![0.0, -f64::ln(50000.0), -f64::ln(49000.0)],
+            vec![inf, 0.0, -f64::ln(0.999)],
+            vec![inf, inf, 0.0],
+        ];
+        let table = RouteTable::compute(&graph, &currencies(&["BTC_kraken", "USDT_kraken", "USDC_coinbase"]));
+        
+        let route = table.best_route("BTC_kraken", "USDC_coinbase").unwrap();
+        assert_eq!(route.path, vec!["BTC_kraken", "USDT_kraken", "USDC_coinbase"]);
+        assert_eq!(route.hops, 2);
+        assert!((route.rate - 49950.0).abs() < 1e-3);
+    }
+    
+    #[test]
+    fn test_unreachable_and_unknown_pairs() {
+        let inf = f64::INFINITY;
+        let graph = vec![vec![0.0, inf], vec![inf, 0.0]];
+        let table = RouteTable::compute(&graph, &currencies(&["A_x", "B_x"]));
+        
+        assert!(table.best_route("A_x", "B_x").is_none());
+        assert!(table.best_route("A_x", "C_x").is_none());
+        assert!(RouteTable::default().best_route("A_x", "B_x").is_none());
+    }
+    
+    #[test]
+    fn test_route_through_arbitrage_cycle_is_omitted() {
+        let inf = f64::INFINITY;
+        // 0 <-> 1 is a profitable loop feeding into 2
+        let graph = vec![
+            vec![0.0, -f64::ln(1.1), inf],
+            vec![-f64::ln(1.1), 0.0, -f64::ln(1.0)],
+            vec![inf, inf, 0.0],
+        ];
+        let table = RouteTable::compute(&graph, &currencies(&["A_x", "B_x", "C_x"]));
+        
+        assert!(table.best_route("A_x", "C_x").is_none());
+    }
+}
@@ -0,0 +1,113 @@
+// arbitrage/snapshot.rs - Epoch-stamped immutable views of the price graph
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Copy of the active part of the price graph as of one write epoch.
+///
+/// The market data processor publishes a new snapshot only after whole
+/// messages (single ticks or batches) are applied, so readers never see one
+/// leg of a book updated and the other stale.
+#[derive(Debug, Default)]
+pub struct GraphSnapshot {
+    pub epoch: u64,
+    pub weights: Vec<Vec<f64>>,
+    pub currencies: HashMap<String, usize>,
+}
+
+impl GraphSnapshot {
+    pub fn new(epoch: u64, weights: Vec<Vec<f64>>, currencies: HashMap<String, usize>) -> Self {
+        Self {
+            epoch,
+            weights,
+            currencies,
+        }
+    }
+    
+    /// Copy only the `n x n` block of currencies in use, not the preallocated matrix
+    pub fn capture(epoch: u64, graph: &[Vec<f64>], currencies: &HashMap<String, usize>) -> Self {
+        let n = currencies.len().min(graph.len());
+        let weights = graph.iter().take(n).map(|row| row[..n].to_vec()).collect();
+        
+        Self::new(epoch, weights, currencies.clone())
+    }
+    
+    pub fn len(&self) -> usize {
+        self.currencies.len().min(self.weights.len())
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Latest published snapshot. Readers clone the `Arc` and drop the lock
+/// immediately, so a slow detection pass never blocks the writer.
+pub struct SnapshotCell {
+    current: RwLock<Arc<GraphSnapshot>>,
+    epoch: AtomicU64,
+}
+
+impl SnapshotCell {
+    pub fn new() -> Self {
+        Self {
+            current: RwLock::new(Arc::new(GraphSnapshot::default())),
+            epoch: AtomicU64::new(0),
+        }
+    }
+    
+    pub fn load(&self) -> Arc<GraphSnapshot> {
+        Arc::clone(&self.current.read().unwrap_or_else(|poisoned| poisoned.into_inner()))
+    }
+    
+    /// Capture and publish the next epoch, returning its number
+    pub fn publish(&self, graph: &[Vec<f64>], currencies: &HashMap<String, usize>) -> u64 {
+        let epoch = self.epoch.fetch_add(1, Ordering::SeqCst) + 1;
+        let snapshot = Arc::new(GraphSnapshot::capture(epoch, graph, currencies));
+        
+        *self.current.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = snapshot;
+        epoch
+    }
+    
+    pub fn epoch(&self) -> u64 {
+        self.epoch.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn currencies(names: &[&str]) -> HashMap<String, usize> {
+        names.iter().enumerate().map(|(i, name)| (name.to_string(), i)).collect()
+    }
+    
+    #[test]
+    fn test_capture_copies_active_block_only() {
+        let graph = vec![vec![1.0; 8]; 8];
+        let snapshot = GraphSnapshot::capture(3, &graph, &currencies(&["A_x", "B_x"]));
+        
+        assert_eq!(snapshot.epoch, 3);
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot.weights, vec![vec![1.0, 1.0], vec![1.0, 1.0]]);
+    }
+    
+    #[test]
+    fn test_loaded_snapshot_is_isolated_from_later_writes() {
+        let cell = SnapshotCell::new();
+        assert!(cell.load().is_empty());
+        
+        let mut graph = vec![vec![0.5; 2]; 2];
+        let names = currencies(&["A_x", "B_x"]);
+        assert_eq!(cell.publish(&graph, &names), 1);
+        let held = cell.load();
+        
+        graph[0][1] = 0.25;
+        assert_eq!(cell.publish(&graph, &names), 2);
+        
+        assert_eq!(held.epoch, 1);
+        assert_eq!(held.weights[0][1], 0.5);
+        assert_eq!(cell.load().weights[0][1], 0.25);
+        assert_eq!(cell.epoch(), 2);
+    }
+}
@@ -0,0 +1,371 @@
+// arbitrage/statarb.rs - Rolling spreads between correlated instruments, z-scored for mean-reversion signals
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use super::types::OpportunityKind;
+use super::quotes::QuoteBook;
+use crate::execution::Side;
+
+/// Signals kept for the API after they fire
+const RECENT_SIGNALS: usize = 100;
+
+pub type SpreadSignalCallback = Box<dyn Fn(SpreadSignal) + Send + Sync>;
+
+/// One instrument in a spread, weighted on its log mid
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpreadLeg {
+    pub exchange: String,
+    pub symbol: String,
+    /// 1 and -1 for a plain ratio between two legs, a hedge ratio otherwise
+    pub weight: f64,
+}
+
+/// A named basket whose weighted log prices should hold steady, e.g. ETH/BTC
+/// against ETH/USDT over BTC/USDT on the same venue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpreadDefinition {
+    pub name: String,
+    pub legs: Vec<SpreadLeg>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatArbConfig {
+    pub enabled: bool,
+    /// Also track each symbol between every two venues quoting it
+    pub cross_exchange: bool,
+    pub spreads: Vec<SpreadDefinition>,
+    #[serde(with = "humantime_serde")]
+    pub sample_interval: Duration,
+    /// Samples the rolling mean and deviation are taken over
+    pub window: usize,
+    /// Samples a spread needs before its z-score means anything
+    pub min_samples: usize,
+    /// How many deviations from the mean a spread has to stray to open a signal
+    pub entry_z: f64,
+    /// An open signal closes once the spread is back within this many
+    pub exit_z: f64,
+}
+
+impl Default for StatArbConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cross_exchange: true,
+            spreads: Vec::new(),
+            sample_interval: Duration::from_secs(1),
+            window: 300,
+            min_samples: 60,
+            entry_z: 2.0,
+            exit_z: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalAction {
+    Enter, // The spread strayed past `entry_z`
+    Exit,  // It came back within `exit_z`, or crossed its mean
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpreadSide {
+    Long,  // Spread below its mean: buy the positive-weight legs, sell the rest
+    Short, // Spread above its mean: the reverse
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SignalLeg {
+    pub exchange: String,
+    pub symbol: String,
+    pub side: Side,
+}
+
+/// A spread opening or closing a mean-reversion position; `legs` are the
+/// trades that do it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpreadSignal {
+    pub kind: OpportunityKind,
+    pub spread: String,
+    pub action: SignalAction,
+    pub side: SpreadSide,
+    pub z_score: f64,
+    /// Spread minus its rolling mean, roughly in basis points of price
+    pub deviation_bps: f64,
+    pub legs: Vec<SignalLeg>,
+    pub at_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpreadStatus {
+    pub spread: String,
+    pub samples: usize,
+    /// Latest weighted sum of log mids
+    pub value: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+    /// None until `min_samples` are in
+    pub z_score: Option<f64>,
+    pub open: Option<SpreadSide>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatArbReport {
+    /// Furthest from the mean first
+    pub spreads: Vec<SpreadStatus>,
+    /// Newest first
+    pub signals: Vec<SpreadSignal>,
+}
+
+#[derive(Default)]
+struct Series {
+    legs: Vec<SpreadLeg>,
+    samples: VecDeque<f64>,
+    value: f64,
+    z_score: Option<f64>,
+    open: Option<SpreadSide>,
+}
+
+impl Series {
+    /// Mean and population deviation of the window, once it's long enough
+    /// and not flat
+    fn moments(&self, min_samples: usize) -> Option<(f64, f64)> {
+        let n = self.samples.len();
+        if n < min_samples.max(2) {
+            return None;
+        }
+        let mean = self.samples.iter().sum::<f64>() / n as f64;
+        let variance = self.samples.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / n as f64;
+        let std_dev = variance.sqrt();
+        (std_dev > 1e-12).then_some((mean, std_dev))
+    }
+}
+
+/// Samples configured and cross-exchange spreads off the quote book and
+/// signals when one strays from its rolling mean and when it returns
+pub struct StatArbMonitor {
+    config: RwLock<StatArbConfig>,
+    series: Mutex<HashMap<String, Series>>,
+    recent: Mutex<VecDeque<SpreadSignal>>,
+}
+
+impl StatArbMonitor {
+    pub fn new(config: StatArbConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            series: Mutex::new(HashMap::new()),
+            recent: Mutex::new(VecDeque::new()),
+        }
+    }
+    
+    /// Windows restart under the new config; open signals are dropped with them
+    pub fn set_config(&self, config: StatArbConfig) {
+        *self.config.write().unwrap() = config;
+        self.series.lock().unwrap().clear();
+    }
+    
+    pub fn sample_interval(&self) -> Duration {
+        self.config.read().unwrap().sample_interval
+    }
+    
+    /// Configured spreads, then every venue pair per symbol if `cross_exchange`
+    fn definitions(config: &StatArbConfig, book: &QuoteBook) -> Vec<SpreadDefinition> {
+        let mut definitions = config.spreads.clone();
+        if !config.cross_exchange {
+            return definitions;
+        }
+        let exchanges = book.exchanges();
+        for symbol in book.symbols() {
+            let venues: Vec<&String> = exchanges.iter().filter(|exchange| book.top(exchange, &symbol).is_some()).collect();
+            for (i, a) in venues.iter().enumerate() {
+                for b in &venues[i + 1..] {
+                    let leg = |exchange: &str, weight: f64| SpreadLeg { exchange: exchange.to_string(), symbol: symbol.clone(), weight };
+                    definitions.push(SpreadDefinition {
+                        name: format!("{} {}/{}", symbol, a, b),
+                        legs: vec![leg(a, 1.0), leg(b, -1.0)],
+                    });
+                }
+            }
+        }
+        definitions
+    }
+    
+    fn log_mid(book: &QuoteBook, leg: &SpreadLeg) -> Option<f64> {
+        let (bid, ask) = book.top(&leg.exchange, &leg.symbol).filter(|&(bid, ask)| bid > 0.0 && ask >= bid)?;
+        Some(((bid + ask) / 2.0).ln())
+    }
+    
+    /// Trades that open (or close) `side` on a spread
+    fn trades(legs: &[SpreadLeg], side: SpreadSide, action: SignalAction) -> Vec<SignalLeg> {
+        let buy_positive = (side == SpreadSide::Long) == (action == SignalAction::Enter);
+        legs.iter()
+            .map(|leg| SignalLeg {
+                exchange: leg.exchange.clone(),
+                symbol: leg.symbol.clone(),
+                side: if (leg.weight > 0.0) == buy_positive { Side::Buy } else { Side::Sell },
+            })
+            .collect()
+    }
+    
+    /// Add a sample to every spread whose legs are all quoted, and return the
+    /// signals it opened or closed
+    pub fn sample(&self, book: &QuoteBook, now_ms: u64) -> Vec<SpreadSignal> {
+        let config = self.config.read().unwrap().clone();
+        if !config.enabled {
+            return Vec::new();
+        }
+        let definitions = Self::definitions(&config, book);
+        let mut series = self.series.lock().unwrap();
+        // Spreads that lost a venue or were removed start over if they return
+        let names: HashSet<&str> = definitions.iter().map(|definition| definition.name.as_str()).collect();
+        series.retain(|name, _| names.contains(name.as_str()));
+        
+        let mut signals = Vec::new();
+        for definition in &definitions {
+            let Some(value) = definition.legs.iter().map(|leg| Some(leg.weight * Self::log_mid(book, leg)?)).sum::<Option<f64>>() else {
+                continue;
+            };
+            let state = series.entry(definition.name.clone()).or_default();
+            if state.legs != definition.legs {
+                *state = Series { legs: definition.legs.clone(), ..Series::default() };
+            }
+            // Scored against the window before this sample, so a jump isn't
+            // diluted by itself
+            let moments = state.moments(config.min_samples);
+            if state.samples.len() >= config.window.max(1) {
+                state.samples.pop_front();
+            }
+            state.samples.push_back(value);
+            state.value = value;
+            let Some((mean, std_dev)) = moments else {
+                state.z_score = None;
+                continue;
+            };
+            let z_score = (value - mean) / std_dev;
+            state.z_score = Some(z_score);
+            
+            let transition = match state.open {
+                None if z_score.abs() >= config.entry_z => {
+                    let side = if z_score > 0.0 { SpreadSide::Short } else { SpreadSide::Long };
+                    state.open = Some(side);
+                    Some((SignalAction::Enter, side))
+                }
+                Some(side) if z_score.abs() <= config.exit_z || (z_score > 0.0) == (side == SpreadSide::Long) => {
+                    state.open = None;
+                    Some((SignalAction::Exit, side))
+                }
+                _ => None,
+            };
+            if let Some((action, side)) = transition {
+                signals.push(SpreadSignal {
+                    kind: OpportunityKind::StatArb,
+                    spread: definition.name.clone(),
+                    action,
+                    side,
+                    z_score,
+                    deviation_bps: (value - mean) * 10_000.0,
+                    legs: Self::trades(&definition.legs, side, action),
+                    at_ms: now_ms,
+                });
+            }
+        }
+        drop(series);
+        
+        let mut recent = self.recent.lock().unwrap();
+        for signal in &signals {
+            if recent.len() == RECENT_SIGNALS {
+                recent.pop_back();
+            }
+            recent.push_front(signal.clone());
+        }
+        signals
+    }
+    
+    pub fn report(&self) -> StatArbReport {
+        let min_samples = self.config.read().unwrap().min_samples;
+        let mut spreads: Vec<SpreadStatus> = self
+            .series
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, state)| {
+                let (mean, std_dev) = state.moments(min_samples).unwrap_or((state.value, 0.0));
+                SpreadStatus {
+                    spread: name.clone(),
+                    samples: state.samples.len(),
+                    value: state.value,
+                    mean,
+                    std_dev,
+                    z_score: state.z_score,
+                    open: state.open,
+                }
+            })
+            .collect();
+        let distance = |status: &SpreadStatus| status.z_score.map_or(-1.0, f64::abs);
+        spreads.sort_by(|a, b| distance(b).total_cmp(&distance(a)).then_with(|| a.spread.cmp(&b.spread)));
+        StatArbReport {
+            spreads,
+            signals: self.recent.lock().unwrap().iter().cloned().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use crate::arbitrage::types::MarketTick;
+    
+    fn quote(book: &QuoteBook, exchange: &str, symbol: &str, mid: f64) {
+        book.update(&MarketTick {
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            bid: mid - 0.001,
+            ask: mid + 0.001,
+            last_price: mid,
+            volume: 1.0,
+            timestamp: Instant::now(),
+            sequence: 0,
+        });
+    }
+    
+    #[test]
+    fn test_cross_exchange_spread_signals_on_divergence_and_return() {
+        let monitor = StatArbMonitor::new(StatArbConfig::default());
+        let book = QuoteBook::new();
+        quote(&book, "kraken", "BTC/USDT", 100.0);
+        
+        // Binance jitters a basis point around kraken while the window fills
+        for step in 0..60 {
+            quote(&book, "binance", "BTC/USDT", if step % 2 == 0 { 100.0 } else { 100.01 });
+            assert!(monitor.sample(&book, step).is_empty());
+        }
+        assert_eq!(monitor.report().spreads[0].samples, 60);
+        
+        // Kraken runs ten basis points rich: buy binance, sell kraken
+        quote(&book, "binance", "BTC/USDT", 100.0);
+        quote(&book, "kraken", "BTC/USDT", 100.1);
+        let signals = monitor.sample(&book, 60);
+        assert_eq!(signals.len(), 1);
+        let entry = &signals[0];
+        assert_eq!((entry.spread.as_str(), entry.action, entry.side), ("BTC/USDT binance/kraken", SignalAction::Enter, SpreadSide::Long));
+        assert!(entry.z_score < -10.0 && entry.deviation_bps < -9.0);
+        assert_eq!(entry.legs.iter().map(|leg| (leg.exchange.as_str(), leg.side)).collect::<Vec<_>>(), vec![("binance", Side::Buy), ("kraken", Side::Sell)]);
+        assert_eq!(serde_json::to_value(entry).unwrap()["kind"], "stat_arb");
+        
+        // Staying out doesn't signal again; coming back unwinds the legs
+        assert!(monitor.sample(&book, 61).is_empty());
+        quote(&book, "kraken", "BTC/USDT", 100.005);
+        let exit = monitor.sample(&book, 62);
+        assert_eq!(exit.len(), 1);
+        assert_eq!((exit[0].action, exit[0].side), (SignalAction::Exit, SpreadSide::Long));
+        assert_eq!(exit[0].legs[0].side, Side::Sell);
+        
+        let report = monitor.report();
+        assert_eq!(report.spreads[0].open, None);
+        assert_eq!(report.signals.iter().map(|signal| signal.action).collect::<Vec<_>>(), vec![SignalAction::Exit, SignalAction::Enter]);
+    }
+}
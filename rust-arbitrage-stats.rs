@@ -0,0 +1,138 @@
+// arbitrage/stats.rs - Contention-free performance counters
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+use crossbeam::utils::CachePadded;
+
+use super::types::PerformanceStats;
+
+const STAT_SHARDS: usize = 16;
+
+#[derive(Default)]
+struct Shard {
+    messages: AtomicU64,
+    latency_sum_ns: AtomicU64,
+}
+
+/// Lock-free replacement for `Mutex<PerformanceStats>`.
+///
+/// Hot-path counters are sharded per thread (cache-line padded) and only
+/// summed when a snapshot is requested, so recording a tick is a couple of
+/// relaxed atomic adds and never blocks an async task.
+pub struct StatsCollector {
+    shards: Box<[CachePadded<Shard>]>,
+    opportunities_found: AtomicU64,
+    detection_latency_ns: AtomicU64,
+}
+
+impl StatsCollector {
+    pub fn new() -> Self {
+        Self {
+            shards: (0..STAT_SHARDS).map(|_| CachePadded::new(Shard::default())).collect(),
+            opportunities_found: AtomicU64::new(0),
+            detection_latency_ns: AtomicU64::new(0),
+        }
+    }
+    
+    /// Record one processed market message and its ingest latency
+    pub fn record_message(&self, latency: Duration) {
+        let shard = &self.shards[Self::shard_index()];
+        shard.messages.fetch_add(1, Ordering::Relaxed);
+        shard.latency_sum_ns.fetch_add(latency.as_nanos() as u64, Ordering::Relaxed);
+    }
+    
+    pub fn record_opportunity(&self) {
+        self.opportunities_found.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    /// Latency of the most recent detection pass
+    pub fn record_detection(&self, latency: Duration) {
+        self.detection_latency_ns.store(latency.as_nanos() as u64, Ordering::Relaxed);
+    }
+    
+    /// Aggregate all shards into a `PerformanceStats` value
+    pub fn snapshot(&self) -> PerformanceStats {
+        let (messages, latency_sum_ns) = self.shards.iter().fold((0u64, 0u64), |(m, l), shard| {
+            (
+                m + shard.messages.load(Ordering::Relaxed),
+                l + shard.latency_sum_ns.load(Ordering::Relaxed),
+            )
+        });
+        
+        let avg_latency_us = if messages > 0 {
+            latency_sum_ns as f64 / messages as f64 / 1000.0
+        } else {
+            0.0
+        };
+        
+        PerformanceStats {
+            messages_processed: messages,
+            opportunities_found: self.opportunities_found.load(Ordering::Relaxed),
+            avg_latency_us,
+            detection_latency_us: self.detection_latency_ns.load(Ordering::Relaxed) as f64 / 1000.0,
+            ..PerformanceStats::default()
+        }
+    }
+    
+    fn shard_index() -> usize {
+        static NEXT_SHARD: AtomicUsize = AtomicUsize::new(0);
+        thread_local! {
+            static SHARD: usize = NEXT_SHARD.fetch_add(1, Ordering::Relaxed) % STAT_SHARDS;
+        }
+        SHARD.with(|shard| *shard)
+    }
+}
+
+impl Default for StatsCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    
+    #[test]
+    fn test_counts_aggregate_across_threads() {
+        let stats = Arc::new(StatsCollector::new());
+        
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let stats = Arc::clone(&stats);
+                std::thread::spawn(move || {
+                    for _ in 0..1000 {
+                        stats.record_message(Duration::from_micros(2));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.messages_processed, 8000);
+        assert!((snapshot.avg_latency_us - 2.0).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_opportunity_and_detection_latency() {
+        let stats = StatsCollector::new();
+        stats.record_opportunity();
+        stats.record_opportunity();
+        stats.record_detection(Duration::from_micros(750));
+        stats.record_detection(Duration::from_micros(300));
+        
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.opportunities_found, 2);
+        assert_eq!(snapshot.detection_latency_us, 300.0);
+    }
+    
+    #[test]
+    fn test_empty_snapshot_has_zero_latency() {
+        let snapshot = StatsCollector::new().snapshot();
+        assert_eq!(snapshot.messages_processed, 0);
+        assert_eq!(snapshot.avg_latency_us, 0.0);
+    }
+}
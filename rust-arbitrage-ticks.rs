@@ -0,0 +1,139 @@
+// arbitrage/ticks.rs - Validated ticks fanned out to external subscribers
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Deserializer, Serialize};
+use tokio::sync::broadcast;
+
+use super::types::MarketTick;
+
+/// Ticks a slow subscriber can fall behind by before it starts missing them
+const TICK_FEED_CAPACITY: usize = 4096;
+
+/// A tick as the engine applied it to the price graph
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PublishedTick {
+    pub exchange: String,
+    pub symbol: String,
+    pub bid: f64,
+    pub ask: f64,
+    pub volume: f64,
+    pub sequence: u64,
+    /// Wall-clock time the engine applied it
+    pub timestamp_ms: u64,
+}
+
+/// Which ticks a subscriber wants; an empty list matches everything. Lists
+/// are comma-separated in a query string (`?exchanges=binance,kraken`)
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct TickFilter {
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub exchanges: Vec<String>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub symbols: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    Joined(String),
+    List(Vec<String>),
+}
+
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::Joined(joined) => joined
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(String::from)
+            .collect(),
+        OneOrMany::List(list) => list,
+    })
+}
+
+impl TickFilter {
+    pub fn matches(&self, tick: &PublishedTick) -> bool {
+        let listed = |list: &[String], value: &str| list.is_empty() || list.iter().any(|item| item.eq_ignore_ascii_case(value));
+        listed(&self.exchanges, &tick.exchange) && listed(&self.symbols, &tick.symbol)
+    }
+}
+
+/// Broadcast of every tick that made it into the price graph
+pub struct TickFeed {
+    sender: broadcast::Sender<PublishedTick>,
+}
+
+impl TickFeed {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(TICK_FEED_CAPACITY);
+        Self { sender }
+    }
+    
+    /// Hand `tick` to every subscriber; free when nobody is listening
+    pub fn publish(&self, tick: &MarketTick) {
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+        let timestamp_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+        // Only fails when the last subscriber left since the check above
+        let _ = self.sender.send(PublishedTick {
+            exchange: tick.exchange.clone(),
+            symbol: tick.symbol.clone(),
+            bid: tick.bid,
+            ask: tick.ask,
+            volume: tick.volume,
+            sequence: tick.sequence,
+            timestamp_ms,
+        });
+    }
+    
+    pub fn subscribe(&self) -> broadcast::Receiver<PublishedTick> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    
+    fn tick(exchange: &str, symbol: &str) -> MarketTick {
+        MarketTick {
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            bid: 100.0,
+            ask: 100.1,
+            last_price: 100.05,
+            volume: 1.0,
+            timestamp: Instant::now(),
+            sequence: 7,
+        }
+    }
+    
+    #[test]
+    fn test_subscribers_get_the_ticks_their_filter_selects() {
+        let feed = TickFeed::new();
+        // Nobody listening yet: dropped rather than buffered
+        feed.publish(&tick("binance", "BTC/USDT"));
+        
+        let mut ticks = feed.subscribe();
+        feed.publish(&tick("binance", "BTC/USDT"));
+        feed.publish(&tick("kraken", "ETH/USDT"));
+        let first = ticks.try_recv().unwrap();
+        assert_eq!((first.exchange.as_str(), first.sequence), ("binance", 7));
+        assert!(ticks.try_recv().is_ok());
+        assert!(ticks.try_recv().is_err());
+        
+        let filter: TickFilter = serde_urlencoded::from_str("exchanges=Binance,%20kraken&symbols=BTC/USDT").unwrap();
+        assert_eq!(filter.exchanges, vec!["Binance", "kraken"]);
+        assert!(filter.matches(&first));
+        let mut other = first.clone();
+        other.symbol = "ETH/USDT".to_string();
+        assert!(!filter.matches(&other));
+        
+        // The same filter sent over the socket as JSON lists
+        let updated: TickFilter = serde_json::from_str(r#"{"symbols": ["ETH/USDT"]}"#).unwrap();
+        assert!(updated.exchanges.is_empty());
+        assert!(updated.matches(&other) && !updated.matches(&first));
+        assert!(TickFilter::default().matches(&first));
+    }
+}
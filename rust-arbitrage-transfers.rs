@@ -0,0 +1,200 @@
+// arbitrage/transfers.rs - What moving funds between exchanges costs and how long it takes
+use std::collections::HashMap;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::markets::{self, AssetAlias};
+
+/// Moving an asset over one chain; fees in the base currency, like balances
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainTransfer {
+    pub chain: String,
+    /// Charged by the sending exchange
+    pub withdrawal_fee: f64,
+    /// Gas or miner fee on top, where the exchange passes it on
+    #[serde(default)]
+    pub network_fee: f64,
+    /// Typical time until the receiving exchange credits the deposit
+    #[serde(with = "humantime_serde")]
+    pub confirmation_time: Duration,
+}
+
+impl ChainTransfer {
+    pub fn fee(&self) -> f64 {
+        self.withdrawal_fee + self.network_fee
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransferConfig {
+    /// Chains each asset can travel on; a transfer takes the cheapest, then
+    /// the fastest. Assets not listed move free and in unknown time
+    pub assets: HashMap<String, Vec<ChainTransfer>>,
+}
+
+impl TransferConfig {
+    /// The chain `asset` moves over
+    pub fn route(&self, asset: &str) -> Option<&ChainTransfer> {
+        self.assets.get(asset)?.iter().min_by(|a, b| {
+            a.fee().total_cmp(&b.fee()).then(a.confirmation_time.cmp(&b.confirmation_time))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Settlement {
+    /// Every leg trades on one exchange or sells inventory already held where
+    /// the asset would arrive; rebalancing can wait
+    InventoryBased,
+    /// At least one leg waits for a withdrawal to confirm
+    TransferRequired,
+}
+
+/// One leg of a cycle that moves an asset between exchanges
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransferLeg {
+    pub from: String,
+    pub to: String,
+    pub asset: String,
+    /// Held on the receiving exchange, so the leg doesn't wait on the transfer
+    pub inventory: bool,
+    pub chain: Option<String>,
+    /// Withdrawal and network fees as a fraction of the trade size; zero for inventory legs
+    pub cost: f64,
+    pub confirmation_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransferPlan {
+    pub settlement: Settlement,
+    pub legs: Vec<TransferLeg>,
+    /// Fraction of the trade size the transfers cost
+    pub cost: f64,
+    /// Longest confirmation among the transfers, if every one is known
+    pub confirmation_secs: Option<u64>,
+}
+
+impl TransferPlan {
+    /// Fraction of notional left after the transfers
+    pub fn multiplier(&self) -> f64 {
+        (1.0 - self.cost).max(0.0)
+    }
+}
+
+/// Transfers `path` ("BTC_binance -> USDT_binance -> USDT_kraken") needs at a
+/// trade size of `notional`, given `balances` (exchange -> asset -> value).
+/// A leg whose asset is already held on the receiving exchange sells that
+/// inventory instead; its fees are paid whenever balances are rebalanced
+pub fn plan(
+    path: &str,
+    transfers: &TransferConfig,
+    balances: &HashMap<String, HashMap<String, f64>>,
+    notional: f64,
+    aliases: &[AssetAlias],
+) -> TransferPlan {
+    let mut nodes: Vec<(&str, &str)> = path
+        .split(" -> ")
+        .map(|node| node.trim().rsplit_once('_').unwrap_or((node.trim(), "")))
+        .collect();
+    // Paths may repeat the start node to show the cycle closing
+    if nodes.len() > 1 && nodes.first() == nodes.last() {
+        nodes.pop();
+    }
+    
+    let mut legs = Vec::new();
+    for (i, &(asset, exchange)) in nodes.iter().enumerate() {
+        let (next_asset, next_exchange) = nodes[(i + 1) % nodes.len()];
+        if exchange == next_exchange || (asset != next_asset && markets::link(aliases, asset, next_asset).is_none()) {
+            continue;
+        }
+        let held = balances
+            .get(next_exchange)
+            .and_then(|assets| assets.get(next_asset))
+            .is_some_and(|&value| value >= notional);
+        let route = transfers.route(asset);
+        legs.push(TransferLeg {
+            from: format!("{}_{}", asset, exchange),
+            to: format!("{}_{}", next_asset, next_exchange),
+            asset: asset.to_string(),
+            inventory: held,
+            chain: route.map(|route| route.chain.clone()),
+            cost: match route {
+                Some(route) if !held && notional > 0.0 => route.fee() / notional,
+                _ => 0.0,
+            },
+            confirmation_secs: route.map(|route| route.confirmation_time.as_secs()),
+        });
+    }
+    
+    let waiting: Vec<&TransferLeg> = legs.iter().filter(|leg| !leg.inventory).collect();
+    let confirmation_secs = waiting
+        .iter()
+        .map(|leg| leg.confirmation_secs)
+        .collect::<Option<Vec<u64>>>()
+        .map(|secs| secs.into_iter().max().unwrap_or(0));
+    TransferPlan {
+        settlement: if waiting.is_empty() { Settlement::InventoryBased } else { Settlement::TransferRequired },
+        cost: 1.0 - waiting.iter().map(|leg| 1.0 - leg.cost).product::<f64>(),
+        confirmation_secs,
+        legs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn transfers() -> TransferConfig {
+        let chain = |chain: &str, withdrawal_fee, network_fee, minutes: u64| ChainTransfer {
+            chain: chain.to_string(),
+            withdrawal_fee,
+            network_fee,
+            confirmation_time: Duration::from_secs(minutes * 60),
+        };
+        TransferConfig {
+            assets: HashMap::from([
+                ("USDT".to_string(), vec![chain("ERC20", 4.0, 2.0, 5), chain("TRC20", 1.0, 0.0, 2)]),
+                ("BTC".to_string(), vec![chain("bitcoin", 15.0, 5.0, 30)]),
+            ]),
+        }
+    }
+    
+    #[test]
+    fn test_transfers_cost_unless_inventory_covers_them() {
+        let triangle = "BTC_binance -> ETH_binance -> USDT_binance";
+        let single = plan(triangle, &transfers(), &HashMap::new(), 1000.0, &[]);
+        assert_eq!((single.settlement, single.cost, single.legs.len()), (Settlement::InventoryBased, 0.0, 0));
+        
+        let cross = "BTC_binance -> USDT_binance -> USDT_kraken -> BTC_kraken -> BTC_binance";
+        let moved = plan(cross, &transfers(), &HashMap::new(), 1000.0, &[]);
+        assert_eq!(moved.settlement, Settlement::TransferRequired);
+        assert_eq!(moved.legs.len(), 2);
+        assert_eq!(moved.legs[0].chain.as_deref(), Some("TRC20"));
+        // 1 USDT over TRC20 and 20 for BTC on 1000
+        assert!((moved.cost - (1.0 - 0.999 * 0.98)).abs() < 1e-12);
+        assert_eq!(moved.confirmation_secs, Some(30 * 60));
+        
+        // BTC already on binance: only the USDT leg waits
+        let balances = HashMap::from([("binance".to_string(), HashMap::from([("BTC".to_string(), 5000.0)]))]);
+        let partly = plan(cross, &transfers(), &balances, 1000.0, &[]);
+        assert!(partly.legs[1].inventory);
+        assert!((partly.cost - 0.001).abs() < 1e-12);
+        assert_eq!(partly.confirmation_secs, Some(2 * 60));
+        
+        let both = HashMap::from([
+            ("binance".to_string(), HashMap::from([("BTC".to_string(), 5000.0)])),
+            ("kraken".to_string(), HashMap::from([("USDT".to_string(), 1000.0)])),
+        ]);
+        let held = plan(cross, &transfers(), &both, 1000.0, &[]);
+        assert_eq!((held.settlement, held.cost, held.multiplier()), (Settlement::InventoryBased, 0.0, 1.0));
+        
+        // Unlisted assets move free, in unknown time; aliases moving across exchanges are transfers
+        let path = "USDT_kraken -> RNDR_kraken -> RENDER_binance -> USDT_binance";
+        let renamed = plan(path, &transfers(), &HashMap::new(), 1000.0, &AssetAlias::known());
+        assert_eq!(renamed.legs.len(), 2);
+        assert_eq!((renamed.legs[0].asset.as_str(), renamed.legs[0].chain.clone()), ("RNDR", None));
+        assert_eq!(renamed.confirmation_secs, None);
+        assert!((renamed.cost - 0.001).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,78 @@
+// arbitrage/types.rs - Market ticks, opportunities and engine statistics
+use std::time::Instant;
+use serde::{Deserialize, Serialize};
+
+/// What an opportunity is, as its `kind` in API payloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpportunityKind {
+    /// A cycle of trades that returns more than it started with
+    Cycle,
+    /// Spot held against a perpetual for its funding
+    Funding,
+    /// Spot held against a dated future until it settles
+    Basis,
+    /// A spread between correlated instruments betting on its mean
+    StatArb,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArbitrageOpportunity {
+    /// Set by the detector that found it
+    pub kind: OpportunityKind,
+    pub path: String,
+    /// Return of the cycle net of trading and transfer fees
+    pub profit_percentage: f64,
+    /// What the quotes paid before fees when the opportunity was found
+    pub gross_profit: f64,
+    pub max_volume: f64,
+    pub confidence: u32,
+    #[serde(skip)]
+    pub detected_at: Instant,
+    pub exchanges: Vec<String>,
+}
+
+impl ArbitrageOpportunity {
+    /// An opportunity on `path` found just now, on the exchanges its nodes
+    /// name; alert templates are checked against one
+    pub fn sample(path: &str, profit: f64) -> Self {
+        let mut exchanges: Vec<String> = Vec::new();
+        for node in path.split(" -> ") {
+            if let Some((_, exchange)) = node.rsplit_once('_') {
+                if !exchanges.iter().any(|e| e == exchange) {
+                    exchanges.push(exchange.to_string());
+                }
+            }
+        }
+        Self {
+            kind: OpportunityKind::Cycle,
+            path: path.to_string(),
+            profit_percentage: profit,
+            gross_profit: profit,
+            max_volume: 100.0,
+            confidence: 80,
+            detected_at: Instant::now(),
+            exchanges,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MarketTick {
+    pub exchange: String,
+    pub symbol: String,
+    pub bid: f64,
+    pub ask: f64,
+    pub last_price: f64,
+    pub volume: f64,
+    pub timestamp: Instant,
+    pub sequence: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PerformanceStats {
+    pub messages_processed: u64,
+    pub opportunities_found: u64,
+    pub avg_latency_us: f64,
+    pub detection_latency_us: f64,
+}
@@ -0,0 +1,319 @@
+// arbitrage/washtrade.rs - Fake liquidity heuristics per venue and symbol: frozen quotes with churning size, and self-crossing prints
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use super::fees;
+use super::quotes::VenueQuote;
+use super::types::{ArbitrageOpportunity, MarketTick};
+
+/// What happens to opportunities trading a suspect feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WashAction {
+    Downweight, // Confidence scaled by `confidence_factor` per suspect leg
+    Exclude,    // Dropped before alerting
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WashTradingConfig {
+    pub enabled: bool,
+    /// Recent ticks per feed the shares are taken over
+    pub window: usize,
+    /// Ticks a feed needs before it can be called suspect
+    pub min_ticks: usize,
+    /// How far, in basis points, the other venues have to move for a feed
+    /// that hasn't repriced since to count as frozen
+    pub move_bps: f64,
+    /// Share of ticks that change size at a frozen price
+    pub max_frozen_share: f64,
+    /// Share of ticks with the bid at or above the ask
+    pub max_crossed_share: f64,
+    pub action: WashAction,
+    pub confidence_factor: f64,
+}
+
+impl Default for WashTradingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window: 500,
+            min_ticks: 100,
+            move_bps: 5.0,
+            max_frozen_share: 0.3,
+            max_crossed_share: 0.02,
+            action: WashAction::Downweight,
+            confidence_factor: 0.5,
+        }
+    }
+}
+
+/// How one venue's feed of a symbol looks over the last `window` ticks
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeedIntegrity {
+    pub exchange: String,
+    pub symbol: String,
+    pub ticks: usize,
+    /// Size changed at an unchanged price while the other venues moved
+    pub frozen_share: f64,
+    /// Bid at or above the ask, as when a venue prints against itself
+    pub crossed_share: f64,
+    pub suspect: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Sample {
+    frozen: bool,
+    crossed: bool,
+}
+
+#[derive(Default)]
+struct FeedState {
+    /// Bid, ask and size of the previous tick
+    last: Option<(f64, f64, f64)>,
+    /// The other venues' median mid when this feed last repriced
+    anchor: Option<f64>,
+    samples: VecDeque<Sample>,
+    frozen: usize,
+    crossed: usize,
+    suspect: bool,
+}
+
+impl FeedState {
+    fn share(count: usize, ticks: usize) -> f64 {
+        if ticks == 0 {
+            0.0
+        } else {
+            count as f64 / ticks as f64
+        }
+    }
+    
+    fn push(&mut self, sample: Sample, config: &WashTradingConfig) {
+        while self.samples.len() >= config.window.max(1) {
+            if let Some(old) = self.samples.pop_front() {
+                self.frozen -= usize::from(old.frozen);
+                self.crossed -= usize::from(old.crossed);
+            }
+        }
+        self.frozen += usize::from(sample.frozen);
+        self.crossed += usize::from(sample.crossed);
+        self.samples.push_back(sample);
+    }
+    
+    fn judge(&self, config: &WashTradingConfig) -> bool {
+        let ticks = self.samples.len();
+        ticks >= config.min_ticks
+            && (Self::share(self.frozen, ticks) > config.max_frozen_share || Self::share(self.crossed, ticks) > config.max_crossed_share)
+    }
+}
+
+/// Median mid of the venues other than `exchange` with a usable quote
+fn others_mid(exchange: &str, venues: &[VenueQuote]) -> Option<f64> {
+    let mut mids: Vec<f64> = venues
+        .iter()
+        .filter(|quote| quote.exchange != exchange && quote.bid > 0.0 && quote.ask >= quote.bid && quote.ask.is_finite())
+        .map(|quote| (quote.bid + quote.ask) / 2.0)
+        .collect();
+    if mids.is_empty() {
+        return None;
+    }
+    mids.sort_by(f64::total_cmp);
+    Some(mids[mids.len() / 2])
+}
+
+/// Watches every feed for liquidity that doesn't trade: sizes churning on a
+/// price the rest of the market has left behind, and books crossed against
+/// themselves. Opportunities through a suspect feed lose confidence or are
+/// dropped
+pub struct WashMonitor {
+    config: RwLock<WashTradingConfig>,
+    /// Keyed by (exchange, symbol)
+    feeds: Mutex<HashMap<(String, String), FeedState>>,
+}
+
+impl WashMonitor {
+    pub fn new(config: WashTradingConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            feeds: Mutex::new(HashMap::new()),
+        }
+    }
+    
+    /// Rejudges every feed under the new limits
+    pub fn set_config(&self, config: WashTradingConfig) {
+        let mut feeds = self.feeds.lock().unwrap();
+        for feed in feeds.values_mut() {
+            while feed.samples.len() > config.window.max(1) {
+                if let Some(old) = feed.samples.pop_front() {
+                    feed.frozen -= usize::from(old.frozen);
+                    feed.crossed -= usize::from(old.crossed);
+                }
+            }
+            feed.suspect = feed.judge(&config);
+        }
+        *self.config.write().unwrap() = config;
+    }
+    
+    /// Record `tick` against `venues`, the latest quotes for its symbol
+    pub fn observe(&self, tick: &MarketTick, venues: &[VenueQuote]) {
+        let config = self.config.read().unwrap();
+        let priced = tick.bid > 0.0 && tick.ask > 0.0;
+        if !config.enabled || !priced {
+            return;
+        }
+        let reference = others_mid(&tick.exchange, venues);
+        let mut feeds = self.feeds.lock().unwrap();
+        let feed = feeds.entry((tick.exchange.clone(), tick.symbol.clone())).or_default();
+        
+        let repriced = feed.last.is_none_or(|(bid, ask, _)| bid != tick.bid || ask != tick.ask);
+        let resized = feed.last.is_some_and(|(_, _, size)| size != tick.volume);
+        if repriced || feed.anchor.is_none() {
+            feed.anchor = reference;
+        }
+        let market_moved = match (feed.anchor, reference) {
+            (Some(anchor), Some(mid)) => (mid / anchor - 1.0).abs() * 10_000.0 >= config.move_bps,
+            _ => false,
+        };
+        feed.last = Some((tick.bid, tick.ask, tick.volume));
+        feed.push(
+            Sample {
+                frozen: !repriced && resized && market_moved,
+                crossed: tick.bid >= tick.ask,
+            },
+            &config,
+        );
+        
+        let suspect = feed.judge(&config);
+        if suspect != feed.suspect {
+            feed.suspect = suspect;
+            let ticks = feed.samples.len();
+            info!(
+                "{} {} feed {} (frozen {:.1}%, crossed {:.1}% of {} ticks)",
+                tick.exchange,
+                tick.symbol,
+                if suspect { "looks like fake liquidity" } else { "no longer suspect" },
+                FeedState::share(feed.frozen, ticks) * 100.0,
+                FeedState::share(feed.crossed, ticks) * 100.0,
+                ticks
+            );
+        }
+    }
+    
+    /// Apply the configured action to `opportunity` for each of its legs
+    /// trading a suspect feed; false if it should be dropped
+    pub fn screen(&self, opportunity: &mut ArbitrageOpportunity) -> bool {
+        let config = self.config.read().unwrap();
+        if !config.enabled {
+            return true;
+        }
+        let feeds = self.feeds.lock().unwrap();
+        let suspect = |exchange: &str, base: &str, quote: &str| {
+            [format!("{}/{}", base, quote), format!("{}/{}", quote, base)]
+                .into_iter()
+                .any(|symbol| feeds.get(&(exchange.to_string(), symbol)).is_some_and(|feed| feed.suspect))
+        };
+        let nodes = fees::path_nodes(&opportunity.path);
+        let legs = (0..nodes.len())
+            .filter(|&i| {
+                let (from, to) = (nodes[i], nodes[(i + 1) % nodes.len()]);
+                from.1 == to.1 && suspect(from.1, from.0, to.0)
+            })
+            .count();
+        if legs == 0 {
+            return true;
+        }
+        match config.action {
+            WashAction::Exclude => {
+                debug!("Dropping {}: {} legs trade suspect feeds", opportunity.path, legs);
+                false
+            }
+            WashAction::Downweight => {
+                opportunity.confidence = (opportunity.confidence as f64 * config.confidence_factor.powi(legs as i32)) as u32;
+                true
+            }
+        }
+    }
+    
+    /// Every feed seen, suspect ones first
+    pub fn report(&self) -> Vec<FeedIntegrity> {
+        let feeds = self.feeds.lock().unwrap();
+        let mut report: Vec<FeedIntegrity> = feeds
+            .iter()
+            .map(|((exchange, symbol), feed)| {
+                let ticks = feed.samples.len();
+                FeedIntegrity {
+                    exchange: exchange.clone(),
+                    symbol: symbol.clone(),
+                    ticks,
+                    frozen_share: FeedState::share(feed.frozen, ticks),
+                    crossed_share: FeedState::share(feed.crossed, ticks),
+                    suspect: feed.suspect,
+                }
+            })
+            .collect();
+        report.sort_by(|a, b| b.suspect.cmp(&a.suspect).then_with(|| (&a.exchange, &a.symbol).cmp(&(&b.exchange, &b.symbol))));
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    
+    fn tick(exchange: &str, bid: f64, ask: f64, volume: f64) -> MarketTick {
+        MarketTick {
+            exchange: exchange.to_string(),
+            symbol: "BTC/USDT".to_string(),
+            bid,
+            ask,
+            last_price: (bid + ask) / 2.0,
+            volume,
+            timestamp: Instant::now(),
+            sequence: 0,
+        }
+    }
+    
+    fn quote(exchange: &str, mid: f64) -> VenueQuote {
+        VenueQuote { exchange: exchange.to_string(), bid: mid - 0.5, ask: mid + 0.5, volume: 1.0, age_ms: 0 }
+    }
+    
+    #[test]
+    fn test_frozen_and_crossed_feeds_are_suspect() {
+        let config = WashTradingConfig { min_ticks: 20, window: 40, ..WashTradingConfig::default() };
+        let monitor = WashMonitor::new(config.clone());
+        
+        // The market climbs 10 bps a tick; "shady" holds its price and churns size
+        for step in 0..40 {
+            let mid = 100.0 * (1.0 + 0.001 * step as f64);
+            let venues = [quote("binance", mid), quote("kraken", mid), quote("shady", 100.0)];
+            monitor.observe(&tick("binance", mid - 0.5, mid + 0.5, 1.0 + step as f64), &venues);
+            monitor.observe(&tick("shady", 99.5, 100.5, 50.0 + step as f64), &venues);
+            // Crossed one tick in four
+            let (bid, ask) = if step % 4 == 0 { (mid + 0.1, mid) } else { (mid - 0.5, mid + 0.5) };
+            monitor.observe(&tick("kraken", bid, ask, 1.0), &venues);
+        }
+        let report = monitor.report();
+        let feed = |exchange: &str| report.iter().find(|feed| feed.exchange == exchange).unwrap();
+        assert!(feed("shady").suspect && feed("shady").frozen_share > 0.9);
+        assert!(feed("kraken").suspect && feed("kraken").crossed_share == 0.25);
+        assert!(!feed("binance").suspect);
+        assert_eq!(feed("binance").ticks, 40);
+        assert!(report[0].suspect && !report[2].suspect);
+        
+        // Two suspect legs halve confidence twice; the cross-exchange hop isn't a trade
+        let mut opp = ArbitrageOpportunity::sample("BTC_shady -> USDT_shady -> USDT_kraken -> BTC_kraken", 0.01);
+        assert!(monitor.screen(&mut opp));
+        assert_eq!(opp.confidence, 20);
+        let mut clean = ArbitrageOpportunity::sample("BTC_binance -> USDT_binance -> ETH_binance", 0.01);
+        assert!(monitor.screen(&mut clean));
+        assert_eq!(clean.confidence, 80);
+        
+        monitor.set_config(WashTradingConfig { action: WashAction::Exclude, ..config.clone() });
+        assert!(!monitor.screen(&mut ArbitrageOpportunity::sample("USDT_shady -> BTC_shady -> ETH_shady", 0.01)));
+        // Looser limits clear the feeds at once
+        monitor.set_config(WashTradingConfig { max_frozen_share: 1.0, max_crossed_share: 1.0, ..config });
+        assert!(monitor.report().iter().all(|feed| !feed.suspect));
+    }
+}
@@ -0,0 +1,275 @@
+// bot/commands.rs - Chat command parsing and execution
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::arbitrage::ArbitrageEngine;
+use crate::control::{ControlAction, ControlPlane};
+use crate::display::DisplayFormat;
+use crate::execution::ExecutionJournal;
+
+const DEFAULT_TOP: usize = 5;
+const MAX_TOP: usize = 20;
+
+pub const HELP: &str = "/status - scanner health\n\
+/top [n] - best recent opportunities\n\
+/pause <exchange> - stop alerting on an exchange\n\
+/resume <exchange> - undo /pause\n\
+/threshold <0.2% | 20bps> - minimum profit to alert on\n\
+/pnl - realized vs expected profit\n\
+/approvals - opportunities awaiting approval\n\
+/approve <id>, /reject <id> - decide on a queued opportunity";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BotCommand {
+    Status,
+    Top(usize),
+    Pause(String),
+    Resume(String),
+    Threshold(f64), // Fraction, 0.002 = 0.2%
+    Pnl,
+    Approvals,
+    Approve(u64),
+    Reject(u64),
+    Help,
+}
+
+impl FromStr for BotCommand {
+    type Err = String;
+    
+    /// Parse "/cmd args"; Telegram group chats append "@botname" to the command
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let command = parts.next().unwrap_or_default();
+        let command = command.split('@').next().unwrap_or(command).to_lowercase();
+        let arg = parts.next();
+        
+        match (command.as_str(), arg) {
+            ("/status", _) => Ok(BotCommand::Status),
+            ("/top", None) => Ok(BotCommand::Top(DEFAULT_TOP)),
+            ("/top", Some(n)) => match n.parse::<usize>() {
+                Ok(n) if n > 0 => Ok(BotCommand::Top(n.min(MAX_TOP))),
+                _ => Err(format!("'{}' is not a positive count", n)),
+            },
+            ("/pause", Some(exchange)) => Ok(BotCommand::Pause(exchange.to_lowercase())),
+            ("/resume", Some(exchange)) => Ok(BotCommand::Resume(exchange.to_lowercase())),
+            ("/pause" | "/resume", None) => Err(format!("usage: {} <exchange>", command)),
+            ("/threshold", Some(value)) => parse_threshold(value).map(BotCommand::Threshold),
+            ("/threshold", None) => Err("usage: /threshold 0.2% or /threshold 20bps".to_string()),
+            ("/pnl", _) => Ok(BotCommand::Pnl),
+            ("/approvals", _) => Ok(BotCommand::Approvals),
+            ("/approve" | "/reject", Some(id)) => {
+                let id = id.trim_start_matches('#');
+                let id = id.parse::<u64>().map_err(|_| format!("'{}' is not an approval id", id))?;
+                Ok(if command == "/approve" { BotCommand::Approve(id) } else { BotCommand::Reject(id) })
+            }
+            ("/approve" | "/reject", None) => Err(format!("usage: {} <id>", command)),
+            ("/help" | "/start", _) => Ok(BotCommand::Help),
+            _ => Err(format!("unknown command '{}'; try /help", command)),
+        }
+    }
+}
+
+/// "0.2%" or "20bps" as a fraction; bare numbers are refused as ambiguous
+fn parse_threshold(value: &str) -> Result<f64, String> {
+    let value = value.trim().to_lowercase();
+    let (number, scale) = if let Some(pct) = value.strip_suffix('%') {
+        (pct, 100.0)
+    } else if let Some(bps) = value.strip_suffix("bps") {
+        (bps, 10_000.0)
+    } else {
+        return Err(format!("'{}' needs a unit: 0.2% or 20bps", value));
+    };
+    
+    match number.trim().parse::<f64>() {
+        Ok(n) if (0.0..1.0).contains(&(n / scale)) => Ok(n / scale),
+        Ok(_) => Err(format!("{} is outside 0% to 100%", value)),
+        Err(_) => Err(format!("'{}' is not a number", number)),
+    }
+}
+
+/// What commands can see and change
+pub struct CommandContext {
+    pub engine: Arc<ArbitrageEngine>,
+    pub journal: Arc<ExecutionJournal>,
+    pub exchanges: Vec<String>,
+    pub display: DisplayFormat,
+    pub control: Arc<ControlPlane>,
+}
+
+impl CommandContext {
+    /// Plain-text reply for `command`
+    pub async fn execute(&self, command: BotCommand) -> String {
+        let controls = self.engine.controls();
+        
+        match command {
+            BotCommand::Status => {
+                let stats = self.engine.get_performance_stats().await;
+                let paused = controls.paused_exchanges();
+                format!(
+                    "Scanner {}\nmessages: {} | opportunities: {}\navg latency: {:.1}us | detection: {:.1}us\nthreshold: {}\npaused: {}",
+                    if self.engine.is_running().await { "running" } else { "stopped" },
+                    stats.messages_processed,
+                    stats.opportunities_found,
+                    stats.avg_latency_us,
+                    stats.detection_latency_us,
+                    self.display.profit(controls.min_profit_threshold()),
+                    if paused.is_empty() { "none".to_string() } else { paused.join(", ") },
+                )
+            }
+            BotCommand::Top(n) => {
+                let mut opportunities = self.engine.get_recent_opportunities(100).await;
+                if opportunities.is_empty() {
+                    return "No recent opportunities".to_string();
+                }
+                opportunities.sort_by(|a, b| b.profit_percentage.total_cmp(&a.profit_percentage));
+                opportunities
+                    .iter()
+                    .take(n)
+                    .enumerate()
+                    .map(|(i, opp)| format!("{}. {} {}", i + 1, self.display.profit(opp.profit_percentage), opp.path))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            BotCommand::Pause(exchange) => {
+                if !self.exchanges.contains(&exchange) {
+                    return format!("{} is not a configured exchange ({})", exchange, self.exchanges.join(", "));
+                }
+                if controls.pause_exchange(&exchange) {
+                    format!("Paused alerts on {}", exchange)
+                } else {
+                    format!("{} is already paused", exchange)
+                }
+            }
+            BotCommand::Resume(exchange) => {
+                if controls.resume_exchange(&exchange) {
+                    format!("Resumed alerts on {}", exchange)
+                } else {
+                    format!("{} is not paused", exchange)
+                }
+            }
+            BotCommand::Threshold(threshold) => {
+                let previous = controls.min_profit_threshold();
+                controls.set_min_profit_threshold(threshold);
+                format!(
+                    "Threshold {} -> {}",
+                    self.display.profit(previous),
+                    self.display.profit(threshold)
+                )
+            }
+            BotCommand::Pnl => {
+                let executions = self.journal.executions();
+                let realized: f64 = executions.iter().map(|e| e.realized_profit).sum();
+                let expected: f64 = executions.iter().map(|e| e.expected_profit).sum();
+                format!(
+                    "{} executions\nrealized: {}\nexpected: {}\nslippage: {}",
+                    executions.len(),
+                    self.display.amount(realized),
+                    self.display.amount(expected),
+                    self.display.amount(realized - expected),
+                )
+            }
+            BotCommand::Approvals => {
+                let pending = self.control.approvals().pending(crate::outbox::now_ms());
+                if pending.is_empty() {
+                    return "Nothing awaiting approval".to_string();
+                }
+                pending
+                    .iter()
+                    .map(|p| format!("#{} {} {}", p.id, self.display.profit(p.opportunity.profit_percentage), p.opportunity.path))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            BotCommand::Approve(id) => self.decide(ControlAction::Approve { id }).await,
+            BotCommand::Reject(id) => self.decide(ControlAction::Reject { id }).await,
+            BotCommand::Help => HELP.to_string(),
+        }
+    }
+    
+    async fn decide(&self, action: ControlAction) -> String {
+        match self.control.apply(action).await {
+            Ok(message) => message,
+            Err(error) => format!("Failed: {}", error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrage::Config;
+    use crate::compliance::ComplianceGate;
+    use crate::execution::approval::ApprovalQueue;
+    use crate::execution::ExecutionRecord;
+    
+    fn context() -> CommandContext {
+        let config = Config::default();
+        let engine = Arc::new(ArbitrageEngine::new(config.clone()));
+        let journal = Arc::new(ExecutionJournal::new());
+        let control = ControlPlane::new(
+            engine.clone(),
+            journal.clone(),
+            Arc::new(ApprovalQueue::new(config.approval.clone())),
+            Arc::new(ComplianceGate::from_config(&config.compliance)),
+            DisplayFormat::default(),
+        );
+        CommandContext {
+            engine,
+            journal,
+            exchanges: config.exchanges,
+            display: DisplayFormat::default(),
+            control: Arc::new(control),
+        }
+    }
+    
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!("/status@ArbBot".parse(), Ok(BotCommand::Status));
+        assert_eq!("/top".parse(), Ok(BotCommand::Top(DEFAULT_TOP)));
+        assert_eq!("/top 50".parse(), Ok(BotCommand::Top(MAX_TOP)));
+        assert_eq!("/pause Binance".parse(), Ok(BotCommand::Pause("binance".to_string())));
+        assert_eq!("/threshold 0.2%".parse::<BotCommand>().map(|c| format!("{:?}", c)), Ok("Threshold(0.002)".to_string()));
+        assert_eq!("/threshold 20bps".parse::<BotCommand>().map(|c| format!("{:?}", c)), Ok("Threshold(0.002)".to_string()));
+        
+        assert!("/threshold 0.2".parse::<BotCommand>().is_err());
+        assert!("/threshold 150%".parse::<BotCommand>().is_err());
+        assert!("/pause".parse::<BotCommand>().is_err());
+        assert!("/withdraw all".parse::<BotCommand>().is_err());
+        
+        assert_eq!("/approve #12".parse(), Ok(BotCommand::Approve(12)));
+        assert_eq!("/reject 3".parse(), Ok(BotCommand::Reject(3)));
+        assert!("/approve".parse::<BotCommand>().is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_threshold_and_pause_change_engine_controls() {
+        let ctx = context();
+        let controls = ctx.engine.controls();
+        
+        assert_eq!(ctx.execute(BotCommand::Threshold(0.002)).await, "Threshold 0.10% -> 0.20%");
+        assert_eq!(controls.min_profit_threshold(), 0.002);
+        
+        assert_eq!(ctx.execute(BotCommand::Pause("binance".to_string())).await, "Paused alerts on binance");
+        assert!(ctx.execute(BotCommand::Pause("ftx".to_string())).await.contains("not a configured exchange"));
+        assert!(ctx.execute(BotCommand::Status).await.contains("paused: binance"));
+    }
+    
+    #[tokio::test]
+    async fn test_pnl_sums_journal() {
+        let ctx = context();
+        for (expected, realized) in [(10.0, 8.0), (5.0, 5.5)] {
+            ctx.journal.record_execution(ExecutionRecord {
+                opportunity_path: "BTC -> ETH -> BTC".to_string(),
+                exchanges: vec!["binance".to_string()],
+                notional: 1000.0,
+                expected_profit: expected,
+                realized_profit: realized,
+                completed_at_ms: 0,
+            });
+        }
+        
+        assert_eq!(
+            ctx.execute(BotCommand::Pnl).await,
+            "2 executions\nrealized: 13.50 USD\nexpected: 15.00 USD\nslippage: -1.50 USD"
+        );
+    }
+}
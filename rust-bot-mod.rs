@@ -0,0 +1,6 @@
+// bot/mod.rs - Remote operator commands over chat
+pub mod commands;
+pub mod telegram;
+
+pub use commands::CommandContext;
+pub use telegram::{TelegramBot, TelegramBotConfig};
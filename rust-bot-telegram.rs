@@ -0,0 +1,203 @@
+// bot/telegram.rs - Telegram bot long-polling transport
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{info, warn};
+
+use super::commands::{BotCommand, CommandContext};
+
+const API_BASE: &str = "https://api.telegram.org";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramBotConfig {
+    pub bot_token: String,
+    /// Only these chats may issue commands; everything else is ignored
+    pub allowed_chat_ids: Vec<i64>,
+    /// Long-poll duration for getUpdates
+    #[serde(with = "humantime_serde")]
+    pub poll_timeout: Duration,
+}
+
+impl Default for TelegramBotConfig {
+    fn default() -> Self {
+        Self {
+            bot_token: String::new(),
+            allowed_chat_ids: Vec::new(),
+            poll_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdatesResponse {
+    ok: bool,
+    #[serde(default)]
+    result: Vec<Update>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Update {
+    update_id: i64,
+    message: Option<ChatMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    chat: Chat,
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+pub struct TelegramBot {
+    config: TelegramBotConfig,
+    context: CommandContext,
+    client: reqwest::Client,
+}
+
+impl TelegramBot {
+    pub fn new(config: TelegramBotConfig, context: CommandContext) -> Self {
+        Self {
+            config,
+            context,
+            client: reqwest::Client::new(),
+        }
+    }
+    
+    /// Poll for commands until the task is aborted
+    pub async fn run(self) {
+        info!("Telegram bot accepting commands from {} chats", self.config.allowed_chat_ids.len());
+        let mut offset = 0;
+        
+        loop {
+            let updates = match self.get_updates(offset).await {
+                Ok(updates) => updates,
+                Err(e) => {
+                    warn!("Telegram getUpdates failed: {}", e);
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+            
+            for update in updates {
+                offset = offset.max(update.update_id + 1);
+                let Some((chat_id, command)) = self.command_for(&update) else { continue };
+                
+                let reply = match command {
+                    Ok(command) => self.context.execute(command).await,
+                    Err(e) => e,
+                };
+                if let Err(e) = self.send_message(chat_id, &reply).await {
+                    warn!("Telegram reply to {} failed: {}", chat_id, e);
+                }
+            }
+        }
+    }
+    
+    /// The command in `update`, if it is a command from an allowed chat
+    fn command_for(&self, update: &Update) -> Option<(i64, Result<BotCommand, String>)> {
+        let message = update.message.as_ref()?;
+        let text = message.text.as_deref()?.trim();
+        if !text.starts_with('/') {
+            return None;
+        }
+        if !self.config.allowed_chat_ids.contains(&message.chat.id) {
+            warn!("Ignoring Telegram command from unauthorized chat {}", message.chat.id);
+            return None;
+        }
+        Some((message.chat.id, text.parse()))
+    }
+    
+    async fn get_updates(&self, offset: i64) -> Result<Vec<Update>, String> {
+        let url = format!("{}/bot{}/getUpdates", API_BASE, self.config.bot_token);
+        let response: UpdatesResponse = self
+            .client
+            .get(url)
+            .query(&[("offset", offset), ("timeout", self.config.poll_timeout.as_secs() as i64)])
+            .timeout(self.config.poll_timeout + Duration::from_secs(10))
+            .send()
+            .await
+            .map_err(|e| e.without_url().to_string())? // The URL carries the bot token
+            .json()
+            .await
+            .map_err(|e| e.without_url().to_string())?;
+        
+        if response.ok {
+            Ok(response.result)
+        } else {
+            Err(response.description.unwrap_or_else(|| "request rejected".to_string()))
+        }
+    }
+    
+    async fn send_message(&self, chat_id: i64, text: &str) -> Result<(), String> {
+        let url = format!("{}/bot{}/sendMessage", API_BASE, self.config.bot_token);
+        let response = self
+            .client
+            .post(url)
+            .json(&json!({ "chat_id": chat_id, "text": text }))
+            .send()
+            .await
+            .map_err(|e| e.without_url().to_string())?;
+        
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("sendMessage returned {}", response.status()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::arbitrage::{ArbitrageEngine, Config};
+    use crate::compliance::ComplianceGate;
+    use crate::control::ControlPlane;
+    use crate::display::DisplayFormat;
+    use crate::execution::approval::{ApprovalConfig, ApprovalQueue};
+    use crate::execution::ExecutionJournal;
+    
+    #[test]
+    fn test_only_allowed_chats_issue_commands() {
+        let engine = Arc::new(ArbitrageEngine::new(Config::default()));
+        let journal = Arc::new(ExecutionJournal::new());
+        let control = ControlPlane::new(
+            engine.clone(),
+            journal.clone(),
+            Arc::new(ApprovalQueue::new(ApprovalConfig::default())),
+            Arc::new(ComplianceGate::from_config(&Default::default())),
+            DisplayFormat::default(),
+        );
+        let bot = TelegramBot::new(
+            TelegramBotConfig {
+                bot_token: "123:abc".to_string(),
+                allowed_chat_ids: vec![42],
+                ..TelegramBotConfig::default()
+            },
+            CommandContext {
+                engine,
+                journal,
+                exchanges: Vec::new(),
+                display: DisplayFormat::default(),
+                control: Arc::new(control),
+            },
+        );
+        let updates: UpdatesResponse = serde_json::from_str(
+            r#"{"ok":true,"result":[
+                {"update_id":1,"message":{"chat":{"id":42},"text":"/status"}},
+                {"update_id":2,"message":{"chat":{"id":7},"text":"/pause binance"}},
+                {"update_id":3,"message":{"chat":{"id":42},"text":"hello"}},
+                {"update_id":4,"edited_message":{"chat":{"id":42},"text":"/pnl"}}
+            ]}"#,
+        )
+        .unwrap();
+        
+        let commands: Vec<_> = updates.result.iter().filter_map(|u| bot.command_for(u)).collect();
+        assert_eq!(commands, vec![(42, Ok(BotCommand::Status))]);
+    }
+}
@@ -0,0 +1,96 @@
+// clock.rs - Injectable time source, so replays and tests run deterministically
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::outbox;
+
+/// Where the engine and connectors read the time. Latency measurements stay
+/// on `Instant::now`, since they time this machine's work, not the market's
+pub trait Clock: Send + Sync {
+    /// Monotonic time for timestamps, TTLs and cadences
+    fn now(&self) -> Instant;
+    
+    /// Wall-clock milliseconds since the Unix epoch
+    fn unix_ms(&self) -> u64;
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+pub fn system() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+    
+    fn unix_ms(&self) -> u64 {
+        outbox::now_ms()
+    }
+}
+
+/// Time that moves only when told to, for replays and tests. Every `Instant`
+/// it hands out is a fixed offset from its creation, so durations between
+/// them are exact
+pub struct ManualClock {
+    origin: Instant,
+    origin_ms: u64,
+    elapsed: Mutex<Duration>,
+}
+
+impl ManualClock {
+    /// A clock reading `unix_ms`
+    pub fn new(unix_ms: u64) -> Self {
+        Self {
+            origin: Instant::now(),
+            origin_ms: unix_ms,
+            elapsed: Mutex::new(Duration::ZERO),
+        }
+    }
+    
+    pub fn advance(&self, by: Duration) {
+        *self.elapsed.lock().unwrap() += by;
+    }
+    
+    /// Move to `unix_ms`; a time before the current one is ignored, since
+    /// `now` must never go backwards
+    pub fn set_unix_ms(&self, unix_ms: u64) {
+        let target = Duration::from_millis(unix_ms.saturating_sub(self.origin_ms));
+        let mut elapsed = self.elapsed.lock().unwrap();
+        *elapsed = (*elapsed).max(target);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.origin + *self.elapsed.lock().unwrap()
+    }
+    
+    fn unix_ms(&self) -> u64 {
+        self.origin_ms + self.elapsed.lock().unwrap().as_millis() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_manual_clock_moves_only_forward_when_told() {
+        let clock = ManualClock::new(1_700_000_000_000);
+        let start = clock.now();
+        assert_eq!(clock.now(), start);
+        
+        clock.advance(Duration::from_millis(250));
+        assert_eq!(clock.now() - start, Duration::from_millis(250));
+        assert_eq!(clock.unix_ms(), 1_700_000_000_250);
+        
+        clock.set_unix_ms(1_700_000_001_000);
+        assert_eq!(clock.now() - start, Duration::from_secs(1));
+        clock.set_unix_ms(1_700_000_000_500);
+        assert_eq!(clock.unix_ms(), 1_700_000_001_000, "never backwards");
+    }
+}
@@ -0,0 +1,188 @@
+// compliance.rs - Pre-trade compliance hooks
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::arbitrage::types::ArbitrageOpportunity;
+
+/// Where in the pipeline a check runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComplianceStage {
+    Alert,
+    Execution,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplianceViolation {
+    pub check: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ComplianceViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.check, self.reason)
+    }
+}
+
+impl std::error::Error for ComplianceViolation {}
+
+/// User-pluggable rule evaluated before any execution (and optionally before alerts)
+pub trait ComplianceCheck: Send + Sync {
+    fn name(&self) -> &str;
+    
+    /// `Err(reason)` blocks the opportunity at this stage
+    fn check(&self, opportunity: &ArbitrageOpportunity, stage: ComplianceStage) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ComplianceConfig {
+    /// Assets that may never be traded or alerted on (e.g. sanctioned tokens)
+    pub restricted_assets: Vec<String>,
+    /// Venues the operator may not use
+    pub restricted_exchanges: Vec<String>,
+    /// If non-empty, only these venues are permitted (jurisdiction whitelist)
+    pub allowed_exchanges: Vec<String>,
+    /// Also apply checks before alerts, not only before execution
+    pub check_alerts: bool,
+}
+
+/// Default config-driven implementation of `ComplianceCheck`
+pub struct ConfigCompliance {
+    config: ComplianceConfig,
+}
+
+impl ConfigCompliance {
+    pub fn new(config: ComplianceConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl ComplianceCheck for ConfigCompliance {
+    fn name(&self) -> &str {
+        "config"
+    }
+    
+    fn check(&self, opportunity: &ArbitrageOpportunity, _stage: ComplianceStage) -> Result<(), String> {
+        // Path nodes look like "BTC_binance"
+        for node in opportunity.path.split(" -> ") {
+            let (asset, exchange) = node.trim().split_once('_').unwrap_or((node, ""));
+            
+            if self.config.restricted_assets.iter().any(|a| a.eq_ignore_ascii_case(asset)) {
+                return Err(format!("asset {} is restricted", asset));
+            }
+            if self.config.restricted_exchanges.iter().any(|e| e.eq_ignore_ascii_case(exchange)) {
+                return Err(format!("exchange {} is restricted", exchange));
+            }
+            if !self.config.allowed_exchanges.is_empty()
+                && !self.config.allowed_exchanges.iter().any(|e| e.eq_ignore_ascii_case(exchange))
+            {
+                return Err(format!("exchange {} is not in the allowed list", exchange));
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+/// Runs every registered check; the first failure blocks the opportunity
+pub struct ComplianceGate {
+    checks: Vec<Box<dyn ComplianceCheck>>,
+    check_alerts: bool,
+}
+
+impl ComplianceGate {
+    /// Gate with the config-driven default check installed
+    pub fn from_config(config: &ComplianceConfig) -> Self {
+        Self {
+            checks: vec![Box::new(ConfigCompliance::new(config.clone()))],
+            check_alerts: config.check_alerts,
+        }
+    }
+    
+    pub fn add_check(&mut self, check: Box<dyn ComplianceCheck>) {
+        self.checks.push(check);
+    }
+    
+    pub fn evaluate(
+        &self,
+        opportunity: &ArbitrageOpportunity,
+        stage: ComplianceStage,
+    ) -> Result<(), ComplianceViolation> {
+        if stage == ComplianceStage::Alert && !self.check_alerts {
+            return Ok(());
+        }
+        
+        for check in &self.checks {
+            if let Err(reason) = check.check(opportunity, stage) {
+                warn!("Compliance check '{}' blocked {} at {:?}: {}", check.name(), opportunity.path, stage, reason);
+                return Err(ComplianceViolation {
+                    check: check.name().to_string(),
+                    reason,
+                });
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_restricted_asset_blocks_execution() {
+        let gate = ComplianceGate::from_config(&ComplianceConfig {
+            restricted_assets: vec!["xmr".to_string()],
+            ..ComplianceConfig::default()
+        });
+        
+        let violation = gate
+            .evaluate(&ArbitrageOpportunity::sample("XMR_kraken -> USDT_kraken -> BTC_kraken", 0.01), ComplianceStage::Execution)
+            .unwrap_err();
+        assert_eq!(violation.check, "config");
+        assert!(gate
+            .evaluate(&ArbitrageOpportunity::sample("BTC_kraken -> USDT_kraken -> ETH_kraken", 0.01), ComplianceStage::Execution)
+            .is_ok());
+    }
+    
+    #[test]
+    fn test_alert_stage_only_checked_when_enabled() {
+        let mut config = ComplianceConfig {
+            allowed_exchanges: vec!["kraken".to_string()],
+            ..ComplianceConfig::default()
+        };
+        let opp = ArbitrageOpportunity::sample("BTC_binance -> USDT_binance -> BTC_kraken", 0.01);
+        
+        assert!(ComplianceGate::from_config(&config).evaluate(&opp, ComplianceStage::Alert).is_ok());
+        assert!(ComplianceGate::from_config(&config).evaluate(&opp, ComplianceStage::Execution).is_err());
+        
+        config.check_alerts = true;
+        assert!(ComplianceGate::from_config(&config).evaluate(&opp, ComplianceStage::Alert).is_err());
+    }
+    
+    #[test]
+    fn test_custom_check() {
+        struct NoLongCycles;
+        impl ComplianceCheck for NoLongCycles {
+            fn name(&self) -> &str {
+                "no_long_cycles"
+            }
+            fn check(&self, opp: &ArbitrageOpportunity, _: ComplianceStage) -> Result<(), String> {
+                if opp.path.split(" -> ").count() > 3 {
+                    Err("too many legs".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+        }
+        
+        let mut gate = ComplianceGate::from_config(&ComplianceConfig::default());
+        gate.add_check(Box::new(NoLongCycles));
+        
+        let violation = gate
+            .evaluate(&ArbitrageOpportunity::sample("A_x -> B_x -> C_x -> D_x", 0.01), ComplianceStage::Execution)
+            .unwrap_err();
+        assert_eq!(violation.check, "no_long_cycles");
+    }
+}
@@ -0,0 +1,186 @@
+// compression.rs - Compressed, conditionally cached replies for large API responses
+use std::io::Write;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use sha2::{Digest, Sha256};
+use warp::http::{header, Response, StatusCode};
+use warp::hyper::Body;
+use warp::Filter;
+
+/// Bodies smaller than this go out as they are; compressing them saves less
+/// than the headers cost
+const MIN_COMPRESSED_BYTES: usize = 1024;
+const BROTLI_QUALITY: u32 = 5; // Fast enough per request, well ahead of gzip on JSON
+const BROTLI_WINDOW: u32 = 22;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Brotli,
+    Gzip,
+    Identity,
+}
+
+impl Encoding {
+    fn token(self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+            Encoding::Identity => "identity",
+        }
+    }
+    
+    /// Best encoding `accept_encoding` allows, preferring brotli, then gzip, on
+    /// equal weight. A coding listed with q=0 is refused, as is any unlisted
+    /// coding when "*;q=0" is given
+    pub fn negotiate(accept_encoding: Option<&str>) -> Self {
+        let Some(accept) = accept_encoding else {
+            return Encoding::Identity;
+        };
+        let weights: Vec<(&str, f64)> = accept
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.split(';');
+                let coding = parts.next()?.trim();
+                let weight = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f64>().ok())?;
+                (!coding.is_empty()).then_some((coding, weight))
+            })
+            .collect();
+        let weight = |token: &str| {
+            weights
+                .iter()
+                .find(|(coding, _)| coding.eq_ignore_ascii_case(token))
+                .or_else(|| weights.iter().find(|(coding, _)| *coding == "*"))
+                .map_or(0.0, |&(_, weight)| weight)
+        };
+        
+        [Encoding::Brotli, Encoding::Gzip]
+            .into_iter()
+            .map(|encoding| (encoding, weight(encoding.token())))
+            .filter(|&(_, weight)| weight > 0.0)
+            .fold(None, |best: Option<(Encoding, f64)>, candidate| match best {
+                Some(best) if best.1 >= candidate.1 => Some(best),
+                _ => Some(candidate),
+            })
+            .map_or(Encoding::Identity, |(encoding, _)| encoding)
+    }
+    
+    pub fn encode(self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Encoding::Brotli => {
+                let mut encoder = brotli::CompressorWriter::new(Vec::new(), 4096, BROTLI_QUALITY, BROTLI_WINDOW);
+                encoder.write_all(body)?;
+                encoder.flush()?;
+                Ok(encoder.into_inner())
+            }
+            Encoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+            Encoding::Identity => Ok(body.to_vec()),
+        }
+    }
+}
+
+/// The request headers a cached, compressed reply depends on
+#[derive(Debug, Clone, Default)]
+pub struct Conditional {
+    pub accept_encoding: Option<String>,
+    pub if_none_match: Option<String>,
+}
+
+/// Extracts `Conditional` from the request, for routes replying through `reply`
+pub fn conditional() -> impl Filter<Extract = (Conditional,), Error = std::convert::Infallible> + Clone {
+    warp::header::optional::<String>("accept-encoding")
+        .and(warp::header::optional::<String>("if-none-match"))
+        .map(|accept_encoding, if_none_match| Conditional {
+            accept_encoding,
+            if_none_match,
+        })
+        .recover(|_| async { Ok::<_, std::convert::Infallible>(Conditional::default()) })
+        .unify()
+}
+
+/// Strong validator for `body`; identical bodies get identical tags whatever
+/// encoding they're sent in, since the reply varies on Accept-Encoding
+pub fn etag(body: &[u8]) -> String {
+    format!("\"{}\"", hex::encode(&Sha256::digest(body)[..16]))
+}
+
+/// Whether an If-None-Match header already names `etag`
+pub fn matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == "*" || tag == etag)
+}
+
+/// `body` as a reply: 304 when the client's copy is current, otherwise the
+/// body with its ETag, compressed when the client accepts it and it's large
+/// enough to be worth it
+pub fn reply(body: Vec<u8>, content_type: &str, conditional: &Conditional) -> Response<Body> {
+    let tag = etag(&body);
+    let builder = Response::builder()
+        .header(header::ETAG, &tag)
+        .header(header::VARY, "accept-encoding")
+        .header(header::CACHE_CONTROL, "no-cache");
+    
+    if conditional.if_none_match.as_deref().is_some_and(|header| matches(header, &tag)) {
+        return builder.status(StatusCode::NOT_MODIFIED).body(Body::empty()).unwrap();
+    }
+    
+    let builder = builder.header(header::CONTENT_TYPE, content_type);
+    let encoding = if body.len() < MIN_COMPRESSED_BYTES {
+        Encoding::Identity
+    } else {
+        Encoding::negotiate(conditional.accept_encoding.as_deref())
+    };
+    match encoding.encode(&body) {
+        Ok(encoded) if encoding != Encoding::Identity => builder
+            .header(header::CONTENT_ENCODING, encoding.token())
+            .body(Body::from(encoded))
+            .unwrap(),
+        _ => builder.body(Body::from(body)).unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use flate2::read::GzDecoder;
+    
+    #[test]
+    fn test_negotiation_etags_and_not_modified() {
+        assert_eq!(Encoding::negotiate(Some("gzip, deflate, br")), Encoding::Brotli);
+        assert_eq!(Encoding::negotiate(Some("br;q=0.5, gzip")), Encoding::Gzip);
+        assert_eq!(Encoding::negotiate(Some("br;q=0, *")), Encoding::Gzip);
+        assert_eq!(Encoding::negotiate(Some("*;q=0, identity")), Encoding::Identity);
+        assert_eq!(Encoding::negotiate(None), Encoding::Identity);
+        
+        let body = serde_json::to_vec(&vec![serde_json::json!({"path": "BTC_binance -> ETH_binance -> USDT_binance"}); 64]).unwrap();
+        let tag = etag(&body);
+        let fresh = Conditional {
+            accept_encoding: Some("gzip".to_string()),
+            if_none_match: None,
+        };
+        let response = reply(body.clone(), "application/json", &fresh);
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+        assert_eq!(response.headers()[header::ETAG], tag.as_str());
+        
+        let mut decoded = Vec::new();
+        let compressed = Encoding::Gzip.encode(&body).unwrap();
+        assert!(compressed.len() < body.len() / 4);
+        GzDecoder::new(compressed.as_slice()).read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, body);
+        
+        let cached = Conditional {
+            if_none_match: Some(format!("\"stale\", W/{}", tag)),
+            ..fresh
+        };
+        assert_eq!(reply(body.clone(), "application/json", &cached).status(), StatusCode::NOT_MODIFIED);
+        assert!(reply(b"[]".to_vec(), "application/json", &cached).headers().get(header::CONTENT_ENCODING).is_none());
+    }
+}
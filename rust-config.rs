@@ -0,0 +1,1319 @@
+// config.rs - Configuration loading, environment overrides and validation
+use std::collections::HashSet;
+use std::fmt;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+use tracing::warn;
+
+use crate::arbitrage::atomicity::AtomicityClass;
+use crate::arbitrage::Config;
+use crate::dex::PoolSourceKind;
+use crate::exchange::deribit;
+use crate::exchange::ExchangeRegistry;
+use crate::execution::approval::ExecutionMode;
+use crate::execution::eod::parse_time_of_day;
+use crate::execution::report::ReportFormat;
+use crate::execution::router::CompletionStrategy;
+use crate::feetiers;
+use crate::jobs::Cron;
+use crate::markets::FeeAsset;
+use crate::silence::{SilenceSource, DETECTORS};
+use crate::standby::StandbyRole;
+use crate::templates::AlertTemplates;
+
+/// Prefix for environment overrides, e.g. `SCANNER_MIN_PROFIT_THRESHOLD=0.002`
+pub const ENV_PREFIX: &str = "SCANNER_";
+
+/// Earlier prefix, still read; `SCANNER_` variables take precedence over it
+pub const LEGACY_ENV_PREFIX: &str = "ARB_";
+
+/// Separator for nested fields, e.g. `SCANNER_LATENCY_BUDGETS__DETECT=5ms`
+const NESTED_SEPARATOR: &str = "__";
+
+/// Looked for in the working directory when no `--config` is given
+const DEFAULT_FILES: [&str; 3] = ["config.toml", "config.yaml", "config.yml"];
+
+/// Top-level fields a running scanner applies on reload; a change to any
+/// other field is logged and waits for a restart
+pub const RELOADABLE_FIELDS: &[&str] = &[
+    // Feeds: symbols are subscribed and unsubscribed on the open connections,
+    // which also pick up the new timeouts
+    "exchanges",
+    "symbols",
+    "tardis",
+    "websocket_timeout",
+    "reconnect_interval",
+    "max_reconnect_attempts",
+    "subscription_ack_timeout",
+    // Detection
+    "min_profit_threshold",
+    "fees",
+    "transfers",
+    "maker_legs",
+    "atomicity",
+    "detection_backend",
+    "graph_partitions",
+    "hot_detection_interval",
+    "cold_detection_interval",
+    "hot_symbol_ttl",
+    "gpu_detection_min_nodes",
+    "opportunity_cluster_similarity",
+    "structural_spread_after",
+    "structural_blacklist_duration",
+    "fair_value",
+    "lead_lag",
+    "wash_trading",
+    "opportunity_dedup",
+    "bridge",
+    "basis",
+    "stat_arb",
+    // Alerts
+    "alert_templates",
+    "alert_languages",
+];
+
+/// When a running scanner re-reads its config file; SIGHUP always does
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadConfig {
+    /// Also reload whenever the file's modification time changes
+    pub watch: bool,
+    #[serde(with = "humantime_serde")]
+    pub poll_interval: Duration,
+}
+
+impl Default for ReloadConfig {
+    fn default() -> Self {
+        Self {
+            watch: false,
+            poll_interval: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Top-level fields that differ between the running and the reloaded config
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigChanges {
+    pub applied: Vec<String>,
+    pub restart_required: Vec<String>,
+}
+
+impl ConfigChanges {
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty() && self.restart_required.is_empty()
+    }
+}
+
+/// One field that failed validation and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    pub field: String,
+    pub problem: String,
+}
+
+impl ConfigIssue {
+    pub(crate) fn new(field: &str, problem: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            problem: problem.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.problem)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// The config file is unreadable, malformed or doesn't match the schema
+    File { path: String, reason: String },
+    UnknownField { var: String },
+    InvalidValue { var: String, value: String, reason: String },
+    Invalid(Vec<ConfigIssue>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::File { path, reason } => write!(f, "{}: {}", path, reason),
+            ConfigError::UnknownField { var } => {
+                write!(f, "{} does not match any config field", var)
+            }
+            ConfigError::InvalidValue { var, value, reason } => {
+                write!(f, "{}={:?} is invalid: {}", var, value, reason)
+            }
+            ConfigError::Invalid(issues) => {
+                write!(f, "invalid configuration ({} problems)", issues.len())?;
+                for issue in issues {
+                    write!(f, "\n  - {}", issue)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// The `--config` file, else the first of `config.toml`, `config.yaml` and
+/// `config.yml` in the working directory, if any
+pub fn config_file(explicit: Option<&Path>) -> Option<PathBuf> {
+    match explicit {
+        Some(path) => Some(path.to_path_buf()),
+        None => DEFAULT_FILES.iter().map(PathBuf::from).find(|path| path.is_file()),
+    }
+}
+
+/// Overlay the TOML or YAML file at `path` (by extension) onto `config`.
+///
+/// Files only need the fields they change; sections merge field by field, so
+/// `latency_budgets: {detect: 5ms}` keeps the other budgets. Unknown top-level
+/// fields are rejected so a typo doesn't silently fall back to the default.
+pub fn load_file(config: Config, path: &Path) -> Result<Config, ConfigError> {
+    let error = |reason: String| ConfigError::File { path: path.display().to_string(), reason };
+    let text = std::fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+    let overlay: Value = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(&text).map_err(|e| error(e.to_string()))?,
+        Some("yaml" | "yml") => serde_yaml::from_str(&text).map_err(|e| error(e.to_string()))?,
+        _ => return Err(error("expected a .toml, .yaml or .yml file".to_string())),
+    };
+    let Value::Object(fields) = overlay else {
+        return Err(error("expected a mapping of config fields at the top level".to_string()));
+    };
+    
+    let mut tree = serde_json::to_value(&config).map_err(|e| error(e.to_string()))?;
+    for (key, value) in fields {
+        let slot = tree
+            .as_object_mut()
+            .and_then(|tree| tree.get_mut(&key))
+            .ok_or_else(|| error(format!("{:?} is not a config field", key)))?;
+        merge(slot, value);
+    }
+    serde_json::from_value(tree).map_err(|e| error(e.to_string()))
+}
+
+/// Deep-merge `overlay` into `base`; anything but a section replaces the base value
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(slot) => merge(slot, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Overlay `SCANNER_*` variables, and the older `ARB_*` ones, onto `config`.
+///
+/// Any field can be overridden: the variable name is the field name upper-cased,
+/// with `__` between nested levels. Values are parsed according to the field's
+/// current type; lists are comma-separated (`SCANNER_EXCHANGES=binance,kraken`).
+/// Where both prefixes set a field, `SCANNER_` wins. Variables naming no field
+/// are logged and skipped, since the host sets prefixed variables of its own
+pub fn apply_env_overrides<I>(config: Config, vars: I) -> Result<Config, ConfigError>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let vars: Vec<(String, String)> = vars.into_iter().collect();
+    let mut overrides = Vec::new();
+    for prefix in [LEGACY_ENV_PREFIX, ENV_PREFIX] {
+        let mut matching: Vec<&(String, String)> = vars.iter().filter(|(key, _)| key.starts_with(prefix)).collect();
+        // Deterministic order so the first bad variable is always the one reported
+        matching.sort();
+        overrides.extend(matching.into_iter().map(|(var, raw)| Override {
+            source: var.clone(),
+            path: var[prefix.len()..].split(NESTED_SEPARATOR).map(str::to_lowercase).collect(),
+            raw: raw.clone(),
+        }));
+    }
+    apply_overrides(config, overrides, true)
+}
+
+/// Overlay `--set field=value` flags onto `config`, in order.
+///
+/// Nested fields are separated by dots (`latency_budgets.detect=5ms`); values
+/// parse as for environment variables.
+pub fn apply_cli_overrides(config: Config, sets: &[String]) -> Result<Config, ConfigError> {
+    let overrides = sets
+        .iter()
+        .map(|set| {
+            let (field, raw) = set.split_once('=').ok_or_else(|| ConfigError::InvalidValue {
+                var: format!("--set {}", set),
+                value: String::new(),
+                reason: "expected FIELD=VALUE".to_string(),
+            })?;
+            Ok(Override {
+                source: format!("--set {}", field.trim()),
+                path: field.trim().split('.').map(str::to_lowercase).collect(),
+                raw: raw.to_string(),
+            })
+        })
+        .collect::<Result<Vec<_>, ConfigError>>()?;
+    apply_overrides(config, overrides, false)
+}
+
+/// One field override; `source` names it in errors ("SCANNER_DASHBOARD_PORT", "--set dashboard_port")
+struct Override {
+    source: String,
+    path: Vec<String>,
+    raw: String,
+}
+
+/// With `skip_unknown`, overrides naming no field are logged and skipped
+/// rather than rejected
+fn apply_overrides(config: Config, overrides: Vec<Override>, skip_unknown: bool) -> Result<Config, ConfigError> {
+    if overrides.is_empty() {
+        return Ok(config);
+    }
+    let mut tree = serde_json::to_value(&config).map_err(|e| ConfigError::InvalidValue {
+        var: overrides[0].source.clone(),
+        value: String::new(),
+        reason: e.to_string(),
+    })?;
+    
+    for Override { source, path, raw } in &overrides {
+        let Some(slot) = lookup_mut(&mut tree, path) else {
+            if !skip_unknown {
+                return Err(ConfigError::UnknownField { var: source.clone() });
+            }
+            warn!("Ignoring {}: no config field {}", source, path.join("."));
+            continue;
+        };
+        *slot = parse_like(slot, raw).map_err(|reason| ConfigError::InvalidValue {
+            var: source.clone(),
+            value: raw.clone(),
+            reason,
+        })?;
+        
+        // Check each override on its own so type errors name the variable
+        serde_json::from_value::<Config>(tree.clone()).map_err(|e| ConfigError::InvalidValue {
+            var: source.clone(),
+            value: raw.clone(),
+            reason: e.to_string(),
+        })?;
+    }
+    
+    serde_json::from_value(tree).map_err(|e| ConfigError::InvalidValue {
+        var: overrides[0].source.clone(),
+        value: String::new(),
+        reason: e.to_string(),
+    })
+}
+
+/// Which fields `new` changes, split by whether a reload applies them
+pub fn changes(old: &Config, new: &Config) -> ConfigChanges {
+    let (Ok(Value::Object(old)), Ok(Value::Object(new))) = (serde_json::to_value(old), serde_json::to_value(new)) else {
+        return ConfigChanges::default();
+    };
+    
+    let mut changes = ConfigChanges::default();
+    for (field, value) in &new {
+        if old.get(field) == Some(value) {
+            continue;
+        }
+        if RELOADABLE_FIELDS.contains(&field.as_str()) {
+            changes.applied.push(field.clone());
+        } else {
+            changes.restart_required.push(field.clone());
+        }
+    }
+    changes
+}
+
+/// `running` with the reloadable fields taken from `new`: what the scanner
+/// runs after a reload, until a restart picks up the rest
+pub fn apply_reloadable(running: &Config, new: &Config) -> Result<Config, ConfigError> {
+    let error = |e: serde_json::Error| ConfigError::InvalidValue {
+        var: "reload".to_string(),
+        value: String::new(),
+        reason: e.to_string(),
+    };
+    let mut tree = serde_json::to_value(running).map_err(error)?;
+    let reloaded = serde_json::to_value(new).map_err(error)?;
+    for field in RELOADABLE_FIELDS {
+        if let (Some(slot), Some(value)) = (tree.get_mut(*field), reloaded.get(*field)) {
+            *slot = value.clone();
+        }
+    }
+    serde_json::from_value(tree).map_err(error)
+}
+
+/// Full validation run at startup and by `scanner check-config`.
+///
+/// Reports every problem at once rather than stopping at the first, and
+/// includes the dashboard port check, which binds the port briefly.
+pub fn validate(config: &Config, exchanges: &ExchangeRegistry) -> Result<(), ConfigError> {
+    let mut issues = check_fields(config, exchanges);
+    issues.extend(check_port_available(config.dashboard_port));
+    
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid(issues))
+    }
+}
+
+/// Checks that need nothing but the config and the exchanges it can name
+pub fn check_fields(config: &Config, exchanges: &ExchangeRegistry) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    
+    if config.exchanges.is_empty() {
+        issues.push(ConfigIssue::new("exchanges", "must list at least one exchange"));
+    }
+    issues.extend(exchanges.config_issues(config));
+    for (field, patterns) in [("discovery.include", &config.discovery.include), ("discovery.exclude", &config.discovery.exclude)] {
+        for pattern in patterns.iter().filter(|pattern| pattern.matches('/').count() != 1) {
+            issues.push(ConfigIssue::new(field, format!("{:?} is not a BASE/QUOTE pattern like \"*/USDT\"", pattern)));
+        }
+    }
+    let listing_threshold = config.discovery.new_listings.min_profit_threshold;
+    if !(0.0..1.0).contains(&listing_threshold) {
+        issues.push(ConfigIssue::new(
+            "discovery.new_listings.min_profit_threshold",
+            format!("{} is a fraction and must be in [0, 1)", listing_threshold),
+        ));
+    }
+    
+    if config.exchanges.iter().any(|name| name == "tardis") {
+        let tardis = &config.tardis;
+        if !(tardis.machine_url.starts_with("ws://") || tardis.machine_url.starts_with("wss://")) {
+            issues.push(ConfigIssue::new("tardis.machine_url", format!("{:?} must be a ws:// or wss:// URL", tardis.machine_url)));
+        }
+        if tardis.venues.is_empty() {
+            issues.push(ConfigIssue::new("tardis.venues", "must list at least one venue to stream"));
+        }
+        for data_type in &tardis.data_types {
+            if data_type != "quote" && !data_type.starts_with("book_snapshot_") {
+                issues.push(ConfigIssue::new("tardis.data_types", format!("{:?} is not \"quote\" or \"book_snapshot_<levels>_<interval>\"", data_type)));
+            }
+        }
+        if tardis.data_types.is_empty() {
+            issues.push(ConfigIssue::new("tardis.data_types", "must list at least one data type"));
+        }
+    }
+    
+    if config.exchanges.iter().any(|name| name == "deribit") {
+        for instrument in &config.deribit.instruments {
+            if deribit::instrument_symbol(instrument).is_none() {
+                issues.push(ConfigIssue::new("deribit.instruments", format!("{:?} is not a Deribit perpetual, future or spot pair", instrument)));
+            }
+        }
+    }
+    
+    if config.exchanges.iter().any(|name| name == "curve") {
+        let curve = &config.curve;
+        if !(curve.rpc_url.starts_with("ws://") || curve.rpc_url.starts_with("wss://")) {
+            issues.push(ConfigIssue::new("curve.rpc_url", format!("{:?} must be a ws:// or wss:// URL", curve.rpc_url)));
+        }
+        if curve.pools.is_empty() {
+            issues.push(ConfigIssue::new("curve.pools", "must list at least one pool"));
+        }
+        for pool in &curve.pools {
+            let address = pool.address.strip_prefix("0x").unwrap_or_default();
+            if address.len() != 40 || !address.chars().all(|c| c.is_ascii_hexdigit()) {
+                issues.push(ConfigIssue::new("curve.pools", format!("{}: {:?} is not a 0x-prefixed contract address", pool.name, pool.address)));
+            }
+            if pool.coins.len() < 2 {
+                issues.push(ConfigIssue::new("curve.pools", format!("{}: needs at least two coins", pool.name)));
+            }
+            if let Some(coin) = pool.coins.iter().find(|coin| coin.decimals > 30) {
+                issues.push(ConfigIssue::new("curve.pools", format!("{}: {} has {} decimals; at most 30 are supported", pool.name, coin.symbol, coin.decimals)));
+            }
+        }
+        if curve.sizes.is_empty() {
+            issues.push(ConfigIssue::new("curve.sizes", "must list at least one trade size"));
+        }
+        if curve.sizes.iter().any(|size| !(size.is_finite() && *size > 0.0)) {
+            issues.push(ConfigIssue::new("curve.sizes", "must all be above zero"));
+        }
+    }
+    
+    for (exchange, source) in &config.markets.sources {
+        if !config.exchanges.contains(exchange) {
+            issues.push(ConfigIssue::new("markets.sources", format!("{:?} is not a configured exchange", exchange)));
+        }
+        if source.trim().is_empty() {
+            issues.push(ConfigIssue::new("markets.sources", format!("{} has an empty path or URL", exchange)));
+        }
+    }
+    if !config.markets.sources.is_empty() && config.markets.refresh_interval.is_zero() {
+        issues.push(ConfigIssue::new("markets.refresh_interval", "must be greater than zero"));
+    }
+    let mut alias_tickers = HashSet::new();
+    for (i, alias) in config.markets.aliases.iter().enumerate() {
+        let field = format!("markets.aliases[{}]", i);
+        if alias.asset.trim().is_empty() || alias.aliases.is_empty() {
+            issues.push(ConfigIssue::new(&field, "needs an asset and at least one other ticker"));
+        }
+        for ticker in alias.tickers() {
+            if !alias_tickers.insert(ticker) {
+                issues.push(ConfigIssue::new(&field, format!("{} is listed more than once across markets.aliases", ticker)));
+            }
+        }
+        if !(0.0..1.0).contains(&alias.conversion_cost) {
+            issues.push(ConfigIssue::new(
+                &format!("{}.conversion_cost", field),
+                format!("{} is a fraction and must be in [0, 1)", alias.conversion_cost),
+            ));
+        }
+    }
+    
+    if config.symbols.is_empty() {
+        issues.push(ConfigIssue::new("symbols", "must list at least one symbol"));
+    }
+    for symbol in &config.symbols {
+        let valid = matches!(symbol.split_once('/'), Some((base, quote)) if !base.is_empty() && !quote.is_empty() && !quote.contains('/'));
+        if !valid {
+            issues.push(ConfigIssue::new("symbols", format!("{:?} must look like BASE/QUOTE, e.g. BTC/USDT", symbol)));
+        }
+    }
+    
+    if !(0.0..1.0).contains(&config.min_profit_threshold) {
+        issues.push(ConfigIssue::new(
+            "min_profit_threshold",
+            format!("{} is a fraction and must be in [0, 1); 0.001 means 0.1%", config.min_profit_threshold),
+        ));
+    }
+    let mut fee_exchanges: Vec<&String> = config.fees.keys().collect();
+    fee_exchanges.sort();
+    for exchange in fee_exchanges {
+        let rate = &config.fees[exchange];
+        for (side, fee) in [("maker", rate.maker), ("taker", rate.taker)] {
+            // Rebates make maker fees negative on some venues
+            if !(-0.01..0.1).contains(&fee) {
+                issues.push(ConfigIssue::new(
+                    &format!("fees.{}.{}", exchange, side),
+                    format!("{} is a fraction and must be in [-0.01, 0.1); 0.001 means 0.1%", fee),
+                ));
+            }
+        }
+        if let Some(FeeAsset::Token { discount }) = rate.charged_in {
+            if !(0.0..1.0).contains(&discount) {
+                issues.push(ConfigIssue::new(
+                    &format!("fees.{}.charged_in.discount", exchange),
+                    format!("{} is a fraction and must be in [0, 1); 0.25 means 25% off", discount),
+                ));
+            }
+        }
+    }
+    for class in AtomicityClass::ALL {
+        if let Some(&threshold) = config.atomicity.min_profit_threshold.get(&class) {
+            if !(0.0..1.0).contains(&threshold) {
+                issues.push(ConfigIssue::new(
+                    &format!("atomicity.min_profit_threshold.{}", class.name()),
+                    format!("{} is a fraction and must be in [0, 1)", threshold),
+                ));
+            }
+        }
+    }
+    if config.maker_legs.max_maker_legs == 0 {
+        issues.push(ConfigIssue::new("maker_legs.max_maker_legs", "must be at least 1"));
+    }
+    let mut probabilities: Vec<(String, f64)> = config
+        .maker_legs
+        .fill_probability
+        .iter()
+        .map(|(exchange, &p)| (format!("maker_legs.fill_probability.{}", exchange), p))
+        .collect();
+    probabilities.sort_by(|a, b| a.0.cmp(&b.0));
+    probabilities.push(("maker_legs.default_fill_probability".to_string(), config.maker_legs.default_fill_probability));
+    for (field, probability) in probabilities {
+        if !(probability > 0.0 && probability <= 1.0) {
+            issues.push(ConfigIssue::new(&field, format!("{} is a probability and must be in (0, 1]", probability)));
+        }
+    }
+    let mut transfer_assets: Vec<&String> = config.transfers.assets.keys().collect();
+    transfer_assets.sort();
+    for asset in transfer_assets {
+        for (i, chain) in config.transfers.assets[asset].iter().enumerate() {
+            let field = format!("transfers.assets.{}[{}]", asset, i);
+            if chain.chain.trim().is_empty() {
+                issues.push(ConfigIssue::new(&format!("{}.chain", field), "must name the chain, e.g. TRC20"));
+            }
+            for (name, fee) in [("withdrawal_fee", chain.withdrawal_fee), ("network_fee", chain.network_fee)] {
+                if !(fee.is_finite() && fee >= 0.0) {
+                    issues.push(ConfigIssue::new(
+                        &format!("{}.{}", field, name),
+                        format!("{} must be a non-negative amount in the base currency", fee),
+                    ));
+                }
+            }
+        }
+    }
+    if !(config.edge_pruning.margin.is_finite() && config.edge_pruning.margin > 0.0) {
+        issues.push(ConfigIssue::new(
+            "edge_pruning.margin",
+            format!("{} must be a positive multiple of min_profit_threshold", config.edge_pruning.margin),
+        ));
+    }
+    let mut partition_names = HashSet::new();
+    for (i, partition) in config.graph_partitions.iter().enumerate() {
+        let field = format!("graph_partitions[{}]", i);
+        if partition.name.trim().is_empty() || !partition_names.insert(partition.name.as_str()) {
+            issues.push(ConfigIssue::new(&format!("{}.name", field), format!("{:?} must be a unique, non-empty name", partition.name)));
+        }
+        if partition.quotes.is_empty() {
+            issues.push(ConfigIssue::new(&format!("{}.quotes", field), "must list at least one quote currency"));
+        }
+        if let Some(threshold) = partition.min_profit_threshold {
+            if !(0.0..1.0).contains(&threshold) {
+                issues.push(ConfigIssue::new(
+                    &format!("{}.min_profit_threshold", field),
+                    format!("{} is a fraction and must be in [0, 1); 0.001 means 0.1%", threshold),
+                ));
+            }
+        }
+    }
+    if !(config.max_position_size.is_finite() && config.max_position_size > 0.0) {
+        issues.push(ConfigIssue::new("max_position_size", format!("{} must be a positive amount", config.max_position_size)));
+    }
+    if !(0.0..=1.0).contains(&config.opportunity_cluster_similarity) {
+        issues.push(ConfigIssue::new(
+            "opportunity_cluster_similarity",
+            format!("{} is a Jaccard similarity and must be in [0, 1]", config.opportunity_cluster_similarity),
+        ));
+    }
+    if !(config.funding.min_annualized_yield.is_finite() && config.funding.min_annualized_yield >= 0.0) {
+        issues.push(ConfigIssue::new(
+            "funding.min_annualized_yield",
+            format!("{} is a fraction and must be at least 0; 0.1 means 10% a year", config.funding.min_annualized_yield),
+        ));
+    }
+    if !(config.basis.min_annualized_yield.is_finite() && config.basis.min_annualized_yield >= 0.0) {
+        issues.push(ConfigIssue::new(
+            "basis.min_annualized_yield",
+            format!("{} is a fraction and must be at least 0; 0.1 means 10% a year", config.basis.min_annualized_yield),
+        ));
+    }
+    for (i, asset) in config.bridge.assets.iter().enumerate() {
+        if asset.trim().is_empty() || asset.contains('/') {
+            issues.push(ConfigIssue::new(&format!("bridge.assets[{}]", i), format!("{:?} must be a single asset, e.g. USDT", asset)));
+        }
+    }
+    let dedup = &config.opportunity_dedup;
+    if !dedup.window.is_zero() {
+        if dedup.capacity == 0 {
+            issues.push(ConfigIssue::new("opportunity_dedup.capacity", "must be at least 1"));
+        }
+        if !(dedup.false_positive_rate > 0.0 && dedup.false_positive_rate < 1.0) {
+            issues.push(ConfigIssue::new(
+                "opportunity_dedup.false_positive_rate",
+                format!("{} is a probability and must be in (0, 1)", dedup.false_positive_rate),
+            ));
+        }
+    }
+    
+    let fair_value = &config.fair_value;
+    if !(fair_value.outlier_mads.is_finite() && fair_value.outlier_mads > 0.0) {
+        issues.push(ConfigIssue::new("fair_value.outlier_mads", format!("{} must be a positive multiple", fair_value.outlier_mads)));
+    }
+    for (field, fraction) in [
+        ("fair_value.min_outlier_band", fair_value.min_outlier_band),
+        ("fair_value.max_tick_deviation", fair_value.max_tick_deviation),
+    ] {
+        if !(0.0..1.0).contains(&fraction) {
+            issues.push(ConfigIssue::new(field, format!("{} is a fraction and must be in [0, 1)", fraction)));
+        }
+    }
+    
+    let order_book = &config.order_book;
+    if order_book.levels == 0 {
+        issues.push(ConfigIssue::new("order_book.levels", "must be at least 1"));
+    }
+    if !(0.0..1.0).contains(&order_book.max_slippage) {
+        issues.push(ConfigIssue::new("order_book.max_slippage", format!("{} is a fraction and must be in [0, 1)", order_book.max_slippage)));
+    }
+    
+    let lead_lag = &config.lead_lag;
+    if !(lead_lag.move_bps.is_finite() && lead_lag.move_bps > 0.0) {
+        issues.push(ConfigIssue::new("lead_lag.move_bps", format!("{} must be positive", lead_lag.move_bps)));
+    }
+    if !(0.0..=1.0).contains(&lead_lag.min_follow_rate) {
+        issues.push(ConfigIssue::new("lead_lag.min_follow_rate", format!("{} is a fraction and must be in [0, 1]", lead_lag.min_follow_rate)));
+    }
+    if lead_lag.min_lag >= lead_lag.max_lag {
+        issues.push(ConfigIssue::new("lead_lag.min_lag", format!("must be below lead_lag.max_lag ({:?})", lead_lag.max_lag)));
+    }
+    
+    let stat_arb = &config.stat_arb;
+    if stat_arb.min_samples < 2 {
+        issues.push(ConfigIssue::new("stat_arb.min_samples", "must be at least 2"));
+    } else if stat_arb.min_samples > stat_arb.window {
+        issues.push(ConfigIssue::new("stat_arb.min_samples", format!("must be at most stat_arb.window ({})", stat_arb.window)));
+    }
+    if !(stat_arb.exit_z.is_finite() && stat_arb.exit_z >= 0.0) {
+        issues.push(ConfigIssue::new("stat_arb.exit_z", format!("{} must be at least 0", stat_arb.exit_z)));
+    }
+    if !(stat_arb.entry_z.is_finite() && stat_arb.entry_z > stat_arb.exit_z) {
+        issues.push(ConfigIssue::new("stat_arb.entry_z", format!("{} must be above stat_arb.exit_z ({})", stat_arb.entry_z, stat_arb.exit_z)));
+    }
+    for (i, spread) in stat_arb.spreads.iter().enumerate() {
+        if spread.name.trim().is_empty() {
+            issues.push(ConfigIssue::new(&format!("stat_arb.spreads[{}].name", i), "must not be empty"));
+        }
+        if spread.legs.is_empty() {
+            issues.push(ConfigIssue::new(&format!("stat_arb.spreads[{}].legs", i), "must have at least one leg"));
+        }
+        for (j, leg) in spread.legs.iter().enumerate() {
+            if !leg.symbol.contains('/') {
+                issues.push(ConfigIssue::new(&format!("stat_arb.spreads[{}].legs[{}].symbol", i, j), format!("{:?} must be a pair, e.g. ETH/BTC", leg.symbol)));
+            }
+            if !(leg.weight.is_finite() && leg.weight != 0.0) {
+                issues.push(ConfigIssue::new(&format!("stat_arb.spreads[{}].legs[{}].weight", i, j), format!("{} must be a non-zero number", leg.weight)));
+            }
+        }
+    }
+    
+    let wash = &config.wash_trading;
+    if wash.window == 0 {
+        issues.push(ConfigIssue::new("wash_trading.window", "must be at least 1"));
+    } else if wash.min_ticks > wash.window {
+        issues.push(ConfigIssue::new("wash_trading.min_ticks", format!("must be at most wash_trading.window ({})", wash.window)));
+    }
+    if !(wash.move_bps.is_finite() && wash.move_bps > 0.0) {
+        issues.push(ConfigIssue::new("wash_trading.move_bps", format!("{} must be positive", wash.move_bps)));
+    }
+    for (field, share) in [
+        ("wash_trading.max_frozen_share", wash.max_frozen_share),
+        ("wash_trading.max_crossed_share", wash.max_crossed_share),
+        ("wash_trading.confidence_factor", wash.confidence_factor),
+    ] {
+        if !(0.0..=1.0).contains(&share) {
+            issues.push(ConfigIssue::new(field, format!("{} is a fraction and must be in [0, 1]", share)));
+        }
+    }
+    
+    if config.dashboard_port == 0 {
+        issues.push(ConfigIssue::new("dashboard_port", "must be a fixed port, not 0"));
+    }
+    if config.control_token.as_deref() == Some("") {
+        issues.push(ConfigIssue::new("control_token", "is empty; omit it to allow control from this machine only"));
+    }
+    if config.thread_pool_size == 0 {
+        issues.push(ConfigIssue::new("thread_pool_size", "must be at least 1"));
+    }
+    if config.opportunity_history_size == 0 {
+        issues.push(ConfigIssue::new("opportunity_history_size", "must be at least 1"));
+    }
+    for (field, share) in [
+        ("allocation.max_exchange_share", config.allocation.max_exchange_share),
+        ("allocation.max_asset_share", config.allocation.max_asset_share),
+    ] {
+        if !(share > 0.0 && share <= 1.0) {
+            issues.push(ConfigIssue::new(field, format!("{} must be in (0, 1]", share)));
+        }
+    }
+    for (exchange, assets) in &config.allocation.balances {
+        for (asset, value) in assets {
+            if !(value.is_finite() && *value >= 0.0) {
+                issues.push(ConfigIssue::new("allocation.balances", format!("{} {} = {} must be a non-negative amount", exchange, asset, value)));
+            }
+        }
+    }
+    
+    match &config.order_routing.on_partial_fill {
+        CompletionStrategy::Chase { max_price_move, steps } => {
+            if !(*max_price_move > 0.0 && *max_price_move < 1.0) {
+                issues.push(ConfigIssue::new("order_routing.on_partial_fill.max_price_move", format!("{} is a fraction and must be in (0, 1)", max_price_move)));
+            }
+            if *steps == 0 {
+                issues.push(ConfigIssue::new("order_routing.on_partial_fill.steps", "must be at least 1"));
+            }
+        }
+        CompletionStrategy::AlternativeVenue { venues } => {
+            if venues.is_empty() {
+                issues.push(ConfigIssue::new("order_routing.on_partial_fill.venues", "must list at least one exchange"));
+            }
+            for venue in venues.iter().filter(|venue| !config.exchanges.contains(venue)) {
+                issues.push(ConfigIssue::new("order_routing.on_partial_fill.venues", format!("{:?} is not a configured exchange", venue)));
+            }
+        }
+        CompletionStrategy::Unwind => {}
+    }
+    if config.end_of_day.enabled {
+        if parse_time_of_day(&config.end_of_day.at).is_none() {
+            issues.push(ConfigIssue::new("end_of_day.at", format!("{:?} must be a UTC time like 23:55", config.end_of_day.at)));
+        }
+        if config.end_of_day.settle_asset.trim().is_empty() {
+            issues.push(ConfigIssue::new("end_of_day.settle_asset", "must name an asset, e.g. USDT"));
+        }
+    }
+    
+    for (field, value) in [
+        ("websocket_timeout", config.websocket_timeout),
+        ("reconnect_interval", config.reconnect_interval),
+        ("subscription_ack_timeout", config.subscription_ack_timeout),
+        ("hot_detection_interval", config.hot_detection_interval),
+        ("cold_detection_interval", config.cold_detection_interval),
+        ("route_scan_interval", config.route_scan_interval),
+        ("edge_pruning.window", config.edge_pruning.window),
+        ("heat_window", config.heat_window),
+        ("funding.poll_interval", config.funding.poll_interval),
+        ("allocation.report_interval", config.allocation.report_interval),
+        ("stale_orders.max_order_age", config.stale_orders.max_order_age),
+        ("stale_orders.sweep_interval", config.stale_orders.sweep_interval),
+        ("alert_outbox.initial_backoff", config.alert_outbox.initial_backoff),
+        ("alert_outbox.breaker_cooldown", config.alert_outbox.breaker_cooldown),
+        ("alert_outbox.poll_interval", config.alert_outbox.poll_interval),
+        ("config_reload.poll_interval", config.config_reload.poll_interval),
+        ("fair_value.max_quote_age", config.fair_value.max_quote_age),
+        ("lead_lag.max_lag", config.lead_lag.max_lag),
+        ("stat_arb.sample_interval", config.stat_arb.sample_interval),
+        ("standby.heartbeat_interval", config.standby.heartbeat_interval),
+        ("standby.takeover_after", config.standby.takeover_after),
+        ("service.warmup_timeout", config.service.warmup_timeout),
+        ("service.release_timeout", config.service.release_timeout),
+        ("recording.retention", config.recording.retention),
+        ("silence.check_interval", config.silence.check_interval),
+        ("fee_tiers.refresh_interval", config.fee_tiers.refresh_interval),
+        ("dex.poll_interval", config.dex.poll_interval),
+    ] {
+        if value == Duration::ZERO {
+            issues.push(ConfigIssue::new(field, "must be greater than zero"));
+        }
+    }
+    if config.cold_detection_interval < config.hot_detection_interval {
+        issues.push(ConfigIssue::new(
+            "cold_detection_interval",
+            format!(
+                "{:?} is shorter than hot_detection_interval ({:?}); full scans can't run more often than hot scans",
+                config.cold_detection_interval, config.hot_detection_interval
+            ),
+        ));
+    }
+    if config.standby.takeover_after <= config.standby.heartbeat_interval {
+        issues.push(ConfigIssue::new(
+            "standby.takeover_after",
+            format!(
+                "{:?} must be longer than standby.heartbeat_interval ({:?}), or the standby takes over between heartbeats",
+                config.standby.takeover_after, config.standby.heartbeat_interval
+            ),
+        ));
+    }
+    if config.service.handover && config.standby.role != StandbyRole::Solo {
+        issues.push(ConfigIssue::new(
+            "service.handover",
+            "restart a standby pair one scanner at a time instead; handover is for solo scanners",
+        ));
+    }
+    for (job, expression) in config.jobs.schedules() {
+        if let Some(Err(e)) = expression.map(str::parse::<Cron>) {
+            issues.push(ConfigIssue::new(&format!("jobs.{}", job), e));
+        }
+    }
+    if config.jobs.retention.is_some() && !config.recording.enabled {
+        issues.push(ConfigIssue::new("jobs.retention", "trims the recording, so recording.enabled must be on"));
+    }
+    if config.jobs.discovery.is_some() && !config.discovery.enabled {
+        issues.push(ConfigIssue::new("jobs.discovery", "repeats symbol discovery, so discovery.enabled must be on"));
+    }
+    if config.jobs.report_format.parse::<ReportFormat>().is_err() {
+        issues.push(ConfigIssue::new(
+            "jobs.report_format",
+            format!("{:?} is not csv, koinly or cointracking", config.jobs.report_format),
+        ));
+    }
+    if config.alert_outbox.max_backoff < config.alert_outbox.initial_backoff {
+        issues.push(ConfigIssue::new("alert_outbox.max_backoff", "must be at least alert_outbox.initial_backoff"));
+    }
+    if config.alert_outbox.max_attempts == 0 {
+        issues.push(ConfigIssue::new("alert_outbox.max_attempts", "must be at least 1"));
+    }
+    if config.alert_outbox.breaker_threshold == 0 {
+        issues.push(ConfigIssue::new("alert_outbox.breaker_threshold", "must be at least 1"));
+    }
+    if config.display.separators().is_none() {
+        issues.push(ConfigIssue::new("display.locale", format!("{:?} is not a supported locale", config.display.locale)));
+    }
+    if config.display.decimals > 8 {
+        issues.push(ConfigIssue::new("display.decimals", format!("{} is more precision than prices carry; use at most 8", config.display.decimals)));
+    }
+    if let Err(e) = AlertTemplates::from_config(&config.alert_templates, &config.alert_languages, &config.display) {
+        issues.push(ConfigIssue::new(&format!("{}.{}", e.setting, e.channel), e.reason));
+    }
+    if let Some(ntfy) = &config.notifications.ntfy {
+        if ntfy.topic.is_empty() {
+            issues.push(ConfigIssue::new("notifications.ntfy.topic", "must name the topic to publish to"));
+        }
+        if !ntfy.server.starts_with("http://") && !ntfy.server.starts_with("https://") {
+            issues.push(ConfigIssue::new("notifications.ntfy.server", format!("{:?} must be an http(s) URL", ntfy.server)));
+        }
+        if !(1..=5).contains(&ntfy.priority) {
+            issues.push(ConfigIssue::new("notifications.ntfy.priority", format!("{} must be in 1..=5", ntfy.priority)));
+        }
+    }
+    if let Some(slack) = &config.notifications.slack {
+        if !slack.webhook_url.starts_with("https://") {
+            issues.push(ConfigIssue::new("notifications.slack.webhook_url", "must be the app's https incoming webhook"));
+        }
+        if slack.signing_secret.as_deref() == Some("") {
+            issues.push(ConfigIssue::new("notifications.slack.signing_secret", "is empty; omit it to post without buttons"));
+        }
+    }
+    if let Some(batching) = &config.notifications.batching {
+        if batching.interval.is_zero() {
+            issues.push(ConfigIssue::new("notifications.batching.interval", "must be greater than zero"));
+        }
+        if batching.below_profit <= 0.0 {
+            issues.push(ConfigIssue::new("notifications.batching.below_profit", format!("{} would batch nothing", batching.below_profit)));
+        }
+    }
+    if let Some(twilio) = &config.notifications.twilio {
+        if twilio.account_sid.is_empty() || twilio.auth_token.is_empty() {
+            issues.push(ConfigIssue::new("notifications.twilio", "account_sid and auth_token are both required"));
+        }
+        for number in std::iter::once(&twilio.from).chain(&twilio.to) {
+            let e164 = number.len() > 1 && number.starts_with('+') && number[1..].bytes().all(|b| b.is_ascii_digit());
+            if !e164 {
+                issues.push(ConfigIssue::new("notifications.twilio", format!("{:?} must be an E.164 number like +14155550100", number)));
+            }
+        }
+        if twilio.to.is_empty() {
+            issues.push(ConfigIssue::new("notifications.twilio.to", "must list at least one on-call number"));
+        }
+    }
+    if config.approval.mode == ExecutionMode::Manual {
+        if config.approval.ttl == Duration::ZERO {
+            issues.push(ConfigIssue::new("approval.ttl", "must be greater than zero in manual mode"));
+        }
+        if config.approval.min_profit < 0.0 {
+            issues.push(ConfigIssue::new("approval.min_profit", "must not be negative"));
+        }
+    }
+    let chaos = &config.fault_injection.chaos;
+    for (field, rate) in [
+        ("fault_injection.feeds.drop_rate", config.fault_injection.feeds.drop_rate),
+        ("fault_injection.executor.drop_rate", config.fault_injection.executor.drop_rate),
+        ("fault_injection.chaos.disconnect_rate", chaos.disconnect_rate),
+        ("fault_injection.chaos.duplicate_rate", chaos.duplicate_rate),
+        ("fault_injection.chaos.reorder_rate", chaos.reorder_rate),
+    ] {
+        if !(0.0..1.0).contains(&rate) {
+            issues.push(ConfigIssue::new(field, format!("{} must be in [0, 1)", rate)));
+        }
+    }
+    if let Some(bot) = &config.telegram_bot {
+        if bot.bot_token.is_empty() {
+            issues.push(ConfigIssue::new("telegram_bot.bot_token", "is required"));
+        }
+        if bot.allowed_chat_ids.is_empty() {
+            issues.push(ConfigIssue::new("telegram_bot.allowed_chat_ids", "must list at least one chat; the bot ignores all others"));
+        }
+        if bot.poll_timeout == Duration::ZERO {
+            issues.push(ConfigIssue::new("telegram_bot.poll_timeout", "must be greater than zero"));
+        }
+    }
+    if let Some(pushover) = &config.notifications.pushover {
+        if pushover.user_key.is_empty() || pushover.app_token.is_empty() {
+            issues.push(ConfigIssue::new("notifications.pushover", "user_key and app_token are both required"));
+        }
+        if !(-2..=1).contains(&pushover.priority) {
+            issues.push(ConfigIssue::new("notifications.pushover.priority", format!("{} must be in -2..=1", pushover.priority)));
+        }
+    }
+    for (exchange, credentials) in &config.fee_tiers.accounts {
+        let field = format!("fee_tiers.accounts.{}", exchange);
+        if !feetiers::SUPPORTED.contains(&exchange.as_str()) {
+            issues.push(ConfigIssue::new(&field, format!("fee tier detection supports {}", feetiers::SUPPORTED.join(", "))));
+        } else if !config.exchanges.contains(exchange) {
+            issues.push(ConfigIssue::new(&field, "is not a configured exchange"));
+        }
+        if credentials.api_key.is_empty() || credentials.api_secret.is_empty() {
+            issues.push(ConfigIssue::new(&field, "api_key and api_secret are both required"));
+        }
+    }
+    let dex = &config.dex;
+    if dex.enabled {
+        if dex.pools.is_empty() {
+            issues.push(ConfigIssue::new("dex.pools", "at least one pool is required when dex is enabled"));
+        }
+        match dex.source {
+            PoolSourceKind::Rpc if dex.rpc_url.is_empty() => {
+                issues.push(ConfigIssue::new("dex.rpc_url", "is required for the rpc source"));
+            }
+            PoolSourceKind::Subgraph if dex.subgraph_url.is_empty() => {
+                issues.push(ConfigIssue::new("dex.subgraph_url", "is required for the subgraph source"));
+            }
+            _ => {}
+        }
+        if dex.gas.gas_price_gwei.is_none() && dex.rpc_url.is_empty() {
+            issues.push(ConfigIssue::new("dex.rpc_url", "is required to read gas prices when dex.gas.gas_price_gwei is unset"));
+        }
+        if dex.gas.gas_price_gwei.is_some_and(|gwei| !gwei.is_finite() || gwei < 0.0) {
+            issues.push(ConfigIssue::new("dex.gas.gas_price_gwei", "must not be negative"));
+        }
+        if !(dex.gas.trade_size.is_finite() && dex.gas.trade_size > 0.0) {
+            issues.push(ConfigIssue::new("dex.gas.trade_size", "must be greater than zero"));
+        }
+    }
+    for pool in &dex.pools {
+        let field = format!("dex.pools.{}", pool.address);
+        let hex = pool.address.strip_prefix("0x").unwrap_or_default();
+        if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            issues.push(ConfigIssue::new(&field, "address must be 0x followed by 40 hex digits"));
+        }
+        if !(0.0..1.0).contains(&pool.fee) {
+            issues.push(ConfigIssue::new(&field, format!("fee {} must be in [0, 1)", pool.fee)));
+        }
+        if pool.token0.symbol.is_empty() || pool.token1.symbol.is_empty() {
+            issues.push(ConfigIssue::new(&field, "token0 and token1 both need a symbol"));
+        }
+    }
+    for watch in &config.silence.watches {
+        match watch.source {
+            SilenceSource::Exchange if !config.exchanges.contains(&watch.name) => {
+                issues.push(ConfigIssue::new("silence.watches", format!("{:?} is not a configured exchange", watch.name)));
+            }
+            SilenceSource::Detector if !DETECTORS.contains(&watch.name.as_str()) => {
+                issues.push(ConfigIssue::new(
+                    "silence.watches",
+                    format!("{:?} is not a detector ({})", watch.name, DETECTORS.join(", ")),
+                ));
+            }
+            _ => {}
+        }
+        if watch.after == Duration::ZERO {
+            issues.push(ConfigIssue::new("silence.watches", format!("{:?} needs an `after` greater than zero", watch.name)));
+        }
+    }
+    
+    let compliance = &config.compliance;
+    for exchange in &compliance.restricted_exchanges {
+        if compliance.allowed_exchanges.contains(exchange) {
+            issues.push(ConfigIssue::new(
+                "compliance.allowed_exchanges",
+                format!("{:?} is also in compliance.restricted_exchanges", exchange),
+            ));
+        }
+    }
+    if !compliance.allowed_exchanges.is_empty()
+        && !config.exchanges.iter().any(|e| compliance.allowed_exchanges.contains(e))
+    {
+        issues.push(ConfigIssue::new(
+            "compliance.allowed_exchanges",
+            "none of the configured exchanges are allowed, so nothing can ever be traded",
+        ));
+    }
+    
+    issues
+}
+
+fn check_port_available(port: u16) -> Option<ConfigIssue> {
+    if port == 0 {
+        return None; // Already reported by check_fields
+    }
+    
+    TcpListener::bind(("0.0.0.0", port)).err().map(|e| {
+        ConfigIssue::new("dashboard_port", format!("port {} is not available: {}", port, e))
+    })
+}
+
+fn lookup_mut<'a>(tree: &'a mut Value, path: &[String]) -> Option<&'a mut Value> {
+    let mut node = tree;
+    for key in path {
+        node = node.as_object_mut()?.get_mut(key)?;
+    }
+    Some(node)
+}
+
+/// Parse `raw` into the same JSON shape as the field's current value
+fn parse_like(current: &Value, raw: &str) -> Result<Value, String> {
+    match current {
+        Value::Bool(_) => raw
+            .trim()
+            .parse::<bool>()
+            .map(Value::Bool)
+            .map_err(|_| "expected true or false".to_string()),
+        Value::Number(n) => parse_number(n, raw.trim()),
+        Value::Array(items) => {
+            let element = items.first().cloned().unwrap_or(Value::String(String::new()));
+            raw.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| parse_like(&element, item))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array)
+        }
+        Value::Object(_) => Err("is a section; set its fields individually with __".to_string()),
+        Value::String(_) | Value::Null => Ok(Value::String(raw.to_string())),
+    }
+}
+
+fn parse_number(current: &Number, raw: &str) -> Result<Value, String> {
+    if current.is_f64() {
+        raw.parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| "expected a number".to_string())
+    } else if current.is_u64() {
+        raw.parse::<u64>()
+            .map(|v| Value::Number(v.into()))
+            .map_err(|_| "expected a non-negative integer".to_string())
+    } else {
+        raw.parse::<i64>()
+            .map(|v| Value::Number(v.into()))
+            .map_err(|_| "expected an integer".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+    
+    #[test]
+    fn test_overrides_scalars_lists_and_nested_fields() {
+        let config = apply_env_overrides(
+            Config::default(),
+            vars(&[
+                ("ARB_MIN_PROFIT_THRESHOLD", "0.002"),
+                ("ARB_EXCHANGES", "binance, kraken"),
+                ("ARB_DASHBOARD_PORT", "9090"),
+                ("ARB_ENABLE_CROSS_EXCHANGE", "false"),
+                ("ARB_WEBSOCKET_TIMEOUT", "45s"),
+                ("ARB_LATENCY_BUDGETS__DETECT", "5ms"),
+                ("ARB_COMPLIANCE__RESTRICTED_ASSETS", "XMR"),
+                ("ARB_DETECTION_BACKEND", "johnson"),
+                ("PATH", "/usr/bin"),
+            ]),
+        )
+        .unwrap();
+        
+        assert_eq!(config.min_profit_threshold, 0.002);
+        assert_eq!(config.exchanges, vec!["binance", "kraken"]);
+        assert_eq!(config.dashboard_port, 9090);
+        assert!(!config.enable_cross_exchange);
+        assert_eq!(config.websocket_timeout, Duration::from_secs(45));
+        assert_eq!(config.latency_budgets.detect, Duration::from_millis(5));
+        assert_eq!(config.compliance.restricted_assets, vec!["XMR"]);
+        assert_eq!(config.detection_backend, crate::arbitrage::DetectionBackend::Johnson);
+    }
+    
+    fn write_temp(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+    
+    #[test]
+    fn test_loads_partial_yaml_and_toml_over_defaults() {
+        let yaml = write_temp(
+            "config.yaml",
+            "exchanges: [binance, kraken]\nmin_profit_threshold: 0.002\nwebsocket_timeout: 45s\nlatency_budgets:\n  detect: 5ms\n",
+        );
+        let config = load_file(Config::default(), &yaml).unwrap();
+        assert_eq!(config.exchanges, vec!["binance", "kraken"]);
+        assert_eq!(config.min_profit_threshold, 0.002);
+        assert_eq!(config.websocket_timeout, Duration::from_secs(45));
+        assert_eq!(config.latency_budgets.detect, Duration::from_millis(5));
+        assert_eq!(config.latency_budgets.parse, Config::default().latency_budgets.parse);
+        assert_eq!(config.symbols, Config::default().symbols);
+        
+        let toml = write_temp(
+            "config.toml",
+            "dashboard_port = 9090\ndetection_backend = \"johnson\"\n\n[compliance]\nrestricted_assets = [\"XMR\"]\n",
+        );
+        let config = load_file(Config::default(), &toml).unwrap();
+        assert_eq!(config.dashboard_port, 9090);
+        assert_eq!(config.detection_backend, crate::arbitrage::DetectionBackend::Johnson);
+        assert_eq!(config.compliance.restricted_assets, vec!["XMR"]);
+        
+        let _ = std::fs::remove_file(yaml);
+        let _ = std::fs::remove_file(toml);
+    }
+    
+    #[test]
+    fn test_bad_files_name_the_problem() {
+        for (name, contents, problem) in [
+            ("typo.yaml", "min_profit: 0.1\n", "\"min_profit\" is not a config field"),
+            ("type.yaml", "dashboard_port: eighty\n", "invalid type"),
+            ("config.json", "{}", "expected a .toml, .yaml or .yml file"),
+        ] {
+            let path = write_temp(name, contents);
+            match load_file(Config::default(), &path) {
+                Err(ConfigError::File { reason, .. }) => assert!(reason.contains(problem), "{}: {}", name, reason),
+                other => panic!("{} gave {:?}", name, other.map(|_| ())),
+            }
+            let _ = std::fs::remove_file(path);
+        }
+    }
+    
+    #[test]
+    fn test_precedence_is_cli_then_env_then_file() {
+        let file = write_temp("precedence.yaml", "dashboard_port: 7000\nmin_profit_threshold: 0.003\nsymbols: [BTC/USDT]\n");
+        let config = load_file(Config::default(), &file).unwrap();
+        let config = apply_env_overrides(
+            config,
+            vars(&[
+                ("ARB_DASHBOARD_PORT", "7100"),
+                ("SCANNER_DASHBOARD_PORT", "7200"),
+                ("SCANNER_MIN_PROFIT_THRESHOLD", "0.004"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(config.dashboard_port, 7200, "SCANNER_ beats ARB_");
+        
+        let sets = vec!["min_profit_threshold=0.005".to_string(), "latency_budgets.detect=5ms".to_string()];
+        let config = apply_cli_overrides(config, &sets).unwrap();
+        assert_eq!(config.min_profit_threshold, 0.005);
+        assert_eq!(config.latency_budgets.detect, Duration::from_millis(5));
+        assert_eq!(config.dashboard_port, 7200);
+        assert_eq!(config.symbols, vec!["BTC/USDT"]);
+        let _ = std::fs::remove_file(file);
+        
+        let err = apply_cli_overrides(Config::default(), &["min_profit=0.1".to_string()]).unwrap_err();
+        assert_eq!(err, ConfigError::UnknownField { var: "--set min_profit".to_string() });
+        assert!(apply_cli_overrides(Config::default(), &["dashboard_port".to_string()]).is_err());
+    }
+    
+    #[test]
+    fn test_reload_applies_only_reloadable_fields() {
+        let running = Config::default();
+        let new = Config {
+            symbols: vec!["BTC/USDT".to_string()],
+            min_profit_threshold: 0.004,
+            dashboard_port: 9090,
+            ..Config::default()
+        };
+        
+        let diff = changes(&running, &new);
+        assert_eq!(diff.applied, vec!["min_profit_threshold", "symbols"]);
+        assert_eq!(diff.restart_required, vec!["dashboard_port"]);
+        assert!(changes(&running, &running).is_empty());
+        
+        let effective = apply_reloadable(&running, &new).unwrap();
+        assert_eq!(effective.symbols, new.symbols);
+        assert_eq!(effective.min_profit_threshold, 0.004);
+        assert_eq!(effective.dashboard_port, running.dashboard_port);
+    }
+    
+    #[test]
+    fn test_default_config_is_valid() {
+        assert!(check_fields(&Config::default(), &ExchangeRegistry::builtin()).is_empty());
+    }
+    
+    #[test]
+    fn test_validation_reports_every_bad_field() {
+        let config = Config {
+            exchanges: vec!["binance".to_string(), "ftx".to_string()],
+            symbols: vec!["BTCUSDT".to_string()],
+            discovery: crate::exchange::discovery::DiscoveryConfig {
+                include: vec!["USDT".to_string()],
+                ..Default::default()
+            },
+            min_profit_threshold: 1.5,
+            fees: std::collections::HashMap::from([(
+                "binance".to_string(),
+                crate::markets::Fees {
+                    maker: 0.001,
+                    taker: 0.1,
+                    charged_in: Some(FeeAsset::Token { discount: 1.0 }),
+                },
+            )]),
+            cold_detection_interval: Duration::from_micros(500),
+            markets: crate::markets::MarketsConfig {
+                aliases: {
+                    let mut aliases = crate::markets::AssetAlias::known();
+                    let mut wrapped = aliases[2].clone();
+                    wrapped.asset = "CBBTC".to_string();
+                    aliases.push(wrapped);
+                    aliases
+                },
+                ..Default::default()
+            },
+            compliance: crate::compliance::ComplianceConfig {
+                allowed_exchanges: vec!["kraken".to_string()],
+                ..Default::default()
+            },
+            jobs: crate::jobs::JobsConfig {
+                reports: Some("0 25 * * *".to_string()),
+                ..Default::default()
+            },
+            ..Config::default()
+        };
+        
+        let fields: Vec<String> = check_fields(&config, &ExchangeRegistry::builtin()).into_iter().map(|issue| issue.field).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "exchanges",
+                "discovery.include",
+                "markets.aliases[4]",
+                "symbols",
+                "min_profit_threshold",
+                "fees.binance.taker",
+                "fees.binance.charged_in.discount",
+                "cold_detection_interval",
+                "jobs.reports",
+                "compliance.allowed_exchanges",
+            ]
+        );
+    }
+    
+    #[test]
+    fn test_port_in_use_is_reported() {
+        let listener = TcpListener::bind(("0.0.0.0", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        
+        let issue = check_port_available(port).unwrap();
+        assert_eq!(issue.field, "dashboard_port");
+        drop(listener);
+    }
+    
+    #[test]
+    fn test_unknown_variables_are_skipped() {
+        let config = apply_env_overrides(
+            Config::default(),
+            vars(&[("ARB_MIN_PROFIT", "0.1"), ("ARB_CLUSTER_ID", "eu-1"), ("ARB_MIN_PROFIT_THRESHOLD", "0.003")]),
+        )
+        .unwrap();
+        assert_eq!(config.min_profit_threshold, 0.003);
+    }
+    
+    #[test]
+    fn test_kubernetes_service_variables_are_skipped() {
+        let config = apply_env_overrides(
+            Config::default(),
+            vars(&[
+                ("SCANNER_SERVICE_HOST", "10.96.0.12"),
+                ("SCANNER_SERVICE_PORT", "8080"),
+                ("SCANNER_PORT", "tcp://10.96.0.12:8080"),
+                ("SCANNER_SYMBOLS", "BTC/USDT"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(config.symbols, vec!["BTC/USDT"]);
+        assert_eq!(config.dashboard_port, Config::default().dashboard_port);
+    }
+    
+    #[test]
+    fn test_invalid_values_name_the_variable() {
+        for (var, value) in [
+            ("ARB_DASHBOARD_PORT", "eighty"),
+            ("ARB_DASHBOARD_PORT", "70000"),
+            ("ARB_RECONNECT_INTERVAL", "soon"),
+            ("ARB_DETECTION_BACKEND", "dijkstra"),
+        ] {
+            match apply_env_overrides(Config::default(), vars(&[(var, value)])) {
+                Err(ConfigError::InvalidValue { var: reported, .. }) => assert_eq!(reported, var),
+                other => panic!("{}={} gave {:?}", var, value, other.map(|_| ())),
+            }
+        }
+    }
+}
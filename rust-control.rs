@@ -0,0 +1,534 @@
+// control.rs - Operator actions on opportunities (approve, paper execute, blacklist, snooze)
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::arbitrage::quotes::VenueQuote;
+use crate::arbitrage::types::{ArbitrageOpportunity, OpportunityKind};
+use crate::arbitrage::ArbitrageEngine;
+use crate::compliance::{ComplianceGate, ComplianceStage};
+use crate::display::DisplayFormat;
+use crate::execution::approval::ApprovalQueue;
+use crate::execution::locks::{ExecutionLocks, LockConfig};
+use crate::execution::orders::{NewOrder, OrderEvent, OrderStore};
+use crate::execution::router::{RouteOutcome, SmartOrderRouter};
+use crate::execution::{ExecutionJournal, PaperTrade, Side};
+use crate::faults::{FaultInjector, FaultStats};
+
+/// How far back "execute paper" looks for the opportunity it was shown
+const RECENT_LOOKUP: usize = 1000;
+const MAX_SNOOZE: Duration = Duration::from_secs(7 * 24 * 3600);
+pub const DEFAULT_SNOOZE: Duration = Duration::from_secs(3600);
+
+fn default_snooze() -> Duration {
+    DEFAULT_SNOOZE
+}
+
+/// Human-in-the-loop decision about an alerted opportunity, from the control
+/// API or a chat button
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ControlAction {
+    /// Execute an opportunity queued for manual confirmation
+    Approve { id: u64 },
+    /// Drop it from the queue unexecuted
+    Reject { id: u64 },
+    /// Record a simulated fill at the detected size and profit
+    ExecutePaper { path: String },
+    /// Stop alerting on the path as if it had been found structural
+    BlacklistPath { path: String },
+    /// Stop alerting on anything that trades `pair` ("ETH/BTC") for a while
+    SnoozePair {
+        pair: String,
+        #[serde(default = "default_snooze", with = "humantime_serde")]
+        duration: Duration,
+    },
+}
+
+/// A hand-built trade submitted for execution, e.g. by an external signal
+/// system, rather than one the scanner detected
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CustomTrade {
+    /// Executed in order; a buy of BTC/USDT takes USDT to BTC on its exchange
+    pub legs: Vec<CustomLeg>,
+    /// Expected profit as a fraction of the first leg's notional, for the journal
+    #[serde(default)]
+    pub expected_profit: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CustomLeg {
+    pub exchange: String,
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: f64,
+    pub limit_price: f64,
+}
+
+impl CustomTrade {
+    /// The trade as an opportunity, so compliance and locking see its nodes
+    fn opportunity(&self) -> Result<ArbitrageOpportunity, String> {
+        let first = self.legs.first().ok_or("a trade needs at least one leg")?;
+        let mut nodes: Vec<String> = Vec::new();
+        let mut exchanges: Vec<String> = Vec::new();
+        for (i, leg) in self.legs.iter().enumerate() {
+            let Some((base, quote)) = leg.symbol.split_once('/').filter(|(b, q)| !b.is_empty() && !q.is_empty()) else {
+                return Err(format!("leg {}: {:?} is not a pair like BTC/USDT", i + 1, leg.symbol));
+            };
+            if !(leg.quantity.is_finite() && leg.quantity > 0.0 && leg.limit_price.is_finite() && leg.limit_price > 0.0) {
+                return Err(format!("leg {}: quantity and limit_price must be positive", i + 1));
+            }
+            let (from, to) = match leg.side {
+                Side::Buy => (quote, base),
+                Side::Sell => (base, quote),
+            };
+            for node in [format!("{}_{}", from, leg.exchange), format!("{}_{}", to, leg.exchange)] {
+                if nodes.last() != Some(&node) {
+                    nodes.push(node);
+                }
+            }
+            if !exchanges.contains(&leg.exchange) {
+                exchanges.push(leg.exchange.clone());
+            }
+        }
+        Ok(ArbitrageOpportunity {
+            kind: OpportunityKind::Cycle,
+            path: nodes.join(" -> "),
+            profit_percentage: self.expected_profit,
+            gross_profit: self.expected_profit,
+            max_volume: first.quantity * first.limit_price,
+            confidence: 100,
+            detected_at: Instant::now(),
+            exchanges,
+        })
+    }
+    
+    fn orders(&self, path: &str) -> Vec<NewOrder> {
+        self.legs
+            .iter()
+            .map(|leg| NewOrder {
+                opportunity_path: path.to_string(),
+                exchange: leg.exchange.clone(),
+                symbol: leg.symbol.clone(),
+                side: leg.side,
+                quantity: leg.quantity,
+                limit_price: Some(leg.limit_price),
+            })
+            .collect()
+    }
+}
+
+/// Who may act through the dashboard's control routes: requests carrying the
+/// configured bearer token or, with none configured, requests from this machine
+pub struct ControlAccess {
+    token: Option<String>,
+}
+
+impl ControlAccess {
+    pub fn new(token: Option<String>) -> Self {
+        Self { token }
+    }
+    
+    /// Whether a request with this `Authorization` header from `remote` may act
+    pub fn permits(&self, authorization: Option<&str>, remote: Option<SocketAddr>) -> bool {
+        match &self.token {
+            Some(token) => authorization
+                .and_then(|header| header.strip_prefix("Bearer "))
+                .is_some_and(|given| same_token(given.as_bytes(), token.as_bytes())),
+            None => remote.is_some_and(|addr| addr.ip().is_loopback()),
+        }
+    }
+}
+
+/// Compare without stopping at the first differing byte
+fn same_token(given: &[u8], expected: &[u8]) -> bool {
+    given.len() == expected.len() && given.iter().zip(expected).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+pub struct ControlPlane {
+    engine: Arc<ArbitrageEngine>,
+    journal: Arc<ExecutionJournal>,
+    approvals: Arc<ApprovalQueue>,
+    compliance: Arc<ComplianceGate>,
+    display: DisplayFormat,
+    executor_faults: Option<FaultInjector>,
+    locks: ExecutionLocks,
+    orders: Option<Arc<OrderStore>>,
+    router: Option<Arc<SmartOrderRouter>>,
+}
+
+impl ControlPlane {
+    pub fn new(
+        engine: Arc<ArbitrageEngine>,
+        journal: Arc<ExecutionJournal>,
+        approvals: Arc<ApprovalQueue>,
+        compliance: Arc<ComplianceGate>,
+        display: DisplayFormat,
+    ) -> Self {
+        Self {
+            engine,
+            journal,
+            approvals,
+            compliance,
+            display,
+            executor_faults: None,
+            locks: ExecutionLocks::new(LockConfig::default()),
+            orders: None,
+            router: None,
+        }
+    }
+    
+    /// Delay or lose execution acknowledgments, for robustness testing
+    pub fn with_executor_faults(mut self, faults: FaultInjector) -> Self {
+        self.executor_faults = Some(faults);
+        self
+    }
+    
+    /// Reject or queue executions that overlap one already in flight
+    pub fn with_locks(mut self, config: LockConfig) -> Self {
+        self.locks = ExecutionLocks::new(config);
+        self
+    }
+    
+    /// Track each paper trade's legs through the persisted order state machine
+    pub fn with_orders(mut self, orders: Arc<OrderStore>) -> Self {
+        self.orders = Some(orders);
+        self
+    }
+    
+    /// Place legs through the router, which completes partial fills, instead
+    /// of filling them in full at their limit
+    pub fn with_router(mut self, router: Arc<SmartOrderRouter>) -> Self {
+        self.router = Some(router);
+        self
+    }
+    
+    pub fn executor_fault_stats(&self) -> Option<FaultStats> {
+        self.executor_faults.as_ref().map(|faults| faults.stats())
+    }
+    
+    pub fn approvals(&self) -> Arc<ApprovalQueue> {
+        Arc::clone(&self.approvals)
+    }
+    
+    /// Apply `action`; the message is meant for whoever pressed the button
+    pub async fn apply(&self, action: ControlAction) -> Result<String, String> {
+        info!("Control action: {:?}", action);
+        match action {
+            ControlAction::Approve { id } => {
+                let opportunity = self.approvals.approve(id, unix_ms()).map_err(|e| e.to_string())?;
+                // Approval is consent, not an exemption from the execution-stage rules
+                if let Err(violation) = self.compliance.evaluate(&opportunity, ComplianceStage::Execution) {
+                    return Err(format!("approval #{} blocked by compliance: {}", id, violation));
+                }
+                Ok(format!("Approved #{}. {}", id, self.paper_trade(&opportunity).await?))
+            }
+            ControlAction::Reject { id } => {
+                let opportunity = self.approvals.reject(id).map_err(|e| e.to_string())?;
+                Ok(format!("Rejected #{} ({})", id, opportunity.path))
+            }
+            ControlAction::ExecutePaper { path } => {
+                let recent = self.engine.get_recent_opportunities(RECENT_LOOKUP).await;
+                match recent.into_iter().find(|opp| opp.path == path) {
+                    Some(opportunity) => self.paper_trade(&opportunity).await,
+                    None => Err(format!("no recent opportunity on {}", path)),
+                }
+            }
+            ControlAction::BlacklistPath { path } => {
+                if self.engine.blacklist_path(&path).await {
+                    Ok(format!("Blacklisted {}", path))
+                } else {
+                    Ok(format!("{} was already blacklisted; extended", path))
+                }
+            }
+            ControlAction::SnoozePair { pair, duration } => {
+                if pair.split('/').filter(|asset| !asset.trim().is_empty()).count() != 2 {
+                    return Err(format!("'{}' is not a pair like ETH/BTC", pair));
+                }
+                if duration.is_zero() || duration > MAX_SNOOZE {
+                    return Err(format!("snooze must be between 1s and {}h", MAX_SNOOZE.as_secs() / 3600));
+                }
+                self.engine.controls().snooze_pair(&pair, self.engine.clock().now() + duration);
+                Ok(format!("Snoozed {} for {}m", pair.to_uppercase(), duration.as_secs().div_ceil(60)))
+            }
+        }
+    }
+    
+    /// Execute a trade the scanner didn't detect, under the same compliance,
+    /// locking and order tracking as one it did
+    pub async fn execute_custom(&self, trade: CustomTrade) -> Result<String, String> {
+        let opportunity = trade.opportunity()?;
+        info!("Custom trade: {}", opportunity.path);
+        if let Err(violation) = self.compliance.evaluate(&opportunity, ComplianceStage::Execution) {
+            return Err(format!("blocked by compliance: {}", violation));
+        }
+        let plan = trade.orders(&opportunity.path);
+        self.execute(&opportunity, Some(plan)).await
+    }
+    
+    /// Simulated execution at the detected size and profit; the only executor
+    /// the scanner has
+    async fn paper_trade(&self, opportunity: &ArbitrageOpportunity) -> Result<String, String> {
+        let plan = match self.orders {
+            Some(_) => self.plan_legs(opportunity).await,
+            None => None,
+        };
+        self.execute(opportunity, plan).await
+    }
+    
+    async fn execute(&self, opportunity: &ArbitrageOpportunity, plan: Option<Vec<NewOrder>>) -> Result<String, String> {
+        // Held until the fill is recorded, so overlapping trades can't both use the same inventory
+        let _guard = self.locks.acquire(opportunity).await.map_err(|e| format!("not executed: {}", e))?;
+        let legs = self.submit_legs(opportunity, plan);
+        if let Some(faults) = &self.executor_faults {
+            if !faults.apply().await {
+                // Legs stay submitted; reconciliation settles them on the next start
+                return Err(format!("no acknowledgment for {} (injected fault)", opportunity.path));
+            }
+        }
+        if let Some(router) = &self.router {
+            for (id, _) in legs {
+                match router.execute(id, unix_ms()).await {
+                    RouteOutcome::Filled | RouteOutcome::Completed { .. } => {}
+                    outcome => warn!("Paper order #{} of {}: {:?}", id, opportunity.path, outcome),
+                }
+            }
+        } else if let Some(orders) = &self.orders {
+            for (id, order) in legs {
+                let fill = OrderEvent::Filled { quantity: order.quantity, price: order.limit_price.unwrap_or_default() };
+                for event in [OrderEvent::Acked { exchange_order_id: format!("paper-{}", id) }, fill] {
+                    if let Err(e) = orders.apply(id, event, unix_ms()) {
+                        warn!("Paper order #{}: {}", id, e);
+                    }
+                }
+            }
+        }
+        let expected_profit = opportunity.profit_percentage * opportunity.max_volume;
+        self.journal.record_paper_trade(PaperTrade {
+            opportunity_path: opportunity.path.clone(),
+            exchanges: opportunity.exchanges.clone(),
+            notional: opportunity.max_volume,
+            expected_profit,
+            executed_at_ms: unix_ms(),
+        });
+        Ok(format!(
+            "Paper trade {} on {}: expected {}",
+            self.display.amount(opportunity.max_volume),
+            opportunity.path,
+            self.display.amount(expected_profit)
+        ))
+    }
+    
+    /// Create and submit `opportunity`'s legs, if orders are tracked and every
+    /// leg has a price
+    fn submit_legs(&self, opportunity: &ArbitrageOpportunity, plan: Option<Vec<NewOrder>>) -> Vec<(u64, NewOrder)> {
+        let Some(orders) = &self.orders else { return Vec::new() };
+        let Some(plan) = plan else {
+            warn!("No quotes to price every leg of {}; paper trade has no orders", opportunity.path);
+            return Vec::new();
+        };
+        plan.into_iter()
+            .map(|order| {
+                let id = orders.create(order.clone(), unix_ms());
+                if let Err(e) = orders.apply(id, OrderEvent::Submitted, unix_ms()) {
+                    warn!("Paper order #{}: {}", id, e);
+                }
+                (id, order)
+            })
+            .collect()
+    }
+    
+    /// Legs at the latest quotes, starting with `max_volume` of the first node's
+    /// asset: each same-exchange hop sells into a bid or buys at an ask;
+    /// cross-exchange hops are transfers and need no order
+    async fn plan_legs(&self, opportunity: &ArbitrageOpportunity) -> Option<Vec<NewOrder>> {
+        let nodes: Vec<(&str, &str)> = opportunity.path.split(" -> ").filter_map(|node| node.split_once('_')).collect();
+        let mut amount = opportunity.max_volume;
+        let mut legs = Vec::new();
+        
+        for i in 0..nodes.len() {
+            let (from, exchange) = nodes[i];
+            let (to, next_exchange) = nodes[(i + 1) % nodes.len()];
+            if exchange != next_exchange || from == to {
+                continue;
+            }
+            let leg = |symbol: String, side: Side, quantity: f64, price: f64| NewOrder {
+                opportunity_path: opportunity.path.clone(),
+                exchange: exchange.to_string(),
+                symbol,
+                side,
+                quantity,
+                limit_price: Some(price),
+            };
+            
+            let sell = format!("{}/{}", from, to);
+            let buy = format!("{}/{}", to, from);
+            if let Some(bid) = self.quote(&sell, exchange).await.map(|quote| quote.bid).filter(|bid| *bid > 0.0) {
+                legs.push(leg(sell, Side::Sell, amount, bid));
+                amount *= bid;
+            } else {
+                let ask = self.quote(&buy, exchange).await.map(|quote| quote.ask).filter(|ask| *ask > 0.0)?;
+                amount /= ask;
+                legs.push(leg(buy, Side::Buy, amount, ask));
+            }
+        }
+        Some(legs)
+    }
+    
+    async fn quote(&self, symbol: &str, exchange: &str) -> Option<VenueQuote> {
+        let snapshot = self.engine.get_market_snapshot(symbol).await?;
+        snapshot.quotes.into_iter().find(|quote| quote.exchange == exchange)
+    }
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrage::Config;
+    use crate::compliance::ComplianceConfig;
+    use crate::execution::approval::{ApprovalConfig, ExecutionMode};
+    use crate::execution::orders::OrderState;
+    
+    fn plane(engine: Arc<ArbitrageEngine>, journal: Arc<ExecutionJournal>, compliance: ComplianceConfig) -> ControlPlane {
+        let approvals = ApprovalQueue::new(ApprovalConfig { mode: ExecutionMode::Manual, ..ApprovalConfig::default() });
+        ControlPlane::new(
+            engine,
+            journal,
+            Arc::new(approvals),
+            Arc::new(ComplianceGate::from_config(&compliance)),
+            DisplayFormat::default(),
+        )
+    }
+    
+    #[test]
+    fn test_action_json() {
+        let action: ControlAction = serde_json::from_str(r#"{"action":"snooze_pair","pair":"ETH/BTC"}"#).unwrap();
+        assert_eq!(action, ControlAction::SnoozePair { pair: "ETH/BTC".to_string(), duration: Duration::from_secs(3600) });
+        
+        let action: ControlAction = serde_json::from_str(r#"{"action":"blacklist_path","path":"BTC_binance -> BTC_kraken"}"#).unwrap();
+        assert!(matches!(action, ControlAction::BlacklistPath { .. }));
+    }
+    
+    #[tokio::test]
+    async fn test_apply_actions() {
+        let engine = Arc::new(ArbitrageEngine::new(Config::default()));
+        let journal = Arc::new(ExecutionJournal::new());
+        let plane = plane(engine.clone(), journal.clone(), ComplianceConfig::default());
+        let path = "BTC_binance -> ETH_binance -> BTC_kraken".to_string();
+        
+        assert!(plane.apply(ControlAction::BlacklistPath { path: path.clone() }).await.is_ok());
+        assert_eq!(engine.get_blacklisted_paths().await, vec![path.clone()]);
+        
+        let snooze = ControlAction::SnoozePair { pair: "eth/btc".to_string(), duration: Duration::from_secs(3600) };
+        assert_eq!(plane.apply(snooze).await.unwrap(), "Snoozed ETH/BTC for 60m");
+        let bad = ControlAction::SnoozePair { pair: "ETH".to_string(), duration: Duration::from_secs(60) };
+        assert!(plane.apply(bad).await.is_err());
+        
+        // Nothing detected yet, so there's nothing to paper trade
+        assert!(plane.apply(ControlAction::ExecutePaper { path }).await.is_err());
+        assert!(journal.paper_trades().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_approval_executes_once_and_respects_compliance() {
+        let engine = Arc::new(ArbitrageEngine::new(Config::default()));
+        let journal = Arc::new(ExecutionJournal::new());
+        let compliance = ComplianceConfig { restricted_assets: vec!["XMR".to_string()], ..ComplianceConfig::default() };
+        let plane = plane(engine, journal.clone(), compliance);
+        let opportunity = |path: &str| ArbitrageOpportunity { max_volume: 200.0, ..ArbitrageOpportunity::sample(path, 0.01) };
+        let approvals = plane.approvals();
+        let ok = approvals.submit(&opportunity("BTC_binance -> ETH_binance -> BTC_binance"), unix_ms()).unwrap();
+        let blocked = approvals.submit(&opportunity("BTC_binance -> XMR_binance -> BTC_binance"), unix_ms()).unwrap();
+        
+        let reply = plane.apply(ControlAction::Approve { id: ok }).await.unwrap();
+        assert!(reply.ends_with("expected 2.00 USD"), "{}", reply);
+        assert!(plane.apply(ControlAction::Approve { id: ok }).await.is_err());
+        assert!(plane.apply(ControlAction::Approve { id: blocked }).await.unwrap_err().contains("compliance"));
+        assert_eq!(journal.paper_trades().len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_paper_trade_fills_priced_order_legs() {
+        use crate::arbitrage::PriceUpdate;
+        
+        let engine = Arc::new(ArbitrageEngine::new(Config::default()));
+        engine.start().await;
+        let update = |symbol: &str, bid: f64, ask: f64| PriceUpdate {
+            exchange: "binance".to_string(),
+            symbol: symbol.to_string(),
+            bid,
+            ask,
+            volume: 1.0,
+        };
+        engine
+            .update_prices(&[update("BTC/USDT", 50_000.0, 50_000.0), update("ETH/BTC", 0.05, 0.05), update("ETH/USDT", 2_600.0, 2_600.0)])
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        
+        let path = std::env::temp_dir().join(format!("control-orders-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let orders = Arc::new(OrderStore::open(path.clone()));
+        let plane = plane(engine.clone(), Arc::new(ExecutionJournal::new()), ComplianceConfig::default()).with_orders(orders.clone());
+        let opportunity = ArbitrageOpportunity { max_volume: 1_000.0, ..ArbitrageOpportunity::sample("USDT_binance -> BTC_binance -> ETH_binance", 0.04) };
+        let id = plane.approvals().submit(&opportunity, unix_ms()).unwrap();
+        plane.apply(ControlAction::Approve { id }).await.unwrap();
+        
+        // USDT -> BTC buys BTC/USDT, BTC -> ETH buys ETH/BTC, ETH -> USDT sells ETH/USDT
+        let legs: Vec<_> = (0..3).map(|id| orders.get(id).unwrap()).collect();
+        let summary: Vec<(&str, Side, OrderState)> = legs.iter().map(|leg| (leg.symbol.as_str(), leg.side, leg.state)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("BTC/USDT", Side::Buy, OrderState::Filled),
+                ("ETH/BTC", Side::Buy, OrderState::Filled),
+                ("ETH/USDT", Side::Sell, OrderState::Filled),
+            ]
+        );
+        assert!((legs[1].quantity - 0.4).abs() < 1e-9);
+        assert!(orders.in_flight().is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+    
+    #[tokio::test]
+    async fn test_custom_trade_runs_through_compliance_and_orders() {
+        let path = std::env::temp_dir().join(format!("control-custom-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let orders = Arc::new(OrderStore::open(path.clone()));
+        let journal = Arc::new(ExecutionJournal::new());
+        let compliance = ComplianceConfig { restricted_exchanges: vec!["coinbase".to_string()], ..ComplianceConfig::default() };
+        let plane = plane(Arc::new(ArbitrageEngine::new(Config::default())), journal.clone(), compliance).with_orders(orders.clone());
+        let trade: CustomTrade = serde_json::from_str(
+            r#"{"legs": [
+                {"exchange": "binance", "symbol": "BTC/USDT", "side": "buy", "quantity": 0.02, "limit_price": 50000},
+                {"exchange": "kraken", "symbol": "BTC/USDT", "side": "sell", "quantity": 0.02, "limit_price": 50100}
+            ], "expected_profit": 0.002}"#,
+        )
+        .unwrap();
+        
+        let reply = plane.execute_custom(trade.clone()).await.unwrap();
+        assert!(reply.contains("USDT_binance -> BTC_binance -> BTC_kraken -> USDT_kraken"), "{}", reply);
+        let legs: Vec<_> = (0..2).map(|id| orders.get(id).unwrap()).collect();
+        assert_eq!(legs[1].limit_price, Some(50_100.0));
+        assert!(legs.iter().all(|leg| leg.state == OrderState::Filled));
+        assert_eq!(journal.paper_trades()[0].notional, 1_000.0);
+        
+        let mut restricted = trade.clone();
+        restricted.legs[1].exchange = "coinbase".to_string();
+        assert!(plane.execute_custom(restricted).await.unwrap_err().contains("compliance"));
+        let mut unpriced = trade;
+        unpriced.legs[0].limit_price = 0.0;
+        assert!(plane.execute_custom(unpriced).await.is_err());
+        assert_eq!(journal.paper_trades().len(), 1);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,243 @@
+// dex/mod.rs - On-chain pool prices as a venue in the price graph, and what swapping there costs in gas
+pub mod uniswap;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use uniswap::{RpcPools, SubgraphPools};
+
+/// Venue pool quotes are published under, in the graph and the quote book
+pub const VENUE: &str = "uniswap";
+
+pub type PoolFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<PoolQuote>, String>> + Send + 'a>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolSourceKind {
+    Rpc,      // eth_call on each pool's slot0, batched
+    Subgraph, // One GraphQL query for every pool
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenConfig {
+    /// Ticker the graph uses, e.g. WETH; wrapped tokens link to their asset through `markets.aliases`
+    pub symbol: String,
+    pub decimals: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolConfig {
+    /// Pool contract address, 0x-prefixed
+    pub address: String,
+    pub token0: TokenConfig,
+    pub token1: TokenConfig,
+    /// Swap fee as a fraction: 0.0005 for the 5 bps tier
+    pub fee: f64,
+}
+
+impl PoolConfig {
+    /// Internal symbol, token0 priced in token1 as the pool does: "USDC/WETH"
+    pub fn symbol(&self) -> String {
+        format!("{}/{}", self.token0.symbol, self.token1.symbol).to_uppercase()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GasConfig {
+    /// Gas one swap uses
+    pub units_per_swap: u64,
+    /// Fixed gas price; unset reads eth_gasPrice from `rpc_url` every poll
+    pub gas_price_gwei: Option<f64>,
+    /// Pair the chain's native token is priced by, from the quote book
+    pub native_symbol: String,
+    /// Trade size each swap's gas is spread over, in `native_symbol`'s quote currency
+    pub trade_size: f64,
+}
+
+impl Default for GasConfig {
+    fn default() -> Self {
+        Self {
+            units_per_swap: 150_000,
+            gas_price_gwei: None,
+            native_symbol: "ETH/USDT".to_string(),
+            trade_size: 10_000.0,
+        }
+    }
+}
+
+impl GasConfig {
+    pub fn estimate(&self, gas_price_gwei: f64, native_price: f64) -> GasEstimate {
+        let cost_per_swap = self.units_per_swap as f64 * gas_price_gwei * 1e-9 * native_price;
+        GasEstimate {
+            gas_price_gwei,
+            native_price,
+            cost_per_swap,
+            cost_fraction: cost_per_swap / self.trade_size,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DexConfig {
+    pub enabled: bool,
+    pub source: PoolSourceKind,
+    /// Ethereum JSON-RPC endpoint; needed for the rpc source and for live gas prices
+    pub rpc_url: String,
+    /// Uniswap v3 subgraph endpoint, for the subgraph source
+    pub subgraph_url: String,
+    #[serde(with = "humantime_serde")]
+    pub poll_interval: Duration,
+    pub pools: Vec<PoolConfig>,
+    pub gas: GasConfig,
+}
+
+impl Default for DexConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            source: PoolSourceKind::Rpc,
+            rpc_url: String::new(),
+            subgraph_url: String::new(),
+            poll_interval: Duration::from_secs(12), // One block
+            pools: Vec::new(),
+            gas: GasConfig::default(),
+        }
+    }
+}
+
+/// One pool's price, token1 per token0, as of its last read
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PoolQuote {
+    pub address: String,
+    pub symbol: String,
+    pub price: f64,
+    pub fee: f64,
+}
+
+impl PoolQuote {
+    /// What a small swap gets each way: the pool price less its fee. Size
+    /// moves the price along the curve; the trade size is kept small enough
+    /// that the fee dominates
+    pub fn bid_ask(&self) -> (f64, f64) {
+        (self.price * (1.0 - self.fee), self.price / (1.0 - self.fee))
+    }
+}
+
+/// Gas for one swap at the current gas and native token prices
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GasEstimate {
+    pub gas_price_gwei: f64,
+    pub native_price: f64,
+    /// In `native_symbol`'s quote currency
+    pub cost_per_swap: f64,
+    /// `cost_per_swap` over `trade_size`; each swap is charged this like a fee
+    pub cost_fraction: f64,
+}
+
+/// Reads the current price of every configured pool
+pub trait PoolSource: Send + Sync {
+    fn fetch<'a>(&'a self, client: &'a reqwest::Client) -> PoolFuture<'a>;
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DexSnapshot {
+    pub venue: &'static str,
+    pub pools: Vec<PoolQuote>,
+    pub gas: Option<GasEstimate>,
+}
+
+/// Latest pool quotes and gas estimate. A failed read keeps the last good ones
+pub struct Dex {
+    config: DexConfig,
+    source: Box<dyn PoolSource>,
+    quotes: Mutex<Vec<PoolQuote>>,
+    gas: Mutex<Option<GasEstimate>>,
+}
+
+impl Dex {
+    pub fn from_config(config: &DexConfig) -> Self {
+        let source: Box<dyn PoolSource> = match config.source {
+            PoolSourceKind::Rpc => Box::new(RpcPools::new(&config.rpc_url, &config.pools)),
+            PoolSourceKind::Subgraph => Box::new(SubgraphPools::new(&config.subgraph_url, &config.pools)),
+        };
+        Self {
+            config: config.clone(),
+            source,
+            quotes: Mutex::new(Vec::new()),
+            gas: Mutex::new(None),
+        }
+    }
+    
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled && !self.config.pools.is_empty()
+    }
+    
+    pub fn poll_interval(&self) -> Duration {
+        self.config.poll_interval
+    }
+    
+    pub fn native_symbol(&self) -> &str {
+        &self.config.gas.native_symbol
+    }
+    
+    /// Read every pool, returning the new quotes
+    pub async fn refresh(&self, client: &reqwest::Client) -> Vec<PoolQuote> {
+        match self.source.fetch(client).await {
+            Ok(quotes) => {
+                *self.quotes.lock().unwrap() = quotes.clone();
+                quotes
+            }
+            Err(e) => {
+                warn!("DEX pool read failed, keeping previous prices: {}", e);
+                Vec::new()
+            }
+        }
+    }
+    
+    /// Price a swap's gas with `native_price`, at the configured or current gas price
+    pub async fn update_gas(&self, client: &reqwest::Client, native_price: f64) -> Result<GasEstimate, String> {
+        let gas_price_gwei = match self.config.gas.gas_price_gwei {
+            Some(gwei) => gwei,
+            None => uniswap::gas_price_gwei(client, &self.config.rpc_url).await?,
+        };
+        let estimate = self.config.gas.estimate(gas_price_gwei, native_price);
+        *self.gas.lock().unwrap() = Some(estimate.clone());
+        Ok(estimate)
+    }
+    
+    pub fn snapshot(&self) -> DexSnapshot {
+        DexSnapshot {
+            venue: VENUE,
+            pools: self.quotes.lock().unwrap().clone(),
+            gas: self.gas.lock().unwrap().clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_gas_is_a_fraction_of_the_trade_size() {
+        let gas = GasConfig::default();
+        // 150k gas at 20 gwei is 0.003 ETH; at 3000 that's 9 on a 10k trade
+        let estimate = gas.estimate(20.0, 3000.0);
+        assert!((estimate.cost_per_swap - 9.0).abs() < 1e-9);
+        assert!((estimate.cost_fraction - 0.0009).abs() < 1e-12);
+        
+        let quote = PoolQuote {
+            address: "0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640".to_string(),
+            symbol: "USDC/WETH".to_string(),
+            price: 0.0004,
+            fee: 0.0005,
+        };
+        let (bid, ask) = quote.bid_ask();
+        assert!(bid < quote.price && quote.price < ask);
+        assert!((ask / bid - 1.0 / 0.9995f64.powi(2)).abs() < 1e-12);
+    }
+}
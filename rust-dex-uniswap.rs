@@ -0,0 +1,197 @@
+// dex/uniswap.rs - Uniswap v3 pool prices from slot0 over JSON-RPC, or from the subgraph
+use std::time::Duration;
+use serde_json::{json, Value};
+
+use super::{PoolConfig, PoolFuture, PoolQuote, PoolSource};
+
+/// Reads give up after this long; the next poll tries again
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Selector of `slot0()`, whose first word is the pool's sqrtPriceX96
+const SLOT0: &str = "0x3850c7bd";
+
+/// Unsigned integer from a 0x-prefixed hex word, as a float
+fn hex_to_f64(hex: &str) -> Option<f64> {
+    let digits = hex.strip_prefix("0x").unwrap_or(hex);
+    if digits.is_empty() {
+        return None;
+    }
+    digits
+        .chars()
+        .try_fold(0.0, |value, digit| Some(value * 16.0 + digit.to_digit(16)? as f64))
+}
+
+/// Human price of token0 in token1 from sqrtPriceX96: (sqrtPriceX96 / 2^96)^2
+/// counts token1's smallest units per token0 unit, scaled by the decimals
+pub fn slot0_price(slot0: &str, pool: &PoolConfig) -> Option<f64> {
+    let digits = slot0.strip_prefix("0x").unwrap_or(slot0);
+    let sqrt_price = hex_to_f64(digits.get(..64)?)? / 2f64.powi(96);
+    let price = sqrt_price * sqrt_price * 10f64.powi(pool.token0.decimals as i32 - pool.token1.decimals as i32);
+    (price.is_finite() && price > 0.0).then_some(price)
+}
+
+async fn post(client: &reqwest::Client, url: &str, body: &Value) -> Result<Value, String> {
+    let response = client
+        .post(url)
+        .json(body)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let status = response.status();
+    let text = response.text().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(format!("{} returned {}: {}", url, status, text));
+    }
+    serde_json::from_str(&text).map_err(|e| format!("{}: {}", url, e))
+}
+
+/// Current gas price from eth_gasPrice, in gwei
+pub async fn gas_price_gwei(client: &reqwest::Client, rpc_url: &str) -> Result<f64, String> {
+    let reply = post(client, rpc_url, &json!({ "jsonrpc": "2.0", "id": 1, "method": "eth_gasPrice", "params": [] })).await?;
+    let wei = reply
+        .get("result")
+        .and_then(Value::as_str)
+        .and_then(hex_to_f64)
+        .ok_or_else(|| format!("eth_gasPrice: unexpected reply {}", reply))?;
+    Ok(wei * 1e-9)
+}
+
+/// Every pool's slot0 in one JSON-RPC batch
+pub struct RpcPools {
+    url: String,
+    pools: Vec<PoolConfig>,
+}
+
+impl RpcPools {
+    pub fn new(url: &str, pools: &[PoolConfig]) -> Self {
+        Self {
+            url: url.to_string(),
+            pools: pools.to_vec(),
+        }
+    }
+    
+    fn request(&self) -> Value {
+        self.pools
+            .iter()
+            .enumerate()
+            .map(|(id, pool)| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "method": "eth_call",
+                    "params": [{ "to": pool.address, "data": SLOT0 }, "latest"],
+                })
+            })
+            .collect()
+    }
+    
+    /// Quotes from a batch reply, matched to pools by id; pools whose call
+    /// failed are left out
+    fn parse(&self, reply: &Value) -> Result<Vec<PoolQuote>, String> {
+        let results = reply.as_array().ok_or_else(|| format!("expected a batch reply, got {}", reply))?;
+        Ok(results
+            .iter()
+            .filter_map(|result| {
+                let pool = self.pools.get(result.get("id")?.as_u64()? as usize)?;
+                let price = slot0_price(result.get("result")?.as_str()?, pool)?;
+                Some(PoolQuote {
+                    address: pool.address.clone(),
+                    symbol: pool.symbol(),
+                    price,
+                    fee: pool.fee,
+                })
+            })
+            .collect())
+    }
+}
+
+impl PoolSource for RpcPools {
+    fn fetch<'a>(&'a self, client: &'a reqwest::Client) -> PoolFuture<'a> {
+        Box::pin(async move { self.parse(&post(client, &self.url, &self.request()).await?) })
+    }
+}
+
+/// Every pool in one subgraph query; `token1Price` is token1 per token0
+pub struct SubgraphPools {
+    url: String,
+    pools: Vec<PoolConfig>,
+}
+
+impl SubgraphPools {
+    pub fn new(url: &str, pools: &[PoolConfig]) -> Self {
+        Self {
+            url: url.to_string(),
+            pools: pools.to_vec(),
+        }
+    }
+    
+    fn request(&self) -> Value {
+        let ids: Vec<String> = self.pools.iter().map(|pool| format!("\"{}\"", pool.address.to_lowercase())).collect();
+        json!({ "query": format!("{{ pools(where: {{id_in: [{}]}}) {{ id token1Price }} }}", ids.join(", ")) })
+    }
+    
+    fn parse(&self, reply: &Value) -> Result<Vec<PoolQuote>, String> {
+        if let Some(errors) = reply.get("errors") {
+            return Err(format!("subgraph query failed: {}", errors));
+        }
+        let pools = reply
+            .pointer("/data/pools")
+            .and_then(Value::as_array)
+            .ok_or_else(|| format!("unexpected subgraph reply {}", reply))?;
+        Ok(pools
+            .iter()
+            .filter_map(|data| {
+                let id = data.get("id")?.as_str()?;
+                let pool = self.pools.iter().find(|pool| pool.address.eq_ignore_ascii_case(id))?;
+                let price: f64 = data.get("token1Price")?.as_str()?.parse().ok()?;
+                (price.is_finite() && price > 0.0).then(|| PoolQuote {
+                    address: pool.address.clone(),
+                    symbol: pool.symbol(),
+                    price,
+                    fee: pool.fee,
+                })
+            })
+            .collect())
+    }
+}
+
+impl PoolSource for SubgraphPools {
+    fn fetch<'a>(&'a self, client: &'a reqwest::Client) -> PoolFuture<'a> {
+        Box::pin(async move { self.parse(&post(client, &self.url, &self.request()).await?) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::TokenConfig;
+    
+    #[test]
+    fn test_slot0_and_subgraph_prices_agree() {
+        let pool = PoolConfig {
+            address: "0x88E6A0c2dDD26FEEb64F039a2c41296FcB3f5640".to_string(),
+            token0: TokenConfig { symbol: "USDC".to_string(), decimals: 6 },
+            token1: TokenConfig { symbol: "WETH".to_string(), decimals: 18 },
+            fee: 0.0005,
+        };
+        // sqrtPriceX96 = 18257 * 2^96: 18257^2 wei per micro-USDC, i.e. 1 USDC = 0.000333... WETH
+        let slot0 = format!("0x{:064x}{}", 18257u128 << 96, "0".repeat(64 * 6));
+        let expected = 18257f64.powi(2) * 1e-12;
+        assert!((slot0_price(&slot0, &pool).unwrap() / expected - 1.0).abs() < 1e-12);
+        assert_eq!(slot0_price("0x", &pool), None);
+        
+        let rpc = RpcPools::new("http://localhost:8545", std::slice::from_ref(&pool));
+        assert_eq!(rpc.request()[0]["params"][0]["data"], SLOT0);
+        let quotes = rpc
+            .parse(&json!([{ "jsonrpc": "2.0", "id": 0, "result": slot0 }, { "jsonrpc": "2.0", "id": 1, "error": {"code": -32000} }]))
+            .unwrap();
+        assert_eq!((quotes.len(), quotes[0].symbol.as_str()), (1, "USDC/WETH"));
+        
+        let subgraph = SubgraphPools::new("http://localhost:8000", &[pool]);
+        assert!(subgraph.request()["query"].as_str().unwrap().contains("\"0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640\""));
+        let reply = json!({"data": {"pools": [{"id": "0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640", "token1Price": "0.000333318049"}]}});
+        let quotes = subgraph.parse(&reply).unwrap();
+        assert!((quotes[0].price / expected - 1.0).abs() < 1e-6);
+        assert!(subgraph.parse(&json!({"errors": [{"message": "indexing_error"}]})).is_err());
+    }
+}
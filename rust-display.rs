@@ -0,0 +1,158 @@
+// display.rs - Locale-aware number, profit and amount formatting for alerts and the API
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::arbitrage::types::ArbitrageOpportunity;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfitUnit {
+    Percent,  // 0.0012 -> "0.12%"
+    Bps,      // 0.0012 -> "12 bps"
+    Fraction, // 0.0012 -> "0.0012"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplayFormat {
+    pub profit_unit: ProfitUnit,
+    /// BCP 47 tag; only the language picks separators ("de-CH" formats like "de")
+    pub locale: String,
+    /// Decimal places for percentages; bps and fractions keep the same precision
+    pub decimals: usize,
+    pub thousands_separator: bool,
+    /// Currency code shown after amounts such as volume
+    pub base_currency: String,
+}
+
+impl Default for DisplayFormat {
+    fn default() -> Self {
+        Self {
+            profit_unit: ProfitUnit::Percent,
+            locale: "en-US".to_string(),
+            decimals: 2,
+            thousands_separator: true,
+            base_currency: "USD".to_string(),
+        }
+    }
+}
+
+/// (thousands, decimal) separators by language
+const SEPARATORS: &[(&str, char, char)] = &[
+    ("en", ',', '.'),
+    ("zh", ',', '.'),
+    ("ja", ',', '.'),
+    ("ko", ',', '.'),
+    ("de", '.', ','),
+    ("es", '.', ','),
+    ("it", '.', ','),
+    ("pt", '.', ','),
+    ("nl", '.', ','),
+    ("tr", '.', ','),
+    ("id", '.', ','),
+    ("fr", '\u{202F}', ','), // Narrow no-break space
+    ("ru", '\u{00A0}', ','),
+    ("pl", '\u{00A0}', ','),
+    ("uk", '\u{00A0}', ','),
+];
+
+impl DisplayFormat {
+    /// Separators for `locale`, or `None` if the language isn't known
+    pub fn separators(&self) -> Option<(char, char)> {
+        let language = self.locale.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+        SEPARATORS
+            .iter()
+            .find(|(lang, _, _)| *lang == language)
+            .map(|&(_, thousands, decimal)| (thousands, decimal))
+    }
+    
+    /// `value` with `decimals` places and the locale's separators
+    pub fn number(&self, value: f64, decimals: usize) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+        let (thousands, decimal) = self.separators().unwrap_or((',', '.'));
+        
+        let fixed = format!("{:.*}", decimals, value.abs());
+        let (int_part, frac_part) = fixed.split_once('.').unwrap_or((&fixed, ""));
+        
+        let mut out = String::new();
+        if value < 0.0 && fixed.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+            out.push('-');
+        }
+        for (i, digit) in int_part.chars().enumerate() {
+            if self.thousands_separator && i > 0 && (int_part.len() - i) % 3 == 0 {
+                out.push(thousands);
+            }
+            out.push(digit);
+        }
+        if !frac_part.is_empty() {
+            out.push(decimal);
+            out.push_str(frac_part);
+        }
+        out
+    }
+    
+    /// A profit given as a fraction (0.0012 = 0.12%) in the configured unit
+    pub fn profit(&self, fraction: f64) -> String {
+        match self.profit_unit {
+            ProfitUnit::Percent => format!("{}%", self.number(fraction * 100.0, self.decimals)),
+            ProfitUnit::Bps => format!("{} bps", self.number(fraction * 10_000.0, self.decimals.saturating_sub(2))),
+            ProfitUnit::Fraction => self.number(fraction, self.decimals + 2),
+        }
+    }
+    
+    /// An amount in the base currency, e.g. "12,345.68 USD"
+    pub fn amount(&self, value: f64) -> String {
+        format!("{} {}", self.number(value, 2), self.base_currency)
+    }
+    
+    /// `opportunity` as JSON with `profit_display`, `gross_profit_display` and
+    /// `max_volume_display` added
+    pub fn opportunity_json(&self, opportunity: &ArbitrageOpportunity) -> Value {
+        let mut data = serde_json::to_value(opportunity).unwrap_or_else(|_| json!({}));
+        if let Value::Object(fields) = &mut data {
+            fields.insert("profit_display".to_string(), json!(self.profit(opportunity.profit_percentage)));
+            fields.insert("gross_profit_display".to_string(), json!(self.profit(opportunity.gross_profit)));
+            fields.insert("max_volume_display".to_string(), json!(self.amount(opportunity.max_volume)));
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_profit_units_share_precision() {
+        let percent = DisplayFormat::default();
+        let bps = DisplayFormat { profit_unit: ProfitUnit::Bps, ..DisplayFormat::default() };
+        let fraction = DisplayFormat { profit_unit: ProfitUnit::Fraction, ..DisplayFormat::default() };
+        
+        assert_eq!(percent.profit(0.0012), "0.12%");
+        assert_eq!(bps.profit(0.0012), "12 bps");
+        assert_eq!(fraction.profit(0.0012), "0.0012");
+        assert_eq!(bps.profit(-0.00012), "-1 bps");
+    }
+    
+    #[test]
+    fn test_locale_separators() {
+        let en = DisplayFormat::default();
+        let de = DisplayFormat { locale: "de-DE".to_string(), base_currency: "EUR".to_string(), ..DisplayFormat::default() };
+        let plain = DisplayFormat { thousands_separator: false, ..DisplayFormat::default() };
+        
+        assert_eq!(en.amount(1234567.891), "1,234,567.89 USD");
+        assert_eq!(de.amount(1234567.891), "1.234.567,89 EUR");
+        assert_eq!(plain.number(1234567.891, 1), "1234567.9");
+        assert_eq!(en.number(-999.0, 0), "-999");
+        assert_eq!(en.number(-0.001, 2), "0.00");
+    }
+    
+    #[test]
+    fn test_unknown_locale_has_no_separators() {
+        let format = DisplayFormat { locale: "xx".to_string(), ..DisplayFormat::default() };
+        
+        assert_eq!(format.separators(), None);
+        assert_eq!(format.number(1000.5, 1), "1,000.5");
+    }
+}
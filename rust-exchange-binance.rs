@@ -0,0 +1,183 @@
+// exchange/binance.rs - Binance spot connector (combined bookTicker streams)
+use serde_json::{json, Value};
+
+use super::connector::{
+    decimal, field, levels, list, parse_frame, split_concatenated, CanonicalTick, ExchangeConnector,
+    NormalizeError, NormalizedEvent, OrderBookUpdate,
+};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::schema::MessageSchema;
+
+const WS_URL: &str = "wss://stream.binance.com:9443/stream";
+const TESTNET_WS_URL: &str = "wss://testnet.binance.vision/stream";
+const INSTRUMENTS_URL: &str = "https://api.binance.com/api/v3/exchangeInfo?permissions=SPOT";
+const QUOTE_ASSETS: &[&str] = &["USDT", "USDC", "FDUSD", "BUSD", "TUSD", "BTC", "ETH", "BNB", "EUR", "TRY"];
+/// Partial book depths Binance streams; each frame is a whole top-N book
+const DEPTH_LEVELS: &[usize] = &[5, 10, 20];
+
+static BOOK_TICKER_SCHEMA: MessageSchema = MessageSchema {
+    required: &["s", "b", "B", "a", "A"],
+    optional: &["u", "e", "E", "T"],
+    payload: book_ticker_payload,
+};
+
+fn book_ticker_payload(frame: &Value) -> Option<&Value> {
+    // Control responses ({"result":..,"id":..} / {"error":..,"id":..}) carry no market data
+    if frame.get("id").is_some() || depth_stream(frame).is_some() {
+        None
+    } else {
+        Some(frame.get("data").unwrap_or(frame))
+    }
+}
+
+/// Symbol part of a partial depth stream name: "btcusdt@depth20@100ms"
+fn depth_stream(frame: &Value) -> Option<&str> {
+    let stream = frame.get("stream")?.as_str()?;
+    let (symbol, channel) = stream.split_once('@')?;
+    channel.starts_with("depth").then_some(symbol)
+}
+
+pub struct BinanceConnector;
+
+impl BinanceConnector {
+    pub fn new() -> Self {
+        Self
+    }
+    
+    fn depth_streams(&self, symbols: &[String], levels: usize) -> Vec<String> {
+        let depth = DEPTH_LEVELS.iter().copied().find(|&depth| depth >= levels).unwrap_or(20);
+        symbols
+            .iter()
+            .map(|s| format!("{}@depth{}@100ms", self.to_exchange_symbol(s).to_lowercase(), depth))
+            .collect()
+    }
+}
+
+impl ExchangeConnector for BinanceConnector {
+    fn name(&self) -> &'static str {
+        "binance"
+    }
+    
+    fn ws_url(&self) -> &str {
+        WS_URL
+    }
+    
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        symbol.replace('/', "").to_uppercase()
+    }
+    
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        split_concatenated(raw, QUOTE_ASSETS)
+    }
+    
+    fn subscribe_messages(&self, symbols: &[String], request_id: u64) -> Vec<String> {
+        let streams: Vec<String> = symbols
+            .iter()
+            .map(|s| format!("{}@bookTicker", self.to_exchange_symbol(s).to_lowercase()))
+            .collect();
+        
+        vec![json!({ "method": "SUBSCRIBE", "params": streams, "id": request_id }).to_string()]
+    }
+    
+    fn unsubscribe_messages(&self, symbols: &[String]) -> Vec<String> {
+        let streams: Vec<String> = symbols
+            .iter()
+            .map(|s| format!("{}@bookTicker", self.to_exchange_symbol(s).to_lowercase()))
+            .collect();
+        
+        // Id 0 is never a subscription request, so the reply confirms nothing
+        vec![json!({ "method": "UNSUBSCRIBE", "params": streams, "id": 0 }).to_string()]
+    }
+    
+    fn depth_subscribe_messages(&self, symbols: &[String], levels: usize, request_id: u64) -> Vec<String> {
+        vec![json!({ "method": "SUBSCRIBE", "params": self.depth_streams(symbols, levels), "id": request_id }).to_string()]
+    }
+    
+    fn depth_unsubscribe_messages(&self, symbols: &[String], levels: usize) -> Vec<String> {
+        vec![json!({ "method": "UNSUBSCRIBE", "params": self.depth_streams(symbols, levels), "id": 0 }).to_string()]
+    }
+    
+    fn instruments_url(&self) -> Option<&str> {
+        Some(INSTRUMENTS_URL)
+    }
+    
+    fn parse_instruments(&self, body: &str) -> Result<Vec<String>, NormalizeError> {
+        let info = parse_frame(body)?;
+        Ok(list(&info, "symbols")?
+            .iter()
+            .filter(|market| market["status"] == "TRADING" && market["isSpotTradingAllowed"] == true)
+            .filter_map(|market| Some(format!("{}/{}", market["baseAsset"].as_str()?, market["quoteAsset"].as_str()?)))
+            .collect())
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: true,
+            trading: false,
+            testnet_ws_url: Some(TESTNET_WS_URL),
+            max_streams_per_connection: Some(1024),
+            streams_per_symbol: 1,
+            max_messages_per_second: Some(5),
+            symbol_format: SymbolFormat::Concatenated,
+        }
+    }
+    
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        Some(&BOOK_TICKER_SCHEMA)
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        
+        // Subscription responses: {"result":null,"id":1}
+        if frame.get("id").is_some() && frame.get("result").is_some() {
+            return Ok(vec![NormalizedEvent::SubscriptionAck {
+                request_id: frame["id"].as_u64(),
+                symbols: Vec::new(),
+            }]);
+        }
+        
+        // Rejected requests: {"error":{"code":2,"msg":"Invalid request"},"id":1}
+        if let Some(error) = frame.get("error") {
+            return Ok(vec![NormalizedEvent::SubscriptionRejected {
+                symbols: Vec::new(),
+                reason: error.get("msg").and_then(Value::as_str).unwrap_or_default().to_string(),
+            }]);
+        }
+        
+        // Combined streams wrap the payload in {"stream": ..., "data": {...}}
+        let data = frame.get("data").unwrap_or(&frame);
+        
+        // Partial depth frames only name the symbol in the stream
+        if let Some(raw_symbol) = depth_stream(&frame) {
+            let symbol = self
+                .from_exchange_symbol(raw_symbol)
+                .ok_or_else(|| NormalizeError::UnknownSymbol(raw_symbol.to_string()))?;
+            return Ok(vec![NormalizedEvent::Book(OrderBookUpdate {
+                exchange: self.name().to_string(),
+                symbol,
+                snapshot: true,
+                bids: levels(field(data, "bids")?, "bids")?,
+                asks: levels(field(data, "asks")?, "asks")?,
+                sequence: data.get("lastUpdateId").and_then(Value::as_u64),
+                depth: None,
+            })]);
+        }
+        
+        let raw_symbol = field(data, "s")?
+            .as_str()
+            .ok_or(NormalizeError::MissingField("s"))?;
+        let symbol = self
+            .from_exchange_symbol(raw_symbol)
+            .ok_or_else(|| NormalizeError::UnknownSymbol(raw_symbol.to_string()))?;
+        
+        Ok(vec![NormalizedEvent::Tick(CanonicalTick {
+            exchange: self.name().to_string(),
+            symbol,
+            bid: decimal(field(data, "b")?, "b")?,
+            bid_size: decimal(field(data, "B")?, "B")?,
+            ask: decimal(field(data, "a")?, "a")?,
+            ask_size: decimal(field(data, "A")?, "A")?,
+        })])
+    }
+}
@@ -0,0 +1,298 @@
+// exchange/bitfinex.rs - Bitfinex spot connector (WebSocket v2 ticker and book channels)
+use std::collections::HashMap;
+use std::sync::RwLock;
+use serde_json::{json, Value};
+
+use super::connector::{
+    decimal, field, parse_frame, CanonicalTick, ExchangeConnector, NormalizeError, NormalizedEvent, OrderBookUpdate,
+};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::schema::MessageSchema;
+
+const WS_URL: &str = "wss://api-pub.bitfinex.com/ws/2";
+const INSTRUMENTS_URL: &str = "https://api-pub.bitfinex.com/v2/conf/pub:list:pair:exchange";
+
+// Bitfinex spells a few assets with its own three-letter codes
+const ASSET_ALIASES: &[(&str, &str)] = &[("USDT", "UST"), ("USDC", "UDC"), ("TUSD", "TSD"), ("DASH", "DSH")];
+/// Raw book lengths Bitfinex streams
+const BOOK_LENGTHS: &[usize] = &[1, 25, 100, 250];
+
+/// A subscribed channel's symbol, and whether it streams the book or the ticker
+struct Channel {
+    symbol: String,
+    book: bool,
+}
+
+pub struct BitfinexConnector {
+    /// Channel id -> channel, learned from `subscribed` events. Ids are only
+    /// unique per connection, so each connection gets its own connector
+    channels: RwLock<HashMap<u64, Channel>>,
+}
+
+impl BitfinexConnector {
+    pub fn new() -> Self {
+        Self {
+            channels: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    fn to_bitfinex_asset(asset: &str) -> &str {
+        ASSET_ALIASES
+            .iter()
+            .find(|(internal, _)| *internal == asset)
+            .map(|(_, bitfinex)| *bitfinex)
+            .unwrap_or(asset)
+    }
+    
+    fn from_bitfinex_asset(asset: &str) -> &str {
+        ASSET_ALIASES
+            .iter()
+            .find(|(_, bitfinex)| *bitfinex == asset)
+            .map(|(internal, _)| *internal)
+            .unwrap_or(asset)
+    }
+    
+    /// Unsubscribe frames for the channels of `symbols` that are (or aren't) books
+    fn unsubscribe_channels(&self, symbols: &[String], book: bool) -> Vec<String> {
+        let channels = self.channels.read().unwrap();
+        channels
+            .iter()
+            .filter(|(_, channel)| channel.book == book && symbols.contains(&channel.symbol))
+            .map(|(id, _)| json!({ "event": "unsubscribe", "chanId": id }).to_string())
+            .collect()
+    }
+    
+    /// Ticker payload: [BID, BID_SIZE, ASK, ASK_SIZE, DAILY_CHANGE, ...]
+    fn tick(&self, symbol: String, payload: &Value) -> Result<NormalizedEvent, NormalizeError> {
+        let values = payload
+            .as_array()
+            .filter(|values| values.len() >= 4)
+            .ok_or_else(|| NormalizeError::Malformed(format!("unexpected ticker payload {}", payload)))?;
+        
+        Ok(NormalizedEvent::Tick(CanonicalTick {
+            exchange: self.name().to_string(),
+            symbol,
+            bid: decimal(&values[0], "bid")?,
+            bid_size: decimal(&values[1], "bid_size")?.abs(),
+            ask: decimal(&values[2], "ask")?,
+            ask_size: decimal(&values[3], "ask_size")?.abs(),
+        }))
+    }
+    
+    /// Book payload: a snapshot [[PRICE, COUNT, AMOUNT], ...] or one changed
+    /// level [PRICE, COUNT, AMOUNT]. Bids have a positive amount and asks a
+    /// negative one; a count of 0 removes the level
+    fn book(&self, symbol: String, payload: &Value) -> Result<NormalizedEvent, NormalizeError> {
+        let malformed = || NormalizeError::Malformed(format!("unexpected book payload {}", payload));
+        let entries = payload.as_array().ok_or_else(malformed)?;
+        let snapshot = entries.first().is_some_and(Value::is_array);
+        let entries: Vec<&Value> = if snapshot { entries.iter().collect() } else { vec![payload] };
+        
+        let mut update = OrderBookUpdate {
+            exchange: self.name().to_string(),
+            symbol,
+            snapshot,
+            bids: Vec::new(),
+            asks: Vec::new(),
+            sequence: None,
+            depth: None,
+        };
+        for entry in entries {
+            let level = entry.as_array().filter(|level| level.len() >= 3).ok_or_else(malformed)?;
+            let price = decimal(&level[0], "price")?;
+            let count = decimal(&level[1], "count")?;
+            let amount = decimal(&level[2], "amount")?;
+            let size = if count == 0.0 { 0.0 } else { amount.abs() };
+            if amount > 0.0 {
+                update.bids.push((price, size));
+            } else {
+                update.asks.push((price, size));
+            }
+        }
+        Ok(NormalizedEvent::Book(update))
+    }
+    
+    fn book_requests(&self, symbols: &[String], levels: usize) -> Vec<String> {
+        let length = BOOK_LENGTHS.iter().copied().find(|&length| length >= levels).unwrap_or(250);
+        symbols
+            .iter()
+            .map(|symbol| {
+                json!({
+                    "event": "subscribe",
+                    "channel": "book",
+                    "symbol": self.to_exchange_symbol(symbol),
+                    "prec": "P0",
+                    "len": length.to_string(),
+                })
+                .to_string()
+            })
+            .collect()
+    }
+}
+
+impl ExchangeConnector for BitfinexConnector {
+    fn name(&self) -> &'static str {
+        "bitfinex"
+    }
+    
+    fn ws_url(&self) -> &str {
+        WS_URL
+    }
+    
+    /// Trading pairs are prefixed with `t`; assets longer than three letters
+    /// need a colon separator (tBTCUST, tDOGE:UST)
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        match symbol.split_once('/') {
+            Some((base, quote)) => {
+                let (base, quote) = (Self::to_bitfinex_asset(base), Self::to_bitfinex_asset(quote));
+                if base.len() == 3 && quote.len() == 3 {
+                    format!("t{}{}", base, quote)
+                } else {
+                    format!("t{}:{}", base, quote)
+                }
+            }
+            None => symbol.to_string(),
+        }
+    }
+    
+    /// Accepts the `t` prefix or not, since `pair` fields omit it
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        let pair = raw.strip_prefix('t').unwrap_or(raw).to_uppercase();
+        let (base, quote) = match pair.split_once(':') {
+            Some(split) => split,
+            None if pair.len() == 6 => pair.split_at(3),
+            None => return None,
+        };
+        Some(format!("{}/{}", Self::from_bitfinex_asset(base), Self::from_bitfinex_asset(quote)))
+    }
+    
+    fn subscribe_messages(&self, symbols: &[String], _request_id: u64) -> Vec<String> {
+        // One channel per symbol; the `subscribed` reply carries the channel id
+        symbols
+            .iter()
+            .map(|symbol| {
+                json!({
+                    "event": "subscribe",
+                    "channel": "ticker",
+                    "symbol": self.to_exchange_symbol(symbol),
+                })
+                .to_string()
+            })
+            .collect()
+    }
+    
+    fn unsubscribe_messages(&self, symbols: &[String]) -> Vec<String> {
+        self.unsubscribe_channels(symbols, false)
+    }
+    
+    /// A fresh book subscription starts with a snapshot
+    fn depth_subscribe_messages(&self, symbols: &[String], levels: usize, _request_id: u64) -> Vec<String> {
+        self.book_requests(symbols, levels)
+    }
+    
+    fn depth_unsubscribe_messages(&self, symbols: &[String], _levels: usize) -> Vec<String> {
+        self.unsubscribe_channels(symbols, true)
+    }
+    
+    fn instruments_url(&self) -> Option<&str> {
+        Some(INSTRUMENTS_URL)
+    }
+    
+    fn parse_instruments(&self, body: &str) -> Result<Vec<String>, NormalizeError> {
+        // [["BTCUSD", "ETHUST", "DOGE:USD", ...]]
+        let config = parse_frame(body)?;
+        let pairs = config[0].as_array().ok_or_else(|| NormalizeError::Malformed("expected a pair list".to_string()))?;
+        Ok(pairs.iter().filter_map(|pair| self.from_exchange_symbol(pair.as_str()?)).collect())
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: true,
+            trading: false,
+            testnet_ws_url: None,
+            max_streams_per_connection: Some(30),
+            streams_per_symbol: 1,
+            max_messages_per_second: None,
+            symbol_format: SymbolFormat::Prefixed,
+        }
+    }
+    
+    /// Payloads are positional arrays with no field names to check; a
+    /// changed layout still counts as unparseable messages
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        None
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        
+        // Channel data: [chanId, payload], [chanId, "hb"] or [chanId, "cs", checksum]
+        if let Some(parts) = frame.as_array() {
+            let id = parts
+                .first()
+                .and_then(Value::as_u64)
+                .ok_or(NormalizeError::MissingField("chanId"))?;
+            match parts.get(1) {
+                Some(Value::String(hb)) if hb == "hb" => return Ok(vec![NormalizedEvent::Heartbeat]),
+                // Book checksums, sent when `conf` asks for them; books aren't checked against them
+                Some(Value::String(cs)) if cs == "cs" => return Ok(Vec::new()),
+                _ => {}
+            }
+            let (symbol, book) = match self.channels.read().unwrap().get(&id) {
+                Some(channel) => (channel.symbol.clone(), channel.book),
+                None => return Err(NormalizeError::UnknownSymbol(format!("channel {}", id))),
+            };
+            return match parts.get(1) {
+                // The snapshot of a book with no resting orders
+                Some(Value::Array(levels)) if parts.len() == 2 && book && levels.is_empty() => Ok(Vec::new()),
+                Some(payload) if parts.len() == 2 && book => Ok(vec![self.book(symbol, payload)?]),
+                Some(payload) if parts.len() == 2 => Ok(vec![self.tick(symbol, payload)?]),
+                _ => Err(NormalizeError::Malformed(format!("unexpected channel frame of {} parts", parts.len()))),
+            };
+        }
+        
+        let symbols = || -> Vec<String> {
+            frame
+                .get("symbol")
+                .and_then(Value::as_str)
+                .and_then(|symbol| self.from_exchange_symbol(symbol))
+                .into_iter()
+                .collect()
+        };
+        match field(&frame, "event")?.as_str().unwrap_or_default() {
+            "subscribed" => {
+                let id = field(&frame, "chanId")?.as_u64().ok_or(NormalizeError::MissingField("chanId"))?;
+                let symbols = symbols();
+                if let Some(symbol) = symbols.first() {
+                    let channel = Channel {
+                        symbol: symbol.clone(),
+                        book: frame.get("channel").and_then(Value::as_str) == Some("book"),
+                    };
+                    self.channels.write().unwrap().insert(id, channel);
+                }
+                Ok(vec![NormalizedEvent::SubscriptionAck { request_id: None, symbols }])
+            }
+            "unsubscribed" => {
+                if let Some(channel) = frame.get("chanId").and_then(Value::as_u64) {
+                    self.channels.write().unwrap().remove(&channel);
+                }
+                Ok(vec![NormalizedEvent::Control("unsubscribed".to_string())])
+            }
+            "error" => Ok(vec![NormalizedEvent::SubscriptionRejected {
+                symbols: symbols(),
+                reason: frame.get("msg").and_then(Value::as_str).unwrap_or_default().to_string(),
+            }]),
+            // Includes maintenance notices (code 20051 asks clients to reconnect)
+            "info" => match frame.get("code").and_then(Value::as_u64) {
+                Some(code) => Ok(vec![NormalizedEvent::Control(format!("info {}", code))]),
+                None => Ok(vec![NormalizedEvent::Control("info".to_string())]),
+            },
+            event @ ("conf" | "pong") => Ok(vec![NormalizedEvent::Control(event.to_string())]),
+            other => Err(NormalizeError::Malformed(format!("unexpected event '{}'", other))),
+        }
+    }
+    
+    fn for_connection(&self) -> Option<Box<dyn ExchangeConnector>> {
+        Some(Box::new(BitfinexConnector::new()))
+    }
+}
@@ -0,0 +1,173 @@
+// exchange/bitstamp.rs - Bitstamp connector (WebSocket v2 order_book channels)
+use serde_json::{json, Value};
+
+use super::connector::{
+    decimal, field, levels, parse_frame, split_concatenated, CanonicalTick, ExchangeConnector, NormalizeError,
+    NormalizedEvent, OrderBookUpdate,
+};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::schema::MessageSchema;
+
+const WS_URL: &str = "wss://ws.bitstamp.net";
+const INSTRUMENTS_URL: &str = "https://www.bitstamp.net/api/v2/trading-pairs-info/";
+// Fiat pairs (EUR/USD, GBP/USD) let cycles cross between fiat quotes
+const QUOTE_ASSETS: &[&str] = &["USD", "EUR", "GBP", "USDT", "USDC", "BTC", "ETH"];
+const BOOK_CHANNEL: &str = "order_book_";
+
+static ORDER_BOOK_SCHEMA: MessageSchema = MessageSchema {
+    required: &["bids", "asks"],
+    optional: &["timestamp", "microtimestamp"],
+    payload: order_book_payload,
+};
+
+fn order_book_payload(frame: &Value) -> Option<&Value> {
+    frame.get("event").filter(|event| *event == "data").and(frame.get("data"))
+}
+
+/// Quotes come from the top of each `order_book` snapshot; `live_trades`
+/// carries prints, not a bid and ask, so it isn't subscribed. Each snapshot
+/// is also a whole top-100 book, so depth needs no subscription of its own
+pub struct BitstampConnector;
+
+impl BitstampConnector {
+    pub fn new() -> Self {
+        Self
+    }
+    
+    fn channel(&self, symbol: &str) -> String {
+        format!("{}{}", BOOK_CHANNEL, self.to_exchange_symbol(symbol))
+    }
+    
+    fn channel_symbol(&self, frame: &Value) -> Option<String> {
+        let channel = frame.get("channel")?.as_str()?;
+        self.from_exchange_symbol(channel.strip_prefix(BOOK_CHANNEL)?)
+    }
+    
+    /// Best level of a book side: [["price", "amount"], ...]
+    fn best(data: &Value, side: &'static str) -> Result<(f64, f64), NormalizeError> {
+        let level = field(data, side)?
+            .get(0)
+            .ok_or_else(|| NormalizeError::Malformed(format!("empty {}", side)))?;
+        let price = level.get(0).ok_or(NormalizeError::MissingField(side))?;
+        let amount = level.get(1).ok_or(NormalizeError::MissingField(side))?;
+        Ok((decimal(price, side)?, decimal(amount, side)?))
+    }
+}
+
+impl ExchangeConnector for BitstampConnector {
+    fn name(&self) -> &'static str {
+        "bitstamp"
+    }
+    
+    fn ws_url(&self) -> &str {
+        WS_URL
+    }
+    
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        symbol.replace('/', "").to_lowercase()
+    }
+    
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        split_concatenated(raw, QUOTE_ASSETS)
+    }
+    
+    fn subscribe_messages(&self, symbols: &[String], _request_id: u64) -> Vec<String> {
+        symbols
+            .iter()
+            .map(|symbol| json!({ "event": "bts:subscribe", "data": { "channel": self.channel(symbol) } }).to_string())
+            .collect()
+    }
+    
+    fn unsubscribe_messages(&self, symbols: &[String]) -> Vec<String> {
+        symbols
+            .iter()
+            .map(|symbol| json!({ "event": "bts:unsubscribe", "data": { "channel": self.channel(symbol) } }).to_string())
+            .collect()
+    }
+    
+    fn instruments_url(&self) -> Option<&str> {
+        Some(INSTRUMENTS_URL)
+    }
+    
+    fn parse_instruments(&self, body: &str) -> Result<Vec<String>, NormalizeError> {
+        let pairs = parse_frame(body)?;
+        let pairs = pairs.as_array().ok_or_else(|| NormalizeError::Malformed("expected a pair list".to_string()))?;
+        Ok(pairs
+            .iter()
+            .filter(|pair| pair["trading"] == "Enabled")
+            .filter_map(|pair| self.from_exchange_symbol(pair["url_symbol"].as_str()?))
+            .collect())
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: true,
+            trading: false,
+            testnet_ws_url: None,
+            max_streams_per_connection: None,
+            streams_per_symbol: 1,
+            max_messages_per_second: None,
+            symbol_format: SymbolFormat::Concatenated,
+        }
+    }
+    
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        Some(&ORDER_BOOK_SCHEMA)
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        
+        match field(&frame, "event")?.as_str().unwrap_or_default() {
+            // {"event": "data", "channel": "order_book_btcusd", "data": {"bids": [..], "asks": [..]}}
+            "data" => {
+                let channel = field(&frame, "channel")?.as_str().unwrap_or_default();
+                let symbol = self
+                    .channel_symbol(&frame)
+                    .ok_or_else(|| NormalizeError::UnknownSymbol(channel.to_string()))?;
+                let data = field(&frame, "data")?;
+                let (bid, bid_size) = Self::best(data, "bids")?;
+                let (ask, ask_size) = Self::best(data, "asks")?;
+                let book = OrderBookUpdate {
+                    exchange: self.name().to_string(),
+                    symbol: symbol.clone(),
+                    snapshot: true,
+                    bids: levels(field(data, "bids")?, "bids")?,
+                    asks: levels(field(data, "asks")?, "asks")?,
+                    sequence: data.get("microtimestamp").and_then(Value::as_str).and_then(|at| at.parse().ok()),
+                    depth: None,
+                };
+                
+                Ok(vec![
+                    NormalizedEvent::Tick(CanonicalTick {
+                        exchange: self.name().to_string(),
+                        symbol,
+                        bid,
+                        bid_size,
+                        ask,
+                        ask_size,
+                    }),
+                    NormalizedEvent::Book(book),
+                ])
+            }
+            "bts:subscription_succeeded" => Ok(vec![NormalizedEvent::SubscriptionAck {
+                request_id: None,
+                symbols: self.channel_symbol(&frame).into_iter().collect(),
+            }]),
+            "bts:error" => Ok(vec![NormalizedEvent::SubscriptionRejected {
+                symbols: self.channel_symbol(&frame).into_iter().collect(),
+                reason: frame
+                    .pointer("/data/message")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            }]),
+            "bts:heartbeat" => Ok(vec![NormalizedEvent::Heartbeat]),
+            // Sent before maintenance; the manager's reconnect loop handles the drop
+            event @ ("bts:request_reconnect" | "bts:unsubscription_succeeded") => {
+                Ok(vec![NormalizedEvent::Control(event.to_string())])
+            }
+            other => Err(NormalizeError::Malformed(format!("unexpected event '{}'", other))),
+        }
+    }
+}
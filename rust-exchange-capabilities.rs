@@ -0,0 +1,252 @@
+// exchange/capabilities.rs - Per-connector capability manifests and Config checks
+use serde::Serialize;
+
+use crate::arbitrage::Config;
+use crate::config::ConfigIssue;
+use super::registry::Exchange;
+
+/// How a venue spells `BASE/QUOTE` on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolFormat {
+    Concatenated, // BTCUSDT
+    Dashed,       // BTC-USD
+    Prefixed,     // tBTCUSD, tDOGE:USD
+    QuoteFirst,   // KRW-BTC
+    Slashed,      // XBT/USD
+    Underscored,  // BTC_USDT
+}
+
+/// What a connector supports, declared by the connector itself
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Capabilities {
+    /// Order book depth (L2) feed available
+    pub depth: bool,
+    /// Order placement implemented for this venue
+    pub trading: bool,
+    /// Sandbox market data endpoint, if the venue has one
+    pub testnet_ws_url: Option<&'static str>,
+    /// Streams (topics) one socket may carry
+    pub max_streams_per_connection: Option<usize>,
+    /// Streams one symbol costs with this connector's subscribe messages
+    pub streams_per_symbol: usize,
+    /// Client-to-server frame limit; subscribe frames are paced to respect it
+    pub max_messages_per_second: Option<u32>,
+    pub symbol_format: SymbolFormat,
+}
+
+/// Streams the configured symbols need on one venue, against what it allows
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SubscriptionBudget {
+    pub exchange: String,
+    pub symbols: usize,
+    pub streams_needed: usize,
+    pub max_streams_per_connection: Option<usize>,
+    /// Connections needed to carry every stream
+    pub connections: usize,
+}
+
+impl SubscriptionBudget {
+    pub fn for_symbols(exchange: &dyn Exchange, symbols: usize) -> Self {
+        let capabilities = exchange.capabilities();
+        let streams_needed = symbols * capabilities.streams_per_symbol;
+        let connections = match capabilities.max_streams_per_connection {
+            Some(limit) => streams_needed.div_ceil(limit.max(1)).max(1),
+            None => 1,
+        };
+        Self {
+            exchange: exchange.name().to_string(),
+            symbols,
+            streams_needed,
+            max_streams_per_connection: capabilities.max_streams_per_connection,
+            connections,
+        }
+    }
+    
+    pub fn fits_one_connection(&self) -> bool {
+        self.connections <= 1
+    }
+}
+
+/// `symbols` split into per-connection shards that each stay within the
+/// venue's stream limit; a single shard when everything fits
+pub fn shard_symbols(exchange: &dyn Exchange, symbols: &[String]) -> Vec<Vec<String>> {
+    match symbols_per_connection(exchange) {
+        Some(per_shard) if !symbols.is_empty() => symbols.chunks(per_shard).map(|chunk| chunk.to_vec()).collect(),
+        _ => vec![symbols.to_vec()],
+    }
+}
+
+/// Existing `shards` edited to carry exactly `symbols`, as a config reload
+/// does without reconnecting: removed symbols leave their shard, new ones fill
+/// shards in order up to the stream limit, and whatever fits nowhere is
+/// returned second, for new connections
+pub fn reshard_symbols(
+    exchange: &dyn Exchange,
+    shards: &[Vec<String>],
+    symbols: &[String],
+) -> (Vec<Vec<String>>, Vec<String>) {
+    let mut resharded: Vec<Vec<String>> = shards
+        .iter()
+        .map(|shard| shard.iter().filter(|symbol| symbols.contains(symbol)).cloned().collect())
+        .collect();
+    let mut added: Vec<String> = symbols
+        .iter()
+        .filter(|symbol| !shards.iter().any(|shard| shard.contains(symbol)))
+        .cloned()
+        .collect();
+    
+    let per_connection = symbols_per_connection(exchange);
+    for shard in &mut resharded {
+        let room = per_connection.map_or(added.len(), |limit| limit.saturating_sub(shard.len()));
+        shard.extend(added.drain(..room.min(added.len())));
+    }
+    (resharded, added)
+}
+
+/// Most symbols one connection can carry; `None` if the venue has no stream limit
+pub fn symbols_per_connection(exchange: &dyn Exchange) -> Option<usize> {
+    let capabilities = exchange.capabilities();
+    capabilities
+        .max_streams_per_connection
+        .map(|limit| (limit / capabilities.streams_per_symbol.max(1)).max(1))
+}
+
+/// Config combinations this exchange can't serve, as field-level issues
+pub fn check_config(exchange: &dyn Exchange, config: &Config) -> Vec<ConfigIssue> {
+    let name = exchange.name();
+    let capabilities = exchange.capabilities();
+    let mut issues = Vec::new();
+    
+    if config.use_testnet && capabilities.testnet_ws_url.is_none() {
+        issues.push(ConfigIssue::new(
+            "use_testnet",
+            format!("{} has no testnet; remove it from exchanges or disable use_testnet", name),
+        ));
+    }
+    
+    if config.order_book.enabled && !capabilities.depth {
+        issues.push(ConfigIssue::new(
+            "order_book.enabled",
+            format!("{} has no depth feed; remove it from exchanges or disable order_book", name),
+        ));
+    }
+    
+    // Over budget fails fast unless sharding may spread it over more sockets;
+    // silently subscribing to a prefix would just lose the rest
+    let budget = SubscriptionBudget::for_symbols(exchange, config.symbols.len());
+    if let Some(limit) = budget.max_streams_per_connection {
+        if !budget.fits_one_connection() && !config.auto_shard_subscriptions {
+            issues.push(ConfigIssue::new(
+                "symbols",
+                format!(
+                    "{} symbols need {} streams, over {}'s limit of {} per connection; enable auto_shard_subscriptions to use {} connections",
+                    budget.symbols, budget.streams_needed, name, limit, budget.connections
+                ),
+            ));
+        }
+    }
+    
+    issues.extend(exchange.config_issues(config));
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{
+        binance::BinanceConnector, bitfinex::BitfinexConnector, bitstamp::BitstampConnector,
+        coinbase::CoinbaseConnector, cryptocom::CryptocomConnector, curve::CurveConnector, deribit::DeribitConnector,
+        dydx::DydxConnector, gateio::GateioConnector, gemini::GeminiConnector, htx::HtxConnector,
+        kraken::KrakenConnector, kucoin::KucoinConnector, mexc::MexcConnector, upbit::UpbitConnector,
+    };
+    
+    fn config_with(symbols: &[&str], use_testnet: bool) -> Config {
+        Config {
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            use_testnet,
+            ..Config::default()
+        }
+    }
+    
+    #[test]
+    fn test_default_symbols_supported_everywhere() {
+        let config = config_with(&["BTC/USDT", "ETH/USDT", "ETH/BTC"], false);
+        
+        assert!(check_config(&BinanceConnector::new(), &config).is_empty());
+        assert!(check_config(&CoinbaseConnector::new(), &config).is_empty());
+        assert!(check_config(&KrakenConnector::new(), &config).is_empty());
+        assert!(check_config(&KucoinConnector::new(), &config).is_empty());
+        assert!(check_config(&GateioConnector::new(), &config).is_empty());
+        assert!(check_config(&HtxConnector::new(), &config).is_empty());
+        assert!(check_config(&BitfinexConnector::new(), &config).is_empty());
+        assert!(check_config(&MexcConnector::new(), &config).is_empty());
+        assert!(check_config(&BitstampConnector::new(), &config).is_empty());
+        assert!(check_config(&GeminiConnector::new(), &config).is_empty());
+        assert!(check_config(&CryptocomConnector::new(), &config).is_empty());
+        assert!(check_config(&UpbitConnector::new(), &config).is_empty());
+        assert!(check_config(&DeribitConnector::new(&Default::default()), &config).is_empty());
+        assert!(check_config(&DydxConnector::new(), &config).is_empty());
+        assert!(check_config(&CurveConnector::new(&Default::default(), &config.symbols), &config).is_empty());
+    }
+    
+    #[test]
+    fn test_testnet_requires_sandbox_endpoint() {
+        let config = config_with(&["BTC/USDT"], true);
+        
+        assert!(check_config(&BinanceConnector::new(), &config).is_empty());
+        let issues = check_config(&KrakenConnector::new(), &config);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].field, "use_testnet");
+    }
+    
+    #[test]
+    fn test_unmappable_symbol_and_symbol_limit() {
+        // Binance can only split concatenated symbols on known quote assets
+        let issues = check_config(&BinanceConnector::new(), &config_with(&["BTC/XYZ"], false));
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].problem.contains("BTC/XYZ"));
+        
+        let many: Vec<String> = (0..1100).map(|i| format!("A{}/USDT", i)).collect();
+        let config = Config { symbols: many, ..Config::default() };
+        let issues = check_config(&BinanceConnector::new(), &config);
+        assert!(issues.iter().any(|issue| issue.problem.contains("limit of 1024")));
+        
+        let sharded = Config { auto_shard_subscriptions: true, ..config };
+        assert!(check_config(&BinanceConnector::new(), &sharded).is_empty());
+    }
+    
+    #[test]
+    fn test_budget_and_shards_respect_stream_limit() {
+        let symbols: Vec<String> = (0..2500).map(|i| format!("A{}/USDT", i)).collect();
+        let budget = SubscriptionBudget::for_symbols(&BinanceConnector::new(), symbols.len());
+        assert_eq!((budget.streams_needed, budget.connections), (2500, 3));
+        
+        let shards = shard_symbols(&BinanceConnector::new(), &symbols);
+        assert_eq!(shards.iter().map(Vec::len).collect::<Vec<_>>(), vec![1024, 1024, 452]);
+        assert_eq!(shards.concat(), symbols);
+        
+        // Coinbase has no published limit, but each product costs ticker + heartbeat
+        let budget = SubscriptionBudget::for_symbols(&CoinbaseConnector::new(), 10);
+        assert_eq!((budget.streams_needed, budget.connections), (20, 1));
+        assert_eq!(shard_symbols(&CoinbaseConnector::new(), &symbols).len(), 1);
+    }
+    
+    #[test]
+    fn test_reshard_fills_open_connections_before_new_ones() {
+        let names = |range: std::ops::Range<usize>| -> Vec<String> { range.map(|i| format!("A{}/USDT", i)).collect() };
+        let shards = vec![names(0..1024), names(1024..1500)];
+        
+        // Drop 0..10 and add 1500..2100: ten take the freed slots, 548 fill
+        // the second connection and 42 need a third
+        let symbols = names(10..2100);
+        let (resharded, overflow) = reshard_symbols(&BinanceConnector::new(), &shards, &symbols);
+        assert_eq!(resharded.iter().map(Vec::len).collect::<Vec<_>>(), vec![1024, 1024]);
+        assert_eq!(resharded[0][1014..], names(1500..1510)[..]);
+        assert_eq!(overflow, names(2058..2100));
+        
+        let (resharded, overflow) = reshard_symbols(&KrakenConnector::new(), &[names(0..3)], &names(2..5));
+        assert_eq!(resharded, vec![names(2..5)]);
+        assert!(overflow.is_empty());
+    }
+}
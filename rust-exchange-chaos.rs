@@ -0,0 +1,136 @@
+// exchange/chaos.rs - Random disconnects, duplicates and reordering on feeds (feature "chaos")
+use tracing::warn;
+
+use crate::faults::{clock_seed, ChaosConfig, SplitMix64};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChaosAction {
+    /// Frames to parse, in order; empty while a frame is held back
+    Deliver(Vec<String>),
+    /// Drop the connection as if the venue closed it
+    Disconnect,
+}
+
+/// Per-exchange chaos state; outlives reconnects so a seeded run doesn't
+/// replay the same disconnect on every new socket
+pub struct Chaos {
+    config: ChaosConfig,
+    rng: SplitMix64,
+    held: Option<String>, // Frame waiting to be overtaken
+}
+
+impl Chaos {
+    pub fn new(config: ChaosConfig, seed: u64) -> Self {
+        Self {
+            config,
+            rng: SplitMix64::new(seed),
+            held: None,
+        }
+    }
+    
+    /// Chaos for `exchange`'s connection, or `None` when it's off. A fixed
+    /// seed is mixed with the exchange name so venues don't fail in lockstep
+    pub fn for_exchange(exchange: &str, config: &ChaosConfig, seed: Option<u64>) -> Option<Self> {
+        if !config.is_active() {
+            return None;
+        }
+        warn!(
+            "Chaos mode on {}: disconnect {}, duplicate {}, reorder {}",
+            exchange, config.disconnect_rate, config.duplicate_rate, config.reorder_rate
+        );
+        let name_hash = exchange
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, b| (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3));
+        Some(Self::new(config.clone(), seed.unwrap_or_else(clock_seed) ^ name_hash))
+    }
+    
+    pub fn process(&mut self, frame: String) -> ChaosAction {
+        if self.rng.next_f64() < self.config.disconnect_rate {
+            self.held = None; // Lost with the socket
+            return ChaosAction::Disconnect;
+        }
+        
+        let mut frames = vec![frame];
+        if self.rng.next_f64() < self.config.duplicate_rate {
+            frames.push(frames[0].clone());
+        }
+        match self.held.take() {
+            Some(held) => frames.push(held),
+            None if self.rng.next_f64() < self.config.reorder_rate => self.held = Some(frames.remove(0)),
+            None => {}
+        }
+        ChaosAction::Deliver(frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchange::binance::BinanceConnector;
+    use crate::exchange::connector::{ExchangeConnector, NormalizedEvent};
+    
+    fn frames(n: u64) -> Vec<String> {
+        (0..n)
+            .map(|u| format!(r#"{{"u":{},"s":"BTCUSDT","b":"{}.0","B":"1.0","a":"{}.5","A":"1.0"}}"#, u, 50_000 + u, 50_000 + u))
+            .collect()
+    }
+    
+    #[test]
+    fn test_duplicates_and_reorders_without_loss() {
+        let config = ChaosConfig {
+            duplicate_rate: 0.2,
+            reorder_rate: 0.2,
+            ..ChaosConfig::default()
+        };
+        let mut chaos = Chaos::new(config.clone(), 9);
+        let mut delivered = Vec::new();
+        for frame in frames(500) {
+            match chaos.process(frame) {
+                ChaosAction::Deliver(frames) => delivered.extend(frames),
+                ChaosAction::Disconnect => panic!("disconnect rate is zero"),
+            }
+        }
+        delivered.extend(chaos.held.take());
+        
+        let mut unique = delivered.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 500);
+        assert!(delivered.len() > 550, "{} frames", delivered.len());
+        assert_ne!(delivered.iter().take(500).collect::<Vec<_>>(), frames(500).iter().collect::<Vec<_>>());
+        
+        let run = |seed| {
+            let mut chaos = Chaos::new(config.clone(), seed);
+            frames(50).into_iter().map(|f| chaos.process(f)).collect::<Vec<_>>()
+        };
+        assert_eq!(run(9), run(9), "same seed, same chaos");
+    }
+    
+    #[test]
+    fn test_chaotic_feed_still_normalizes_and_keeps_flowing_after_disconnects() {
+        let connector = BinanceConnector::new();
+        let mut chaos = Chaos::new(
+            ChaosConfig {
+                disconnect_rate: 0.05,
+                duplicate_rate: 0.1,
+                reorder_rate: 0.1,
+            },
+            3,
+        );
+        let (mut disconnects, mut ticks) = (0, 0);
+        for frame in frames(1000) {
+            match chaos.process(frame) {
+                ChaosAction::Disconnect => disconnects += 1,
+                ChaosAction::Deliver(frames) => {
+                    for frame in frames {
+                        let events = connector.normalize(&frame).expect("chaos must not corrupt frames");
+                        ticks += events.iter().filter(|e| matches!(e, NormalizedEvent::Tick(_))).count();
+                    }
+                }
+            }
+        }
+        
+        assert!((20..80).contains(&disconnects), "{} disconnects", disconnects);
+        assert!(ticks > 900, "{} ticks", ticks);
+    }
+}
@@ -0,0 +1,210 @@
+// exchange/coinbase.rs - Coinbase Exchange connector (ticker channel)
+use serde_json::{json, Value};
+
+use super::connector::{
+    decimal, field, levels, parse_frame, CanonicalTick, ExchangeConnector, NormalizeError, NormalizedEvent,
+    OrderBookUpdate,
+};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::schema::MessageSchema;
+
+const WS_URL: &str = "wss://ws-feed.exchange.coinbase.com";
+const TESTNET_WS_URL: &str = "wss://ws-feed-public.sandbox.exchange.coinbase.com";
+const INSTRUMENTS_URL: &str = "https://api.exchange.coinbase.com/products";
+
+static TICKER_SCHEMA: MessageSchema = MessageSchema {
+    required: &["type", "product_id", "best_bid", "best_bid_size", "best_ask", "best_ask_size"],
+    optional: &[
+        "sequence", "price", "open_24h", "volume_24h", "low_24h", "high_24h", "volume_30d",
+        "side", "time", "trade_id", "last_size",
+    ],
+    payload: ticker_payload,
+};
+
+fn ticker_payload(frame: &Value) -> Option<&Value> {
+    frame.get("type").filter(|t| *t == "ticker").map(|_| frame)
+}
+
+pub struct CoinbaseConnector;
+
+impl CoinbaseConnector {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ExchangeConnector for CoinbaseConnector {
+    fn name(&self) -> &'static str {
+        "coinbase"
+    }
+    
+    fn ws_url(&self) -> &str {
+        WS_URL
+    }
+    
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        symbol.replace('/', "-").to_uppercase()
+    }
+    
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        let (base, quote) = raw.split_once('-')?;
+        Some(format!("{}/{}", base.to_uppercase(), quote.to_uppercase()))
+    }
+    
+    fn subscribe_messages(&self, symbols: &[String], _request_id: u64) -> Vec<String> {
+        let product_ids: Vec<String> = symbols.iter().map(|s| self.to_exchange_symbol(s)).collect();
+        
+        vec![json!({
+            "type": "subscribe",
+            "product_ids": product_ids,
+            "channels": ["ticker", "heartbeat"],
+        })
+        .to_string()]
+    }
+    
+    fn unsubscribe_messages(&self, symbols: &[String]) -> Vec<String> {
+        let product_ids: Vec<String> = symbols.iter().map(|s| self.to_exchange_symbol(s)).collect();
+        
+        vec![json!({
+            "type": "unsubscribe",
+            "product_ids": product_ids,
+            "channels": ["ticker", "heartbeat"],
+        })
+        .to_string()]
+    }
+    
+    /// `level2_batch` sends the whole book, then changed levels every 50ms;
+    /// its depth can't be chosen
+    fn depth_subscribe_messages(&self, symbols: &[String], _levels: usize, _request_id: u64) -> Vec<String> {
+        let product_ids: Vec<String> = symbols.iter().map(|s| self.to_exchange_symbol(s)).collect();
+        
+        vec![json!({
+            "type": "subscribe",
+            "product_ids": product_ids,
+            "channels": ["level2_batch"],
+        })
+        .to_string()]
+    }
+    
+    fn depth_unsubscribe_messages(&self, symbols: &[String], _levels: usize) -> Vec<String> {
+        let product_ids: Vec<String> = symbols.iter().map(|s| self.to_exchange_symbol(s)).collect();
+        
+        vec![json!({
+            "type": "unsubscribe",
+            "product_ids": product_ids,
+            "channels": ["level2_batch"],
+        })
+        .to_string()]
+    }
+    
+    fn instruments_url(&self) -> Option<&str> {
+        Some(INSTRUMENTS_URL)
+    }
+    
+    fn parse_instruments(&self, body: &str) -> Result<Vec<String>, NormalizeError> {
+        let products = parse_frame(body)?;
+        let products = products.as_array().ok_or_else(|| NormalizeError::Malformed("expected a product list".to_string()))?;
+        Ok(products
+            .iter()
+            .filter(|product| product["status"] == "online" && product["trading_disabled"] != true)
+            .filter_map(|product| self.from_exchange_symbol(product["id"].as_str()?))
+            .collect())
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: true,
+            trading: false,
+            testnet_ws_url: Some(TESTNET_WS_URL),
+            max_streams_per_connection: None,
+            streams_per_symbol: 2, // ticker + heartbeat
+            max_messages_per_second: Some(8),
+            symbol_format: SymbolFormat::Dashed,
+        }
+    }
+    
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        Some(&TICKER_SCHEMA)
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        let message_type = field(&frame, "type")?.as_str().unwrap_or_default();
+        
+        match message_type {
+            "ticker" => {
+                let product_id = field(&frame, "product_id")?
+                    .as_str()
+                    .ok_or(NormalizeError::MissingField("product_id"))?;
+                let symbol = self
+                    .from_exchange_symbol(product_id)
+                    .ok_or_else(|| NormalizeError::UnknownSymbol(product_id.to_string()))?;
+                
+                Ok(vec![NormalizedEvent::Tick(CanonicalTick {
+                    exchange: self.name().to_string(),
+                    symbol,
+                    bid: decimal(field(&frame, "best_bid")?, "best_bid")?,
+                    bid_size: decimal(field(&frame, "best_bid_size")?, "best_bid_size")?,
+                    ask: decimal(field(&frame, "best_ask")?, "best_ask")?,
+                    ask_size: decimal(field(&frame, "best_ask_size")?, "best_ask_size")?,
+                })])
+            }
+            "snapshot" | "l2update" => {
+                let product_id = field(&frame, "product_id")?
+                    .as_str()
+                    .ok_or(NormalizeError::MissingField("product_id"))?;
+                let symbol = self
+                    .from_exchange_symbol(product_id)
+                    .ok_or_else(|| NormalizeError::UnknownSymbol(product_id.to_string()))?;
+                let mut update = OrderBookUpdate {
+                    exchange: self.name().to_string(),
+                    symbol,
+                    snapshot: message_type == "snapshot",
+                    bids: Vec::new(),
+                    asks: Vec::new(),
+                    sequence: None,
+                    depth: None,
+                };
+                if update.snapshot {
+                    update.bids = levels(field(&frame, "bids")?, "bids")?;
+                    update.asks = levels(field(&frame, "asks")?, "asks")?;
+                } else {
+                    // Changes are [side, price, new size]
+                    let changes = field(&frame, "changes")?.as_array().ok_or(NormalizeError::MissingField("changes"))?;
+                    for change in changes {
+                        let side = change.get(0).and_then(Value::as_str).ok_or(NormalizeError::MissingField("changes"))?;
+                        let price = decimal(change.get(1).ok_or(NormalizeError::MissingField("changes"))?, "changes")?;
+                        let size = decimal(change.get(2).ok_or(NormalizeError::MissingField("changes"))?, "changes")?;
+                        match side {
+                            "buy" => update.bids.push((price, size)),
+                            "sell" => update.asks.push((price, size)),
+                            other => return Err(NormalizeError::Malformed(format!("unexpected side '{}'", other))),
+                        }
+                    }
+                }
+                Ok(vec![NormalizedEvent::Book(update)])
+            }
+            "heartbeat" => Ok(vec![NormalizedEvent::Heartbeat]),
+            "subscriptions" => {
+                // Coinbase echoes the full subscription set for every channel
+                let mut symbols: Vec<String> = frame
+                    .get("channels")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter(|channel| channel.get("name").map_or(false, |n| n == "ticker"))
+                    .filter_map(|channel| channel.get("product_ids").and_then(Value::as_array))
+                    .flatten()
+                    .filter_map(|id| id.as_str().and_then(|id| self.from_exchange_symbol(id)))
+                    .collect();
+                symbols.dedup();
+                
+                Ok(vec![NormalizedEvent::SubscriptionAck { request_id: None, symbols }])
+            }
+            "error" => Err(NormalizeError::Malformed(
+                frame.get("message").map(|m| m.to_string()).unwrap_or_default(),
+            )),
+            other => Err(NormalizeError::Malformed(format!("unexpected message type '{}'", other))),
+        }
+    }
+}
@@ -0,0 +1,495 @@
+// exchange/conformance.rs - Checks every ExchangeConnector must pass, against a scripted mock venue
+use std::collections::BTreeSet;
+use serde_json::Value;
+
+use crate::arbitrage::depth::{BookApply, DepthBooks, OrderBookConfig};
+use super::connector::{ExchangeConnector, NormalizeError, NormalizedEvent};
+
+/// Levels per side a book checksum covers, as on the venues that send one
+pub const CHECKSUM_LEVELS: usize = 25;
+
+/// Frames no venue sends whole; a connector may reject or ignore them, but
+/// never turn them into market data
+const GARBAGE_FRAMES: &[&str] = &["", "{", "null", "[]", "{}", "[1, 2", "\"ticker\""];
+const REQUEST_ID: u64 = 1;
+
+/// One thing the mock venue does, in script order
+#[derive(Debug, Clone)]
+pub enum Step {
+    /// Send a frame to the connector
+    Send(String),
+    /// Drop the socket; the kit clears the books and resubscribes, as the manager does
+    Disconnect,
+    /// The venue's own book for `symbol` at this point; the one rebuilt from
+    /// the connector's updates must have the same checksum
+    ExpectBook { symbol: String, bids: Vec<(f64, f64)>, asks: Vec<(f64, f64)> },
+}
+
+/// A venue that plays a script against a connector, standing in for the
+/// socket and the manager: it subscribes on every connect, hands each frame
+/// to `normalize`, applies book updates to a `DepthBooks` and asks for a
+/// resync when one falls out of sync. Every connect uses the same request
+/// id, so the frames of different connections compare
+#[derive(Debug, Clone)]
+pub struct MockVenue {
+    symbols: Vec<String>,
+    depth_levels: Option<usize>,
+    script: Vec<Step>,
+}
+
+/// What one connection saw
+#[derive(Debug, Default)]
+pub struct ConnectionLog {
+    /// Subscribe frames sent on connect, depth included
+    pub subscribed: Vec<String>,
+    pub events: Vec<NormalizedEvent>,
+    pub errors: Vec<NormalizeError>,
+    /// Frames sent to resync books that fell out of sync, per symbol
+    pub resyncs: Vec<(String, Vec<String>)>,
+}
+
+/// What a script run produced; one log per connection
+#[derive(Debug, Default)]
+pub struct Session {
+    pub connections: Vec<ConnectionLog>,
+    /// One line per `ExpectBook` whose checksum didn't match
+    pub checksum_mismatches: Vec<String>,
+}
+
+impl MockVenue {
+    pub fn new(symbols: &[&str]) -> Self {
+        Self {
+            symbols: symbols.iter().map(|symbol| symbol.to_string()).collect(),
+            depth_levels: None,
+            script: Vec::new(),
+        }
+    }
+    
+    /// A venue replaying a normalization fixture (tests/fixtures/normalization/)
+    /// as one session: each case's setup frames, then its raw frame. Cases
+    /// the fixture marks as errors are left out
+    pub fn from_fixture(symbols: &[&str], fixture: &str) -> Self {
+        let fixture: Value = serde_json::from_str(fixture).expect("invalid fixture");
+        let mut venue = Self::new(symbols);
+        for case in fixture["cases"].as_array().into_iter().flatten() {
+            if case["error"].as_bool().unwrap_or(false) {
+                continue;
+            }
+            for frame in case["setup"].as_array().into_iter().flatten() {
+                venue = venue.send(&frame.to_string());
+            }
+            venue = venue.send(&case["raw"].to_string());
+        }
+        venue
+    }
+    
+    /// Subscribe to `levels` of depth too, on every connect
+    pub fn with_depth(mut self, levels: usize) -> Self {
+        self.depth_levels = Some(levels);
+        self
+    }
+    
+    pub fn send(mut self, frame: &str) -> Self {
+        self.script.push(Step::Send(frame.to_string()));
+        self
+    }
+    
+    pub fn disconnect(mut self) -> Self {
+        self.script.push(Step::Disconnect);
+        self
+    }
+    
+    pub fn expect_book(mut self, symbol: &str, bids: &[(f64, f64)], asks: &[(f64, f64)]) -> Self {
+        self.script.push(Step::ExpectBook {
+            symbol: symbol.to_string(),
+            bids: bids.to_vec(),
+            asks: asks.to_vec(),
+        });
+        self
+    }
+    
+    fn subscribe(&self, connector: &dyn ExchangeConnector) -> Vec<String> {
+        let mut frames = connector.subscribe_messages(&self.symbols, REQUEST_ID);
+        if let Some(levels) = self.depth_levels {
+            frames.extend(connector.depth_subscribe_messages(&self.symbols, levels, REQUEST_ID));
+        }
+        frames
+    }
+    
+    /// Play the script against `connector`
+    pub fn run(&self, connector: &dyn ExchangeConnector) -> Session {
+        let books = DepthBooks::new(&OrderBookConfig::default());
+        let levels = self.depth_levels.unwrap_or(CHECKSUM_LEVELS);
+        let mut session = Session::default();
+        let mut log = ConnectionLog {
+            subscribed: self.subscribe(connector),
+            ..Default::default()
+        };
+        
+        for step in &self.script {
+            match step {
+                Step::Send(frame) => match connector.normalize(frame) {
+                    Ok(events) => {
+                        for event in events {
+                            if let NormalizedEvent::Book(update) = &event {
+                                if books.apply(update) == BookApply::OutOfSync {
+                                    let symbol = vec![update.symbol.clone()];
+                                    let mut frames = connector.depth_unsubscribe_messages(&symbol, levels);
+                                    frames.extend(connector.depth_subscribe_messages(&symbol, levels, 0));
+                                    log.resyncs.push((update.symbol.clone(), frames));
+                                }
+                            }
+                            log.events.push(event);
+                        }
+                    }
+                    Err(e) => log.errors.push(e),
+                },
+                Step::Disconnect => {
+                    books.clear(connector.name(), &self.symbols);
+                    session.connections.push(std::mem::take(&mut log));
+                    log.subscribed = self.subscribe(connector);
+                }
+                Step::ExpectBook { symbol, bids, asks } => {
+                    let expected = book_checksum(bids, asks);
+                    let actual = books
+                        .view(connector.name(), symbol, CHECKSUM_LEVELS)
+                        .map(|view| book_checksum(&view.bids, &view.asks));
+                    if actual != Some(expected) {
+                        session.checksum_mismatches.push(format!(
+                            "{} book checksum {:?}, venue has {} (bids {:?}, asks {:?})",
+                            symbol, actual, expected, bids, asks
+                        ));
+                    }
+                }
+            }
+        }
+        session.connections.push(log);
+        session
+    }
+}
+
+/// CRC-32 (IEEE) of `bytes`
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// Checksum of a book's best `CHECKSUM_LEVELS` levels per side, OKX-style:
+/// CRC-32 of "bid:size:ask:size:..." alternating sides from the top. Levels
+/// may come in any order
+pub fn book_checksum(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> u32 {
+    let mut bids = bids.to_vec();
+    let mut asks = asks.to_vec();
+    bids.sort_by(|a, b| b.0.total_cmp(&a.0));
+    asks.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut fields = Vec::new();
+    for level in 0..CHECKSUM_LEVELS {
+        for side in [&bids, &asks] {
+            if let Some((price, size)) = side.get(level) {
+                fields.push(format!("{}:{}", price, size));
+            }
+        }
+    }
+    crc32(fields.join(":").as_bytes())
+}
+
+/// Whether `symbol` is in the internal form a connector maps to and from
+/// itself: it must survive a trip to the venue's spelling and back
+fn is_canonical(connector: &dyn ExchangeConnector, symbol: &str) -> bool {
+    symbol.split_once('/').is_some_and(|(base, quote)| !base.is_empty() && !quote.is_empty())
+        && symbol == symbol.to_uppercase()
+        && connector.from_exchange_symbol(&connector.to_exchange_symbol(symbol)).as_deref() == Some(symbol)
+}
+
+/// Everything `connector` gets wrong, checked on its own and against
+/// `venue`'s script; empty when it conforms
+pub fn check(connector: &dyn ExchangeConnector, venue: &MockVenue) -> Vec<String> {
+    let name = connector.name();
+    let capabilities = connector.capabilities();
+    let mut failures = Vec::new();
+    
+    // The connector on its own
+    if name.is_empty() || name.chars().any(|c| !(c.is_ascii_lowercase() || c.is_ascii_digit())) {
+        failures.push(format!("name {:?} must be lowercase letters and digits, as in `exchanges`", name));
+    }
+    for url in std::iter::once(connector.ws_url()).chain(capabilities.testnet_ws_url) {
+        if !(url.starts_with("wss://") || url.starts_with("ws://")) {
+            failures.push(format!("{:?} is not a WebSocket URL", url));
+        }
+    }
+    if capabilities.max_messages_per_second == Some(0) || capabilities.max_streams_per_connection == Some(0) {
+        failures.push("a declared rate or stream limit of zero would never subscribe".to_string());
+    }
+    if !capabilities.depth && !connector.depth_subscribe_messages(&venue.symbols, 10, 1).is_empty() {
+        failures.push("sends depth subscriptions without declaring depth".to_string());
+    }
+    
+    // Symbol normalization: every symbol maps to a venue spelling whose
+    // internal form maps back to that same spelling
+    for symbol in venue.symbols.iter().map(String::as_str).chain(["BTC/USDT", "ETH/BTC"]) {
+        let native = connector.to_exchange_symbol(symbol);
+        match connector.from_exchange_symbol(&native) {
+            Some(internal) if is_canonical(connector, &internal) && connector.to_exchange_symbol(&internal) == native => {}
+            other => failures.push(format!("{} -> {:?} -> {:?} is not a stable round trip", symbol, native, other)),
+        }
+    }
+    if let Some(internal) = connector.from_exchange_symbol("") {
+        failures.push(format!("empty venue symbol maps to {:?}", internal));
+    }
+    for frame in GARBAGE_FRAMES {
+        if let Ok(events) = connector.normalize(frame) {
+            if events.iter().any(|event| matches!(event, NormalizedEvent::Tick(_) | NormalizedEvent::Book(_))) {
+                failures.push(format!("garbage frame {:?} produced market data: {:?}", frame, events));
+            }
+        }
+    }
+    
+    let session = venue.run(connector);
+    failures.extend(session.checksum_mismatches.iter().cloned());
+    
+    // Canonical events
+    for event in session.connections.iter().flat_map(|log| &log.events) {
+        match event {
+            NormalizedEvent::Tick(tick) => {
+                if tick.exchange != name || !is_canonical(connector, &tick.symbol) {
+                    failures.push(format!("tick for {}/{} is not in canonical form", tick.exchange, tick.symbol));
+                }
+                let prices_valid = [tick.bid, tick.ask].iter().all(|price| price.is_finite() && *price > 0.0);
+                let sizes_valid = [tick.bid_size, tick.ask_size].iter().all(|size| size.is_finite() && *size >= 0.0);
+                if !prices_valid || !sizes_valid || tick.bid > tick.ask {
+                    failures.push(format!("degenerate tick {:?}", tick));
+                }
+            }
+            NormalizedEvent::Book(update) => {
+                if !capabilities.depth {
+                    failures.push(format!("book update for {} without declaring depth", update.symbol));
+                }
+                if update.exchange != name || !is_canonical(connector, &update.symbol) {
+                    failures.push(format!("book for {}/{} is not in canonical form", update.exchange, update.symbol));
+                }
+                let levels_valid = update
+                    .bids
+                    .iter()
+                    .chain(&update.asks)
+                    .all(|(price, size)| price.is_finite() && *price > 0.0 && size.is_finite() && *size >= 0.0);
+                if !levels_valid {
+                    failures.push(format!("book update for {} has invalid levels", update.symbol));
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    // Reconnect: a new socket asks for the same streams as the first, and
+    // so does resubscribing everything after unsubscribing it
+    let first = &session.connections[0].subscribed;
+    if !venue.symbols.is_empty() && first.is_empty() {
+        failures.push("subscribing sends no frames".to_string());
+    }
+    let streams = |frames: &[String]| frames.iter().cloned().collect::<BTreeSet<_>>();
+    if session.connections.iter().any(|log| streams(&log.subscribed) != streams(first)) {
+        failures.push("a reconnect subscribes to different streams than the first connect".to_string());
+    }
+    // Unsubscribing: each symbol has frames of its own, unless the venue
+    // has no unsubscribe at all
+    let unsubscribed: Vec<Vec<String>> = venue
+        .symbols
+        .iter()
+        .map(|symbol| connector.unsubscribe_messages(std::slice::from_ref(symbol)))
+        .collect();
+    let all = connector.unsubscribe_messages(&venue.symbols);
+    if !all.is_empty() || unsubscribed.iter().any(|frames| !frames.is_empty()) {
+        if all.is_empty() {
+            failures.push("unsubscribing everything sends no frames, but unsubscribing one symbol does".to_string());
+        }
+        for (symbol, frames) in venue.symbols.iter().zip(&unsubscribed) {
+            if frames.is_empty() {
+                failures.push(format!("unsubscribing {} sends no frames", symbol));
+            }
+        }
+    }
+    if streams(&venue.subscribe(connector)) != streams(first) {
+        failures.push("resubscribing after unsubscribing everything asks for different streams".to_string());
+    }
+    
+    // A session replayed on a fresh connection normalizes as it did the first time
+    let frames: Vec<&Step> = venue.script.iter().take_while(|step| !matches!(step, Step::Disconnect)).collect();
+    let mut replayed = Vec::new();
+    for step in &frames {
+        if let Step::Send(frame) = step {
+            replayed.extend(connector.normalize(frame).unwrap_or_default());
+        }
+    }
+    let first_events: Vec<&NormalizedEvent> = session.connections[0].events.iter().collect();
+    if replayed.iter().collect::<Vec<_>>() != first_events {
+        failures.push("replaying the first connection's frames after a reconnect normalizes differently".to_string());
+    }
+    
+    // Resync: a book out of sync is asked for again
+    for (symbol, frames) in session.connections.iter().flat_map(|log| &log.resyncs) {
+        if frames.is_empty() {
+            failures.push(format!("{} fell out of sync and resyncing it sends no frames", symbol));
+        }
+    }
+    failures
+}
+
+/// Panic with everything `connector` gets wrong against `venue`
+pub fn assert_conforms(connector: &dyn ExchangeConnector, venue: &MockVenue) {
+    let failures = check(connector, venue);
+    assert!(failures.is_empty(), "{} does not conform:\n  {}", connector.name(), failures.join("\n  "));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::clock::ManualClock;
+    use super::super::{
+        binance::BinanceConnector, bitfinex::BitfinexConnector, bitstamp::BitstampConnector,
+        coinbase::CoinbaseConnector, cryptocom::CryptocomConnector, deribit::DeribitConnector, gateio::GateioConnector,
+        gemini::GeminiConnector, htx::HtxConnector, kraken::KrakenConnector, kucoin::KucoinConnector,
+        mexc::MexcConnector, registry::ExchangeRegistry, tardis::TardisConnector, upbit::UpbitConnector,
+    };
+    
+    #[test]
+    fn test_connectors_conform_on_their_fixtures() {
+        // Each venue replays its normalization fixture; Crypto.com stamps
+        // its requests, so its clock stands still
+        let connectors: Vec<(Box<dyn ExchangeConnector>, &[&str], &str)> = vec![
+            (
+                Box::new(BinanceConnector::new()) as Box<dyn ExchangeConnector>,
+                &["BTC/USDT", "ETH/USDT"],
+                include_str!("../../tests/fixtures/normalization/binance.json"),
+            ),
+            (
+                Box::new(BitfinexConnector::new()),
+                &["BTC/USDT"],
+                include_str!("../../tests/fixtures/normalization/bitfinex.json"),
+            ),
+            (
+                Box::new(BitstampConnector::new()),
+                &["BTC/USDT"],
+                include_str!("../../tests/fixtures/normalization/bitstamp.json"),
+            ),
+            (
+                Box::new(CoinbaseConnector::new()),
+                &["BTC/USDT", "ETH/USD"],
+                include_str!("../../tests/fixtures/normalization/coinbase.json"),
+            ),
+            (
+                Box::new(CryptocomConnector::new().with_clock(Arc::new(ManualClock::new(1_700_000_000_000)))),
+                &["BTC/USDT"],
+                include_str!("../../tests/fixtures/normalization/cryptocom.json"),
+            ),
+            (
+                Box::new(DeribitConnector::new(&Default::default())),
+                &["BTC/USDT"],
+                include_str!("../../tests/fixtures/normalization/deribit.json"),
+            ),
+            (
+                Box::new(GateioConnector::new()),
+                &["BTC/USDT"],
+                include_str!("../../tests/fixtures/normalization/gateio.json"),
+            ),
+            (
+                Box::new(GeminiConnector::new()),
+                &["BTC/USDT", "ETH/USD", "BTC/GUSD", "SOL/USD"],
+                include_str!("../../tests/fixtures/normalization/gemini.json"),
+            ),
+            (
+                Box::new(HtxConnector::new()),
+                &["BTC/USDT"],
+                include_str!("../../tests/fixtures/normalization/htx.json"),
+            ),
+            (
+                Box::new(KrakenConnector::new()),
+                &["BTC/USDT", "DOGE/USD"],
+                include_str!("../../tests/fixtures/normalization/kraken.json"),
+            ),
+            (
+                Box::new(KucoinConnector::new()),
+                &["BTC/USDT"],
+                include_str!("../../tests/fixtures/normalization/kucoin.json"),
+            ),
+            (
+                Box::new(MexcConnector::new()),
+                &["BTC/USDT"],
+                include_str!("../../tests/fixtures/normalization/mexc.json"),
+            ),
+            (
+                Box::new(UpbitConnector::new()),
+                &["BTC/KRW"],
+                include_str!("../../tests/fixtures/normalization/upbit.json"),
+            ),
+        ];
+        for (connector, symbols, fixture) in connectors {
+            let venue = MockVenue::from_fixture(symbols, fixture).with_depth(10);
+            assert_conforms(connector.as_ref(), &venue);
+        }
+    }
+    
+    #[test]
+    fn test_mock_venue_catches_lost_updates_and_resyncs() {
+        let snapshot = r#"[336, {"as": [["50001.00000", "2.00000000", "1718020800.1"], ["50002.00000", "1.00000000", "1718020800.1"]], "bs": [["50000.00000", "1.50000000", "1718020800.1"]]}, "book-10", "XBT/USDT"]"#;
+        let update = r#"[336, {"a": [["50001.00000", "0.00000000", "1718020801.1"]]}, {"b": [["49999.00000", "0.40000000", "1718020801.2", "r"]]}, "book-10", "XBT/USDT"]"#;
+        let script = || {
+            MockVenue::new(&["BTC/USDT"])
+                .with_depth(10)
+                .send(snapshot)
+                .send(update)
+                .expect_book("BTC/USDT", &[(50000.0, 1.5), (49999.0, 0.4)], &[(50002.0, 1.0)])
+                .disconnect()
+                // The update overtakes the snapshot on the new socket
+                .send(update)
+                .send(snapshot)
+        };
+        let kraken = KrakenConnector::new();
+        assert_conforms(&kraken, &script());
+        
+        let session = script().expect_book("BTC/USDT", &[(50000.0, 1.5)], &[(50001.0, 2.0), (50002.0, 1.0)]).run(&kraken);
+        assert_eq!(session.connections.len(), 2);
+        assert!(session.checksum_mismatches.is_empty(), "{:?}", session.checksum_mismatches);
+        let (symbol, frames) = &session.connections[1].resyncs[0];
+        assert_eq!(symbol, "BTC/USDT");
+        assert!(frames.iter().any(|frame| frame.contains("subscribe") && frame.contains("XBT/USDT")), "{:?}", frames);
+        
+        // The venue dropped a level the connector never heard about
+        let stale = script().expect_book("BTC/USDT", &[(50000.0, 1.5)], &[(50002.0, 1.0)]);
+        assert_eq!(check(&kraken, &stale).len(), 1);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+    
+    #[test]
+    fn test_check_fails_when_the_venue_book_checksum_differs() {
+        let depth = r#"{"stream": "btcusdt@depth10@100ms", "data": {"lastUpdateId": 7, "bids": [["50000.00", "1.5"]], "asks": [["50001.00", "2.0"]]}}"#;
+        let venue = || MockVenue::new(&["BTC/USDT"]).with_depth(10).send(depth);
+        let binance = BinanceConnector::new();
+        assert_conforms(&binance, &venue().expect_book("BTC/USDT", &[(50000.0, 1.5)], &[(50001.0, 2.0)]));
+        
+        // The venue's book has a size the connector never reported
+        let failures = check(&binance, &venue().expect_book("BTC/USDT", &[(50000.0, 1.4)], &[(50001.0, 2.0)]));
+        assert_eq!(failures.len(), 1, "{:?}", failures);
+        assert!(failures[0].starts_with("BTC/USDT book checksum"), "{}", failures[0]);
+    }
+    
+    #[test]
+    fn test_check_wants_unsubscribe_frames_for_every_symbol() {
+        // Bitfinex unsubscribes by channel id, so only symbols with a known channel can go
+        let bitfinex = BitfinexConnector::new();
+        let subscribed = r#"{"event": "subscribed", "channel": "ticker", "chanId": 17470, "symbol": "tBTCUST", "pair": "BTCUST"}"#;
+        let venue = MockVenue::new(&["BTC/USDT", "ETH/USDT"]).send(subscribed);
+        assert_eq!(check(&bitfinex, &venue), vec!["unsubscribing ETH/USDT sends no frames".to_string()]);
+        
+        // Tardis subscribes in its URL and has no unsubscribe
+        let tardis = TardisConnector::new(&Default::default(), &[], &ExchangeRegistry::builtin());
+        assert!(tardis.unsubscribe_messages(&["BTC/USDT".to_string()]).is_empty());
+        assert!(check(&tardis, &MockVenue::new(&[])).is_empty());
+    }
+}
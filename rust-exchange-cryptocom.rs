@@ -0,0 +1,259 @@
+// exchange/cryptocom.rs - Crypto.com Exchange connector (v1 market ticker and book channels)
+use std::time::Duration;
+use serde_json::{json, Value};
+
+use crate::clock::{self, SharedClock};
+use super::connector::{
+    decimal, field, levels, list, parse_frame, CanonicalTick, ExchangeConnector, NormalizeError, NormalizedEvent,
+    OrderBookUpdate,
+};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::schema::MessageSchema;
+
+const WS_URL: &str = "wss://stream.crypto.com/exchange/v1/market";
+const TESTNET_WS_URL: &str = "wss://uat-stream.3ona.co/exchange/v1/market";
+const INSTRUMENTS_URL: &str = "https://api.crypto.com/exchange/v1/public/get-instruments";
+const TICKER_CHANNEL: &str = "ticker.";
+const BOOK_CHANNEL: &str = "book.";
+/// Book depths Crypto.com streams
+const DEPTH_LEVELS: &[usize] = &[10, 50];
+
+static TICKER_SCHEMA: MessageSchema = MessageSchema {
+    required: &["i", "b", "bs", "k", "ks"],
+    optional: &["h", "l", "a", "c", "v", "vv", "oi", "t"],
+    payload: ticker_payload,
+};
+
+fn ticker_payload(frame: &Value) -> Option<&Value> {
+    frame
+        .pointer("/result/channel")
+        .filter(|channel| *channel == "ticker")
+        .and(frame.pointer("/result/data/0"))
+}
+
+/// Rate limits are counted from the second a socket opens, so requests sent
+/// straight away can be refused; the venue asks clients to wait a second
+pub struct CryptocomConnector {
+    /// Stamps each request's nonce
+    clock: SharedClock,
+}
+
+impl CryptocomConnector {
+    pub fn new() -> Self {
+        Self { clock: clock::system() }
+    }
+    
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    fn request(&self, method: &str, params: Value, request_id: u64) -> String {
+        json!({
+            "id": request_id,
+            "method": method,
+            "params": params,
+            "nonce": self.clock.unix_ms(),
+        })
+        .to_string()
+    }
+    
+    fn ticker_params(&self, symbols: &[String]) -> Value {
+        let channels: Vec<String> = symbols
+            .iter()
+            .map(|s| format!("{}{}", TICKER_CHANNEL, self.to_exchange_symbol(s)))
+            .collect();
+        json!({ "channels": channels })
+    }
+    
+    /// Book channels in snapshot mode, so every push is a whole top-N book
+    fn book_params(&self, symbols: &[String], levels: usize) -> Value {
+        let depth = DEPTH_LEVELS.iter().copied().find(|&depth| depth >= levels).unwrap_or(50);
+        let channels: Vec<String> = symbols
+            .iter()
+            .map(|s| format!("{}{}.{}", BOOK_CHANNEL, self.to_exchange_symbol(s), depth))
+            .collect();
+        json!({ "channels": channels, "book_subscription_type": "SNAPSHOT", "book_update_frequency": 100 })
+    }
+    
+    /// Internal symbol a ticker or book channel names
+    fn channel_symbol(&self, channel: &str) -> Option<String> {
+        let instrument = match channel.strip_prefix(BOOK_CHANNEL) {
+            Some(book) => book.rsplit_once('.').map_or(book, |(instrument, _depth)| instrument),
+            None => channel.strip_prefix(TICKER_CHANNEL)?,
+        };
+        self.from_exchange_symbol(instrument)
+    }
+    
+    /// Ticker entry: {"i": "BTC_USDT", "b": bid, "bs": bid size, "k": ask, "ks": ask size, ..}
+    fn tick(&self, data: &Value) -> Result<NormalizedEvent, NormalizeError> {
+        let instrument = field(data, "i")?.as_str().ok_or(NormalizeError::MissingField("i"))?;
+        let symbol = self
+            .from_exchange_symbol(instrument)
+            .ok_or_else(|| NormalizeError::UnknownSymbol(instrument.to_string()))?;
+        
+        Ok(NormalizedEvent::Tick(CanonicalTick {
+            exchange: self.name().to_string(),
+            symbol,
+            bid: decimal(field(data, "b")?, "b")?,
+            bid_size: decimal(field(data, "bs")?, "bs")?,
+            ask: decimal(field(data, "k")?, "k")?,
+            ask_size: decimal(field(data, "ks")?, "ks")?,
+        }))
+    }
+    
+    /// Book entry: {"bids": [["price", "qty", "orders"], ..], "asks": [..], "u": update id}
+    fn book(&self, symbol: &str, data: &Value) -> Result<NormalizedEvent, NormalizeError> {
+        Ok(NormalizedEvent::Book(OrderBookUpdate {
+            exchange: self.name().to_string(),
+            symbol: symbol.to_string(),
+            snapshot: true,
+            bids: levels(field(data, "bids")?, "bids")?,
+            asks: levels(field(data, "asks")?, "asks")?,
+            sequence: data.get("u").and_then(Value::as_u64),
+            depth: None,
+        }))
+    }
+}
+
+impl ExchangeConnector for CryptocomConnector {
+    fn name(&self) -> &'static str {
+        "cryptocom"
+    }
+    
+    fn ws_url(&self) -> &str {
+        WS_URL
+    }
+    
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        symbol.replace('/', "_").to_uppercase()
+    }
+    
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        let (base, quote) = raw.split_once('_')?;
+        if base.is_empty() || quote.is_empty() {
+            return None;
+        }
+        Some(format!("{}/{}", base.to_uppercase(), quote.to_uppercase()))
+    }
+    
+    fn subscribe_messages(&self, symbols: &[String], request_id: u64) -> Vec<String> {
+        vec![self.request("subscribe", self.ticker_params(symbols), request_id)]
+    }
+    
+    fn subscribe_delay(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+    
+    fn unsubscribe_messages(&self, symbols: &[String]) -> Vec<String> {
+        // Id 0 is never a subscription request, so the reply confirms nothing
+        vec![self.request("unsubscribe", self.ticker_params(symbols), 0)]
+    }
+    
+    fn depth_subscribe_messages(&self, symbols: &[String], levels: usize, request_id: u64) -> Vec<String> {
+        vec![self.request("subscribe", self.book_params(symbols, levels), request_id)]
+    }
+    
+    fn depth_unsubscribe_messages(&self, symbols: &[String], levels: usize) -> Vec<String> {
+        vec![self.request("unsubscribe", self.book_params(symbols, levels), 0)]
+    }
+    
+    fn instruments_url(&self) -> Option<&str> {
+        Some(INSTRUMENTS_URL)
+    }
+    
+    fn parse_instruments(&self, body: &str) -> Result<Vec<String>, NormalizeError> {
+        let response = parse_frame(body)?;
+        Ok(list(field(&response, "result")?, "data")?
+            .iter()
+            .filter(|instrument| instrument["inst_type"] == "CCY_PAIR" && instrument["tradable"] == true)
+            .filter_map(|instrument| self.from_exchange_symbol(instrument["symbol"].as_str()?))
+            .collect())
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: true,
+            trading: false,
+            testnet_ws_url: Some(TESTNET_WS_URL),
+            max_streams_per_connection: None,
+            streams_per_symbol: 1,
+            max_messages_per_second: Some(100),
+            symbol_format: SymbolFormat::Underscored,
+        }
+    }
+    
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        Some(&TICKER_SCHEMA)
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        let method = field(&frame, "method")?.as_str().unwrap_or_default();
+        let id = frame.get("id").and_then(Value::as_i64);
+        
+        // The socket is closed unless each heartbeat is answered with its id
+        if method == "public/heartbeat" {
+            let reply = json!({ "id": id, "method": "public/respond-heartbeat" }).to_string();
+            return Ok(vec![NormalizedEvent::Heartbeat, NormalizedEvent::Reply(reply)]);
+        }
+        
+        let code = frame.get("code").and_then(Value::as_i64).unwrap_or_default();
+        if code != 0 {
+            let channels = frame.pointer("/result/subscription").and_then(Value::as_str);
+            return Ok(vec![NormalizedEvent::SubscriptionRejected {
+                symbols: channels.and_then(|channel| self.channel_symbol(channel)).into_iter().collect(),
+                reason: frame
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .map_or_else(|| format!("code {}", code), str::to_string),
+            }]);
+        }
+        
+        match (method, frame.get("result")) {
+            // Channel data: {"id": -1, "method": "subscribe", "result": {"channel": "ticker", "data": [..]}}
+            ("subscribe", Some(result)) => {
+                let data = field(result, "data")?
+                    .as_array()
+                    .ok_or_else(|| NormalizeError::Malformed("data is not an array".to_string()))?;
+                if result.get("channel").and_then(Value::as_str) != Some("book") {
+                    return data.iter().map(|data| self.tick(data)).collect();
+                }
+                // Book data names the instrument once: {"channel": "book", "instrument_name": "BTC_USDT", ..}
+                let instrument = field(result, "instrument_name")?
+                    .as_str()
+                    .ok_or(NormalizeError::MissingField("instrument_name"))?;
+                let symbol = self
+                    .from_exchange_symbol(instrument)
+                    .ok_or_else(|| NormalizeError::UnknownSymbol(instrument.to_string()))?;
+                data.iter().map(|data| self.book(&symbol, data)).collect()
+            }
+            // Request reply: {"id": 7, "method": "subscribe", "code": 0}
+            ("subscribe", None) => Ok(vec![NormalizedEvent::SubscriptionAck {
+                request_id: id.and_then(|id| u64::try_from(id).ok()).filter(|&id| id != 0),
+                symbols: Vec::new(),
+            }]),
+            ("unsubscribe", _) => Ok(vec![NormalizedEvent::Control("unsubscribed".to_string())]),
+            (other, _) => Err(NormalizeError::Malformed(format!("unexpected method '{}'", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_heartbeat_is_answered_with_its_id() {
+        let events = CryptocomConnector::new()
+            .normalize(r#"{"id": 1700000000555, "method": "public/heartbeat", "code": 0}"#)
+            .unwrap();
+        
+        assert_eq!(events[0], NormalizedEvent::Heartbeat);
+        let NormalizedEvent::Reply(reply) = &events[1] else {
+            panic!("no heartbeat reply in {:?}", events);
+        };
+        let reply: Value = serde_json::from_str(reply).unwrap();
+        assert_eq!(reply, json!({ "id": 1700000000555_i64, "method": "public/respond-heartbeat" }));
+    }
+}
@@ -0,0 +1,374 @@
+// exchange/curve.rs - Curve stableswap pools, quoted by simulating swaps (get_dy) at several sizes
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::connector::{parse_frame, CanonicalTick, ExchangeConnector, NormalizeError, NormalizedEvent};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::schema::MessageSchema;
+
+/// get_dy(int128,int128,uint256): coin j received for dx of coin i, pool fee included
+const GET_DY_SELECTOR: &str = "5e0d443f";
+/// JSON-RPC ids from here up are get_dy calls, by index; below are subscribe requests
+const CALL_ID_BASE: u64 = 1 << 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurveCoin {
+    pub symbol: String,
+    pub decimals: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurvePool {
+    pub name: String,
+    /// Pool contract address, 0x-prefixed
+    pub address: String,
+    /// Coins in the pool's index order
+    pub coins: Vec<CurveCoin>,
+}
+
+/// Pools behind the "curve" exchange entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurveConfig {
+    /// Ethereum node's JSON-RPC WebSocket endpoint
+    pub rpc_url: String,
+    pub pools: Vec<CurvePool>,
+    /// Trade sizes quoted, in units of the coin sold; each is its own venue
+    pub sizes: Vec<f64>,
+}
+
+impl Default for CurveConfig {
+    fn default() -> Self {
+        let coin = |symbol: &str, decimals| CurveCoin { symbol: symbol.to_string(), decimals };
+        Self {
+            rpc_url: "ws://localhost:8546".to_string(),
+            pools: vec![CurvePool {
+                name: "3pool".to_string(),
+                address: "0xbEbc44782C7dB0a1A60Cb6fe97d0b483032FF1C7".to_string(),
+                coins: vec![coin("DAI", 18), coin("USDC", 6), coin("USDT", 6)],
+            }],
+            sizes: vec![1_000.0, 100_000.0, 1_000_000.0],
+        }
+    }
+}
+
+/// Venue one size's quotes are published under, e.g. "curve-100000"
+pub fn venue(size: f64) -> String {
+    format!("curve-{}", size)
+}
+
+/// (price, size) on one side of a quote
+type Level = (f64, f64);
+
+/// Two coins of a pool as an internal symbol
+struct Pair {
+    pool: usize,
+    base: usize,
+    quote: usize,
+    symbol: String,
+}
+
+/// Every coin pair of every pool, as `BASE/QUOTE` the way `symbols` spells
+/// it where listed there, else in pool index order
+fn pairs(config: &CurveConfig, symbols: &[String]) -> Vec<Pair> {
+    let mut pairs = Vec::new();
+    for (p, pool) in config.pools.iter().enumerate() {
+        for i in 0..pool.coins.len() {
+            for j in i + 1..pool.coins.len() {
+                let (a, b) = (&pool.coins[i].symbol, &pool.coins[j].symbol);
+                let reversed = format!("{}/{}", b, a).to_uppercase();
+                let (base, quote, symbol) = if symbols.iter().any(|s| s.to_uppercase() == reversed) {
+                    (j, i, reversed)
+                } else {
+                    (i, j, format!("{}/{}", a, b).to_uppercase())
+                };
+                pairs.push(Pair { pool: p, base, quote, symbol });
+            }
+        }
+    }
+    pairs
+}
+
+/// (venue, symbol) for every quote `config` produces
+pub fn feeds(config: &CurveConfig, symbols: &[String]) -> Vec<(String, String)> {
+    pairs(config, symbols)
+        .into_iter()
+        .flat_map(|pair| config.sizes.iter().map(move |&size| (venue(size), pair.symbol.clone())))
+        .collect()
+}
+
+/// Calldata for get_dy(i, j, dx), each argument a 32-byte word
+fn get_dy_data(i: usize, j: usize, dx: u128) -> String {
+    format!("0x{}{:064x}{:064x}{:064x}", GET_DY_SELECTOR, i, j, dx)
+}
+
+/// A uint256 return value ("0x..." hex) as a float
+fn uint256(hex: &str) -> Option<f64> {
+    hex.strip_prefix("0x")?
+        .chars()
+        .try_fold(0.0, |value, digit| Some(value * 16.0 + digit.to_digit(16)? as f64))
+}
+
+/// A pool quotes whatever size it's asked about, at a price that worsens
+/// with size. Each new block re-simulates a swap of every configured size
+/// both ways for every pair; each size is published as its own venue
+/// (`curve-1000`, `curve-1000000`), so a spread against a CEX shows up at
+/// the sizes it survives the pool's slippage
+pub struct CurveConnector {
+    url: String,
+    pools: Vec<CurvePool>,
+    pairs: Vec<Pair>,
+    sizes: Vec<f64>,
+}
+
+impl CurveConnector {
+    pub fn new(config: &CurveConfig, symbols: &[String]) -> Self {
+        Self {
+            url: config.rpc_url.clone(),
+            pools: config.pools.clone(),
+            pairs: pairs(config, symbols),
+            sizes: config.sizes.clone(),
+        }
+    }
+    
+    /// (pair, size, sells the base) for call index `call`: pairs x sizes x both ways
+    fn call(&self, call: usize) -> Option<(usize, usize, bool)> {
+        let pair = call.checked_div(2 * self.sizes.len())?;
+        (pair < self.pairs.len()).then_some((pair, call / 2 % self.sizes.len(), call.is_multiple_of(2)))
+    }
+    
+    /// One JSON-RPC batch of every get_dy call
+    fn quote_request(&self) -> String {
+        let calls: Vec<Value> = (0..self.pairs.len() * self.sizes.len() * 2)
+            .filter_map(|call| {
+                let (pair, size, sell_base) = self.call(call)?;
+                let (pair, size) = (&self.pairs[pair], self.sizes[size]);
+                let pool = &self.pools[pair.pool];
+                let (from, to) = if sell_base { (pair.base, pair.quote) } else { (pair.quote, pair.base) };
+                let dx = (size * 10f64.powi(pool.coins[from].decimals as i32)) as u128;
+                Some(json!({
+                    "jsonrpc": "2.0",
+                    "id": CALL_ID_BASE + call as u64,
+                    "method": "eth_call",
+                    "params": [{ "to": pool.address, "data": get_dy_data(from, to, dx) }, "latest"],
+                }))
+            })
+            .collect();
+        Value::from(calls).to_string()
+    }
+    
+    /// Ticks from get_dy results: selling `size` of the base gives the bid,
+    /// spending `size` of the quote gives the ask. Failed calls leave their
+    /// size unquoted
+    fn quotes(&self, responses: &[Value]) -> Vec<NormalizedEvent> {
+        // (pair, size) -> (bid, ask)
+        let mut sides: HashMap<(usize, usize), (Option<Level>, Option<Level>)> = HashMap::new();
+        for response in responses {
+            let Some(call) = response.get("id").and_then(Value::as_u64).and_then(|id| id.checked_sub(CALL_ID_BASE)) else {
+                continue;
+            };
+            let Some((pair_index, size_index, sell_base)) = self.call(call as usize) else {
+                continue;
+            };
+            let (pair, size) = (&self.pairs[pair_index], self.sizes[size_index]);
+            let received_coin = if sell_base { pair.quote } else { pair.base };
+            let decimals = self.pools[pair.pool].coins[received_coin].decimals;
+            let Some(received) = response.get("result").and_then(Value::as_str).and_then(uint256) else {
+                continue;
+            };
+            let received = received / 10f64.powi(decimals as i32);
+            if received <= 0.0 {
+                continue;
+            }
+            
+            let entry = sides.entry((pair_index, size_index)).or_default();
+            if sell_base {
+                entry.0 = Some((received / size, size));
+            } else {
+                entry.1 = Some((size / received, received));
+            }
+        }
+        
+        let mut quotes: Vec<((usize, usize), CanonicalTick)> = sides
+            .into_iter()
+            .filter_map(|((pair, size), sides)| {
+                let ((bid, bid_size), (ask, ask_size)) = (sides.0?, sides.1?);
+                Some((
+                    (pair, size),
+                    CanonicalTick {
+                        exchange: venue(self.sizes[size]),
+                        symbol: self.pairs[pair].symbol.clone(),
+                        bid,
+                        bid_size,
+                        ask,
+                        ask_size,
+                    },
+                ))
+            })
+            .collect();
+        quotes.sort_by_key(|(key, _)| *key);
+        quotes.into_iter().map(|(_, tick)| NormalizedEvent::Tick(tick)).collect()
+    }
+}
+
+impl ExchangeConnector for CurveConnector {
+    fn name(&self) -> &'static str {
+        "curve"
+    }
+    
+    fn ws_url(&self) -> &str {
+        &self.url
+    }
+    
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        symbol.to_uppercase()
+    }
+    
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        let (base, quote) = raw.split_once('/')?;
+        if base.is_empty() || quote.is_empty() {
+            return None;
+        }
+        Some(format!("{}/{}", base.to_uppercase(), quote.to_uppercase()))
+    }
+    
+    /// Pools quote the pairs in `CurveConfig`, not `symbols`. New blocks
+    /// trigger each re-quote; the first goes out now rather than a block later
+    fn subscribe_messages(&self, _symbols: &[String], request_id: u64) -> Vec<String> {
+        vec![
+            json!({ "jsonrpc": "2.0", "id": request_id, "method": "eth_subscribe", "params": ["newHeads"] }).to_string(),
+            self.quote_request(),
+        ]
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: false,
+            trading: false,
+            testnet_ws_url: None,
+            max_streams_per_connection: None,
+            streams_per_symbol: 1,
+            max_messages_per_second: None,
+            symbol_format: SymbolFormat::Slashed,
+        }
+    }
+    
+    /// Frames are the node's JSON-RPC envelopes, whose layout the spec fixes
+    /// rather than the venue; the quotes inside are ABI-encoded words with no
+    /// fields to check
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        None
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        
+        // get_dy results come back as one batch per request
+        if let Value::Array(responses) = &frame {
+            return Ok(self.quotes(responses));
+        }
+        
+        // {"method": "eth_subscription", "params": {"result": {"number": "0x..", ..}}}
+        if frame.get("method").and_then(Value::as_str) == Some("eth_subscription") {
+            return Ok(vec![NormalizedEvent::Heartbeat, NormalizedEvent::Reply(self.quote_request())]);
+        }
+        
+        let id = frame.get("id").and_then(Value::as_u64);
+        if let Some(error) = frame.get("error") {
+            return Ok(vec![NormalizedEvent::SubscriptionRejected {
+                symbols: Vec::new(),
+                reason: error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            }]);
+        }
+        match (id, frame.get("result")) {
+            // A node that answers batch calls one by one
+            (Some(id), Some(_)) if id >= CALL_ID_BASE => Ok(self.quotes(std::slice::from_ref(&frame))),
+            // eth_subscribe reply: {"id": 7, "result": "0x<subscription id>"}
+            (Some(id), Some(_)) => Ok(vec![NormalizedEvent::SubscriptionAck {
+                request_id: Some(id),
+                symbols: Vec::new(),
+            }]),
+            _ => Err(NormalizeError::Malformed("not a JSON-RPC reply or notification".to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn word(value: u128) -> String {
+        format!("0x{:064x}", value)
+    }
+    
+    #[test]
+    fn test_quotes_each_size_as_its_own_venue() {
+        let config = CurveConfig {
+            pools: vec![CurvePool {
+                name: "usdc-usdt".to_string(),
+                address: "0x0000000000000000000000000000000000000001".to_string(),
+                coins: vec![
+                    CurveCoin { symbol: "USDC".to_string(), decimals: 6 },
+                    CurveCoin { symbol: "USDT".to_string(), decimals: 6 },
+                ],
+            }],
+            sizes: vec![1_000.0, 1_000_000.0],
+            ..CurveConfig::default()
+        };
+        // Spelled as the CEX symbol, so USDT is the base (coin 1)
+        let connector = CurveConnector::new(&config, &["USDT/USDC".to_string()]);
+        assert_eq!(
+            feeds(&config, &["USDT/USDC".to_string()]),
+            vec![
+                ("curve-1000".to_string(), "USDT/USDC".to_string()),
+                ("curve-1000000".to_string(), "USDT/USDC".to_string()),
+            ]
+        );
+        
+        // The first call sells 1000 USDT (coin 1) for USDC (coin 0)
+        let subscribe = connector.subscribe_messages(&[], 7);
+        let batch: Value = serde_json::from_str(&subscribe[1]).unwrap();
+        assert_eq!(batch.as_array().unwrap().len(), 4);
+        assert_eq!(
+            batch[0]["params"][0]["data"],
+            format!("0x5e0d443f{:064x}{:064x}{:064x}", 1, 0, 1_000_000_000u64)
+        );
+        
+        let reply = |call: u64, received: u128| json!({ "jsonrpc": "2.0", "id": CALL_ID_BASE + call, "result": word(received) });
+        let responses = json!([
+            reply(0, 999_600_000),         // 1000 USDT -> 999.6 USDC
+            reply(1, 999_500_000),         // 1000 USDC -> 999.5 USDT
+            reply(2, 997_000_000_000),     // 1M USDT -> 997k USDC
+            reply(3, 996_000_000_000),     // 1M USDC -> 996k USDT
+        ]);
+        let ticks: Vec<CanonicalTick> = connector
+            .normalize(&responses.to_string())
+            .unwrap()
+            .into_iter()
+            .filter_map(|event| match event {
+                NormalizedEvent::Tick(tick) => Some(tick),
+                _ => None,
+            })
+            .collect();
+        
+        assert_eq!(ticks.len(), 2);
+        assert_eq!((ticks[0].exchange.as_str(), ticks[0].symbol.as_str()), ("curve-1000", "USDT/USDC"));
+        assert!((ticks[0].bid - 0.9996).abs() < 1e-12);
+        assert!((ticks[0].ask - 1000.0 / 999.5).abs() < 1e-12);
+        assert_eq!((ticks[0].bid_size, ticks[0].ask_size), (1_000.0, 999.5));
+        // The pool's curve widens the spread at size
+        assert_eq!(ticks[1].exchange, "curve-1000000");
+        assert!((ticks[1].bid - 0.997).abs() < 1e-12);
+        assert!(ticks[1].ask - ticks[1].bid > ticks[0].ask - ticks[0].bid);
+        
+        // Each block re-quotes
+        let events = connector
+            .normalize(r#"{"jsonrpc": "2.0", "method": "eth_subscription", "params": {"subscription": "0xab", "result": {"number": "0x1312d00"}}}"#)
+            .unwrap();
+        assert_eq!(events, vec![NormalizedEvent::Heartbeat, NormalizedEvent::Reply(connector.quote_request())]);
+    }
+}
@@ -0,0 +1,323 @@
+// exchange/deribit.rs - Deribit connector (JSON-RPC quote and book channels for spot, perpetuals and futures)
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::connector::{
+    decimal, field, levels, parse_frame, CanonicalTick, ExchangeConnector, NormalizeError, NormalizedEvent,
+    OrderBookUpdate,
+};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::schema::MessageSchema;
+
+const WS_URL: &str = "wss://www.deribit.com/ws/api/v2";
+const TESTNET_WS_URL: &str = "wss://test.deribit.com/ws/api/v2";
+const QUOTE_CHANNEL: &str = "quote.";
+const BOOK_CHANNEL: &str = "book.";
+/// Grouped book depths Deribit streams; each notification is a whole top-N book
+const DEPTH_LEVELS: &[usize] = &[1, 10, 20];
+/// Seconds between the venue's `test_request` heartbeats
+const HEARTBEAT_INTERVAL: u64 = 30;
+
+static QUOTE_SCHEMA: MessageSchema = MessageSchema {
+    required: &["instrument_name", "best_bid_price", "best_bid_amount", "best_ask_price", "best_ask_amount"],
+    optional: &["timestamp"],
+    payload: quote_payload,
+};
+
+fn quote_payload(frame: &Value) -> Option<&Value> {
+    frame
+        .pointer("/params/channel")
+        .and_then(Value::as_str)
+        .filter(|channel| frame["method"] == "subscription" && channel.starts_with(QUOTE_CHANNEL))
+        .and(frame.pointer("/params/data"))
+}
+
+/// Inverse contracts (BTC-PERPETUAL, BTC-27DEC24) size in USD, not the base asset
+fn is_inverse(instrument: &str) -> bool {
+    instrument.contains('-') && !instrument.contains('_')
+}
+
+/// Derivatives streamed alongside `Config.symbols`, which every exchange shares
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeribitConfig {
+    /// Deribit instrument names: "BTC-PERPETUAL", "BTC_USDC-PERPETUAL", "BTC-27DEC24"
+    pub instruments: Vec<String>,
+}
+
+impl Default for DeribitConfig {
+    fn default() -> Self {
+        Self {
+            instruments: vec!["BTC-PERPETUAL".to_string(), "ETH-PERPETUAL".to_string()],
+        }
+    }
+}
+
+/// Internal symbol for a Deribit instrument. Spot pairs keep their assets
+/// (BTC_USDC -> BTC/USDC); perpetuals and futures become assets of their own,
+/// named for the underlying (BTC-PERPETUAL -> BTC-PERP/USD, BTC_USDC-PERPETUAL
+/// -> BTC-PERP/USDC, BTC-27DEC24 -> BTC-27DEC24/USD). Options aren't quoted
+pub fn instrument_symbol(instrument: &str) -> Option<String> {
+    let instrument = instrument.to_uppercase();
+    let (market, contract) = match instrument.split_once('-') {
+        Some((market, contract)) => (market, Some(contract)),
+        None => (instrument.as_str(), None),
+    };
+    // Inverse contracts are quoted in USD and name only the underlying
+    let (base, quote) = match market.split_once('_') {
+        Some(pair) => pair,
+        None if contract.is_some() => (market, "USD"),
+        None => return None,
+    };
+    if base.is_empty() || quote.is_empty() {
+        return None;
+    }
+    match contract {
+        None => Some(format!("{}/{}", base, quote)),
+        Some("PERPETUAL") => Some(format!("{}-PERP/{}", base, quote)),
+        Some(expiry) if !expiry.is_empty() && !expiry.contains('-') => Some(format!("{}-{}/{}", base, expiry, quote)),
+        Some(_) => None,
+    }
+}
+
+/// Spot symbols come from `Config.symbols`; perpetuals and futures from
+/// `DeribitConfig`, as graph nodes that link to their underlying
+pub struct DeribitConnector {
+    derivatives: Vec<String>,
+}
+
+impl DeribitConnector {
+    pub fn new(config: &DeribitConfig) -> Self {
+        Self {
+            derivatives: config.instruments.iter().filter_map(|i| instrument_symbol(i)).collect(),
+        }
+    }
+    
+    fn channels(&self, symbols: &[String]) -> Vec<String> {
+        symbols
+            .iter()
+            .map(|s| format!("{}{}", QUOTE_CHANNEL, self.to_exchange_symbol(s)))
+            .collect()
+    }
+    
+    /// Ungrouped book channels, `levels` deep: "book.BTC-PERPETUAL.none.10.100ms"
+    fn book_channels(&self, symbols: &[String], levels: usize) -> Vec<String> {
+        let depth = DEPTH_LEVELS.iter().copied().find(|&depth| depth >= levels).unwrap_or(20);
+        symbols
+            .iter()
+            .map(|s| format!("{}{}.none.{}.100ms", BOOK_CHANNEL, self.to_exchange_symbol(s), depth))
+            .collect()
+    }
+    
+    /// Every symbol and configured derivative
+    fn with_derivatives(&self, symbols: &[String]) -> Vec<String> {
+        symbols.iter().chain(&self.derivatives).cloned().collect()
+    }
+    
+    /// Internal symbols named by quote and book channels
+    fn channel_symbols(&self, channels: &Value) -> Vec<String> {
+        channels
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .filter_map(|channel| {
+                channel
+                    .strip_prefix(QUOTE_CHANNEL)
+                    .or_else(|| channel.strip_prefix(BOOK_CHANNEL)?.split('.').next())
+            })
+            .filter_map(|instrument| self.from_exchange_symbol(instrument))
+            .collect()
+    }
+    
+    fn request(&self, method: &str, channels: Vec<String>, request_id: u64) -> String {
+        json!({
+            "jsonrpc": "2.0",
+            "id": request_id,
+            "method": method,
+            "params": { "channels": channels },
+        })
+        .to_string()
+    }
+    
+    /// Quote data: {"instrument_name", "best_bid_price", "best_bid_amount", "best_ask_price", "best_ask_amount"}
+    fn tick(&self, data: &Value) -> Result<NormalizedEvent, NormalizeError> {
+        let instrument = field(data, "instrument_name")?
+            .as_str()
+            .ok_or(NormalizeError::MissingField("instrument_name"))?;
+        let symbol = self
+            .from_exchange_symbol(instrument)
+            .ok_or_else(|| NormalizeError::UnknownSymbol(instrument.to_string()))?;
+        let bid = decimal(field(data, "best_bid_price")?, "best_bid_price")?;
+        let ask = decimal(field(data, "best_ask_price")?, "best_ask_price")?;
+        let mut bid_size = decimal(field(data, "best_bid_amount")?, "best_bid_amount")?;
+        let mut ask_size = decimal(field(data, "best_ask_amount")?, "best_ask_amount")?;
+        // Ticks size in the base asset
+        if is_inverse(instrument) && bid > 0.0 && ask > 0.0 {
+            bid_size /= bid;
+            ask_size /= ask;
+        }
+        
+        Ok(NormalizedEvent::Tick(CanonicalTick {
+            exchange: self.name().to_string(),
+            symbol,
+            bid,
+            bid_size,
+            ask,
+            ask_size,
+        }))
+    }
+    
+    /// Book data: {"instrument_name", "change_id", "bids": [[price, amount], ..], "asks": [..]}
+    fn book(&self, data: &Value) -> Result<NormalizedEvent, NormalizeError> {
+        let instrument = field(data, "instrument_name")?
+            .as_str()
+            .ok_or(NormalizeError::MissingField("instrument_name"))?;
+        let symbol = self
+            .from_exchange_symbol(instrument)
+            .ok_or_else(|| NormalizeError::UnknownSymbol(instrument.to_string()))?;
+        let mut bids = levels(field(data, "bids")?, "bids")?;
+        let mut asks = levels(field(data, "asks")?, "asks")?;
+        // Book levels size in the base asset, as ticks do
+        if is_inverse(instrument) {
+            for (price, size) in bids.iter_mut().chain(asks.iter_mut()) {
+                *size /= *price;
+            }
+        }
+        
+        Ok(NormalizedEvent::Book(OrderBookUpdate {
+            exchange: self.name().to_string(),
+            symbol,
+            snapshot: true,
+            bids,
+            asks,
+            sequence: data.get("change_id").and_then(Value::as_u64),
+            depth: None,
+        }))
+    }
+}
+
+impl ExchangeConnector for DeribitConnector {
+    fn name(&self) -> &'static str {
+        "deribit"
+    }
+    
+    fn ws_url(&self) -> &str {
+        WS_URL
+    }
+    
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        let symbol = symbol.to_uppercase();
+        let Some((base, quote)) = symbol.split_once('/') else {
+            return symbol;
+        };
+        match base.split_once('-') {
+            None => format!("{}_{}", base, quote),
+            Some((underlying, contract)) => {
+                let contract = if contract == "PERP" { "PERPETUAL" } else { contract };
+                if quote == "USD" {
+                    format!("{}-{}", underlying, contract)
+                } else {
+                    format!("{}_{}-{}", underlying, quote, contract)
+                }
+            }
+        }
+    }
+    
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        instrument_symbol(raw)
+    }
+    
+    /// Every subscribe also covers the configured derivatives, which aren't
+    /// in `symbols`; subscribing twice to a channel is harmless
+    fn subscribe_messages(&self, symbols: &[String], request_id: u64) -> Vec<String> {
+        vec![
+            json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "method": "public/set_heartbeat",
+                "params": { "interval": HEARTBEAT_INTERVAL },
+            })
+            .to_string(),
+            self.request("public/subscribe", self.channels(&self.with_derivatives(symbols)), request_id),
+        ]
+    }
+    
+    fn unsubscribe_messages(&self, symbols: &[String]) -> Vec<String> {
+        // Id 0 is never a subscription request, so the reply confirms nothing
+        vec![self.request("public/unsubscribe", self.channels(symbols), 0)]
+    }
+    
+    fn depth_subscribe_messages(&self, symbols: &[String], levels: usize, request_id: u64) -> Vec<String> {
+        vec![self.request("public/subscribe", self.book_channels(&self.with_derivatives(symbols), levels), request_id)]
+    }
+    
+    fn depth_unsubscribe_messages(&self, symbols: &[String], levels: usize) -> Vec<String> {
+        vec![self.request("public/unsubscribe", self.book_channels(symbols, levels), 0)]
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: true,
+            trading: false,
+            testnet_ws_url: Some(TESTNET_WS_URL),
+            max_streams_per_connection: None,
+            streams_per_symbol: 1,
+            max_messages_per_second: Some(20),
+            symbol_format: SymbolFormat::Underscored,
+        }
+    }
+    
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        Some(&QUOTE_SCHEMA)
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        
+        match frame.get("method").and_then(Value::as_str) {
+            // {"method": "subscription", "params": {"channel": "quote.BTC-PERPETUAL", "data": {..}}}
+            Some("subscription") => {
+                let data = frame
+                    .pointer("/params/data")
+                    .ok_or(NormalizeError::MissingField("data"))?;
+                let channel = frame.pointer("/params/channel").and_then(Value::as_str).unwrap_or_default();
+                if channel.starts_with(BOOK_CHANNEL) {
+                    return Ok(vec![self.book(data)?]);
+                }
+                return Ok(vec![self.tick(data)?]);
+            }
+            // A `test_request` closes the socket unless answered with public/test
+            Some("heartbeat") => {
+                if frame.pointer("/params/type").and_then(Value::as_str) == Some("test_request") {
+                    let reply = json!({ "jsonrpc": "2.0", "id": 0, "method": "public/test", "params": {} }).to_string();
+                    return Ok(vec![NormalizedEvent::Heartbeat, NormalizedEvent::Reply(reply)]);
+                }
+                return Ok(vec![NormalizedEvent::Heartbeat]);
+            }
+            Some(other) => return Err(NormalizeError::Malformed(format!("unexpected method '{}'", other))),
+            None => {}
+        }
+        
+        // Replies: {"id": 7, "result": [channels]} or {"id": 7, "error": {"code", "message"}}
+        let id = field(&frame, "id")?.as_u64().filter(|&id| id != 0);
+        if let Some(error) = frame.get("error") {
+            return Ok(vec![NormalizedEvent::SubscriptionRejected {
+                symbols: Vec::new(),
+                reason: error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            }]);
+        }
+        let result = field(&frame, "result")?;
+        match id {
+            Some(request_id) => Ok(vec![NormalizedEvent::SubscriptionAck {
+                request_id: Some(request_id),
+                symbols: self.channel_symbols(result),
+            }]),
+            // Heartbeat setup, public/test and unsubscribe replies
+            None => Ok(vec![NormalizedEvent::Control("ok".to_string())]),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+// exchange/discovery.rs - Symbols discovered from the exchanges' instrument lists
+use std::collections::BTreeSet;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::arbitrage::listings::NewListingConfig;
+use crate::arbitrage::Config;
+use super::registry::ExchangeRegistry;
+
+/// Subscribe to what the configured exchanges list in common, on top of `symbols`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiscoveryConfig {
+    pub enabled: bool,
+    /// `BASE/QUOTE` patterns to keep, `*` matching any asset (`*/USDT`,
+    /// `BTC/*`); empty keeps every pair
+    pub include: Vec<String>,
+    /// Patterns to drop, applied after `include`
+    pub exclude: Vec<String>,
+    /// Warm-up and threshold for pairs the discovery job finds after startup
+    pub new_listings: NewListingConfig,
+}
+
+/// Whether `symbol` matches a `BASE/QUOTE` pattern; case-insensitive
+pub fn matches(pattern: &str, symbol: &str) -> bool {
+    let (Some((base, quote)), Some((symbol_base, symbol_quote))) = (pattern.split_once('/'), symbol.split_once('/')) else {
+        return false;
+    };
+    let side = |pattern: &str, asset: &str| pattern == "*" || pattern.eq_ignore_ascii_case(asset);
+    side(base, symbol_base) && side(quote, symbol_quote)
+}
+
+/// Pairs every listing lists, filtered by `config`, sorted
+pub fn select(listings: &[(String, Vec<String>)], config: &DiscoveryConfig) -> Vec<String> {
+    let mut listings = listings.iter().map(|(_, symbols)| symbols.iter().collect::<BTreeSet<_>>());
+    let Some(first) = listings.next() else {
+        return Vec::new();
+    };
+    let common = listings.fold(first, |common, listed| common.intersection(&listed).copied().collect());
+    common
+        .into_iter()
+        .filter(|symbol| config.include.is_empty() || config.include.iter().any(|pattern| matches(pattern, symbol)))
+        .filter(|symbol| !config.exclude.iter().any(|pattern| matches(pattern, symbol)))
+        .cloned()
+        .collect()
+}
+
+/// Fetch the instrument list of every configured exchange that has one and
+/// keep the pairs they all trade. Exchanges without a list (tardis, curve,
+/// deribit) don't narrow the result; a list that fails to load fails discovery
+pub async fn discover(config: &Config, exchanges: &ExchangeRegistry, client: &reqwest::Client) -> Result<Vec<String>, String> {
+    let mut listings = Vec::new();
+    for name in &config.exchanges {
+        let Some(exchange) = exchanges.build(name, config) else {
+            continue;
+        };
+        match exchange.list_symbols(client).await {
+            Some(Ok(symbols)) => {
+                info!("{}: {} tradable pairs listed", name, symbols.len());
+                listings.push((name.clone(), symbols));
+            }
+            Some(Err(e)) => return Err(format!("{}: listing instruments failed: {}", name, e)),
+            None => info!("{}: no instrument list; not part of symbol discovery", name),
+        }
+    }
+    if listings.is_empty() {
+        return Err("discovery needs at least one exchange that lists its instruments".to_string());
+    }
+    
+    let symbols = select(&listings, &config.discovery);
+    info!("Discovered {} symbols listed on {} exchanges", symbols.len(), listings.len());
+    Ok(symbols)
+}
+
+/// `symbols` followed by the discovered ones it doesn't already have
+pub fn add_symbols(symbols: &mut Vec<String>, discovered: &[String]) {
+    for symbol in discovered {
+        if !symbols.iter().any(|listed| listed.eq_ignore_ascii_case(symbol)) {
+            symbols.push(symbol.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn listing(exchange: &str, symbols: &[&str]) -> (String, Vec<String>) {
+        (exchange.to_string(), symbols.iter().map(|s| s.to_string()).collect())
+    }
+    
+    #[test]
+    fn test_keeps_common_pairs_through_filters() {
+        let listings = [
+            listing("binance", &["BTC/USDT", "ETH/USDT", "ETH/BTC", "DOGE/USDT", "BNB/USDT"]),
+            listing("kraken", &["BTC/USDT", "ETH/USDT", "ETH/BTC", "DOGE/USDT", "BTC/USD"]),
+            listing("coinbase", &["ETH/BTC", "DOGE/USDT", "ETH/USDT", "BTC/USDT"]),
+        ];
+        let all = DiscoveryConfig { enabled: true, ..DiscoveryConfig::default() };
+        assert_eq!(select(&listings, &all), vec!["BTC/USDT", "DOGE/USDT", "ETH/BTC", "ETH/USDT"]);
+        
+        let filtered = DiscoveryConfig {
+            include: vec!["*/usdt".to_string(), "ETH/BTC".to_string()],
+            exclude: vec!["DOGE/*".to_string()],
+            ..all
+        };
+        assert_eq!(select(&listings, &filtered), vec!["BTC/USDT", "ETH/BTC", "ETH/USDT"]);
+        assert!(select(&[], &filtered).is_empty());
+        
+        let mut symbols = vec!["eth/usdt".to_string(), "SOL/USDT".to_string()];
+        add_symbols(&mut symbols, &select(&listings, &filtered));
+        assert_eq!(symbols, vec!["eth/usdt", "SOL/USDT", "BTC/USDT", "ETH/BTC"]);
+    }
+}
@@ -0,0 +1,284 @@
+// exchange/dydx.rs - dYdX v4 connector (indexer orderbook channel for perpetuals)
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::RwLock;
+use serde_json::{json, Value};
+
+use super::connector::{decimal, field, parse_frame, CanonicalTick, ExchangeConnector, NormalizeError, NormalizedEvent};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::schema::MessageSchema;
+
+const WS_URL: &str = "wss://indexer.dydx.trade/v4/ws";
+const TESTNET_WS_URL: &str = "wss://indexer.v4testnet.dydx.exchange/v4/ws";
+const ORDERBOOK_CHANNEL: &str = "v4_orderbook";
+/// Every market is a perpetual margined in USDC and quoted in USD
+const QUOTE: &str = "USD";
+
+static ORDERBOOK_SCHEMA: MessageSchema = MessageSchema {
+    required: &["type", "channel", "id", "contents"],
+    optional: &["connection_id", "message_id", "version"],
+    payload: orderbook_payload,
+};
+
+fn orderbook_payload(frame: &Value) -> Option<&Value> {
+    frame
+        .get("type")
+        .filter(|kind| *kind == "subscribed" || *kind == "channel_data")
+        .and(Some(frame))
+}
+
+/// Price levels keyed by the price's bits, as in the Gemini connector
+#[derive(Default)]
+struct Book {
+    bids: BTreeMap<u64, f64>,
+    asks: BTreeMap<u64, f64>,
+}
+
+impl Book {
+    fn set(levels: &mut BTreeMap<u64, f64>, price: f64, size: f64) {
+        if size == 0.0 {
+            levels.remove(&price.to_bits());
+        } else {
+            levels.insert(price.to_bits(), size);
+        }
+    }
+    
+    fn top(&self) -> Option<((f64, f64), (f64, f64))> {
+        let (bid, bid_size) = self.bids.iter().next_back()?;
+        let (ask, ask_size) = self.asks.iter().next()?;
+        Some(((f64::from_bits(*bid), *bid_size), (f64::from_bits(*ask), *ask_size)))
+    }
+}
+
+/// (price, size) of one level: {"price", "size"} in a snapshot, ["price", "size"] in an update
+fn level(entry: &Value) -> Result<(f64, f64), NormalizeError> {
+    let (price, size) = match entry {
+        Value::Array(pair) => (pair.first(), pair.get(1)),
+        _ => (entry.get("price"), entry.get("size")),
+    };
+    Ok((
+        decimal(price.ok_or(NormalizeError::MissingField("price"))?, "price")?,
+        decimal(size.ok_or(NormalizeError::MissingField("size"))?, "size")?,
+    ))
+}
+
+/// Asset a pair's perpetual is on: BTC for BTC/USDT and for BTC-PERP/USD
+fn underlying(symbol: &str) -> String {
+    let symbol = symbol.to_uppercase();
+    let base = symbol.split_once('/').map_or(symbol.as_str(), |(base, _)| base);
+    base.strip_suffix("-PERP").unwrap_or(base).to_string()
+}
+
+/// Internal symbol of the perpetual a configured pair follows (BTC/USDT -> BTC-PERP/USD)
+pub fn perpetual_symbol(symbol: &str) -> String {
+    format!("{}-PERP/{}", underlying(symbol), QUOTE)
+}
+
+/// dYdX lists perpetuals only, so a configured pair stands for the perpetual
+/// on its base: BTC/USDT and BTC/USDC both subscribe BTC-USD. Its ticks are
+/// published as BTC-PERP/USD, a derivative the graph links to BTC on the
+/// other venues. The indexer sends the whole book on subscribing and
+/// changes after that, so the book is kept per market
+pub struct DydxConnector {
+    /// Market -> the configured symbols that asked for it
+    requested: RwLock<HashMap<String, BTreeSet<String>>>,
+    books: RwLock<HashMap<String, Book>>,
+}
+
+impl DydxConnector {
+    pub fn new() -> Self {
+        Self {
+            requested: RwLock::new(HashMap::new()),
+            books: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    /// Apply one frame's levels to `market`'s book and quote its new top
+    fn apply(&self, market: &str, contents: &Value, snapshot: bool) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let symbol = self
+            .from_exchange_symbol(market)
+            .ok_or_else(|| NormalizeError::UnknownSymbol(market.to_string()))?;
+        
+        let mut books = self.books.write().unwrap();
+        let book = books.entry(market.to_string()).or_default();
+        if snapshot {
+            *book = Book::default();
+        }
+        for (side, levels) in [("bids", &mut book.bids), ("asks", &mut book.asks)] {
+            for entry in contents.get(side).and_then(Value::as_array).into_iter().flatten() {
+                let (price, size) = level(entry)?;
+                Book::set(levels, price, size);
+            }
+        }
+        
+        // One-sided, or crossed while the indexer catches up with the chain
+        let Some(((bid, bid_size), (ask, ask_size))) = book.top().filter(|((bid, _), (ask, _))| bid < ask) else {
+            return Ok(Vec::new());
+        };
+        Ok(vec![NormalizedEvent::Tick(CanonicalTick {
+            exchange: self.name().to_string(),
+            symbol,
+            bid,
+            bid_size,
+            ask,
+            ask_size,
+        })])
+    }
+}
+
+impl ExchangeConnector for DydxConnector {
+    fn name(&self) -> &'static str {
+        "dydx"
+    }
+    
+    fn ws_url(&self) -> &str {
+        WS_URL
+    }
+    
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        format!("{}-{}", underlying(symbol), QUOTE)
+    }
+    
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        let (base, quote) = raw.split_once('-')?;
+        if base.is_empty() || !quote.eq_ignore_ascii_case(QUOTE) {
+            return None;
+        }
+        Some(perpetual_symbol(base))
+    }
+    
+    /// One frame per market; a market already subscribed for another pair is
+    /// asked for again, and the venue's "already subscribed" error is harmless
+    fn subscribe_messages(&self, symbols: &[String], _request_id: u64) -> Vec<String> {
+        let mut requested = self.requested.write().unwrap();
+        let mut markets = BTreeSet::new();
+        for symbol in symbols {
+            let market = self.to_exchange_symbol(symbol);
+            requested.entry(market.clone()).or_default().insert(symbol.clone());
+            markets.insert(market);
+        }
+        markets
+            .into_iter()
+            .map(|market| json!({ "type": "subscribe", "channel": ORDERBOOK_CHANNEL, "id": market, "batched": false }).to_string())
+            .collect()
+    }
+    
+    /// A market is left only once no configured pair stands on it
+    fn unsubscribe_messages(&self, symbols: &[String]) -> Vec<String> {
+        let mut requested = self.requested.write().unwrap();
+        let mut books = self.books.write().unwrap();
+        let mut frames = Vec::new();
+        for symbol in symbols {
+            let market = self.to_exchange_symbol(symbol);
+            let Some(pairs) = requested.get_mut(&market) else {
+                continue;
+            };
+            pairs.remove(symbol);
+            if pairs.is_empty() {
+                requested.remove(&market);
+                books.remove(&market);
+                frames.push(json!({ "type": "unsubscribe", "channel": ORDERBOOK_CHANNEL, "id": market }).to_string());
+            }
+        }
+        frames
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: false,
+            trading: false,
+            testnet_ws_url: Some(TESTNET_WS_URL),
+            // Per channel
+            max_streams_per_connection: Some(32),
+            streams_per_symbol: 1,
+            max_messages_per_second: Some(2),
+            symbol_format: SymbolFormat::Dashed,
+        }
+    }
+    
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        Some(&ORDERBOOK_SCHEMA)
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        
+        match field(&frame, "type")?.as_str().unwrap_or_default() {
+            // {"type": "subscribed", "channel": "v4_orderbook", "id": "BTC-USD", "contents": {"bids": [{"price", "size"}], "asks": [..]}}
+            "subscribed" => {
+                let market = field(&frame, "id")?.as_str().ok_or(NormalizeError::MissingField("id"))?;
+                let symbols = self
+                    .requested
+                    .read()
+                    .unwrap()
+                    .get(market)
+                    .map(|pairs| pairs.iter().cloned().collect())
+                    .unwrap_or_default();
+                let mut events = vec![NormalizedEvent::SubscriptionAck { request_id: None, symbols }];
+                events.extend(self.apply(market, field(&frame, "contents")?, true)?);
+                Ok(events)
+            }
+            // {"type": "channel_data", "id": "BTC-USD", "contents": {"bids": [["price", "size"]]}}, size "0" removing a level
+            "channel_data" => {
+                let market = field(&frame, "id")?.as_str().ok_or(NormalizeError::MissingField("id"))?;
+                self.apply(market, field(&frame, "contents")?, false)
+            }
+            // {"type": "error", "message": ".."}; it doesn't say which market
+            "error" => Ok(vec![NormalizedEvent::SubscriptionRejected {
+                symbols: Vec::new(),
+                reason: frame
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            }]),
+            event @ ("connected" | "unsubscribed") => Ok(vec![NormalizedEvent::Control(event.to_string())]),
+            other => Err(NormalizeError::Malformed(format!("unexpected message type '{}'", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn tick(events: &[NormalizedEvent]) -> Option<(f64, f64, f64, f64)> {
+        events.iter().find_map(|event| match event {
+            NormalizedEvent::Tick(tick) => Some((tick.bid, tick.bid_size, tick.ask, tick.ask_size)),
+            _ => None,
+        })
+    }
+    
+    #[test]
+    fn test_pairs_share_the_perpetual_on_their_base() {
+        let dydx = DydxConnector::new();
+        let symbols = ["BTC/USDT".to_string(), "BTC/USDC".to_string(), "ETH/BTC".to_string()];
+        let frames = dydx.subscribe_messages(&symbols, 1);
+        assert_eq!(frames.len(), 2);
+        assert!(frames[0].contains(r#""id":"BTC-USD""#) && frames[1].contains(r#""id":"ETH-USD""#));
+        
+        let snapshot = r#"{"type":"subscribed","connection_id":"c1","message_id":1,"channel":"v4_orderbook","id":"BTC-USD","contents":{"bids":[{"price":"50000","size":"1.5"},{"price":"49999","size":"3"}],"asks":[{"price":"50001","size":"2"}]}}"#;
+        let events = dydx.normalize(snapshot).unwrap();
+        assert_eq!(
+            events[0],
+            NormalizedEvent::SubscriptionAck {
+                request_id: None,
+                symbols: vec!["BTC/USDC".to_string(), "BTC/USDT".to_string()],
+            }
+        );
+        let NormalizedEvent::Tick(first) = &events[1] else {
+            panic!("expected a tick: {:?}", events);
+        };
+        assert_eq!(first.symbol, "BTC-PERP/USD");
+        
+        // The best bid goes; an ask under the next bid leaves the book crossed until it clears
+        let update = r#"{"type":"channel_data","channel":"v4_orderbook","id":"BTC-USD","version":"2","contents":{"bids":[["50000","0"]],"asks":[["49998","0.5"]]}}"#;
+        assert_eq!(tick(&dydx.normalize(update).unwrap()), None);
+        let cleared = r#"{"type":"channel_data","channel":"v4_orderbook","id":"BTC-USD","contents":{"asks":[["49998","0"]]}}"#;
+        assert_eq!(tick(&dydx.normalize(cleared).unwrap()), Some((49999.0, 3.0, 50001.0, 2.0)));
+        
+        // BTC-USD stays while BTC/USDC still stands on it
+        assert!(dydx.unsubscribe_messages(&symbols[..1]).is_empty());
+        assert_eq!(dydx.unsubscribe_messages(&symbols[1..2]).len(), 1);
+        assert_eq!(dydx.from_exchange_symbol("BTC-USDT"), None);
+    }
+}
@@ -0,0 +1,190 @@
+// exchange/funding.rs - Perpetual funding rates polled from derivatives venues' REST APIs
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::arbitrage::funding::FundingRate;
+use crate::arbitrage::ArbitrageEngine;
+use super::connector::{decimal, field, list, parse_frame, NormalizeError};
+use super::registry::BoxError;
+
+/// Binance and Bybit settle most perpetuals every 8 hours and don't say so
+/// in the rate itself
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(8 * 3600);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FundingVenue {
+    BinanceFutures,
+    Bybit,
+    Okx,
+}
+
+impl FundingVenue {
+    pub fn name(self) -> &'static str {
+        match self {
+            FundingVenue::BinanceFutures => "binance_futures",
+            FundingVenue::Bybit => "bybit",
+            FundingVenue::Okx => "okx",
+        }
+    }
+    
+    /// The USDT-margined perpetual on `symbol` ("BTC/USDT"), as the venue names it
+    pub fn perpetual(self, symbol: &str) -> String {
+        let (base, quote) = symbol.split_once('/').unwrap_or((symbol, ""));
+        match self {
+            FundingVenue::BinanceFutures | FundingVenue::Bybit => format!("{}{}", base, quote).to_uppercase(),
+            FundingVenue::Okx => format!("{}-{}-SWAP", base, quote).to_uppercase(),
+        }
+    }
+    
+    /// Binance and Bybit list every perpetual in one reply; OKX takes one per request
+    fn urls(self, symbols: &[String]) -> Vec<String> {
+        match self {
+            FundingVenue::BinanceFutures => vec!["https://fapi.binance.com/fapi/v1/premiumIndex".to_string()],
+            FundingVenue::Bybit => vec!["https://api.bybit.com/v5/market/tickers?category=linear".to_string()],
+            FundingVenue::Okx => symbols
+                .iter()
+                .map(|symbol| format!("https://www.okx.com/api/v5/public/funding-rate?instId={}", self.perpetual(symbol)))
+                .collect(),
+        }
+    }
+    
+    /// Rates in one reply for the perpetuals on `symbols`; others are skipped
+    pub fn parse(self, body: &str, symbols: &[String]) -> Result<Vec<FundingRate>, NormalizeError> {
+        let reply = parse_frame(body)?;
+        let by_perpetual: HashMap<String, &String> = symbols.iter().map(|symbol| (self.perpetual(symbol), symbol)).collect();
+        let (entries, id) = match self {
+            FundingVenue::BinanceFutures => (reply.as_array().ok_or(NormalizeError::Malformed("expected a list".to_string()))?, "symbol"),
+            FundingVenue::Bybit => (list(field(&reply, "result")?, "list")?, "symbol"),
+            FundingVenue::Okx => (list(&reply, "data")?, "instId"),
+        };
+        
+        let mut rates = Vec::new();
+        for entry in entries {
+            let Some(symbol) = entry[id].as_str().and_then(|perpetual| by_perpetual.get(perpetual)) else {
+                continue;
+            };
+            let (rate, next, interval) = match self {
+                FundingVenue::BinanceFutures => (decimal(field(entry, "lastFundingRate")?, "lastFundingRate")?, millis(&entry["nextFundingTime"]), DEFAULT_INTERVAL),
+                FundingVenue::Bybit => (decimal(field(entry, "fundingRate")?, "fundingRate")?, millis(&entry["nextFundingTime"]), DEFAULT_INTERVAL),
+                FundingVenue::Okx => {
+                    // fundingTime is the coming settlement, nextFundingTime the one after
+                    let (current, next) = (millis(&entry["fundingTime"]), millis(&entry["nextFundingTime"]));
+                    let interval = match (current, next) {
+                        (Some(current), Some(next)) if next > current => Duration::from_millis(next - current),
+                        _ => DEFAULT_INTERVAL,
+                    };
+                    (decimal(field(entry, "fundingRate")?, "fundingRate")?, current, interval)
+                }
+            };
+            rates.push(FundingRate {
+                exchange: self.name().to_string(),
+                symbol: symbol.to_string(),
+                rate,
+                interval,
+                next_funding_ms: next,
+                mark_price: decimal(&entry["markPrice"], "markPrice").ok(),
+            });
+        }
+        Ok(rates)
+    }
+    
+    pub async fn poll(self, symbols: &[String], client: &reqwest::Client) -> Result<Vec<FundingRate>, BoxError> {
+        let mut rates = Vec::new();
+        for url in self.urls(symbols) {
+            let body = client.get(url).send().await?.error_for_status()?.text().await?;
+            rates.extend(self.parse(&body, symbols)?);
+        }
+        Ok(rates)
+    }
+}
+
+fn millis(value: &Value) -> Option<u64> {
+    decimal(value, "time").ok().filter(|ms| *ms > 0.0).map(|ms| ms as u64)
+}
+
+/// Perpetual venues polled for the funding detector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundingConfig {
+    /// Empty turns the detector off
+    pub venues: Vec<FundingVenue>,
+    #[serde(with = "humantime_serde")]
+    pub poll_interval: Duration,
+    /// Smallest annualized funding yield reported, as a fraction
+    pub min_annualized_yield: f64,
+}
+
+impl Default for FundingConfig {
+    fn default() -> Self {
+        Self {
+            venues: Vec::new(),
+            poll_interval: Duration::from_secs(60),
+            min_annualized_yield: 0.1,
+        }
+    }
+}
+
+/// Poll every venue's rates for `symbols` into the engine, logging what pays
+pub async fn run(engine: Arc<ArbitrageEngine>, config: FundingConfig, symbols: Vec<String>) {
+    let client = reqwest::Client::new();
+    let mut interval = tokio::time::interval(config.poll_interval);
+    loop {
+        interval.tick().await;
+        for venue in &config.venues {
+            match venue.poll(&symbols, &client).await {
+                Ok(rates) => engine.record_funding(rates),
+                Err(e) => warn!("{}: polling funding rates failed: {}", venue.name(), e),
+            }
+        }
+        for opportunity in engine.get_funding_opportunities().await {
+            info!(
+                "Funding: {} on {} at {:+.4}% per {}h, {:.2}% a year against {} spot",
+                opportunity.symbol,
+                opportunity.perp_exchange,
+                opportunity.funding_rate * 100.0,
+                opportunity.interval_hours,
+                opportunity.annualized_yield * 100.0,
+                opportunity.spot_exchange
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parses_each_venues_funding_reply() {
+        let symbols = vec!["BTC/USDT".to_string(), "ETH/USDT".to_string()];
+        
+        let binance = r#"[
+            {"symbol": "BTCUSDT", "markPrice": "60010.5", "lastFundingRate": "0.00010000", "nextFundingTime": 1700006400000},
+            {"symbol": "XRPUSDT", "markPrice": "0.6", "lastFundingRate": "0.0003", "nextFundingTime": 1700006400000}
+        ]"#;
+        let rates = FundingVenue::BinanceFutures.parse(binance, &symbols).unwrap();
+        assert_eq!(rates.len(), 1);
+        assert_eq!((rates[0].exchange.as_str(), rates[0].symbol.as_str(), rates[0].rate), ("binance_futures", "BTC/USDT", 0.0001));
+        assert_eq!((rates[0].mark_price, rates[0].next_funding_ms), (Some(60010.5), Some(1700006400000)));
+        
+        let bybit = r#"{"retCode": 0, "result": {"category": "linear", "list": [
+            {"symbol": "ETHUSDT", "markPrice": "3000.1", "fundingRate": "-0.00005", "nextFundingTime": "1700006400000"}
+        ]}}"#;
+        let rates = FundingVenue::Bybit.parse(bybit, &symbols).unwrap();
+        assert_eq!((rates[0].symbol.as_str(), rates[0].rate, rates[0].interval), ("ETH/USDT", -0.00005, DEFAULT_INTERVAL));
+        
+        // OKX spells out its interval: 4h here
+        let okx = r#"{"code": "0", "data": [
+            {"instId": "BTC-USDT-SWAP", "fundingRate": "0.0002", "fundingTime": "1700006400000", "nextFundingTime": "1700020800000"}
+        ]}"#;
+        let rates = FundingVenue::Okx.parse(okx, &symbols).unwrap();
+        assert_eq!((rates[0].interval, rates[0].mark_price), (Duration::from_secs(4 * 3600), None));
+        assert_eq!(FundingVenue::Okx.urls(&symbols).len(), 2);
+        
+        assert!(FundingVenue::Bybit.parse(r#"{"retCode": 10001}"#, &symbols).is_err());
+    }
+}
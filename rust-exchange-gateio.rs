@@ -0,0 +1,208 @@
+// exchange/gateio.rs - Gate.io spot connector (WS v4 book ticker, batched subscriptions)
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde_json::{json, Value};
+
+use super::connector::{
+    decimal, field, levels, parse_frame, CanonicalTick, ExchangeConnector, NormalizeError, NormalizedEvent, OrderBookUpdate,
+};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::schema::MessageSchema;
+
+const WS_URL: &str = "wss://api.gateio.ws/ws/v4/";
+const INSTRUMENTS_URL: &str = "https://api.gateio.ws/api/v4/spot/currency_pairs";
+/// Currency pairs one subscribe frame carries in its payload
+const SYMBOLS_PER_FRAME: usize = 100;
+/// Book depths `spot.order_book` accepts
+const DEPTHS: [usize; 5] = [5, 10, 20, 50, 100];
+
+static TICKER_SCHEMA: MessageSchema = MessageSchema {
+    required: &["s", "b", "B", "a", "A"],
+    optional: &["t", "u"],
+    payload: ticker_payload,
+};
+
+fn ticker_payload(frame: &Value) -> Option<&Value> {
+    frame
+        .get("channel")
+        .filter(|channel| *channel == "spot.book_ticker")
+        .and_then(|_| frame.get("event"))
+        .filter(|event| *event == "update")
+        .and_then(|_| frame.get("result"))
+}
+
+pub struct GateioConnector;
+
+impl GateioConnector {
+    pub fn new() -> Self {
+        Self
+    }
+    
+    /// One frame per `SYMBOLS_PER_FRAME` pairs; `extra` follows each pair in
+    /// channels that take per-pair arguments
+    fn channel_messages(&self, event: &str, channel: &str, symbols: &[String], extra: &[String], request_id: u64) -> Vec<String> {
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        symbols
+            .chunks(SYMBOLS_PER_FRAME)
+            .map(|chunk| {
+                let mut payload: Vec<String> = Vec::new();
+                for symbol in chunk {
+                    payload.push(self.to_exchange_symbol(symbol));
+                    payload.extend(extra.iter().cloned());
+                }
+                json!({
+                    "time": time,
+                    "id": request_id,
+                    "channel": channel,
+                    "event": event,
+                    "payload": payload,
+                })
+                .to_string()
+            })
+            .collect()
+    }
+    
+    /// Smallest depth Gate.io offers that covers `levels`
+    fn depth_args(levels: usize) -> Vec<String> {
+        let depth = DEPTHS.into_iter().find(|depth| *depth >= levels).unwrap_or(DEPTHS[DEPTHS.len() - 1]);
+        vec![depth.to_string(), "100ms".to_string()]
+    }
+    
+    fn symbol(&self, result: &Value) -> Result<String, NormalizeError> {
+        let raw = field(result, "s")?.as_str().ok_or(NormalizeError::MissingField("s"))?;
+        self.from_exchange_symbol(raw).ok_or_else(|| NormalizeError::UnknownSymbol(raw.to_string()))
+    }
+}
+
+impl ExchangeConnector for GateioConnector {
+    fn name(&self) -> &'static str {
+        "gateio"
+    }
+    
+    fn ws_url(&self) -> &str {
+        WS_URL
+    }
+    
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        symbol.replace('/', "_").to_uppercase()
+    }
+    
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        let (base, quote) = raw.split_once('_')?;
+        Some(format!("{}/{}", base.to_uppercase(), quote.to_uppercase()))
+    }
+    
+    fn subscribe_messages(&self, symbols: &[String], request_id: u64) -> Vec<String> {
+        self.channel_messages("subscribe", "spot.book_ticker", symbols, &[], request_id)
+    }
+    
+    fn unsubscribe_messages(&self, symbols: &[String]) -> Vec<String> {
+        self.channel_messages("unsubscribe", "spot.book_ticker", symbols, &[], 0)
+    }
+    
+    /// Whole books of the chosen depth every 100ms
+    fn depth_subscribe_messages(&self, symbols: &[String], levels: usize, request_id: u64) -> Vec<String> {
+        self.channel_messages("subscribe", "spot.order_book", symbols, &Self::depth_args(levels), request_id)
+    }
+    
+    fn depth_unsubscribe_messages(&self, symbols: &[String], levels: usize) -> Vec<String> {
+        self.channel_messages("unsubscribe", "spot.order_book", symbols, &Self::depth_args(levels), 0)
+    }
+    
+    fn instruments_url(&self) -> Option<&str> {
+        Some(INSTRUMENTS_URL)
+    }
+    
+    fn parse_instruments(&self, body: &str) -> Result<Vec<String>, NormalizeError> {
+        let pairs = parse_frame(body)?;
+        let pairs = pairs.as_array().ok_or_else(|| NormalizeError::Malformed("expected a currency pair list".to_string()))?;
+        Ok(pairs
+            .iter()
+            .filter(|pair| pair["trade_status"] == "tradable")
+            .filter_map(|pair| self.from_exchange_symbol(pair["id"].as_str()?))
+            .collect())
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: true,
+            trading: false,
+            testnet_ws_url: None,
+            max_streams_per_connection: None,
+            streams_per_symbol: 1,
+            max_messages_per_second: None,
+            symbol_format: SymbolFormat::Underscored,
+        }
+    }
+    
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        Some(&TICKER_SCHEMA)
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        let channel = field(&frame, "channel")?.as_str().unwrap_or_default();
+        let event = frame.get("event").and_then(Value::as_str).unwrap_or_default();
+        
+        // {"id":7,"channel":"spot.book_ticker","event":"subscribe","error":{"code":2,"message":"unknown currency pair GT_XYZ"},"result":null}
+        if let Some(error) = frame.get("error").filter(|error| !error.is_null()) {
+            return Ok(vec![NormalizedEvent::SubscriptionRejected {
+                symbols: Vec::new(),
+                reason: error.get("message").and_then(Value::as_str).map_or_else(|| error.to_string(), String::from),
+            }]);
+        }
+        
+        match (channel, event) {
+            ("spot.pong", _) => Ok(vec![NormalizedEvent::Heartbeat]),
+            (_, "subscribe") => Ok(vec![NormalizedEvent::SubscriptionAck {
+                request_id: frame.get("id").and_then(Value::as_u64),
+                symbols: Vec::new(),
+            }]),
+            (_, "unsubscribe") => Ok(Vec::new()),
+            ("spot.book_ticker", "update") => {
+                let result = field(&frame, "result")?;
+                Ok(vec![NormalizedEvent::Tick(CanonicalTick {
+                    exchange: self.name().to_string(),
+                    symbol: self.symbol(result)?,
+                    bid: decimal(field(result, "b")?, "b")?,
+                    bid_size: decimal(field(result, "B")?, "B")?,
+                    ask: decimal(field(result, "a")?, "a")?,
+                    ask_size: decimal(field(result, "A")?, "A")?,
+                })])
+            }
+            ("spot.order_book", "update") => {
+                let result = field(&frame, "result")?;
+                Ok(vec![NormalizedEvent::Book(OrderBookUpdate {
+                    exchange: self.name().to_string(),
+                    symbol: self.symbol(result)?,
+                    snapshot: true,
+                    bids: levels(field(result, "bids")?, "bids")?,
+                    asks: levels(field(result, "asks")?, "asks")?,
+                    sequence: result.get("lastUpdateId").and_then(Value::as_u64),
+                    depth: None,
+                })])
+            }
+            _ => Err(NormalizeError::Malformed(format!("unexpected {} event '{}'", channel, event))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_subscriptions_are_batched_with_pair_arguments() {
+        let connector = GateioConnector::new();
+        let symbols: Vec<String> = (0..250).map(|i| format!("A{}/USDT", i)).collect();
+        let frames = connector.subscribe_messages(&symbols, 7);
+        assert_eq!(frames.len(), 3);
+        let last: Value = serde_json::from_str(&frames[2]).unwrap();
+        assert_eq!((last["id"].as_u64(), last["channel"].as_str()), (Some(7), Some("spot.book_ticker")));
+        assert_eq!(last["payload"].as_array().unwrap().len(), 50);
+        assert_eq!(last["payload"][0], "A200_USDT");
+        
+        // Depth takes the next size up, after every pair
+        let depth: Value = serde_json::from_str(&connector.depth_subscribe_messages(&symbols[..2], 15, 8)[0]).unwrap();
+        assert_eq!(depth["payload"], json!(["A0_USDT", "20", "100ms", "A1_USDT", "20", "100ms"]));
+    }
+}
@@ -0,0 +1,231 @@
+// exchange/gemini.rs - Gemini connector (market data v2 l2 events)
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+use serde_json::{json, Value};
+
+use super::connector::{
+    decimal, field, parse_frame, split_concatenated, CanonicalTick, ExchangeConnector, NormalizeError,
+    NormalizedEvent, OrderBookUpdate,
+};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::schema::MessageSchema;
+
+const WS_URL: &str = "wss://api.gemini.com/v2/marketdata";
+const INSTRUMENTS_URL: &str = "https://api.gemini.com/v1/symbols";
+const QUOTE_ASSETS: &[&str] = &["USD", "GUSD", "USDT", "USDC", "BTC", "ETH", "EUR", "GBP", "SGD"];
+/// Levels per side of each book update; the venue sends the whole book
+const BOOK_LEVELS: usize = 50;
+
+static L2_UPDATES_SCHEMA: MessageSchema = MessageSchema {
+    required: &["type", "symbol", "changes"],
+    optional: &["trades", "auction_events"],
+    payload: l2_updates_payload,
+};
+
+fn l2_updates_payload(frame: &Value) -> Option<&Value> {
+    frame.get("type").filter(|kind| *kind == "l2_updates").and(Some(frame))
+}
+
+/// Price levels keyed by the price's bits: positive floats order the same as
+/// their bit patterns, so each side stays sorted by price
+#[derive(Default)]
+struct Book {
+    bids: BTreeMap<u64, f64>,
+    asks: BTreeMap<u64, f64>,
+}
+
+impl Book {
+    fn apply(&mut self, side: &str, price: f64, quantity: f64) -> Result<(), NormalizeError> {
+        let levels = match side {
+            "buy" => &mut self.bids,
+            "sell" => &mut self.asks,
+            other => return Err(NormalizeError::Malformed(format!("unexpected side '{}'", other))),
+        };
+        if quantity == 0.0 {
+            levels.remove(&price.to_bits());
+        } else {
+            levels.insert(price.to_bits(), quantity);
+        }
+        Ok(())
+    }
+    
+    fn top(&self) -> Option<((f64, f64), (f64, f64))> {
+        let (bid, bid_size) = self.bids.iter().next_back()?;
+        let (ask, ask_size) = self.asks.iter().next()?;
+        Some(((f64::from_bits(*bid), *bid_size), (f64::from_bits(*ask), *ask_size)))
+    }
+    
+    /// Best `levels` bids, from the top
+    fn best_bids(&self, levels: usize) -> Vec<(f64, f64)> {
+        self.bids.iter().rev().take(levels).map(|(price, size)| (f64::from_bits(*price), *size)).collect()
+    }
+    
+    fn best_asks(&self, levels: usize) -> Vec<(f64, f64)> {
+        self.asks.iter().take(levels).map(|(price, size)| (f64::from_bits(*price), *size)).collect()
+    }
+}
+
+/// Every symbol shares one socket. The first `l2_updates` after subscribing
+/// is the full book and later ones are changes to it, so the book is kept
+/// per symbol; depth updates are snapshots of its top, so an engine book
+/// that fell out of sync is whole again on the next update
+pub struct GeminiConnector {
+    books: RwLock<HashMap<String, Book>>,
+}
+
+impl GeminiConnector {
+    pub fn new() -> Self {
+        Self {
+            books: RwLock::new(HashMap::new()),
+        }
+    }
+    
+    fn exchange_symbols(&self, symbols: &[String]) -> Vec<String> {
+        symbols.iter().map(|s| self.to_exchange_symbol(s)).collect()
+    }
+    
+    /// Apply `changes` ([["buy", "price", "quantity"], ...]) and quote the new book
+    fn l2_updates(&self, frame: &Value) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let raw_symbol = field(frame, "symbol")?
+            .as_str()
+            .ok_or(NormalizeError::MissingField("symbol"))?;
+        let symbol = self
+            .from_exchange_symbol(raw_symbol)
+            .ok_or_else(|| NormalizeError::UnknownSymbol(raw_symbol.to_string()))?;
+        let changes = field(frame, "changes")?
+            .as_array()
+            .ok_or_else(|| NormalizeError::Malformed("changes is not an array".to_string()))?;
+        
+        let mut books = self.books.write().unwrap();
+        let book = books.entry(symbol.clone()).or_default();
+        for change in changes {
+            let side = change.get(0).and_then(Value::as_str).ok_or(NormalizeError::MissingField("side"))?;
+            let price = decimal(change.get(1).ok_or(NormalizeError::MissingField("price"))?, "price")?;
+            let quantity = decimal(change.get(2).ok_or(NormalizeError::MissingField("quantity"))?, "quantity")?;
+            book.apply(side, price, quantity)?;
+        }
+        
+        let update = NormalizedEvent::Book(OrderBookUpdate {
+            exchange: self.name().to_string(),
+            symbol: symbol.clone(),
+            snapshot: true,
+            bids: book.best_bids(BOOK_LEVELS),
+            asks: book.best_asks(BOOK_LEVELS),
+            sequence: None,
+            depth: None,
+        });
+        
+        // A one-sided book has no quote yet
+        let Some(((bid, bid_size), (ask, ask_size))) = book.top() else {
+            return Ok(vec![update]);
+        };
+        Ok(vec![
+            NormalizedEvent::Tick(CanonicalTick {
+                exchange: self.name().to_string(),
+                symbol,
+                bid,
+                bid_size,
+                ask,
+                ask_size,
+            }),
+            update,
+        ])
+    }
+}
+
+impl ExchangeConnector for GeminiConnector {
+    fn name(&self) -> &'static str {
+        "gemini"
+    }
+    
+    fn ws_url(&self) -> &str {
+        WS_URL
+    }
+    
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        symbol.replace('/', "").to_uppercase()
+    }
+    
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        split_concatenated(raw, QUOTE_ASSETS)
+    }
+    
+    fn subscribe_messages(&self, symbols: &[String], _request_id: u64) -> Vec<String> {
+        // The reply is a fresh snapshot, so stale levels from a dropped socket go
+        let mut books = self.books.write().unwrap();
+        for symbol in symbols {
+            books.remove(symbol);
+        }
+        vec![json!({
+            "type": "subscribe",
+            "subscriptions": [{ "name": "l2", "symbols": self.exchange_symbols(symbols) }],
+        })
+        .to_string()]
+    }
+    
+    fn unsubscribe_messages(&self, symbols: &[String]) -> Vec<String> {
+        let mut books = self.books.write().unwrap();
+        for symbol in symbols {
+            books.remove(symbol);
+        }
+        vec![json!({
+            "type": "unsubscribe",
+            "subscriptions": [{ "name": "l2", "symbols": self.exchange_symbols(symbols) }],
+        })
+        .to_string()]
+    }
+    
+    fn instruments_url(&self) -> Option<&str> {
+        Some(INSTRUMENTS_URL)
+    }
+    
+    fn parse_instruments(&self, body: &str) -> Result<Vec<String>, NormalizeError> {
+        // Lowercase symbols; perpetuals ("btcgusdperp") match no quote asset
+        let symbols = parse_frame(body)?;
+        let symbols = symbols.as_array().ok_or_else(|| NormalizeError::Malformed("expected a symbol list".to_string()))?;
+        Ok(symbols.iter().filter_map(|symbol| self.from_exchange_symbol(symbol.as_str()?)).collect())
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: true,
+            trading: false,
+            testnet_ws_url: Some("wss://api.sandbox.gemini.com/v2/marketdata"),
+            max_streams_per_connection: None,
+            streams_per_symbol: 1,
+            max_messages_per_second: None,
+            symbol_format: SymbolFormat::Concatenated,
+        }
+    }
+    
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        Some(&L2_UPDATES_SCHEMA)
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        
+        // Errors come back REST-style: {"result": "error", "reason": .., "message": ..}
+        if frame.get("result").and_then(Value::as_str) == Some("error") {
+            return Ok(vec![NormalizedEvent::SubscriptionRejected {
+                symbols: Vec::new(),
+                reason: frame
+                    .get("message")
+                    .or_else(|| frame.get("reason"))
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            }]);
+        }
+        
+        match field(&frame, "type")?.as_str().unwrap_or_default() {
+            "l2_updates" => self.l2_updates(&frame),
+            "heartbeat" => Ok(vec![NormalizedEvent::Heartbeat]),
+            // Prints and auction results aren't quotes
+            event @ ("trade" | "auction_result" | "auction_indicative") => {
+                Ok(vec![NormalizedEvent::Control(event.to_string())])
+            }
+            other => Err(NormalizeError::Malformed(format!("unexpected event type '{}'", other))),
+        }
+    }
+}
@@ -0,0 +1,206 @@
+// exchange/htx.rs - HTX (Huobi) spot connector (gzip frames, bbo topics, ping replies)
+use std::io::Read;
+use flate2::read::GzDecoder;
+use serde_json::{json, Value};
+
+use super::connector::{
+    decimal, field, levels, list, parse_frame, split_concatenated, CanonicalTick, ExchangeConnector,
+    NormalizeError, NormalizedEvent, OrderBookUpdate,
+};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::schema::MessageSchema;
+
+const WS_URL: &str = "wss://api.huobi.pro/ws";
+const INSTRUMENTS_URL: &str = "https://api.huobi.pro/v1/common/symbols";
+const QUOTE_ASSETS: &[&str] = &["USDT", "USDC", "USDD", "HUSD", "BTC", "ETH", "HT", "TRX", "EUR"];
+/// Depths the 100ms `mbp.refresh` book topic comes in
+const DEPTH_LEVELS: &[usize] = &[5, 10, 20];
+
+static BBO_SCHEMA: MessageSchema = MessageSchema {
+    required: &["bid", "bidSize", "ask", "askSize"],
+    optional: &["seqId", "quoteTime", "symbol"],
+    payload: bbo_payload,
+};
+
+fn bbo_payload(frame: &Value) -> Option<&Value> {
+    let channel = frame.get("ch")?.as_str()?;
+    channel.ends_with(".bbo").then(|| frame.get("tick")).flatten()
+}
+
+pub struct HtxConnector;
+
+impl HtxConnector {
+    pub fn new() -> Self {
+        Self
+    }
+    
+    /// One frame per topic; HTX takes a single topic per request
+    fn topic_messages(&self, kind: &str, symbols: &[String], suffix: &str, request_id: u64) -> Vec<String> {
+        symbols
+            .iter()
+            .map(|s| {
+                json!({
+                    kind: format!("market.{}.{}", self.to_exchange_symbol(s), suffix),
+                    "id": request_id.to_string(),
+                })
+                .to_string()
+            })
+            .collect()
+    }
+    
+    fn depth_topic(levels: usize) -> String {
+        let depth = DEPTH_LEVELS.iter().copied().find(|&depth| depth >= levels).unwrap_or(20);
+        format!("mbp.refresh.{}", depth)
+    }
+}
+
+impl ExchangeConnector for HtxConnector {
+    fn name(&self) -> &'static str {
+        "htx"
+    }
+    
+    fn ws_url(&self) -> &str {
+        WS_URL
+    }
+    
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        symbol.replace('/', "").to_lowercase()
+    }
+    
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        split_concatenated(raw, QUOTE_ASSETS)
+    }
+    
+    fn subscribe_messages(&self, symbols: &[String], request_id: u64) -> Vec<String> {
+        self.topic_messages("sub", symbols, "bbo", request_id)
+    }
+    
+    fn unsubscribe_messages(&self, symbols: &[String]) -> Vec<String> {
+        self.topic_messages("unsub", symbols, "bbo", 0)
+    }
+    
+    /// Whole top-5, 10 or 20 books every 100ms
+    fn depth_subscribe_messages(&self, symbols: &[String], levels: usize, request_id: u64) -> Vec<String> {
+        self.topic_messages("sub", symbols, &Self::depth_topic(levels), request_id)
+    }
+    
+    fn depth_unsubscribe_messages(&self, symbols: &[String], levels: usize) -> Vec<String> {
+        self.topic_messages("unsub", symbols, &Self::depth_topic(levels), 0)
+    }
+    
+    fn instruments_url(&self) -> Option<&str> {
+        Some(INSTRUMENTS_URL)
+    }
+    
+    fn parse_instruments(&self, body: &str) -> Result<Vec<String>, NormalizeError> {
+        let response = parse_frame(body)?;
+        Ok(list(&response, "data")?
+            .iter()
+            .filter(|market| market["state"] == "online")
+            .filter_map(|market| {
+                let (base, quote) = (market["base-currency"].as_str()?, market["quote-currency"].as_str()?);
+                Some(format!("{}/{}", base, quote).to_uppercase())
+            })
+            .collect())
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: true,
+            trading: false,
+            testnet_ws_url: None,
+            max_streams_per_connection: None,
+            streams_per_symbol: 1,
+            max_messages_per_second: None,
+            symbol_format: SymbolFormat::Concatenated,
+        }
+    }
+    
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        Some(&BBO_SCHEMA)
+    }
+    
+    /// Every market data frame arrives gzip-compressed
+    fn decode_binary(&self, data: &[u8]) -> Result<String, NormalizeError> {
+        let mut text = String::new();
+        GzDecoder::new(data)
+            .read_to_string(&mut text)
+            .map_err(|e| NormalizeError::Malformed(format!("gzip frame: {}", e)))?;
+        Ok(text)
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        
+        // The server pings with a timestamp and drops sockets that don't echo it
+        if let Some(ping) = frame.get("ping") {
+            return Ok(vec![NormalizedEvent::Reply(json!({ "pong": ping }).to_string())]);
+        }
+        if let Some(status) = frame.get("status").and_then(Value::as_str) {
+            let request_id = frame.get("id").and_then(Value::as_str).and_then(|id| id.parse().ok());
+            return Ok(match status {
+                "ok" if frame.get("subbed").is_some() => vec![NormalizedEvent::SubscriptionAck { request_id, symbols: Vec::new() }],
+                "ok" => Vec::new(),
+                // {"id":"1","status":"error","err-code":"bad-request","err-msg":"invalid symbol xyzusdt"}
+                _ => vec![NormalizedEvent::SubscriptionRejected {
+                    symbols: Vec::new(),
+                    reason: frame.get("err-msg").and_then(Value::as_str).unwrap_or(status).to_string(),
+                }],
+            });
+        }
+        
+        // "market.btcusdt.bbo" or "market.btcusdt.mbp.refresh.20"
+        let channel = field(&frame, "ch")?.as_str().ok_or(NormalizeError::MissingField("ch"))?;
+        let mut parts = channel.splitn(3, '.');
+        let (Some("market"), Some(market), Some(topic)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(NormalizeError::Malformed(format!("unexpected channel '{}'", channel)));
+        };
+        let symbol = self
+            .from_exchange_symbol(market)
+            .ok_or_else(|| NormalizeError::UnknownSymbol(market.to_string()))?;
+        let tick = field(&frame, "tick")?;
+        
+        if topic.starts_with("mbp.refresh.") {
+            return Ok(vec![NormalizedEvent::Book(OrderBookUpdate {
+                exchange: self.name().to_string(),
+                symbol,
+                snapshot: true,
+                bids: levels(field(tick, "bids")?, "bids")?,
+                asks: levels(field(tick, "asks")?, "asks")?,
+                sequence: tick.get("seqNum").and_then(Value::as_u64),
+                depth: None,
+            })]);
+        }
+        if topic != "bbo" {
+            return Err(NormalizeError::Malformed(format!("unexpected channel '{}'", channel)));
+        }
+        Ok(vec![NormalizedEvent::Tick(CanonicalTick {
+            exchange: self.name().to_string(),
+            symbol,
+            bid: decimal(field(tick, "bid")?, "bid")?,
+            bid_size: decimal(field(tick, "bidSize")?, "bidSize")?,
+            ask: decimal(field(tick, "ask")?, "ask")?,
+            ask_size: decimal(field(tick, "askSize")?, "askSize")?,
+        })])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    
+    #[test]
+    fn test_gzip_frames_and_ping_replies() {
+        let connector = HtxConnector::new();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(br#"{"ping":1718000000000}"#).unwrap();
+        let text = connector.decode_binary(&encoder.finish().unwrap()).unwrap();
+        
+        assert_eq!(connector.normalize(&text).unwrap(), vec![NormalizedEvent::Reply(r#"{"pong":1718000000000}"#.to_string())]);
+        // Plain text is not a gzip frame
+        assert!(connector.decode_binary(br#"{"ping":1}"#).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+// exchange/kraken.rs - Kraken spot connector (WebSocket v1 ticker channel)
+use serde_json::{json, Value};
+
+use super::connector::{
+    decimal, field, parse_frame, CanonicalTick, ExchangeConnector, NormalizeError, NormalizedEvent, OrderBookUpdate,
+};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::schema::MessageSchema;
+
+const WS_URL: &str = "wss://ws.kraken.com";
+const INSTRUMENTS_URL: &str = "https://api.kraken.com/0/public/AssetPairs";
+
+// Kraken keeps legacy ISO-4217-style codes for a few assets
+const ASSET_ALIASES: &[(&str, &str)] = &[("BTC", "XBT"), ("DOGE", "XDG")];
+/// Book depths Kraken subscribes to
+const DEPTH_LEVELS: &[usize] = &[10, 25, 100, 500, 1000];
+
+static TICKER_SCHEMA: MessageSchema = MessageSchema {
+    required: &["a", "b"],
+    optional: &["c", "v", "p", "t", "l", "h", "o"],
+    payload: ticker_payload,
+};
+
+fn ticker_payload(frame: &Value) -> Option<&Value> {
+    // Book frames share the array layout but not the payload
+    frame.as_array().filter(|parts| parts.get(2).is_some_and(|channel| channel == "ticker")).and_then(|parts| parts.get(1))
+}
+
+pub struct KrakenConnector;
+
+impl KrakenConnector {
+    pub fn new() -> Self {
+        Self
+    }
+    
+    fn to_kraken_asset(asset: &str) -> &str {
+        ASSET_ALIASES
+            .iter()
+            .find(|(internal, _)| *internal == asset)
+            .map(|(_, kraken)| *kraken)
+            .unwrap_or(asset)
+    }
+    
+    fn from_kraken_asset(asset: &str) -> &str {
+        ASSET_ALIASES
+            .iter()
+            .find(|(_, kraken)| *kraken == asset)
+            .map(|(internal, _)| *internal)
+            .unwrap_or(asset)
+    }
+    
+    /// Ticker side: [price, whole lot volume, lot volume]
+    fn level(side: &Value, name: &'static str) -> Result<(f64, f64), NormalizeError> {
+        let price = side.get(0).ok_or(NormalizeError::MissingField(name))?;
+        let size = side.get(2).ok_or(NormalizeError::MissingField(name))?;
+        Ok((decimal(price, name)?, decimal(size, name)?))
+    }
+    
+    fn depth(levels: usize) -> usize {
+        DEPTH_LEVELS.iter().copied().find(|&depth| depth >= levels).unwrap_or(1000)
+    }
+    
+    /// Book levels: [[price, volume, timestamp(, "r")], ...]
+    fn book_levels(payload: &Value, name: &'static str, levels: &mut Vec<(f64, f64)>) -> Result<(), NormalizeError> {
+        let Some(side) = payload.get(name) else {
+            return Ok(());
+        };
+        for level in side.as_array().ok_or(NormalizeError::MissingField(name))? {
+            let price = level.get(0).ok_or(NormalizeError::MissingField(name))?;
+            let volume = level.get(1).ok_or(NormalizeError::MissingField(name))?;
+            levels.push((decimal(price, name)?, decimal(volume, name)?));
+        }
+        Ok(())
+    }
+    
+    /// Book frames: [channelID, {"as": .., "bs": ..}, "book-25", pair] for the
+    /// snapshot, then [channelID, {"a": ..}, ({"b": ..},) "book-25", pair]
+    fn book_update(&self, parts: &[Value], channel: &str, pair: &str) -> Result<OrderBookUpdate, NormalizeError> {
+        let symbol = self
+            .from_exchange_symbol(pair)
+            .ok_or_else(|| NormalizeError::UnknownSymbol(pair.to_string()))?;
+        let mut update = OrderBookUpdate {
+            exchange: self.name().to_string(),
+            symbol,
+            snapshot: false,
+            bids: Vec::new(),
+            asks: Vec::new(),
+            sequence: None,
+            depth: channel.strip_prefix("book-").and_then(|depth| depth.parse().ok()),
+        };
+        for payload in &parts[1..parts.len() - 2] {
+            if payload.get("as").is_some() || payload.get("bs").is_some() {
+                update.snapshot = true;
+                Self::book_levels(payload, "as", &mut update.asks)?;
+                Self::book_levels(payload, "bs", &mut update.bids)?;
+            } else {
+                Self::book_levels(payload, "a", &mut update.asks)?;
+                Self::book_levels(payload, "b", &mut update.bids)?;
+            }
+        }
+        Ok(update)
+    }
+}
+
+impl ExchangeConnector for KrakenConnector {
+    fn name(&self) -> &'static str {
+        "kraken"
+    }
+    
+    fn ws_url(&self) -> &str {
+        WS_URL
+    }
+    
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        match symbol.split_once('/') {
+            Some((base, quote)) => format!("{}/{}", Self::to_kraken_asset(base), Self::to_kraken_asset(quote)),
+            None => symbol.to_string(),
+        }
+    }
+    
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        let (base, quote) = raw.split_once('/')?;
+        Some(format!("{}/{}", Self::from_kraken_asset(base), Self::from_kraken_asset(quote)))
+    }
+    
+    fn subscribe_messages(&self, symbols: &[String], request_id: u64) -> Vec<String> {
+        let pairs: Vec<String> = symbols.iter().map(|s| self.to_exchange_symbol(s)).collect();
+        
+        vec![json!({
+            "event": "subscribe",
+            "reqid": request_id,
+            "pair": pairs,
+            "subscription": { "name": "ticker" },
+        })
+        .to_string()]
+    }
+    
+    fn unsubscribe_messages(&self, symbols: &[String]) -> Vec<String> {
+        let pairs: Vec<String> = symbols.iter().map(|s| self.to_exchange_symbol(s)).collect();
+        
+        vec![json!({
+            "event": "unsubscribe",
+            "pair": pairs,
+            "subscription": { "name": "ticker" },
+        })
+        .to_string()]
+    }
+    
+    fn depth_subscribe_messages(&self, symbols: &[String], levels: usize, request_id: u64) -> Vec<String> {
+        let pairs: Vec<String> = symbols.iter().map(|s| self.to_exchange_symbol(s)).collect();
+        
+        vec![json!({
+            "event": "subscribe",
+            "reqid": request_id,
+            "pair": pairs,
+            "subscription": { "name": "book", "depth": Self::depth(levels) },
+        })
+        .to_string()]
+    }
+    
+    fn depth_unsubscribe_messages(&self, symbols: &[String], levels: usize) -> Vec<String> {
+        let pairs: Vec<String> = symbols.iter().map(|s| self.to_exchange_symbol(s)).collect();
+        
+        vec![json!({
+            "event": "unsubscribe",
+            "pair": pairs,
+            "subscription": { "name": "book", "depth": Self::depth(levels) },
+        })
+        .to_string()]
+    }
+    
+    fn instruments_url(&self) -> Option<&str> {
+        Some(INSTRUMENTS_URL)
+    }
+    
+    fn parse_instruments(&self, body: &str) -> Result<Vec<String>, NormalizeError> {
+        let response = parse_frame(body)?;
+        let pairs = field(&response, "result")?
+            .as_object()
+            .ok_or_else(|| NormalizeError::Malformed("expected pairs by name".to_string()))?;
+        // Pairs are keyed by REST name; `wsname` is what the socket uses
+        Ok(pairs
+            .values()
+            .filter(|pair| pair["status"] == "online")
+            .filter_map(|pair| self.from_exchange_symbol(pair["wsname"].as_str()?))
+            .collect())
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: true,
+            trading: false,
+            testnet_ws_url: None,
+            max_streams_per_connection: None,
+            streams_per_symbol: 1,
+            max_messages_per_second: Some(1),
+            symbol_format: SymbolFormat::Slashed,
+        }
+    }
+    
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        Some(&TICKER_SCHEMA)
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        
+        if let Some(parts) = frame.as_array() {
+            let channel = parts.len().checked_sub(2).and_then(|i| parts[i].as_str()).unwrap_or_default();
+            if channel.starts_with("book-") && parts.len() >= 4 {
+                let pair = parts[parts.len() - 1].as_str().ok_or(NormalizeError::MissingField("pair"))?;
+                return Ok(vec![NormalizedEvent::Book(self.book_update(parts, channel, pair)?)]);
+            }
+            // Ticker data: [channelID, payload, "ticker", "XBT/USDT"]
+            if parts.len() != 4 || channel != "ticker" {
+                return Err(NormalizeError::Malformed(format!("unexpected channel frame of {} parts", parts.len())));
+            }
+            
+            let pair = parts[3].as_str().ok_or(NormalizeError::MissingField("pair"))?;
+            let symbol = self
+                .from_exchange_symbol(pair)
+                .ok_or_else(|| NormalizeError::UnknownSymbol(pair.to_string()))?;
+            let (bid, bid_size) = Self::level(field(&parts[1], "b")?, "b")?;
+            let (ask, ask_size) = Self::level(field(&parts[1], "a")?, "a")?;
+            
+            return Ok(vec![NormalizedEvent::Tick(CanonicalTick {
+                exchange: self.name().to_string(),
+                symbol,
+                bid,
+                bid_size,
+                ask,
+                ask_size,
+            })]);
+        }
+        
+        match field(&frame, "event")?.as_str().unwrap_or_default() {
+            "heartbeat" => Ok(vec![NormalizedEvent::Heartbeat]),
+            "subscriptionStatus" => {
+                let symbols: Vec<String> = frame
+                    .get("pair")
+                    .and_then(Value::as_str)
+                    .and_then(|pair| self.from_exchange_symbol(pair))
+                    .into_iter()
+                    .collect();
+                
+                match frame.get("status").and_then(Value::as_str) {
+                    Some("subscribed") => Ok(vec![NormalizedEvent::SubscriptionAck {
+                        request_id: frame.get("reqid").and_then(Value::as_u64),
+                        symbols,
+                    }]),
+                    Some("error") => Ok(vec![NormalizedEvent::SubscriptionRejected {
+                        symbols,
+                        reason: frame.get("errorMessage").and_then(Value::as_str).unwrap_or_default().to_string(),
+                    }]),
+                    _ => Ok(vec![NormalizedEvent::Control("subscriptionStatus".to_string())]),
+                }
+            }
+            event @ ("systemStatus" | "pong") => {
+                Ok(vec![NormalizedEvent::Control(event.to_string())])
+            }
+            other => Err(NormalizeError::Malformed(format!("unexpected event '{}'", other))),
+        }
+    }
+}
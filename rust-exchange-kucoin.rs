@@ -0,0 +1,243 @@
+// exchange/kucoin.rs - KuCoin spot connector (token handshake, ticker topics)
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use futures_util::future::BoxFuture;
+use serde_json::{json, Value};
+
+use super::connector::{
+    decimal, field, levels, list, parse_frame, CanonicalTick, Endpoint, ExchangeConnector, NormalizeError,
+    NormalizedEvent, OrderBookUpdate,
+};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::schema::MessageSchema;
+
+/// Public token endpoint; the socket address comes back with the token
+const BULLET_URL: &str = "https://api.kucoin.com/api/v1/bullet-public";
+const WS_URL: &str = "wss://ws-api-spot.kucoin.com/";
+const INSTRUMENTS_URL: &str = "https://api.kucoin.com/api/v2/symbols";
+/// Symbols one subscribe frame may carry in its topic
+const SYMBOLS_PER_FRAME: usize = 100;
+
+static TICKER_SCHEMA: MessageSchema = MessageSchema {
+    required: &["bestBid", "bestBidSize", "bestAsk", "bestAskSize"],
+    optional: &["sequence", "price", "size", "Time"],
+    payload: ticker_payload,
+};
+
+fn ticker_payload(frame: &Value) -> Option<&Value> {
+    frame.get("subject").filter(|subject| *subject == "trade.ticker").and_then(|_| frame.get("data"))
+}
+
+pub struct KucoinConnector;
+
+impl KucoinConnector {
+    pub fn new() -> Self {
+        Self
+    }
+    
+    /// One frame per `SYMBOLS_PER_FRAME` symbols of `topic`
+    fn topic_messages(&self, kind: &str, topic: &str, symbols: &[String], request_id: u64) -> Vec<String> {
+        symbols
+            .chunks(SYMBOLS_PER_FRAME)
+            .map(|chunk| {
+                let markets: Vec<String> = chunk.iter().map(|s| self.to_exchange_symbol(s)).collect();
+                json!({
+                    "id": request_id.to_string(),
+                    "type": kind,
+                    "topic": format!("{}:{}", topic, markets.join(",")),
+                    "privateChannel": false,
+                    "response": true,
+                })
+                .to_string()
+            })
+            .collect()
+    }
+    
+    fn depth_topic(levels: usize) -> &'static str {
+        if levels <= 5 {
+            "/spotMarket/level2Depth5"
+        } else {
+            "/spotMarket/level2Depth50"
+        }
+    }
+    
+    /// Socket address, token and ping cadence from a bullet-public response
+    pub(crate) fn parse_bullet(body: &str, connect_id: u64) -> Result<Endpoint, NormalizeError> {
+        let response = parse_frame(body)?;
+        if response.get("code").and_then(Value::as_str) != Some("200000") {
+            return Err(NormalizeError::Malformed(format!("token request refused: {}", body)));
+        }
+        let data = field(&response, "data")?;
+        let token = field(data, "token")?.as_str().ok_or(NormalizeError::MissingField("token"))?;
+        let server = field(data, "instanceServers")?
+            .as_array()
+            .and_then(|servers| servers.first())
+            .ok_or(NormalizeError::MissingField("instanceServers"))?;
+        let endpoint = field(server, "endpoint")?.as_str().ok_or(NormalizeError::MissingField("endpoint"))?;
+        let ping_ms = field(server, "pingInterval")?.as_u64().ok_or(NormalizeError::MissingField("pingInterval"))?;
+        
+        Ok(Endpoint {
+            url: format!("{}?token={}&connectId={}", endpoint, token, connect_id),
+            keepalive: Some((
+                Duration::from_millis(ping_ms),
+                json!({ "id": connect_id.to_string(), "type": "ping" }).to_string(),
+            )),
+        })
+    }
+}
+
+impl ExchangeConnector for KucoinConnector {
+    fn name(&self) -> &'static str {
+        "kucoin"
+    }
+    
+    fn ws_url(&self) -> &str {
+        WS_URL
+    }
+    
+    fn handshake<'a>(&'a self, client: &'a reqwest::Client) -> Option<BoxFuture<'a, Result<Endpoint, NormalizeError>>> {
+        Some(Box::pin(async move {
+            let body = client
+                .post(BULLET_URL)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| NormalizeError::Malformed(format!("token request failed: {}", e)))?
+                .text()
+                .await
+                .map_err(|e| NormalizeError::Malformed(format!("token response unreadable: {}", e)))?;
+            let connect_id = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos() as u64;
+            Self::parse_bullet(&body, connect_id)
+        }))
+    }
+    
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        symbol.replace('/', "-").to_uppercase()
+    }
+    
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        let (base, quote) = raw.split_once('-')?;
+        Some(format!("{}/{}", base.to_uppercase(), quote.to_uppercase()))
+    }
+    
+    fn subscribe_messages(&self, symbols: &[String], request_id: u64) -> Vec<String> {
+        self.topic_messages("subscribe", "/market/ticker", symbols, request_id)
+    }
+    
+    fn unsubscribe_messages(&self, symbols: &[String]) -> Vec<String> {
+        self.topic_messages("unsubscribe", "/market/ticker", symbols, 0)
+    }
+    
+    /// Whole top-5 or top-50 books every 100ms
+    fn depth_subscribe_messages(&self, symbols: &[String], levels: usize, request_id: u64) -> Vec<String> {
+        self.topic_messages("subscribe", Self::depth_topic(levels), symbols, request_id)
+    }
+    
+    fn depth_unsubscribe_messages(&self, symbols: &[String], levels: usize) -> Vec<String> {
+        self.topic_messages("unsubscribe", Self::depth_topic(levels), symbols, 0)
+    }
+    
+    fn instruments_url(&self) -> Option<&str> {
+        Some(INSTRUMENTS_URL)
+    }
+    
+    fn parse_instruments(&self, body: &str) -> Result<Vec<String>, NormalizeError> {
+        let response = parse_frame(body)?;
+        Ok(list(&response, "data")?
+            .iter()
+            .filter(|market| market["enableTrading"] == true)
+            .filter_map(|market| self.from_exchange_symbol(market["symbol"].as_str()?))
+            .collect())
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: true,
+            trading: false,
+            testnet_ws_url: None,
+            max_streams_per_connection: Some(400),
+            streams_per_symbol: 1,
+            max_messages_per_second: Some(10),
+            symbol_format: SymbolFormat::Dashed,
+        }
+    }
+    
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        Some(&TICKER_SCHEMA)
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        
+        match field(&frame, "type")?.as_str().unwrap_or_default() {
+            "message" => {
+                // Topics name the market after the colon: "/market/ticker:BTC-USDT"
+                let topic = field(&frame, "topic")?.as_str().ok_or(NormalizeError::MissingField("topic"))?;
+                let (channel, market) = topic
+                    .split_once(':')
+                    .ok_or_else(|| NormalizeError::Malformed(format!("topic without a market: {}", topic)))?;
+                let symbol = self
+                    .from_exchange_symbol(market)
+                    .ok_or_else(|| NormalizeError::UnknownSymbol(market.to_string()))?;
+                let data = field(&frame, "data")?;
+                
+                if channel.starts_with("/spotMarket/level2Depth") {
+                    return Ok(vec![NormalizedEvent::Book(OrderBookUpdate {
+                        exchange: self.name().to_string(),
+                        symbol,
+                        snapshot: true,
+                        bids: levels(field(data, "bids")?, "bids")?,
+                        asks: levels(field(data, "asks")?, "asks")?,
+                        sequence: None,
+                        depth: None,
+                    })]);
+                }
+                if channel != "/market/ticker" {
+                    return Err(NormalizeError::Malformed(format!("unexpected topic '{}'", topic)));
+                }
+                Ok(vec![NormalizedEvent::Tick(CanonicalTick {
+                    exchange: self.name().to_string(),
+                    symbol,
+                    bid: decimal(field(data, "bestBid")?, "bestBid")?,
+                    bid_size: decimal(field(data, "bestBidSize")?, "bestBidSize")?,
+                    ask: decimal(field(data, "bestAsk")?, "bestAsk")?,
+                    ask_size: decimal(field(data, "bestAskSize")?, "bestAskSize")?,
+                })])
+            }
+            "ack" => Ok(vec![NormalizedEvent::SubscriptionAck {
+                request_id: frame.get("id").and_then(Value::as_str).and_then(|id| id.parse().ok()),
+                symbols: Vec::new(),
+            }]),
+            // {"id":"1","type":"error","code":404,"data":"topic /market/ticker:XYZ-USDT is not found"}
+            "error" => Ok(vec![NormalizedEvent::SubscriptionRejected {
+                symbols: Vec::new(),
+                reason: frame.get("data").map(|data| data.as_str().map_or_else(|| data.to_string(), String::from)).unwrap_or_default(),
+            }]),
+            "pong" => Ok(vec![NormalizedEvent::Heartbeat]),
+            "welcome" => Ok(vec![NormalizedEvent::Control("welcome".to_string())]),
+            other => Err(NormalizeError::Malformed(format!("unexpected message type '{}'", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_bullet_response_gives_tokenized_url_and_ping() {
+        let body = r#"{"code":"200000","data":{"token":"2neAiuYvAU61ZDXANAGAsiL4","instanceServers":[
+            {"endpoint":"wss://ws-api-spot.kucoin.com/","encrypt":true,"protocol":"websocket","pingInterval":18000,"pingTimeout":10000}]}}"#;
+        let endpoint = KucoinConnector::parse_bullet(body, 42).unwrap();
+        assert_eq!(endpoint.url, "wss://ws-api-spot.kucoin.com/?token=2neAiuYvAU61ZDXANAGAsiL4&connectId=42");
+        let (interval, ping) = endpoint.keepalive.unwrap();
+        assert_eq!((interval, ping.as_str()), (Duration::from_secs(18), r#"{"id":"42","type":"ping"}"#));
+        
+        assert!(KucoinConnector::parse_bullet(r#"{"code":"429000","msg":"Too many requests"}"#, 42).is_err());
+        
+        // More symbols than one topic takes are split over several frames
+        let symbols: Vec<String> = (0..150).map(|i| format!("A{}/USDT", i)).collect();
+        let frames = KucoinConnector::new().subscribe_messages(&symbols, 7);
+        assert_eq!(frames.len(), 2);
+        assert!(frames[1].contains("/market/ticker:A100-USDT,A101-USDT"));
+    }
+}
@@ -0,0 +1,517 @@
+// exchange/manager.rs - Connection lifecycle for all configured exchanges
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use serde::Serialize;
+use tokio::runtime::Handle;
+use tokio::sync::Notify;
+use tokio::{task, time};
+use tracing::{debug, error, info, warn};
+
+use crate::arbitrage::depth::BookApply;
+use crate::arbitrage::{ArbitrageEngine, Config};
+use crate::config::ConfigError;
+use crate::faults::{FaultInjector, FaultStats};
+use super::capabilities::{self, SubscriptionBudget};
+#[cfg(feature = "chaos")]
+use super::chaos::Chaos;
+use super::connector::NormalizedEvent;
+use super::registry::{BoxError, Exchange, ExchangeRegistry, ExchangeSession, SessionContext};
+use super::schema::SchemaMonitor;
+use super::subscriptions::SubscriptionTracker;
+
+/// Protocol alerts fire once a drift has been seen this many times
+const SCHEMA_ALERT_THRESHOLD: u64 = 20;
+
+/// Raised when a connection exhausts `max_reconnect_attempts` and stops
+#[derive(Debug, Clone)]
+pub struct ExchangeDown {
+    pub exchange: String,
+    pub attempts: u32,
+    pub error: String,
+}
+
+pub type ExchangeDownCallback = Box<dyn Fn(ExchangeDown) + Send + Sync>;
+
+/// Raised when a connection drops and again once it is re-established
+#[derive(Debug, Clone)]
+pub struct ConnectionChange {
+    pub exchange: String,
+    pub connected: bool,
+}
+
+pub type ConnectionCallback = Box<dyn Fn(ConnectionChange) + Send + Sync>;
+
+/// Symbols one connection carries. A config reload edits them in place and
+/// wakes the connection, which subscribes and unsubscribes the difference on
+/// its open socket
+struct Shard {
+    symbols: RwLock<Vec<String>>,
+    dropped: RwLock<HashSet<String>>, // Unsubscribed on reload; ticks still in flight are discarded
+    resync: Mutex<HashSet<String>>, // Books out of sync, resubscribed for a fresh snapshot
+    changed: Notify,
+}
+
+/// A running connection task and the shard it serves
+struct Connection {
+    exchange: String,
+    url: String, // Exchange endpoint; connectors that encode symbols in it reconnect on change
+    shard: Arc<Shard>,
+    connected: Arc<AtomicBool>,
+    handle: task::JoinHandle<()>,
+}
+
+/// One connection as support bundles report it
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionState {
+    pub exchange: String,
+    pub connected: bool,
+    pub symbols: Vec<String>,
+}
+
+pub struct ExchangeManager {
+    config: Arc<RwLock<Config>>, // Connections read their timeouts from it, so a reload reaches them
+    engine: Arc<ArbitrageEngine>,
+    registry: Arc<ExchangeRegistry>, // What `Config.exchanges` names
+    subscriptions: Arc<SubscriptionTracker>,
+    schema_monitor: Arc<SchemaMonitor>,
+    is_running: Arc<AtomicBool>,
+    connections: Mutex<Vec<Connection>>,
+    down_callbacks: Arc<RwLock<Vec<ExchangeDownCallback>>>,
+    connection_callbacks: Arc<RwLock<Vec<ConnectionCallback>>>,
+    feed_faults: Option<Arc<FaultInjector>>, // Testing only; see `Config::fault_injection`
+    http: reqwest::Client, // Session handshakes
+    runtime: Option<Handle>, // Where connection tasks run; the caller's runtime when unset
+}
+
+/// Everything one connection task needs, cloned per exchange
+#[derive(Clone)]
+struct ConnectionContext {
+    exchange: Arc<dyn Exchange>,
+    session: SessionContext,
+    shard: Arc<Shard>,
+    config: Arc<RwLock<Config>>,
+    engine: Arc<ArbitrageEngine>,
+    subscriptions: Arc<SubscriptionTracker>,
+    is_running: Arc<AtomicBool>,
+    down_callbacks: Arc<RwLock<Vec<ExchangeDownCallback>>>,
+    connection_callbacks: Arc<RwLock<Vec<ConnectionCallback>>>,
+    /// Whether this shard's socket is up, so drops are only reported once
+    connected: Arc<AtomicBool>,
+    feed_faults: Option<Arc<FaultInjector>>,
+}
+
+impl ExchangeManager {
+    pub fn new(config: Config, engine: Arc<ArbitrageEngine>, registry: Arc<ExchangeRegistry>) -> Self {
+        let subscriptions = Arc::new(SubscriptionTracker::new(config.subscription_ack_timeout).with_clock(engine.clock()));
+        let faults = &config.fault_injection;
+        let feed_faults = FaultInjector::for_profile("feeds", &faults.feeds, faults.seed).map(Arc::new);
+        #[cfg(not(feature = "chaos"))]
+        if faults.chaos.is_active() {
+            warn!("fault_injection.chaos is set but this build lacks the \"chaos\" feature; ignoring it");
+        }
+        
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            engine,
+            registry,
+            subscriptions,
+            schema_monitor: Arc::new(SchemaMonitor::new(SCHEMA_ALERT_THRESHOLD)),
+            is_running: Arc::new(AtomicBool::new(false)),
+            connections: Mutex::new(Vec::new()),
+            down_callbacks: Arc::new(RwLock::new(Vec::new())),
+            connection_callbacks: Arc::new(RwLock::new(Vec::new())),
+            feed_faults,
+            http: reqwest::Client::new(),
+            runtime: None,
+        }
+    }
+    
+    /// A manager whose connection tasks, including those a config reload
+    /// opens, run on `runtime` rather than the runtime `start` is called from
+    pub fn new_in(config: Config, engine: Arc<ArbitrageEngine>, registry: Arc<ExchangeRegistry>, runtime: Handle) -> Self {
+        Self {
+            runtime: Some(runtime),
+            ..Self::new(config, engine, registry)
+        }
+    }
+    
+    pub async fn start(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.is_running.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        
+        // Refuse configs the connectors can't serve before opening any socket
+        let config = self.config.read().unwrap().clone();
+        let issues = self.registry.config_issues(&config);
+        if !issues.is_empty() {
+            self.is_running.store(false, Ordering::SeqCst);
+            return Err(ConfigError::Invalid(issues).into());
+        }
+        
+        let mut connections = self.connections.lock().unwrap();
+        for exchange in config.exchanges.iter().filter_map(|name| self.registry.build(name, &config)) {
+            connections.extend(self.spawn_connections(exchange, &config.symbols, &config));
+        }
+        
+        info!("Exchange manager started {} connections", connections.len());
+        Ok(())
+    }
+    
+    pub async fn stop(&self) {
+        self.is_running.store(false, Ordering::SeqCst);
+        
+        let connections: Vec<_> = self.connections.lock().unwrap().drain(..).collect();
+        for connection in connections {
+            connection.handle.abort();
+            let _ = connection.handle.await;
+        }
+        
+        info!("Exchange manager stopped");
+    }
+    
+    /// Reconcile the open connections with a reloaded config without dropping
+    /// them: removed exchanges disconnect, new ones connect, and each
+    /// connection subscribes and unsubscribes its share of the symbol changes.
+    /// Rejected (and nothing changes) if a connector can't serve `config`
+    pub fn apply_config(&self, config: Config) -> Result<(), ConfigError> {
+        let issues = self.registry.config_issues(&config);
+        if !issues.is_empty() {
+            return Err(ConfigError::Invalid(issues));
+        }
+        self.subscriptions.set_ack_timeout(config.subscription_ack_timeout);
+        let previous = std::mem::replace(&mut *self.config.write().unwrap(), config.clone());
+        if !self.is_running.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        
+        let mut connections = self.connections.lock().unwrap();
+        let (mut kept, closed): (Vec<Connection>, Vec<Connection>) = connections.drain(..).partition(|connection| {
+            config.exchanges.contains(&connection.exchange)
+                && self.registry.build(&connection.exchange, &config).is_some_and(|e| e.endpoint() == connection.url)
+        });
+        for connection in closed {
+            info!("{}: closing connection removed by config reload", connection.exchange);
+            connection.handle.abort();
+            let symbols = connection.shard.symbols.read().unwrap().clone();
+            self.subscriptions.forget(&connection.exchange, &symbols);
+        }
+        
+        for exchange in config.exchanges.iter().filter_map(|name| self.registry.build(name, &config)) {
+            let name = exchange.name();
+            let shards: Vec<&Connection> = kept.iter().filter(|connection| connection.exchange == name).collect();
+            if shards.is_empty() {
+                info!("{}: connecting for config reload", name);
+                connections.extend(self.spawn_connections(Arc::clone(&exchange), &config.symbols, &config));
+                continue;
+            }
+            
+            let current: Vec<Vec<String>> = shards.iter().map(|c| c.shard.symbols.read().unwrap().clone()).collect();
+            let (resharded, overflow) = capabilities::reshard_symbols(exchange.as_ref(), &current, &config.symbols);
+            for ((connection, before), after) in shards.iter().zip(&current).zip(resharded) {
+                if *before == after {
+                    continue;
+                }
+                let removed: Vec<String> = before.iter().filter(|symbol| !after.contains(symbol)).cloned().collect();
+                {
+                    let mut dropped = connection.shard.dropped.write().unwrap();
+                    dropped.retain(|symbol| !after.contains(symbol));
+                    dropped.extend(removed.iter().cloned());
+                }
+                self.subscriptions.forget(name, &removed);
+                *connection.shard.symbols.write().unwrap() = after;
+                connection.shard.changed.notify_one();
+            }
+            if !overflow.is_empty() {
+                connections.extend(self.spawn_connections(Arc::clone(&exchange), &overflow, &config));
+            }
+        }
+        
+        // A shard whose symbols were all removed has nothing left to stream
+        kept.retain(|connection| {
+            let empty = connection.shard.symbols.read().unwrap().is_empty();
+            if empty {
+                connection.handle.abort();
+            }
+            !empty
+        });
+        // Wake kept connections so they wait on the new timeouts now, not
+        // after their next message
+        if (previous.websocket_timeout, previous.subscription_ack_timeout)
+            != (config.websocket_timeout, config.subscription_ack_timeout)
+        {
+            for connection in &kept {
+                connection.shard.changed.notify_one();
+            }
+        }
+        connections.extend(kept);
+        info!("Config reload applied: {} connections", connections.len());
+        Ok(())
+    }
+    
+    /// One connection per shard when `symbols` exceed the venue's stream limit
+    fn spawn_connections(&self, exchange: Arc<dyn Exchange>, symbols: &[String], config: &Config) -> Vec<Connection> {
+        let shards = capabilities::shard_symbols(exchange.as_ref(), symbols);
+        let budget = SubscriptionBudget::for_symbols(exchange.as_ref(), symbols.len());
+        info!(
+            "{}: {} streams for {} symbols over {} connection(s) (limit {:?} per connection)",
+            budget.exchange, budget.streams_needed, budget.symbols, shards.len(), budget.max_streams_per_connection
+        );
+        #[cfg(feature = "chaos")]
+        let chaos = Chaos::for_exchange(exchange.name(), &config.fault_injection.chaos, config.fault_injection.seed)
+            .map(|chaos| Arc::new(Mutex::new(chaos)));
+        let session = SessionContext {
+            use_testnet: config.use_testnet,
+            depth_levels: config.order_book.enabled.then_some(config.order_book.levels),
+            http: self.http.clone(),
+            schema_monitor: Arc::clone(&self.schema_monitor),
+            budgets: self.engine.budget_tracker(),
+            #[cfg(feature = "chaos")]
+            chaos,
+        };
+        
+        shards
+            .into_iter()
+            .map(|symbols| {
+                let shard = Arc::new(Shard {
+                    symbols: RwLock::new(symbols),
+                    dropped: RwLock::new(HashSet::new()),
+                    resync: Mutex::new(HashSet::new()),
+                    changed: Notify::new(),
+                });
+                let connected = Arc::new(AtomicBool::new(false));
+                let ctx = ConnectionContext {
+                    exchange: Arc::clone(&exchange),
+                    session: session.clone(),
+                    shard: Arc::clone(&shard),
+                    config: Arc::clone(&self.config),
+                    engine: Arc::clone(&self.engine),
+                    subscriptions: Arc::clone(&self.subscriptions),
+                    is_running: Arc::clone(&self.is_running),
+                    down_callbacks: Arc::clone(&self.down_callbacks),
+                    connection_callbacks: Arc::clone(&self.connection_callbacks),
+                    connected: Arc::clone(&connected),
+                    feed_faults: self.feed_faults.clone(),
+                };
+                Connection {
+                    exchange: exchange.name().to_string(),
+                    url: exchange.endpoint().to_string(),
+                    shard,
+                    connected,
+                    handle: match &self.runtime {
+                        Some(runtime) => runtime.spawn(Self::run_connection(ctx)),
+                        None => task::spawn(Self::run_connection(ctx)),
+                    },
+                }
+            })
+            .collect()
+    }
+    
+    pub fn subscriptions(&self) -> Arc<SubscriptionTracker> {
+        Arc::clone(&self.subscriptions)
+    }
+    
+    pub fn schema_monitor(&self) -> Arc<SchemaMonitor> {
+        Arc::clone(&self.schema_monitor)
+    }
+    
+    /// Every connection, one per shard, and whether its socket is up
+    pub fn connection_states(&self) -> Vec<ConnectionState> {
+        self.connections
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|connection| ConnectionState {
+                exchange: connection.exchange.clone(),
+                connected: connection.connected.load(Ordering::SeqCst),
+                symbols: connection.shard.symbols.read().unwrap().clone(),
+            })
+            .collect()
+    }
+    
+    /// What the feed fault injector has done so far, if enabled
+    pub fn feed_fault_stats(&self) -> Option<FaultStats> {
+        self.feed_faults.as_ref().map(|faults| faults.stats())
+    }
+    
+    /// Subscribe to connections that give up reconnecting
+    pub fn register_down_callback(&self, callback: ExchangeDownCallback) {
+        self.down_callbacks.write().unwrap().push(callback);
+    }
+    
+    /// Subscribe to connections dropping and coming back
+    pub fn register_connection_callback(&self, callback: ConnectionCallback) {
+        self.connection_callbacks.write().unwrap().push(callback);
+    }
+    
+    /// Reconnect loop for one exchange
+    async fn run_connection(ctx: ConnectionContext) {
+        let name = ctx.exchange.name();
+        let mut failures = 0;
+        
+        while ctx.is_running.load(Ordering::SeqCst) {
+            let outcome = Self::stream_exchange(&ctx).await;
+            if ctx.connected.swap(false, Ordering::SeqCst) && ctx.is_running.load(Ordering::SeqCst) {
+                Self::notify_connection(&ctx, false);
+            }
+            let (max_attempts, reconnect_interval) = {
+                let config = ctx.config.read().unwrap();
+                (config.max_reconnect_attempts, config.reconnect_interval)
+            };
+            match outcome {
+                Ok(()) => failures = 0,
+                Err(e) => {
+                    failures += 1;
+                    warn!("{} connection failed ({}/{}): {}", name, failures, max_attempts, e);
+                    if failures >= max_attempts {
+                        error!("Giving up on {} after {} attempts", name, failures);
+                        let down = ExchangeDown {
+                            exchange: name.to_string(),
+                            attempts: failures,
+                            error: e.to_string(),
+                        };
+                        for callback in ctx.down_callbacks.read().unwrap().iter() {
+                            callback(down.clone());
+                        }
+                        break;
+                    }
+                }
+            }
+            
+            let symbols = ctx.shard.symbols.read().unwrap().clone();
+            ctx.subscriptions.reset(name, &symbols);
+            // Whatever the books missed while down, the resubscribe snapshots replace
+            ctx.engine.clear_books(name, &symbols);
+            time::sleep(reconnect_interval).await;
+        }
+    }
+    
+    fn notify_connection(ctx: &ConnectionContext, connected: bool) {
+        let change = ConnectionChange {
+            exchange: ctx.exchange.name().to_string(),
+            connected,
+        };
+        for callback in ctx.connection_callbacks.read().unwrap().iter() {
+            callback(change.clone());
+        }
+    }
+    
+    async fn stream_exchange(ctx: &ConnectionContext) -> Result<(), BoxError> {
+        let name = ctx.exchange.name();
+        let mut session = ctx.exchange.connect(&ctx.session).await?;
+        info!("Connected to {}", name);
+        ctx.connected.store(true, Ordering::SeqCst);
+        Self::notify_connection(ctx, true);
+        
+        let mut subscribed = ctx.shard.symbols.read().unwrap().clone();
+        let request_id = ctx.subscriptions.request(name, &subscribed);
+        session.subscribe(&subscribed, request_id).await?;
+        
+        let mut ack_timeout = ctx.config.read().unwrap().subscription_ack_timeout;
+        let mut ack_check = time::interval(ack_timeout);
+        ack_check.tick().await; // First tick fires immediately
+        
+        while ctx.is_running.load(Ordering::SeqCst) {
+            let silence = ctx.config.read().unwrap().websocket_timeout;
+            tokio::select! {
+                events = time::timeout(silence, session.next_events()) => {
+                    let events = match events {
+                        Err(_) => return Err(format!("{} silent for {:?}", name, silence).into()),
+                        Ok(None) => return Ok(()),
+                        Ok(Some(events)) => events?,
+                    };
+                    if let Some(faults) = &ctx.feed_faults {
+                        if !faults.apply().await {
+                            continue;
+                        }
+                    }
+                    Self::handle_events(ctx, events).await;
+                }
+                _ = ack_check.tick() => {
+                    // Only this connection's shard; siblings retry their own
+                    let mut pending = ctx.subscriptions.unconfirmed(name);
+                    pending.retain(|symbol| subscribed.contains(symbol));
+                    if !pending.is_empty() {
+                        warn!("{}: {} subscriptions unconfirmed, re-requesting", name, pending.len());
+                        let request_id = ctx.subscriptions.request(name, &pending);
+                        session.subscribe(&pending, request_id).await?;
+                    }
+                }
+                _ = ctx.shard.changed.notified() => {
+                    Self::apply_shard_change(ctx, session.as_mut(), &mut subscribed).await?;
+                    let reloaded = ctx.config.read().unwrap().subscription_ack_timeout;
+                    if reloaded != ack_timeout {
+                        ack_timeout = reloaded;
+                        ack_check = time::interval_at(time::Instant::now() + ack_timeout, ack_timeout);
+                    }
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Config reload or a book resync: move the session onto the shard's
+    /// symbols, and ask again for the books that lost sync
+    async fn apply_shard_change(
+        ctx: &ConnectionContext,
+        session: &mut (dyn ExchangeSession + '_),
+        subscribed: &mut Vec<String>,
+    ) -> Result<(), BoxError> {
+        let name = ctx.exchange.name();
+        let symbols = ctx.shard.symbols.read().unwrap().clone();
+        let removed: Vec<String> = subscribed.iter().filter(|s| !symbols.contains(s)).cloned().collect();
+        let added: Vec<String> = symbols.iter().filter(|s| !subscribed.contains(s)).cloned().collect();
+        if !removed.is_empty() {
+            session.unsubscribe(&removed).await?;
+        }
+        if !added.is_empty() {
+            let request_id = ctx.subscriptions.request(name, &added);
+            session.subscribe(&added, request_id).await?;
+        }
+        let resync: Vec<String> = ctx.shard.resync.lock().unwrap().drain().filter(|s| symbols.contains(s)).collect();
+        if !resync.is_empty() {
+            session.resync(&resync).await?;
+        }
+        if !added.is_empty() || !removed.is_empty() {
+            info!("{}: subscribed {} and unsubscribed {} symbols on reload", name, added.len(), removed.len());
+        }
+        if !resync.is_empty() {
+            info!("{}: resubscribed depth for {} books out of sync", name, resync.len());
+        }
+        *subscribed = symbols;
+        Ok(())
+    }
+    
+    /// Forward one message's ticks and books to the engine
+    async fn handle_events(ctx: &ConnectionContext, events: Vec<NormalizedEvent>) {
+        let name = ctx.exchange.name();
+        for event in events {
+            ctx.subscriptions.handle_event(name, &event);
+            
+            match event {
+                NormalizedEvent::Tick(tick) => {
+                    if ctx.shard.dropped.read().unwrap().contains(&tick.symbol) {
+                        continue;
+                    }
+                    let volume = tick.bid_size.min(tick.ask_size);
+                    if let Err(e) = ctx.engine.update_price(&tick.exchange, &tick.symbol, tick.bid, tick.ask, volume).await {
+                        error!("{}: failed to forward tick: {}", name, e);
+                    }
+                }
+                NormalizedEvent::Book(update) => {
+                    if ctx.shard.dropped.read().unwrap().contains(&update.symbol) {
+                        continue;
+                    }
+                    if ctx.engine.update_book(&update) == BookApply::OutOfSync
+                        && ctx.shard.resync.lock().unwrap().insert(update.symbol.clone())
+                    {
+                        debug!("{}: {} book out of sync, resubscribing", name, update.symbol);
+                        ctx.shard.changed.notify_one();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
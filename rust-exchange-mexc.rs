@@ -0,0 +1,192 @@
+// exchange/mexc.rs - MEXC spot connector (JSON v3 bookTicker and partial depth streams)
+use serde_json::{json, Value};
+
+use super::connector::{
+    decimal, field, list, parse_frame, split_concatenated, CanonicalTick, ExchangeConnector, NormalizeError,
+    NormalizedEvent, OrderBookUpdate,
+};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::schema::MessageSchema;
+
+const WS_URL: &str = "wss://wbs.mexc.com/ws";
+const INSTRUMENTS_URL: &str = "https://api.mexc.com/api/v3/exchangeInfo";
+const QUOTE_ASSETS: &[&str] = &["USDT", "USDC", "USDE", "BTC", "ETH", "EUR", "TRY"];
+const CHANNEL_PREFIX: &str = "spot@public.bookTicker.v3.api@";
+const DEPTH_CHANNEL_PREFIX: &str = "spot@public.limit.depth.v3.api@";
+/// Partial book depths MEXC streams; each frame is a whole top-N book
+const DEPTH_LEVELS: &[usize] = &[5, 10, 20];
+
+static BOOK_TICKER_SCHEMA: MessageSchema = MessageSchema {
+    required: &["a", "A", "b", "B"],
+    optional: &[],
+    payload: book_ticker_payload,
+};
+
+fn book_ticker_payload(frame: &Value) -> Option<&Value> {
+    // Replies to requests ({"id":..,"code":..,"msg":..}) carry no market data
+    let channel = frame.get("c")?.as_str()?;
+    channel.starts_with(CHANNEL_PREFIX).then(|| frame.get("d"))?
+}
+
+/// Depth levels: [{"p": "price", "v": "quantity"}, ...]
+fn depth_levels(data: &Value, side: &'static str) -> Result<Vec<(f64, f64)>, NormalizeError> {
+    list(data, side)?
+        .iter()
+        .map(|level| Ok((decimal(field(level, "p")?, side)?, decimal(field(level, "v")?, side)?)))
+        .collect()
+}
+
+pub struct MexcConnector;
+
+impl MexcConnector {
+    pub fn new() -> Self {
+        Self
+    }
+    
+    fn channels(&self, symbols: &[String]) -> Vec<String> {
+        symbols
+            .iter()
+            .map(|s| format!("{}{}", CHANNEL_PREFIX, self.to_exchange_symbol(s)))
+            .collect()
+    }
+    
+    fn depth_channels(&self, symbols: &[String], levels: usize) -> Vec<String> {
+        let depth = DEPTH_LEVELS.iter().copied().find(|&depth| depth >= levels).unwrap_or(20);
+        symbols
+            .iter()
+            .map(|s| format!("{}{}@{}", DEPTH_CHANNEL_PREFIX, self.to_exchange_symbol(s), depth))
+            .collect()
+    }
+    
+    /// Internal symbols named by the channels in a reply's `msg`
+    fn reply_symbols(&self, msg: &str) -> Vec<String> {
+        msg.split(|c: char| c == ',' || c == '[' || c == ']' || c.is_whitespace())
+            .filter_map(|channel| {
+                channel.strip_prefix(CHANNEL_PREFIX).or_else(|| {
+                    let (raw, _depth) = channel.strip_prefix(DEPTH_CHANNEL_PREFIX)?.split_once('@')?;
+                    Some(raw)
+                })
+            })
+            .filter_map(|raw| self.from_exchange_symbol(raw))
+            .collect()
+    }
+}
+
+impl ExchangeConnector for MexcConnector {
+    fn name(&self) -> &'static str {
+        "mexc"
+    }
+    
+    fn ws_url(&self) -> &str {
+        WS_URL
+    }
+    
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        symbol.replace('/', "").to_uppercase()
+    }
+    
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        split_concatenated(raw, QUOTE_ASSETS)
+    }
+    
+    fn subscribe_messages(&self, symbols: &[String], request_id: u64) -> Vec<String> {
+        vec![json!({ "method": "SUBSCRIPTION", "params": self.channels(symbols), "id": request_id }).to_string()]
+    }
+    
+    fn unsubscribe_messages(&self, symbols: &[String]) -> Vec<String> {
+        // Id 0 is never a subscription request, so the reply confirms nothing
+        vec![json!({ "method": "UNSUBSCRIPTION", "params": self.channels(symbols), "id": 0 }).to_string()]
+    }
+    
+    fn depth_subscribe_messages(&self, symbols: &[String], levels: usize, request_id: u64) -> Vec<String> {
+        vec![json!({ "method": "SUBSCRIPTION", "params": self.depth_channels(symbols, levels), "id": request_id }).to_string()]
+    }
+    
+    fn depth_unsubscribe_messages(&self, symbols: &[String], levels: usize) -> Vec<String> {
+        vec![json!({ "method": "UNSUBSCRIPTION", "params": self.depth_channels(symbols, levels), "id": 0 }).to_string()]
+    }
+    
+    fn instruments_url(&self) -> Option<&str> {
+        Some(INSTRUMENTS_URL)
+    }
+    
+    fn parse_instruments(&self, body: &str) -> Result<Vec<String>, NormalizeError> {
+        let info = parse_frame(body)?;
+        Ok(list(&info, "symbols")?
+            .iter()
+            .filter(|market| market["isSpotTradingAllowed"] == true)
+            .filter_map(|market| Some(format!("{}/{}", market["baseAsset"].as_str()?, market["quoteAsset"].as_str()?)))
+            .collect())
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: true,
+            trading: false,
+            testnet_ws_url: None,
+            max_streams_per_connection: Some(30),
+            streams_per_symbol: 1,
+            max_messages_per_second: Some(100),
+            symbol_format: SymbolFormat::Concatenated,
+        }
+    }
+    
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        Some(&BOOK_TICKER_SCHEMA)
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        
+        // Replies: {"id":1,"code":0,"msg":"spot@public.bookTicker.v3.api@BTCUSDT"}.
+        // A refused channel still has code 0, but its msg says so
+        if let Some(msg) = frame.get("msg").and_then(Value::as_str) {
+            if msg == "PONG" {
+                return Ok(vec![NormalizedEvent::Control("pong".to_string())]);
+            }
+            let refused = msg.starts_with("Not Subscribed");
+            if refused || frame.get("code").and_then(Value::as_i64).is_some_and(|code| code != 0) {
+                return Ok(vec![NormalizedEvent::SubscriptionRejected {
+                    symbols: self.reply_symbols(msg),
+                    reason: msg.to_string(),
+                }]);
+            }
+            return Ok(vec![NormalizedEvent::SubscriptionAck {
+                request_id: frame.get("id").and_then(Value::as_u64).filter(|&id| id != 0),
+                symbols: self.reply_symbols(msg),
+            }]);
+        }
+        
+        // Stream data: {"c": channel, "d": {"a","A","b","B"}, "s": "BTCUSDT", "t": ms}
+        let data = field(&frame, "d")?;
+        let raw_symbol = field(&frame, "s")?
+            .as_str()
+            .ok_or(NormalizeError::MissingField("s"))?;
+        let symbol = self
+            .from_exchange_symbol(raw_symbol)
+            .ok_or_else(|| NormalizeError::UnknownSymbol(raw_symbol.to_string()))?;
+        
+        // Partial depth: {"c": "spot@public.limit.depth.v3.api@BTCUSDT@20", "d": {"bids", "asks", "r": version}, ..}
+        let channel = frame.get("c").and_then(Value::as_str).unwrap_or_default();
+        if channel.starts_with(DEPTH_CHANNEL_PREFIX) {
+            return Ok(vec![NormalizedEvent::Book(OrderBookUpdate {
+                exchange: self.name().to_string(),
+                symbol,
+                snapshot: true,
+                bids: depth_levels(data, "bids")?,
+                asks: depth_levels(data, "asks")?,
+                sequence: data.get("r").and_then(Value::as_str).and_then(|version| version.parse().ok()),
+                depth: None,
+            })]);
+        }
+        
+        Ok(vec![NormalizedEvent::Tick(CanonicalTick {
+            exchange: self.name().to_string(),
+            symbol,
+            bid: decimal(field(data, "b")?, "b")?,
+            bid_size: decimal(field(data, "B")?, "B")?,
+            ask: decimal(field(data, "a")?, "a")?,
+            ask_size: decimal(field(data, "A")?, "A")?,
+        })])
+    }
+}
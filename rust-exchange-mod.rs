@@ -0,0 +1,36 @@
+// exchange/mod.rs - Exchange connectivity and message normalization
+mod manager;
+mod websocket;
+pub mod connector;
+pub mod binance;
+pub mod bitfinex;
+pub mod bitstamp;
+pub mod capabilities;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+pub mod coinbase;
+// Connector test kit; this crate's own tests always build it
+#[cfg(any(test, feature = "conformance"))]
+pub mod conformance;
+pub mod cryptocom;
+pub mod curve;
+pub mod deribit;
+pub mod discovery;
+pub mod dydx;
+pub mod funding;
+pub mod gateio;
+pub mod gemini;
+pub mod htx;
+pub mod kraken;
+pub mod kucoin;
+pub mod mexc;
+pub mod registry;
+pub mod schema;
+pub mod subscriptions;
+pub mod tardis;
+pub mod upbit;
+
+pub use capabilities::{Capabilities, SymbolFormat};
+pub use connector::{CanonicalTick, Endpoint, ExchangeConnector, NormalizeError, NormalizedEvent, OrderBookUpdate};
+pub use manager::{ConnectionChange, ExchangeDown, ExchangeManager};
+pub use registry::{Exchange, ExchangeRegistry, ExchangeSession, SessionContext};
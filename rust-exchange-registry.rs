@@ -0,0 +1,354 @@
+// exchange/registry.rs - Exchange trait and the registry of exchanges by name
+use std::collections::BTreeMap;
+#[cfg(feature = "chaos")]
+use std::sync::Mutex;
+use std::sync::Arc;
+use futures_util::future::BoxFuture;
+
+use crate::arbitrage::budget::BudgetTracker;
+use crate::arbitrage::Config;
+use crate::config::ConfigIssue;
+use crate::markets::Fees;
+use super::binance::BinanceConnector;
+use super::bitfinex::BitfinexConnector;
+use super::bitstamp::BitstampConnector;
+use super::capabilities::{self, Capabilities};
+#[cfg(feature = "chaos")]
+use super::chaos::Chaos;
+use super::coinbase::CoinbaseConnector;
+use super::connector::{ExchangeConnector, NormalizedEvent};
+use super::cryptocom::CryptocomConnector;
+use super::curve::CurveConnector;
+use super::deribit::DeribitConnector;
+use super::dydx::DydxConnector;
+use super::gateio::GateioConnector;
+use super::gemini::GeminiConnector;
+use super::htx::HtxConnector;
+use super::kraken::KrakenConnector;
+use super::kucoin::KucoinConnector;
+use super::mexc::MexcConnector;
+use super::schema::SchemaMonitor;
+use super::tardis::TardisConnector;
+use super::upbit::UpbitConnector;
+
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A market data venue the manager can run. Every `ExchangeConnector` is one,
+/// streaming over WebSocket; implement this directly for venues with another
+/// transport and make them available with `ExchangeRegistry::register`
+pub trait Exchange: Send + Sync {
+    /// Name as used in `Config.exchanges`
+    fn name(&self) -> &str;
+    
+    /// Where sessions connect; a config reload that changes it reconnects
+    fn endpoint(&self) -> &str;
+    
+    /// Declared feature set, checked against Config at startup
+    fn capabilities(&self) -> Capabilities;
+    
+    /// Maker and taker fees, used when `Config.fees` doesn't list this exchange
+    fn fees(&self) -> Option<Fees> {
+        None
+    }
+    
+    /// Tradable `BASE/QUOTE` symbols the venue lists, for symbol discovery;
+    /// `None` if it can't list them
+    fn list_symbols<'a>(&'a self, _client: &'a reqwest::Client) -> BoxFuture<'a, Option<Result<Vec<String>, BoxError>>> {
+        Box::pin(async { None })
+    }
+    
+    /// Config settings this exchange can't serve, beyond its capabilities
+    fn config_issues(&self, _config: &Config) -> Vec<ConfigIssue> {
+        Vec::new()
+    }
+    
+    /// Open one market data session; the manager subscribes it and reads
+    /// events until it ends, then reconnects
+    fn connect<'a>(&'a self, context: &'a SessionContext) -> BoxFuture<'a, Result<Box<dyn ExchangeSession + 'a>, BoxError>>;
+    
+    /// This exchange as an `ExchangeConnector`, if it is one
+    fn connector(self: Arc<Self>) -> Option<Arc<dyn ExchangeConnector>> {
+        None
+    }
+}
+
+/// One open connection to an exchange
+pub trait ExchangeSession: Send {
+    /// Subscribe `symbols`, with depth when `SessionContext.depth_levels` asks
+    /// for it; venues that echo a request id in acks get `request_id`
+    fn subscribe<'a>(&'a mut self, symbols: &'a [String], request_id: u64) -> BoxFuture<'a, Result<(), BoxError>>;
+    
+    /// Stop streaming `symbols`; by default they keep streaming and the
+    /// manager discards their ticks
+    fn unsubscribe<'a>(&'a mut self, _symbols: &'a [String]) -> BoxFuture<'a, Result<(), BoxError>> {
+        Box::pin(async { Ok(()) })
+    }
+    
+    /// Ask again for the books of `symbols`, which fell out of sync, so the
+    /// venue sends fresh snapshots; by default they stay dropped until the
+    /// next reconnect
+    fn resync<'a>(&'a mut self, _symbols: &'a [String]) -> BoxFuture<'a, Result<(), BoxError>> {
+        Box::pin(async { Ok(()) })
+    }
+    
+    /// Events from the next message, `None` once the venue ends the session.
+    /// Must be cancel safe: the manager stops waiting to resubscribe
+    fn next_events(&mut self) -> BoxFuture<'_, Option<Result<Vec<NormalizedEvent>, BoxError>>>;
+}
+
+/// What the manager hands an exchange when it opens a session
+#[derive(Clone)]
+pub struct SessionContext {
+    /// Connect to the venue's sandbox instead of production
+    pub use_testnet: bool,
+    /// Levels of L2 depth to subscribe alongside top of book, if any
+    pub depth_levels: Option<usize>,
+    /// For REST calls a venue needs before connecting
+    pub http: reqwest::Client,
+    pub(crate) schema_monitor: Arc<SchemaMonitor>,
+    pub(crate) budgets: Arc<BudgetTracker>,
+    #[cfg(feature = "chaos")]
+    pub(crate) chaos: Option<Arc<Mutex<Chaos>>>,
+}
+
+pub type ExchangeFactory = Box<dyn Fn(&Config, &ExchangeRegistry) -> Arc<dyn Exchange> + Send + Sync>;
+
+/// The exchanges `Config.exchanges` can name. Seeded with the connectors this
+/// crate ships; whoever builds the manager registers their own and passes it in
+pub struct ExchangeRegistry {
+    factories: BTreeMap<String, ExchangeFactory>,
+}
+
+impl ExchangeRegistry {
+    /// The connectors this crate ships; "tardis" aggregates the venues in
+    /// `Config.tardis` over one tardis-machine connection, "deribit" adds the
+    /// derivatives in `Config.deribit` and "curve" quotes `Config.curve`'s pools
+    pub fn builtin() -> Self {
+        let factories: [(&str, ExchangeFactory); 16] = [
+            ("binance", Box::new(|_, _| Arc::new(BinanceConnector::new()))),
+            ("bitfinex", Box::new(|_, _| Arc::new(BitfinexConnector::new()))),
+            ("bitstamp", Box::new(|_, _| Arc::new(BitstampConnector::new()))),
+            ("coinbase", Box::new(|_, _| Arc::new(CoinbaseConnector::new()))),
+            ("cryptocom", Box::new(|_, _| Arc::new(CryptocomConnector::new()))),
+            ("curve", Box::new(|config, _| Arc::new(CurveConnector::new(&config.curve, &config.symbols)))),
+            ("deribit", Box::new(|config, _| Arc::new(DeribitConnector::new(&config.deribit)))),
+            ("dydx", Box::new(|_, _| Arc::new(DydxConnector::new()))),
+            ("gateio", Box::new(|_, _| Arc::new(GateioConnector::new()))),
+            ("gemini", Box::new(|_, _| Arc::new(GeminiConnector::new()))),
+            ("htx", Box::new(|_, _| Arc::new(HtxConnector::new()))),
+            ("kraken", Box::new(|_, _| Arc::new(KrakenConnector::new()))),
+            ("kucoin", Box::new(|_, _| Arc::new(KucoinConnector::new()))),
+            ("mexc", Box::new(|_, _| Arc::new(MexcConnector::new()))),
+            ("tardis", Box::new(|config, registry| Arc::new(TardisConnector::new(&config.tardis, &config.symbols, registry)))),
+            ("upbit", Box::new(|_, _| Arc::new(UpbitConnector::new()))),
+        ];
+        let factories = factories.into_iter().map(|(name, factory)| (name.to_string(), factory)).collect();
+        Self { factories }
+    }
+    
+    /// Make `name` usable in `Config.exchanges`, replacing any exchange of
+    /// that name. `factory` runs whenever the config is checked or applied,
+    /// so it should only construct; the registry it gets is for looking up
+    /// other exchanges
+    pub fn register<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&Config, &ExchangeRegistry) -> Arc<dyn Exchange> + Send + Sync + 'static,
+    {
+        self.factories.insert(name.to_string(), Box::new(factory));
+    }
+    
+    /// Every registered name, sorted
+    pub fn names(&self) -> Vec<String> {
+        self.factories.keys().cloned().collect()
+    }
+    
+    /// The exchange registered as `name`, set up for `config`
+    pub fn build(&self, name: &str, config: &Config) -> Option<Arc<dyn Exchange>> {
+        self.factories.get(name).map(|factory| factory(config, self))
+    }
+    
+    /// Connector implementing `name`'s own protocol, for tardis venues and
+    /// recording imports; only its symbol spellings are used, so it is built
+    /// for the default config
+    pub fn connector(&self, name: &str) -> Option<Arc<dyn ExchangeConnector>> {
+        self.build(name, &Config::default())?.connector()
+    }
+    
+    /// Unregistered exchanges, and Config settings an exchange's capability
+    /// manifest or own checks rule out
+    pub fn config_issues(&self, config: &Config) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        for name in &config.exchanges {
+            match self.build(name, config) {
+                Some(exchange) => issues.extend(capabilities::check_config(exchange.as_ref(), config)),
+                None => issues.push(ConfigIssue::new(
+                    "exchanges",
+                    format!("{:?} is not a registered exchange (registered: {})", name, self.names().join(", ")),
+                )),
+            }
+        }
+        issues
+    }
+    
+    /// `config` with the fees of configured exchanges that declare them and
+    /// aren't in `Config.fees`
+    pub fn with_exchange_fees(&self, mut config: Config) -> Config {
+        for name in config.exchanges.clone() {
+            if config.fees.contains_key(&name) {
+                continue;
+            }
+            if let Some(fees) = self.build(&name, &config).and_then(|exchange| exchange.fees()) {
+                config.fees.insert(name, fees);
+            }
+        }
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use crate::arbitrage::ArbitrageEngine;
+    use crate::exchange::capabilities::SymbolFormat;
+    use crate::exchange::subscriptions::SubscriptionState;
+    use crate::exchange::ExchangeManager;
+    
+    /// An exchange outside this crate: acks what it's asked for, then goes quiet
+    struct PaperExchange {
+        subscribed: Arc<Mutex<Vec<String>>>,
+    }
+    
+    struct PaperSession {
+        subscribed: Arc<Mutex<Vec<String>>>,
+        acks: Vec<NormalizedEvent>,
+    }
+    
+    impl Exchange for PaperExchange {
+        fn name(&self) -> &str {
+            "paper"
+        }
+        
+        fn endpoint(&self) -> &str {
+            "paper://local"
+        }
+        
+        fn capabilities(&self) -> Capabilities {
+            Capabilities {
+                depth: false,
+                trading: false,
+                testnet_ws_url: None,
+                max_streams_per_connection: None,
+                streams_per_symbol: 1,
+                max_messages_per_second: None,
+                symbol_format: SymbolFormat::Slashed,
+            }
+        }
+        
+        fn fees(&self) -> Option<Fees> {
+            Some(Fees { maker: 0.0, taker: 0.002, charged_in: None })
+        }
+        
+        fn connect<'a>(&'a self, _context: &'a SessionContext) -> BoxFuture<'a, Result<Box<dyn ExchangeSession + 'a>, BoxError>> {
+            let session = PaperSession {
+                subscribed: Arc::clone(&self.subscribed),
+                acks: Vec::new(),
+            };
+            Box::pin(async move { Ok(Box::new(session) as Box<dyn ExchangeSession>) })
+        }
+    }
+    
+    impl ExchangeSession for PaperSession {
+        fn subscribe<'a>(&'a mut self, symbols: &'a [String], request_id: u64) -> BoxFuture<'a, Result<(), BoxError>> {
+            self.subscribed.lock().unwrap().extend(symbols.iter().cloned());
+            self.acks.push(NormalizedEvent::SubscriptionAck { request_id: Some(request_id), symbols: Vec::new() });
+            Box::pin(async { Ok(()) })
+        }
+        
+        fn next_events(&mut self) -> BoxFuture<'_, Option<Result<Vec<NormalizedEvent>, BoxError>>> {
+            let acks = std::mem::take(&mut self.acks);
+            Box::pin(async move {
+                if acks.is_empty() {
+                    std::future::pending::<()>().await;
+                }
+                Some(Ok(acks))
+            })
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_registered_exchange_runs_in_the_manager() {
+        let subscribed = Arc::new(Mutex::new(Vec::new()));
+        let shared = Arc::clone(&subscribed);
+        let mut registry = ExchangeRegistry::builtin();
+        registry.register("paper", move |_, _| Arc::new(PaperExchange { subscribed: Arc::clone(&shared) }));
+        assert!(registry.names().contains(&"paper".to_string()));
+        
+        let config = Config {
+            exchanges: vec!["paper".to_string()],
+            symbols: vec!["BTC/USDT".to_string()],
+            ..Config::default()
+        };
+        assert!(registry.config_issues(&config).is_empty());
+        assert_eq!(registry.with_exchange_fees(config.clone()).fees["paper"].taker, 0.002);
+        assert!(ExchangeRegistry::builtin().config_issues(&config)[0].problem.contains("not a registered exchange"));
+        
+        let manager = ExchangeManager::new(config.clone(), Arc::new(ArbitrageEngine::new(config)), Arc::new(registry));
+        manager.start().await.unwrap();
+        let confirmed = || {
+            manager
+                .subscriptions()
+                .snapshot("paper")
+                .is_some_and(|statuses| statuses.iter().all(|status| status.state == SubscriptionState::Confirmed))
+        };
+        for _ in 0..100 {
+            if confirmed() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(confirmed());
+        assert_eq!(*subscribed.lock().unwrap(), vec!["BTC/USDT"]);
+        manager.stop().await;
+    }
+    
+    #[tokio::test]
+    async fn test_reloaded_websocket_timeout_reaches_kept_connections() {
+        let mut registry = ExchangeRegistry::builtin();
+        registry.register("paper", |_, _| Arc::new(PaperExchange { subscribed: Arc::default() }));
+        let config = Config {
+            exchanges: vec!["paper".to_string()],
+            symbols: vec!["BTC/USDT".to_string()],
+            websocket_timeout: Duration::from_secs(60),
+            reconnect_interval: Duration::from_millis(10),
+            ..Config::default()
+        };
+        let manager = ExchangeManager::new(config.clone(), Arc::new(ArbitrageEngine::new(config.clone())), Arc::new(registry));
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&changes);
+        manager.register_connection_callback(Box::new(move |change| seen.lock().unwrap().push(change.connected)));
+        manager.start().await.unwrap();
+        
+        let connected = || manager.connection_states().iter().all(|state| state.connected);
+        for _ in 0..100 {
+            if connected() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(connected());
+        
+        // The paper venue is silent after its ack; the old timeout would wait a minute
+        manager.apply_config(Config { websocket_timeout: Duration::from_millis(50), ..config }).unwrap();
+        for _ in 0..100 {
+            if changes.lock().unwrap().contains(&false) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(changes.lock().unwrap()[..2], [true, false], "the kept connection timed out on the new value");
+        assert_eq!(manager.connection_states().len(), 1);
+        manager.stop().await;
+    }
+}
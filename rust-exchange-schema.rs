@@ -0,0 +1,253 @@
+// exchange/schema.rs - Protocol drift detection for exchange payloads
+use std::collections::{HashMap, HashSet};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Mutex, RwLock};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{error, warn};
+
+use super::connector::{ExchangeConnector, NormalizeError, NormalizedEvent};
+
+/// Expected layout of a connector's market data payload
+pub struct MessageSchema {
+    /// Fields every data payload must carry
+    pub required: &'static [&'static str],
+    /// Fields we know about and deliberately ignore
+    pub optional: &'static [&'static str],
+    /// Extract the data payload from a frame; `None` for control frames
+    pub payload: fn(&Value) -> Option<&Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "field", rename_all = "snake_case")]
+pub enum DriftKind {
+    UnexpectedField(String),
+    MissingField(String),
+    UnparseableMessages,
+}
+
+/// Operational alert raised when an exchange appears to have changed its protocol
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaDriftAlert {
+    pub exchange: String,
+    pub drift: DriftKind,
+    pub occurrences: u64,
+    pub sample: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SchemaReport {
+    pub exchange: String,
+    pub messages: u64,
+    pub failures: u64,
+    pub unexpected_fields: HashMap<String, u64>,
+    pub missing_fields: HashMap<String, u64>,
+}
+
+pub type SchemaAlertCallback = Box<dyn Fn(SchemaDriftAlert) + Send + Sync>;
+
+#[derive(Default)]
+struct ExchangeSchemaState {
+    report: SchemaReport,
+    alerted: HashSet<String>,
+}
+
+/// Counts unknown/missing payload fields and normalization failures per
+/// exchange, raising one alert per drift kind once it crosses the threshold
+pub struct SchemaMonitor {
+    alert_threshold: u64,
+    state: Mutex<HashMap<String, ExchangeSchemaState>>,
+    callbacks: RwLock<Vec<SchemaAlertCallback>>,
+}
+
+impl SchemaMonitor {
+    pub fn new(alert_threshold: u64) -> Self {
+        Self {
+            alert_threshold: alert_threshold.max(1),
+            state: Mutex::new(HashMap::new()),
+            callbacks: RwLock::new(Vec::new()),
+        }
+    }
+    
+    pub fn register_callback(&self, callback: SchemaAlertCallback) {
+        self.callbacks.write().unwrap().push(callback);
+    }
+    
+    /// Inspect one raw frame and its normalization outcome; returns any newly raised alerts
+    pub fn inspect(
+        &self,
+        connector: &dyn ExchangeConnector,
+        raw: &str,
+        outcome: &Result<Vec<NormalizedEvent>, NormalizeError>,
+    ) -> Vec<SchemaDriftAlert> {
+        let mut drifts = Vec::new();
+        
+        if outcome.is_err() {
+            drifts.push(DriftKind::UnparseableMessages);
+        }
+        
+        if let (Some(schema), Ok(frame)) = (connector.schema(), serde_json::from_str::<Value>(raw)) {
+            if let Some(payload) = (schema.payload)(&frame).and_then(Value::as_object) {
+                for key in payload.keys() {
+                    if !schema.required.contains(&key.as_str()) && !schema.optional.contains(&key.as_str()) {
+                        drifts.push(DriftKind::UnexpectedField(key.clone()));
+                    }
+                }
+                for &required in schema.required {
+                    if !payload.contains_key(required) {
+                        drifts.push(DriftKind::MissingField(required.to_string()));
+                    }
+                }
+            }
+        }
+        
+        let alerts = self.record(connector.name(), raw, drifts);
+        
+        if !alerts.is_empty() {
+            // A panicking callback must not take the connection's read loop with it
+            let callbacks = self.callbacks.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+            for alert in &alerts {
+                warn!("Possible {} protocol change: {:?} x{}", alert.exchange, alert.drift, alert.occurrences);
+                for (index, callback) in callbacks.iter().enumerate() {
+                    if panic::catch_unwind(AssertUnwindSafe(|| callback(alert.clone()))).is_err() {
+                        error!("Schema alert callback #{} panicked", index);
+                    }
+                }
+            }
+        }
+        
+        alerts
+    }
+    
+    pub fn reports(&self) -> Vec<SchemaReport> {
+        let state = self.state.lock().unwrap();
+        let mut reports: Vec<SchemaReport> = state.values().map(|s| s.report.clone()).collect();
+        reports.sort_by(|a, b| a.exchange.cmp(&b.exchange));
+        reports
+    }
+    
+    fn record(&self, exchange: &str, raw: &str, drifts: Vec<DriftKind>) -> Vec<SchemaDriftAlert> {
+        let mut state = self.state.lock().unwrap();
+        let entry = state.entry(exchange.to_string()).or_insert_with(|| ExchangeSchemaState {
+            report: SchemaReport {
+                exchange: exchange.to_string(),
+                ..SchemaReport::default()
+            },
+            alerted: HashSet::new(),
+        });
+        
+        entry.report.messages += 1;
+        let mut alerts = Vec::new();
+        
+        for drift in drifts {
+            let (key, occurrences) = match &drift {
+                DriftKind::UnparseableMessages => {
+                    entry.report.failures += 1;
+                    ("unparseable".to_string(), entry.report.failures)
+                }
+                DriftKind::UnexpectedField(name) => {
+                    let count = entry.report.unexpected_fields.entry(name.clone()).or_insert(0);
+                    *count += 1;
+                    (format!("unexpected:{}", name), *count)
+                }
+                DriftKind::MissingField(name) => {
+                    let count = entry.report.missing_fields.entry(name.clone()).or_insert(0);
+                    *count += 1;
+                    (format!("missing:{}", name), *count)
+                }
+            };
+            
+            if occurrences >= self.alert_threshold && entry.alerted.insert(key) {
+                alerts.push(SchemaDriftAlert {
+                    exchange: exchange.to_string(),
+                    drift,
+                    occurrences,
+                    sample: raw.chars().take(512).collect(),
+                });
+            }
+        }
+        
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::binance::BinanceConnector;
+    use super::super::kraken::KrakenConnector;
+    
+    const BOOK_TICKER: &str = r#"{"stream":"btcusdt@bookTicker","data":{"u":1,"s":"BTCUSDT","b":"50000","B":"1","a":"50001","A":"2"}}"#;
+    const BOOK_TICKER_EXTRA: &str = r#"{"stream":"btcusdt@bookTicker","data":{"u":1,"s":"BTCUSDT","b":"50000","B":"1","a":"50001","A":"2","X":"NEW"}}"#;
+    
+    #[test]
+    fn test_clean_messages_raise_nothing() {
+        let monitor = SchemaMonitor::new(1);
+        let connector = BinanceConnector::new();
+        
+        for raw in [BOOK_TICKER, r#"{"result":null,"id":1}"#] {
+            let outcome = connector.normalize(raw);
+            assert!(monitor.inspect(&connector, raw, &outcome).is_empty());
+        }
+        
+        let report = &monitor.reports()[0];
+        assert_eq!(report.messages, 2);
+        assert!(report.unexpected_fields.is_empty() && report.missing_fields.is_empty());
+    }
+    
+    #[test]
+    fn test_unexpected_field_alerts_once_after_threshold() {
+        let monitor = SchemaMonitor::new(3);
+        let connector = BinanceConnector::new();
+        let raised = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = raised.clone();
+        monitor.register_callback(Box::new(move |_| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }));
+        
+        let mut alerts = Vec::new();
+        for _ in 0..5 {
+            let outcome = connector.normalize(BOOK_TICKER_EXTRA);
+            alerts.extend(monitor.inspect(&connector, BOOK_TICKER_EXTRA, &outcome));
+        }
+        
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].drift, DriftKind::UnexpectedField("X".to_string()));
+        assert_eq!(alerts[0].occurrences, 3);
+        assert_eq!(raised.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+    
+    #[test]
+    fn test_panicking_callback_does_not_stop_the_others() {
+        let monitor = SchemaMonitor::new(1);
+        let connector = BinanceConnector::new();
+        let raised = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = raised.clone();
+        monitor.register_callback(Box::new(|_| panic!("alert sink bug")));
+        monitor.register_callback(Box::new(move |_| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }));
+        
+        let outcome = connector.normalize(BOOK_TICKER_EXTRA);
+        assert_eq!(monitor.inspect(&connector, BOOK_TICKER_EXTRA, &outcome).len(), 1);
+        assert_eq!(raised.load(std::sync::atomic::Ordering::SeqCst), 1);
+        
+        // Still usable afterwards
+        monitor.register_callback(Box::new(|_| {}));
+        assert_eq!(monitor.callbacks.read().unwrap().len(), 3);
+    }
+    
+    #[test]
+    fn test_missing_fields_and_failures_are_counted() {
+        let monitor = SchemaMonitor::new(1);
+        let connector = KrakenConnector::new();
+        let raw = r#"[340,{"a":["50001.0",2,"2.0"],"c":["50000.5","0.01"]},"ticker","XBT/USDT"]"#;
+        
+        let outcome = connector.normalize(raw);
+        let alerts = monitor.inspect(&connector, raw, &outcome);
+        
+        assert!(alerts.iter().any(|a| a.drift == DriftKind::MissingField("b".to_string())));
+        assert!(alerts.iter().any(|a| a.drift == DriftKind::UnparseableMessages));
+        assert_eq!(monitor.reports()[0].failures, 1);
+    }
+}
@@ -0,0 +1,317 @@
+// exchange/subscriptions.rs - Requested vs confirmed subscription tracking
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+
+use crate::clock::{self, SharedClock};
+use super::connector::NormalizedEvent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionState {
+    /// Not subscribed on the current connection (e.g. after a reconnect)
+    Inactive,
+    Requested,
+    Confirmed,
+    Rejected,
+}
+
+/// Per-symbol subscription state as exposed by the dashboard API
+#[derive(Debug, Clone, Serialize)]
+pub struct SubscriptionStatus {
+    pub symbol: String,
+    pub state: SubscriptionState,
+    pub attempts: u32,
+    pub last_request_ms_ago: Option<u64>,
+    pub confirmed_ms_ago: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+struct Entry {
+    state: SubscriptionState,
+    attempts: u32,
+    last_requested: Option<Instant>,
+    confirmed_at: Option<Instant>,
+    last_error: Option<String>,
+}
+
+#[derive(Default)]
+struct ExchangeSubscriptions {
+    symbols: HashMap<String, Entry>,
+    requests: HashMap<u64, Vec<String>>,
+    next_request_id: u64,
+}
+
+/// Tracks which symbols each connector asked for and which the venue has
+/// actually confirmed, so silent subscription failures become visible and
+/// can be retried
+pub struct SubscriptionTracker {
+    ack_timeout: RwLock<Duration>,
+    exchanges: RwLock<HashMap<String, ExchangeSubscriptions>>,
+    clock: SharedClock,
+}
+
+impl SubscriptionTracker {
+    pub fn new(ack_timeout: Duration) -> Self {
+        Self {
+            ack_timeout: RwLock::new(ack_timeout),
+            exchanges: RwLock::new(HashMap::new()),
+            clock: clock::system(),
+        }
+    }
+    
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// A reloaded ack timeout, applied from the next `unconfirmed` check
+    pub fn set_ack_timeout(&self, ack_timeout: Duration) {
+        *self.ack_timeout.write().unwrap() = ack_timeout;
+    }
+    
+    /// Record a subscription request and return the request id to send with it
+    pub fn request(&self, exchange: &str, symbols: &[String]) -> u64 {
+        let mut exchanges = self.exchanges.write().unwrap();
+        let subs = exchanges.entry(exchange.to_string()).or_default();
+        subs.next_request_id += 1;
+        let request_id = subs.next_request_id;
+        let now = self.clock.now();
+        
+        for symbol in symbols {
+            let entry = subs.symbols.entry(symbol.clone()).or_insert(Entry {
+                state: SubscriptionState::Inactive,
+                attempts: 0,
+                last_requested: None,
+                confirmed_at: None,
+                last_error: None,
+            });
+            entry.state = SubscriptionState::Requested;
+            entry.attempts += 1;
+            entry.last_requested = Some(now);
+        }
+        subs.requests.insert(request_id, symbols.to_vec());
+        
+        request_id
+    }
+    
+    /// Apply a normalized event; market data for a symbol also counts as confirmation
+    pub fn handle_event(&self, exchange: &str, event: &NormalizedEvent) {
+        let (symbols, outcome) = {
+            let exchanges = self.exchanges.read().unwrap();
+            let Some(subs) = exchanges.get(exchange) else {
+                return;
+            };
+            
+            match event {
+                NormalizedEvent::SubscriptionAck { request_id, symbols } => {
+                    let mut confirmed = symbols.clone();
+                    if let Some(requested) = request_id.and_then(|id| subs.requests.get(&id)) {
+                        confirmed.extend(requested.iter().cloned());
+                    }
+                    (confirmed, Ok(()))
+                }
+                NormalizedEvent::SubscriptionRejected { symbols, reason } => (symbols.clone(), Err(reason.clone())),
+                NormalizedEvent::Tick(tick) => {
+                    // Fast path: avoid the write lock once a symbol is confirmed
+                    match subs.symbols.get(&tick.symbol) {
+                        Some(entry) if entry.state != SubscriptionState::Confirmed => {
+                            (vec![tick.symbol.clone()], Ok(()))
+                        }
+                        _ => return,
+                    }
+                }
+                _ => return,
+            }
+        };
+        
+        let mut exchanges = self.exchanges.write().unwrap();
+        let Some(subs) = exchanges.get_mut(exchange) else {
+            return;
+        };
+        
+        for symbol in symbols {
+            if let Some(entry) = subs.symbols.get_mut(&symbol) {
+                match &outcome {
+                    Ok(()) => {
+                        entry.state = SubscriptionState::Confirmed;
+                        entry.confirmed_at = Some(self.clock.now());
+                        entry.last_error = None;
+                    }
+                    Err(reason) => {
+                        entry.state = SubscriptionState::Rejected;
+                        entry.last_error = Some(reason.clone());
+                    }
+                }
+            }
+        }
+        
+        if let NormalizedEvent::SubscriptionAck { request_id: Some(id), .. } = event {
+            subs.requests.remove(id);
+        }
+        // Venues that ack by channel or symbol never name the request: it is
+        // done once none of its symbols still waits for an answer
+        let ExchangeSubscriptions { symbols: entries, requests, .. } = subs;
+        requests.retain(|_, requested| {
+            requested
+                .iter()
+                .any(|symbol| entries.get(symbol).is_some_and(|entry| entry.state == SubscriptionState::Requested))
+        });
+    }
+    
+    /// Requested symbols still unconfirmed after the ack timeout; callers re-request these
+    pub fn unconfirmed(&self, exchange: &str) -> Vec<String> {
+        let exchanges = self.exchanges.read().unwrap();
+        let Some(subs) = exchanges.get(exchange) else {
+            return Vec::new();
+        };
+        let now = self.clock.now();
+        let ack_timeout = *self.ack_timeout.read().unwrap();
+        
+        let mut symbols: Vec<String> = subs
+            .symbols
+            .iter()
+            .filter(|(_, entry)| {
+                entry.state == SubscriptionState::Requested
+                    && entry.last_requested.map_or(true, |at| now.saturating_duration_since(at) >= ack_timeout)
+            })
+            .map(|(symbol, _)| symbol.clone())
+            .collect();
+        symbols.sort();
+        symbols
+    }
+    
+    /// Connection carrying `symbols` dropped: they must be requested again.
+    /// Other connections (shards) of the same exchange are unaffected
+    pub fn reset(&self, exchange: &str, symbols: &[String]) {
+        if let Some(subs) = self.exchanges.write().unwrap().get_mut(exchange) {
+            subs.requests.retain(|_, requested| !requested.iter().any(|s| symbols.contains(s)));
+            for symbol in symbols {
+                if let Some(entry) = subs.symbols.get_mut(symbol) {
+                    entry.state = SubscriptionState::Inactive;
+                    entry.confirmed_at = None;
+                }
+            }
+        }
+    }
+    
+    /// `symbols` were unsubscribed on reload: stop tracking them altogether
+    pub fn forget(&self, exchange: &str, symbols: &[String]) {
+        if let Some(subs) = self.exchanges.write().unwrap().get_mut(exchange) {
+            subs.requests.retain(|_, requested| !requested.iter().any(|s| symbols.contains(s)));
+            for symbol in symbols {
+                subs.symbols.remove(symbol);
+            }
+        }
+    }
+    
+    pub fn snapshot(&self, exchange: &str) -> Option<Vec<SubscriptionStatus>> {
+        let exchanges = self.exchanges.read().unwrap();
+        let subs = exchanges.get(exchange)?;
+        let now = self.clock.now();
+        let ms_ago = |at: Option<Instant>| at.map(|at| now.saturating_duration_since(at).as_millis() as u64);
+        
+        let mut statuses: Vec<SubscriptionStatus> = subs
+            .symbols
+            .iter()
+            .map(|(symbol, entry)| SubscriptionStatus {
+                symbol: symbol.clone(),
+                state: entry.state,
+                attempts: entry.attempts,
+                last_request_ms_ago: ms_ago(entry.last_requested),
+                confirmed_ms_ago: ms_ago(entry.confirmed_at),
+                last_error: entry.last_error.clone(),
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        Some(statuses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::clock::ManualClock;
+    use super::super::connector::CanonicalTick;
+    
+    fn symbols(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+    
+    fn state_of(tracker: &SubscriptionTracker, symbol: &str) -> SubscriptionState {
+        tracker.snapshot("binance").unwrap().into_iter().find(|s| s.symbol == symbol).unwrap().state
+    }
+    
+    #[test]
+    fn test_ack_by_request_id_confirms_batch() {
+        let tracker = SubscriptionTracker::new(Duration::from_secs(10));
+        let id = tracker.request("binance", &symbols(&["BTC/USDT", "ETH/USDT"]));
+        
+        tracker.handle_event("binance", &NormalizedEvent::SubscriptionAck { request_id: Some(id), symbols: vec![] });
+        
+        assert_eq!(state_of(&tracker, "BTC/USDT"), SubscriptionState::Confirmed);
+        assert_eq!(state_of(&tracker, "ETH/USDT"), SubscriptionState::Confirmed);
+        assert!(tracker.snapshot("kraken").is_none());
+    }
+    
+    #[test]
+    fn test_unconfirmed_symbols_are_due_for_retry() {
+        let clock = Arc::new(ManualClock::new(0));
+        let tracker = SubscriptionTracker::new(Duration::from_secs(10)).with_clock(clock.clone());
+        tracker.request("binance", &symbols(&["BTC/USDT", "ETH/USDT", "ADA/USDT"]));
+        
+        tracker.handle_event("binance", &NormalizedEvent::SubscriptionRejected {
+            symbols: symbols(&["ADA/USDT"]),
+            reason: "Invalid symbol".to_string(),
+        });
+        tracker.handle_event("binance", &NormalizedEvent::Tick(CanonicalTick {
+            exchange: "binance".to_string(),
+            symbol: "BTC/USDT".to_string(),
+            bid: 50000.0,
+            bid_size: 1.0,
+            ask: 50001.0,
+            ask_size: 1.0,
+        }));
+        
+        assert!(tracker.unconfirmed("binance").is_empty(), "still within the ack timeout");
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(tracker.unconfirmed("binance"), symbols(&["ETH/USDT"]));
+        
+        tracker.request("binance", &tracker.unconfirmed("binance"));
+        let eth = tracker.snapshot("binance").unwrap().into_iter().find(|s| s.symbol == "ETH/USDT").unwrap();
+        assert_eq!(eth.attempts, 2);
+        assert_eq!(state_of(&tracker, "ADA/USDT"), SubscriptionState::Rejected);
+    }
+    
+    #[test]
+    fn test_reset_marks_connection_symbols_inactive() {
+        let tracker = SubscriptionTracker::new(Duration::from_secs(10));
+        let first = tracker.request("binance", &symbols(&["BTC/USDT"]));
+        let second = tracker.request("binance", &symbols(&["ETH/USDT"]));
+        tracker.handle_event("binance", &NormalizedEvent::SubscriptionAck { request_id: Some(first), symbols: vec![] });
+        tracker.handle_event("binance", &NormalizedEvent::SubscriptionAck { request_id: Some(second), symbols: vec![] });
+        
+        tracker.reset("binance", &symbols(&["BTC/USDT"]));
+        
+        assert_eq!(state_of(&tracker, "BTC/USDT"), SubscriptionState::Inactive);
+        assert_eq!(state_of(&tracker, "ETH/USDT"), SubscriptionState::Confirmed);
+        assert!(tracker.unconfirmed("binance").is_empty());
+    }
+    
+    #[test]
+    fn test_acks_by_symbol_retire_the_request() {
+        let tracker = SubscriptionTracker::new(Duration::from_secs(10));
+        let pending = || tracker.exchanges.read().unwrap()["binance"].requests.len();
+        tracker.request("binance", &symbols(&["BTC/USDT", "ETH/USDT"]));
+        
+        // Bitfinex-style: one ack per channel, naming its symbol but no request
+        let ack = |symbol: &str| NormalizedEvent::SubscriptionAck { request_id: None, symbols: symbols(&[symbol]) };
+        tracker.handle_event("binance", &ack("BTC/USDT"));
+        assert_eq!(pending(), 1, "ETH/USDT is still waiting");
+        tracker.handle_event("binance", &ack("ETH/USDT"));
+        assert_eq!(pending(), 0);
+        assert_eq!(state_of(&tracker, "ETH/USDT"), SubscriptionState::Confirmed);
+    }
+}
@@ -0,0 +1,312 @@
+// exchange/tardis.rs - Tardis Machine connector (normalized multi-venue live and replayed data)
+use std::collections::HashMap;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::connector::{
+    decimal, field, parse_frame, CanonicalTick, ExchangeConnector, NormalizeError, NormalizedEvent, OrderBookUpdate,
+};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::registry::ExchangeRegistry;
+use super::schema::MessageSchema;
+
+static QUOTE_SCHEMA: MessageSchema = MessageSchema {
+    required: &["type", "exchange", "symbol", "bidPrice", "bidAmount", "askPrice", "askAmount"],
+    optional: &["timestamp", "localTimestamp", "name"],
+    payload: quote_payload,
+};
+
+fn quote_payload(frame: &Value) -> Option<&Value> {
+    frame.get("type").filter(|t| *t == "quote").map(|_| frame)
+}
+
+/// Replay window for historical data; both ends as ISO 8601 dates or times
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TardisReplay {
+    pub from: String,
+    pub to: String,
+}
+
+/// A self-hosted tardis-machine server; it holds the Tardis API key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TardisConfig {
+    /// Base WebSocket URL of tardis-machine
+    pub machine_url: String,
+    /// Venues to stream, by Tardis exchange id ("binance", "coinbase", "kraken", ...)
+    pub venues: Vec<String>,
+    /// "quote" for top of book, or "book_snapshot_<levels>_<interval>" for L2
+    pub data_types: Vec<String>,
+    /// Replay this window of history instead of streaming live
+    pub replay: Option<TardisReplay>,
+}
+
+impl Default for TardisConfig {
+    fn default() -> Self {
+        Self {
+            machine_url: "ws://localhost:8000".to_string(),
+            venues: Vec::new(),
+            data_types: vec!["quote".to_string()],
+            replay: None,
+        }
+    }
+}
+
+/// Every configured venue through one tardis-machine socket. Subscriptions
+/// are part of the URL, so `subscribe_messages` sends nothing. Ticks keep the
+/// underlying venue as their exchange, so the graph sees real markets. Depth
+/// comes from `book_snapshot` data types, whole books that need no resync
+pub struct TardisConnector {
+    url: String,
+    data_types: Vec<String>,
+    /// Native connectors, for the venues' own symbol spellings
+    venues: HashMap<String, Option<Arc<dyn ExchangeConnector>>>,
+}
+
+impl TardisConnector {
+    pub fn new(config: &TardisConfig, symbols: &[String], registry: &ExchangeRegistry) -> Self {
+        let venues: HashMap<String, Option<Arc<dyn ExchangeConnector>>> = config
+            .venues
+            .iter()
+            .map(|venue| (venue.clone(), registry.connector(venue)))
+            .collect();
+        
+        let options: Vec<Value> = config
+            .venues
+            .iter()
+            .map(|venue| {
+                let native: Vec<String> = symbols.iter().map(|s| venue_symbol(venues[venue].as_deref(), s)).collect();
+                let mut option = json!({ "exchange": venue, "symbols": native, "dataTypes": config.data_types });
+                if let Some(replay) = &config.replay {
+                    option["from"] = json!(replay.from);
+                    option["to"] = json!(replay.to);
+                }
+                option
+            })
+            .collect();
+        let endpoint = if config.replay.is_some() { "ws-replay-normalized" } else { "ws-stream-normalized" };
+        let query = serde_urlencoded::to_string([("options", Value::from(options).to_string())]).unwrap_or_default();
+        
+        Self {
+            url: format!("{}/{}?{}", config.machine_url.trim_end_matches('/'), endpoint, query),
+            data_types: config.data_types.clone(),
+            venues,
+        }
+    }
+    
+    /// Internal symbol for `raw` as Tardis spells it on `venue`
+    fn venue_to_internal(&self, venue: &str, raw: &str) -> Option<String> {
+        match self.venues.get(venue) {
+            Some(Some(connector)) => connector.from_exchange_symbol(raw),
+            _ => self.from_exchange_symbol(raw),
+        }
+    }
+    
+    /// Underlying venue and internal symbol of a message
+    fn market(&self, frame: &Value) -> Result<(String, String), NormalizeError> {
+        let venue = field(frame, "exchange")?.as_str().ok_or(NormalizeError::MissingField("exchange"))?;
+        let raw = field(frame, "symbol")?.as_str().ok_or(NormalizeError::MissingField("symbol"))?;
+        let symbol = self
+            .venue_to_internal(venue, raw)
+            .ok_or_else(|| NormalizeError::UnknownSymbol(format!("{}:{}", venue, raw)))?;
+        Ok((venue.to_string(), symbol))
+    }
+    
+    fn tick(&self, frame: &Value, bid: (f64, f64), ask: (f64, f64)) -> Result<NormalizedEvent, NormalizeError> {
+        let (venue, symbol) = self.market(frame)?;
+        
+        Ok(NormalizedEvent::Tick(CanonicalTick {
+            exchange: venue,
+            symbol,
+            bid: bid.0,
+            bid_size: bid.1,
+            ask: ask.0,
+            ask_size: ask.1,
+        }))
+    }
+    
+    fn book(&self, frame: &Value) -> Result<NormalizedEvent, NormalizeError> {
+        let (venue, symbol) = self.market(frame)?;
+        
+        Ok(NormalizedEvent::Book(OrderBookUpdate {
+            exchange: venue,
+            symbol,
+            snapshot: true,
+            bids: book_levels(frame, "bids")?,
+            asks: book_levels(frame, "asks")?,
+            sequence: None,
+            depth: None,
+        }))
+    }
+}
+
+/// `symbol` as `venue` spells it; dashed for venues without a native connector
+fn venue_symbol(venue: Option<&dyn ExchangeConnector>, symbol: &str) -> String {
+    match venue {
+        Some(connector) => connector.to_exchange_symbol(symbol),
+        None => symbol.replace('/', "-").to_uppercase(),
+    }
+}
+
+/// Best level of a `book_snapshot` side: `[{"price": .., "amount": ..}, ..]`
+fn best_level(frame: &Value, side: &'static str) -> Result<(f64, f64), NormalizeError> {
+    let level = field(frame, side)?
+        .as_array()
+        .and_then(|levels| levels.first())
+        .ok_or_else(|| NormalizeError::Malformed(format!("empty {} side", side)))?;
+    Ok((decimal(field(level, "price")?, "price")?, decimal(field(level, "amount")?, "amount")?))
+}
+
+/// Every level of a `book_snapshot` side
+fn book_levels(frame: &Value, side: &'static str) -> Result<Vec<(f64, f64)>, NormalizeError> {
+    field(frame, side)?
+        .as_array()
+        .ok_or(NormalizeError::MissingField(side))?
+        .iter()
+        .map(|level| Ok((decimal(field(level, "price")?, "price")?, decimal(field(level, "amount")?, "amount")?)))
+        .collect()
+}
+
+impl ExchangeConnector for TardisConnector {
+    fn name(&self) -> &'static str {
+        "tardis"
+    }
+    
+    fn ws_url(&self) -> &str {
+        &self.url
+    }
+    
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        venue_symbol(None, symbol)
+    }
+    
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        let (base, quote) = raw.split_once(['-', '/', '_'])?;
+        Some(format!("{}/{}", base.to_uppercase(), quote.to_uppercase()))
+    }
+    
+    fn subscribe_messages(&self, _symbols: &[String], _request_id: u64) -> Vec<String> {
+        Vec::new()
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: self.data_types.iter().any(|data_type| data_type.starts_with("book_snapshot")),
+            trading: false,
+            testnet_ws_url: None,
+            max_streams_per_connection: None,
+            streams_per_symbol: (self.venues.len() * self.data_types.len()).max(1),
+            max_messages_per_second: None,
+            symbol_format: SymbolFormat::Dashed,
+        }
+    }
+    
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        Some(&QUOTE_SCHEMA)
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        let message_type = field(&frame, "type")?.as_str().unwrap_or_default();
+        
+        match message_type {
+            "quote" => {
+                let bid = (decimal(field(&frame, "bidPrice")?, "bidPrice")?, decimal(field(&frame, "bidAmount")?, "bidAmount")?);
+                let ask = (decimal(field(&frame, "askPrice")?, "askPrice")?, decimal(field(&frame, "askAmount")?, "askAmount")?);
+                Ok(vec![self.tick(&frame, bid, ask)?])
+            }
+            "book_snapshot" => Ok(vec![
+                self.tick(&frame, best_level(&frame, "bids")?, best_level(&frame, "asks")?)?,
+                self.book(&frame)?,
+            ]),
+            "disconnect" => Ok(vec![NormalizedEvent::Control(format!(
+                "tardis-machine lost {}",
+                frame.get("exchange").and_then(Value::as_str).unwrap_or("a venue")
+            ))]),
+            other => Err(NormalizeError::Malformed(format!("unexpected message type '{}'", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrage::Config;
+    use crate::exchange::capabilities::check_config;
+    
+    fn connector(replay: Option<TardisReplay>) -> TardisConnector {
+        let config = TardisConfig {
+            venues: vec!["binance".to_string(), "kraken".to_string()],
+            replay,
+            ..TardisConfig::default()
+        };
+        TardisConnector::new(&config, &["BTC/USDT".to_string()], &ExchangeRegistry::builtin())
+    }
+    
+    #[test]
+    fn test_depth_needs_a_book_snapshot_data_type() {
+        let mut config = Config::default();
+        config.order_book.enabled = true;
+        assert!(!connector(None).capabilities().depth);
+        assert_eq!(check_config(&connector(None), &config)[0].field, "order_book.enabled");
+        
+        config.tardis.data_types = vec!["quote".to_string(), "book_snapshot_10_100ms".to_string()];
+        let books = TardisConnector::new(&config.tardis, &config.symbols, &ExchangeRegistry::builtin());
+        assert!(books.capabilities().depth);
+        assert!(check_config(&books, &config).is_empty());
+    }
+    
+    #[test]
+    fn test_url_carries_venue_symbols_and_replay_window() {
+        let live = connector(None);
+        assert!(live.ws_url().starts_with("ws://localhost:8000/ws-stream-normalized?options="));
+        let (_, options) = serde_urlencoded::from_str::<Vec<(String, String)>>(live.ws_url().split_once('?').unwrap().1).unwrap().remove(0);
+        let options: Value = serde_json::from_str(&options).unwrap();
+        assert_eq!(options[0]["symbols"], json!(["BTCUSDT"]));
+        assert_eq!(options[1]["symbols"], json!(["XBT/USDT"]));
+        assert_eq!(options[1]["dataTypes"], json!(["quote"]));
+        assert!(live.subscribe_messages(&["BTC/USDT".to_string()], 1).is_empty());
+        
+        let replay = connector(Some(TardisReplay { from: "2024-01-01".to_string(), to: "2024-01-02".to_string() }));
+        assert!(replay.ws_url().contains("/ws-replay-normalized?"));
+        assert!(replay.ws_url().contains("2024-01-02"));
+    }
+    
+    #[test]
+    fn test_normalizes_quotes_and_book_snapshots_per_venue() {
+        let tardis = connector(None);
+        let quote = r#"{"type":"quote","symbol":"XBT/USD","exchange":"kraken","askAmount":0.5,"askPrice":42001.5,
+            "bidPrice":42000.0,"bidAmount":1.25,"timestamp":"2024-01-01T00:00:00.120Z","localTimestamp":"2024-01-01T00:00:00.125Z"}"#;
+        let events = tardis.normalize(quote).unwrap();
+        assert_eq!(
+            events,
+            vec![NormalizedEvent::Tick(CanonicalTick {
+                exchange: "kraken".to_string(),
+                symbol: "BTC/USD".to_string(),
+                bid: 42000.0,
+                bid_size: 1.25,
+                ask: 42001.5,
+                ask_size: 0.5,
+            })]
+        );
+        
+        let snapshot = r#"{"type":"book_snapshot","symbol":"BTCUSDT","exchange":"binance","name":"book_snapshot_5_0ms","depth":5,
+            "bids":[{"price":50000.1,"amount":2},{"price":50000.0,"amount":1}],"asks":[{"price":50000.2,"amount":3}]}"#;
+        let events = tardis.normalize(snapshot).unwrap();
+        match &events[0] {
+            NormalizedEvent::Tick(tick) => assert_eq!((tick.symbol.as_str(), tick.bid, tick.ask_size), ("BTC/USDT", 50000.1, 3.0)),
+            other => panic!("expected a tick, got {:?}", other),
+        }
+        match &events[1] {
+            NormalizedEvent::Book(book) => {
+                assert_eq!((book.exchange.as_str(), book.snapshot), ("binance", true));
+                assert_eq!(book.bids, vec![(50000.1, 2.0), (50000.0, 1.0)]);
+                assert_eq!(book.asks, vec![(50000.2, 3.0)]);
+            }
+            other => panic!("expected a book, got {:?}", other),
+        }
+        
+        let empty_side = r#"{"type":"book_snapshot","symbol":"BTCUSDT","exchange":"binance","bids":[],"asks":[]}"#;
+        assert!(tardis.normalize(empty_side).is_err());
+    }
+}
@@ -0,0 +1,211 @@
+// exchange/upbit.rs - Upbit connector (orderbook streams, KRW markets alongside the configured pairs)
+use std::collections::BTreeSet;
+use std::sync::RwLock;
+use serde_json::{json, Value};
+
+use super::connector::{decimal, field, parse_frame, CanonicalTick, ExchangeConnector, NormalizeError, NormalizedEvent};
+use super::capabilities::{Capabilities, SymbolFormat};
+use super::schema::MessageSchema;
+
+const WS_URL: &str = "wss://api.upbit.com/websocket/v1";
+const INSTRUMENTS_URL: &str = "https://api.upbit.com/v1/market/all";
+/// Upbit's home market; every configured asset is also followed against it
+const LOCAL_QUOTE: &str = "KRW";
+
+static ORDERBOOK_SCHEMA: MessageSchema = MessageSchema {
+    required: &["type", "code", "orderbook_units"],
+    optional: &["timestamp", "total_ask_size", "total_bid_size", "stream_type", "level"],
+    payload: orderbook_payload,
+};
+
+fn orderbook_payload(frame: &Value) -> Option<&Value> {
+    frame.get("type").filter(|kind| *kind == "orderbook").and(Some(frame))
+}
+
+/// Markets are named quote first ("KRW-BTC", "USDT-ETH"). Most volume is in
+/// KRW, so each configured pair brings its assets' KRW markets with it: the
+/// graph gets a KRW node linking every Upbit asset, and KRW prices can be
+/// compared with other regions through KRW-USDT.
+/// Each request replaces the socket's previous one, so the connector keeps
+/// every symbol asked for and always requests the whole set
+pub struct UpbitConnector {
+    symbols: RwLock<BTreeSet<String>>,
+}
+
+impl UpbitConnector {
+    pub fn new() -> Self {
+        Self {
+            symbols: RwLock::new(BTreeSet::new()),
+        }
+    }
+    
+    /// Native codes for `symbols` plus the KRW market of every asset in them
+    fn codes(&self, symbols: &BTreeSet<String>) -> Vec<String> {
+        let mut codes = BTreeSet::new();
+        for symbol in symbols {
+            codes.insert(self.to_exchange_symbol(symbol));
+            for asset in symbol.to_uppercase().split('/').filter(|asset| *asset != LOCAL_QUOTE) {
+                codes.insert(format!("{}-{}", LOCAL_QUOTE, asset));
+            }
+        }
+        codes.into_iter().collect()
+    }
+    
+    /// Best level of an orderbook frame: {"code": "KRW-BTC", "orderbook_units": [{"ask_price", "bid_price", "ask_size", "bid_size"}, ..]}
+    fn tick(&self, frame: &Value) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let code = field(frame, "code")?.as_str().ok_or(NormalizeError::MissingField("code"))?;
+        let symbol = self
+            .from_exchange_symbol(code)
+            .ok_or_else(|| NormalizeError::UnknownSymbol(code.to_string()))?;
+        let Some(best) = field(frame, "orderbook_units")?.as_array().and_then(|units| units.first()) else {
+            return Ok(Vec::new());
+        };
+        
+        Ok(vec![NormalizedEvent::Tick(CanonicalTick {
+            exchange: self.name().to_string(),
+            symbol,
+            bid: decimal(field(best, "bid_price")?, "bid_price")?,
+            bid_size: decimal(field(best, "bid_size")?, "bid_size")?,
+            ask: decimal(field(best, "ask_price")?, "ask_price")?,
+            ask_size: decimal(field(best, "ask_size")?, "ask_size")?,
+        })])
+    }
+}
+
+impl ExchangeConnector for UpbitConnector {
+    fn name(&self) -> &'static str {
+        "upbit"
+    }
+    
+    fn ws_url(&self) -> &str {
+        WS_URL
+    }
+    
+    fn to_exchange_symbol(&self, symbol: &str) -> String {
+        match symbol.split_once('/') {
+            Some((base, quote)) => format!("{}-{}", quote, base).to_uppercase(),
+            None => symbol.to_uppercase(),
+        }
+    }
+    
+    fn from_exchange_symbol(&self, raw: &str) -> Option<String> {
+        let (quote, base) = raw.split_once('-')?;
+        if quote.is_empty() || base.is_empty() {
+            return None;
+        }
+        Some(format!("{}/{}", base, quote).to_uppercase())
+    }
+    
+    fn subscribe_messages(&self, symbols: &[String], request_id: u64) -> Vec<String> {
+        let mut subscribed = self.symbols.write().unwrap();
+        subscribed.extend(symbols.iter().cloned());
+        vec![json!([
+            { "ticket": format!("scanner-{}", request_id) },
+            { "type": "orderbook", "codes": self.codes(&subscribed) },
+        ])
+        .to_string()]
+    }
+    
+    fn unsubscribe_messages(&self, symbols: &[String]) -> Vec<String> {
+        let mut subscribed = self.symbols.write().unwrap();
+        for symbol in symbols {
+            subscribed.remove(symbol);
+        }
+        // With nothing left the old request stays; the manager drops its ticks
+        if subscribed.is_empty() {
+            return Vec::new();
+        }
+        vec![json!([
+            { "ticket": "scanner-0" },
+            { "type": "orderbook", "codes": self.codes(&subscribed) },
+        ])
+        .to_string()]
+    }
+    
+    /// Market data arrives in binary frames holding UTF-8 JSON
+    fn instruments_url(&self) -> Option<&str> {
+        Some(INSTRUMENTS_URL)
+    }
+    
+    fn parse_instruments(&self, body: &str) -> Result<Vec<String>, NormalizeError> {
+        let markets = parse_frame(body)?;
+        let markets = markets.as_array().ok_or_else(|| NormalizeError::Malformed("expected a market list".to_string()))?;
+        Ok(markets.iter().filter_map(|market| self.from_exchange_symbol(market["market"].as_str()?)).collect())
+    }
+    
+    fn decode_binary(&self, data: &[u8]) -> Result<String, NormalizeError> {
+        String::from_utf8(data.to_vec()).map_err(|e| NormalizeError::Malformed(format!("binary frame: {}", e)))
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            depth: false,
+            trading: false,
+            testnet_ws_url: None,
+            max_streams_per_connection: None,
+            // The pair itself and, at most, its assets' KRW markets
+            streams_per_symbol: 2,
+            max_messages_per_second: Some(5),
+            symbol_format: SymbolFormat::QuoteFirst,
+        }
+    }
+    
+    fn schema(&self) -> Option<&'static MessageSchema> {
+        Some(&ORDERBOOK_SCHEMA)
+    }
+    
+    fn normalize(&self, raw: &str) -> Result<Vec<NormalizedEvent>, NormalizeError> {
+        let frame = parse_frame(raw)?;
+        
+        // {"error": {"name": "INVALID_PARAM", "message": ".."}} refuses the whole request
+        if let Some(error) = frame.get("error") {
+            return Ok(vec![NormalizedEvent::SubscriptionRejected {
+                symbols: Vec::new(),
+                reason: error
+                    .get("message")
+                    .or_else(|| error.get("name"))
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            }]);
+        }
+        // Answer to a "PING" text frame
+        if frame.get("status").and_then(Value::as_str) == Some("UP") {
+            return Ok(vec![NormalizedEvent::Heartbeat]);
+        }
+        
+        match field(&frame, "type")?.as_str().unwrap_or_default() {
+            "orderbook" => self.tick(&frame),
+            other => Err(NormalizeError::Malformed(format!("unexpected message type '{}'", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_krw_markets_ride_along_and_frames_are_binary() {
+        let upbit = UpbitConnector::new();
+        let request = |frames: Vec<String>| serde_json::from_str::<Value>(&frames[0]).unwrap();
+        let symbols = ["BTC/USDT".to_string(), "ETH/BTC".to_string()];
+        let first = request(upbit.subscribe_messages(&symbols, 7));
+        assert_eq!(first[0]["ticket"], "scanner-7");
+        assert_eq!(first[1]["codes"], json!(["BTC-ETH", "KRW-BTC", "KRW-ETH", "KRW-USDT", "USDT-BTC"]));
+        
+        // Later requests carry the earlier symbols too
+        let added = request(upbit.subscribe_messages(&["XRP/KRW".to_string()], 8));
+        assert_eq!(added[1]["codes"].as_array().unwrap().len(), 6);
+        let removed = request(upbit.unsubscribe_messages(&symbols));
+        assert_eq!(removed[1]["codes"], json!(["KRW-XRP"]));
+        
+        let frame = br#"{"type":"orderbook","code":"KRW-BTC","orderbook_units":[{"ask_price":130050000.0,"bid_price":130000000.0,"ask_size":0.5,"bid_size":0.25}]}"#;
+        let events = upbit.normalize(&upbit.decode_binary(frame).unwrap()).unwrap();
+        let NormalizedEvent::Tick(tick) = &events[0] else {
+            panic!("expected a tick: {:?}", events);
+        };
+        assert_eq!((tick.symbol.as_str(), tick.bid, tick.ask), ("BTC/KRW", 130000000.0, 130050000.0));
+        assert!(upbit.decode_binary(&[0xff, 0xfe]).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+// exchange/websocket.rs - WebSocket sessions and REST instrument lists for every ExchangeConnector
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use futures_util::future::BoxFuture;
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
+use tokio::time;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tracing::debug;
+
+use crate::arbitrage::budget::PipelineStage;
+use crate::arbitrage::Config;
+use crate::config::ConfigIssue;
+use crate::markets;
+use super::capabilities::Capabilities;
+#[cfg(feature = "chaos")]
+use super::chaos::ChaosAction;
+use super::connector::{Endpoint, ExchangeConnector, NormalizedEvent};
+use super::registry::{BoxError, Exchange, ExchangeSession, SessionContext};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+impl<C: ExchangeConnector + 'static> Exchange for C {
+    fn name(&self) -> &str {
+        ExchangeConnector::name(self)
+    }
+    
+    fn endpoint(&self) -> &str {
+        self.ws_url()
+    }
+    
+    fn capabilities(&self) -> Capabilities {
+        ExchangeConnector::capabilities(self)
+    }
+    
+    /// Listed pairs the stream can't name both ways are left out
+    fn list_symbols<'a>(&'a self, client: &'a reqwest::Client) -> BoxFuture<'a, Option<Result<Vec<String>, BoxError>>> {
+        Box::pin(async move {
+            let url = self.instruments_url()?;
+            let listed = async {
+                let body = client.get(url).send().await?.error_for_status()?.text().await?;
+                Ok::<_, BoxError>(self.parse_instruments(&body)?)
+            };
+            Some(listed.await.map(|symbols| symbols.into_iter().filter(|symbol| round_trips(self, symbol)).collect()))
+        })
+    }
+    
+    /// Every BASE/QUOTE symbol must survive the venue's format in both
+    /// directions, or come back as a contract on its base from venues listing
+    /// only those; malformed ones are reported by the shape check in `config`
+    fn config_issues(&self, config: &Config) -> Vec<ConfigIssue> {
+        let format = ExchangeConnector::capabilities(self).symbol_format;
+        config
+            .symbols
+            .iter()
+            .filter(|symbol| symbol.contains('/') && !round_trips(self, symbol))
+            .map(|symbol| {
+                ConfigIssue::new(
+                    "symbols",
+                    format!(
+                        "{} can't map {:?} ({:?} form {:?} doesn't round-trip)",
+                        ExchangeConnector::name(self),
+                        symbol,
+                        format,
+                        self.to_exchange_symbol(symbol)
+                    ),
+                )
+            })
+            .collect()
+    }
+    
+    fn connect<'a>(&'a self, context: &'a SessionContext) -> BoxFuture<'a, Result<Box<dyn ExchangeSession + 'a>, BoxError>> {
+        Box::pin(async move {
+            let session: Box<dyn ExchangeSession + 'a> = Box::new(WebSocketSession::connect(self, context).await?);
+            Ok(session)
+        })
+    }
+    
+    fn connector(self: Arc<Self>) -> Option<Arc<dyn ExchangeConnector>> {
+        Some(self)
+    }
+}
+
+/// Whether `symbol` comes back from the venue's format as itself, or as a
+/// contract on its base
+fn round_trips(connector: &dyn ExchangeConnector, symbol: &str) -> bool {
+    let native = connector.to_exchange_symbol(symbol);
+    let symbol_base = symbol.split_once('/').map(|(base, _)| base.to_uppercase());
+    connector.from_exchange_symbol(&native).is_some_and(|mapped| {
+        mapped == symbol.to_uppercase()
+            || mapped.split_once('/').and_then(|(base, _)| markets::underlying(base)) == symbol_base.as_deref()
+    })
+}
+
+/// One socket speaking a connector's protocol: control frames are paced to
+/// the venue's message rate, and replies and keepalives it asks for go back
+/// on the socket
+struct WebSocketSession<'a> {
+    shared: &'a dyn ExchangeConnector,
+    /// This connection's own connector, for venues that want one
+    own: Option<Box<dyn ExchangeConnector>>,
+    context: SessionContext,
+    write: SplitSink<Socket, Message>,
+    read: SplitStream<Socket>,
+    frame_gap: Option<Duration>,
+    keepalive: Option<(time::Interval, String)>,
+    /// Replies not yet sent, kept here so a cancelled read loses none
+    replies: Vec<String>,
+}
+
+impl<'a> WebSocketSession<'a> {
+    async fn connect(connector: &'a dyn ExchangeConnector, context: &SessionContext) -> Result<Self, BoxError> {
+        let capabilities = connector.capabilities();
+        let endpoint = match connector.handshake(&context.http) {
+            Some(handshake) => handshake.await?,
+            None => Endpoint {
+                url: match capabilities.testnet_ws_url {
+                    Some(testnet) if context.use_testnet => testnet,
+                    _ => connector.ws_url(),
+                }
+                .to_string(),
+                keepalive: None,
+            },
+        };
+        // Space out control frames so bulk subscribes stay under the venue's rate limit
+        let frame_gap = capabilities
+            .max_messages_per_second
+            .map(|limit| Duration::from_secs(1) / limit.max(1));
+        
+        let (socket, _) = connect_async(endpoint.url.as_str()).await?;
+        let (write, read) = socket.split();
+        let keepalive = endpoint
+            .keepalive
+            .map(|(every, frame)| (time::interval_at(time::Instant::now() + every, every), frame));
+        let delay = connector.subscribe_delay();
+        if !delay.is_zero() {
+            time::sleep(delay).await;
+        }
+        Ok(Self {
+            shared: connector,
+            own: connector.for_connection(),
+            context: context.clone(),
+            write,
+            read,
+            frame_gap,
+            keepalive,
+            replies: Vec::new(),
+        })
+    }
+    
+    fn connector(&self) -> &dyn ExchangeConnector {
+        self.own.as_deref().unwrap_or(self.shared)
+    }
+    
+    async fn send_all(&mut self, frames: Vec<String>) -> Result<(), BoxError> {
+        for frame in frames {
+            self.write.send(Message::Text(frame.into())).await?;
+            if let Some(gap) = self.frame_gap {
+                time::sleep(gap).await;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Events in one raw frame; replies are queued rather than returned
+    fn normalize(&mut self, raw: &str) -> Vec<NormalizedEvent> {
+        let parse_start = Instant::now();
+        let outcome = self.connector().normalize(raw);
+        self.context.budgets.record(PipelineStage::Parse, parse_start.elapsed());
+        self.context.schema_monitor.inspect(self.connector(), raw, &outcome);
+        
+        match outcome {
+            Ok(events) => events
+                .into_iter()
+                .filter_map(|event| match event {
+                    NormalizedEvent::Reply(frame) => {
+                        self.replies.push(frame);
+                        None
+                    }
+                    event => Some(event),
+                })
+                .collect(),
+            Err(e) => {
+                debug!("{}: dropping frame: {}", self.connector().name(), e);
+                Vec::new()
+            }
+        }
+    }
+    
+    async fn next_message(&mut self) -> Option<Result<Vec<NormalizedEvent>, BoxError>> {
+        loop {
+            while let Some(reply) = self.replies.first() {
+                if let Err(e) = self.write.send(Message::Text(reply.clone().into())).await {
+                    return Some(Err(e.into()));
+                }
+                self.replies.remove(0);
+            }
+            
+            let message = tokio::select! {
+                message = self.read.next() => message?,
+                frame = keepalive_due(&mut self.keepalive) => {
+                    self.replies.push(frame);
+                    continue;
+                }
+            };
+            let text = match message {
+                Err(e) => return Some(Err(e.into())),
+                Ok(Message::Close(_)) => return None,
+                Ok(Message::Text(text)) => text.to_string(),
+                Ok(Message::Binary(data)) => match self.connector().decode_binary(&data) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        debug!("{}: dropping binary frame: {}", self.connector().name(), e);
+                        continue;
+                    }
+                },
+                Ok(_) => continue, // Ping/pong handled by tungstenite
+            };
+            #[cfg(feature = "chaos")]
+            let frames = match &self.context.chaos {
+                Some(chaos) => match chaos.lock().unwrap().process(text) {
+                    ChaosAction::Deliver(frames) => frames,
+                    ChaosAction::Disconnect => {
+                        return Some(Err(format!("{} killed by chaos mode", self.connector().name()).into()))
+                    }
+                },
+                None => vec![text],
+            };
+            #[cfg(not(feature = "chaos"))]
+            let frames = [text];
+            
+            let events = frames.iter().flat_map(|frame| self.normalize(frame)).collect();
+            return Some(Ok(events));
+        }
+    }
+}
+
+/// The keepalive frame each time it is due; never, for venues without one
+async fn keepalive_due(keepalive: &mut Option<(time::Interval, String)>) -> String {
+    match keepalive {
+        Some((interval, frame)) => {
+            interval.tick().await;
+            frame.clone()
+        }
+        None => std::future::pending().await,
+    }
+}
+
+impl ExchangeSession for WebSocketSession<'_> {
+    fn subscribe<'b>(&'b mut self, symbols: &'b [String], request_id: u64) -> BoxFuture<'b, Result<(), BoxError>> {
+        let mut frames = self.connector().subscribe_messages(symbols, request_id);
+        if let Some(levels) = self.context.depth_levels {
+            frames.extend(self.connector().depth_subscribe_messages(symbols, levels, request_id));
+        }
+        Box::pin(self.send_all(frames))
+    }
+    
+    fn unsubscribe<'b>(&'b mut self, symbols: &'b [String]) -> BoxFuture<'b, Result<(), BoxError>> {
+        let mut frames = self.connector().unsubscribe_messages(symbols);
+        if let Some(levels) = self.context.depth_levels {
+            frames.extend(self.connector().depth_unsubscribe_messages(symbols, levels));
+        }
+        Box::pin(self.send_all(frames))
+    }
+    
+    /// Books that lost sync start over from a new depth snapshot
+    fn resync<'b>(&'b mut self, symbols: &'b [String]) -> BoxFuture<'b, Result<(), BoxError>> {
+        let frames = match self.context.depth_levels {
+            Some(levels) => {
+                let mut frames = self.connector().depth_unsubscribe_messages(symbols, levels);
+                frames.extend(self.connector().depth_subscribe_messages(symbols, levels, 0));
+                frames
+            }
+            None => Vec::new(),
+        };
+        Box::pin(self.send_all(frames))
+    }
+    
+    fn next_events(&mut self) -> BoxFuture<'_, Option<Result<Vec<NormalizedEvent>, BoxError>>> {
+        Box::pin(self.next_message())
+    }
+}
@@ -0,0 +1,108 @@
+// execution/analytics.rs - Realized vs expected profit aggregation
+use std::collections::BTreeMap;
+use std::time::Duration;
+use serde::Serialize;
+
+use super::types::ExecutionRecord;
+
+/// Expected vs realized profit for one exchange pair in one time bucket
+#[derive(Debug, Clone, Serialize)]
+pub struct SlippageBucket {
+    pub exchange_pair: String,
+    pub bucket_start_ms: u64,
+    pub executions: u64,
+    pub notional: f64,
+    pub expected_profit: f64,
+    pub realized_profit: f64,
+    /// (expected - realized) / notional, in basis points; positive means worse than modeled
+    pub slippage_bps: f64,
+}
+
+/// Group execution records by exchange pair and time bucket, oldest bucket first
+pub fn slippage_report(records: &[ExecutionRecord], bucket: Duration) -> Vec<SlippageBucket> {
+    let bucket_ms = (bucket.as_millis() as u64).max(1);
+    let mut buckets: BTreeMap<(u64, String), SlippageBucket> = BTreeMap::new();
+    
+    for record in records {
+        let pair = exchange_pair(&record.exchanges);
+        let start = record.completed_at_ms - record.completed_at_ms % bucket_ms;
+        
+        let entry = buckets.entry((start, pair.clone())).or_insert_with(|| SlippageBucket {
+            exchange_pair: pair,
+            bucket_start_ms: start,
+            executions: 0,
+            notional: 0.0,
+            expected_profit: 0.0,
+            realized_profit: 0.0,
+            slippage_bps: 0.0,
+        });
+        entry.executions += 1;
+        entry.notional += record.notional;
+        entry.expected_profit += record.expected_profit;
+        entry.realized_profit += record.realized_profit;
+    }
+    
+    buckets
+        .into_values()
+        .map(|mut b| {
+            if b.notional > 0.0 {
+                b.slippage_bps = (b.expected_profit - b.realized_profit) / b.notional * 10_000.0;
+            }
+            b
+        })
+        .collect()
+}
+
+/// Order-independent label, e.g. "binance/kraken"
+pub(crate) fn exchange_pair(exchanges: &[String]) -> String {
+    let mut unique: Vec<&str> = exchanges.iter().map(String::as_str).collect();
+    unique.sort_unstable();
+    unique.dedup();
+    unique.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn record(exchanges: &[&str], expected: f64, realized: f64, at_ms: u64) -> ExecutionRecord {
+        ExecutionRecord {
+            opportunity_path: "BTC_binance -> USDT_binance -> BTC_kraken".to_string(),
+            exchanges: exchanges.iter().map(|e| e.to_string()).collect(),
+            notional: 10_000.0,
+            expected_profit: expected,
+            realized_profit: realized,
+            completed_at_ms: at_ms,
+        }
+    }
+    
+    #[test]
+    fn test_groups_by_pair_and_bucket() {
+        let hour = Duration::from_secs(3600);
+        let records = vec![
+            record(&["kraken", "binance"], 20.0, 15.0, 1_000),
+            record(&["binance", "kraken", "binance"], 10.0, 5.0, 2_000),
+            record(&["binance", "kraken"], 10.0, 10.0, 3_600_000 + 5),
+            record(&["coinbase"], 5.0, 6.0, 3_000),
+        ];
+        
+        let report = slippage_report(&records, hour);
+        assert_eq!(report.len(), 3);
+        
+        let first = &report[0];
+        assert_eq!(first.exchange_pair, "binance/kraken");
+        assert_eq!(first.executions, 2);
+        assert_eq!(first.bucket_start_ms, 0);
+        // (30 - 20) / 20_000 = 5 bps
+        assert!((first.slippage_bps - 5.0).abs() < 1e-9);
+        
+        assert_eq!(report[1].exchange_pair, "coinbase");
+        assert!(report[1].slippage_bps < 0.0);
+        assert_eq!(report[2].bucket_start_ms, 3_600_000);
+    }
+    
+    #[test]
+    fn test_empty_records() {
+        assert!(slippage_report(&[], Duration::from_secs(60)).is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+// execution/approval.rs - Manual confirmation queue between alerting and execution
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::arbitrage::types::ArbitrageOpportunity;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionMode {
+    /// Alerts only; nothing is executed
+    #[default]
+    AlertOnly,
+    /// Opportunities wait for a human to approve them before execution
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalConfig {
+    pub mode: ExecutionMode,
+    /// How long a queued opportunity stays approvable; prices move on
+    #[serde(with = "humantime_serde")]
+    pub ttl: Duration,
+    /// Only opportunities at least this profitable (fraction) are queued
+    pub min_profit: f64,
+}
+
+impl Default for ApprovalConfig {
+    fn default() -> Self {
+        Self {
+            mode: ExecutionMode::AlertOnly,
+            ttl: Duration::from_secs(120),
+            min_profit: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingApproval {
+    pub id: u64,
+    pub opportunity: ArbitrageOpportunity,
+    pub queued_at_ms: u64,
+    pub expires_at_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApprovalError {
+    Unknown(u64),
+    Expired(u64),
+}
+
+impl fmt::Display for ApprovalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApprovalError::Unknown(id) => write!(f, "no pending approval #{}", id),
+            ApprovalError::Expired(id) => write!(f, "approval #{} expired", id),
+        }
+    }
+}
+
+impl std::error::Error for ApprovalError {}
+
+struct Queue {
+    next_id: u64,
+    pending: BTreeMap<u64, PendingApproval>,
+}
+
+/// Opportunities waiting for a human decision. Each path has at most one entry;
+/// re-detections refresh its numbers and TTL instead of queueing duplicates
+pub struct ApprovalQueue {
+    config: ApprovalConfig,
+    queue: Mutex<Queue>,
+}
+
+impl ApprovalQueue {
+    pub fn new(config: ApprovalConfig) -> Self {
+        Self {
+            config,
+            queue: Mutex::new(Queue {
+                next_id: 1,
+                pending: BTreeMap::new(),
+            }),
+        }
+    }
+    
+    /// Queue `opportunity` if manual mode is on and it clears `min_profit`;
+    /// returns the approval id to show the operator
+    pub fn submit(&self, opportunity: &ArbitrageOpportunity, now_ms: u64) -> Option<u64> {
+        if self.config.mode != ExecutionMode::Manual || opportunity.profit_percentage < self.config.min_profit {
+            return None;
+        }
+        let expires_at_ms = now_ms + self.config.ttl.as_millis() as u64;
+        let mut queue = self.queue.lock().unwrap();
+        queue.pending.retain(|_, pending| now_ms < pending.expires_at_ms);
+        
+        if let Some(pending) = queue.pending.values_mut().find(|p| p.opportunity.path == opportunity.path) {
+            pending.opportunity = opportunity.clone();
+            pending.expires_at_ms = expires_at_ms;
+            return Some(pending.id);
+        }
+        
+        let id = queue.next_id;
+        queue.next_id += 1;
+        queue.pending.insert(
+            id,
+            PendingApproval {
+                id,
+                opportunity: opportunity.clone(),
+                queued_at_ms: now_ms,
+                expires_at_ms,
+            },
+        );
+        Some(id)
+    }
+    
+    /// Take approval `id` for execution
+    pub fn approve(&self, id: u64, now_ms: u64) -> Result<ArbitrageOpportunity, ApprovalError> {
+        let pending = self.take(id)?;
+        if now_ms >= pending.expires_at_ms {
+            return Err(ApprovalError::Expired(id));
+        }
+        Ok(pending.opportunity)
+    }
+    
+    pub fn reject(&self, id: u64) -> Result<ArbitrageOpportunity, ApprovalError> {
+        self.take(id).map(|pending| pending.opportunity)
+    }
+    
+    /// Unexpired approvals, oldest first
+    pub fn pending(&self, now_ms: u64) -> Vec<PendingApproval> {
+        let mut queue = self.queue.lock().unwrap();
+        queue.pending.retain(|_, pending| now_ms < pending.expires_at_ms);
+        queue.pending.values().cloned().collect()
+    }
+    
+    fn take(&self, id: u64) -> Result<PendingApproval, ApprovalError> {
+        self.queue
+            .lock()
+            .unwrap()
+            .pending
+            .remove(&id)
+            .ok_or(ApprovalError::Unknown(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn manual() -> ApprovalQueue {
+        ApprovalQueue::new(ApprovalConfig {
+            mode: ExecutionMode::Manual,
+            ttl: Duration::from_secs(60),
+            min_profit: 0.002,
+        })
+    }
+    
+    #[test]
+    fn test_submit_dedupes_by_path_and_respects_mode() {
+        let queue = manual();
+        let a = ArbitrageOpportunity::sample("BTC_binance -> ETH_binance -> BTC_binance", 0.003);
+        
+        assert_eq!(queue.submit(&a, 0), Some(1));
+        assert_eq!(queue.submit(&ArbitrageOpportunity::sample(&a.path, 0.004), 1_000), Some(1));
+        assert_eq!(queue.submit(&ArbitrageOpportunity::sample("ETH_binance -> BTC_binance", 0.001), 1_000), None);
+        assert_eq!(queue.pending(1_000).len(), 1);
+        assert_eq!(queue.pending(1_000)[0].opportunity.profit_percentage, 0.004);
+        
+        let alert_only = ApprovalQueue::new(ApprovalConfig::default());
+        assert_eq!(alert_only.submit(&a, 0), None);
+    }
+    
+    #[test]
+    fn test_approve_reject_and_expiry() {
+        let queue = manual();
+        let first = queue.submit(&ArbitrageOpportunity::sample("A_x -> B_x", 0.01), 0).unwrap();
+        let second = queue.submit(&ArbitrageOpportunity::sample("B_x -> C_x", 0.01), 0).unwrap();
+        let third = queue.submit(&ArbitrageOpportunity::sample("C_x -> D_x", 0.01), 0).unwrap();
+        
+        assert_eq!(queue.approve(first, 59_000).unwrap().path, "A_x -> B_x");
+        assert_eq!(queue.approve(first, 59_000).err(), Some(ApprovalError::Unknown(first)));
+        assert!(queue.reject(second).is_ok());
+        assert_eq!(queue.approve(third, 60_000).err(), Some(ApprovalError::Expired(third)));
+        assert!(queue.pending(0).is_empty());
+    }
+}
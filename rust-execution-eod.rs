@@ -0,0 +1,307 @@
+// execution/eod.rs - End-of-day flattening of residual inventory and daily summary
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::arbitrage::ArbitrageEngine;
+use super::journal::ExecutionJournal;
+use super::orders::{NewOrder, OrderEvent, OrderLeg, OrderStore};
+use super::router::{RouteOutcome, SmartOrderRouter};
+use super::types::Side;
+
+const DAY_MS: u64 = 86_400_000;
+
+/// Opportunity path recorded on flattening orders
+const FLATTEN_PATH: &str = "end-of-day flatten";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndOfDayConfig {
+    pub enabled: bool,
+    /// UTC time of day to flatten, "HH:MM"; the trading day runs from one flatten to the next
+    pub at: String,
+    /// Residual inventory is sold into or bought back with this asset
+    pub settle_asset: String,
+}
+
+impl Default for EndOfDayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            at: "23:55".to_string(),
+            settle_asset: "USDT".to_string(),
+        }
+    }
+}
+
+/// Minutes past midnight for "HH:MM"
+pub fn parse_time_of_day(at: &str) -> Option<u64> {
+    let (hours, minutes) = at.trim().split_once(':')?;
+    let (hours, minutes): (u64, u64) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+/// First occurrence of `minutes` past midnight UTC strictly after `now_ms`
+fn next_run_ms(now_ms: u64, minutes: u64) -> u64 {
+    let run = now_ms - now_ms % DAY_MS + minutes * 60_000;
+    if run > now_ms { run } else { run + DAY_MS }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FlattenOrder {
+    pub exchange: String,
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: f64,
+    pub price: f64,
+    pub filled: bool,
+}
+
+/// Holding left over after flattening, e.g. an asset with no settle-asset market
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Residual {
+    pub exchange: String,
+    pub asset: String,
+    pub quantity: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DailySummary {
+    pub since_ms: u64,
+    pub until_ms: u64,
+    pub paper_trades: usize,
+    pub expected_profit: f64,
+    pub legs_filled: usize,
+    pub flatten_orders: Vec<FlattenOrder>,
+    pub residuals: Vec<Residual>,
+    /// Net change in the settle asset over the day, flattening included;
+    /// residual holdings aren't marked to market
+    pub realized_pnl: f64,
+}
+
+/// Net change per (exchange, asset) from what `legs` filled
+fn inventory_changes(legs: &[OrderLeg]) -> BTreeMap<(String, String), f64> {
+    let mut changes: BTreeMap<(String, String), f64> = BTreeMap::new();
+    for leg in legs {
+        let Some((base, quote)) = leg.symbol.split_once('/') else { continue };
+        let value = leg.filled_quantity * leg.avg_fill_price.unwrap_or(0.0);
+        let (base_change, quote_change) = match leg.side {
+            Side::Buy => (leg.filled_quantity, -value),
+            Side::Sell => (-leg.filled_quantity, value),
+        };
+        *changes.entry((leg.exchange.clone(), base.to_string())).or_default() += base_change;
+        *changes.entry((leg.exchange.clone(), quote.to_string())).or_default() += quote_change;
+    }
+    changes
+}
+
+/// Trades inventory that arbitrage left behind back to what was held at the
+/// start of the day, for operators who don't want overnight exposure. Each
+/// exchange's net change in an asset is sold into, or bought back with, the
+/// settle asset at the current quote
+pub struct EndOfDay {
+    config: EndOfDayConfig,
+    engine: Arc<ArbitrageEngine>,
+    orders: Arc<OrderStore>,
+    router: Arc<SmartOrderRouter>,
+    journal: Arc<ExecutionJournal>,
+}
+
+impl EndOfDay {
+    pub fn new(
+        config: EndOfDayConfig,
+        engine: Arc<ArbitrageEngine>,
+        orders: Arc<OrderStore>,
+        router: Arc<SmartOrderRouter>,
+        journal: Arc<ExecutionJournal>,
+    ) -> Self {
+        Self { config, engine, orders, router, journal }
+    }
+    
+    /// Flatten and log the summary at `config.at` every day
+    pub async fn run(self: Arc<Self>) {
+        let Some(minutes) = parse_time_of_day(&self.config.at) else {
+            warn!("end_of_day.at {:?} is not HH:MM; not flattening", self.config.at);
+            return;
+        };
+        let now = unix_ms();
+        let mut since = next_run_ms(now, minutes).saturating_sub(DAY_MS);
+        loop {
+            let now = unix_ms();
+            tokio::time::sleep(Duration::from_millis(next_run_ms(now, minutes) - now)).await;
+            let summary = self.flatten(since, unix_ms()).await;
+            log_summary(&summary, &self.config.settle_asset);
+            // The next day starts after this flatten, so its orders aren't flattened again
+            since = unix_ms() + 1;
+        }
+    }
+    
+    /// Flatten what legs filled since `since_ms` changed, and summarize the day
+    pub async fn flatten(&self, since_ms: u64, now_ms: u64) -> DailySummary {
+        let settle = self.config.settle_asset.as_str();
+        let changes = inventory_changes(&self.orders.filled_since(since_ms));
+        let mut flatten_orders = Vec::new();
+        let mut residuals = Vec::new();
+        
+        for ((exchange, asset), quantity) in changes {
+            if asset == settle || quantity.abs() < 1e-12 {
+                continue;
+            }
+            let symbol = format!("{}/{}", asset, settle);
+            let side = if quantity > 0.0 { Side::Sell } else { Side::Buy };
+            let quote = self
+                .engine
+                .get_market_snapshot(&symbol)
+                .await
+                .and_then(|snapshot| snapshot.quotes.into_iter().find(|quote| quote.exchange == exchange));
+            let price = quote.map(|quote| if side == Side::Sell { quote.bid } else { quote.ask }).filter(|price| *price > 0.0);
+            let Some(price) = price else {
+                residuals.push(Residual { exchange, asset, quantity });
+                continue;
+            };
+            
+            let order = NewOrder {
+                opportunity_path: FLATTEN_PATH.to_string(),
+                exchange: exchange.clone(),
+                symbol: symbol.clone(),
+                side,
+                quantity: quantity.abs(),
+                limit_price: Some(price),
+            };
+            let id = self.orders.create(order, now_ms);
+            if let Err(e) = self.orders.apply(id, OrderEvent::Submitted, now_ms) {
+                warn!("Flatten order #{}: {}", id, e);
+            }
+            let outcome = self.router.execute(id, now_ms).await;
+            let filled = matches!(outcome, RouteOutcome::Filled | RouteOutcome::Completed { .. });
+            if !filled {
+                warn!("Flattening {} {} on {}: {:?}", quantity, asset, exchange, outcome);
+                let left = self.orders.get(id).map_or(quantity.abs(), |leg| leg.quantity - leg.filled_quantity);
+                residuals.push(Residual { exchange: exchange.clone(), asset, quantity: left * quantity.signum() });
+            }
+            flatten_orders.push(FlattenOrder { exchange, symbol, side, quantity: quantity.abs(), price, filled });
+        }
+        
+        let day = self.orders.filled_since(since_ms);
+        let realized_pnl = inventory_changes(&day)
+            .into_iter()
+            .filter(|((_, asset), _)| asset == settle)
+            .map(|(_, change)| change)
+            .sum();
+        let trades: Vec<_> = self.journal.paper_trades().into_iter().filter(|trade| trade.executed_at_ms >= since_ms).collect();
+        DailySummary {
+            since_ms,
+            until_ms: now_ms,
+            paper_trades: trades.len(),
+            expected_profit: trades.iter().map(|trade| trade.expected_profit).sum(),
+            legs_filled: day.iter().filter(|leg| leg.opportunity_path != FLATTEN_PATH).count(),
+            flatten_orders,
+            residuals,
+            realized_pnl,
+        }
+    }
+}
+
+fn log_summary(summary: &DailySummary, settle: &str) {
+    info!(
+        "End of day: {} paper trades ({:.2} expected), {} legs filled, {} flatten orders, realized {:+.2} {}",
+        summary.paper_trades,
+        summary.expected_profit,
+        summary.legs_filled,
+        summary.flatten_orders.len(),
+        summary.realized_pnl,
+        settle
+    );
+    for residual in &summary.residuals {
+        warn!("  Still holding {:+} {} on {} overnight", residual.quantity, residual.asset, residual.exchange);
+    }
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrage::{Config, PriceUpdate};
+    use crate::execution::orders::{OrderVenue, PaperVenue};
+    use crate::execution::router::RouterConfig;
+    
+    #[test]
+    fn test_schedule() {
+        assert_eq!(parse_time_of_day("23:55"), Some(23 * 60 + 55));
+        assert_eq!(parse_time_of_day("24:00"), None);
+        assert_eq!(parse_time_of_day("noon"), None);
+        
+        let day = 20_000 * DAY_MS;
+        assert_eq!(next_run_ms(day + 60_000, 23 * 60), day + 23 * 3_600_000);
+        assert_eq!(next_run_ms(day + 23 * 3_600_000, 23 * 60), day + DAY_MS + 23 * 3_600_000);
+    }
+    
+    #[tokio::test]
+    async fn test_flattens_residual_inventory_to_settle_asset() {
+        let engine = Arc::new(ArbitrageEngine::new(Config::default()));
+        engine.start().await;
+        let update = |exchange: &str, symbol: &str, bid: f64, ask: f64| PriceUpdate {
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            bid,
+            ask,
+            volume: 1.0,
+        };
+        engine
+            .update_prices(&[
+                update("binance", "BTC/USDT", 50_050.0, 50_060.0),
+                update("kraken", "BTC/USDT", 50_090.0, 50_100.0),
+                update("binance", "ETH/USDT", 1_990.0, 1_991.0),
+            ])
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        
+        let path = std::env::temp_dir().join(format!("eod-orders-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let orders = Arc::new(OrderStore::open(path.clone()));
+        let filled = |exchange: &str, symbol: &str, side: Side, quantity: f64, price: f64| {
+            let order = NewOrder {
+                opportunity_path: "USDT_binance -> BTC_binance -> BTC_kraken -> USDT_kraken".to_string(),
+                exchange: exchange.to_string(),
+                symbol: symbol.to_string(),
+                side,
+                quantity,
+                limit_price: Some(price),
+            };
+            let id = orders.create(order, 1_000);
+            orders.apply(id, OrderEvent::Submitted, 1_000).unwrap();
+            orders.apply(id, OrderEvent::Filled { quantity, price }, 1_000).unwrap();
+        };
+        filled("binance", "BTC/USDT", Side::Buy, 0.02, 50_000.0);
+        filled("kraken", "BTC/USDT", Side::Sell, 0.02, 50_100.0);
+        filled("binance", "ETH/USDT", Side::Buy, 0.5, 2_000.0);
+        filled("binance", "SOL/USDT", Side::Buy, 1.0, 100.0); // No SOL quote to flatten at
+        
+        let venues: Vec<Arc<dyn OrderVenue>> = vec![Arc::new(PaperVenue::new("binance")), Arc::new(PaperVenue::new("kraken"))];
+        let router = Arc::new(SmartOrderRouter::new(RouterConfig::default(), orders.clone(), venues));
+        let eod = EndOfDay::new(EndOfDayConfig::default(), engine, orders, router, Arc::new(ExecutionJournal::new()));
+        let summary = eod.flatten(0, 2_000).await;
+        
+        let flattened: Vec<(&str, &str, Side, f64)> =
+            summary.flatten_orders.iter().map(|o| (o.exchange.as_str(), o.symbol.as_str(), o.side, o.price)).collect();
+        assert_eq!(
+            flattened,
+            vec![
+                ("binance", "BTC/USDT", Side::Sell, 50_050.0),
+                ("binance", "ETH/USDT", Side::Sell, 1_990.0),
+                ("kraken", "BTC/USDT", Side::Buy, 50_100.0),
+            ]
+        );
+        assert!(summary.flatten_orders.iter().all(|o| o.filled));
+        assert_eq!(summary.residuals, vec![Residual { exchange: "binance".to_string(), asset: "SOL".to_string(), quantity: 1.0 }]);
+        assert_eq!(summary.legs_filled, 4);
+        // -1000 + 1002 - 1000 - 100 for the day's legs, +1001 + 995 - 1002 flattening
+        assert!((summary.realized_pnl + 104.0).abs() < 1e-6, "{}", summary.realized_pnl);
+        let _ = std::fs::remove_file(&path);
+    }
+}
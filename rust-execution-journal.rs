@@ -0,0 +1,54 @@
+// execution/journal.rs - In-memory record of executed fills
+use std::sync::RwLock;
+
+use super::types::{ExecutionRecord, Fill, PaperTrade};
+
+/// Append-only store of fills and execution outcomes, shared by reporting endpoints
+pub struct ExecutionJournal {
+    fills: RwLock<Vec<Fill>>,
+    executions: RwLock<Vec<ExecutionRecord>>,
+    paper_trades: RwLock<Vec<PaperTrade>>,
+}
+
+impl ExecutionJournal {
+    pub fn new() -> Self {
+        Self {
+            fills: RwLock::new(Vec::new()),
+            executions: RwLock::new(Vec::new()),
+            paper_trades: RwLock::new(Vec::new()),
+        }
+    }
+    
+    pub fn record_fill(&self, fill: Fill) {
+        self.fills.write().unwrap().push(fill);
+    }
+    
+    pub fn record_execution(&self, record: ExecutionRecord) {
+        self.executions.write().unwrap().push(record);
+    }
+    
+    pub fn executions(&self) -> Vec<ExecutionRecord> {
+        self.executions.read().unwrap().clone()
+    }
+    
+    pub fn record_paper_trade(&self, trade: PaperTrade) {
+        self.paper_trades.write().unwrap().push(trade);
+    }
+    
+    pub fn paper_trades(&self) -> Vec<PaperTrade> {
+        self.paper_trades.read().unwrap().clone()
+    }
+    
+    /// All fills in execution order
+    pub fn fills(&self) -> Vec<Fill> {
+        let mut fills = self.fills.read().unwrap().clone();
+        fills.sort_by_key(|f| f.timestamp_ms);
+        fills
+    }
+}
+
+impl Default for ExecutionJournal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,174 @@
+// execution/locks.rs - In-flight locking so overlapping opportunities never execute concurrently
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+use crate::arbitrage::types::ArbitrageOpportunity;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Fail at once if another execution holds any of the same inventory
+    Reject,
+    /// Wait for conflicting executions to finish, up to `queue_timeout`
+    #[default]
+    Queue,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockConfig {
+    pub on_conflict: ConflictPolicy,
+    #[serde(with = "humantime_serde")]
+    pub queue_timeout: Duration,
+}
+
+impl Default for LockConfig {
+    fn default() -> Self {
+        Self {
+            on_conflict: ConflictPolicy::Queue,
+            queue_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LockError {
+    /// `resource` is held by the execution of `holder`
+    Conflict { resource: String, holder: String },
+    TimedOut { resource: String, holder: String },
+}
+
+impl fmt::Display for LockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockError::Conflict { resource, holder } => write!(f, "{} is in use by in-flight {}", resource, holder),
+            LockError::TimedOut { resource, holder } => {
+                write!(f, "timed out waiting for {} (in use by in-flight {})", resource, holder)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LockError {}
+
+/// Inventory each in-flight execution is using, keyed by node ("BTC_binance").
+///
+/// A leg trades out of the inventory at its source node, and two
+/// opportunities sharing a leg always share its nodes, so locking every node
+/// of a cycle covers both shared edges and shared inventory. Locks are taken
+/// all at once, so executions can't deadlock on each other.
+pub struct ExecutionLocks {
+    config: LockConfig,
+    held: Mutex<HashMap<String, String>>, // Node -> path of the execution holding it
+    released: Notify,
+}
+
+/// Releases its execution's locks on drop
+pub struct ExecutionGuard<'a> {
+    locks: &'a ExecutionLocks,
+    nodes: Vec<String>,
+}
+
+impl Drop for ExecutionGuard<'_> {
+    fn drop(&mut self) {
+        let mut held = self.locks.held.lock().unwrap();
+        for node in &self.nodes {
+            held.remove(node);
+        }
+        drop(held);
+        self.locks.released.notify_waiters();
+    }
+}
+
+impl ExecutionLocks {
+    pub fn new(config: LockConfig) -> Self {
+        Self {
+            config,
+            held: Mutex::new(HashMap::new()),
+            released: Notify::new(),
+        }
+    }
+    
+    /// Lock every node of `opportunity`, rejecting or queueing on conflict per the config
+    pub async fn acquire(&self, opportunity: &ArbitrageOpportunity) -> Result<ExecutionGuard<'_>, LockError> {
+        let mut nodes: Vec<String> = opportunity.path.split(" -> ").map(str::to_string).collect();
+        nodes.sort();
+        nodes.dedup();
+        let deadline = Instant::now() + self.config.queue_timeout;
+        
+        loop {
+            // Register for wakeups before checking, so a release in between isn't missed
+            let released = self.released.notified();
+            tokio::pin!(released);
+            released.as_mut().enable();
+            
+            let (resource, holder) = match self.try_acquire(&opportunity.path, &nodes) {
+                Ok(guard) => return Ok(guard),
+                Err(conflict) => conflict,
+            };
+            if self.config.on_conflict == ConflictPolicy::Reject {
+                return Err(LockError::Conflict { resource, holder });
+            }
+            if tokio::time::timeout_at(deadline, released).await.is_err() {
+                return Err(LockError::TimedOut { resource, holder });
+            }
+        }
+    }
+    
+    fn try_acquire(&self, path: &str, nodes: &[String]) -> Result<ExecutionGuard<'_>, (String, String)> {
+        let mut held = self.held.lock().unwrap();
+        if let Some((node, holder)) = nodes.iter().find_map(|node| held.get(node).map(|holder| (node, holder))) {
+            return Err((node.clone(), holder.clone()));
+        }
+        for node in nodes {
+            held.insert(node.clone(), path.to_string());
+        }
+        Ok(ExecutionGuard { locks: self, nodes: nodes.to_vec() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    
+    #[tokio::test]
+    async fn test_rejects_overlapping_and_allows_disjoint() {
+        let locks = ExecutionLocks::new(LockConfig { on_conflict: ConflictPolicy::Reject, ..LockConfig::default() });
+        let first = ArbitrageOpportunity::sample("BTC_binance -> ETH_binance -> USDT_binance", 0.01);
+        let guard = locks.acquire(&first).await.unwrap();
+        
+        let overlapping = locks.acquire(&ArbitrageOpportunity::sample("ETH_binance -> SOL_binance -> USDT_kraken", 0.01)).await;
+        assert_eq!(
+            overlapping.err(),
+            Some(LockError::Conflict { resource: "ETH_binance".to_string(), holder: first.path.clone() })
+        );
+        assert!(locks.acquire(&ArbitrageOpportunity::sample("BTC_kraken -> ETH_kraken -> USDT_kraken", 0.01)).await.is_ok());
+        
+        drop(guard);
+        assert!(locks.acquire(&ArbitrageOpportunity::sample("ETH_binance -> SOL_binance -> USDT_kraken", 0.01)).await.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_queued_execution_runs_after_release_or_times_out() {
+        let locks = Arc::new(ExecutionLocks::new(LockConfig { queue_timeout: Duration::from_millis(200), ..LockConfig::default() }));
+        let guard = locks.acquire(&ArbitrageOpportunity::sample("BTC_binance -> ETH_binance", 0.01)).await.unwrap();
+        
+        let waiter = {
+            let locks = locks.clone();
+            tokio::spawn(async move { locks.acquire(&ArbitrageOpportunity::sample("ETH_binance -> BTC_binance", 0.01)).await.map(|_| ()) })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+        drop(guard);
+        assert!(waiter.await.unwrap().is_ok());
+        
+        let _held = locks.acquire(&ArbitrageOpportunity::sample("BTC_binance -> ETH_binance", 0.01)).await.unwrap();
+        let timed_out = locks.acquire(&ArbitrageOpportunity::sample("ETH_binance -> SOL_binance", 0.01)).await;
+        assert!(matches!(timed_out, Err(LockError::TimedOut { .. })));
+    }
+}
@@ -0,0 +1,14 @@
+// execution/mod.rs - Executed trade records and reporting
+pub mod analytics;
+pub mod approval;
+pub mod eod;
+pub mod journal;
+pub mod locks;
+pub mod orders;
+pub mod report;
+pub mod router;
+pub mod sweeper;
+pub mod types;
+
+pub use journal::ExecutionJournal;
+pub use types::{ExecutionRecord, Fill, PaperTrade, Side};
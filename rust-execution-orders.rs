@@ -0,0 +1,524 @@
+// execution/orders.rs - Persisted order-leg state machine with restart reconciliation
+use std::fmt;
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::types::Side;
+
+/// created -> submitted -> acked -> partially_filled -> filled | cancelled | rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderState {
+    Created,
+    Submitted,
+    Acked,
+    PartiallyFilled,
+    Filled,
+    Cancelled,
+    Rejected,
+}
+
+impl OrderState {
+    pub fn is_terminal(self) -> bool {
+        matches!(self, OrderState::Filled | OrderState::Cancelled | OrderState::Rejected)
+    }
+}
+
+impl fmt::Display for OrderState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            OrderState::Created => "created",
+            OrderState::Submitted => "submitted",
+            OrderState::Acked => "acked",
+            OrderState::PartiallyFilled => "partially filled",
+            OrderState::Filled => "filled",
+            OrderState::Cancelled => "cancelled",
+            OrderState::Rejected => "rejected",
+        };
+        f.write_str(name)
+    }
+}
+
+/// What happened to a leg, from the executor or the venue
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderEvent {
+    Submitted,
+    Acked { exchange_order_id: String },
+    /// `quantity` more base units filled at `price`
+    Filled { quantity: f64, price: f64 },
+    Cancelled { reason: String },
+    Rejected { reason: String },
+}
+
+/// A leg to place: sell or buy `quantity` of `symbol`'s base asset
+#[derive(Debug, Clone)]
+pub struct NewOrder {
+    pub opportunity_path: String,
+    pub exchange: String,
+    pub symbol: String, // BASE/QUOTE
+    pub side: Side,
+    pub quantity: f64,
+    pub limit_price: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderLeg {
+    pub id: u64,
+    /// Sent with the order so the venue can be asked about it after a restart
+    pub client_order_id: String,
+    pub exchange_order_id: Option<String>,
+    pub opportunity_path: String,
+    pub exchange: String,
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: f64,
+    pub limit_price: Option<f64>,
+    pub filled_quantity: f64,
+    pub avg_fill_price: Option<f64>,
+    pub state: OrderState,
+    /// Why it was cancelled or rejected
+    pub reason: Option<String>,
+    pub created_at_ms: u64,
+    pub updated_at_ms: u64,
+}
+
+impl OrderLeg {
+    fn remaining(&self) -> f64 {
+        (self.quantity - self.filled_quantity).max(0.0)
+    }
+    
+    /// Apply `event`, or leave the leg untouched if the machine doesn't allow it
+    fn apply(&mut self, event: &OrderEvent, now_ms: u64) -> Result<OrderState, OrderError> {
+        use OrderState::*;
+        let next = match (self.state, event) {
+            (Created, OrderEvent::Submitted) => Submitted,
+            (Submitted, OrderEvent::Acked { .. }) => Acked,
+            // Fills can overtake the ack on some venues' streams
+            (Submitted | Acked | PartiallyFilled, OrderEvent::Filled { quantity, .. }) if *quantity > 0.0 => {
+                if *quantity >= self.remaining() * (1.0 - 1e-9) {
+                    Filled
+                } else {
+                    PartiallyFilled
+                }
+            }
+            (Created | Submitted | Acked | PartiallyFilled, OrderEvent::Cancelled { .. }) => Cancelled,
+            (Created | Submitted, OrderEvent::Rejected { .. }) => Rejected,
+            (from, event) => return Err(OrderError::InvalidTransition { id: self.id, from, event: event.clone() }),
+        };
+        
+        match event {
+            OrderEvent::Acked { exchange_order_id } => self.exchange_order_id = Some(exchange_order_id.clone()),
+            OrderEvent::Filled { quantity, price } => {
+                let quantity = quantity.min(self.remaining());
+                let filled_value = self.avg_fill_price.unwrap_or(0.0) * self.filled_quantity + price * quantity;
+                self.filled_quantity += quantity;
+                self.avg_fill_price = Some(filled_value / self.filled_quantity);
+            }
+            OrderEvent::Cancelled { reason } | OrderEvent::Rejected { reason } => self.reason = Some(reason.clone()),
+            OrderEvent::Submitted => {}
+        }
+        self.state = next;
+        self.updated_at_ms = now_ms;
+        Ok(next)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderError {
+    Unknown(u64),
+    InvalidTransition { id: u64, from: OrderState, event: OrderEvent },
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderError::Unknown(id) => write!(f, "no order #{}", id),
+            OrderError::InvalidTransition { id, from, event } => write!(f, "order #{} is {}; can't apply {:?}", id, from, event),
+        }
+    }
+}
+
+impl std::error::Error for OrderError {}
+
+/// Where a venue says an order stands
+#[derive(Debug, Clone, PartialEq)]
+pub struct VenueOrder {
+    pub exchange_order_id: String,
+    pub filled_quantity: f64,
+    pub avg_fill_price: f64,
+    /// Whether the venue still has it working
+    pub open: bool,
+    pub cancelled: bool,
+}
+
+pub type VenueFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, String>> + Send + 'a>>;
+
+/// Order entry and lookups on one exchange
+pub trait OrderVenue: Send + Sync {
+    fn exchange(&self) -> &str;
+    
+    /// The venue's view of `leg` by client order id; `None` if it never received it
+    fn order_status<'a>(&'a self, leg: &'a OrderLeg) -> VenueFuture<'a, Option<VenueOrder>>;
+    
+    /// Send a created leg; the venue's view of it right after
+    fn place<'a>(&'a self, leg: &'a OrderLeg) -> VenueFuture<'a, VenueOrder>;
+    
+    fn cancel<'a>(&'a self, leg: &'a OrderLeg) -> VenueFuture<'a, ()>;
+}
+
+/// The only executor is paper trading, whose orders exist nowhere but the
+/// store: anything still in flight at shutdown never reached a market
+pub struct PaperVenue {
+    exchange: String,
+}
+
+impl PaperVenue {
+    pub fn new(exchange: &str) -> Self {
+        Self { exchange: exchange.to_string() }
+    }
+}
+
+impl OrderVenue for PaperVenue {
+    fn exchange(&self) -> &str {
+        &self.exchange
+    }
+    
+    fn order_status<'a>(&'a self, _leg: &'a OrderLeg) -> VenueFuture<'a, Option<VenueOrder>> {
+        Box::pin(async { Ok(None) })
+    }
+    
+    /// Fills in full at the leg's limit price
+    fn place<'a>(&'a self, leg: &'a OrderLeg) -> VenueFuture<'a, VenueOrder> {
+        Box::pin(async move {
+            Ok(VenueOrder {
+                exchange_order_id: format!("paper-{}", leg.id),
+                filled_quantity: leg.quantity,
+                avg_fill_price: leg.limit_price.unwrap_or_default(),
+                open: false,
+                cancelled: false,
+            })
+        })
+    }
+    
+    fn cancel<'a>(&'a self, _leg: &'a OrderLeg) -> VenueFuture<'a, ()> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Outcome of reconciling recovered legs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReconcileSummary {
+    /// Still working at the venue; tracked again
+    pub resumed: usize,
+    /// Brought to the venue's terminal state
+    pub closed: usize,
+    /// No venue to ask, or the lookup failed; left as they were
+    pub unresolved: usize,
+}
+
+/// In-flight legs and the id counter, as a primary hands them to its standby
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrderHandoff {
+    pub next_id: u64,
+    pub legs: Vec<OrderLeg>,
+}
+
+/// On-disk form of the store
+#[derive(Default, Serialize, Deserialize)]
+struct StoredOrders {
+    next_id: u64,
+    orders: Vec<OrderLeg>,
+}
+
+/// Every leg and its state, rewritten to `path` on each transition
+pub struct OrderStore {
+    path: PathBuf,
+    state: Mutex<StoredOrders>,
+}
+
+impl OrderStore {
+    /// Store backed by `path`, keeping the legs a previous run left in flight;
+    /// finished legs are already in the journal and are dropped
+    pub fn open(path: PathBuf) -> Self {
+        let mut stored = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable order store {}: {}", path.display(), e);
+                StoredOrders::default()
+            }),
+            Err(_) => StoredOrders::default(),
+        };
+        stored.orders.retain(|leg| !leg.state.is_terminal());
+        if !stored.orders.is_empty() {
+            info!("Recovered {} in-flight order legs from {}", stored.orders.len(), path.display());
+        }
+        
+        Self { path, state: Mutex::new(stored) }
+    }
+    
+    pub fn create(&self, order: NewOrder, now_ms: u64) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.orders.push(OrderLeg {
+            id,
+            client_order_id: format!("arb-{}-{}", now_ms, id),
+            exchange_order_id: None,
+            opportunity_path: order.opportunity_path,
+            exchange: order.exchange,
+            symbol: order.symbol,
+            side: order.side,
+            quantity: order.quantity,
+            limit_price: order.limit_price,
+            filled_quantity: 0.0,
+            avg_fill_price: None,
+            state: OrderState::Created,
+            reason: None,
+            created_at_ms: now_ms,
+            updated_at_ms: now_ms,
+        });
+        self.persist(&state);
+        id
+    }
+    
+    pub fn apply(&self, id: u64, event: OrderEvent, now_ms: u64) -> Result<OrderState, OrderError> {
+        let mut state = self.state.lock().unwrap();
+        let leg = state.orders.iter_mut().find(|leg| leg.id == id).ok_or(OrderError::Unknown(id))?;
+        let next = leg.apply(&event, now_ms)?;
+        self.persist(&state);
+        Ok(next)
+    }
+    
+    pub fn get(&self, id: u64) -> Option<OrderLeg> {
+        self.state.lock().unwrap().orders.iter().find(|leg| leg.id == id).cloned()
+    }
+    
+    /// Every leg of the opportunity on `path`, finished or not
+    pub fn legs_for(&self, path: &str) -> Vec<OrderLeg> {
+        self.state.lock().unwrap().orders.iter().filter(|leg| leg.opportunity_path == path).cloned().collect()
+    }
+    
+    /// Legs with any fill that changed since `since_ms`
+    pub fn filled_since(&self, since_ms: u64) -> Vec<OrderLeg> {
+        self.state
+            .lock()
+            .unwrap()
+            .orders
+            .iter()
+            .filter(|leg| leg.filled_quantity > 0.0 && leg.updated_at_ms >= since_ms)
+            .cloned()
+            .collect()
+    }
+    
+    /// Legs not yet filled, cancelled or rejected
+    pub fn in_flight(&self) -> Vec<OrderLeg> {
+        self.state.lock().unwrap().orders.iter().filter(|leg| !leg.state.is_terminal()).cloned().collect()
+    }
+    
+    /// What a standby needs to carry on from here
+    pub fn handoff(&self) -> OrderHandoff {
+        let state = self.state.lock().unwrap();
+        OrderHandoff {
+            next_id: state.next_id,
+            legs: state.orders.iter().filter(|leg| !leg.state.is_terminal()).cloned().collect(),
+        }
+    }
+    
+    /// Replace the legs with the primary's. Persisted, so a standby restarted
+    /// before it takes over still has them; `reconcile` then settles them
+    pub fn adopt(&self, handoff: OrderHandoff) {
+        let mut state = self.state.lock().unwrap();
+        let next_id = state.next_id.max(handoff.next_id);
+        if state.next_id == next_id && state.orders == handoff.legs {
+            return;
+        }
+        state.next_id = next_id;
+        state.orders = handoff.legs;
+        self.persist(&state);
+    }
+    
+    /// Bring every in-flight leg in line with its venue before execution resumes
+    pub async fn reconcile(&self, venues: &[Arc<dyn OrderVenue>], now_ms: u64) -> ReconcileSummary {
+        let mut summary = ReconcileSummary::default();
+        for leg in self.in_flight() {
+            let Some(venue) = venues.iter().find(|venue| venue.exchange() == leg.exchange) else {
+                warn!("No venue to reconcile order #{} on {}; leaving it {}", leg.id, leg.exchange, leg.state);
+                summary.unresolved += 1;
+                continue;
+            };
+            let events = match venue.order_status(&leg).await {
+                Ok(status) => reconcile_events(&leg, status),
+                Err(e) => {
+                    warn!("Order #{} lookup on {} failed, leaving it {}: {}", leg.id, leg.exchange, leg.state, e);
+                    summary.unresolved += 1;
+                    continue;
+                }
+            };
+            
+            for event in events {
+                if let Err(e) = self.apply(leg.id, event, now_ms) {
+                    warn!("Reconciling order #{}: {}", leg.id, e);
+                }
+            }
+            let state = self.get(leg.id).map_or(leg.state, |reconciled| reconciled.state);
+            if state.is_terminal() {
+                summary.closed += 1;
+            } else {
+                summary.resumed += 1;
+            }
+            info!("Reconciled order #{} ({} {}): {} -> {}", leg.id, leg.exchange, leg.symbol, leg.state, state);
+        }
+        summary
+    }
+    
+    /// Write-then-rename so a crash never leaves a truncated store
+    fn persist(&self, state: &StoredOrders) {
+        let tmp = self.path.with_extension("tmp");
+        let result = serde_json::to_vec(state)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| fs::write(&tmp, bytes).map_err(|e| e.to_string()))
+            .and_then(|()| fs::rename(&tmp, &self.path).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("Failed to persist order store to {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Events that take `leg` from its recorded state to the venue's
+pub(super) fn reconcile_events(leg: &OrderLeg, status: Option<VenueOrder>) -> Vec<OrderEvent> {
+    let Some(venue) = status else {
+        return vec![match leg.state {
+            OrderState::Created => OrderEvent::Cancelled { reason: "never submitted before restart".to_string() },
+            OrderState::Submitted => OrderEvent::Rejected { reason: "venue has no record of it".to_string() },
+            _ => OrderEvent::Cancelled { reason: "no longer known to the venue".to_string() },
+        }];
+    };
+    
+    let mut events = Vec::new();
+    if leg.state == OrderState::Created {
+        events.push(OrderEvent::Submitted);
+    }
+    if matches!(leg.state, OrderState::Created | OrderState::Submitted) {
+        events.push(OrderEvent::Acked { exchange_order_id: venue.exchange_order_id.clone() });
+    }
+    let missed = venue.filled_quantity - leg.filled_quantity;
+    if missed > 0.0 {
+        // Fills we didn't see; price them so the leg's average matches the venue's
+        let price = (venue.avg_fill_price * venue.filled_quantity - leg.avg_fill_price.unwrap_or(0.0) * leg.filled_quantity) / missed;
+        events.push(OrderEvent::Filled { quantity: missed, price });
+    }
+    if !venue.open && venue.filled_quantity < leg.quantity {
+        let reason = if venue.cancelled { "cancelled at venue" } else { "closed at venue" };
+        events.push(OrderEvent::Cancelled { reason: reason.to_string() });
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn order(exchange: &str) -> NewOrder {
+        NewOrder {
+            opportunity_path: "USDT_binance -> BTC_binance -> ETH_binance".to_string(),
+            exchange: exchange.to_string(),
+            symbol: "BTC/USDT".to_string(),
+            side: Side::Buy,
+            quantity: 2.0,
+            limit_price: Some(50_000.0),
+        }
+    }
+    
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}.json", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+    
+    #[test]
+    fn test_transitions_follow_the_machine() {
+        let store = OrderStore::open(temp_path("orders-machine"));
+        let id = store.create(order("binance"), 1);
+        
+        assert!(store.apply(id, OrderEvent::Acked { exchange_order_id: "x".to_string() }, 2).is_err());
+        assert_eq!(store.apply(id, OrderEvent::Submitted, 2), Ok(OrderState::Submitted));
+        assert_eq!(store.apply(id, OrderEvent::Acked { exchange_order_id: "42".to_string() }, 3), Ok(OrderState::Acked));
+        assert_eq!(store.apply(id, OrderEvent::Filled { quantity: 0.5, price: 50_000.0 }, 4), Ok(OrderState::PartiallyFilled));
+        assert_eq!(store.apply(id, OrderEvent::Filled { quantity: 1.5, price: 50_100.0 }, 5), Ok(OrderState::Filled));
+        
+        let leg = store.get(id).unwrap();
+        assert_eq!(leg.exchange_order_id.as_deref(), Some("42"));
+        assert!((leg.avg_fill_price.unwrap() - 50_075.0).abs() < 1e-9);
+        assert!(matches!(
+            store.apply(id, OrderEvent::Cancelled { reason: "late".to_string() }, 6),
+            Err(OrderError::InvalidTransition { from: OrderState::Filled, .. })
+        ));
+        assert!(store.in_flight().is_empty());
+    }
+    
+    struct FakeVenue(VenueOrder);
+    
+    impl OrderVenue for FakeVenue {
+        fn exchange(&self) -> &str {
+            "kraken"
+        }
+        
+        fn order_status<'a>(&'a self, _leg: &'a OrderLeg) -> VenueFuture<'a, Option<VenueOrder>> {
+            let status = self.0.clone();
+            Box::pin(async move { Ok(Some(status)) })
+        }
+        
+        fn place<'a>(&'a self, _leg: &'a OrderLeg) -> VenueFuture<'a, VenueOrder> {
+            let status = self.0.clone();
+            Box::pin(async move { Ok(status) })
+        }
+        
+        fn cancel<'a>(&'a self, _leg: &'a OrderLeg) -> VenueFuture<'a, ()> {
+            Box::pin(async { Ok(()) })
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_restart_recovers_and_reconciles_in_flight_legs() {
+        let path = temp_path("orders-recovery");
+        {
+            let store = OrderStore::open(path.clone());
+            let done = store.create(order("binance"), 1);
+            store.apply(done, OrderEvent::Submitted, 1).unwrap();
+            store.apply(done, OrderEvent::Filled { quantity: 2.0, price: 50_000.0 }, 2).unwrap();
+            let lost_ack = store.create(order("binance"), 3);
+            store.apply(lost_ack, OrderEvent::Submitted, 3).unwrap();
+            let working = store.create(order("kraken"), 4);
+            store.apply(working, OrderEvent::Submitted, 4).unwrap();
+            store.create(order("coinbase"), 5);
+        } // Crash
+        
+        let store = OrderStore::open(path.clone());
+        assert_eq!(store.in_flight().len(), 3);
+        let venues: Vec<Arc<dyn OrderVenue>> = vec![
+            Arc::new(PaperVenue::new("binance")),
+            Arc::new(FakeVenue(VenueOrder {
+                exchange_order_id: "K-9".to_string(),
+                filled_quantity: 0.5,
+                avg_fill_price: 49_990.0,
+                open: true,
+                cancelled: false,
+            })),
+        ];
+        
+        let summary = store.reconcile(&venues, 10).await;
+        assert_eq!(summary, ReconcileSummary { resumed: 1, closed: 1, unresolved: 1 });
+        
+        let legs = store.in_flight();
+        let kraken = legs.iter().find(|leg| leg.exchange == "kraken").unwrap();
+        assert_eq!((kraken.state, kraken.filled_quantity), (OrderState::PartiallyFilled, 0.5));
+        assert_eq!(kraken.exchange_order_id.as_deref(), Some("K-9"));
+        assert_eq!(legs.iter().find(|leg| leg.exchange == "coinbase").unwrap().state, OrderState::Created);
+        assert!(legs.iter().all(|leg| leg.exchange != "binance"));
+        let _ = fs::remove_file(&path);
+    }
+}
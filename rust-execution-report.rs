@@ -0,0 +1,265 @@
+// execution/report.rs - Fill export for tax and accounting tools
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+
+use super::types::{Fill, Side};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// One row per fill with FIFO cost basis and realized P&L in the quote asset
+    FifoCsv,
+    /// Koinly "universal" import layout
+    Koinly,
+    /// CoinTracking CSV import layout
+    CoinTracking,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" | "fifo" => Ok(ReportFormat::FifoCsv),
+            "koinly" => Ok(ReportFormat::Koinly),
+            "cointracking" => Ok(ReportFormat::CoinTracking),
+            other => Err(format!("unknown report format '{}'", other)),
+        }
+    }
+}
+
+pub fn export_fills(fills: &[Fill], format: ReportFormat) -> String {
+    match format {
+        ReportFormat::FifoCsv => fifo_csv(fills),
+        ReportFormat::Koinly => koinly_csv(fills),
+        ReportFormat::CoinTracking => cointracking_csv(fills),
+    }
+}
+
+struct Lot {
+    quantity: f64,
+    unit_cost: f64,
+}
+
+/// FIFO lots are tracked per BASE/QUOTE pair so cost basis stays in one currency
+fn fifo_csv(fills: &[Fill]) -> String {
+    let mut lots: HashMap<(String, String), VecDeque<Lot>> = HashMap::new();
+    let mut out = String::from(
+        "timestamp,exchange,symbol,side,quantity,price,fee,fee_asset,cost_basis,proceeds,realized_pnl,unmatched_quantity\n",
+    );
+    
+    for fill in fills {
+        let quote_fee = if fill.fee_asset == fill.quote_asset() { fill.fee } else { 0.0 };
+        let base_fee = if fill.fee_asset == fill.base_asset() { fill.fee } else { 0.0 };
+        let queue = lots
+            .entry((fill.base_asset().to_string(), fill.quote_asset().to_string()))
+            .or_default();
+        
+        let (cost_basis, proceeds, realized, unmatched) = match fill.side {
+            Side::Buy => {
+                let received = fill.quantity - base_fee;
+                let cost = fill.price * fill.quantity + quote_fee;
+                if received > 0.0 {
+                    queue.push_back(Lot {
+                        quantity: received,
+                        unit_cost: cost / received,
+                    });
+                }
+                (cost, 0.0, 0.0, 0.0)
+            }
+            Side::Sell => {
+                let proceeds = fill.price * fill.quantity - quote_fee;
+                let mut remaining = fill.quantity;
+                let mut basis = 0.0;
+                
+                while remaining > 1e-12 {
+                    let Some(lot) = queue.front_mut() else {
+                        break;
+                    };
+                    let used = lot.quantity.min(remaining);
+                    basis += used * lot.unit_cost;
+                    lot.quantity -= used;
+                    remaining -= used;
+                    if lot.quantity <= 1e-12 {
+                        queue.pop_front();
+                    }
+                }
+                
+                (basis, proceeds, proceeds - basis, remaining.max(0.0))
+            }
+        };
+        
+        out.push_str(&csv_row(&[
+            format_utc(fill.timestamp_ms),
+            fill.exchange.clone(),
+            fill.symbol.clone(),
+            format!("{:?}", fill.side).to_lowercase(),
+            fill.quantity.to_string(),
+            fill.price.to_string(),
+            fill.fee.to_string(),
+            fill.fee_asset.clone(),
+            format!("{:.8}", cost_basis),
+            format!("{:.8}", proceeds),
+            format!("{:.8}", realized),
+            format!("{:.8}", unmatched),
+        ]));
+    }
+    
+    out
+}
+
+fn koinly_csv(fills: &[Fill]) -> String {
+    let mut out = String::from(
+        "Date,Sent Amount,Sent Currency,Received Amount,Received Currency,Fee Amount,Fee Currency,Net Worth Amount,Net Worth Currency,Label,Description,TxHash\n",
+    );
+    
+    for fill in fills {
+        let notional = (fill.price * fill.quantity).to_string();
+        let quantity = fill.quantity.to_string();
+        let (sent, sent_currency, received, received_currency) = match fill.side {
+            Side::Buy => (notional, fill.quote_asset(), quantity, fill.base_asset()),
+            Side::Sell => (quantity, fill.base_asset(), notional, fill.quote_asset()),
+        };
+        
+        out.push_str(&csv_row(&[
+            format!("{} UTC", format_utc(fill.timestamp_ms)),
+            sent,
+            sent_currency.to_string(),
+            received,
+            received_currency.to_string(),
+            fill.fee.to_string(),
+            fill.fee_asset.clone(),
+            String::new(),
+            String::new(),
+            String::new(),
+            format!("{} arbitrage leg {}", fill.exchange, fill.order_id),
+            fill.fill_id.clone(),
+        ]));
+    }
+    
+    out
+}
+
+fn cointracking_csv(fills: &[Fill]) -> String {
+    let mut out = String::from(
+        "Type,Buy Amount,Buy Currency,Sell Amount,Sell Currency,Fee,Fee Currency,Exchange,Trade-Group,Comment,Date\n",
+    );
+    
+    for fill in fills {
+        let notional = (fill.price * fill.quantity).to_string();
+        let quantity = fill.quantity.to_string();
+        let (bought, bought_currency, sold, sold_currency) = match fill.side {
+            Side::Buy => (quantity, fill.base_asset(), notional, fill.quote_asset()),
+            Side::Sell => (notional, fill.quote_asset(), quantity, fill.base_asset()),
+        };
+        
+        out.push_str(&csv_row(&[
+            "Trade".to_string(),
+            bought,
+            bought_currency.to_string(),
+            sold,
+            sold_currency.to_string(),
+            fill.fee.to_string(),
+            fill.fee_asset.clone(),
+            fill.exchange.clone(),
+            "Arbitrage".to_string(),
+            fill.fill_id.clone(),
+            format_utc(fill.timestamp_ms),
+        ]));
+    }
+    
+    out
+}
+
+fn csv_row(fields: &[String]) -> String {
+    let escaped: Vec<String> = fields
+        .iter()
+        .map(|f| {
+            if f.contains(',') || f.contains('"') || f.contains('\n') {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f.clone()
+            }
+        })
+        .collect();
+    escaped.join(",") + "\n"
+}
+
+/// `YYYY-MM-DD HH:MM:SS` in UTC from Unix milliseconds (civil-from-days)
+fn format_utc(timestamp_ms: u64) -> String {
+    let secs = timestamp_ms / 1000;
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year, month, day, rem / 3600, rem % 3600 / 60, rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn fill(side: Side, price: f64, quantity: f64, fee: f64, timestamp_ms: u64) -> Fill {
+        Fill {
+            fill_id: format!("f{}", timestamp_ms),
+            order_id: "o1".to_string(),
+            exchange: "binance".to_string(),
+            symbol: "BTC/USDT".to_string(),
+            side,
+            price,
+            quantity,
+            fee,
+            fee_asset: "USDT".to_string(),
+            timestamp_ms,
+        }
+    }
+    
+    #[test]
+    fn test_fifo_realized_pnl() {
+        let fills = vec![
+            fill(Side::Buy, 100.0, 1.0, 0.0, 1000),
+            fill(Side::Buy, 200.0, 1.0, 0.0, 2000),
+            fill(Side::Sell, 300.0, 1.5, 1.0, 3000),
+        ];
+        
+        let csv = export_fills(&fills, ReportFormat::FifoCsv);
+        let sell_row: Vec<&str> = csv.lines().nth(3).unwrap().split(',').collect();
+        
+        // Basis: 1.0 @ 100 + 0.5 @ 200 = 200; proceeds 450 - 1 fee
+        assert_eq!(sell_row[8], "200.00000000");
+        assert_eq!(sell_row[9], "449.00000000");
+        assert_eq!(sell_row[10], "249.00000000");
+        assert_eq!(sell_row[11], "0.00000000");
+    }
+    
+    #[test]
+    fn test_koinly_and_cointracking_layouts() {
+        let fills = vec![fill(Side::Sell, 50000.0, 0.1, 2.5, 1_709_294_400_000)];
+        
+        let koinly = export_fills(&fills, ReportFormat::Koinly);
+        assert!(koinly.starts_with("Date,Sent Amount,Sent Currency"));
+        assert!(koinly.contains("2024-03-01 12:00:00 UTC,0.1,BTC,5000,USDT,2.5,USDT"));
+        
+        let cointracking = export_fills(&fills, ReportFormat::CoinTracking);
+        assert!(cointracking.contains("Trade,5000,USDT,0.1,BTC,2.5,USDT,binance,Arbitrage"));
+    }
+    
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00:00");
+        assert_eq!(format_utc(951_825_600_000), "2000-02-29 12:00:00");
+        assert_eq!("koinly".parse::<ReportFormat>(), Ok(ReportFormat::Koinly));
+        assert!("xlsx".parse::<ReportFormat>().is_err());
+    }
+}
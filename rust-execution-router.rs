@@ -0,0 +1,351 @@
+// execution/router.rs - Smart order router: places legs and completes partial fills
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::orders::{reconcile_events, NewOrder, OrderEvent, OrderLeg, OrderState, OrderStore, OrderVenue};
+use super::types::Side;
+
+/// Quantities below this are rounding, not a remainder
+const QUANTITY_EPSILON: f64 = 1e-9;
+
+/// What to do with the rest of a leg that only partially filled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum CompletionStrategy {
+    /// Re-price the remainder in `steps` equal moves, up to `max_price_move`
+    /// (a fraction of the leg's limit) worse than the original price
+    Chase { max_price_move: f64, steps: u32 },
+    /// Send the remainder at the original limit to these exchanges, in order
+    AlternativeVenue { venues: Vec<String> },
+    /// Trade what filled back at its average price and give up on the leg
+    Unwind,
+}
+
+impl Default for CompletionStrategy {
+    fn default() -> Self {
+        CompletionStrategy::Chase { max_price_move: 0.001, steps: 3 }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RouterConfig {
+    pub on_partial_fill: CompletionStrategy,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RouteOutcome {
+    Filled,
+    /// Partially filled, then completed by these follow-up orders
+    Completed { follow_ups: Vec<u64> },
+    /// Partially filled, and the fill reversed by `order`
+    Unwound { order: u64 },
+    /// Still short `remaining` after the strategy ran out of options
+    Incomplete { remaining: f64, follow_ups: Vec<u64> },
+    /// Resting unfilled; left to the stale-order sweeper
+    Resting,
+    Failed { reason: String },
+}
+
+/// Sends legs to their venue and, when one partially fills, cancels the
+/// remainder and completes it per `RouterConfig::on_partial_fill`. Every
+/// follow-up order is a leg of the same opportunity in the order store
+pub struct SmartOrderRouter {
+    config: RouterConfig,
+    orders: Arc<OrderStore>,
+    venues: Vec<Arc<dyn OrderVenue>>,
+}
+
+impl SmartOrderRouter {
+    pub fn new(config: RouterConfig, orders: Arc<OrderStore>, venues: Vec<Arc<dyn OrderVenue>>) -> Self {
+        Self { config, orders, venues }
+    }
+    
+    /// Place submitted leg `id` and see it through to a fill or a completion
+    pub async fn execute(&self, id: u64, now_ms: u64) -> RouteOutcome {
+        let Some(leg) = self.orders.get(id) else {
+            return RouteOutcome::Failed { reason: format!("unknown order #{}", id) };
+        };
+        let leg = match self.place(&leg, now_ms).await {
+            Ok(leg) => leg,
+            Err(reason) => return RouteOutcome::Failed { reason },
+        };
+        
+        match leg.state {
+            OrderState::Filled => return RouteOutcome::Filled,
+            OrderState::Rejected => return RouteOutcome::Failed { reason: leg.reason.unwrap_or_default() },
+            _ if leg.filled_quantity <= 0.0 => {
+                return if leg.state.is_terminal() {
+                    RouteOutcome::Failed { reason: leg.reason.unwrap_or_default() }
+                } else {
+                    RouteOutcome::Resting
+                };
+            }
+            _ => {}
+        }
+        
+        // Stop the remainder resting at the old price before completing it elsewhere
+        if !leg.state.is_terminal() {
+            if let Err(reason) = self.cancel(&leg, "partial fill; completing remainder", now_ms).await {
+                return RouteOutcome::Failed { reason: format!("cancelling remainder of #{}: {}", id, reason) };
+            }
+        }
+        info!(
+            "Order #{} filled {} of {} {}; completing with {:?}",
+            id, leg.filled_quantity, leg.quantity, leg.symbol, self.config.on_partial_fill
+        );
+        self.complete(&leg, now_ms).await
+    }
+    
+    async fn complete(&self, leg: &OrderLeg, now_ms: u64) -> RouteOutcome {
+        let mut remaining = leg.quantity - leg.filled_quantity;
+        let price = leg.limit_price.or(leg.avg_fill_price);
+        let mut follow_ups = Vec::new();
+        
+        match &self.config.on_partial_fill {
+            CompletionStrategy::Chase { max_price_move, steps } => {
+                let worse = match leg.side {
+                    Side::Buy => 1.0,
+                    Side::Sell => -1.0,
+                };
+                for step in 1..=*steps {
+                    let limit = price.map(|price| price * (1.0 + worse * max_price_move * step as f64 / *steps as f64));
+                    if let Some(order) = self.follow_up(leg, &leg.exchange, leg.side, remaining, limit, now_ms).await {
+                        remaining -= order.filled_quantity;
+                        follow_ups.push(order.id);
+                    }
+                    if remaining <= QUANTITY_EPSILON {
+                        return RouteOutcome::Completed { follow_ups };
+                    }
+                }
+            }
+            CompletionStrategy::AlternativeVenue { venues } => {
+                for exchange in venues.iter().filter(|exchange| **exchange != leg.exchange) {
+                    if let Some(order) = self.follow_up(leg, exchange, leg.side, remaining, price, now_ms).await {
+                        remaining -= order.filled_quantity;
+                        follow_ups.push(order.id);
+                    }
+                    if remaining <= QUANTITY_EPSILON {
+                        return RouteOutcome::Completed { follow_ups };
+                    }
+                }
+            }
+            CompletionStrategy::Unwind => {
+                let side = match leg.side {
+                    Side::Buy => Side::Sell,
+                    Side::Sell => Side::Buy,
+                };
+                return match self.follow_up(leg, &leg.exchange, side, leg.filled_quantity, leg.avg_fill_price, now_ms).await {
+                    Some(order) if order.state == OrderState::Filled => RouteOutcome::Unwound { order: order.id },
+                    Some(order) => RouteOutcome::Incomplete {
+                        remaining: leg.filled_quantity - order.filled_quantity,
+                        follow_ups: vec![order.id],
+                    },
+                    None => RouteOutcome::Incomplete { remaining: leg.filled_quantity, follow_ups },
+                };
+            }
+        }
+        warn!("Order #{} still {} {} short after {:?}", leg.id, remaining, leg.symbol, self.config.on_partial_fill);
+        RouteOutcome::Incomplete { remaining, follow_ups }
+    }
+    
+    /// Create, place and settle an order for `parent`'s opportunity. Whatever
+    /// doesn't fill at once is cancelled, so at most one follow-up is live
+    async fn follow_up(
+        &self,
+        parent: &OrderLeg,
+        exchange: &str,
+        side: Side,
+        quantity: f64,
+        limit_price: Option<f64>,
+        now_ms: u64,
+    ) -> Option<OrderLeg> {
+        let order = NewOrder {
+            opportunity_path: parent.opportunity_path.clone(),
+            exchange: exchange.to_string(),
+            symbol: parent.symbol.clone(),
+            side,
+            quantity,
+            limit_price,
+        };
+        let id = self.orders.create(order, now_ms);
+        if let Err(e) = self.orders.apply(id, OrderEvent::Submitted, now_ms) {
+            warn!("Follow-up order #{}: {}", id, e);
+        }
+        let order = self.place(&self.orders.get(id)?, now_ms).await.ok()?;
+        if !order.state.is_terminal() {
+            if let Err(e) = self.cancel(&order, "unfilled follow-up", now_ms).await {
+                warn!("Follow-up order #{} is still open: {}", id, e);
+            }
+        }
+        self.orders.get(id)
+    }
+    
+    /// Send `leg` and record the venue's response; the leg as it stands after
+    async fn place(&self, leg: &OrderLeg, now_ms: u64) -> Result<OrderLeg, String> {
+        let placed = match self.venue(&leg.exchange) {
+            Some(venue) => venue.place(leg).await,
+            None => Err(format!("no order venue for {}", leg.exchange)),
+        };
+        let events = match placed {
+            Ok(status) => reconcile_events(leg, Some(status)),
+            Err(reason) => {
+                let _ = self.orders.apply(leg.id, OrderEvent::Rejected { reason: reason.clone() }, now_ms);
+                return Err(reason);
+            }
+        };
+        for event in events {
+            if let Err(e) = self.orders.apply(leg.id, event, now_ms) {
+                warn!("Order #{}: {}", leg.id, e);
+            }
+        }
+        self.orders.get(leg.id).ok_or_else(|| format!("order #{} vanished", leg.id))
+    }
+    
+    async fn cancel(&self, leg: &OrderLeg, reason: &str, now_ms: u64) -> Result<(), String> {
+        match self.venue(&leg.exchange) {
+            Some(venue) => venue.cancel(leg).await?,
+            None => return Err(format!("no order venue for {}", leg.exchange)),
+        }
+        self.orders
+            .apply(leg.id, OrderEvent::Cancelled { reason: reason.to_string() }, now_ms)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+    
+    fn venue(&self, exchange: &str) -> Option<&Arc<dyn OrderVenue>> {
+        self.venues.iter().find(|venue| venue.exchange() == exchange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use crate::execution::orders::{VenueFuture, VenueOrder};
+    
+    /// Simulated venue: buys take resting asks at or below their limit, sells
+    /// take bids at or above it; whatever can't fill rests on the book
+    struct SimVenue {
+        exchange: String,
+        asks: Mutex<Vec<(f64, f64)>>,
+        bids: Mutex<Vec<(f64, f64)>>,
+    }
+    
+    impl SimVenue {
+        fn with_book(exchange: &str, asks: &[(f64, f64)], bids: &[(f64, f64)]) -> Arc<dyn OrderVenue> {
+            Arc::new(Self {
+                exchange: exchange.to_string(),
+                asks: Mutex::new(asks.to_vec()),
+                bids: Mutex::new(bids.to_vec()),
+            })
+        }
+    }
+    
+    impl OrderVenue for SimVenue {
+        fn exchange(&self) -> &str {
+            &self.exchange
+        }
+        
+        fn order_status<'a>(&'a self, _leg: &'a OrderLeg) -> VenueFuture<'a, Option<VenueOrder>> {
+            Box::pin(async { Ok(None) })
+        }
+        
+        fn place<'a>(&'a self, leg: &'a OrderLeg) -> VenueFuture<'a, VenueOrder> {
+            let (mut book, crosses): (_, fn(f64, f64) -> bool) = match leg.side {
+                Side::Buy => (self.asks.lock().unwrap(), |level, limit| level <= limit),
+                Side::Sell => (self.bids.lock().unwrap(), |level, limit| level >= limit),
+            };
+            let (mut filled, mut value) = (0.0, 0.0);
+            for (price, size) in book.iter_mut() {
+                if leg.limit_price.is_some_and(|limit| !crosses(*price, limit)) {
+                    continue;
+                }
+                let take = size.min(leg.quantity - filled);
+                filled += take;
+                value += take * *price;
+                *size -= take;
+            }
+            book.retain(|(_, size)| *size > 0.0);
+            let status = VenueOrder {
+                exchange_order_id: format!("{}-{}", self.exchange, leg.id),
+                filled_quantity: filled,
+                avg_fill_price: if filled > 0.0 { value / filled } else { 0.0 },
+                open: filled < leg.quantity,
+                cancelled: false,
+            };
+            Box::pin(async move { Ok(status) })
+        }
+        
+        fn cancel<'a>(&'a self, _leg: &'a OrderLeg) -> VenueFuture<'a, ()> {
+            Box::pin(async { Ok(()) })
+        }
+    }
+    
+    fn setup(name: &str, strategy: CompletionStrategy, venues: Vec<Arc<dyn OrderVenue>>) -> (SmartOrderRouter, Arc<OrderStore>, u64) {
+        let path = std::env::temp_dir().join(format!("router-{}-{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let orders = Arc::new(OrderStore::open(path));
+        let id = orders.create(
+            NewOrder {
+                opportunity_path: "USDT_binance -> BTC_binance -> BTC_kraken -> USDT_kraken".to_string(),
+                exchange: "binance".to_string(),
+                symbol: "BTC/USDT".to_string(),
+                side: Side::Buy,
+                quantity: 2.0,
+                limit_price: Some(100.0),
+            },
+            1,
+        );
+        orders.apply(id, OrderEvent::Submitted, 1).unwrap();
+        let config = RouterConfig { on_partial_fill: strategy };
+        (SmartOrderRouter::new(config, orders.clone(), venues), orders, id)
+    }
+    
+    #[tokio::test]
+    async fn test_chase_completes_within_limit_and_stops_beyond_it() {
+        let chase = CompletionStrategy::Chase { max_price_move: 0.001, steps: 2 };
+        let (router, orders, id) = setup("chase", chase.clone(), vec![SimVenue::with_book("binance", &[(100.0, 1.0), (100.04, 0.6), (100.09, 5.0)], &[])]);
+        let RouteOutcome::Completed { follow_ups } = router.execute(id, 2).await else { panic!("chase should complete") };
+        assert_eq!(follow_ups.len(), 2, "0.6 at +0.05%, the last 0.4 at +0.1%");
+        let original = orders.get(id).unwrap();
+        assert_eq!((original.state, original.filled_quantity), (OrderState::Cancelled, 1.0));
+        let prices: Vec<f64> = follow_ups.iter().map(|id| orders.get(*id).unwrap().limit_price.unwrap()).collect();
+        assert!((prices[0] - 100.05).abs() < 1e-9 && (prices[1] - 100.1).abs() < 1e-9, "{:?}", prices);
+        assert!(orders.in_flight().is_empty());
+        
+        let (router, orders, id) = setup("chase-limit", chase, vec![SimVenue::with_book("binance", &[(100.0, 1.0), (101.0, 5.0)], &[])]);
+        assert_eq!(router.execute(id, 2).await, RouteOutcome::Incomplete { remaining: 1.0, follow_ups: vec![1, 2] });
+        assert!(orders.in_flight().is_empty(), "unfilled chase orders are cancelled");
+    }
+    
+    #[tokio::test]
+    async fn test_alternative_venue_completes_remainder() {
+        let venues = vec![
+            SimVenue::with_book("binance", &[(100.0, 0.5)], &[]),
+            SimVenue::with_book("coinbase", &[(100.2, 5.0)], &[]),
+            SimVenue::with_book("kraken", &[(99.9, 5.0)], &[]),
+        ];
+        let strategy = CompletionStrategy::AlternativeVenue { venues: vec!["coinbase".to_string(), "kraken".to_string()] };
+        let (router, orders, id) = setup("alternative", strategy, venues);
+        
+        assert_eq!(router.execute(id, 2).await, RouteOutcome::Completed { follow_ups: vec![1, 2] });
+        let kraken = orders.get(2).unwrap();
+        assert_eq!((kraken.exchange.as_str(), kraken.quantity, kraken.state), ("kraken", 1.5, OrderState::Filled));
+        assert_eq!(orders.get(1).unwrap().state, OrderState::Cancelled, "coinbase's ask is above the limit");
+    }
+    
+    #[tokio::test]
+    async fn test_unwind_reverses_partial_fill() {
+        let venues = vec![SimVenue::with_book("binance", &[(100.0, 0.5)], &[(100.0, 3.0)])];
+        let (router, orders, id) = setup("unwind", CompletionStrategy::Unwind, venues);
+        
+        assert_eq!(router.execute(id, 2).await, RouteOutcome::Unwound { order: 1 });
+        let unwind = orders.get(1).unwrap();
+        assert_eq!((unwind.side, unwind.quantity, unwind.limit_price, unwind.state), (Side::Sell, 0.5, Some(100.0), OrderState::Filled));
+        
+        let venues = vec![SimVenue::with_book("binance", &[(100.0, 2.0)], &[])];
+        let (router, _, id) = setup("filled", CompletionStrategy::Unwind, venues);
+        assert_eq!(router.execute(id, 2).await, RouteOutcome::Filled);
+    }
+}
@@ -0,0 +1,303 @@
+// execution/sweeper.rs - Cancel stale and connection-interrupted order legs
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::orders::{reconcile_events, NewOrder, OrderEvent, OrderLeg, OrderStore, OrderVenue};
+use super::types::Side;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SweepPolicy {
+    /// Cancel every unfilled leg and keep whatever already filled
+    #[default]
+    CancelAll,
+    /// Unwind filled quantity at its average price first, then cancel the rest
+    HedgeThenCancel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepConfig {
+    pub policy: SweepPolicy,
+    /// Legs with no update for this long are swept
+    #[serde(with = "humantime_serde")]
+    pub max_order_age: Duration,
+    #[serde(with = "humantime_serde")]
+    pub sweep_interval: Duration,
+}
+
+impl Default for SweepConfig {
+    fn default() -> Self {
+        Self {
+            policy: SweepPolicy::CancelAll,
+            max_order_age: Duration::from_secs(30),
+            sweep_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+/// A leg the venue wouldn't cancel; it may still fill
+#[derive(Debug, Clone, PartialEq)]
+pub struct CancelFailure {
+    pub order_id: u64,
+    pub exchange: String,
+    pub symbol: String,
+    pub error: String,
+}
+
+impl fmt::Display for CancelFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "order #{} ({} {}) could not be cancelled: {}", self.order_id, self.exchange, self.symbol, self.error)
+    }
+}
+
+/// Settles executions that can no longer complete as planned: legs left open
+/// too long, and executions with a leg on an exchange whose connection dropped.
+/// The latter are swept once the connection is back, since cancels need it
+pub struct OrderSweeper {
+    config: SweepConfig,
+    orders: Arc<OrderStore>,
+    venues: Vec<Arc<dyn OrderVenue>>,
+    /// (exchange, opportunity path) for legs in flight when the exchange disconnected
+    interrupted: Mutex<HashSet<(String, String)>>,
+}
+
+impl OrderSweeper {
+    pub fn new(config: SweepConfig, orders: Arc<OrderStore>, venues: Vec<Arc<dyn OrderVenue>>) -> Self {
+        Self {
+            config,
+            orders,
+            venues,
+            interrupted: Mutex::new(HashSet::new()),
+        }
+    }
+    
+    pub fn sweep_interval(&self) -> Duration {
+        self.config.sweep_interval
+    }
+    
+    pub fn connection_lost(&self, exchange: &str) {
+        let mut interrupted = self.interrupted.lock().unwrap();
+        for leg in self.orders.in_flight().into_iter().filter(|leg| leg.exchange == exchange) {
+            if interrupted.insert((leg.exchange.clone(), leg.opportunity_path.clone())) {
+                warn!("{} disconnected mid-execution of {}; sweeping when it reconnects", exchange, leg.opportunity_path);
+            }
+        }
+    }
+    
+    /// Sweep executions `exchange`'s disconnect interrupted
+    pub async fn connection_restored(&self, exchange: &str, now_ms: u64) -> Vec<CancelFailure> {
+        let paths: Vec<String> = {
+            let mut interrupted = self.interrupted.lock().unwrap();
+            let mut paths = Vec::new();
+            interrupted.retain(|(lost, path)| {
+                if lost != exchange {
+                    return true;
+                }
+                paths.push(path.clone());
+                false
+            });
+            paths
+        };
+        
+        let mut failures = Vec::new();
+        for path in paths {
+            failures.extend(self.sweep(&path, now_ms).await);
+        }
+        failures
+    }
+    
+    /// Sweep executions with a leg that hasn't moved for `max_order_age`
+    pub async fn sweep_stale(&self, now_ms: u64) -> Vec<CancelFailure> {
+        let cutoff = now_ms.saturating_sub(self.config.max_order_age.as_millis() as u64);
+        let mut stale: Vec<String> = self
+            .orders
+            .in_flight()
+            .into_iter()
+            .filter(|leg| leg.updated_at_ms < cutoff)
+            .map(|leg| leg.opportunity_path)
+            .collect();
+        stale.sort();
+        stale.dedup();
+        
+        let mut failures = Vec::new();
+        for path in stale {
+            failures.extend(self.sweep(&path, now_ms).await);
+        }
+        failures
+    }
+    
+    async fn sweep(&self, path: &str, now_ms: u64) -> Vec<CancelFailure> {
+        let legs = self.orders.legs_for(path);
+        if legs.iter().all(|leg| leg.state.is_terminal()) {
+            return Vec::new();
+        }
+        info!("Sweeping execution of {} ({:?})", path, self.config.policy);
+        
+        if self.config.policy == SweepPolicy::HedgeThenCancel {
+            for leg in legs.iter().filter(|leg| leg.filled_quantity > 0.0) {
+                self.hedge(leg, now_ms).await;
+            }
+        }
+        
+        let mut failures = Vec::new();
+        for leg in legs.iter().filter(|leg| !leg.state.is_terminal()) {
+            let result = match self.venue(&leg.exchange) {
+                Some(venue) => venue.cancel(leg).await,
+                None => Err("no order venue for this exchange".to_string()),
+            };
+            match result {
+                Ok(()) => {
+                    let reason = format!("swept ({:?})", self.config.policy);
+                    if let Err(e) = self.orders.apply(leg.id, OrderEvent::Cancelled { reason }, now_ms) {
+                        warn!("Sweeping order #{}: {}", leg.id, e);
+                    }
+                }
+                Err(error) => failures.push(CancelFailure {
+                    order_id: leg.id,
+                    exchange: leg.exchange.clone(),
+                    symbol: leg.symbol.clone(),
+                    error,
+                }),
+            }
+        }
+        failures
+    }
+    
+    /// Reverse `leg`'s filled quantity at its average fill price
+    async fn hedge(&self, leg: &OrderLeg, now_ms: u64) {
+        let Some(venue) = self.venue(&leg.exchange) else {
+            warn!("No order venue on {} to hedge order #{}", leg.exchange, leg.id);
+            return;
+        };
+        let side = match leg.side {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        };
+        let id = self.orders.create(
+            NewOrder {
+                opportunity_path: leg.opportunity_path.clone(),
+                exchange: leg.exchange.clone(),
+                symbol: leg.symbol.clone(),
+                side,
+                quantity: leg.filled_quantity,
+                limit_price: leg.avg_fill_price,
+            },
+            now_ms,
+        );
+        let Some(hedge) = self.orders.get(id) else { return };
+        
+        let placed = venue.place(&hedge).await;
+        let events = match placed {
+            Ok(status) => reconcile_events(&hedge, Some(status)),
+            Err(e) => vec![OrderEvent::Rejected { reason: e }],
+        };
+        for event in events {
+            if let Err(e) = self.orders.apply(id, event, now_ms) {
+                warn!("Hedge order #{}: {}", id, e);
+            }
+        }
+        info!("Hedged order #{} with #{}: {:?} {} {}", leg.id, id, side, leg.filled_quantity, leg.symbol);
+    }
+    
+    fn venue(&self, exchange: &str) -> Option<&Arc<dyn OrderVenue>> {
+        self.venues.iter().find(|venue| venue.exchange() == exchange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::orders::{OrderState, PaperVenue, VenueFuture, VenueOrder};
+    
+    fn store(name: &str) -> Arc<OrderStore> {
+        let path = std::env::temp_dir().join(format!("sweeper-{}-{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Arc::new(OrderStore::open(path))
+    }
+    
+    fn leg(store: &OrderStore, exchange: &str, side: Side, fill: Option<f64>, at_ms: u64) -> u64 {
+        let id = store.create(
+            NewOrder {
+                opportunity_path: "BTC_binance -> ETH_binance -> BTC_kraken".to_string(),
+                exchange: exchange.to_string(),
+                symbol: "ETH/BTC".to_string(),
+                side,
+                quantity: 2.0,
+                limit_price: Some(0.05),
+            },
+            at_ms,
+        );
+        store.apply(id, OrderEvent::Submitted, at_ms).unwrap();
+        store.apply(id, OrderEvent::Acked { exchange_order_id: format!("x{}", id) }, at_ms).unwrap();
+        if let Some(quantity) = fill {
+            store.apply(id, OrderEvent::Filled { quantity, price: 0.05 }, at_ms).unwrap();
+        }
+        id
+    }
+    
+    #[tokio::test]
+    async fn test_hedges_then_cancels_after_reconnect() {
+        let orders = store("hedge");
+        let filled = leg(&orders, "binance", Side::Buy, Some(2.0), 1);
+        let open = leg(&orders, "kraken", Side::Sell, Some(0.5), 1);
+        let venues: Vec<Arc<dyn OrderVenue>> = vec![Arc::new(PaperVenue::new("binance")), Arc::new(PaperVenue::new("kraken"))];
+        let config = SweepConfig { policy: SweepPolicy::HedgeThenCancel, ..SweepConfig::default() };
+        let sweeper = OrderSweeper::new(config, orders.clone(), venues);
+        
+        sweeper.connection_lost("kraken");
+        assert!(sweeper.connection_restored("binance", 2).await.is_empty());
+        assert_eq!(orders.get(open).unwrap().state, OrderState::PartiallyFilled, "binance reconnecting doesn't touch the kraken leg's execution");
+        
+        assert!(sweeper.connection_restored("kraken", 3).await.is_empty());
+        let legs = orders.legs_for("BTC_binance -> ETH_binance -> BTC_kraken");
+        let hedges: Vec<(&str, Side, f64, OrderState)> = legs
+            .iter()
+            .filter(|l| l.id != filled && l.id != open)
+            .map(|l| (l.exchange.as_str(), l.side, l.quantity, l.state))
+            .collect();
+        assert_eq!(hedges, vec![("binance", Side::Sell, 2.0, OrderState::Filled), ("kraken", Side::Buy, 0.5, OrderState::Filled)]);
+        assert_eq!(orders.get(open).unwrap().state, OrderState::Cancelled);
+        assert!(orders.in_flight().is_empty());
+    }
+    
+    struct StuckVenue;
+    
+    impl OrderVenue for StuckVenue {
+        fn exchange(&self) -> &str {
+            "kraken"
+        }
+        
+        fn order_status<'a>(&'a self, _leg: &'a OrderLeg) -> VenueFuture<'a, Option<VenueOrder>> {
+            Box::pin(async { Err("timeout".to_string()) })
+        }
+        
+        fn place<'a>(&'a self, _leg: &'a OrderLeg) -> VenueFuture<'a, VenueOrder> {
+            Box::pin(async { Err("timeout".to_string()) })
+        }
+        
+        fn cancel<'a>(&'a self, _leg: &'a OrderLeg) -> VenueFuture<'a, ()> {
+            Box::pin(async { Err("order locked".to_string()) })
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_stale_legs_are_cancelled_and_failures_reported() {
+        let orders = store("stale");
+        let stale = leg(&orders, "binance", Side::Buy, None, 1_000);
+        let stuck = leg(&orders, "kraken", Side::Sell, None, 1_000);
+        let venues: Vec<Arc<dyn OrderVenue>> = vec![Arc::new(PaperVenue::new("binance")), Arc::new(StuckVenue)];
+        let sweeper = OrderSweeper::new(SweepConfig::default(), orders.clone(), venues);
+        
+        assert!(sweeper.sweep_stale(20_000).await.is_empty(), "younger than max_order_age");
+        
+        let failures = sweeper.sweep_stale(31_001).await;
+        assert_eq!(orders.get(stale).unwrap().state, OrderState::Cancelled);
+        assert_eq!(failures.len(), 1);
+        assert_eq!((failures[0].order_id, failures[0].error.as_str()), (stuck, "order locked"));
+        assert_eq!(orders.get(stuck).unwrap().state, OrderState::Acked);
+    }
+}
@@ -0,0 +1,56 @@
+// execution/types.rs - Core execution record types
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+/// One executed fill of an order leg
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fill {
+    pub fill_id: String,
+    pub order_id: String,
+    pub exchange: String,
+    pub symbol: String, // BASE/QUOTE
+    pub side: Side,
+    pub price: f64,
+    pub quantity: f64, // Base asset units
+    pub fee: f64,
+    pub fee_asset: String,
+    pub timestamp_ms: u64, // Unix epoch milliseconds
+}
+
+impl Fill {
+    pub fn base_asset(&self) -> &str {
+        self.symbol.split('/').next().unwrap_or(&self.symbol)
+    }
+    
+    pub fn quote_asset(&self) -> &str {
+        self.symbol.split('/').nth(1).unwrap_or("")
+    }
+}
+
+/// Outcome of one executed opportunity, used to calibrate the slippage model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionRecord {
+    pub opportunity_path: String,
+    pub exchanges: Vec<String>,
+    pub notional: f64,          // Quote-currency size of the first leg
+    pub expected_profit: f64,   // Quote currency, as evaluated at detection
+    pub realized_profit: f64,   // Quote currency, from actual fills
+    pub completed_at_ms: u64,   // Unix epoch milliseconds
+}
+
+/// Simulated execution of an opportunity at its detected price and size, kept
+/// apart from real executions so it never skews P&L or slippage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperTrade {
+    pub opportunity_path: String,
+    pub exchanges: Vec<String>,
+    pub notional: f64,          // Detected max volume
+    pub expected_profit: f64,   // Quote currency
+    pub executed_at_ms: u64,    // Unix epoch milliseconds
+}
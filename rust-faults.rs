@@ -0,0 +1,209 @@
+// faults.rs - Artificial latency, jitter, drops and chaos for robustness testing
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Network conditions to simulate on one path
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FaultProfile {
+    /// Added to every message
+    #[serde(with = "humantime_serde")]
+    pub latency: Duration,
+    /// Extra delay drawn uniformly from 0..jitter
+    #[serde(with = "humantime_serde")]
+    pub jitter: Duration,
+    /// Probability (0..1) that a message is lost
+    pub drop_rate: f64,
+}
+
+impl FaultProfile {
+    pub fn is_active(&self) -> bool {
+        !self.latency.is_zero() || !self.jitter.is_zero() || self.drop_rate > 0.0
+    }
+}
+
+/// Connection-level chaos; only used with the "chaos" feature
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChaosConfig {
+    /// Per-frame probability of killing the connection, forcing reconnect and resubscribe
+    pub disconnect_rate: f64,
+    /// Per-frame probability of delivering a frame twice
+    pub duplicate_rate: f64,
+    /// Per-frame probability of holding a frame back until after the next one
+    pub reorder_rate: f64,
+}
+
+impl ChaosConfig {
+    pub fn is_active(&self) -> bool {
+        self.disconnect_rate > 0.0 || self.duplicate_rate > 0.0 || self.reorder_rate > 0.0
+    }
+}
+
+/// Off unless a profile is set; never enable against live trading
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FaultConfig {
+    /// Inbound exchange frames, before parsing
+    pub feeds: FaultProfile,
+    /// Execution acknowledgments
+    pub executor: FaultProfile,
+    pub chaos: ChaosConfig, // Only used with the "chaos" feature
+    /// Fixed seed for reproducible runs; random when unset
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fault {
+    Deliver(Duration), // After this extra delay
+    Drop,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct FaultStats {
+    pub delivered: u64,
+    pub dropped: u64,
+    pub delayed_ms: u64,
+}
+
+/// Decides the fate of each message on one path
+pub struct FaultInjector {
+    profile: FaultProfile,
+    rng: Mutex<SplitMix64>,
+    delivered: AtomicU64,
+    dropped: AtomicU64,
+    delayed_ms: AtomicU64,
+}
+
+impl FaultInjector {
+    pub fn new(profile: FaultProfile, seed: u64) -> Self {
+        Self {
+            profile,
+            rng: Mutex::new(SplitMix64::new(seed)),
+            delivered: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            delayed_ms: AtomicU64::new(0),
+        }
+    }
+    
+    /// Injector for `path` ("feeds", "executor"), or `None` when its profile is off
+    pub fn for_profile(path: &str, profile: &FaultProfile, seed: Option<u64>) -> Option<Self> {
+        if !profile.is_active() {
+            return None;
+        }
+        warn!(
+            "Fault injection on {}: latency {:?} + jitter {:?}, drop rate {}",
+            path, profile.latency, profile.jitter, profile.drop_rate
+        );
+        Some(Self::new(profile.clone(), seed.unwrap_or_else(clock_seed)))
+    }
+    
+    pub fn decide(&self) -> Fault {
+        let mut rng = self.rng.lock().unwrap();
+        let fault = if rng.next_f64() < self.profile.drop_rate {
+            Fault::Drop
+        } else {
+            Fault::Deliver(self.profile.latency + self.profile.jitter.mul_f64(rng.next_f64()))
+        };
+        drop(rng);
+        
+        match fault {
+            Fault::Drop => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            Fault::Deliver(delay) => {
+                self.delayed_ms.fetch_add(delay.as_millis() as u64, Ordering::Relaxed);
+                self.delivered.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        fault
+    }
+    
+    /// Wait out the injected delay; false if the message should be dropped
+    pub async fn apply(&self) -> bool {
+        match self.decide() {
+            Fault::Drop => false,
+            Fault::Deliver(delay) => {
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+                true
+            }
+        }
+    }
+    
+    pub fn stats(&self) -> FaultStats {
+        FaultStats {
+            delivered: self.delivered.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            delayed_ms: self.delayed_ms.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Seed for runs that don't need to be replayed
+pub(crate) fn clock_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+/// Small seedable generator so runs can be replayed; not for anything secret
+pub(crate) struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+    
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    
+    /// Uniform in [0, 1)
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn profile(latency_ms: u64, jitter_ms: u64, drop_rate: f64) -> FaultProfile {
+        FaultProfile {
+            latency: Duration::from_millis(latency_ms),
+            jitter: Duration::from_millis(jitter_ms),
+            drop_rate,
+        }
+    }
+    
+    #[test]
+    fn test_delays_within_latency_plus_jitter() {
+        let injector = FaultInjector::new(profile(50, 20, 0.0), 7);
+        for _ in 0..1000 {
+            match injector.decide() {
+                Fault::Deliver(delay) => assert!(delay >= Duration::from_millis(50) && delay < Duration::from_millis(70)),
+                Fault::Drop => panic!("drop rate is zero"),
+            }
+        }
+        assert_eq!(injector.stats().delivered, 1000);
+        assert!(FaultInjector::for_profile("feeds", &FaultProfile::default(), None).is_none());
+    }
+    
+    #[test]
+    fn test_seeded_drops_are_reproducible() {
+        let a = FaultInjector::new(profile(0, 0, 0.25), 42);
+        let b = FaultInjector::new(profile(0, 0, 0.25), 42);
+        let fates: Vec<Fault> = (0..10_000).map(|_| a.decide()).collect();
+        
+        assert_eq!(fates, (0..10_000).map(|_| b.decide()).collect::<Vec<_>>());
+        let dropped = a.stats().dropped;
+        assert!((2_200..2_800).contains(&dropped), "dropped {}", dropped);
+    }
+}
@@ -0,0 +1,436 @@
+// feetiers.rs - Account fee tier detection and public fee schedules per exchange
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::Duration;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use tracing::{info, warn};
+
+use crate::markets::{FeeAsset, Fees};
+use crate::outbox::now_ms;
+
+/// Account requests give up after this long; the next refresh tries again
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Exchanges with a `FeeTierSource` for account credentials
+pub const SUPPORTED: [&str; 1] = ["binance"];
+
+pub type TierFuture<'a> = Pin<Box<dyn Future<Output = Result<AccountFeeTier, String>> + Send + 'a>>;
+
+/// Rates of every pair in a venue's public market list
+type ScheduleParser = fn(&Value) -> Result<Vec<Fees>, String>;
+
+/// Read-only API key; trading and withdrawal permissions are never needed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiCredentials {
+    pub api_key: String,
+    pub api_secret: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeTierConfig {
+    /// Credentials by exchange; exchanges without them keep `Config::fees`
+    pub accounts: HashMap<String, ApiCredentials>,
+    /// Fetch the published base rates of configured exchanges without
+    /// credentials, replacing their `Config::fees`
+    pub public_schedules: bool,
+    #[serde(with = "humantime_serde")]
+    pub refresh_interval: Duration,
+}
+
+impl Default for FeeTierConfig {
+    fn default() -> Self {
+        Self {
+            accounts: HashMap::new(),
+            public_schedules: true,
+            refresh_interval: Duration::from_secs(24 * 3600),
+        }
+    }
+}
+
+/// The rates an account actually pays on one exchange
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountFeeTier {
+    pub exchange: String,
+    /// The exchange's name for the tier, e.g. "VIP 1"; discounts are appended
+    pub tier: String,
+    pub fees: Fees,
+}
+
+/// Looks up an account's fee tier on one exchange
+pub trait FeeTierSource: Send + Sync {
+    fn exchange(&self) -> &'static str;
+    
+    fn fetch<'a>(&'a self, client: &'a reqwest::Client) -> TierFuture<'a>;
+}
+
+/// Source for `exchange`, if there is one
+pub fn source_for(exchange: &str, credentials: ApiCredentials) -> Option<Box<dyn FeeTierSource>> {
+    match exchange {
+        "binance" => Some(Box::new(BinanceFeeTier::new(credentials))),
+        _ => None,
+    }
+}
+
+/// Public schedule for `exchange`, if it publishes one
+pub fn public_source_for(exchange: &str) -> Option<Box<dyn FeeTierSource>> {
+    let (exchange, url, parse): (&'static str, &'static str, ScheduleParser) = match exchange {
+        "gateio" => ("gateio", "https://api.gateio.ws/api/v4/spot/currency_pairs", PublicSchedule::parse_gateio),
+        "kraken" => ("kraken", "https://api.kraken.com/0/public/AssetPairs", PublicSchedule::parse_kraken),
+        "mexc" => ("mexc", "https://api.mexc.com/api/v3/exchangeInfo", PublicSchedule::parse_mexc),
+        _ => return None,
+    };
+    Some(Box::new(PublicSchedule { exchange, url, parse }))
+}
+
+/// Spot commission rates and VIP level; fees paid in BNB are charged as a token at a 25% discount
+pub struct BinanceFeeTier {
+    credentials: ApiCredentials,
+}
+
+impl BinanceFeeTier {
+    const API_URL: &'static str = "https://api.binance.com";
+    /// Taken off the fee when it's paid in BNB
+    const BNB_DISCOUNT: f64 = 0.25;
+    
+    pub fn new(credentials: ApiCredentials) -> Self {
+        Self { credentials }
+    }
+    
+    /// Signed GET: the query carries a timestamp and its HMAC-SHA256 signature
+    fn request(&self, client: &reqwest::Client, path: &str, timestamp_ms: u64) -> reqwest::RequestBuilder {
+        let query = format!("recvWindow=5000&timestamp={}", timestamp_ms);
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.credentials.api_secret.as_bytes()).expect("HMAC takes keys of any length");
+        mac.update(query.as_bytes());
+        let signature = hex::encode(mac.finalize().into_bytes());
+        client
+            .get(format!("{}{}?{}&signature={}", Self::API_URL, path, query, signature))
+            .header("X-MBX-APIKEY", &self.credentials.api_key)
+            .timeout(REQUEST_TIMEOUT)
+    }
+    
+    async fn get(&self, client: &reqwest::Client, path: &str) -> Result<Value, String> {
+        let response = self.request(client, path, now_ms()).send().await.map_err(|e| e.to_string())?;
+        let status = response.status();
+        let body = response.text().await.map_err(|e| e.to_string())?;
+        if !status.is_success() {
+            // {"code":-2015,"msg":"Invalid API-key, IP, or permissions for action."}
+            return Err(format!("{} returned {}: {}", path, status, body));
+        }
+        serde_json::from_str(&body).map_err(|e| format!("{}: {}", path, e))
+    }
+    
+    /// From `/api/v3/account`, `/sapi/v1/account/info` and `/sapi/v1/bnbBurn`
+    fn parse(account: &Value, info: &Value, burn: &Value) -> Result<AccountFeeTier, String> {
+        let rate = |side: &str| {
+            account
+                .get("commissionRates")
+                .and_then(|rates| rates.get(side))
+                .and_then(|rate| rate.as_str().and_then(|s| s.parse().ok()).or_else(|| rate.as_f64()))
+                .ok_or_else(|| format!("account has no commissionRates.{}", side))
+        };
+        let mut fees = Fees { maker: rate("maker")?, taker: rate("taker")?, charged_in: None };
+        let mut tier = match info.get("vipLevel").and_then(Value::as_u64) {
+            Some(level) => format!("VIP {}", level),
+            None => "VIP ?".to_string(),
+        };
+        if burn.get("spotBNBBurn").and_then(Value::as_bool) == Some(true) {
+            // Paid from the BNB balance, so the traded amounts stay whole
+            fees.charged_in = Some(FeeAsset::Token { discount: Self::BNB_DISCOUNT });
+            tier.push_str(" + BNB");
+        }
+        Ok(AccountFeeTier {
+            exchange: "binance".to_string(),
+            tier,
+            fees,
+        })
+    }
+}
+
+impl FeeTierSource for BinanceFeeTier {
+    fn exchange(&self) -> &'static str {
+        "binance"
+    }
+    
+    fn fetch<'a>(&'a self, client: &'a reqwest::Client) -> TierFuture<'a> {
+        Box::pin(async move {
+            let account = self.get(client, "/api/v3/account").await?;
+            let info = self.get(client, "/sapi/v1/account/info").await?;
+            let burn = self.get(client, "/sapi/v1/bnbBurn").await?;
+            Self::parse(&account, &info, &burn)
+        })
+    }
+}
+
+/// Base tier rates from a venue's public market list. Pairs can carry their
+/// own rates, so the schedule is the rate most pairs pay; promotional
+/// zero-fee pairs don't drag it down
+pub struct PublicSchedule {
+    exchange: &'static str,
+    url: &'static str,
+    parse: ScheduleParser,
+}
+
+impl PublicSchedule {
+    /// Tier name for rates that aren't an account's own
+    const TIER: &'static str = "public";
+    
+    /// Most common rates, the lower on a tie
+    fn prevailing(rates: &[Fees]) -> Option<Fees> {
+        let mut counts: Vec<(Fees, usize)> = Vec::new();
+        for fees in rates {
+            match counts.iter_mut().find(|(seen, _)| seen == fees) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((*fees, 1)),
+            }
+        }
+        counts
+            .into_iter()
+            .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.taker.total_cmp(&a.taker)).then(b.maker.total_cmp(&a.maker)))
+            .map(|(fees, _)| fees)
+    }
+    
+    /// Rates in percent, as strings or numbers, for `side` of a pair
+    fn percent(pair: &Value, side: &str) -> Option<f64> {
+        Self::number(pair.get(side)?).map(|percent| percent / 100.0)
+    }
+    
+    fn number(value: &Value) -> Option<f64> {
+        value.as_str().and_then(|s| s.parse().ok()).or_else(|| value.as_f64())
+    }
+    
+    /// `/0/public/AssetPairs`: volume tiers as [volume, percent], the first
+    /// being the base tier
+    fn parse_kraken(body: &Value) -> Result<Vec<Fees>, String> {
+        let pairs = body.get("result").and_then(Value::as_object).ok_or("AssetPairs has no result")?;
+        let base = |pair: &Value, side: &str| pair.get(side)?.get(0)?.get(1).and_then(Self::number).map(|percent| percent / 100.0);
+        Ok(pairs
+            .values()
+            .filter_map(|pair| {
+                let taker = base(pair, "fees")?;
+                Some(Fees { maker: base(pair, "fees_maker").unwrap_or(taker), taker, charged_in: None })
+            })
+            .collect())
+    }
+    
+    /// `/api/v4/spot/currency_pairs`: one percent rate per tradable pair
+    fn parse_gateio(body: &Value) -> Result<Vec<Fees>, String> {
+        let pairs = body.as_array().ok_or("currency_pairs is not a list")?;
+        Ok(pairs
+            .iter()
+            .filter(|pair| pair.get("trade_status").and_then(Value::as_str) == Some("tradable"))
+            .filter_map(|pair| Self::percent(pair, "fee").map(|rate| Fees { maker: rate, taker: rate, charged_in: None }))
+            .collect())
+    }
+    
+    /// `/api/v3/exchangeInfo`: maker and taker commission as fractions
+    fn parse_mexc(body: &Value) -> Result<Vec<Fees>, String> {
+        let symbols = body.get("symbols").and_then(Value::as_array).ok_or("exchangeInfo has no symbols")?;
+        Ok(symbols
+            .iter()
+            .filter_map(|symbol| {
+                Some(Fees {
+                    maker: symbol.get("makerCommission").and_then(Self::number)?,
+                    taker: symbol.get("takerCommission").and_then(Self::number)?,
+                    charged_in: None,
+                })
+            })
+            .collect())
+    }
+}
+
+impl FeeTierSource for PublicSchedule {
+    fn exchange(&self) -> &'static str {
+        self.exchange
+    }
+    
+    fn fetch<'a>(&'a self, client: &'a reqwest::Client) -> TierFuture<'a> {
+        Box::pin(async move {
+            let response = client.get(self.url).timeout(REQUEST_TIMEOUT).send().await.map_err(|e| e.to_string())?;
+            let status = response.status();
+            let body = response.text().await.map_err(|e| e.to_string())?;
+            if !status.is_success() {
+                return Err(format!("{} returned {}", self.url, status));
+            }
+            let body: Value = serde_json::from_str(&body).map_err(|e| format!("{}: {}", self.url, e))?;
+            let fees = Self::prevailing(&(self.parse)(&body)?).ok_or_else(|| format!("{} lists no fees", self.url))?;
+            Ok(AccountFeeTier {
+                exchange: self.exchange.to_string(),
+                tier: Self::TIER.to_string(),
+                fees,
+            })
+        })
+    }
+}
+
+/// Latest detected tier per exchange. A failed lookup keeps the last good tier
+pub struct FeeTiers {
+    refresh_interval: Duration,
+    sources: Vec<Box<dyn FeeTierSource>>,
+    current: Mutex<HashMap<String, AccountFeeTier>>,
+}
+
+impl FeeTiers {
+    /// An account tier for each credentialed exchange, and the public
+    /// schedule of each other one in `exchanges` that has one
+    pub fn from_config(config: &FeeTierConfig, exchanges: &[String]) -> Self {
+        let mut sources: Vec<Box<dyn FeeTierSource>> = config
+            .accounts
+            .iter()
+            .filter_map(|(exchange, credentials)| source_for(exchange, credentials.clone()))
+            .collect();
+        if config.public_schedules {
+            sources.extend(
+                exchanges
+                    .iter()
+                    .filter(|exchange| !config.accounts.contains_key(*exchange))
+                    .filter_map(|exchange| public_source_for(exchange)),
+            );
+        }
+        sources.sort_by_key(|source| source.exchange());
+        Self {
+            refresh_interval: config.refresh_interval,
+            sources,
+            current: Mutex::new(HashMap::new()),
+        }
+    }
+    
+    pub fn is_enabled(&self) -> bool {
+        !self.sources.is_empty()
+    }
+    
+    pub fn refresh_interval(&self) -> Duration {
+        self.refresh_interval
+    }
+    
+    /// Look up every account's tier and public schedule, returning the ones that are new or changed
+    pub async fn refresh(&self, client: &reqwest::Client) -> Vec<AccountFeeTier> {
+        let mut changed = Vec::new();
+        for source in &self.sources {
+            match source.fetch(client).await {
+                Ok(tier) => {
+                    if self.record(tier.clone()) {
+                        changed.push(tier);
+                    }
+                }
+                Err(e) => warn!("Fee tier lookup for {} failed, keeping previous rates: {}", source.exchange(), e),
+            }
+        }
+        changed
+    }
+    
+    /// Store `tier`, logging how it differs from the last one; false if it doesn't
+    fn record(&self, tier: AccountFeeTier) -> bool {
+        let mut current = self.current.lock().unwrap();
+        match current.get(&tier.exchange) {
+            Some(previous) if *previous == tier => return false,
+            Some(previous) => info!(
+                "{} fee tier changed: {} (maker {:.4}%, taker {:.4}%) -> {} (maker {:.4}%, taker {:.4}%)",
+                tier.exchange,
+                previous.tier,
+                previous.fees.maker * 100.0,
+                previous.fees.taker * 100.0,
+                tier.tier,
+                tier.fees.maker * 100.0,
+                tier.fees.taker * 100.0
+            ),
+            None => info!(
+                "{} fee tier {}: maker {:.4}%, taker {:.4}%",
+                tier.exchange,
+                tier.tier,
+                tier.fees.maker * 100.0,
+                tier.fees.taker * 100.0
+            ),
+        }
+        current.insert(tier.exchange.clone(), tier);
+        true
+    }
+    
+    /// Detected tiers, by exchange
+    pub fn snapshot(&self) -> Vec<AccountFeeTier> {
+        let mut tiers: Vec<AccountFeeTier> = self.current.lock().unwrap().values().cloned().collect();
+        tiers.sort_by(|a, b| a.exchange.cmp(&b.exchange));
+        tiers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    #[test]
+    fn test_binance_rates_take_the_bnb_discount() {
+        let account = json!({"commissionRates": {"maker": "0.00090000", "taker": "0.00100000", "buyer": "0", "seller": "0"}});
+        let tier = BinanceFeeTier::parse(&account, &json!({"vipLevel": 1}), &json!({"spotBNBBurn": true})).unwrap();
+        assert_eq!(tier.tier, "VIP 1 + BNB");
+        assert_eq!((tier.fees.maker, tier.fees.taker), (0.0009, 0.001));
+        assert_eq!(tier.fees.charged_in, Some(FeeAsset::Token { discount: 0.25 }));
+        
+        let tier = BinanceFeeTier::parse(&account, &json!({}), &json!({"spotBNBBurn": false})).unwrap();
+        assert_eq!((tier.tier.as_str(), tier.fees.taker, tier.fees.charged_in), ("VIP ?", 0.001, None));
+        assert!(BinanceFeeTier::parse(&json!({}), &json!({}), &json!({})).is_err());
+        
+        let source = BinanceFeeTier::new(ApiCredentials { api_key: "key".to_string(), api_secret: "secret".to_string() });
+        let request = source.request(&reqwest::Client::new(), "/api/v3/account", 1_700_000_000_000).build().unwrap();
+        assert_eq!(request.headers()["X-MBX-APIKEY"], "key");
+        assert!(request.url().query().unwrap().starts_with("recvWindow=5000&timestamp=1700000000000&signature="));
+    }
+    
+    #[test]
+    fn test_public_schedules_take_the_rate_most_pairs_pay() {
+        let kraken = json!({"error": [], "result": {
+            "XXBTZUSD": {"fees": [[0, 0.26], [50000, 0.24]], "fees_maker": [[0, 0.16], [50000, 0.14]]},
+            "XETHZUSD": {"fees": [[0, 0.26]], "fees_maker": [[0, 0.16]]},
+            "USDCUSD": {"fees": [[0, 0.2]], "fees_maker": [[0, 0.2]]}
+        }});
+        let rates = PublicSchedule::parse_kraken(&kraken).unwrap();
+        let base = PublicSchedule::prevailing(&rates).unwrap();
+        assert!((base.maker - 0.0016).abs() < 1e-12 && (base.taker - 0.0026).abs() < 1e-12);
+        
+        let gateio = json!([
+            {"id": "BTC_USDT", "fee": "0.2", "trade_status": "tradable"},
+            {"id": "PROMO_USDT", "fee": "0", "trade_status": "tradable"},
+            {"id": "OLD_USDT", "fee": "0", "trade_status": "untradable"}
+        ]);
+        let rates = PublicSchedule::parse_gateio(&gateio).unwrap();
+        assert_eq!(rates.len(), 2);
+        // A tie goes to the lower rate
+        assert_eq!(PublicSchedule::prevailing(&rates), Some(Fees { maker: 0.0, taker: 0.0, charged_in: None }));
+        
+        let mexc = json!({"symbols": [
+            {"symbol": "BTCUSDT", "makerCommission": "0", "takerCommission": "0.0005"},
+            {"symbol": "ETHUSDT", "makerCommission": "0", "takerCommission": "0.0005"}
+        ]});
+        assert_eq!(PublicSchedule::prevailing(&PublicSchedule::parse_mexc(&mexc).unwrap()), Some(Fees { maker: 0.0, taker: 0.0005, charged_in: None }));
+        assert!(PublicSchedule::parse_mexc(&json!({})).is_err());
+        assert_eq!(PublicSchedule::prevailing(&[]), None);
+        
+        let exchanges = ["binance", "kraken", "mexc"].map(String::from);
+        let config = FeeTierConfig {
+            accounts: HashMap::from([("mexc".to_string(), ApiCredentials::default())]),
+            ..FeeTierConfig::default()
+        };
+        let tiers = FeeTiers::from_config(&config, &exchanges);
+        let exchanges: Vec<&str> = tiers.sources.iter().map(|source| source.exchange()).collect();
+        assert_eq!(exchanges, vec!["kraken"]);
+    }
+    
+    #[test]
+    fn test_only_new_or_changed_tiers_are_reported() {
+        let tiers = FeeTiers::from_config(&FeeTierConfig::default(), &[]);
+        let tier = |name: &str, taker: f64| AccountFeeTier {
+            exchange: "binance".to_string(),
+            tier: name.to_string(),
+            fees: Fees { maker: 0.001, taker, charged_in: None },
+        };
+        assert!(tiers.record(tier("VIP 0", 0.001)));
+        assert!(!tiers.record(tier("VIP 0", 0.001)));
+        assert!(tiers.record(tier("VIP 1", 0.0009)));
+        assert_eq!(tiers.snapshot(), vec![tier("VIP 1", 0.0009)]);
+    }
+}
@@ -0,0 +1,392 @@
+// jobs.rs - Cron-scheduled background jobs and their status for /api/jobs
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::outbox::now_ms;
+
+const MINUTE_MS: u64 = 60_000;
+const DAY_MS: u64 = 86_400_000;
+/// A cron expression matching no minute in this many days (e.g. "0 0 30 2 *") never runs
+const SEARCH_DAYS: u64 = 5 * 366;
+
+/// When the periodic jobs run, as cron expressions in UTC ("minute hour
+/// day-of-month month day-of-week", or @hourly, @daily, @weekly, @monthly).
+/// A job left unset keeps its interval, or doesn't run if it has none
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobsConfig {
+    /// Drop recorded ticks and opportunities older than `recording.retention`
+    pub retention: Option<String>,
+    /// Detect account fee tiers and fetch public fee schedules again; every `fee_tiers.refresh_interval` when unset
+    pub fee_refresh: Option<String>,
+    /// Fetch the instrument lists again and subscribe pairs listed since startup
+    pub discovery: Option<String>,
+    /// Export every fill so far to a new file in `reports_dir`
+    pub reports: Option<String>,
+    /// Log where capital should move; every `allocation.report_interval` when unset
+    pub rebalancing: Option<String>,
+    pub reports_dir: PathBuf,
+    /// csv (FIFO cost basis), koinly or cointracking
+    pub report_format: String,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        Self {
+            retention: None,
+            fee_refresh: None,
+            discovery: None,
+            reports: None,
+            rebalancing: None,
+            reports_dir: PathBuf::from("reports"),
+            report_format: "csv".to_string(),
+        }
+    }
+}
+
+impl JobsConfig {
+    /// Every schedule that is set, by job name
+    pub fn schedules(&self) -> [(&'static str, Option<&str>); 5] {
+        [
+            ("retention", self.retention.as_deref()),
+            ("fee_refresh", self.fee_refresh.as_deref()),
+            ("discovery", self.discovery.as_deref()),
+            ("reports", self.reports.as_deref()),
+            ("rebalancing", self.rebalancing.as_deref()),
+        ]
+    }
+}
+
+/// Minutes, hours, days, months and weekdays a cron expression matches, one bit each
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cron {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Both day fields are restricted, so a day matching either runs, as in cron
+    either_day: bool,
+}
+
+impl FromStr for Cron {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expression = s.trim();
+        let expanded = match expression {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!("{:?} needs 5 fields (minute hour day-of-month month day-of-week)", expression));
+        };
+        // Sunday is 0 or 7
+        let weekdays = parse_field(weekday, 0, 7, "day of week")?;
+        Ok(Self {
+            expression: expression.to_string(),
+            minutes: parse_field(minute, 0, 59, "minute")?,
+            hours: parse_field(hour, 0, 23, "hour")?,
+            days: parse_field(day, 1, 31, "day of month")?,
+            months: parse_field(month, 1, 12, "month")?,
+            weekdays: (weekdays | weekdays >> 7) & 0x7f,
+            either_day: !day.starts_with('*') && !weekday.starts_with('*'),
+        })
+    }
+}
+
+/// Bits `min..=max` set by a comma-separated list of `*`, `N` and `A-B`, each
+/// with an optional `/STEP`
+fn parse_field(field: &str, min: u64, max: u64, name: &str) -> Result<u64, String> {
+    let number = |value: &str| match value.parse::<u64>() {
+        Ok(n) if (min..=max).contains(&n) => Ok(n),
+        _ => Err(format!("{} {:?} is not a number from {} to {}", name, value, min, max)),
+    };
+    let mut bits = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u64>() {
+                Ok(step) if step > 0 => (range, Some(step)),
+                _ => return Err(format!("{} step {:?} must be a positive number", name, step)),
+            },
+            None => (part, None),
+        };
+        let (from, to) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((from, to)) => (number(from)?, number(to)?),
+            // "5/15" runs from 5 to the end of the range
+            None if step.is_some() => (number(range)?, max),
+            None => (number(range)?, number(range)?),
+        };
+        if from > to {
+            return Err(format!("{} range {:?} runs backwards", name, range));
+        }
+        for value in (from..=to).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+impl Cron {
+    /// The first matching minute strictly after `after_ms`, unix ms
+    pub fn next_after(&self, after_ms: u64) -> Option<u64> {
+        let start = after_ms - after_ms % MINUTE_MS + MINUTE_MS;
+        let first_day = start / DAY_MS;
+        for day in (first_day..first_day + SEARCH_DAYS).filter(|&day| self.day_matches(day)) {
+            let from = if day == first_day { (start % DAY_MS) / MINUTE_MS } else { 0 };
+            let minute = (from..24 * 60).find(|minute| self.hours & 1 << (minute / 60) != 0 && self.minutes & 1 << (minute % 60) != 0);
+            if let Some(minute) = minute {
+                return Some(day * DAY_MS + minute * MINUTE_MS);
+            }
+        }
+        None
+    }
+    
+    fn day_matches(&self, day: u64) -> bool {
+        let (_, month, day_of_month) = civil_from_days(day);
+        let weekday = (day + 4) % 7; // 1970-01-01 was a Thursday
+        let by_date = self.days & 1 << day_of_month != 0;
+        let by_weekday = self.weekdays & 1 << weekday != 0;
+        self.months & 1 << month != 0 && if self.either_day { by_date || by_weekday } else { by_date && by_weekday }
+    }
+}
+
+impl fmt::Display for Cron {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+/// Year, month and day of `days` since the Unix epoch (Hinnant's civil_from_days)
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// "YYYYMMDD-HHMM" in UTC, for file names
+pub fn utc_stamp(unix_ms: u64) -> String {
+    let (year, month, day) = civil_from_days(unix_ms / DAY_MS);
+    let minute = (unix_ms % DAY_MS) / MINUTE_MS;
+    format!("{:04}{:02}{:02}-{:02}{:02}", year, month, day, minute / 60, minute % 60)
+}
+
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    Cron(Cron),
+    Every(Duration),
+}
+
+impl Schedule {
+    /// `expression` if one is configured, otherwise every `fallback`
+    pub fn or_every(expression: Option<&str>, fallback: Duration) -> Result<Self, String> {
+        match expression {
+            Some(expression) => Ok(Schedule::Cron(expression.parse()?)),
+            None => Ok(Schedule::Every(fallback)),
+        }
+    }
+    
+    pub fn next_after(&self, after_ms: u64) -> Option<u64> {
+        match self {
+            Schedule::Cron(cron) => cron.next_after(after_ms),
+            Schedule::Every(period) => Some(after_ms + period.as_millis() as u64),
+        }
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Schedule::Cron(cron) => cron.fmt(f),
+            Schedule::Every(period) => write!(f, "every {:?}", period),
+        }
+    }
+}
+
+/// What a job is doing and how its runs went
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub name: String,
+    pub schedule: String,
+    pub running: bool,
+    pub runs: u64,
+    pub failures: u64,
+    pub last_started_ms: Option<u64>,
+    pub last_duration_ms: Option<u64>,
+    /// What the last successful run did, e.g. "removed 1200 records"
+    pub last_summary: Option<String>,
+    pub last_error: Option<String>,
+    /// `None` once the schedule has no more runs
+    pub next_run_ms: Option<u64>,
+}
+
+/// Runs each job on its schedule on the runtime, one run at a time, and
+/// keeps its status. A run that overlaps its next slot skips that slot
+pub struct Scheduler {
+    jobs: Mutex<BTreeMap<String, JobStatus>>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(BTreeMap::new()),
+        }
+    }
+    
+    /// Run `job` on `schedule` from now on, and once right away with
+    /// `at_start`. It returns a summary of what it did or why it failed
+    pub fn spawn<F, Fut>(self: &Arc<Self>, name: &str, schedule: Schedule, at_start: bool, job: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String, String>> + Send + 'static,
+    {
+        let now = now_ms();
+        let first = if at_start { Some(now) } else { schedule.next_after(now) };
+        self.jobs.lock().unwrap().insert(
+            name.to_string(),
+            JobStatus {
+                name: name.to_string(),
+                schedule: schedule.to_string(),
+                running: false,
+                runs: 0,
+                failures: 0,
+                last_started_ms: None,
+                last_duration_ms: None,
+                last_summary: None,
+                last_error: None,
+                next_run_ms: first,
+            },
+        );
+        info!("Scheduled job {} ({})", name, schedule);
+        
+        let scheduler = Arc::clone(self);
+        let name = name.to_string();
+        tokio::spawn(async move {
+            let mut next = first;
+            while let Some(at) = next {
+                let now = now_ms();
+                if at > now {
+                    tokio::time::sleep(Duration::from_millis(at - now)).await;
+                }
+                let started = now_ms();
+                scheduler.update(&name, |status| {
+                    status.running = true;
+                    status.last_started_ms = Some(started);
+                });
+                let outcome = job().await;
+                let finished = now_ms();
+                next = schedule.next_after(finished.max(at));
+                scheduler.update(&name, |status| {
+                    status.running = false;
+                    status.runs += 1;
+                    status.last_duration_ms = Some(finished - started);
+                    status.next_run_ms = next;
+                    match &outcome {
+                        Ok(summary) => {
+                            info!("Job {} done in {}ms: {}", name, finished - started, summary);
+                            status.last_summary = Some(summary.clone());
+                            status.last_error = None;
+                        }
+                        Err(e) => {
+                            warn!("Job {} failed: {}", name, e);
+                            status.failures += 1;
+                            status.last_error = Some(e.clone());
+                        }
+                    }
+                });
+            }
+            warn!("Job {} has no further runs on {}", name, schedule);
+        });
+    }
+    
+    fn update(&self, name: &str, change: impl FnOnce(&mut JobStatus)) {
+        if let Some(status) = self.jobs.lock().unwrap().get_mut(name) {
+            change(status);
+        }
+    }
+    
+    /// Every scheduled job, by name
+    pub fn statuses(&self) -> Vec<JobStatus> {
+        self.jobs.lock().unwrap().values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// 2026-10-17 (a Saturday) 10:07:30 UTC
+    const SATURDAY: u64 = 1_792_231_200_000 + 7 * MINUTE_MS + 30_000;
+    
+    fn next(expression: &str, after_ms: u64) -> Option<String> {
+        let cron: Cron = expression.parse().unwrap();
+        cron.next_after(after_ms).map(utc_stamp)
+    }
+    
+    #[test]
+    fn test_cron_finds_the_next_matching_minute() {
+        assert_eq!(utc_stamp(SATURDAY), "20261017-1007");
+        assert_eq!(next("* * * * *", SATURDAY).as_deref(), Some("20261017-1008"));
+        assert_eq!(next("*/15 * * * *", SATURDAY).as_deref(), Some("20261017-1015"));
+        assert_eq!(next("30 3 * * *", SATURDAY).as_deref(), Some("20261018-0330"));
+        assert_eq!(next("0 9-17 * * 1-5", SATURDAY).as_deref(), Some("20261019-0900"));
+        assert_eq!(next("@weekly", SATURDAY).as_deref(), Some("20261018-0000"));
+        assert_eq!(next("0 0 * * 7", SATURDAY).as_deref(), Some("20261018-0000"));
+        assert_eq!(next("0 0 1 * *", SATURDAY).as_deref(), Some("20261101-0000"));
+        assert_eq!(next("0 12 29 2 *", SATURDAY).as_deref(), Some("20280229-1200"));
+        // Either day field matches once both are restricted
+        assert_eq!(next("0 0 1 * 1", SATURDAY).as_deref(), Some("20261019-0000"));
+        assert_eq!(next("0 0 30 2 *", SATURDAY), None);
+        
+        for bad in ["* * * *", "60 * * * *", "* 24 * * *", "*/0 * * * *", "5-1 * * * *", "* * 0 * *", "@often"] {
+            assert!(bad.parse::<Cron>().is_err(), "{} parsed", bad);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_scheduler_records_runs_and_failures() {
+        let scheduler = Arc::new(Scheduler::new());
+        let calls = Arc::new(Mutex::new(0));
+        let counted = Arc::clone(&calls);
+        scheduler.spawn("flaky", Schedule::Every(Duration::from_millis(20)), true, move || {
+            let counted = Arc::clone(&counted);
+            async move {
+                let mut calls = counted.lock().unwrap();
+                *calls += 1;
+                match *calls % 2 {
+                    1 => Ok(format!("run {}", calls)),
+                    _ => Err("venue timed out".to_string()),
+                }
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(70)).await;
+        
+        let statuses = scheduler.statuses();
+        let status = &statuses[0];
+        assert_eq!((status.name.as_str(), status.schedule.as_str()), ("flaky", "every 20ms"));
+        assert!(status.runs >= 2, "{:?}", status);
+        assert_eq!(status.failures, status.runs / 2);
+        assert!(status.next_run_ms.is_some());
+        assert!(status.last_summary.as_deref().is_some_and(|summary| summary.starts_with("run ")));
+    }
+}
@@ -1,91 +1,844 @@
 // main.rs - Entry point for Rust arbitrage scanner
-use std::sync::Arc;
-use tokio::{signal, time::Duration};
-use tracing::{info, error, Level};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use clap::{Parser, Subcommand};
+use futures_util::{SinkExt, StreamExt};
+use tokio::sync::{broadcast, Notify};
+use tokio::time::Duration;
+use tracing::{info, warn, error, Level};
 use tracing_subscriber;
+use warp::Filter;
 
 mod exchange;
 mod arbitrage;
+mod bot;
 mod networking;
 mod alert;
+mod clock;
+mod compliance;
+mod compression;
+mod config;
+mod control;
+mod dex;
+mod display;
+mod execution;
+mod faults;
+mod feetiers;
+mod jobs;
+mod markets;
+mod notify;
+mod outbox;
+mod recording;
+mod service;
+mod silence;
+mod standby;
+mod support;
+mod templates;
 
-use exchange::ExchangeManager;
-use arbitrage::{ArbitrageEngine, Config};
+use exchange::{ExchangeManager, ExchangeRegistry};
+use exchange::subscriptions::SubscriptionTracker;
+use exchange::curve::CurveConfig;
+use exchange::funding::{self, FundingConfig};
+use exchange::discovery::{self, DiscoveryConfig};
+use exchange::deribit::DeribitConfig;
+use exchange::tardis::TardisConfig;
+use arbitrage::{ArbitrageEngine, Config, DetectionBackend};
+use arbitrage::allocation::AllocationConfig;
+use arbitrage::atomicity::AtomicityConfig;
+use arbitrage::basis::BasisConfig;
+use arbitrage::bridge::BridgeConfig;
+use arbitrage::budget::{LatencyBudgets, PipelineStage};
+use arbitrage::dedup::DedupConfig;
+use arbitrage::depth::OrderBookConfig;
+use arbitrage::fairvalue::FairValueConfig;
+use arbitrage::types::OpportunityKind;
+use arbitrage::leadlag::LeadLagConfig;
+use arbitrage::maker::MakerConfig;
+use arbitrage::pruning::PruningConfig;
+use arbitrage::quotes::{CompositeQuote, QuoteBook};
+use arbitrage::replay::{trace_opportunity, OpportunityTrace};
+use arbitrage::statarb::StatArbConfig;
+use arbitrage::rollups::RollupQuery;
+use arbitrage::ticks::{PublishedTick, TickFilter};
+use arbitrage::transfers::TransferConfig;
+use arbitrage::types::ArbitrageOpportunity;
+use arbitrage::washtrade::WashTradingConfig;
 use alert::AlertSystem;
+use bot::{CommandContext, TelegramBot};
+use compliance::{ComplianceConfig, ComplianceGate, ComplianceStage};
+use compression::Conditional;
+use config::{ConfigError, ReloadConfig};
+use control::{ControlAccess, ControlAction, ControlPlane, CustomTrade};
+use dex::{Dex, DexConfig};
+use display::DisplayFormat;
+use execution::ExecutionJournal;
+use execution::analytics::slippage_report;
+use execution::approval::{ApprovalConfig, ApprovalQueue};
+use execution::eod::{EndOfDay, EndOfDayConfig};
+use execution::locks::LockConfig;
+use execution::orders::{OrderStore, OrderVenue, PaperVenue};
+use execution::report::{export_fills, ReportFormat};
+use execution::router::{RouterConfig, SmartOrderRouter};
+use execution::sweeper::{CancelFailure, OrderSweeper, SweepConfig};
+use faults::{FaultConfig, FaultInjector};
+use feetiers::{FeeTierConfig, FeeTiers};
+use jobs::{JobsConfig, Schedule, Scheduler};
+use markets::{AssetAlias, Fees, MarketRegistry, MarketsConfig};
+use notify::{CriticalEvent, Notification, Notifiers, NotifyConfig};
+use notify::batch::AlertBatcher;
+use notify::slack::SlackActions;
+use outbox::{AlertOutbox, OutboxConfig};
+use recording::import::{self, ImportFormat, ImportOptions};
+use recording::{Recorder, RecordingConfig};
+use service::{HandoverListener, Predecessor, ServiceConfig, Supervisor};
+use silence::{SilenceConfig, SilenceMonitor, SilenceSource};
+use standby::{Failover, StandbyConfig, StandbyLink, StandbyRole};
+use support::{BundleQuery, LogTail, SupportBundler, DEFAULT_OPPORTUNITIES, LOG_LINES};
+use templates::AlertTemplates;
+
+/// Outbox channel for alerts handed back to `AlertSystem` on retry
+const ALERT_CHANNEL: &str = "alert_system";
+
+#[derive(Parser)]
+#[command(name = "scanner", about = "Real-time crypto arbitrage scanner")]
+struct Cli {
+    /// TOML or YAML config file; defaults to config.toml/config.yaml in the
+    /// working directory, then to built-in defaults
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// Override any config field, e.g. --set latency_budgets.detect=5ms;
+    /// nested fields are dot-separated and lists comma-separated
+    #[arg(long = "set", value_name = "FIELD=VALUE", global = true)]
+    sets: Vec<String>,
+    /// Shorthand for --set exchanges=...
+    #[arg(long, value_delimiter = ',', global = true)]
+    exchanges: Vec<String>,
+    /// Shorthand for --set symbols=...
+    #[arg(long, value_delimiter = ',', global = true)]
+    symbols: Vec<String>,
+    /// Shorthand for --set min_profit_threshold=...
+    #[arg(long, global = true)]
+    min_profit_threshold: Option<f64>,
+    /// Shorthand for --set dashboard_port=...
+    #[arg(long, global = true)]
+    dashboard_port: Option<u16>,
+    /// Report to the Windows service control manager; add it to the
+    /// service's command line (Windows only)
+    #[arg(long)]
+    windows_service: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+impl Cli {
+    /// Every config override on the command line as FIELD=VALUE, the
+    /// shorthand flags after --set so they win
+    fn overrides(&self) -> Vec<String> {
+        let mut sets = self.sets.clone();
+        if !self.exchanges.is_empty() {
+            sets.push(format!("exchanges={}", self.exchanges.join(",")));
+        }
+        if !self.symbols.is_empty() {
+            sets.push(format!("symbols={}", self.symbols.join(",")));
+        }
+        if let Some(threshold) = self.min_profit_threshold {
+            sets.push(format!("min_profit_threshold={}", threshold));
+        }
+        if let Some(port) = self.dashboard_port {
+            sets.push(format!("dashboard_port={}", port));
+        }
+        sets
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Validate the configuration (including environment and CLI overrides) and exit
+    CheckConfig,
+    /// Replay the recorded ticks around an opportunity and show which updates
+    /// created and destroyed it
+    DebugOpportunity {
+        /// Id from the "Recorded opportunity #<id>" log line
+        id: u64,
+        /// Seconds of ticks to replay on each side of the detection
+        #[arg(long, default_value_t = 30)]
+        window_secs: u64,
+    },
+    /// Import historical quotes (binance, tardis or kaiko CSV) into the recording
+    Import {
+        #[arg(long)]
+        format: ImportFormat,
+        /// Venue for files without an exchange column
+        #[arg(long)]
+        exchange: Option<String>,
+        /// BASE/QUOTE for every row, for files that don't name the symbol
+        #[arg(long)]
+        symbol: Option<String>,
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Download recent logs, stats history, the redacted config, connection
+    /// states and recent opportunities from the running scanner as a zip to
+    /// attach to bug reports
+    SupportBundle {
+        /// Where to write the zip; defaults to support-bundle-<unix time>.zip
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// How many of the most recent opportunities to include
+        #[arg(long, default_value_t = DEFAULT_OPPORTUNITIES)]
+        opportunities: usize,
+    },
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize logging
+    let cli = Cli::parse();
+    
+    // Initialize logging; replays trace every tick that touches the cycle.
+    // The last lines are also kept for support bundles
+    let level = match cli.command {
+        Some(Command::DebugOpportunity { .. }) => Level::DEBUG,
+        _ => Level::INFO,
+    };
+    let log_tail = Arc::new(LogTail::new(LOG_LINES));
+    let tail = log_tail.clone();
     tracing_subscriber::fmt()
-        .with_max_level(Level::INFO)
+        .with_max_level(level)
         .with_target(false)
         .with_thread_ids(true)
+        .with_writer(move || tail.writer())
         .init();
-
+    
     info!("Starting Rust Arbitrage Scanner...");
-
-    // Load configuration
-    let config = load_config().await?;
+    
+    let sources = ConfigSources {
+        file: cli.config.clone(),
+        overrides: cli.overrides(),
+        discovered: Arc::new(RwLock::new(Vec::new())),
+        exchanges: Arc::new(ExchangeRegistry::builtin()),
+    };
+    let mut config = sources.load()?;
+    
+    if let Some(Command::CheckConfig) = cli.command {
+        return match config::validate(&config, &sources.exchanges) {
+            Ok(()) => {
+                println!(
+                    "Configuration OK: {} exchanges, {} symbols, dashboard on port {}",
+                    config.exchanges.len(),
+                    config.symbols.len(),
+                    config.dashboard_port
+                );
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        };
+    }
+    if let Some(Command::DebugOpportunity { id, window_secs }) = cli.command {
+        return match debug_opportunity(&config, id, Duration::from_secs(window_secs)) {
+            Ok(trace) => {
+                println!("{}", trace);
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        };
+    }
+    if let Some(Command::Import { format, exchange, symbol, files }) = cli.command {
+        let options = ImportOptions { exchange, symbol };
+        for file in &files {
+            match import::import_file(file, format, &options, &sources.exchanges, &config.recording.dir) {
+                Ok(stats) => {
+                    println!("{}: imported {} ticks, skipped {} rows", file.display(), stats.imported, stats.skipped);
+                    if let Some(error) = stats.first_error {
+                        println!("  first skipped row: {}", error);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        return Ok(());
+    }
+    if let Some(Command::SupportBundle { output, opportunities }) = cli.command {
+        return match download_support_bundle(config.dashboard_port, output, opportunities).await {
+            Ok((path, bytes)) => {
+                println!("Wrote support bundle to {} ({} bytes)", path.display(), bytes);
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        };
+    }
+    if config.discovery.enabled {
+        *sources.discovered.write().unwrap() = discovery::discover(&config, &sources.exchanges, &reqwest::Client::new()).await?;
+        config = sources.load()?;
+    }
+    
+    // With handover on, a scanner already running replaces itself with this
+    // one: it keeps alerting until this one's feeds are up, and keeps the
+    // dashboard port until then too
+    let predecessor = match config.service.handover {
+        true => Predecessor::find(&config.service.handover_listen).await,
+        false => None,
+    };
+    match predecessor {
+        Some(_) => {
+            let issues = config::check_fields(&config, &sources.exchanges);
+            if !issues.is_empty() {
+                return Err(ConfigError::Invalid(issues).into());
+            }
+        }
+        None => config::validate(&config, &sources.exchanges)?,
+    }
+    let supervisor = Supervisor::connect(cli.windows_service);
     
     // Initialize core components
-    let arbitrage_engine = Arc::new(ArbitrageEngine::new(config.clone()));
+    let mut arbitrage_engine = ArbitrageEngine::new(config.clone());
+    let recorder = match config.recording.enabled {
+        true => Some(Arc::new(Recorder::open(&config.recording.dir)?)),
+        false => None,
+    };
+    if let Some(recorder) = &recorder {
+        arbitrage_engine = arbitrage_engine.with_recorder(recorder.clone());
+    }
+    let standby_link = (config.standby.role == StandbyRole::Primary).then(|| Arc::new(StandbyLink::new()));
+    if let Some(link) = &standby_link {
+        arbitrage_engine = arbitrage_engine.with_relay(link.clone());
+    }
+    let failover = Arc::new(Failover::new(config.standby.role));
+    if predecessor.is_some() {
+        failover.demote();
+    }
+    let arbitrage_engine = Arc::new(arbitrage_engine);
     let alert_system = Arc::new(AlertSystem::new(config.clone()));
-    let exchange_manager = ExchangeManager::new(config.clone(), arbitrage_engine.clone());
-
+    let exchange_manager = Arc::new(ExchangeManager::new(config.clone(), arbitrage_engine.clone(), sources.exchanges.clone()));
+    let execution_journal = Arc::new(ExecutionJournal::new());
+    let order_store = Arc::new(OrderStore::open(config.order_store.clone()));
+    let order_venues: Vec<Arc<dyn OrderVenue>> = config
+        .exchanges
+        .iter()
+        .map(|exchange| Arc::new(PaperVenue::new(exchange)) as Arc<dyn OrderVenue>)
+        .collect();
+    let sweeper = Arc::new(OrderSweeper::new(config.stale_orders.clone(), order_store.clone(), order_venues.clone()));
+    let alert_outbox = Arc::new(AlertOutbox::open(config.alert_outbox.clone()));
+    let alert_templates = Arc::new(AlertTemplates::from_config(
+        &config.alert_templates,
+        &config.alert_languages,
+        &config.display,
+    )?);
+    let notifiers = Arc::new(Notifiers::from_config(&config.notifications));
+    let batcher = config.notifications.batching.clone().map(|batching| Arc::new(AlertBatcher::new(batching)));
+    let approvals = Arc::new(ApprovalQueue::new(config.approval.clone()));
+    let compliance = Arc::new(ComplianceGate::from_config(&config.compliance));
+    let silence = Arc::new(SilenceMonitor::new(&config.silence, arbitrage_engine.clock()));
+    
     // Setup opportunity alerting
     let alert_system_clone = alert_system.clone();
+    let outbox_clone = alert_outbox.clone();
+    let notifiers_clone = notifiers.clone();
+    let templates_clone = alert_templates.clone();
+    let quote_book = arbitrage_engine.quote_book();
+    let display = config.display.clone();
+    let compliance_clone = compliance.clone();
+    let approvals_clone = approvals.clone();
+    let budgets = arbitrage_engine.budget_tracker();
+    let failover_clone = failover.clone();
+    let batcher_clone = batcher.clone();
     arbitrage_engine.register_callback(Box::new(move |opportunity| {
+        // A standby detects to stay warm but leaves alerting to the primary,
+        // and a successor to the scanner it replaces
+        if !failover_clone.is_active() {
+            return;
+        }
+        if compliance_clone.evaluate(&opportunity, ComplianceStage::Alert).is_err() {
+            return;
+        }
+        // In manual mode the alert doubles as the approval request
+        let approval_id = approvals_clone.submit(&opportunity, outbox::now_ms());
         let alert_system = alert_system_clone.clone();
+        let outbox = outbox_clone.clone();
+        let notifiers = notifiers_clone.clone();
+        let budgets = budgets.clone();
+        let mut title = notify::title(&opportunity, &display.profit(opportunity.profit_percentage));
+        let composite = quote_book.deviations(&opportunity.path);
+        if let Some(id) = approval_id {
+            title.push_str(&format!(" - approve #{}", id));
+        }
+        // Low-profit alerts wait for each channel's digest; approval requests can't
+        let batcher = batcher_clone.as_ref().filter(|b| approval_id.is_none() && b.batches(&opportunity));
+        if let Some(batcher) = batcher {
+            for notifier in notifiers.for_opportunity(&opportunity) {
+                batcher.add(notifier.channel(), &opportunity);
+            }
+        }
+        let pushes: Vec<(String, Notification)> = notifiers
+            .for_opportunity(&opportunity)
+            .filter(|_| batcher.is_none())
+            .map(|notifier| {
+                let notification = Notification {
+                    title: title.clone(),
+                    body: templates_clone.render(notifier.channel(), &opportunity, &composite),
+                    path: Some(opportunity.path.clone()),
+                    approval_id,
+                };
+                (notifier.channel().to_string(), notification)
+            })
+            .collect();
         let opp = opportunity.clone();
         tokio::spawn(async move {
+            let started = std::time::Instant::now();
+            let payload = serde_json::to_value(&opp);
             if let Err(e) = alert_system.send_alert(opp).await {
-                error!("Failed to send alert: {}", e);
+                warn!("Alert delivery failed, queueing for retry: {}", e);
+                match payload {
+                    Ok(payload) => {
+                        outbox.enqueue(ALERT_CHANNEL, payload, outbox::now_ms());
+                    }
+                    Err(e) => error!("Failed to queue alert: {}", e),
+                }
             }
+            for (channel, notification) in pushes {
+                push(&notifiers, &outbox, &channel, &notification).await;
+            }
+            budgets.record(PipelineStage::Alert, started.elapsed());
         });
     }));
-
+    
+    // Incidents that need a human escalate to phone channels
+    let notifiers_clone = notifiers.clone();
+    let outbox_clone = alert_outbox.clone();
+    exchange_manager.register_down_callback(Box::new(move |down| {
+        let event = CriticalEvent::ExchangeDown {
+            exchange: down.exchange,
+            attempts: down.attempts,
+            error: down.error,
+        };
+        escalate(event, &notifiers_clone, &outbox_clone);
+    }));
+    let notifiers_clone = notifiers.clone();
+    let outbox_clone = alert_outbox.clone();
+    exchange_manager.schema_monitor().register_callback(Box::new(move |alert| {
+        let event = CriticalEvent::ProtocolDrift {
+            exchange: alert.exchange,
+            drift: format!("{:?} x{}", alert.drift, alert.occurrences),
+        };
+        escalate(event, &notifiers_clone, &outbox_clone);
+    }));
+    
+    // Exchanges and detectors that go quiet are paged like any other incident
+    if silence.is_enabled() {
+        let monitor = silence.clone();
+        arbitrage_engine.register_callback(Box::new(move |_| monitor.observe(SilenceSource::Detector, "cycles")));
+        let monitor = silence.clone();
+        arbitrage_engine.register_structural_callback(Box::new(move |_| monitor.observe(SilenceSource::Detector, "structural")));
+        let monitor = silence.clone();
+        arbitrage_engine.register_signal_callback(Box::new(move |_| monitor.observe(SilenceSource::Detector, "statarb")));
+        let ticks = arbitrage_engine.subscribe_ticks();
+        tokio::spawn(run_silence_monitor(silence.clone(), ticks, failover.clone(), notifiers.clone(), alert_outbox.clone()));
+    }
+    
+    // Legs left open by a dropped connection are settled once it's back, and
+    // every change counts toward the exchange's uptime rollup
+    let sweeper_clone = sweeper.clone();
+    let notifiers_clone = notifiers.clone();
+    let outbox_clone = alert_outbox.clone();
+    let engine_clone = arbitrage_engine.clone();
+    exchange_manager.register_connection_callback(Box::new(move |change| {
+        engine_clone.record_connection(&change.exchange, change.connected);
+        if !change.connected {
+            sweeper_clone.connection_lost(&change.exchange);
+            return;
+        }
+        let sweeper = sweeper_clone.clone();
+        let notifiers = notifiers_clone.clone();
+        let outbox = outbox_clone.clone();
+        tokio::spawn(async move {
+            let failures = sweeper.connection_restored(&change.exchange, outbox::now_ms()).await;
+            escalate_cancel_failures(failures, &notifiers, &outbox);
+        });
+    }));
+    
+    // Structural spreads are an operational signal, not a trade
+    arbitrage_engine.register_structural_callback(Box::new(|spread| {
+        warn!(
+            "STRUCTURAL SPREAD {} ({:.4}%) open for {}s on {:?} - path blacklisted for {}s",
+            spread.path,
+            spread.profit_percentage * 100.0,
+            spread.persisted_secs,
+            spread.exchanges,
+            spread.blacklisted_secs
+        );
+    }));
+    
+    // Mean-reversion signals are logged next to cycles; they aren't paged
+    arbitrage_engine.register_signal_callback(Box::new(|signal| {
+        let legs: Vec<String> = signal.legs.iter().map(|leg| format!("{:?} {} on {}", leg.side, leg.symbol, leg.exchange)).collect();
+        info!(
+            "SPREAD {:?} {} {:?} at z {:.2} ({:+.1}bps): {}",
+            signal.action,
+            signal.spread,
+            signal.side,
+            signal.z_score,
+            signal.deviation_bps,
+            legs.join(", ")
+        );
+    }));
+    
     // Start all systems
-    info!("Starting exchange connections...");
-    exchange_manager.start().await?;
-
+    let standby = config.standby.role == StandbyRole::Standby;
+    if !standby || config.standby.direct_feeds {
+        info!("Starting exchange connections...");
+        exchange_manager.start().await?;
+    }
+    
     info!("Starting arbitrage engine...");
     arbitrage_engine.start().await;
-
+    
+    // A standby mirrors the primary until its heartbeat stops, then carries on
+    // from here as the active scanner, reconciling the legs it was handed
+    if standby {
+        info!("Standing by for primary {}", config.standby.primary);
+        let takeover = tokio::select! {
+            takeover = standby::follow(&config.standby, &failover, &arbitrage_engine, &order_store) => takeover,
+            _ = service::stop_requested() => {
+                arbitrage_engine.stop().await;
+                supervisor.stopped();
+                return Ok(());
+            }
+        };
+        let event = CriticalEvent::Takeover {
+            primary: config.standby.primary.clone(),
+            silent_secs: takeover.silent_for.as_secs(),
+            in_flight: takeover.in_flight,
+        };
+        escalate(event, &notifiers, &alert_outbox);
+        if !config.standby.direct_feeds {
+            info!("Starting exchange connections...");
+            exchange_manager.start().await?;
+        }
+    }
+    
+    // A successor takes over once its subscriptions are confirmed: the
+    // running scanner stops alerting, closes its dashboard and hands over
+    // its in-flight legs. A failed handover leaves that scanner running
+    let took_over = predecessor.is_some();
+    if let Some(predecessor) = predecessor {
+        info!("Warming up to take over from the scanner on {}", predecessor.addr());
+        let warmup = config.service.warmup_timeout;
+        let subscriptions = exchange_manager.subscriptions();
+        let handoff = tokio::select! {
+            settled = service::feeds_settled(&subscriptions, &config.exchanges, warmup) => {
+                if !settled {
+                    warn!("Subscriptions still unconfirmed after {:?}; taking over anyway", warmup);
+                }
+                predecessor.release(config.service.release_timeout).await?
+            }
+            _ = service::stop_requested() => {
+                exchange_manager.stop().await;
+                arbitrage_engine.stop().await;
+                return Ok(());
+            }
+        };
+        info!("Took over with {} in-flight legs", handoff.legs.len());
+        order_store.adopt(handoff);
+        failover.promote();
+    }
+    
     info!("Starting alert system...");
     alert_system.start().await?;
-
+    let outbox_handle = tokio::spawn(run_alert_outbox(alert_outbox.clone(), alert_system.clone(), notifiers.clone()));
+    if let Some(batcher) = batcher {
+        tokio::spawn(run_alert_digests(batcher, notifiers.clone(), alert_outbox.clone(), config.display.clone()));
+    }
+    
+    // Settle legs a previous run left in flight before anything new executes
+    let reconciled = order_store.reconcile(&order_venues, outbox::now_ms()).await;
+    if reconciled != Default::default() {
+        info!(
+            "Order recovery: {} resumed, {} closed, {} unresolved",
+            reconciled.resumed, reconciled.closed, reconciled.unresolved
+        );
+    }
+    tokio::spawn(run_order_sweeper(sweeper.clone(), notifiers.clone(), alert_outbox.clone()));
+    
+    // Ticks, heartbeats and the in-flight legs for a warm standby
+    if let Some(link) = standby_link {
+        let standby_config = config.standby.clone();
+        let orders = order_store.clone();
+        tokio::spawn(async move {
+            if let Err(e) = link.serve(standby_config, orders).await {
+                error!("Standby link failed: {}", e);
+            }
+        });
+    }
+    
+    let router = Arc::new(SmartOrderRouter::new(config.order_routing.clone(), order_store.clone(), order_venues.clone()));
+    
+    // Approve / paper execute / blacklist / snooze, from the control API, Slack
+    // buttons and Telegram
+    let mut control_plane = ControlPlane::new(
+        arbitrage_engine.clone(),
+        execution_journal.clone(),
+        approvals.clone(),
+        compliance.clone(),
+        config.display.clone(),
+    )
+    .with_locks(config.execution_locks.clone())
+    .with_orders(order_store.clone())
+    .with_router(router.clone());
+    let faults = &config.fault_injection;
+    if let Some(executor_faults) = FaultInjector::for_profile("executor", &faults.executor, faults.seed) {
+        control_plane = control_plane.with_executor_faults(executor_faults);
+    }
+    let control_plane = Arc::new(control_plane);
+    
+    // Precision, symbol maps and fees from CCXT market metadata
+    let markets = Arc::new(MarketRegistry::new());
+    if !config.markets.sources.is_empty() {
+        tokio::spawn(markets.clone().run(config.markets.clone()));
+    }
+    
+    // Periodic upkeep, on `jobs` cron schedules or the intervals they replace
+    let scheduler = Arc::new(Scheduler::new());
+    
+    // The rates each credentialed account actually pays, or else the venue's
+    // published schedule, instead of `fees`
+    let fee_tiers = Arc::new(FeeTiers::from_config(&config.fee_tiers, &config.exchanges));
+    if fee_tiers.is_enabled() {
+        let schedule = Schedule::or_every(config.jobs.fee_refresh.as_deref(), fee_tiers.refresh_interval())?;
+        let (tiers, engine, client) = (fee_tiers.clone(), arbitrage_engine.clone(), reqwest::Client::new());
+        scheduler.spawn("fee_refresh", schedule, true, move || refresh_fee_tiers(tiers.clone(), engine.clone(), client.clone()));
+    }
+    
+    // Uniswap pools as one more venue, each swap charged the gas it burns
+    let dex = Arc::new(Dex::from_config(&config.dex));
+    if dex.is_enabled() {
+        tokio::spawn(run_dex(dex.clone(), arbitrage_engine.clone()));
+    }
+    
+    // No overnight exposure: flatten what the day's trading left behind
+    if config.end_of_day.enabled {
+        let eod = EndOfDay::new(
+            config.end_of_day.clone(),
+            arbitrage_engine.clone(),
+            order_store.clone(),
+            router.clone(),
+            execution_journal.clone(),
+        );
+        tokio::spawn(Arc::new(eod).run());
+    }
+    
+    // Spot vs perpetual funding, from the perpetual venues' REST APIs
+    if !config.funding.venues.is_empty() {
+        tokio::spawn(funding::run(arbitrage_engine.clone(), config.funding.clone(), config.symbols.clone()));
+    }
+    
+    // Periodic target-inventory report for the configured balances
+    if !config.allocation.balances.is_empty() {
+        let schedule = Schedule::or_every(config.jobs.rebalancing.as_deref(), config.allocation.report_interval)?;
+        let engine = arbitrage_engine.clone();
+        scheduler.spawn("rebalancing", schedule, false, move || log_allocation_report(engine.clone()));
+    }
+    
+    // Recording retention, fill reports and repeated symbol discovery run
+    // only when `jobs` schedules them
+    if let (Some(expression), Some(recorder)) = (&config.jobs.retention, &recorder) {
+        let (recorder, retention) = (recorder.clone(), config.recording.retention);
+        scheduler.spawn("retention", Schedule::Cron(expression.parse()?), false, move || trim_recording(recorder.clone(), retention));
+    }
+    if let Some(expression) = &config.jobs.reports {
+        let (journal, dir) = (execution_journal.clone(), config.jobs.reports_dir.clone());
+        let format: ReportFormat = config.jobs.report_format.parse()?;
+        scheduler.spawn("reports", Schedule::Cron(expression.parse()?), false, move || {
+            write_fills_report(journal.clone(), dir.clone(), format)
+        });
+    }
+    let rediscovered = Arc::new(Notify::new());
+    if let Some(expression) = &config.jobs.discovery {
+        let (sources, rediscovered, engine, client) = (sources.clone(), rediscovered.clone(), arbitrage_engine.clone(), reqwest::Client::new());
+        scheduler.spawn("discovery", Schedule::Cron(expression.parse()?), false, move || {
+            rediscover(sources.clone(), rediscovered.clone(), engine.clone(), client.clone())
+        });
+    }
+    
+    // Remote control over Telegram, for allowed chats only
+    let bot_handle = config.telegram_bot.clone().map(|bot_config| {
+        let context = CommandContext {
+            engine: arbitrage_engine.clone(),
+            journal: execution_journal.clone(),
+            exchanges: config.exchanges.clone(),
+            display: config.display.clone(),
+            control: control_plane.clone(),
+        };
+        tokio::spawn(TelegramBot::new(bot_config, context).run())
+    });
+    
+    let slack_actions = config
+        .notifications
+        .slack
+        .as_ref()
+        .and_then(|slack| slack.signing_secret.clone())
+        .map(|secret| Arc::new(SlackActions::new(secret)));
+    
     // Start web dashboard
     let dashboard_handle = tokio::spawn(start_web_dashboard(
         config.dashboard_port, 
-        arbitrage_engine.clone()
+        DashboardState {
+            engine: arbitrage_engine.clone(),
+            subscriptions: exchange_manager.subscriptions(),
+            journal: execution_journal.clone(),
+            alert_outbox: alert_outbox.clone(),
+            alert_templates: alert_templates.clone(),
+            display: Arc::new(config.display.clone()),
+            control_plane: control_plane.clone(),
+            control_access: Arc::new(ControlAccess::new(config.control_token.clone())),
+            markets: markets.clone(),
+            asset_aliases: Arc::new(config.markets.aliases.clone()),
+            slack_actions,
+            silence: silence.clone(),
+            fee_tiers: fee_tiers.clone(),
+            dex: dex.clone(),
+            support: Arc::new(SupportBundler::new(
+                arbitrage_engine.clone(),
+                exchange_manager.clone(),
+                log_tail,
+                Arc::new(config.display.clone()),
+            )),
+            scheduler: scheduler.clone(),
+        },
     ));
-
+    
+    // Symbols, thresholds and alert templates follow config edits without a restart
+    tokio::spawn(run_config_reloader(
+        config.clone(),
+        sources,
+        rediscovered,
+        arbitrage_engine.clone(),
+        exchange_manager.clone(),
+        alert_templates.clone(),
+    ));
+    
+    // Where the next restart's successor finds this scanner; SIGUSR2 starts one
+    let handover = match config.service.handover {
+        true => match HandoverListener::bind(&config.service.handover_listen).await {
+            Ok(listener) => Some(listener),
+            Err(e) => {
+                error!("Handover unavailable, restarts will stop this scanner first: {}", e);
+                None
+            }
+        },
+        false => None,
+    };
+    #[cfg(unix)]
+    if handover.is_some() {
+        tokio::spawn(service::run_restart_signal());
+    }
+    
     info!("Arbitrage scanner running on all cores...");
     info!("Web dashboard available at http://localhost:{}", config.dashboard_port);
     info!("Press Ctrl+C to shutdown");
-
-    // Wait for shutdown signal
+    let status = format!("Scanning {} symbols on {} exchanges", config.symbols.len(), config.exchanges.len());
+    if took_over {
+        supervisor.took_over(&status);
+    } else {
+        supervisor.ready(&status);
+    }
+    
+    // Wait for shutdown signal, or a successor to hand over to
+    let mut dashboard_handle = dashboard_handle;
+    let successor = async {
+        match handover {
+            Some(listener) => listener.successor().await,
+            None => std::future::pending().await,
+        }
+    };
     tokio::select! {
-        _ = signal::ctrl_c() => {
-            info!("Received shutdown signal");
+        _ = service::stop_requested() => supervisor.stopping(),
+        successor = successor => {
+            // Alerting stops and the dashboard port frees up before the
+            // successor starts either
+            let pid = successor.pid();
+            info!("Handing over to successor process {}", pid);
+            failover.demote();
+            dashboard_handle.abort();
+            let _ = (&mut dashboard_handle).await;
+            if let Err(e) = successor.release(order_store.handoff()).await {
+                error!("Handover to process {} failed: {}", pid, e);
+            }
         }
-        result = dashboard_handle => {
+        result = &mut dashboard_handle => {
             error!("Web dashboard crashed: {:?}", result);
         }
     }
-
+    
     // Graceful shutdown
     info!("Shutting down...");
+    for (path, stats) in [
+        ("feeds", exchange_manager.feed_fault_stats()),
+        ("executor", control_plane.executor_fault_stats()),
+    ] {
+        if let Some(stats) = stats {
+            info!("Injected faults on {}: {:?}", path, stats);
+        }
+    }
     exchange_manager.stop().await;
     arbitrage_engine.stop().await;
+    outbox_handle.abort();
+    if let Some(handle) = bot_handle {
+        handle.abort();
+    }
     alert_system.stop().await;
+    supervisor.stopped();
     
     info!("Shutdown complete");
     Ok(())
 }
 
-async fn load_config() -> Result<Config, Box<dyn std::error::Error>> {
-    Ok(Config {
+/// Where the config comes from, kept so a reload re-reads the same sources
+#[derive(Clone)]
+struct ConfigSources {
+    file: Option<PathBuf>,
+    overrides: Vec<String>, // --set and shorthand flags, as FIELD=VALUE
+    discovered: Arc<RwLock<Vec<String>>>, // Found by `discovery` at startup or by its job; kept across reloads
+    exchanges: Arc<ExchangeRegistry>, // What `exchanges` can name; the exchange manager runs them
+}
+
+impl ConfigSources {
+    /// Precedence: command line, then SCANNER_* environment variables, then the config file, then defaults
+    fn load(&self) -> Result<Config, Box<dyn std::error::Error>> {
+        let config = config::apply_env_overrides(load_config(self.file.as_deref())?, std::env::vars())?;
+        let mut config = config::apply_cli_overrides(config, &self.overrides)?;
+        if config.discovery.enabled {
+            discovery::add_symbols(&mut config.symbols, &self.discovered.read().unwrap());
+        }
+        Ok(self.exchanges.with_exchange_fees(config))
+    }
+}
+
+/// Built-in defaults overlaid with the config file, if there is one
+fn load_config(explicit: Option<&Path>) -> Result<Config, Box<dyn std::error::Error>> {
+    let defaults = default_config();
+    match config::config_file(explicit) {
+        Some(path) => {
+            info!("Loading configuration from {}", path.display());
+            Ok(config::load_file(defaults, &path)?)
+        }
+        None => Ok(defaults),
+    }
+}
+
+fn default_config() -> Config {
+    Config {
         exchanges: vec!["binance", "coinbase", "kraken"]
             .into_iter()
             .map(|s| s.to_string())
@@ -94,107 +847,1449 @@ async fn load_config() -> Result<Config, Box<dyn std::error::Error>> {
             .into_iter()
             .map(|s| s.to_string())
             .collect(),
+        discovery: DiscoveryConfig::default(),
         min_profit_threshold: 0.001, // 0.1%
+        // Published base-tier rates; set your own tier in the config file
+        fees: HashMap::from([
+            ("binance".to_string(), Fees { maker: 0.001, taker: 0.001, charged_in: None }),
+            ("coinbase".to_string(), Fees { maker: 0.004, taker: 0.006, charged_in: None }),
+            ("kraken".to_string(), Fees { maker: 0.0016, taker: 0.0026, charged_in: None }),
+        ]),
+        transfers: TransferConfig::default(),
+        maker_legs: MakerConfig::default(),
+        atomicity: AtomicityConfig::default(),
+        fee_tiers: FeeTierConfig::default(),
+        dex: DexConfig::default(),
         max_position_size: 1000.0,
         dashboard_port: 8080,
+        control_token: None,
         websocket_timeout: Duration::from_secs(30),
         reconnect_interval: Duration::from_secs(5),
         max_reconnect_attempts: 10,
+        use_testnet: false,
         enable_triangle_arbitrage: true,
         enable_cross_exchange: true,
+        bridge: BridgeConfig::default(),
         thread_pool_size: num_cpus::get(),
         enable_thread_pinning: true,
-    })
+        opportunity_history_size: 1000,
+        heat_window: Duration::from_secs(24 * 3600),
+        allocation: AllocationConfig::default(),
+        subscription_ack_timeout: Duration::from_secs(10),
+        auto_shard_subscriptions: false,
+        tardis: TardisConfig::default(),
+        deribit: DeribitConfig::default(),
+        curve: CurveConfig::default(),
+        funding: FundingConfig::default(),
+        basis: BasisConfig::default(),
+        structural_spread_after: Duration::from_secs(300),
+        structural_blacklist_duration: Duration::from_secs(3600),
+        opportunity_dedup: DedupConfig::default(),
+        opportunity_cluster_similarity: 0.6,
+        compliance: ComplianceConfig::default(),
+        latency_budgets: LatencyBudgets::default(),
+        hot_detection_interval: Duration::from_millis(2),
+        cold_detection_interval: Duration::from_millis(10),
+        hot_symbol_ttl: Duration::from_secs(30),
+        gpu_detection_min_nodes: 128,
+        route_scan_interval: Duration::from_secs(5),
+        detection_backend: DetectionBackend::BellmanFord,
+        graph_partitions: Vec::new(),
+        edge_pruning: PruningConfig::default(),
+        alert_outbox: OutboxConfig::default(),
+        alert_templates: HashMap::new(),
+        alert_languages: HashMap::new(),
+        display: DisplayFormat::default(),
+        notifications: NotifyConfig::default(),
+        telegram_bot: None,
+        approval: ApprovalConfig::default(),
+        execution_locks: LockConfig::default(),
+        order_store: PathBuf::from("orders.json"),
+        stale_orders: SweepConfig::default(),
+        order_routing: RouterConfig::default(),
+        end_of_day: EndOfDayConfig::default(),
+        fault_injection: FaultConfig::default(),
+        recording: RecordingConfig::default(),
+        markets: MarketsConfig::default(),
+        fair_value: FairValueConfig::default(),
+        lead_lag: LeadLagConfig::default(),
+        stat_arb: StatArbConfig::default(),
+        wash_trading: WashTradingConfig::default(),
+        order_book: OrderBookConfig::default(),
+        config_reload: ReloadConfig::default(),
+        standby: StandbyConfig::default(),
+        service: ServiceConfig::default(),
+        jobs: JobsConfig::default(),
+        silence: SilenceConfig::default(),
+    }
 }
 
-async fn start_web_dashboard(
+/// Fields of a queued `ArbitrageOpportunity`; `detected_at` isn't serialized,
+/// so retried alerts are stamped when they're finally delivered
+#[derive(serde::Deserialize)]
+struct QueuedOpportunity {
+    /// Absent from alerts queued before it was recorded
+    #[serde(default)]
+    kind: Option<OpportunityKind>,
+    path: String,
+    profit_percentage: f64,
+    /// Absent from alerts queued before it was recorded
+    #[serde(default)]
+    gross_profit: Option<f64>,
+    max_volume: f64,
+    confidence: u32,
+    exchanges: Vec<String>,
+}
+
+/// Send on one notifier channel, queueing for retry on failure
+/// Find opportunity `id` in the recording and replay `window` of ticks either side of it
+fn debug_opportunity(config: &Config, id: u64, window: Duration) -> Result<OpportunityTrace, String> {
+    let dir = &config.recording.dir;
+    let opportunities = recording::read_opportunities(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let Some(opportunity) = opportunities.iter().find(|opp| opp.id == id) else {
+        let recent: Vec<String> = opportunities.iter().rev().take(5).map(|opp| format!("#{}", opp.id)).collect();
+        return Err(format!(
+            "no opportunity #{} recorded in {} (latest: {})",
+            id,
+            dir.display(),
+            if recent.is_empty() { "none".to_string() } else { recent.join(", ") }
+        ));
+    };
+    
+    let window_ms = window.as_millis() as u64;
+    let ticks = recording::read_ticks(dir, opportunity.at_ms.saturating_sub(window_ms), opportunity.at_ms + window_ms)
+        .map_err(|e| format!("{}: {}", dir.display(), e))?;
+    info!("Replaying {} ticks around opportunity #{}", ticks.len(), id);
+    Ok(trace_opportunity(opportunity, &ticks, config))
+}
+
+/// Fetch a support bundle from the scanner's dashboard on `port` and save it
+async fn download_support_bundle(
     port: u16,
-    engine: Arc<ArbitrageEngine>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    use warp::Filter;
+    output: Option<PathBuf>,
+    opportunities: usize,
+) -> Result<(PathBuf, usize), String> {
+    let url = format!("http://127.0.0.1:{}/api/support/bundle?opportunities={}", port, opportunities);
+    let response = reqwest::get(&url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("no support bundle from {} (is the scanner running?): {}", url, e))?;
+    let zip = response.bytes().await.map_err(|e| format!("{}: {}", url, e))?;
+    
+    let path = output.unwrap_or_else(|| PathBuf::from(format!("support-bundle-{}.zip", outbox::now_ms() / 1000)));
+    std::fs::write(&path, &zip).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok((path, zip.len()))
+}
 
-    // CORS headers
-    let cors = warp::cors()
-        .allow_any_origin()
-        .allow_headers(vec!["content-type"])
-        .allow_methods(vec!["GET", "POST", "OPTIONS"]);
+async fn push(notifiers: &Notifiers, outbox: &AlertOutbox, channel: &str, notification: &Notification) {
+    let Some(notifier) = notifiers.get(channel) else { return };
+    if let Err(e) = notifier.send(notification).await {
+        warn!("{} push failed, queueing for retry: {}", channel, e);
+        if let Ok(payload) = serde_json::to_value(notification) {
+            outbox.enqueue(channel, payload, outbox::now_ms());
+        }
+    }
+}
 
-    // API routes
-    let api = warp::path("api");
+/// Page every channel on the escalation path for `event`
+fn escalate(event: CriticalEvent, notifiers: &Arc<Notifiers>, outbox: &Arc<AlertOutbox>) {
+    error!("CRITICAL {}", event);
+    let notification = event.notification();
+    for notifier in notifiers.for_event(&event) {
+        let channel = notifier.channel().to_string();
+        let notifiers = notifiers.clone();
+        let outbox = outbox.clone();
+        let notification = notification.clone();
+        tokio::spawn(async move {
+            push(&notifiers, &outbox, &channel, &notification).await;
+        });
+    }
+}
 
-    // Get recent opportunities
-    let opportunities = api
-        .and(warp::path("opportunities"))
-        .and(warp::get())
-        .and(with_engine(engine.clone()))
-        .and_then(get_opportunities);
+/// Page for legs that are still open at the venue after a sweep tried to cancel them
+fn escalate_cancel_failures(failures: Vec<CancelFailure>, notifiers: &Arc<Notifiers>, outbox: &Arc<AlertOutbox>) {
+    for failure in failures {
+        let event = CriticalEvent::CancelFailed {
+            exchange: failure.exchange,
+            order_id: failure.order_id,
+            symbol: failure.symbol,
+            error: failure.error,
+        };
+        escalate(event, notifiers, outbox);
+    }
+}
 
-    // Get performance stats  
-    let stats = api
-        .and(warp::path("stats"))
-        .and(warp::get())
-        .and(with_engine(engine.clone()))
-        .and_then(get_stats);
+/// Cancel legs that stopped progressing, per `Config::stale_orders`
+async fn run_order_sweeper(sweeper: Arc<OrderSweeper>, notifiers: Arc<Notifiers>, outbox: Arc<AlertOutbox>) {
+    let mut interval = tokio::time::interval(sweeper.sweep_interval());
+    loop {
+        interval.tick().await;
+        let failures = sweeper.sweep_stale(outbox::now_ms()).await;
+        escalate_cancel_failures(failures, &notifiers, &outbox);
+    }
+}
 
-    // Serve static files
-    let static_files = warp::fs::dir("../web-dashboard/");
+/// Count ticks against `Config::silence` and page for watches that went
+/// quiet; a standby only logs them, as the primary does the paging
+async fn run_silence_monitor(
+    monitor: Arc<SilenceMonitor>,
+    mut ticks: broadcast::Receiver<PublishedTick>,
+    failover: Arc<Failover>,
+    notifiers: Arc<Notifiers>,
+    outbox: Arc<AlertOutbox>,
+) {
+    let mut interval = tokio::time::interval(monitor.check_interval());
+    loop {
+        tokio::select! {
+            received = ticks.recv() => match received {
+                Ok(tick) => monitor.observe(SilenceSource::Exchange, &tick.exchange),
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            _ = interval.tick() => {
+                for silence in monitor.check() {
+                    if !failover.is_active() {
+                        warn!("Silence: {}", silence);
+                        continue;
+                    }
+                    let event = CriticalEvent::Silence {
+                        name: silence.name,
+                        events: silence.source.events().to_string(),
+                        silent_secs: silence.silent_for.as_secs(),
+                    };
+                    escalate(event, &notifiers, &outbox);
+                }
+            }
+        }
+    }
+}
 
-    let routes = opportunities
-        .or(stats)
-        .or(static_files)
-        .with(cors);
+/// Reload the config on SIGHUP, when the discovery job changes the symbols,
+/// and when the file changes if `config_reload.watch` is set; a rejected
+/// reload leaves the running config
+async fn run_config_reloader(
+    mut running: Config,
+    sources: ConfigSources,
+    rediscovered: Arc<Notify>,
+    engine: Arc<ArbitrageEngine>,
+    exchanges: Arc<ExchangeManager>,
+    templates: Arc<AlertTemplates>,
+) {
+    let mut hangup = match service::Hangup::new() {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            error!("Config reload on SIGHUP unavailable: {}", e);
+            return;
+        }
+    };
+    let watch = running.config_reload.watch;
+    let file = config::config_file(sources.file.as_deref());
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|meta| meta.modified()).ok();
+    let mut last_modified = file.as_deref().and_then(modified);
+    let mut poll = tokio::time::interval(running.config_reload.poll_interval);
+    
+    loop {
+        tokio::select! {
+            _ = hangup.recv() => info!("Received SIGHUP, reloading configuration"),
+            _ = rediscovered.notified() => info!("Symbol discovery changed the listed pairs, reloading"),
+            _ = poll.tick(), if watch => {
+                let current = file.as_deref().and_then(modified);
+                if current == last_modified {
+                    continue;
+                }
+                last_modified = current;
+                info!("Config file changed, reloading");
+            }
+        }
+        
+        match reload_config(&running, &sources, &engine, &exchanges, &templates) {
+            Ok(config) => running = config,
+            Err(e) => error!("Config reload rejected, keeping the running configuration: {}", e),
+        }
+    }
+}
 
-    info!("Starting web server on port {}", port);
-    warp::serve(routes)
-        .run(([0, 0, 0, 0], port))
-        .await;
+/// Load and validate the config again and apply its reloadable fields;
+/// returns the configuration now running
+fn reload_config(
+    running: &Config,
+    sources: &ConfigSources,
+    engine: &ArbitrageEngine,
+    exchanges: &ExchangeManager,
+    templates: &AlertTemplates,
+) -> Result<Config, Box<dyn std::error::Error>> {
+    let loaded = sources.load()?;
+    let issues = config::check_fields(&loaded, &sources.exchanges);
+    if !issues.is_empty() {
+        return Err(ConfigError::Invalid(issues).into());
+    }
+    
+    let changes = config::changes(running, &loaded);
+    if changes.is_empty() {
+        info!("Configuration unchanged");
+        return Ok(running.clone());
+    }
+    if !changes.restart_required.is_empty() {
+        warn!("Changes to {} take effect after a restart", changes.restart_required.join(", "));
+    }
+    if changes.applied.is_empty() {
+        info!("Nothing to reload until restart");
+        return Ok(running.clone());
+    }
+    
+    let config = config::apply_reloadable(running, &loaded)?;
+    templates.reload(&config.alert_templates, &config.alert_languages, &config.display)?;
+    exchanges.apply_config(config.clone())?;
+    engine.apply_config(config.clone());
+    info!("Reloaded configuration: {}", changes.applied.join(", "));
+    Ok(config)
+}
 
-    Ok(())
+/// Retry alerts that `AlertSystem` or a push notifier failed to deliver
+async fn run_alert_outbox(outbox: Arc<AlertOutbox>, alert_system: Arc<AlertSystem>, notifiers: Arc<Notifiers>) {
+    let mut interval = tokio::time::interval(outbox.poll_interval());
+    loop {
+        interval.tick().await;
+        outbox
+            .deliver_due(|alert| redeliver(alert, alert_system.clone(), notifiers.clone()))
+            .await;
+    }
 }
 
-fn with_engine(
-    engine: Arc<ArbitrageEngine>,
-) -> impl Filter<Extract = (Arc<ArbitrageEngine>,), Error = std::convert::Infallible> + Clone {
-    warp::any().map(move || engine.clone())
+/// Detect account fee tiers and fetch public schedules; new and changed
+/// rates reprice detection from its next pass
+async fn refresh_fee_tiers(tiers: Arc<FeeTiers>, engine: Arc<ArbitrageEngine>, client: reqwest::Client) -> Result<String, String> {
+    let changed = tiers.refresh(&client).await;
+    for tier in &changed {
+        engine.set_account_fees(&tier.exchange, tier.fees);
+    }
+    Ok(format!("{} tiers new or changed", changed.len()))
 }
 
-async fn get_opportunities(
-    engine: Arc<ArbitrageEngine>,
-) -> Result<impl warp::Reply, warp::Rejection> {
-    let opportunities = engine.get_recent_opportunities(100).await;
-    Ok(warp::reply::json(&opportunities))
+/// Drop what the recording holds from before `retention` ago
+async fn trim_recording(recorder: Arc<Recorder>, retention: Duration) -> Result<String, String> {
+    let before_ms = outbox::now_ms().saturating_sub(retention.as_millis() as u64);
+    match recorder.trim(before_ms).await {
+        Ok(dropped) => Ok(format!("dropped {} records older than {:?}", dropped, retention)),
+        Err(e) => Err(format!("trimming the recording failed: {}", e)),
+    }
 }
 
-async fn get_stats(
-    engine: Arc<ArbitrageEngine>,
-) -> Result<impl warp::Reply, warp::Rejection> {
-    let stats = engine.get_performance_stats().await;
-    Ok(warp::reply::json(&stats))
+/// Export every fill so far to a file in `dir` named for when it ran, so
+/// each report's cost basis starts from the first fill
+async fn write_fills_report(journal: Arc<ExecutionJournal>, dir: PathBuf, format: ReportFormat) -> Result<String, String> {
+    let fills = journal.fills();
+    let path = dir.join(format!("fills-{}.csv", jobs::utc_stamp(outbox::now_ms())));
+    std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(&path, export_fills(&fills, format)))
+        .map_err(|e| format!("writing {} failed: {}", path.display(), e))?;
+    Ok(format!("{} fills to {}", fills.len(), path.display()))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::time::Instant;
+/// Discover symbols again; a changed set reloads the config, subscribing
+/// new pairs and dropping delisted ones. New pairs start their listing
+/// warm-up in the engine
+async fn rediscover(
+    sources: ConfigSources,
+    rediscovered: Arc<Notify>,
+    engine: Arc<ArbitrageEngine>,
+    client: reqwest::Client,
+) -> Result<String, String> {
+    let config = sources.load().map_err(|e| e.to_string())?;
+    let found = discovery::discover(&config, &sources.exchanges, &client).await?;
+    let mut discovered = sources.discovered.write().unwrap();
+    let listed: Vec<String> = found.iter().filter(|symbol| !discovered.contains(symbol)).cloned().collect();
+    let delisted = discovered.iter().filter(|symbol| !found.contains(symbol)).count();
+    if !listed.is_empty() {
+        info!("Newly listed on every exchange: {}", listed.join(", "));
+        engine.mark_listed(&listed);
+    }
+    if !listed.is_empty() || delisted > 0 {
+        *discovered = found;
+        rediscovered.notify_one();
+    }
+    Ok(format!("{} pairs newly listed, {} delisted", listed.len(), delisted))
+}
 
-    #[tokio::test]
-    async fn test_arbitrage_engine_creation() {
-        let config = Config::default();
-        let engine = ArbitrageEngine::new(config);
+/// Quote every pool each `dex.poll_interval` and reprice its gas against the
+/// native token's composite mid. Until that pair has a quote, swaps keep the
+/// last gas charge (none at first)
+async fn run_dex(dex: Arc<Dex>, engine: Arc<ArbitrageEngine>) {
+    let client = reqwest::Client::new();
+    let mut interval = tokio::time::interval(dex.poll_interval());
+    loop {
+        interval.tick().await;
+        for quote in dex.refresh(&client).await {
+            let (bid, ask) = quote.bid_ask();
+            if let Err(e) = engine.update_price(dex::VENUE, &quote.symbol, bid, ask, 0.0).await {
+                error!("Failed to publish {} pool price: {}", quote.symbol, e);
+            }
+        }
         
-        assert!(!engine.is_running().await);
+        let Some(native) = engine.get_composite(dex.native_symbol()).await else {
+            continue;
+        };
+        match dex.update_gas(&client, native.mid).await {
+            Ok(gas) => engine.set_gas_cost(dex::VENUE, gas.cost_fraction),
+            Err(e) => warn!("Gas price unavailable, keeping the last estimate: {}", e),
+        }
     }
+}
 
-    #[tokio::test]
-    async fn test_performance_latency() {
-        let config = Config::default();
-        let engine = Arc::new(ArbitrageEngine::new(config));
-        
-        engine.start().await;
-        
-        // Simulate price update and measure latency
-        let start = Instant::now();
-        engine.update_price(
+/// Push each channel's digest of batched low-profit opportunities
+async fn run_alert_digests(batcher: Arc<AlertBatcher>, notifiers: Arc<Notifiers>, outbox: Arc<AlertOutbox>, display: DisplayFormat) {
+    let mut interval = tokio::time::interval(batcher.interval());
+    interval.tick().await; // Nothing batched yet
+    loop {
+        interval.tick().await;
+        for (channel, notification) in batcher.flush(&display) {
+            push(&notifiers, &outbox, &channel, &notification).await;
+        }
+    }
+}
+
+/// Log where capital should move, given the opportunity heat so far
+async fn log_allocation_report(engine: Arc<ArbitrageEngine>) -> Result<String, String> {
+    let report = engine.get_allocation_report().await;
+    info!(
+        "Allocation: expected profit {:.2} at target vs {:.2} now over {}s, {:.2} of {:.2} unallocated",
+        report.expected_profit, report.current_expected_profit, report.window_secs, report.unallocated, report.total_capital
+    );
+    let moves: Vec<_> = report.targets.iter().filter(|t| t.change.abs() >= report.total_capital * 0.01).collect();
+    for target in &moves {
+        info!("  {} {}: {:.2} -> {:.2} ({:+.2})", target.exchange, target.asset, target.current, target.target, target.change);
+    }
+    Ok(format!("{} moves suggested", moves.len()))
+}
+
+async fn redeliver(
+    alert: outbox::PendingAlert,
+    alert_system: Arc<AlertSystem>,
+    notifiers: Arc<Notifiers>,
+) -> Result<(), String> {
+    if alert.channel != ALERT_CHANNEL {
+        let notification: Notification = serde_json::from_value(alert.payload).map_err(|e| e.to_string())?;
+        return match notifiers.get(&alert.channel) {
+            Some(notifier) => notifier.send(&notification).await,
+            None => Err(format!("no notifier configured for {:?}", alert.channel)),
+        };
+    }
+    
+    let queued: QueuedOpportunity = serde_json::from_value(alert.payload).map_err(|e| e.to_string())?;
+    let opportunity = ArbitrageOpportunity {
+        kind: queued.kind.unwrap_or(OpportunityKind::Cycle),
+        path: queued.path,
+        profit_percentage: queued.profit_percentage,
+        gross_profit: queued.gross_profit.unwrap_or(queued.profit_percentage),
+        max_volume: queued.max_volume,
+        confidence: queued.confidence,
+        detected_at: std::time::Instant::now(),
+        exchanges: queued.exchanges,
+    };
+    alert_system.send_alert(opportunity).await.map_err(|e| e.to_string())
+}
+
+/// Everything the dashboard's API routes read or act on
+struct DashboardState {
+    engine: Arc<ArbitrageEngine>,
+    subscriptions: Arc<SubscriptionTracker>,
+    journal: Arc<ExecutionJournal>,
+    alert_outbox: Arc<AlertOutbox>,
+    alert_templates: Arc<AlertTemplates>,
+    display: Arc<DisplayFormat>,
+    control_plane: Arc<ControlPlane>,
+    control_access: Arc<ControlAccess>,
+    markets: Arc<MarketRegistry>,
+    asset_aliases: Arc<Vec<AssetAlias>>, // Fixed at startup, like the graph's conversion edges
+    slack_actions: Option<Arc<SlackActions>>, // Only with a Slack signing secret
+    silence: Arc<SilenceMonitor>,
+    fee_tiers: Arc<FeeTiers>,
+    dex: Arc<Dex>,
+    support: Arc<SupportBundler>,
+    scheduler: Arc<Scheduler>,
+}
+
+async fn start_web_dashboard(
+    port: u16,
+    state: DashboardState,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let DashboardState {
+        engine,
+        subscriptions,
+        journal,
+        alert_outbox,
+        alert_templates,
+        display,
+        control_plane,
+        control_access,
+        markets,
+        asset_aliases,
+        slack_actions,
+        silence,
+        fee_tiers,
+        dex,
+        support,
+        scheduler,
+    } = state;
+    
+    
+    // CORS headers; other sites' pages may read but not act
+    let cors = warp::cors()
+        .allow_any_origin()
+        .allow_headers(vec!["content-type"])
+        .allow_methods(vec!["GET", "OPTIONS"]);
+    
+    // API routes
+    let api = warp::path("api");
+    
+    // Perpetuals whose funding pays for a spot hedge; ahead of the cycle route
+    let funding_opportunities = api
+        .and(warp::path!("opportunities" / "funding"))
+        .and(warp::get())
+        .and(with_engine(engine.clone()))
+        .and_then(get_funding_opportunities);
+    
+    // Dated futures worth carrying against spot; ahead of the cycle route
+    let basis_opportunities = api
+        .and(warp::path!("opportunities" / "basis"))
+        .and(warp::get())
+        .and(with_engine(engine.clone()))
+        .and_then(get_basis_opportunities);
+    
+    // Z-scored spreads and their latest mean-reversion signals; ahead of the cycle route
+    let stat_arb = api
+        .and(warp::path!("opportunities" / "statarb"))
+        .and(warp::get())
+        .and(with_engine(engine.clone()))
+        .and_then(get_stat_arb);
+    
+    // Get recent opportunities
+    let opportunity_display = display.clone();
+    let opportunities = api
+        .and(warp::path("opportunities"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(with_engine(engine.clone()))
+        .and(warp::any().map(move || opportunity_display.clone()))
+        .and_then(get_opportunities);
+    
+    // Everything in the opportunity history, compressed and with an ETag
+    let opportunity_history = api
+        .and(warp::path!("opportunities" / "history"))
+        .and(warp::get())
+        .and(warp::query::<HistoryQuery>())
+        .and(compression::conditional())
+        .and(with_engine(engine.clone()))
+        .and(warp::any().map(move || display.clone()))
+        .and_then(get_opportunity_history);
+    
+    // Get performance stats  
+    let stats = api
+        .and(warp::path("stats"))
+        .and(warp::get())
+        .and(with_engine(engine.clone()))
+        .and_then(get_stats);
+    
+    // Get the composite best bid and offer, for one symbol or all
+    let composite = api
+        .and(warp::path("composite"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::query::<CompositeQuery>())
+        .and(with_engine(engine.clone()))
+        .and_then(get_composite);
+    
+    // Get what local-currency markets pay over other regions (the KRW premium)
+    let premiums = api
+        .and(warp::path!("markets" / "premiums"))
+        .and(warp::get())
+        .and(with_engine(engine.clone()))
+        .and_then(get_premiums);
+    
+    // Get pairs listed since startup that are still held back
+    let new_listings = api
+        .and(warp::path!("markets" / "new-listings"))
+        .and(warp::get())
+        .and(with_engine(engine.clone()))
+        .and_then(get_new_listings);
+    
+    // Get the depth-weighted fair value for one symbol (BTC-USDT in the path)
+    let fair_value = api
+        .and(warp::path!("fair-value" / String))
+        .and(warp::get())
+        .and(with_engine(engine.clone()))
+        .and_then(get_fair_value);
+    
+    // Get measured lead-lag between venues, viable latency arbitrage first
+    let lead_lag = api
+        .and(warp::path("lead-lag"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(with_engine(engine.clone()))
+        .and_then(get_lead_lag);
+    
+    // Get fake liquidity signs per feed, suspect feeds first
+    let wash_trading = api
+        .and(warp::path("wash-trading"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(with_engine(engine.clone()))
+        .and_then(get_wash_trading);
+    
+    // Get one venue's L2 book (BTC-USDT in the path)
+    let book = api
+        .and(warp::path!("book" / String / String))
+        .and(warp::get())
+        .and(warp::query::<BookQuery>())
+        .and(with_engine(engine.clone()))
+        .and_then(get_book);
+    
+    // Get a cross-exchange snapshot for one symbol
+    let snapshot = api
+        .and(warp::path("snapshot"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::query::<SnapshotQuery>())
+        .and(with_engine(engine.clone()))
+        .and_then(get_snapshot);
+    
+    // Best conversion route between two currency/exchange nodes
+    let conversion_routes = api
+        .and(warp::path("routes"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::query::<RouteQuery>())
+        .and(with_engine(engine.clone()))
+        .and_then(get_route);
+    
+    // Which pipeline stage is blowing its latency budget
+    let stats_budgets = api
+        .and(warp::path!("stats" / "budgets"))
+        .and(warp::get())
+        .and(with_engine(engine.clone()))
+        .and_then(get_stats_budgets);
+    
+    // Tick and opportunity rates per silence watch, and which are quiet now
+    let stats_silence = api
+        .and(warp::path!("stats" / "silence"))
+        .and(warp::get())
+        .and(warp::any().map(move || silence.clone()))
+        .and_then(get_stats_silence);
+    
+    // Per-minute opportunity and uptime rollups for dashboard charts
+    let stats_rollups = api
+        .and(warp::path!("stats" / "rollups"))
+        .and(warp::get())
+        .and(warp::query::<RollupQuery>())
+        .and(with_engine(engine.clone()))
+        .and_then(get_stats_rollups);
+    
+    // How many repeat alerts dedup dropped, and its false positive rate
+    let stats_dedup = api
+        .and(warp::path!("stats" / "dedup"))
+        .and(warp::get())
+        .and(with_engine(engine.clone()))
+        .and_then(get_stats_dedup);
+    
+    // Logs, stats, redacted config and connection states for a bug report
+    let support_bundle = api
+        .and(warp::path!("support" / "bundle"))
+        .and(warp::get())
+        .and(warp::query::<BundleQuery>())
+        .and(warp::any().map(move || support.clone()))
+        .and_then(get_support_bundle);
+    
+    // Get subscription state for one exchange
+    let exchange_subscriptions = api
+        .and(warp::path!("exchanges" / String / "subscriptions"))
+        .and(warp::get())
+        .and(warp::any().map(move || subscriptions.clone()))
+        .and_then(get_exchange_subscriptions);
+    
+    // Export executed fills for tax/accounting tools
+    let export = api
+        .and(warp::path("export"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::query::<ExportQuery>())
+        .and(compression::conditional())
+        .and(with_journal(journal.clone()))
+        .and_then(get_export);
+    
+    // Expected vs realized profit per exchange pair and time bucket
+    let slippage = api
+        .and(warp::path!("analytics" / "slippage"))
+        .and(warp::get())
+        .and(warp::query::<SlippageQuery>())
+        .and(with_journal(journal.clone()))
+        .and_then(get_slippage);
+    
+    // Assets the loaded markets list under more than one ticker
+    let registry = markets.clone();
+    let cross_listings = api
+        .and(warp::path!("markets" / "cross-listings"))
+        .and(warp::get())
+        .and(warp::any().map(move || registry.clone()))
+        .and(warp::any().map(move || asset_aliases.clone()))
+        .and_then(get_cross_listings);
+    
+    // Fee tiers detected from the configured accounts
+    let fee_tier_list = api
+        .and(warp::path!("markets" / "fee-tiers"))
+        .and(warp::get())
+        .and(warp::any().map(move || fee_tiers.clone()))
+        .and_then(get_fee_tiers);
+    
+    // Pool prices and the gas each swap is charged
+    let dex_pools = api
+        .and(warp::path!("dex"))
+        .and(warp::get())
+        .and(warp::any().map(move || dex.clone()))
+        .and_then(get_dex);
+    
+    // Markets, precision and fees loaded from CCXT metadata
+    let market_list = api
+        .and(warp::path!("markets"))
+        .and(warp::get())
+        .and(warp::any().map(move || markets.clone()))
+        .and_then(get_markets);
+    
+    // Where opportunities cluster by exchange pair, symbol and hour
+    let heat = api
+        .and(warp::path!("analytics" / "heat"))
+        .and(warp::get())
+        .and(with_engine(engine.clone()))
+        .and_then(get_heat);
+    
+    // Recommended inventory per exchange/asset
+    let allocation = api
+        .and(warp::path!("analytics" / "allocation"))
+        .and(warp::get())
+        .and(with_engine(engine.clone()))
+        .and_then(get_allocation);
+    
+    // Scheduled jobs: schedule, last run and its outcome, next run
+    let jobs = api
+        .and(warp::path!("jobs"))
+        .and(warp::get())
+        .and(warp::any().map(move || scheduler.clone()))
+        .and_then(get_jobs);
+    
+    // Alerts waiting for redelivery and per-channel circuit state
+    let alerts_pending = api
+        .and(warp::path!("alerts" / "pending"))
+        .and(warp::get())
+        .and(warp::any().map(move || alert_outbox.clone()))
+        .and_then(get_alerts_pending);
+    
+    // Latest opportunity rendered with a channel's alert template
+    let alerts_preview = api
+        .and(warp::path!("alerts" / "preview"))
+        .and(warp::get())
+        .and(warp::query::<PreviewQuery>())
+        .and(with_engine(engine.clone()))
+        .and(warp::any().map(move || alert_templates.clone()))
+        .and_then(get_alerts_preview);
+    
+    // Opportunities waiting for manual approval
+    let approval_queue = control_plane.approvals();
+    let approvals = api
+        .and(warp::path("approvals"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::any().map(move || approval_queue.clone()))
+        .and_then(get_approvals);
+    
+    // Human-in-the-loop actions on an opportunity, and hand-built trades
+    let control = control_routes(control_plane.clone(), control_access);
+    
+    // Button presses on Slack alerts; Slack signs the raw form body
+    let slack = api
+        .and(warp::path!("slack" / "actions"))
+        .and(warp::post())
+        .and(warp::header::<String>("x-slack-request-timestamp"))
+        .and(warp::header::<String>("x-slack-signature"))
+        .and(warp::body::bytes())
+        .and(warp::any().map(move || slack_actions.clone()))
+        .and(warp::any().map(move || control_plane.clone()))
+        .and_then(post_slack_action);
+    
+    // Stream validated ticks to external subscribers
+    let tick_stream = warp::path!("ws" / "ticks")
+        .and(warp::ws())
+        .and(warp::query::<TickFilter>())
+        .and(with_engine(engine.clone()))
+        .map(|ws: warp::ws::Ws, filter: TickFilter, engine: Arc<ArbitrageEngine>| {
+            let ticks = engine.subscribe_ticks();
+            ws.on_upgrade(move |socket| stream_ticks(socket, filter, ticks))
+        });
+    
+    // Stream the composite for subscribed symbols as it changes
+    let composite_stream = warp::path!("ws" / "composite")
+        .and(warp::ws())
+        .and(warp::query::<TickFilter>())
+        .and(with_engine(engine.clone()))
+        .map(|ws: warp::ws::Ws, filter: TickFilter, engine: Arc<ArbitrageEngine>| {
+            let ticks = engine.subscribe_ticks();
+            ws.on_upgrade(move |socket| stream_composite(socket, filter, ticks, engine.quote_book()))
+        });
+    
+    // Serve static files
+    let static_files = warp::fs::dir("../web-dashboard/");
+    
+    // Boxed in two halves; one unbroken chain of `or`s nests the filter type
+    // deeper than the compiler will lay out
+    let reads = funding_opportunities
+        .or(basis_opportunities)
+        .or(stat_arb)
+        .or(opportunities)
+        .or(opportunity_history)
+        .or(stats_budgets)
+        .or(stats_silence)
+        .or(stats_rollups)
+        .or(stats_dedup)
+        .or(stats)
+        .or(snapshot)
+        .or(composite)
+        .or(fair_value)
+        .or(lead_lag)
+        .or(wash_trading)
+        .or(book)
+        .or(conversion_routes)
+        .or(exchange_subscriptions)
+        .or(support_bundle)
+        .or(export)
+        .or(slippage)
+        .or(heat)
+        .or(allocation)
+        .or(market_list)
+        .or(cross_listings)
+        .or(fee_tier_list)
+        .boxed();
+    let routes = reads
+        .or(dex_pools)
+        .or(premiums)
+        .or(new_listings)
+        .or(jobs)
+        .or(alerts_pending)
+        .or(alerts_preview)
+        .or(approvals)
+        .or(control)
+        .or(slack)
+        .or(tick_stream)
+        .or(composite_stream)
+        .or(static_files)
+        .with(cors);
+    
+    info!("Starting web server on port {}", port);
+    warp::serve(routes)
+        .run(([0, 0, 0, 0], port))
+        .await;
+    
+    Ok(())
+}
+
+/// Human-in-the-loop actions on an opportunity (approve, reject, paper
+/// execute, blacklist, snooze) and hand-built trades, run through the same
+/// execution path as detected ones; each request needs the control token
+fn control_routes(
+    control_plane: Arc<ControlPlane>,
+    access: Arc<ControlAccess>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let permitted = warp::header::optional::<String>("authorization")
+        .and(warp::addr::remote())
+        .map(move |authorization: Option<String>, remote| access.permits(authorization.as_deref(), remote));
+    
+    let control_plane_clone = control_plane.clone();
+    let control = warp::path("api")
+        .and(warp::path("control"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(permitted.clone())
+        .and(warp::body::json::<ControlAction>())
+        .and(warp::any().map(move || control_plane_clone.clone()))
+        .and_then(post_control);
+    let execute = warp::path("api")
+        .and(warp::path("execute"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(permitted)
+        .and(warp::body::json::<CustomTrade>())
+        .and(warp::any().map(move || control_plane.clone()))
+        .and_then(post_execute);
+    control.or(execute)
+}
+
+fn with_engine(
+    engine: Arc<ArbitrageEngine>,
+) -> impl Filter<Extract = (Arc<ArbitrageEngine>,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || engine.clone())
+}
+
+async fn get_opportunities(
+    engine: Arc<ArbitrageEngine>,
+    display: Arc<DisplayFormat>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let quotes = engine.quote_book();
+    let opportunities: Vec<_> = engine
+        .get_recent_opportunities(100)
+        .await
+        .iter()
+        .map(|opportunity| {
+            let mut data = display.opportunity_json(opportunity);
+            data["composite"] = serde_json::json!(quotes.deviations(&opportunity.path));
+            // Legs that convert between tickers of one asset rather than trade
+            data["caveats"] = serde_json::json!(engine.conversion_caveats(opportunity));
+            // Maker legs worth resting instead of taking, when enabled
+            data["maker_plan"] = serde_json::json!(engine.maker_plan(opportunity));
+            data["atomicity"] = serde_json::json!(engine.atomicity(opportunity));
+            // Inventory-based or waiting on withdrawals, and what those cost
+            data["transfers"] = serde_json::json!(engine.transfer_plan(opportunity));
+            data
+        })
+        .collect();
+    Ok(warp::reply::json(&opportunities))
+}
+
+#[derive(serde::Deserialize)]
+struct HistoryQuery {
+    limit: Option<usize>,
+}
+
+async fn get_opportunity_history(
+    query: HistoryQuery,
+    conditional: Conditional,
+    engine: Arc<ArbitrageEngine>,
+    display: Arc<DisplayFormat>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    // Oldest first, as recorded
+    let history: Vec<_> = engine
+        .get_recent_opportunities(query.limit.unwrap_or(usize::MAX))
+        .await
+        .iter()
+        .map(|opportunity| display.opportunity_json(opportunity))
+        .collect();
+    let body = serde_json::to_vec(&history).unwrap_or_default();
+    Ok(compression::reply(body, "application/json", &conditional))
+}
+
+/// Forward ticks matching `filter` as JSON text frames until the client goes
+/// away. A text frame from the client holding a new filter replaces it
+async fn stream_ticks(
+    socket: warp::ws::WebSocket,
+    mut filter: TickFilter,
+    mut ticks: broadcast::Receiver<PublishedTick>,
+) {
+    let (mut outgoing, mut incoming) = socket.split();
+    loop {
+        let frame = tokio::select! {
+            received = ticks.recv() => match received {
+                Ok(tick) if filter.matches(&tick) => serde_json::to_string(&tick).ok(),
+                Ok(_) => None,
+                // Tell the client where its stream has a gap
+                Err(broadcast::error::RecvError::Lagged(missed)) => Some(serde_json::json!({ "lagged": missed }).to_string()),
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = incoming.next() => match message {
+                Some(Ok(message)) if message.is_close() => break,
+                Some(Ok(message)) => match message.to_str().map(serde_json::from_str::<TickFilter>) {
+                    Ok(Ok(updated)) => {
+                        filter = updated;
+                        None
+                    }
+                    Ok(Err(e)) => Some(serde_json::json!({ "error": format!("invalid filter: {}", e) }).to_string()),
+                    // Pings and binary frames
+                    Err(()) => None,
+                },
+                Some(Err(_)) | None => break,
+            },
+        };
+        if let Some(text) = frame {
+            if outgoing.send(warp::ws::Message::text(text)).await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Push the composite for each symbol `filter` selects whenever a tick moves
+/// it; the client can replace the filter as on `/ws/ticks`
+async fn stream_composite(
+    socket: warp::ws::WebSocket,
+    mut filter: TickFilter,
+    mut ticks: broadcast::Receiver<PublishedTick>,
+    quotes: Arc<QuoteBook>,
+) {
+    let (mut outgoing, mut incoming) = socket.split();
+    let mut sent: HashMap<String, CompositeQuote> = HashMap::new();
+    loop {
+        let frame = tokio::select! {
+            received = ticks.recv() => match received {
+                Ok(tick) if filter.symbols.is_empty() || filter.symbols.iter().any(|symbol| symbol.eq_ignore_ascii_case(&tick.symbol)) => {
+                    match quotes.composite(&tick.symbol) {
+                        Some(composite) if sent.get(&tick.symbol) != Some(&composite) => {
+                            let text = serde_json::to_string(&composite).ok();
+                            sent.insert(tick.symbol, composite);
+                            text
+                        }
+                        _ => None,
+                    }
+                }
+                Ok(_) => None,
+                Err(broadcast::error::RecvError::Lagged(_)) => None,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = incoming.next() => match message {
+                Some(Ok(message)) if message.is_close() => break,
+                Some(Ok(message)) => match message.to_str().map(serde_json::from_str::<TickFilter>) {
+                    Ok(Ok(updated)) => {
+                        filter = updated;
+                        None
+                    }
+                    Ok(Err(e)) => Some(serde_json::json!({ "error": format!("invalid filter: {}", e) }).to_string()),
+                    Err(()) => None,
+                },
+                Some(Err(_)) | None => break,
+            },
+        };
+        if let Some(text) = frame {
+            if outgoing.send(warp::ws::Message::text(text)).await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+async fn get_funding_opportunities(
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.get_funding_opportunities().await))
+}
+
+async fn get_basis_opportunities(
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.get_basis_opportunities().await))
+}
+
+async fn get_stat_arb(
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.get_stat_arb().await))
+}
+
+async fn get_stats(
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let stats = engine.get_performance_stats().await;
+    Ok(warp::reply::json(&stats))
+}
+
+async fn get_stats_budgets(
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let report = engine.get_latency_budget_report().await;
+    Ok(warp::reply::json(&report))
+}
+
+async fn get_stats_silence(
+    silence: Arc<SilenceMonitor>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&silence.report()))
+}
+
+#[derive(serde::Deserialize)]
+struct CompositeQuery {
+    symbol: Option<String>,
+}
+
+async fn get_composite(
+    query: CompositeQuery,
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match query.symbol {
+        Some(symbol) => match engine.get_composite(&symbol).await {
+            Some(composite) => Ok(warp::reply::json(&composite)),
+            None => Err(warp::reject::not_found()),
+        },
+        None => Ok(warp::reply::json(&engine.get_composites().await)),
+    }
+}
+
+async fn get_premiums(
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.get_regional_premiums().await))
+}
+
+async fn get_new_listings(
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.get_new_listings().await))
+}
+
+async fn get_fair_value(
+    symbol: String,
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    // Symbols can't carry their slash in a path segment: BTC-USDT, BTC_USDT or BTC%2FUSDT
+    let symbol = symbol.replace("%2F", "/").replace("%2f", "/").replace(['-', '_'], "/");
+    match engine.get_fair_value(&symbol).await {
+        Some(value) => Ok(warp::reply::json(&value)),
+        None => Err(warp::reject::not_found()),
+    }
+}
+
+async fn get_lead_lag(
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.get_lead_lag().await))
+}
+
+async fn get_wash_trading(
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.get_feed_integrity().await))
+}
+
+#[derive(serde::Deserialize)]
+struct BookQuery {
+    levels: Option<usize>,
+}
+
+async fn get_book(
+    exchange: String,
+    symbol: String,
+    query: BookQuery,
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let symbol = symbol.replace("%2F", "/").replace("%2f", "/").replace(['-', '_'], "/");
+    match engine.get_book(&exchange, &symbol, query.levels.unwrap_or(20)).await {
+        Some(book) => Ok(warp::reply::json(&book)),
+        None => Err(warp::reject::not_found()),
+    }
+}
+
+async fn get_stats_rollups(
+    query: RollupQuery,
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.get_rollups(&query).await))
+}
+
+async fn get_stats_dedup(
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.get_dedup_report().await))
+}
+
+#[derive(serde::Deserialize)]
+struct SnapshotQuery {
+    symbol: String,
+}
+
+async fn get_snapshot(
+    query: SnapshotQuery,
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match engine.get_market_snapshot(&query.symbol).await {
+        Some(snapshot) => Ok(warp::reply::json(&snapshot)),
+        None => Err(warp::reject::not_found()),
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct RouteQuery {
+    from: String,
+    to: String,
+}
+
+async fn get_route(
+    query: RouteQuery,
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match engine.get_best_route(&query.from, &query.to).await {
+        Some(route) => Ok(warp::reply::json(&route)),
+        None => Err(warp::reject::not_found()),
+    }
+}
+
+async fn get_exchange_subscriptions(
+    exchange: String,
+    subscriptions: Arc<SubscriptionTracker>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match subscriptions.snapshot(&exchange) {
+        Some(statuses) => Ok(warp::reply::json(&statuses)),
+        None => Err(warp::reject::not_found()),
+    }
+}
+
+fn with_journal(
+    journal: Arc<ExecutionJournal>,
+) -> impl Filter<Extract = (Arc<ExecutionJournal>,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || journal.clone())
+}
+
+#[derive(serde::Deserialize)]
+struct ExportQuery {
+    format: Option<String>,
+}
+
+async fn get_export(
+    query: ExportQuery,
+    conditional: Conditional,
+    journal: Arc<ExecutionJournal>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let format = match query.format.as_deref().unwrap_or("csv").parse::<ReportFormat>() {
+        Ok(format) => format,
+        Err(_) => return Err(warp::reject::not_found()),
+    };
+    
+    let body = export_fills(&journal.fills(), format);
+    Ok(compression::reply(body.into_bytes(), "text/csv; charset=utf-8", &conditional))
+}
+
+async fn get_support_bundle(
+    query: BundleQuery,
+    support: Arc<SupportBundler>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let zip = support.build(&query).await.map_err(|e| {
+        error!("Failed to build support bundle: {}", e);
+        warp::reject::reject()
+    })?;
+    Ok(warp::reply::with_header(
+        warp::reply::with_header(zip, "content-type", "application/zip"),
+        "content-disposition",
+        "attachment; filename=\"support-bundle.zip\"",
+    ))
+}
+
+#[derive(serde::Deserialize)]
+struct SlippageQuery {
+    bucket_minutes: Option<u64>,
+}
+
+async fn get_slippage(
+    query: SlippageQuery,
+    journal: Arc<ExecutionJournal>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bucket = Duration::from_secs(query.bucket_minutes.unwrap_or(60).max(1) * 60);
+    let report = slippage_report(&journal.executions(), bucket);
+    Ok(warp::reply::json(&report))
+}
+
+async fn get_heat(
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.get_heat_report().await))
+}
+
+async fn get_allocation(
+    engine: Arc<ArbitrageEngine>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&engine.get_allocation_report().await))
+}
+
+async fn get_markets(
+    markets: Arc<MarketRegistry>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&markets.snapshot()))
+}
+
+async fn get_cross_listings(
+    markets: Arc<MarketRegistry>,
+    aliases: Arc<Vec<AssetAlias>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&markets.cross_listings(&aliases)))
+}
+
+async fn get_fee_tiers(
+    tiers: Arc<FeeTiers>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&tiers.snapshot()))
+}
+
+async fn get_dex(
+    dex: Arc<Dex>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&dex.snapshot()))
+}
+
+async fn get_jobs(
+    scheduler: Arc<Scheduler>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&scheduler.statuses()))
+}
+
+async fn get_alerts_pending(
+    alert_outbox: Arc<AlertOutbox>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&alert_outbox.view(outbox::now_ms())))
+}
+
+#[derive(serde::Deserialize)]
+struct PreviewQuery {
+    channel: Option<String>,
+}
+
+async fn get_alerts_preview(
+    query: PreviewQuery,
+    engine: Arc<ArbitrageEngine>,
+    alert_templates: Arc<AlertTemplates>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let channel = query.channel.unwrap_or_else(|| templates::DEFAULT_CHANNEL.to_string());
+    match engine.get_recent_opportunities(1).await.first() {
+        Some(opportunity) => Ok(alert_templates.render(&channel, opportunity, &engine.quote_book().deviations(&opportunity.path))),
+        None => Err(warp::reject::not_found()),
+    }
+}
+
+async fn get_approvals(
+    approvals: Arc<ApprovalQueue>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&approvals.pending(outbox::now_ms())))
+}
+
+async fn post_control(
+    permitted: bool,
+    action: ControlAction,
+    control_plane: Arc<ControlPlane>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if !permitted {
+        return Ok(control_denied());
+    }
+    let (status, reply) = match control_plane.apply(action).await {
+        Ok(message) => (warp::http::StatusCode::OK, serde_json::json!({ "ok": true, "message": message })),
+        Err(error) => (warp::http::StatusCode::BAD_REQUEST, serde_json::json!({ "ok": false, "error": error })),
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&reply), status))
+}
+
+/// 401 for a control request without the right token
+fn control_denied() -> warp::reply::WithStatus<warp::reply::Json> {
+    let reply = serde_json::json!({ "ok": false, "error": "missing or wrong control token" });
+    warp::reply::with_status(warp::reply::json(&reply), warp::http::StatusCode::UNAUTHORIZED)
+}
+
+async fn post_execute(
+    permitted: bool,
+    trade: CustomTrade,
+    control_plane: Arc<ControlPlane>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if !permitted {
+        return Ok(control_denied());
+    }
+    let (status, reply) = match control_plane.execute_custom(trade).await {
+        Ok(message) => (warp::http::StatusCode::OK, serde_json::json!({ "ok": true, "message": message })),
+        Err(error) => (warp::http::StatusCode::BAD_REQUEST, serde_json::json!({ "ok": false, "error": error })),
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&reply), status))
+}
+
+async fn post_slack_action(
+    timestamp: String,
+    signature: String,
+    body: warp::hyper::body::Bytes,
+    slack_actions: Option<Arc<SlackActions>>,
+    control_plane: Arc<ControlPlane>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let Some(slack_actions) = slack_actions else {
+        return Err(warp::reject::not_found());
+    };
+    let now_secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    if let Err(e) = slack_actions.verify(&timestamp, &body, &signature, now_secs) {
+        warn!("Rejected Slack action: {}", e);
+        return Ok(warp::http::StatusCode::UNAUTHORIZED);
+    }
+    let pressed = match slack_actions.parse(&body) {
+        Ok(pressed) => pressed,
+        Err(e) => {
+            warn!("Unreadable Slack action: {}", e);
+            return Ok(warp::http::StatusCode::BAD_REQUEST);
+        }
+    };
+    
+    // Slack wants an answer within 3s; the outcome goes to response_url
+    tokio::spawn(async move {
+        let outcome = match control_plane.apply(pressed.action).await {
+            Ok(message) => message,
+            Err(error) => format!("Failed: {}", error),
+        };
+        let text = format!("{}: {}", pressed.user, outcome);
+        if let Err(e) = slack_actions.respond(&pressed.response_url, &text).await {
+            warn!("Slack response failed: {}", e);
+        }
+    });
+    Ok(warp::http::StatusCode::OK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    
+    #[tokio::test]
+    async fn test_control_routes_need_the_token() {
+        let engine = Arc::new(ArbitrageEngine::new(Config::default()));
+        let control_plane = Arc::new(ControlPlane::new(
+            engine.clone(),
+            Arc::new(ExecutionJournal::new()),
+            Arc::new(ApprovalQueue::new(ApprovalConfig::default())),
+            Arc::new(ComplianceGate::from_config(&ComplianceConfig::default())),
+            DisplayFormat::default(),
+        ));
+        let routes = control_routes(control_plane.clone(), Arc::new(ControlAccess::new(Some("s3cret".to_string()))));
+        let blacklist = serde_json::json!({ "action": "blacklist_path", "path": "BTC_binance -> BTC_kraken" });
+        let request = || {
+            warp::test::request()
+                .method("POST")
+                .path("/api/control")
+                .remote_addr(([127, 0, 0, 1], 40000).into())
+                .json(&blacklist)
+        };
+        
+        assert_eq!(request().reply(&routes).await.status(), 401);
+        assert_eq!(request().header("authorization", "Bearer wrong").reply(&routes).await.status(), 401);
+        assert!(engine.get_blacklisted_paths().await.is_empty());
+        assert_eq!(request().header("authorization", "Bearer s3cret").reply(&routes).await.status(), 200);
+        assert_eq!(engine.get_blacklisted_paths().await.len(), 1);
+        
+        // Without a token, only this machine may act
+        let local = control_routes(control_plane, Arc::new(ControlAccess::new(None)));
+        let remote = request().remote_addr(([10, 0, 0, 7], 40000).into());
+        assert_eq!(remote.reply(&local).await.status(), 401);
+        assert_eq!(request().reply(&local).await.status(), 200);
+        
+        // Hand-built trades take the same token
+        let trade = serde_json::json!({
+            "legs": [{"exchange": "binance", "symbol": "BTC/USDT", "side": "buy", "quantity": 0.01, "limit_price": 50000.0}]
+        });
+        let execute = || warp::test::request().method("POST").path("/api/execute").json(&trade);
+        assert_eq!(execute().reply(&routes).await.status(), 401);
+        assert_ne!(execute().header("authorization", "Bearer s3cret").reply(&routes).await.status(), 401);
+    }
+    
+    #[tokio::test]
+    async fn test_arbitrage_engine_creation() {
+        let config = Config::default();
+        let engine = ArbitrageEngine::new(config);
+        
+        assert!(!engine.is_running().await);
+    }
+    
+    #[tokio::test]
+    async fn test_performance_latency() {
+        let config = Config::default();
+        let engine = Arc::new(ArbitrageEngine::new(config));
+        
+        engine.start().await;
+        
+        // Simulate price update and measure latency
+        let start = Instant::now();
+        engine.update_price(
             "binance", 
             "BTC/USDT", 
             50000.0, 
@@ -209,7 +2304,7 @@ mod tests {
         
         engine.stop().await;
     }
-
+    
     #[test]
     fn test_bellman_ford_negative_cycle() {
         // Test the mathematical correctness of arbitrage detection
@@ -0,0 +1,465 @@
+// markets.rs - Asset registry and fee schedules bootstrapped from CCXT market metadata
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketsConfig {
+    /// CCXT `load_markets()` output per exchange, as a file path or http(s) URL,
+    /// e.g. `python -c "import ccxt, json; print(json.dumps(ccxt.kraken().load_markets()))"`
+    pub sources: HashMap<String, String>,
+    #[serde(with = "humantime_serde")]
+    pub refresh_interval: Duration,
+    /// Assets listed under more than one ticker; replaces the built-in list
+    pub aliases: Vec<AssetAlias>,
+}
+
+impl Default for MarketsConfig {
+    fn default() -> Self {
+        Self {
+            sources: HashMap::new(),
+            refresh_interval: Duration::from_secs(6 * 3600),
+            aliases: AssetAlias::known(),
+        }
+    }
+}
+
+/// Why one asset trades under several tickers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AliasKind {
+    Rename,  // Ticker migration (RNDR -> RENDER); venues convert 1:1 while they support the swap
+    Wrapped, // Wrapped token (WBTC for BTC); minted and redeemed through a custodian or bridge
+    Derivative, // Perpetual or future on the asset (BTC-PERP, BTC-27DEC24); linked without an alias entry
+}
+
+impl AliasKind {
+    /// What converting between the tickers involves beyond the quoted prices
+    pub fn caveat(self) -> &'static str {
+        match self {
+            AliasKind::Rename => "converts only where the venue still supports the migration; deposits under the old ticker may be rejected",
+            AliasKind::Wrapped => "wrapping and redeeming go through a custodian or bridge with its own delays, limits and fees",
+            AliasKind::Derivative => "a perpetual or future tracks its underlying but doesn't convert into it; this is a basis trade held until funding or expiry closes the gap",
+        }
+    }
+}
+
+/// One underlying asset and the other tickers it trades under
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetAlias {
+    pub asset: String,
+    pub aliases: Vec<String>,
+    pub kind: AliasKind,
+    /// Fraction lost converting between tickers (bridge, wrapping or migration fees)
+    #[serde(default)]
+    pub conversion_cost: f64,
+}
+
+impl AssetAlias {
+    /// Well-known migrations and wrapped majors
+    pub fn known() -> Vec<AssetAlias> {
+        let alias = |asset: &str, aliases: &[&str], kind| AssetAlias {
+            asset: asset.to_string(),
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            kind,
+            conversion_cost: 0.0,
+        };
+        vec![
+            alias("RENDER", &["RNDR"], AliasKind::Rename),
+            alias("POL", &["MATIC"], AliasKind::Rename),
+            alias("BTC", &["WBTC"], AliasKind::Wrapped),
+            alias("ETH", &["WETH"], AliasKind::Wrapped),
+        ]
+    }
+    
+    /// `asset` followed by its aliases
+    pub fn tickers(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.asset).chain(&self.aliases)
+    }
+}
+
+/// The alias entry that turns `from` into `to`, if they're different tickers of one asset
+pub fn conversion<'a>(aliases: &'a [AssetAlias], from: &str, to: &str) -> Option<&'a AssetAlias> {
+    if from == to {
+        return None;
+    }
+    aliases.iter().find(|alias| {
+        alias.tickers().any(|ticker| ticker == from) && alias.tickers().any(|ticker| ticker == to)
+    })
+}
+
+/// Underlying of a derivative ticker as connectors name them: "BTC-PERP" for a
+/// perpetual, "BTC-27DEC24" for a future expiring on that date
+pub fn underlying(ticker: &str) -> Option<&str> {
+    let (asset, contract) = ticker.split_once('-')?;
+    // DMMMYY or DDMMMYY
+    let expiry = |contract: &str| {
+        let b = contract.as_bytes();
+        let n = b.len();
+        (6..=7).contains(&n)
+            && b[..n - 5].iter().all(u8::is_ascii_digit)
+            && b[n - 5..n - 2].iter().all(u8::is_ascii_uppercase)
+            && b[n - 2..].iter().all(u8::is_ascii_digit)
+    };
+    (!asset.is_empty() && (contract == "PERP" || expiry(contract))).then_some(asset)
+}
+
+/// One asset's tickers `from` and `to` are linked by, and what it costs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Link<'a> {
+    pub asset: &'a str,
+    pub kind: AliasKind,
+    pub conversion_cost: f64,
+}
+
+/// How `from` becomes `to` when both are one asset: an alias entry, or a
+/// derivative and its underlying
+pub fn link<'a>(aliases: &'a [AssetAlias], from: &'a str, to: &'a str) -> Option<Link<'a>> {
+    if let Some(alias) = conversion(aliases, from, to) {
+        return Some(Link {
+            asset: &alias.asset,
+            kind: alias.kind,
+            conversion_cost: alias.conversion_cost,
+        });
+    }
+    let asset = match (underlying(from), underlying(to)) {
+        (Some(asset), None) if asset == to => asset,
+        (None, Some(asset)) if asset == from => asset,
+        _ => return None,
+    };
+    Some(Link {
+        asset,
+        kind: AliasKind::Derivative,
+        conversion_cost: 0.0,
+    })
+}
+
+/// Where one aliased asset is listed, by exchange
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CrossListing {
+    pub asset: String,
+    pub kind: AliasKind,
+    /// Exchange -> the asset's tickers it lists, sorted
+    pub listings: BTreeMap<String, Vec<String>>,
+}
+
+/// One spot market as CCXT describes it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MarketInfo {
+    pub exchange: String,
+    /// Unified BASE/QUOTE symbol
+    pub symbol: String,
+    pub base: String,
+    pub quote: String,
+    /// The exchange's own market id, e.g. "BTCUSDT" or "XXBTZUSD"
+    pub native_id: String,
+    /// `precision.price` / `precision.amount`: a tick size, or a count of
+    /// decimal places on exchanges CCXT still describes that way
+    pub price_precision: Option<f64>,
+    pub amount_precision: Option<f64>,
+    pub min_amount: Option<f64>,
+    pub min_cost: Option<f64>,
+    /// Fractions of notional, e.g. 0.001 for 10bps
+    pub maker_fee: Option<f64>,
+    pub taker_fee: Option<f64>,
+    pub active: bool,
+}
+
+/// Maker and taker fees for one market, or for an exchange in `Config::fees`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Fees {
+    pub maker: f64,
+    pub taker: f64,
+    /// What the fee is paid in; the venue's usual convention if not set
+    #[serde(default)]
+    pub charged_in: Option<FeeAsset>,
+}
+
+/// The asset a trade's fee is paid in, which decides how it scales the trade
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "asset", rename_all = "snake_case")]
+pub enum FeeAsset {
+    /// Deducted from what the trade pays out, either way round
+    Received,
+    /// The pair's quote currency: added to what a buy spends, deducted from
+    /// what a sell receives
+    Quote,
+    /// The venue's own token (BNB, CRO) from a separate balance, at a
+    /// `discount` off the rate; the traded amounts stay whole
+    Token { discount: f64 },
+}
+
+impl FeeAsset {
+    /// How `exchange` charges by default
+    pub fn convention(exchange: &str) -> FeeAsset {
+        match exchange {
+            "coinbase" | "kraken" | "bitstamp" | "gemini" => FeeAsset::Quote,
+            // binance, bitfinex, cryptocom, mexc and the rest
+            _ => FeeAsset::Received,
+        }
+    }
+    
+    /// Share of the `from` side that arrives as `to` after a fee of `rate`,
+    /// converting `from` into `to` on a pair quoted in `quote` (if known)
+    pub fn multiplier(self, rate: f64, to: &str, quote: Option<&str>) -> f64 {
+        match self {
+            FeeAsset::Received => 1.0 - rate,
+            // Buying spends the quote currency: the fee comes on top of it
+            FeeAsset::Quote if quote.is_some_and(|quote| quote != to) => 1.0 / (1.0 + rate),
+            FeeAsset::Quote => 1.0 - rate,
+            // Still a cost, paid in the token's value
+            FeeAsset::Token { discount } => 1.0 - rate * (1.0 - discount),
+        }
+    }
+}
+
+impl Fees {
+    /// What this exchange's fee is paid in
+    pub fn asset(&self, exchange: &str) -> FeeAsset {
+        self.charged_in.unwrap_or_else(|| FeeAsset::convention(exchange))
+    }
+}
+
+/// Parse CCXT markets JSON, either the symbol-keyed object from `load_markets()`
+/// or the array from `fetch_markets()`. Derivatives are skipped; the graph is spot only
+pub fn parse_ccxt_markets(exchange: &str, json: &str) -> Result<Vec<MarketInfo>, String> {
+    let root: Value = serde_json::from_str(json).map_err(|e| format!("invalid JSON: {}", e))?;
+    let entries: Vec<&Value> = match &root {
+        Value::Object(map) => map.values().collect(),
+        Value::Array(list) => list.iter().collect(),
+        _ => return Err("expected an object keyed by symbol or an array of markets".to_string()),
+    };
+    
+    let mut markets: Vec<MarketInfo> = entries.into_iter().filter_map(|entry| parse_market(exchange, entry)).collect();
+    markets.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    if markets.is_empty() {
+        return Err("no spot markets found".to_string());
+    }
+    Ok(markets)
+}
+
+fn parse_market(exchange: &str, entry: &Value) -> Option<MarketInfo> {
+    let spot = entry.get("spot").and_then(Value::as_bool).unwrap_or(true)
+        && entry.get("type").and_then(Value::as_str).is_none_or(|t| t == "spot");
+    let symbol = entry.get("symbol")?.as_str()?;
+    if !spot || symbol.contains(':') {
+        return None;
+    }
+    let (base, quote) = symbol.split_once('/')?;
+    let number = |path: &[&str]| path.iter().try_fold(entry, |value, key| value.get(key)).and_then(Value::as_f64);
+    
+    Some(MarketInfo {
+        exchange: exchange.to_string(),
+        symbol: symbol.to_string(),
+        base: entry.get("base").and_then(Value::as_str).unwrap_or(base).to_string(),
+        quote: entry.get("quote").and_then(Value::as_str).unwrap_or(quote).to_string(),
+        native_id: entry.get("id").and_then(Value::as_str).unwrap_or(symbol).to_string(),
+        price_precision: number(&["precision", "price"]),
+        amount_precision: number(&["precision", "amount"]),
+        min_amount: number(&["limits", "amount", "min"]),
+        min_cost: number(&["limits", "cost", "min"]),
+        maker_fee: number(&["maker"]),
+        taker_fee: number(&["taker"]),
+        active: entry.get("active").and_then(Value::as_bool).unwrap_or(true),
+    })
+}
+
+/// Markets, assets and fees per exchange. Each refresh replaces an
+/// exchange's markets wholesale; a failed refresh keeps the last good set
+#[derive(Default)]
+pub struct MarketRegistry {
+    markets: RwLock<HashMap<String, HashMap<String, MarketInfo>>>,
+}
+
+impl MarketRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn replace(&self, exchange: &str, markets: Vec<MarketInfo>) {
+        let by_symbol = markets.into_iter().map(|market| (market.symbol.clone(), market)).collect();
+        self.markets.write().unwrap().insert(exchange.to_string(), by_symbol);
+    }
+    
+    pub fn market(&self, exchange: &str, symbol: &str) -> Option<MarketInfo> {
+        self.markets.read().unwrap().get(exchange)?.get(symbol).cloned()
+    }
+    
+    /// Unified symbol for an exchange's native market id
+    pub fn symbol_for(&self, exchange: &str, native_id: &str) -> Option<String> {
+        let markets = self.markets.read().unwrap();
+        markets.get(exchange)?.values().find(|m| m.native_id == native_id).map(|m| m.symbol.clone())
+    }
+    
+    /// Every asset listed on `exchange`, sorted
+    pub fn assets(&self, exchange: &str) -> Vec<String> {
+        let markets = self.markets.read().unwrap();
+        let Some(markets) = markets.get(exchange) else { return Vec::new() };
+        let assets: BTreeSet<&String> = markets.values().flat_map(|m| [&m.base, &m.quote]).collect();
+        assets.into_iter().cloned().collect()
+    }
+    
+    /// Fees for `symbol` on `exchange`, when CCXT published both sides
+    pub fn fees(&self, exchange: &str, symbol: &str) -> Option<Fees> {
+        let market = self.market(exchange, symbol)?;
+        Some(Fees { maker: market.maker_fee?, taker: market.taker_fee?, charged_in: None })
+    }
+    
+    /// Aliased assets listed under more than one of their tickers across
+    /// the loaded exchanges, i.e. those the graph links with conversion edges
+    pub fn cross_listings(&self, aliases: &[AssetAlias]) -> Vec<CrossListing> {
+        let mut exchanges: Vec<String> = self.markets.read().unwrap().keys().cloned().collect();
+        exchanges.sort();
+        let listed: Vec<(String, Vec<String>)> = exchanges
+            .into_iter()
+            .map(|exchange| {
+                let assets = self.assets(&exchange);
+                (exchange, assets)
+            })
+            .collect();
+        
+        aliases
+            .iter()
+            .filter_map(|alias| {
+                let listings: BTreeMap<String, Vec<String>> = listed
+                    .iter()
+                    .filter_map(|(exchange, assets)| {
+                        let tickers: Vec<String> = alias.tickers().filter(|ticker| assets.contains(ticker)).cloned().collect();
+                        (!tickers.is_empty()).then(|| (exchange.clone(), tickers))
+                    })
+                    .collect();
+                let mut tickers: Vec<&String> = listings.values().flatten().collect();
+                tickers.sort();
+                tickers.dedup();
+                (tickers.len() > 1).then(|| CrossListing {
+                    asset: alias.asset.clone(),
+                    kind: alias.kind,
+                    listings,
+                })
+            })
+            .collect()
+    }
+    
+    /// All markets, ordered by exchange then symbol
+    pub fn snapshot(&self) -> Vec<MarketInfo> {
+        let markets = self.markets.read().unwrap();
+        let mut all: Vec<MarketInfo> = markets.values().flat_map(|m| m.values().cloned()).collect();
+        all.sort_by(|a, b| (&a.exchange, &a.symbol).cmp(&(&b.exchange, &b.symbol)));
+        all
+    }
+    
+    /// Reload every configured source, returning how many loaded
+    pub async fn refresh(&self, config: &MarketsConfig, client: &reqwest::Client) -> usize {
+        let mut loaded = 0;
+        for (exchange, source) in &config.sources {
+            match fetch(client, source).await.and_then(|json| parse_ccxt_markets(exchange, &json)) {
+                Ok(markets) => {
+                    info!("Loaded {} {} markets from {}", markets.len(), exchange, source);
+                    self.replace(exchange, markets);
+                    loaded += 1;
+                }
+                Err(e) => warn!("Market metadata for {} from {} failed, keeping previous: {}", exchange, source, e),
+            }
+        }
+        loaded
+    }
+    
+    /// Refresh now and then every `refresh_interval`
+    pub async fn run(self: Arc<Self>, config: MarketsConfig) {
+        let client = reqwest::Client::new();
+        let mut interval = tokio::time::interval(config.refresh_interval);
+        loop {
+            interval.tick().await;
+            self.refresh(&config, &client).await;
+        }
+    }
+}
+
+async fn fetch(client: &reqwest::Client, source: &str) -> Result<String, String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let response = client.get(source).send().await.map_err(|e| e.to_string())?;
+        let response = response.error_for_status().map_err(|e| e.to_string())?;
+        response.text().await.map_err(|e| e.to_string())
+    } else {
+        tokio::fs::read_to_string(source).await.map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const KRAKEN: &str = r#"{
+        "BTC/USD": {"id": "XXBTZUSD", "symbol": "BTC/USD", "base": "BTC", "quote": "USD", "type": "spot", "spot": true,
+            "active": true, "maker": 0.0016, "taker": 0.0026, "precision": {"amount": 1e-8, "price": 0.1},
+            "limits": {"amount": {"min": 0.0001, "max": null}, "cost": {"min": 0.5}}},
+        "ETH/BTC": {"id": "XETHXXBT", "symbol": "ETH/BTC", "base": "ETH", "quote": "BTC", "spot": true,
+            "maker": 0.0016, "precision": {"amount": 1e-8, "price": 1e-5}, "limits": {}},
+        "BTC/USD:USD": {"id": "PF_XBTUSD", "symbol": "BTC/USD:USD", "type": "swap", "spot": false}
+    }"#;
+    
+    #[test]
+    fn test_parses_spot_markets_and_skips_derivatives() {
+        let markets = parse_ccxt_markets("kraken", KRAKEN).unwrap();
+        assert_eq!(markets.len(), 2);
+        assert_eq!(markets[0].symbol, "BTC/USD");
+        assert_eq!(markets[0].native_id, "XXBTZUSD");
+        assert_eq!((markets[0].price_precision, markets[0].min_cost), (Some(0.1), Some(0.5)));
+        assert_eq!(markets[1].taker_fee, None);
+        
+        assert!(parse_ccxt_markets("kraken", "[]").is_err());
+        assert!(parse_ccxt_markets("kraken", "not json").is_err());
+    }
+    
+    #[test]
+    fn test_registry_maps_symbols_assets_and_fees() {
+        let registry = MarketRegistry::new();
+        registry.replace("kraken", parse_ccxt_markets("kraken", KRAKEN).unwrap());
+        
+        assert_eq!(registry.symbol_for("kraken", "XETHXXBT").as_deref(), Some("ETH/BTC"));
+        assert_eq!(registry.assets("kraken"), vec!["BTC", "ETH", "USD"]);
+        assert_eq!(registry.fees("kraken", "BTC/USD"), Some(Fees { maker: 0.0016, taker: 0.0026, charged_in: None }));
+        assert_eq!(registry.fees("kraken", "ETH/BTC"), None);
+        assert!(registry.assets("binance").is_empty());
+        
+        // A refresh replaces the exchange's markets wholesale
+        registry.replace("kraken", Vec::new());
+        assert!(registry.market("kraken", "BTC/USD").is_none());
+    }
+    
+    #[test]
+    fn test_cross_listings_find_assets_under_several_tickers() {
+        let market = |exchange: &str, base: &str, quote: &str| MarketInfo {
+            exchange: exchange.to_string(),
+            symbol: format!("{}/{}", base, quote),
+            base: base.to_string(),
+            quote: quote.to_string(),
+            native_id: format!("{}{}", base, quote),
+            price_precision: None,
+            amount_precision: None,
+            min_amount: None,
+            min_cost: None,
+            maker_fee: None,
+            taker_fee: None,
+            active: true,
+        };
+        let registry = MarketRegistry::new();
+        registry.replace("binance", vec![market("binance", "RENDER", "USDT"), market("binance", "BTC", "USDT")]);
+        registry.replace("kraken", vec![market("kraken", "RNDR", "USD"), market("kraken", "BTC", "USD")]);
+        
+        let aliases = AssetAlias::known();
+        let listings = registry.cross_listings(&aliases);
+        assert_eq!(listings.len(), 1, "BTC is only listed as BTC: {:?}", listings);
+        assert_eq!(listings[0].asset, "RENDER");
+        assert_eq!(listings[0].listings["binance"], vec!["RENDER"]);
+        assert_eq!(listings[0].listings["kraken"], vec!["RNDR"]);
+        
+        assert_eq!(conversion(&aliases, "RNDR", "RENDER").map(|a| a.kind), Some(AliasKind::Rename));
+        assert_eq!(conversion(&aliases, "WBTC", "BTC").map(|a| a.kind), Some(AliasKind::Wrapped));
+        assert!(conversion(&aliases, "BTC", "BTC").is_none());
+        assert!(conversion(&aliases, "WBTC", "ETH").is_none());
+    }
+}
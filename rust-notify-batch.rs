@@ -0,0 +1,127 @@
+// notify/batch.rs - Digest low-profit opportunities into one push per channel per interval
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::arbitrage::types::ArbitrageOpportunity;
+use crate::display::DisplayFormat;
+use super::Notification;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchingConfig {
+    /// How often each channel's digest is sent
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+    /// Opportunities below this profit (fraction) are digested instead of pushed
+    pub below_profit: f64,
+}
+
+impl Default for BatchingConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(30),
+            below_profit: 0.005,
+        }
+    }
+}
+
+struct Digest {
+    count: usize,
+    best: ArbitrageOpportunity,
+}
+
+/// Low-profit opportunities per channel, until the next flush
+pub struct AlertBatcher {
+    config: BatchingConfig,
+    digests: Mutex<BTreeMap<String, Digest>>,
+}
+
+impl AlertBatcher {
+    pub fn new(config: BatchingConfig) -> Self {
+        Self {
+            config,
+            digests: Mutex::new(BTreeMap::new()),
+        }
+    }
+    
+    pub fn interval(&self) -> Duration {
+        self.config.interval
+    }
+    
+    /// Whether `opportunity` goes into digests rather than its own push
+    pub fn batches(&self, opportunity: &ArbitrageOpportunity) -> bool {
+        opportunity.profit_percentage < self.config.below_profit
+    }
+    
+    pub fn add(&self, channel: &str, opportunity: &ArbitrageOpportunity) {
+        let mut digests = self.digests.lock().unwrap();
+        match digests.get_mut(channel) {
+            Some(digest) => {
+                digest.count += 1;
+                if opportunity.profit_percentage > digest.best.profit_percentage {
+                    digest.best = opportunity.clone();
+                }
+            }
+            None => {
+                digests.insert(channel.to_string(), Digest { count: 1, best: opportunity.clone() });
+            }
+        }
+    }
+    
+    /// One digest per channel with anything batched since the last flush,
+    /// e.g. "12 opportunities in last 30s, best 0.18% BTC binance↔kraken"
+    pub fn flush(&self, display: &DisplayFormat) -> Vec<(String, Notification)> {
+        let digests = std::mem::take(&mut *self.digests.lock().unwrap());
+        digests
+            .into_iter()
+            .map(|(channel, digest)| {
+                let noun = if digest.count == 1 { "opportunity" } else { "opportunities" };
+                let title = format!("{} {} in last {}s", digest.count, noun, self.config.interval.as_secs());
+                let asset = digest.best.path.split('_').next().unwrap_or_default();
+                let body = format!(
+                    "{}, best {} {} {}",
+                    title,
+                    display.profit(digest.best.profit_percentage),
+                    asset,
+                    digest.best.exchanges.join("↔")
+                );
+                let notification = Notification {
+                    title,
+                    body,
+                    path: Some(digest.best.path),
+                    approval_id: None,
+                };
+                (channel, notification)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_flush_sends_one_digest_per_channel_with_the_best() {
+        let batcher = AlertBatcher::new(BatchingConfig::default());
+        let best = ArbitrageOpportunity::sample("BTC_binance -> BTC_kraken -> BTC_binance", 0.0018);
+        assert!(batcher.batches(&best));
+        assert!(!batcher.batches(&ArbitrageOpportunity::sample("BTC_binance", 0.01)));
+        
+        for _ in 0..11 {
+            batcher.add("ntfy", &ArbitrageOpportunity::sample("ETH_binance -> USDT_binance -> ETH_binance", 0.0011));
+        }
+        batcher.add("ntfy", &best);
+        batcher.add("slack", &best);
+        
+        let digests = batcher.flush(&DisplayFormat::default());
+        assert_eq!(digests.len(), 2);
+        let (channel, notification) = &digests[0];
+        assert_eq!(channel, "ntfy");
+        assert_eq!(notification.title, "12 opportunities in last 30s");
+        assert_eq!(notification.body, "12 opportunities in last 30s, best 0.18% BTC binance↔kraken");
+        assert_eq!(digests[1].1.title, "1 opportunity in last 30s");
+        assert!(batcher.flush(&DisplayFormat::default()).is_empty(), "flushing empties the digests");
+    }
+}
@@ -0,0 +1,236 @@
+// notify/mod.rs - Push notification channels for alerts
+pub mod batch;
+pub mod ntfy;
+pub mod pushover;
+pub mod slack;
+pub mod twilio;
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::arbitrage::types::ArbitrageOpportunity;
+use self::batch::BatchingConfig;
+use self::ntfy::{NtfyConfig, NtfyNotifier};
+use self::pushover::{PushoverConfig, PushoverNotifier};
+use self::slack::{SlackConfig, SlackNotifier};
+use self::twilio::{TwilioConfig, TwilioNotifier};
+
+/// Requests to notification services give up after this long; the outbox retries
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub type SendFuture<'a> = Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+/// What a notifier delivers; also the outbox payload for retries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+    /// Opportunity path, so channels can offer actions on it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Set when the opportunity awaits manual approval before execution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_id: Option<u64>,
+}
+
+/// Which critical conditions a channel escalates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CriticalKind {
+    ExchangeDown,
+    ProtocolDrift,
+    CancelFailed,
+    Takeover,
+    Silence,
+}
+
+/// Operational incident that needs a human now, as opposed to an opportunity
+#[derive(Debug, Clone, PartialEq)]
+pub enum CriticalEvent {
+    /// A connector gave up reconnecting; the venue is no longer being scanned
+    ExchangeDown { exchange: String, attempts: u32, error: String },
+    /// An exchange's payloads stopped matching its schema
+    ProtocolDrift { exchange: String, drift: String },
+    /// A leg swept after a disconnect or going stale couldn't be cancelled and may still fill
+    CancelFailed { exchange: String, order_id: u64, symbol: String, error: String },
+    /// The primary's heartbeat stopped and this standby took over alerting and execution
+    Takeover { primary: String, silent_secs: u64, in_flight: usize },
+    /// A watched exchange or detector has been quiet for longer than its `silence` watch allows
+    Silence { name: String, events: String, silent_secs: u64 },
+}
+
+impl CriticalEvent {
+    pub fn kind(&self) -> CriticalKind {
+        match self {
+            CriticalEvent::ExchangeDown { .. } => CriticalKind::ExchangeDown,
+            CriticalEvent::ProtocolDrift { .. } => CriticalKind::ProtocolDrift,
+            CriticalEvent::CancelFailed { .. } => CriticalKind::CancelFailed,
+            CriticalEvent::Takeover { .. } => CriticalKind::Takeover,
+            CriticalEvent::Silence { .. } => CriticalKind::Silence,
+        }
+    }
+    
+    pub fn notification(&self) -> Notification {
+        let title = match self {
+            CriticalEvent::ExchangeDown { exchange, .. } => format!("CRITICAL: {} down", exchange),
+            CriticalEvent::ProtocolDrift { exchange, .. } => format!("CRITICAL: {} protocol changed", exchange),
+            CriticalEvent::CancelFailed { exchange, .. } => format!("CRITICAL: {} cancel failed", exchange),
+            CriticalEvent::Takeover { .. } => "CRITICAL: standby took over".to_string(),
+            CriticalEvent::Silence { name, .. } => format!("CRITICAL: {} silent", name),
+        };
+        Notification {
+            title,
+            body: self.to_string(),
+            path: None,
+            approval_id: None,
+        }
+    }
+}
+
+impl fmt::Display for CriticalEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CriticalEvent::ExchangeDown { exchange, attempts, error } => {
+                write!(f, "{} connection gave up after {} attempts: {}", exchange, attempts, error)
+            }
+            CriticalEvent::ProtocolDrift { exchange, drift } => write!(f, "{} payload drift: {}", exchange, drift),
+            CriticalEvent::CancelFailed { exchange, order_id, symbol, error } => {
+                write!(f, "order #{} ({} {}) is still open after a failed cancel: {}", order_id, exchange, symbol, error)
+            }
+            CriticalEvent::Takeover { primary, silent_secs, in_flight } => write!(
+                f,
+                "primary {} silent for {}s; standby is now alerting and executing, with {} in-flight legs handed off",
+                primary, silent_secs, in_flight
+            ),
+            CriticalEvent::Silence { name, events, silent_secs } => {
+                write!(f, "no {} {} for {}s; check the feed or detector is still running", name, events, silent_secs)
+            }
+        }
+    }
+}
+
+/// One push channel
+pub trait Notifier: Send + Sync {
+    /// Outbox channel name; also the key for per-channel alert templates
+    fn channel(&self) -> &str;
+    
+    /// Whether the opportunity clears this channel's tier
+    fn accepts(&self, opportunity: &ArbitrageOpportunity) -> bool;
+    
+    /// Whether this channel is part of the escalation path for `event`
+    fn escalates(&self, _event: &CriticalEvent) -> bool {
+        false
+    }
+    
+    fn send<'a>(&'a self, notification: &'a Notification) -> SendFuture<'a>;
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotifyConfig {
+    pub ntfy: Option<NtfyConfig>,
+    pub pushover: Option<PushoverConfig>,
+    /// Slack app; with a signing secret, alerts carry paper-execute/blacklist/snooze buttons
+    pub slack: Option<SlackConfig>,
+    /// Highest escalation tier: phone calls/SMS for critical events only
+    pub twilio: Option<TwilioConfig>,
+    /// Digest opportunities below a profit into one push per channel per interval
+    pub batching: Option<BatchingConfig>,
+}
+
+/// Every configured notifier
+pub struct Notifiers {
+    notifiers: Vec<Box<dyn Notifier>>,
+}
+
+impl Notifiers {
+    pub fn from_config(config: &NotifyConfig) -> Self {
+        let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+        if let Some(ntfy) = &config.ntfy {
+            notifiers.push(Box::new(NtfyNotifier::new(ntfy.clone())));
+        }
+        if let Some(pushover) = &config.pushover {
+            notifiers.push(Box::new(PushoverNotifier::new(pushover.clone())));
+        }
+        if let Some(slack) = &config.slack {
+            notifiers.push(Box::new(SlackNotifier::new(slack.clone())));
+        }
+        if let Some(twilio) = &config.twilio {
+            notifiers.push(Box::new(TwilioNotifier::new(twilio.clone())));
+        }
+        Self { notifiers }
+    }
+    
+    /// Notifiers whose tier this opportunity reaches
+    pub fn for_opportunity<'a>(&'a self, opportunity: &'a ArbitrageOpportunity) -> impl Iterator<Item = &'a dyn Notifier> {
+        self.notifiers
+            .iter()
+            .map(|n| n.as_ref())
+            .filter(move |n| n.accepts(opportunity))
+    }
+    
+    /// Notifiers that escalate this event
+    pub fn for_event<'a>(&'a self, event: &'a CriticalEvent) -> impl Iterator<Item = &'a dyn Notifier> {
+        self.notifiers
+            .iter()
+            .map(|n| n.as_ref())
+            .filter(move |n| n.escalates(event))
+    }
+    
+    pub fn get(&self, channel: &str) -> Option<&dyn Notifier> {
+        self.notifiers.iter().map(|n| n.as_ref()).find(|n| n.channel() == channel)
+    }
+}
+
+/// Short push title, e.g. "Arbitrage 0.12% on binance, kraken"
+pub fn title(opportunity: &ArbitrageOpportunity, profit: &str) -> String {
+    format!("Arbitrage {} on {}", profit, opportunity.exchanges.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const PATH: &str = "BTC_binance -> ETH_binance -> USDT_binance -> BTC_binance";
+    
+    #[test]
+    fn test_notifiers_filtered_by_tier() {
+        let config = NotifyConfig {
+            ntfy: Some(NtfyConfig {
+                topic: "arb-alerts".to_string(),
+                min_profit: 0.002,
+                ..NtfyConfig::default()
+            }),
+            pushover: Some(PushoverConfig {
+                user_key: "user".to_string(),
+                app_token: "token".to_string(),
+                min_profit: 0.01,
+                ..PushoverConfig::default()
+            }),
+            slack: None,
+            twilio: Some(TwilioConfig::default()),
+            batching: None,
+        };
+        let notifiers = Notifiers::from_config(&config);
+        
+        let channels = |profit| -> Vec<String> {
+            let opp = ArbitrageOpportunity::sample(PATH, profit);
+            notifiers.for_opportunity(&opp).map(|n| n.channel().to_string()).collect()
+        };
+        assert!(channels(0.001).is_empty());
+        assert_eq!(channels(0.005), vec!["ntfy"]);
+        assert_eq!(channels(0.02), vec!["ntfy", "pushover"]);
+        assert!(notifiers.get("pushover").is_some());
+        assert!(notifiers.get("telegram").is_none());
+        
+        let down = CriticalEvent::ExchangeDown {
+            exchange: "binance".to_string(),
+            attempts: 10,
+            error: "timeout".to_string(),
+        };
+        let escalated: Vec<&str> = notifiers.for_event(&down).map(|n| n.channel()).collect();
+        assert_eq!(escalated, vec!["twilio"]);
+    }
+}
@@ -0,0 +1,106 @@
+// notify/ntfy.rs - ntfy.sh push notifications
+use serde::{Deserialize, Serialize};
+
+use crate::arbitrage::types::ArbitrageOpportunity;
+use super::{Notification, Notifier, SendFuture, REQUEST_TIMEOUT};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NtfyConfig {
+    /// ntfy.sh or a self-hosted server
+    pub server: String,
+    pub topic: String,
+    /// Access token for protected topics
+    pub token: Option<String>,
+    /// Only opportunities at least this profitable (fraction) are pushed
+    pub min_profit: f64,
+    /// 1 (min) to 5 (max); 4 and up break through Do Not Disturb on most phones
+    pub priority: u8,
+}
+
+impl Default for NtfyConfig {
+    fn default() -> Self {
+        Self {
+            server: "https://ntfy.sh".to_string(),
+            topic: String::new(),
+            token: None,
+            min_profit: 0.005,
+            priority: 4,
+        }
+    }
+}
+
+pub struct NtfyNotifier {
+    config: NtfyConfig,
+    client: reqwest::Client,
+}
+
+impl NtfyNotifier {
+    pub fn new(config: NtfyConfig) -> Self {
+        Self { config, client: reqwest::Client::new() }
+    }
+    
+    fn request(&self, notification: &Notification) -> reqwest::RequestBuilder {
+        let url = format!("{}/{}", self.config.server.trim_end_matches('/'), self.config.topic);
+        let mut request = self
+            .client
+            .post(url)
+            .timeout(REQUEST_TIMEOUT)
+            .header("Title", &notification.title)
+            .header("Priority", self.config.priority.to_string())
+            .header("Tags", "chart_with_upwards_trend")
+            .body(notification.body.clone());
+        if let Some(token) = &self.config.token {
+            request = request.bearer_auth(token);
+        }
+        request
+    }
+}
+
+impl Notifier for NtfyNotifier {
+    fn channel(&self) -> &str {
+        "ntfy"
+    }
+    
+    fn accepts(&self, opportunity: &ArbitrageOpportunity) -> bool {
+        opportunity.profit_percentage >= self.config.min_profit
+    }
+    
+    fn send<'a>(&'a self, notification: &'a Notification) -> SendFuture<'a> {
+        Box::pin(async move {
+            let response = self.request(notification).send().await.map_err(|e| e.to_string())?;
+            if response.status().is_success() {
+                Ok(())
+            } else {
+                Err(format!("ntfy returned {}", response.status()))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_request_targets_topic_with_headers() {
+        let notifier = NtfyNotifier::new(NtfyConfig {
+            server: "https://ntfy.example.com/".to_string(),
+            topic: "arb".to_string(),
+            token: Some("tk_secret".to_string()),
+            ..NtfyConfig::default()
+        });
+        let notification = Notification {
+            title: "Arbitrage 0.12% on binance".to_string(),
+            body: "BTC -> ETH -> USDT".to_string(),
+            path: None,
+            approval_id: None,
+        };
+        
+        let request = notifier.request(&notification).build().unwrap();
+        assert_eq!(request.url().as_str(), "https://ntfy.example.com/arb");
+        assert_eq!(request.headers()["Title"], "Arbitrage 0.12% on binance");
+        assert_eq!(request.headers()["Priority"], "4");
+        assert_eq!(request.headers()["Authorization"], "Bearer tk_secret");
+        assert_eq!(request.body().and_then(|b| b.as_bytes()), Some("BTC -> ETH -> USDT".as_bytes()));
+    }
+}
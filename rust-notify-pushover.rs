@@ -0,0 +1,98 @@
+// notify/pushover.rs - Pushover push notifications
+use serde::{Deserialize, Serialize};
+
+use crate::arbitrage::types::ArbitrageOpportunity;
+use super::{Notification, Notifier, SendFuture, REQUEST_TIMEOUT};
+
+const API_URL: &str = "https://api.pushover.net/1/messages.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PushoverConfig {
+    pub user_key: String,
+    pub app_token: String,
+    /// Only opportunities at least this profitable (fraction) are pushed
+    pub min_profit: f64,
+    /// -2 (silent) to 1 (high); emergency (2) needs acknowledgement and isn't used
+    pub priority: i8,
+    /// Device name; empty sends to all of the user's devices
+    pub device: Option<String>,
+}
+
+pub struct PushoverNotifier {
+    config: PushoverConfig,
+    client: reqwest::Client,
+}
+
+impl PushoverNotifier {
+    pub fn new(config: PushoverConfig) -> Self {
+        Self { config, client: reqwest::Client::new() }
+    }
+    
+    fn request(&self, notification: &Notification) -> reqwest::RequestBuilder {
+        let priority = self.config.priority.to_string();
+        let mut form = vec![
+            ("token", self.config.app_token.as_str()),
+            ("user", self.config.user_key.as_str()),
+            ("title", notification.title.as_str()),
+            ("message", notification.body.as_str()),
+            ("priority", priority.as_str()),
+        ];
+        if let Some(device) = &self.config.device {
+            form.push(("device", device));
+        }
+        self.client.post(API_URL).timeout(REQUEST_TIMEOUT).form(&form)
+    }
+}
+
+impl Notifier for PushoverNotifier {
+    fn channel(&self) -> &str {
+        "pushover"
+    }
+    
+    fn accepts(&self, opportunity: &ArbitrageOpportunity) -> bool {
+        opportunity.profit_percentage >= self.config.min_profit
+    }
+    
+    fn send<'a>(&'a self, notification: &'a Notification) -> SendFuture<'a> {
+        Box::pin(async move {
+            let response = self.request(notification).send().await.map_err(|e| e.to_string())?;
+            if response.status().is_success() {
+                Ok(())
+            } else {
+                // Pushover explains rejections (bad key, quota) in the body
+                let status = response.status();
+                let detail = response.text().await.unwrap_or_default();
+                Err(format!("pushover returned {}: {}", status, detail))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_request_is_form_encoded() {
+        let notifier = PushoverNotifier::new(PushoverConfig {
+            user_key: "u123".to_string(),
+            app_token: "a456".to_string(),
+            priority: 1,
+            ..PushoverConfig::default()
+        });
+        let notification = Notification {
+            title: "Arbitrage 1.50% on kraken".to_string(),
+            body: "BTC -> EUR".to_string(),
+            path: None,
+            approval_id: None,
+        };
+        
+        let request = notifier.request(&notification).build().unwrap();
+        let body = std::str::from_utf8(request.body().and_then(|b| b.as_bytes()).unwrap()).unwrap();
+        assert_eq!(request.url().as_str(), API_URL);
+        assert_eq!(
+            body,
+            "token=a456&user=u123&title=Arbitrage+1.50%25+on+kraken&message=BTC+-%3E+EUR&priority=1"
+        );
+    }
+}
@@ -0,0 +1,265 @@
+// notify/slack.rs - Slack app messages with interactive opportunity actions
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+use crate::arbitrage::controls::path_pairs;
+use crate::arbitrage::types::ArbitrageOpportunity;
+use crate::control::{ControlAction, DEFAULT_SNOOZE};
+use super::{Notification, Notifier, SendFuture, REQUEST_TIMEOUT};
+
+/// Slack rejects replays older than this; so do we
+const MAX_REQUEST_AGE_SECS: u64 = 300;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SlackConfig {
+    /// Incoming webhook of the Slack app
+    pub webhook_url: String,
+    /// App signing secret; enables the action buttons, whose presses Slack
+    /// posts to /api/slack/actions
+    pub signing_secret: Option<String>,
+    /// Only opportunities at least this profitable (fraction) are posted
+    pub min_profit: f64,
+}
+
+pub struct SlackNotifier {
+    config: SlackConfig,
+    client: reqwest::Client,
+}
+
+impl SlackNotifier {
+    pub fn new(config: SlackConfig) -> Self {
+        Self { config, client: reqwest::Client::new() }
+    }
+    
+    fn request(&self, notification: &Notification) -> reqwest::RequestBuilder {
+        let mut blocks = vec![json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": format!("*{}*\n{}", notification.title, notification.body) },
+        })];
+        if let (Some(path), Some(_)) = (&notification.path, &self.config.signing_secret) {
+            blocks.push(json!({ "type": "actions", "elements": buttons(path, notification.approval_id) }));
+        }
+        
+        self.client
+            .post(&self.config.webhook_url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&json!({ "text": notification.title, "blocks": blocks }))
+    }
+}
+
+/// "Approve"/"Reject" for queued opportunities, then "Execute paper", "Blacklist
+/// path" and, when the path trades a pair, "Snooze pair 1h"
+fn buttons(path: &str, approval_id: Option<u64>) -> Vec<Value> {
+    let button = |text: &str, action_id: &str, value: &str| {
+        json!({
+            "type": "button",
+            "text": { "type": "plain_text", "text": text },
+            "action_id": action_id,
+            "value": value,
+        })
+    };
+    let mut buttons = Vec::new();
+    if let Some(id) = approval_id {
+        let id = id.to_string();
+        buttons.push(button("Approve", "approve", &id));
+        buttons.push(button("Reject", "reject", &id));
+    } else {
+        buttons.push(button("Execute paper", "execute_paper", path));
+    }
+    buttons.push(button("Blacklist path", "blacklist_path", path));
+    if let Some(pair) = path_pairs(path).first() {
+        buttons.push(button("Snooze pair 1h", "snooze_pair", pair));
+    }
+    buttons
+}
+
+impl Notifier for SlackNotifier {
+    fn channel(&self) -> &str {
+        "slack"
+    }
+    
+    fn accepts(&self, opportunity: &ArbitrageOpportunity) -> bool {
+        opportunity.profit_percentage >= self.config.min_profit
+    }
+    
+    fn send<'a>(&'a self, notification: &'a Notification) -> SendFuture<'a> {
+        Box::pin(async move {
+            let response = self.request(notification).send().await.map_err(|e| e.to_string())?;
+            if response.status().is_success() {
+                Ok(())
+            } else {
+                Err(format!("Slack returned {}", response.status()))
+            }
+        })
+    }
+}
+
+/// A verified button press
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlackAction {
+    pub action: ControlAction,
+    pub user: String,
+    /// Where to post the outcome; the HTTP response to Slack is ignored
+    pub response_url: String,
+}
+
+#[derive(Deserialize)]
+struct Interaction {
+    user: InteractionUser,
+    actions: Vec<InteractionAction>,
+    response_url: String,
+}
+
+#[derive(Deserialize)]
+struct InteractionUser {
+    #[serde(default)]
+    username: String,
+}
+
+#[derive(Deserialize)]
+struct InteractionAction {
+    action_id: String,
+    value: String,
+}
+
+/// Receives button presses from Slack's interactivity request URL
+pub struct SlackActions {
+    signing_secret: String,
+    client: reqwest::Client,
+}
+
+impl SlackActions {
+    pub fn new(signing_secret: String) -> Self {
+        Self { signing_secret, client: reqwest::Client::new() }
+    }
+    
+    /// Check `X-Slack-Signature` over the raw body before trusting anything in it
+    pub fn verify(&self, timestamp: &str, body: &[u8], signature: &str, now_secs: u64) -> Result<(), String> {
+        let sent_at: u64 = timestamp.parse().map_err(|_| "bad request timestamp".to_string())?;
+        if now_secs.abs_diff(sent_at) > MAX_REQUEST_AGE_SECS {
+            return Err("request timestamp too old".to_string());
+        }
+        let signature = signature
+            .strip_prefix("v0=")
+            .and_then(|hex_sig| hex::decode(hex_sig).ok())
+            .ok_or_else(|| "malformed signature".to_string())?;
+        
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.signing_secret.as_bytes()).map_err(|e| e.to_string())?;
+        mac.update(b"v0:");
+        mac.update(timestamp.as_bytes());
+        mac.update(b":");
+        mac.update(body);
+        mac.verify_slice(&signature).map_err(|_| "signature mismatch".to_string())
+    }
+    
+    /// The action in a form-encoded `block_actions` payload
+    pub fn parse(&self, body: &[u8]) -> Result<SlackAction, String> {
+        let form: Vec<(String, String)> = serde_urlencoded::from_bytes(body).map_err(|e| e.to_string())?;
+        let payload = form
+            .into_iter()
+            .find(|(key, _)| key == "payload")
+            .map(|(_, value)| value)
+            .ok_or_else(|| "missing payload".to_string())?;
+        let interaction: Interaction = serde_json::from_str(&payload).map_err(|e| e.to_string())?;
+        
+        let pressed = interaction.actions.into_iter().next().ok_or_else(|| "no action pressed".to_string())?;
+        let approval_id = || pressed.value.parse::<u64>().map_err(|_| format!("bad approval id {:?}", pressed.value));
+        let action = match pressed.action_id.as_str() {
+            "approve" => ControlAction::Approve { id: approval_id()? },
+            "reject" => ControlAction::Reject { id: approval_id()? },
+            "execute_paper" => ControlAction::ExecutePaper { path: pressed.value },
+            "blacklist_path" => ControlAction::BlacklistPath { path: pressed.value },
+            "snooze_pair" => ControlAction::SnoozePair {
+                pair: pressed.value,
+                duration: DEFAULT_SNOOZE,
+            },
+            other => return Err(format!("unknown action {:?}", other)),
+        };
+        Ok(SlackAction {
+            action,
+            user: interaction.user.username,
+            response_url: interaction.response_url,
+        })
+    }
+    
+    /// Post the outcome under the alert without replacing it
+    pub async fn respond(&self, response_url: &str, text: &str) -> Result<(), String> {
+        let response = self
+            .client
+            .post(response_url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&json!({ "replace_original": false, "text": text }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("Slack returned {}", response.status()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("v0:{}:", timestamp).as_bytes());
+        mac.update(body);
+        format!("v0={}", hex::encode(mac.finalize().into_bytes()))
+    }
+    
+    #[test]
+    fn test_message_has_buttons_for_path() {
+        let notifier = SlackNotifier::new(SlackConfig {
+            webhook_url: "https://hooks.slack.com/services/T/B/X".to_string(),
+            signing_secret: Some("secret".to_string()),
+            min_profit: 0.002,
+        });
+        let notification = Notification {
+            title: "Arbitrage 0.12% on binance".to_string(),
+            body: "BTC -> ETH".to_string(),
+            path: Some("BTC_binance -> ETH_binance -> BTC_kraken".to_string()),
+            approval_id: None,
+        };
+        
+        let request = notifier.request(&notification).build().unwrap();
+        let body: Value = serde_json::from_slice(request.body().and_then(|b| b.as_bytes()).unwrap()).unwrap();
+        let buttons = &body["blocks"][1]["elements"];
+        assert_eq!(buttons[0]["action_id"], "execute_paper");
+        assert_eq!(buttons[1]["value"], "BTC_binance -> ETH_binance -> BTC_kraken");
+        assert_eq!(buttons[2]["value"], "BTC/ETH");
+        
+        let queued = Notification { approval_id: Some(7), ..notification };
+        let request = notifier.request(&queued).build().unwrap();
+        let body: Value = serde_json::from_slice(request.body().and_then(|b| b.as_bytes()).unwrap()).unwrap();
+        let buttons = &body["blocks"][1]["elements"];
+        assert_eq!((&buttons[0]["action_id"], &buttons[0]["value"]), (&json!("approve"), &json!("7")));
+        assert_eq!(buttons[1]["action_id"], "reject");
+    }
+    
+    #[test]
+    fn test_signed_action_verified_and_parsed() {
+        let actions = SlackActions::new("8f742231b10e8888abcd99yyyzzz85a5".to_string());
+        let payload = json!({
+            "type": "block_actions",
+            "user": { "id": "U1", "username": "ops" },
+            "actions": [{ "action_id": "snooze_pair", "value": "BTC/ETH" }],
+            "response_url": "https://hooks.slack.com/actions/T/1/abc",
+        });
+        let body = serde_urlencoded::to_string([("payload", payload.to_string())]).unwrap();
+        let signature = sign("8f742231b10e8888abcd99yyyzzz85a5", "1531420618", body.as_bytes());
+        
+        assert!(actions.verify("1531420618", body.as_bytes(), &signature, 1531420700).is_ok());
+        assert!(actions.verify("1531420618", b"payload=tampered", &signature, 1531420700).is_err());
+        assert!(actions.verify("1531420618", body.as_bytes(), &signature, 1531421000).is_err());
+        
+        let action = actions.parse(body.as_bytes()).unwrap();
+        assert_eq!(action.user, "ops");
+        assert!(matches!(action.action, ControlAction::SnoozePair { ref pair, .. } if pair == "BTC/ETH"));
+    }
+}
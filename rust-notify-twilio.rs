@@ -0,0 +1,157 @@
+// notify/twilio.rs - Twilio phone call and SMS escalation for critical events
+use serde::{Deserialize, Serialize};
+
+use crate::arbitrage::types::ArbitrageOpportunity;
+use super::{CriticalEvent, CriticalKind, Notification, Notifier, SendFuture, REQUEST_TIMEOUT};
+
+const API_BASE: &str = "https://api.twilio.com/2010-04-01/Accounts";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TwilioMode {
+    Sms,
+    Call, // Text-to-speech voice call; harder to sleep through
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwilioConfig {
+    pub account_sid: String,
+    pub auth_token: String,
+    /// Twilio number in E.164 form, e.g. "+15005550006"
+    pub from: String,
+    /// On-call numbers; every one is contacted
+    pub to: Vec<String>,
+    pub mode: TwilioMode,
+    /// Critical events that escalate to a phone
+    pub events: Vec<CriticalKind>,
+}
+
+impl Default for TwilioConfig {
+    fn default() -> Self {
+        Self {
+            account_sid: String::new(),
+            auth_token: String::new(),
+            from: String::new(),
+            to: Vec::new(),
+            mode: TwilioMode::Sms,
+            events: vec![CriticalKind::ExchangeDown],
+        }
+    }
+}
+
+pub struct TwilioNotifier {
+    config: TwilioConfig,
+    client: reqwest::Client,
+}
+
+impl TwilioNotifier {
+    pub fn new(config: TwilioConfig) -> Self {
+        Self { config, client: reqwest::Client::new() }
+    }
+    
+    fn request(&self, to: &str, notification: &Notification) -> reqwest::RequestBuilder {
+        let text = format!("{}. {}", notification.title, notification.body);
+        let (resource, content_field, content) = match self.config.mode {
+            TwilioMode::Sms => ("Messages", "Body", text),
+            TwilioMode::Call => ("Calls", "Twiml", format!("<Response><Say>{}</Say></Response>", xml_escape(&text))),
+        };
+        let url = format!("{}/{}/{}.json", API_BASE, self.config.account_sid, resource);
+        
+        self.client
+            .post(url)
+            .timeout(REQUEST_TIMEOUT)
+            .basic_auth(&self.config.account_sid, Some(&self.config.auth_token))
+            .form(&[("To", to), ("From", self.config.from.as_str()), (content_field, content.as_str())])
+    }
+}
+
+impl Notifier for TwilioNotifier {
+    fn channel(&self) -> &str {
+        "twilio"
+    }
+    
+    /// Phones are for incidents only, never for opportunities
+    fn accepts(&self, _opportunity: &ArbitrageOpportunity) -> bool {
+        false
+    }
+    
+    fn escalates(&self, event: &CriticalEvent) -> bool {
+        self.config.events.contains(&event.kind())
+    }
+    
+    fn send<'a>(&'a self, notification: &'a Notification) -> SendFuture<'a> {
+        Box::pin(async move {
+            // One failed number fails the whole delivery so the outbox retries;
+            // a repeat SMS beats a missed page
+            for to in &self.config.to {
+                let response = self.request(to, notification).send().await.map_err(|e| e.to_string())?;
+                if !response.status().is_success() {
+                    let status = response.status();
+                    let detail = response.text().await.unwrap_or_default();
+                    return Err(format!("twilio returned {} for {}: {}", status, to, detail));
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn notifier(mode: TwilioMode) -> TwilioNotifier {
+        TwilioNotifier::new(TwilioConfig {
+            account_sid: "AC123".to_string(),
+            auth_token: "secret".to_string(),
+            from: "+15005550006".to_string(),
+            to: vec!["+14155550100".to_string()],
+            mode,
+            ..TwilioConfig::default()
+        })
+    }
+    
+    fn body(request: &reqwest::Request) -> &str {
+        std::str::from_utf8(request.body().and_then(|b| b.as_bytes()).unwrap()).unwrap()
+    }
+    
+    #[test]
+    fn test_sms_and_call_requests() {
+        let notification = Notification {
+            title: "CRITICAL: binance down".to_string(),
+            body: "gave up after 10 attempts".to_string(),
+            path: None,
+            approval_id: None,
+        };
+        
+        let sms = notifier(TwilioMode::Sms).request("+14155550100", &notification).build().unwrap();
+        assert_eq!(sms.url().as_str(), "https://api.twilio.com/2010-04-01/Accounts/AC123/Messages.json");
+        assert!(sms.headers()["Authorization"].to_str().unwrap().starts_with("Basic "));
+        assert!(body(&sms).contains("Body=CRITICAL%3A+binance+down.+gave+up"));
+        
+        let call = notifier(TwilioMode::Call).request("+14155550100", &notification).build().unwrap();
+        assert!(call.url().as_str().ends_with("/Calls.json"));
+        assert!(body(&call).contains("Twiml=%3CResponse%3E%3CSay%3E"));
+    }
+    
+    #[test]
+    fn test_escalates_only_configured_events() {
+        let notifier = notifier(TwilioMode::Call);
+        let down = CriticalEvent::ExchangeDown {
+            exchange: "kraken".to_string(),
+            attempts: 10,
+            error: "connection refused".to_string(),
+        };
+        let drift = CriticalEvent::ProtocolDrift {
+            exchange: "kraken".to_string(),
+            drift: "missing field b".to_string(),
+        };
+        
+        assert!(notifier.escalates(&down));
+        assert!(!notifier.escalates(&drift));
+    }
+}
@@ -0,0 +1,377 @@
+// outbox.rs - Persistent outbound alert queue with retries and circuit breakers
+use std::collections::HashMap;
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, info, warn};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxConfig {
+    /// Queue file; rewritten on every change so undelivered alerts survive restarts
+    pub path: PathBuf,
+    #[serde(with = "humantime_serde")]
+    pub initial_backoff: Duration,
+    #[serde(with = "humantime_serde")]
+    pub max_backoff: Duration,
+    /// Alerts are dropped after this many failed deliveries
+    pub max_attempts: u32,
+    /// Consecutive failures that open a channel's circuit
+    pub breaker_threshold: u32,
+    /// How long an open circuit waits before letting one trial delivery through
+    #[serde(with = "humantime_serde")]
+    pub breaker_cooldown: Duration,
+    #[serde(with = "humantime_serde")]
+    pub poll_interval: Duration,
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("alert_outbox.json"),
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(300),
+            max_attempts: 10,
+            breaker_threshold: 5,
+            breaker_cooldown: Duration::from_secs(60),
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingAlert {
+    pub id: u64,
+    pub channel: String,
+    pub payload: Value,
+    pub attempts: u32,
+    pub enqueued_at_ms: u64,
+    pub next_attempt_at_ms: u64,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    Closed,
+    Open,
+    HalfOpen, // Cooldown elapsed; the next delivery decides
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelStatus {
+    pub channel: String,
+    pub state: BreakerState,
+    pub consecutive_failures: u32,
+    pub pending: usize,
+}
+
+/// Everything `/api/alerts/pending` shows
+#[derive(Debug, Clone, Serialize)]
+pub struct OutboxView {
+    pub pending: Vec<PendingAlert>,
+    pub channels: Vec<ChannelStatus>,
+    pub dropped: u64,
+}
+
+#[derive(Default)]
+struct Breaker {
+    consecutive_failures: u32,
+    open_until_ms: Option<u64>,
+}
+
+impl Breaker {
+    fn state(&self, now_ms: u64) -> BreakerState {
+        match self.open_until_ms {
+            None => BreakerState::Closed,
+            Some(until) if now_ms < until => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+}
+
+/// On-disk form of the queue
+#[derive(Default, Serialize, Deserialize)]
+struct StoredQueue {
+    next_id: u64,
+    alerts: Vec<PendingAlert>,
+}
+
+struct OutboxState {
+    queue: StoredQueue,
+    breakers: HashMap<String, Breaker>,
+    dropped: u64,
+}
+
+pub struct AlertOutbox {
+    config: OutboxConfig,
+    state: Mutex<OutboxState>,
+}
+
+impl AlertOutbox {
+    /// Queue backed by `config.path`, picking up whatever a previous run left behind
+    pub fn open(config: OutboxConfig) -> Self {
+        let queue = match fs::read_to_string(&config.path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable alert outbox {}: {}", config.path.display(), e);
+                StoredQueue::default()
+            }),
+            Err(_) => StoredQueue::default(),
+        };
+        if !queue.alerts.is_empty() {
+            info!("Recovered {} undelivered alerts from {}", queue.alerts.len(), config.path.display());
+        }
+        
+        Self {
+            config,
+            state: Mutex::new(OutboxState {
+                queue,
+                breakers: HashMap::new(),
+                dropped: 0,
+            }),
+        }
+    }
+    
+    pub fn enqueue(&self, channel: &str, payload: Value, now_ms: u64) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let id = state.queue.next_id;
+        state.queue.next_id += 1;
+        state.queue.alerts.push(PendingAlert {
+            id,
+            channel: channel.to_string(),
+            payload,
+            attempts: 0,
+            enqueued_at_ms: now_ms,
+            next_attempt_at_ms: now_ms,
+            last_error: None,
+        });
+        self.persist(&state.queue);
+        id
+    }
+    
+    /// Alerts ready for another attempt; a half-open channel gets a single trial
+    pub fn due(&self, now_ms: u64) -> Vec<PendingAlert> {
+        let state = self.state.lock().unwrap();
+        let mut trials: Vec<&str> = Vec::new();
+        let mut due = Vec::new();
+        
+        for alert in state.queue.alerts.iter().filter(|a| a.next_attempt_at_ms <= now_ms) {
+            let breaker_state = state
+                .breakers
+                .get(&alert.channel)
+                .map_or(BreakerState::Closed, |b| b.state(now_ms));
+            match breaker_state {
+                BreakerState::Closed => due.push(alert.clone()),
+                BreakerState::Open => {}
+                BreakerState::HalfOpen => {
+                    if !trials.contains(&alert.channel.as_str()) {
+                        trials.push(&alert.channel);
+                        due.push(alert.clone());
+                    }
+                }
+            }
+        }
+        
+        due
+    }
+    
+    pub fn record_success(&self, id: u64) {
+        let mut state = self.state.lock().unwrap();
+        let Some(pos) = state.queue.alerts.iter().position(|a| a.id == id) else {
+            return;
+        };
+        let alert = state.queue.alerts.remove(pos);
+        state.breakers.remove(&alert.channel);
+        self.persist(&state.queue);
+    }
+    
+    pub fn record_failure(&self, id: u64, error: &str, now_ms: u64) {
+        let mut state = self.state.lock().unwrap();
+        let Some(pos) = state.queue.alerts.iter().position(|a| a.id == id) else {
+            return;
+        };
+        
+        let channel = state.queue.alerts[pos].channel.clone();
+        let breaker = state.breakers.entry(channel.clone()).or_default();
+        breaker.consecutive_failures += 1;
+        if breaker.consecutive_failures >= self.config.breaker_threshold {
+            if breaker.state(now_ms) != BreakerState::Open {
+                warn!("Alert channel {} circuit open after {} failures", channel, breaker.consecutive_failures);
+            }
+            breaker.open_until_ms = Some(now_ms + self.config.breaker_cooldown.as_millis() as u64);
+        }
+        
+        let alert = &mut state.queue.alerts[pos];
+        alert.attempts += 1;
+        alert.last_error = Some(error.to_string());
+        alert.next_attempt_at_ms = now_ms + self.backoff(alert.attempts).as_millis() as u64;
+        
+        if alert.attempts >= self.config.max_attempts {
+            warn!("Dropping alert {} on {} after {} attempts: {}", id, channel, alert.attempts, error);
+            state.queue.alerts.remove(pos);
+            state.dropped += 1;
+        }
+        self.persist(&state.queue);
+    }
+    
+    /// One delivery pass over everything due now
+    pub async fn deliver_due<F, Fut>(&self, deliver: F)
+    where
+        F: Fn(PendingAlert) -> Fut,
+        Fut: Future<Output = Result<(), String>>,
+    {
+        for alert in self.due(now_ms()) {
+            let id = alert.id;
+            match deliver(alert).await {
+                Ok(()) => self.record_success(id),
+                Err(e) => {
+                    debug!("Alert {} delivery failed: {}", id, e);
+                    self.record_failure(id, &e, now_ms());
+                }
+            }
+        }
+    }
+    
+    pub fn poll_interval(&self) -> Duration {
+        self.config.poll_interval
+    }
+    
+    pub fn view(&self, now_ms: u64) -> OutboxView {
+        let state = self.state.lock().unwrap();
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for alert in &state.queue.alerts {
+            *counts.entry(&alert.channel).or_default() += 1;
+        }
+        for channel in state.breakers.keys() {
+            counts.entry(channel).or_default();
+        }
+        
+        let mut channels: Vec<ChannelStatus> = counts
+            .into_iter()
+            .map(|(channel, pending)| {
+                let breaker = state.breakers.get(channel);
+                ChannelStatus {
+                    channel: channel.to_string(),
+                    state: breaker.map_or(BreakerState::Closed, |b| b.state(now_ms)),
+                    consecutive_failures: breaker.map_or(0, |b| b.consecutive_failures),
+                    pending,
+                }
+            })
+            .collect();
+        channels.sort_by(|a, b| a.channel.cmp(&b.channel));
+        
+        OutboxView {
+            pending: state.queue.alerts.clone(),
+            channels,
+            dropped: state.dropped,
+        }
+    }
+    
+    /// Exponential backoff after the `attempts`-th failure, capped at `max_backoff`
+    fn backoff(&self, attempts: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempts.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.config.initial_backoff.saturating_mul(factor).min(self.config.max_backoff)
+    }
+    
+    /// Write-then-rename so a crash never leaves a truncated queue file
+    fn persist(&self, queue: &StoredQueue) {
+        let tmp = self.config.path.with_extension("tmp");
+        let result = serde_json::to_vec(queue)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| fs::write(&tmp, bytes).map_err(|e| e.to_string()))
+            .and_then(|()| fs::rename(&tmp, &self.config.path).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("Failed to persist alert outbox to {}: {}", self.config.path.display(), e);
+        }
+    }
+}
+
+/// Wall-clock milliseconds; queue timestamps must survive restarts, so no `Instant`
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    fn outbox(name: &str) -> AlertOutbox {
+        let path = std::env::temp_dir().join(format!("outbox-{}-{}.json", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        AlertOutbox::open(OutboxConfig {
+            path,
+            breaker_threshold: 3,
+            ..OutboxConfig::default()
+        })
+    }
+    
+    #[test]
+    fn test_failed_delivery_backs_off_exponentially() {
+        let outbox = outbox("backoff");
+        let id = outbox.enqueue("telegram", json!({"path": "A -> B -> C"}), 0);
+        
+        outbox.record_failure(id, "timeout", 0);
+        assert!(outbox.due(999).is_empty());
+        assert_eq!(outbox.due(1_000).len(), 1);
+        
+        outbox.record_failure(id, "timeout", 1_000);
+        assert!(outbox.due(2_999).is_empty());
+        assert_eq!(outbox.due(3_000)[0].attempts, 2);
+        
+        outbox.record_success(id);
+        assert!(outbox.view(3_000).pending.is_empty());
+    }
+    
+    #[test]
+    fn test_circuit_opens_per_channel_and_half_opens_after_cooldown() {
+        let outbox = outbox("breaker");
+        let ids: Vec<u64> = (0..3).map(|_| outbox.enqueue("discord", json!({}), 0)).collect();
+        outbox.enqueue("webhook", json!({}), 0);
+        
+        for &id in &ids {
+            outbox.record_failure(id, "502", 0);
+        }
+        
+        // Discord is open; webhook keeps flowing
+        let due = outbox.due(59_000);
+        assert!(due.iter().all(|a| a.channel == "webhook"));
+        let view = outbox.view(0);
+        assert_eq!(view.channels[0].state, BreakerState::Open);
+        assert_eq!(view.channels[1].state, BreakerState::Closed);
+        
+        // After the cooldown only one discord alert is let through
+        let due = outbox.due(61_000);
+        assert_eq!(due.iter().filter(|a| a.channel == "discord").count(), 1);
+        outbox.record_success(ids[0]);
+        assert_eq!(outbox.due(61_000).iter().filter(|a| a.channel == "discord").count(), 2);
+    }
+    
+    #[test]
+    fn test_queue_survives_restart_and_drops_after_max_attempts() {
+        let outbox = outbox("restart");
+        let id = outbox.enqueue("webhook", json!({"profit": 0.01}), 0);
+        outbox.record_failure(id, "connection refused", 0);
+        
+        let reopened = AlertOutbox::open(outbox.config.clone());
+        let view = reopened.view(0);
+        assert_eq!(view.pending.len(), 1);
+        assert_eq!(view.pending[0].attempts, 1);
+        assert_eq!(reopened.enqueue("webhook", json!({}), 0), id + 1);
+        
+        for attempt in 1..reopened.config.max_attempts {
+            reopened.record_failure(id, "connection refused", attempt as u64);
+        }
+        let view = reopened.view(0);
+        assert_eq!(view.pending.len(), 1);
+        assert_eq!(view.dropped, 1);
+        
+        let _ = fs::remove_file(&reopened.config.path);
+    }
+}
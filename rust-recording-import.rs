@@ -0,0 +1,332 @@
+// recording/import.rs - Historical quote imports (Binance dumps, Tardis, Kaiko) into recordings
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::exchange::ExchangeRegistry;
+use super::{append_ticks, write_line, RecordedTick};
+
+/// Source layouts; all are best bid/ask quote files, one row per update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// data.binance.vision `bookTicker` CSV, with or without its header row
+    BinanceBookTicker,
+    /// Tardis.dev `quotes` dataset CSV (microsecond timestamps, one or many venues)
+    TardisQuotes,
+    /// Kaiko best bid/ask CSV export for one instrument
+    KaikoQuotes,
+}
+
+impl FromStr for ImportFormat {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "binance" | "binance-book-ticker" => Ok(ImportFormat::BinanceBookTicker),
+            "tardis" | "tardis-quotes" => Ok(ImportFormat::TardisQuotes),
+            "kaiko" | "kaiko-quotes" => Ok(ImportFormat::KaikoQuotes),
+            other => Err(format!("unknown import format '{}' (binance, tardis, kaiko)", other)),
+        }
+    }
+}
+
+/// Accepted header names per column, first match wins
+struct Layout {
+    time: &'static [&'static str],
+    bid: &'static [&'static str],
+    bid_size: &'static [&'static str],
+    ask: &'static [&'static str],
+    ask_size: &'static [&'static str],
+    exchange: &'static [&'static str],
+    symbol: &'static [&'static str],
+    /// Column order of files without a header row
+    headerless: &'static [&'static str],
+}
+
+const BINANCE_LAYOUT: Layout = Layout {
+    time: &["transaction_time", "event_time"],
+    bid: &["best_bid_price"],
+    bid_size: &["best_bid_qty"],
+    ask: &["best_ask_price"],
+    ask_size: &["best_ask_qty"],
+    exchange: &[],
+    symbol: &[],
+    headerless: &["update_id", "best_bid_price", "best_bid_qty", "best_ask_price", "best_ask_qty", "transaction_time", "event_time"],
+};
+
+const TARDIS_LAYOUT: Layout = Layout {
+    time: &["timestamp", "local_timestamp"],
+    bid: &["bid_price"],
+    bid_size: &["bid_amount"],
+    ask: &["ask_price"],
+    ask_size: &["ask_amount"],
+    exchange: &["exchange"],
+    symbol: &["symbol"],
+    headerless: &[],
+};
+
+const KAIKO_LAYOUT: Layout = Layout {
+    time: &["timestamp", "poll_timestamp", "date"],
+    bid: &["bid_price", "best_bid_price"],
+    bid_size: &["bid_volume", "bid_amount", "best_bid_amount"],
+    ask: &["ask_price", "best_ask_price"],
+    ask_size: &["ask_volume", "ask_amount", "best_ask_amount"],
+    exchange: &["exchange", "exchange_code"],
+    symbol: &["instrument", "symbol", "instrument_code"],
+    headerless: &[],
+};
+
+impl ImportFormat {
+    fn layout(self) -> &'static Layout {
+        match self {
+            ImportFormat::BinanceBookTicker => &BINANCE_LAYOUT,
+            ImportFormat::TardisQuotes => &TARDIS_LAYOUT,
+            ImportFormat::KaikoQuotes => &KAIKO_LAYOUT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Venue for files without an exchange column; Binance dumps default to "binance"
+    pub exchange: Option<String>,
+    /// `BASE/QUOTE` for every row, overriding the file's own symbols
+    pub symbol: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportStats {
+    pub imported: usize,
+    pub skipped: usize,
+    /// Why the first skipped row was skipped, to fix the invocation
+    pub first_error: Option<String>,
+}
+
+/// Column positions resolved from the header
+struct Columns {
+    time: usize,
+    bid: usize,
+    bid_size: usize,
+    ask: usize,
+    ask_size: usize,
+    exchange: Option<usize>,
+    symbol: Option<usize>,
+}
+
+impl Columns {
+    fn resolve(layout: &Layout, header: &[&str]) -> Result<Self, String> {
+        let find = |names: &[&str]| names.iter().find_map(|name| header.iter().position(|h| h.eq_ignore_ascii_case(name)));
+        let require = |names: &[&str]| find(names).ok_or_else(|| format!("missing column {}", names.join(" or ")));
+        Ok(Self {
+            time: require(layout.time)?,
+            bid: require(layout.bid)?,
+            bid_size: require(layout.bid_size)?,
+            ask: require(layout.ask)?,
+            ask_size: require(layout.ask_size)?,
+            exchange: find(layout.exchange),
+            symbol: find(layout.symbol),
+        })
+    }
+}
+
+/// Import quote rows from `path` into the recording in `dir`
+pub fn import_file(
+    path: &Path,
+    format: ImportFormat,
+    options: &ImportOptions,
+    exchanges: &ExchangeRegistry,
+    dir: &Path,
+) -> Result<ImportStats, String> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    
+    // Binance dumps are per symbol and named after it: BTCUSDT-bookTicker-2024-01-01.csv
+    let mut options = options.clone();
+    if format == ImportFormat::BinanceBookTicker {
+        options.exchange.get_or_insert_with(|| "binance".to_string());
+    }
+    let file_symbol = match format {
+        ImportFormat::BinanceBookTicker => path.file_name().and_then(|name| name.to_str()?.split('-').next()).map(str::to_string),
+        _ => None,
+    };
+    
+    let mut out = append_ticks(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let stats = import_rows(BufReader::new(file), format, &options, exchanges, file_symbol.as_deref(), |tick| write_line(&mut out, tick))
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    out.flush().map_err(|e| format!("{}: {}", dir.display(), e))?;
+    Ok(stats)
+}
+
+/// Parse `reader` row by row, handing each tick to `sink`; rows that don't
+/// parse are counted and skipped rather than aborting a multi-gigabyte import
+fn import_rows(
+    reader: impl BufRead,
+    format: ImportFormat,
+    options: &ImportOptions,
+    exchanges: &ExchangeRegistry,
+    file_symbol: Option<&str>,
+    mut sink: impl FnMut(&RecordedTick) -> std::io::Result<()>,
+) -> Result<ImportStats, String> {
+    let layout = format.layout();
+    let mut lines = reader.lines();
+    let mut stats = ImportStats::default();
+    let mut symbols: HashMap<(String, String), Option<String>> = HashMap::new();
+    
+    let Some(first) = lines.next().transpose().map_err(|e| e.to_string())? else {
+        return Ok(stats);
+    };
+    let header: Vec<&str> = first.split(',').map(str::trim).collect();
+    let headerless = header.first().is_some_and(|field| field.parse::<f64>().is_ok());
+    let columns = if headerless && !layout.headerless.is_empty() {
+        Columns::resolve(layout, layout.headerless)?
+    } else {
+        Columns::resolve(layout, &header)?
+    };
+    let first_row = headerless.then_some(first.clone());
+    
+    for line in first_row.into_iter().map(Ok).chain(lines) {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        match parse_row(&fields, &columns, options, exchanges, file_symbol, &mut symbols) {
+            Ok(tick) => {
+                sink(&tick).map_err(|e| e.to_string())?;
+                stats.imported += 1;
+            }
+            Err(e) => {
+                stats.skipped += 1;
+                stats.first_error.get_or_insert(e);
+            }
+        }
+    }
+    Ok(stats)
+}
+
+fn parse_row(
+    fields: &[&str],
+    columns: &Columns,
+    options: &ImportOptions,
+    exchanges: &ExchangeRegistry,
+    file_symbol: Option<&str>,
+    symbols: &mut HashMap<(String, String), Option<String>>,
+) -> Result<RecordedTick, String> {
+    let field = |index: usize| fields.get(index).copied().ok_or_else(|| format!("row has {} fields", fields.len()));
+    let number = |index: usize| {
+        let raw = field(index)?;
+        raw.parse::<f64>().map_err(|_| format!("{:?} is not a number", raw))
+    };
+    
+    let exchange = match (&options.exchange, columns.exchange) {
+        (Some(exchange), _) => exchange.clone(),
+        (None, Some(index)) => field(index)?.to_lowercase(),
+        (None, None) => return Err("no exchange column; pass --exchange".to_string()),
+    };
+    let symbol = match (&options.symbol, columns.symbol.map(field).transpose()?.or(file_symbol)) {
+        (Some(symbol), _) => symbol.to_uppercase(),
+        (None, Some(raw)) => symbols
+            .entry((exchange.clone(), raw.to_string()))
+            .or_insert_with(|| canonical_symbol(exchanges, &exchange, raw))
+            .clone()
+            .ok_or_else(|| format!("can't map {} symbol {:?}; pass --symbol", exchange, raw))?,
+        (None, None) => return Err("no symbol column; pass --symbol".to_string()),
+    };
+    
+    let (bid, ask) = (number(columns.bid)?, number(columns.ask)?);
+    if !(bid > 0.0 && ask > 0.0) {
+        return Err(format!("non-positive quote {} / {}", bid, ask));
+    }
+    Ok(RecordedTick {
+        at_ms: timestamp_ms(field(columns.time)?)?,
+        exchange,
+        symbol,
+        bid,
+        ask,
+        volume: number(columns.bid_size)?.min(number(columns.ask_size)?),
+    })
+}
+
+/// `BASE/QUOTE` for a venue-native symbol, via that venue's connector when we have one
+fn canonical_symbol(exchanges: &ExchangeRegistry, exchange: &str, raw: &str) -> Option<String> {
+    if let Some(connector) = exchanges.connector(exchange) {
+        if let Some(symbol) = connector.from_exchange_symbol(raw) {
+            return Some(symbol);
+        }
+    }
+    raw.split_once(['/', '-', '_'])
+        .filter(|(base, quote)| !base.is_empty() && !quote.is_empty())
+        .map(|(base, quote)| format!("{}/{}", base, quote).to_uppercase())
+}
+
+/// Epoch timestamps in seconds, milliseconds or microseconds (told apart by
+/// magnitude), or RFC 3339 as Kaiko exports them
+fn timestamp_ms(raw: &str) -> Result<u64, String> {
+    if let Ok(value) = raw.parse::<f64>() {
+        let ms = match value {
+            v if v >= 1e15 => v / 1_000.0, // Microseconds
+            v if v >= 1e12 => v,           // Milliseconds
+            v => v * 1_000.0,              // Seconds
+        };
+        return Ok(ms as u64);
+    }
+    humantime_serde::re::humantime::parse_rfc3339_weak(raw.trim_end_matches('Z'))
+        .ok()
+        .and_then(|at| at.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|since| since.as_millis() as u64)
+        .ok_or_else(|| format!("{:?} is not a timestamp", raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn import(csv: &str, format: ImportFormat, options: ImportOptions, file_symbol: Option<&str>) -> (ImportStats, Vec<RecordedTick>) {
+        let mut ticks = Vec::new();
+        let stats = import_rows(csv.as_bytes(), format, &options, &ExchangeRegistry::builtin(), file_symbol, |tick| {
+            ticks.push(tick.clone());
+            Ok(())
+        })
+        .unwrap();
+        (stats, ticks)
+    }
+    
+    #[test]
+    fn test_binance_dump_with_and_without_header() {
+        let rows = "1,50000.1,2.5,50000.2,1.5,1704067200000,1704067200001\n2,50000.0,1.0,50000.3,3.0,1704067200005,1704067200006\n";
+        let options = ImportOptions { exchange: Some("binance".to_string()), ..ImportOptions::default() };
+        let (stats, ticks) = import(rows, ImportFormat::BinanceBookTicker, options.clone(), Some("BTCUSDT"));
+        assert_eq!((stats.imported, stats.skipped), (2, 0));
+        assert_eq!((ticks[0].symbol.as_str(), ticks[0].at_ms, ticks[0].volume), ("BTC/USDT", 1704067200000, 1.5));
+        
+        let with_header = format!("update_id,best_bid_price,best_bid_qty,best_ask_price,best_ask_qty,transaction_time,event_time\n{}bad,row\n", rows);
+        let (stats, _) = import(&with_header, ImportFormat::BinanceBookTicker, options, Some("BTCUSDT"));
+        assert_eq!((stats.imported, stats.skipped), (2, 1));
+        assert!(stats.first_error.is_some());
+    }
+    
+    #[test]
+    fn test_tardis_and_kaiko_map_venue_symbols() {
+        let tardis = "exchange,symbol,timestamp,local_timestamp,ask_amount,ask_price,bid_price,bid_amount\n\
+            coinbase,BTC-USD,1704067200123456,1704067200124000,0.5,42000.5,42000.0,1.2\n\
+            kraken,XBT/USD,1704067200200000,1704067200201000,2.0,42001.0,42000.9,0.3\n";
+        let (stats, ticks) = import(tardis, ImportFormat::TardisQuotes, ImportOptions::default(), None);
+        assert_eq!(stats.imported, 2);
+        assert_eq!((ticks[0].exchange.as_str(), ticks[0].symbol.as_str(), ticks[0].at_ms), ("coinbase", "BTC/USD", 1704067200123));
+        assert_eq!(ticks[1].symbol, "BTC/USD");
+        
+        let kaiko = "timestamp,bid_price,bid_volume,ask_price,ask_volume\n2024-01-01T00:00:00.250Z,2300.1,4,2300.2,6\n";
+        let (stats, ticks) = import(kaiko, ImportFormat::KaikoQuotes, ImportOptions::default(), None);
+        assert_eq!(stats.skipped, 1, "no exchange or symbol to attribute the row to");
+        
+        assert!(ticks.is_empty());
+        
+        let options = ImportOptions {
+            exchange: Some("kraken".to_string()),
+            symbol: Some("eth/usd".to_string()),
+        };
+        let (_, ticks) = import(kaiko, ImportFormat::KaikoQuotes, options, None);
+        assert_eq!((ticks[0].symbol.as_str(), ticks[0].at_ms), ("ETH/USD", 1704067200250));
+    }
+}
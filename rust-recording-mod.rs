@@ -0,0 +1,315 @@
+// recording/mod.rs - Tick and opportunity recordings for replay and debugging
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+use crossbeam::channel::{self, Sender};
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+use tracing::{error, info, warn};
+
+use crate::arbitrage::types::ArbitrageOpportunity;
+use crate::clock::{self, SharedClock};
+
+pub mod import;
+
+const TICKS_FILE: &str = "ticks.jsonl";
+const OPPORTUNITIES_FILE: &str = "opportunities.jsonl";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingConfig {
+    /// Record every tick and alerted opportunity; costs disk, not latency
+    pub enabled: bool,
+    /// Directory holding ticks.jsonl and opportunities.jsonl
+    pub dir: PathBuf,
+    /// How much the `jobs.retention` job keeps; imported history older than
+    /// this goes too
+    #[serde(with = "humantime_serde")]
+    pub retention: Duration,
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: PathBuf::from("recordings"),
+            retention: Duration::from_secs(7 * 24 * 3600),
+        }
+    }
+}
+
+/// One price update as the engine received it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedTick {
+    pub at_ms: u64,
+    pub exchange: String,
+    pub symbol: String,
+    pub bid: f64,
+    pub ask: f64,
+    pub volume: f64,
+}
+
+/// An alerted opportunity; `id` is what `scanner debug-opportunity` takes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedOpportunity {
+    pub id: u64,
+    pub at_ms: u64,
+    pub path: String,
+    pub profit_percentage: f64,
+    pub exchanges: Vec<String>,
+}
+
+enum Record {
+    Tick(RecordedTick),
+    Opportunity(RecordedOpportunity),
+    Trim { before_ms: u64, done: oneshot::Sender<io::Result<usize>> },
+}
+
+/// Appends to the recording files from a background thread so the hot path
+/// only pays for a channel send
+pub struct Recorder {
+    sender: Sender<Record>,
+    next_id: AtomicU64,
+    clock: SharedClock,
+}
+
+impl Recorder {
+    /// Append to the recording in `dir`, continuing its opportunity ids
+    pub fn open(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let next_id = read_opportunities(dir)?.iter().map(|opp| opp.id).max().unwrap_or(0) + 1;
+        let mut ticks = append_ticks(dir)?;
+        let mut opportunities = BufWriter::new(append(&dir.join(OPPORTUNITIES_FILE))?);
+        
+        let (sender, receiver) = channel::unbounded::<Record>();
+        let recording_dir = dir.to_path_buf();
+        thread::Builder::new().name("recorder".to_string()).spawn(move || {
+            // Flush whenever the queue drains, so a crash loses at most one burst
+            while let Ok(first) = receiver.recv() {
+                for record in std::iter::once(first).chain(receiver.try_iter()) {
+                    let written = match record {
+                        Record::Tick(tick) => write_line(&mut ticks, &tick),
+                        Record::Opportunity(opp) => write_line(&mut opportunities, &opp),
+                        Record::Trim { before_ms, done } => {
+                            let _ = done.send(trim(&recording_dir, before_ms, &mut ticks, &mut opportunities));
+                            continue;
+                        }
+                    };
+                    if let Err(e) = written {
+                        error!("Recording write failed: {}", e);
+                    }
+                }
+                if let Err(e) = ticks.flush().and_then(|_| opportunities.flush()) {
+                    error!("Recording flush failed: {}", e);
+                }
+            }
+        })?;
+        
+        info!("Recording ticks and opportunities to {}", dir.display());
+        Ok(Self {
+            sender,
+            next_id: AtomicU64::new(next_id),
+            clock: clock::system(),
+        })
+    }
+    
+    /// Stamp records with `clock`'s wall time instead of the system's
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    pub fn record_tick(&self, exchange: &str, symbol: &str, bid: f64, ask: f64, volume: f64) {
+        let _ = self.sender.send(Record::Tick(RecordedTick {
+            at_ms: self.clock.unix_ms(),
+            exchange: exchange.to_string(),
+            symbol: symbol.to_string(),
+            bid,
+            ask,
+            volume,
+        }));
+    }
+    
+    /// Record `opportunity` and return its id
+    pub fn record_opportunity(&self, opportunity: &ArbitrageOpportunity) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let _ = self.sender.send(Record::Opportunity(RecordedOpportunity {
+            id,
+            at_ms: self.clock.unix_ms(),
+            path: opportunity.path.clone(),
+            profit_percentage: opportunity.profit_percentage,
+            exchanges: opportunity.exchanges.clone(),
+        }));
+        id
+    }
+    
+    /// Drop records from before `before_ms` once what's queued is written;
+    /// returns how many went. The newest opportunity stays, so ids carry on
+    /// after a restart
+    pub async fn trim(&self, before_ms: u64) -> io::Result<usize> {
+        let stopped = || io::Error::other("recorder stopped");
+        let (done, trimmed) = oneshot::channel();
+        self.sender.send(Record::Trim { before_ms, done }).map_err(|_| stopped())?;
+        trimmed.await.map_err(|_| stopped())?
+    }
+}
+
+/// Rewrite both files without records from before `before_ms` and point the
+/// writers at the new files
+fn trim(dir: &Path, before_ms: u64, ticks: &mut BufWriter<File>, opportunities: &mut BufWriter<File>) -> io::Result<usize> {
+    ticks.flush()?;
+    opportunities.flush()?;
+    let dropped = trim_file(dir, TICKS_FILE, before_ms, false)? + trim_file(dir, OPPORTUNITIES_FILE, before_ms, true)?;
+    *ticks = append_ticks(dir)?;
+    *opportunities = BufWriter::new(append(&dir.join(OPPORTUNITIES_FILE))?);
+    Ok(dropped)
+}
+
+#[derive(Deserialize)]
+struct Stamped {
+    at_ms: u64,
+}
+
+/// Keep the lines of `dir/file` stamped at or after `before_ms`, and the last
+/// one with `keep_last`; unparsable lines go too
+fn trim_file(dir: &Path, file: &str, before_ms: u64, keep_last: bool) -> io::Result<usize> {
+    let path = dir.join(file);
+    let reader = match File::open(&path) {
+        Ok(file) => BufReader::new(file),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    let trimmed_path = dir.join(format!("{}.trim", file));
+    let mut kept = BufWriter::new(File::create(&trimmed_path)?);
+    let mut dropped = 0;
+    let mut last_old = None;
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Stamped>(&line) {
+            Ok(stamped) if stamped.at_ms >= before_ms => {
+                // A newer line carries the ids on instead
+                dropped += usize::from(last_old.take().is_some());
+                writeln!(kept, "{}", line)?;
+            }
+            Ok(_) if keep_last => {
+                dropped += usize::from(last_old.is_some());
+                last_old = Some(line);
+            }
+            _ => dropped += 1,
+        }
+    }
+    if let Some(line) = last_old {
+        writeln!(kept, "{}", line)?;
+    }
+    kept.flush()?;
+    fs::rename(&trimmed_path, &path)?;
+    Ok(dropped)
+}
+
+fn append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn write_line<T: Serialize>(out: &mut impl Write, record: &T) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, record)?;
+    out.write_all(b"\n")
+}
+
+/// Ticks file of the recording in `dir`, opened for appending (e.g. by importers)
+fn append_ticks(dir: &Path) -> io::Result<BufWriter<File>> {
+    fs::create_dir_all(dir)?;
+    Ok(BufWriter::new(append(&dir.join(TICKS_FILE))?))
+}
+
+/// Parsable lines of `dir/file` that pass `keep`; a torn last line from a crash is skipped
+fn read_lines<T: for<'de> Deserialize<'de>>(dir: &Path, file: &str, keep: impl Fn(&T) -> bool) -> io::Result<Vec<T>> {
+    let path = dir.join(file);
+    let reader = match File::open(&path) {
+        Ok(file) => BufReader::new(file),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    
+    let mut records = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(record) if keep(&record) => records.push(record),
+            Ok(_) => {}
+            Err(e) => warn!("Skipping {}:{}: {}", path.display(), number + 1, e),
+        }
+    }
+    Ok(records)
+}
+
+pub fn read_opportunities(dir: &Path) -> io::Result<Vec<RecordedOpportunity>> {
+    read_lines(dir, OPPORTUNITIES_FILE, |_| true)
+}
+
+/// Recorded ticks with `from_ms <= at_ms <= to_ms`, in file order; imported
+/// history may sit after live data, so sort before replaying
+pub fn read_ticks(dir: &Path, from_ms: u64, to_ms: u64) -> io::Result<Vec<RecordedTick>> {
+    read_lines(dir, TICKS_FILE, |tick: &RecordedTick| (from_ms..=to_ms).contains(&tick.at_ms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    
+    #[test]
+    fn test_recording_round_trip_continues_ids() {
+        let dir = std::env::temp_dir().join(format!("recording-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let opportunity = ArbitrageOpportunity::sample("BTC_binance -> ETH_binance -> USDT_binance", 0.004);
+        
+        let recorder = Recorder::open(&dir).unwrap();
+        recorder.record_tick("binance", "BTC/USDT", 50_000.0, 50_001.0, 1.0);
+        assert_eq!(recorder.record_opportunity(&opportunity), 1);
+        assert_eq!(recorder.record_opportunity(&opportunity), 2);
+        drop(recorder);
+        thread::sleep(Duration::from_millis(100)); // Let the writer drain
+        
+        let ticks = read_ticks(&dir, 0, u64::MAX).unwrap();
+        assert_eq!((ticks.len(), ticks[0].symbol.as_str()), (1, "BTC/USDT"));
+        assert!(read_ticks(&dir, 0, ticks[0].at_ms - 1).unwrap().is_empty());
+        
+        // A torn line doesn't hide the rest of the file
+        fs::OpenOptions::new().append(true).open(dir.join(OPPORTUNITIES_FILE)).unwrap().write_all(b"{\"id\":").unwrap();
+        assert_eq!(read_opportunities(&dir).unwrap().len(), 2);
+        assert_eq!(Recorder::open(&dir).unwrap().record_opportunity(&opportunity), 3);
+        let _ = fs::remove_dir_all(&dir);
+    }
+    
+    #[tokio::test]
+    async fn test_trim_drops_old_records_but_not_the_last_id() {
+        let dir = std::env::temp_dir().join(format!("recording-trim-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let opportunity = ArbitrageOpportunity::sample("BTC_binance -> ETH_binance -> USDT_binance", 0.004);
+        
+        let recorder = Recorder::open(&dir).unwrap();
+        recorder.record_tick("binance", "BTC/USDT", 50_000.0, 50_001.0, 1.0);
+        recorder.record_opportunity(&opportunity);
+        recorder.record_opportunity(&opportunity);
+        assert_eq!(recorder.trim(clock::system().unix_ms() + 60_000).await.unwrap(), 2);
+        assert!(read_ticks(&dir, 0, u64::MAX).unwrap().is_empty());
+        let ids: Vec<u64> = read_opportunities(&dir).unwrap().iter().map(|opp| opp.id).collect();
+        assert_eq!(ids, vec![2]);
+        
+        // Writing carries on into the trimmed files
+        recorder.record_tick("binance", "ETH/USDT", 3_000.0, 3_000.5, 1.0);
+        assert_eq!(recorder.trim(0).await.unwrap(), 0);
+        assert_eq!(read_ticks(&dir, 0, u64::MAX).unwrap()[0].symbol, "ETH/USDT");
+        drop(recorder);
+        assert_eq!(Recorder::open(&dir).unwrap().record_opportunity(&opportunity), 3);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,377 @@
+// service/mod.rs - Running under a service manager: readiness, watchdog, stop requests and handover restarts
+pub mod systemd;
+#[cfg(windows)]
+pub mod windows;
+
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::{signal, time};
+use tracing::{debug, info, warn};
+
+use crate::exchange::subscriptions::{SubscriptionState, SubscriptionTracker};
+use crate::execution::orders::OrderHandoff;
+use systemd::Notifier;
+
+/// A connection to the handover address must ask to take over within this long
+const HANDOVER_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceConfig {
+    /// Restart without a gap: a new scanner connects its feeds before the
+    /// running one stops alerting and exits
+    pub handover: bool,
+    /// Where the running scanner waits for its successor
+    pub handover_listen: String,
+    /// Successor: take over after this long even if some subscriptions are
+    /// still unconfirmed
+    #[serde(with = "humantime_serde")]
+    pub warmup_timeout: Duration,
+    /// How long the running scanner waits for the handover reply
+    #[serde(with = "humantime_serde")]
+    pub release_timeout: Duration,
+}
+
+impl Default for ServiceConfig {
+    fn default() -> Self {
+        Self {
+            handover: false,
+            handover_listen: "127.0.0.1:9101".to_string(),
+            warmup_timeout: Duration::from_secs(30),
+            release_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Whatever supervises this process: systemd through $NOTIFY_SOCKET, the
+/// Windows service control manager when run as a service, or nothing
+pub struct Supervisor {
+    systemd: Arc<Notifier>,
+    #[cfg(windows)]
+    scm: bool,
+}
+
+impl Supervisor {
+    /// `windows_service` connects to the SCM, for `--windows-service`
+    pub fn connect(windows_service: bool) -> Self {
+        #[cfg(windows)]
+        if windows_service {
+            windows::start();
+        }
+        #[cfg(not(windows))]
+        if windows_service {
+            warn!("--windows-service only applies on Windows; ignoring it");
+        }
+        let systemd = Arc::new(Notifier::from_env());
+        if let Some(every) = systemd.watchdog_interval() {
+            tokio::spawn(run_watchdog(systemd.clone(), every));
+        }
+        Self {
+            systemd,
+            #[cfg(windows)]
+            scm: windows_service,
+        }
+    }
+    
+    /// The scanner is streaming and alerting
+    pub fn ready(&self, status: &str) {
+        self.systemd.ready(status);
+        #[cfg(windows)]
+        if self.scm {
+            windows::report(windows_service::service::ServiceState::Running);
+        }
+    }
+    
+    /// This process took over from the unit's previous main process
+    pub fn took_over(&self, status: &str) {
+        self.systemd.main_pid(status);
+    }
+    
+    pub fn stopping(&self) {
+        self.systemd.stopping();
+        #[cfg(windows)]
+        if self.scm {
+            windows::report(windows_service::service::ServiceState::StopPending);
+        }
+    }
+    
+    pub fn stopped(&self) {
+        #[cfg(windows)]
+        if self.scm {
+            windows::report(windows_service::service::ServiceState::Stopped);
+        }
+    }
+}
+
+/// Ping the systemd watchdog while the runtime keeps scheduling tasks; a
+/// wedged runtime misses pings and systemd restarts the unit
+async fn run_watchdog(systemd: Arc<Notifier>, every: Duration) {
+    let mut interval = time::interval(every);
+    loop {
+        interval.tick().await;
+        systemd.watchdog();
+    }
+}
+
+/// Resolves on Ctrl+C, SIGTERM (how systemd stops a unit) or a stop from the
+/// Windows SCM
+pub async fn stop_requested() {
+    #[cfg(unix)]
+    let terminate = async {
+        match signal::unix::signal(signal::unix::SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(e) => {
+                warn!("Shutdown on SIGTERM unavailable: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+    #[cfg(windows)]
+    let scm = windows::stop_requested();
+    #[cfg(not(windows))]
+    let scm = std::future::pending::<()>();
+    
+    tokio::select! {
+        _ = signal::ctrl_c() => info!("Received shutdown signal"),
+        _ = terminate => info!("Received SIGTERM"),
+        _ = scm => info!("Service stop requested"),
+    }
+}
+
+/// SIGHUP, the conventional reload request; Windows has none, so there it
+/// never arrives
+pub struct Hangup {
+    #[cfg(unix)]
+    signal: signal::unix::Signal,
+}
+
+impl Hangup {
+    pub fn new() -> io::Result<Self> {
+        Ok(Self {
+            #[cfg(unix)]
+            signal: signal::unix::signal(signal::unix::SignalKind::hangup())?,
+        })
+    }
+    
+    pub async fn recv(&mut self) {
+        #[cfg(unix)]
+        self.signal.recv().await;
+        #[cfg(not(unix))]
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Start a successor with this process's arguments on every SIGUSR2; it
+/// takes over through the handover address once its feeds are up
+#[cfg(unix)]
+pub async fn run_restart_signal() {
+    let mut restart = match signal::unix::signal(signal::unix::SignalKind::user_defined2()) {
+        Ok(restart) => restart,
+        Err(e) => {
+            warn!("Restart on SIGUSR2 unavailable: {}", e);
+            return;
+        }
+    };
+    loop {
+        restart.recv().await;
+        let spawned = std::env::current_exe().and_then(|exe| {
+            tokio::process::Command::new(exe)
+                .args(std::env::args_os().skip(1))
+                .spawn()
+        });
+        match spawned {
+            Ok(mut child) => {
+                info!("Received SIGUSR2, started successor {:?} to take over", child.id());
+                tokio::spawn(async move {
+                    if let Ok(status) = child.wait().await {
+                        warn!("Successor exited before taking over: {}", status);
+                    }
+                });
+            }
+            Err(e) => warn!("Received SIGUSR2 but couldn't start a successor: {}", e),
+        }
+    }
+}
+
+/// One line of the handover link (newline-delimited JSON)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum HandoverMessage {
+    /// Successor: my feeds are up, stop alerting
+    Release { pid: u32 },
+    /// Running scanner: stopped alerting and closed the dashboard; the legs
+    /// still in flight
+    Released(OrderHandoff),
+}
+
+async fn send(stream: &mut TcpStream, message: &HandoverMessage) -> io::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    stream.write_all(&line).await
+}
+
+async fn receive(reader: &mut BufReader<TcpStream>) -> io::Result<HandoverMessage> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "handover link closed"));
+    }
+    serde_json::from_str(&line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// The running scanner's side: waits on `handover_listen` for a successor
+pub struct HandoverListener {
+    listener: TcpListener,
+}
+
+impl HandoverListener {
+    pub async fn bind(addr: &str) -> io::Result<Self> {
+        let handover = Self {
+            listener: TcpListener::bind(addr).await?,
+        };
+        info!("Handover listening on {}", handover.local_addr()?);
+        Ok(handover)
+    }
+    
+    pub fn local_addr(&self) -> io::Result<std::net::SocketAddr> {
+        self.listener.local_addr()
+    }
+    
+    /// The next connection that asks to take over; others are dropped.
+    /// Closes the listener so the successor can bind the address
+    pub async fn successor(self) -> Successor {
+        loop {
+            let (stream, peer) = match self.listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    warn!("Handover accept failed: {}", e);
+                    continue;
+                }
+            };
+            let mut reader = BufReader::new(stream);
+            match time::timeout(HANDOVER_REQUEST_TIMEOUT, receive(&mut reader)).await {
+                Ok(Ok(HandoverMessage::Release { pid })) => return Successor { pid, reader },
+                Ok(Ok(message)) => debug!("Unexpected handover message from {}: {:?}", peer, message),
+                Ok(Err(e)) => debug!("Handover connection from {} failed: {}", peer, e),
+                Err(_) => debug!("Handover connection from {} asked nothing", peer),
+            }
+        }
+    }
+}
+
+/// A new scanner whose feeds are up, waiting for this one to let go
+pub struct Successor {
+    pid: u32,
+    reader: BufReader<TcpStream>,
+}
+
+impl Successor {
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+    
+    /// Hand over the in-flight legs; call once alerting has stopped
+    pub async fn release(mut self, handoff: OrderHandoff) -> io::Result<()> {
+        send(self.reader.get_mut(), &HandoverMessage::Released(handoff)).await
+    }
+}
+
+/// The new scanner's side: the running scanner it takes over from
+pub struct Predecessor {
+    addr: String,
+    reader: BufReader<TcpStream>,
+}
+
+impl Predecessor {
+    /// The scanner listening on `addr`, if one is running
+    pub async fn find(addr: &str) -> Option<Self> {
+        match TcpStream::connect(addr).await {
+            Ok(stream) => Some(Self {
+                addr: addr.to_string(),
+                reader: BufReader::new(stream),
+            }),
+            Err(e) => {
+                debug!("No scanner to take over from on {}: {}", addr, e);
+                None
+            }
+        }
+    }
+    
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+    
+    /// Ask it to stop alerting; returns the in-flight legs it hands over
+    pub async fn release(mut self, timeout: Duration) -> io::Result<OrderHandoff> {
+        send(self.reader.get_mut(), &HandoverMessage::Release { pid: std::process::id() }).await?;
+        match time::timeout(timeout, receive(&mut self.reader)).await {
+            Ok(Ok(HandoverMessage::Released(handoff))) => Ok(handoff),
+            Ok(Ok(message)) => Err(io::Error::new(io::ErrorKind::InvalidData, format!("expected released, got {:?}", message))),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(io::Error::new(io::ErrorKind::TimedOut, format!("no reply from {} in {:?}", self.addr, timeout))),
+        }
+    }
+}
+
+/// Wait until every symbol of `exchanges` is confirmed or rejected, or
+/// `timeout` passes; returns whether they all settled
+pub async fn feeds_settled(subscriptions: &SubscriptionTracker, exchanges: &[String], timeout: Duration) -> bool {
+    let settled = || {
+        exchanges.iter().all(|exchange| {
+            subscriptions.snapshot(exchange).is_some_and(|statuses| {
+                statuses
+                    .iter()
+                    .all(|status| matches!(status.state, SubscriptionState::Confirmed | SubscriptionState::Rejected))
+            })
+        })
+    };
+    let deadline = time::Instant::now() + timeout;
+    while !settled() {
+        if time::Instant::now() >= deadline {
+            return false;
+        }
+        time::sleep(Duration::from_millis(100)).await;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchange::NormalizedEvent;
+    
+    #[tokio::test]
+    async fn test_successor_takes_over_in_flight_legs() {
+        let tracker = SubscriptionTracker::new(Duration::from_secs(5));
+        let exchanges = vec!["binance".to_string()];
+        assert!(!feeds_settled(&tracker, &exchanges, Duration::from_millis(50)).await);
+        let id = tracker.request("binance", &["BTC/USDT".to_string()]);
+        tracker.handle_event("binance", &NormalizedEvent::SubscriptionAck { request_id: Some(id), symbols: vec![] });
+        assert!(feeds_settled(&tracker, &exchanges, Duration::from_millis(50)).await);
+        
+        let listener = HandoverListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let handoff = OrderHandoff { next_id: 42, legs: Vec::new() };
+        let expected = handoff.clone();
+        let running = tokio::spawn(async move {
+            let successor = listener.successor().await;
+            assert_eq!(successor.pid(), std::process::id());
+            successor.release(handoff).await.unwrap();
+        });
+        
+        // A stray connection doesn't end the wait
+        drop(TcpStream::connect(&addr).await.unwrap());
+        let predecessor = Predecessor::find(&addr).await.unwrap();
+        assert_eq!(predecessor.release(Duration::from_secs(5)).await.unwrap(), expected);
+        running.await.unwrap();
+        
+        // The listener closed with the handover, so nothing is left to take over from
+        assert!(Predecessor::find(&addr).await.is_none());
+    }
+}
@@ -0,0 +1,132 @@
+// service/systemd.rs - sd_notify over $NOTIFY_SOCKET: readiness, watchdog pings and handing over the main PID
+#[cfg(unix)]
+use std::io;
+use std::time::Duration;
+#[cfg(target_os = "linux")]
+use std::os::linux::net::SocketAddrExt;
+#[cfg(unix)]
+use std::os::unix::net::{SocketAddr, UnixDatagram};
+use tracing::debug;
+#[cfg(unix)]
+use tracing::warn;
+
+/// State messages to systemd for a `Type=notify` unit; every call is a no-op
+/// when the process wasn't started by one
+pub struct Notifier {
+    #[cfg(unix)]
+    socket: Option<(UnixDatagram, SocketAddr)>,
+    watchdog: Option<Duration>,
+}
+
+impl Notifier {
+    /// From the environment systemd sets: NOTIFY_SOCKET, and WATCHDOG_USEC
+    /// with WATCHDOG_PID for units with `WatchdogSec=`
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok();
+        let watchdog = watchdog_interval(var("WATCHDOG_USEC").as_deref(), var("WATCHDOG_PID").as_deref(), std::process::id());
+        Self::new(var("NOTIFY_SOCKET").as_deref(), watchdog)
+    }
+    
+    pub fn new(notify_socket: Option<&str>, watchdog: Option<Duration>) -> Self {
+        #[cfg(unix)]
+        let socket = notify_socket.and_then(|path| match connect(path) {
+            Ok(socket) => Some(socket),
+            Err(e) => {
+                warn!("NOTIFY_SOCKET {} unusable, not notifying systemd: {}", path, e);
+                None
+            }
+        });
+        #[cfg(not(unix))]
+        let _ = notify_socket;
+        Self {
+            #[cfg(unix)]
+            socket,
+            watchdog,
+        }
+    }
+    
+    /// How often to ping: half of `WatchdogSec=`, as sd_watchdog_enabled advises
+    pub fn watchdog_interval(&self) -> Option<Duration> {
+        self.watchdog
+    }
+    
+    /// Send newline-separated `KEY=VALUE` assignments
+    pub fn notify(&self, state: &str) {
+        #[cfg(unix)]
+        if let Some((socket, addr)) = &self.socket {
+            if let Err(e) = socket.send_to_addr(state.as_bytes(), addr) {
+                debug!("sd_notify {:?} failed: {}", state, e);
+            }
+        }
+        #[cfg(not(unix))]
+        debug!("No systemd on this platform; dropping {:?}", state);
+    }
+    
+    pub fn ready(&self, status: &str) {
+        self.notify(&format!("READY=1\nSTATUS={}", status));
+    }
+    
+    /// This process replaces the unit's main process, which is about to exit;
+    /// the unit needs `NotifyAccess=all` to accept it from a new process
+    pub fn main_pid(&self, status: &str) {
+        self.notify(&format!("MAINPID={}\nREADY=1\nSTATUS={}", std::process::id(), status));
+    }
+    
+    pub fn stopping(&self) {
+        self.notify("STOPPING=1");
+    }
+    
+    pub fn watchdog(&self) {
+        self.notify("WATCHDOG=1");
+    }
+}
+
+/// Paths name a socket file; a leading '@' names one in Linux's abstract namespace
+#[cfg(unix)]
+fn connect(path: &str) -> io::Result<(UnixDatagram, SocketAddr)> {
+    let addr = match path.strip_prefix('@') {
+        #[cfg(target_os = "linux")]
+        Some(name) => SocketAddr::from_abstract_name(name)?,
+        #[cfg(not(target_os = "linux"))]
+        Some(_) => return Err(io::Error::new(io::ErrorKind::Unsupported, "abstract sockets are Linux only")),
+        None => SocketAddr::from_pathname(path)?,
+    };
+    Ok((UnixDatagram::unbound()?, addr))
+}
+
+/// Ping interval for WATCHDOG_USEC, unless WATCHDOG_PID names another process
+pub fn watchdog_interval(usec: Option<&str>, pid: Option<&str>, own_pid: u32) -> Option<Duration> {
+    if pid.is_some_and(|pid| pid.parse() != Ok(own_pid)) {
+        return None;
+    }
+    let usec: u64 = usec?.parse().ok().filter(|&usec| usec > 0)?;
+    Some(Duration::from_micros(usec) / 2)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_notifications_reach_the_socket() {
+        let path = std::env::temp_dir().join(format!("sd-notify-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let systemd = UnixDatagram::bind(&path).unwrap();
+        
+        let notifier = Notifier::new(path.to_str(), None);
+        notifier.ready("scanning 2 exchanges");
+        notifier.watchdog();
+        let mut buf = [0u8; 256];
+        let len = systemd.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"READY=1\nSTATUS=scanning 2 exchanges");
+        let len = systemd.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"WATCHDOG=1");
+        let _ = std::fs::remove_file(&path);
+        
+        assert_eq!(watchdog_interval(Some("30000000"), None, 7), Some(Duration::from_secs(15)));
+        assert_eq!(watchdog_interval(Some("30000000"), Some("7"), 7), Some(Duration::from_secs(15)));
+        assert_eq!(watchdog_interval(Some("30000000"), Some("8"), 7), None);
+        assert_eq!(watchdog_interval(Some("0"), None, 7), None);
+        assert_eq!(watchdog_interval(None, None, 7), None);
+    }
+}
@@ -0,0 +1,105 @@
+// service/windows.rs - Running under the Windows service control manager
+use std::ffi::OsString;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{error, info};
+use windows_service::service::{
+    ServiceControl, ServiceControlAccept, ServiceExitCode, ServiceState, ServiceStatus, ServiceType,
+};
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult, ServiceStatusHandle};
+use windows_service::{define_windows_service, service_dispatcher};
+
+/// Name the service is installed under, e.g. `sc.exe create arbitrage-scanner`
+pub const SERVICE_NAME: &str = "arbitrage-scanner";
+
+/// Shutdown gets this long before the SCM considers the service hung
+const STOP_WAIT_HINT: Duration = Duration::from_secs(30);
+
+struct Control {
+    stop: watch::Sender<bool>,
+    status: Mutex<Option<ServiceStatusHandle>>,
+}
+
+static CONTROL: OnceLock<Control> = OnceLock::new();
+
+define_windows_service!(ffi_service_main, service_main);
+
+/// Called by the SCM on the dispatcher thread: register for stop requests
+/// and report that the scanner is starting
+fn service_main(_arguments: Vec<OsString>) {
+    let control = CONTROL.get().expect("set before the dispatcher starts");
+    let handler = move |event| match event {
+        ServiceControl::Stop | ServiceControl::Shutdown | ServiceControl::Preshutdown => {
+            control.stop.send_replace(true);
+            ServiceControlHandlerResult::NoError
+        }
+        ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+        _ => ServiceControlHandlerResult::NotImplemented,
+    };
+    match service_control_handler::register(SERVICE_NAME, handler) {
+        Ok(handle) => {
+            *control.status.lock().unwrap() = Some(handle);
+            report(ServiceState::StartPending);
+        }
+        Err(e) => {
+            error!("Failed to register with the service control manager: {}", e);
+            control.stop.send_replace(true);
+        }
+    }
+}
+
+/// Connect to the SCM on a dispatcher thread. The scanner keeps running on
+/// its runtime; a stop request from the SCM shows up in `stop_requested`
+pub fn start() {
+    let (stop, _) = watch::channel(false);
+    if CONTROL.set(Control { stop, status: Mutex::new(None) }).is_err() {
+        return;
+    }
+    std::thread::spawn(|| {
+        if let Err(e) = service_dispatcher::start(SERVICE_NAME, ffi_service_main) {
+            error!("Not running as a Windows service ({}); stopping", e);
+            if let Some(control) = CONTROL.get() {
+                control.stop.send_replace(true);
+            }
+        }
+    });
+    info!("Connecting to the Windows service control manager as {}", SERVICE_NAME);
+}
+
+/// Resolves once the SCM asks the service to stop; never if `start` wasn't called
+pub async fn stop_requested() {
+    let Some(control) = CONTROL.get() else {
+        return std::future::pending().await;
+    };
+    let mut stop = control.stop.subscribe();
+    let _ = stop.wait_for(|stop| *stop).await;
+}
+
+/// Tell the SCM what state the scanner is in
+pub fn report(state: ServiceState) {
+    let Some(control) = CONTROL.get() else {
+        return;
+    };
+    let Some(handle) = *control.status.lock().unwrap() else {
+        return;
+    };
+    let status = ServiceStatus {
+        service_type: ServiceType::OWN_PROCESS,
+        current_state: state,
+        controls_accepted: match state {
+            ServiceState::Running => ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+            _ => ServiceControlAccept::empty(),
+        },
+        exit_code: ServiceExitCode::Win32(0),
+        checkpoint: 0,
+        wait_hint: match state {
+            ServiceState::StartPending | ServiceState::StopPending => STOP_WAIT_HINT,
+            _ => Duration::ZERO,
+        },
+        process_id: None,
+    };
+    if let Err(e) = handle.set_service_status(status) {
+        error!("Failed to report {:?} to the service control manager: {}", state, e);
+    }
+}
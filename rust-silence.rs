@@ -0,0 +1,243 @@
+// silence.rs - Silence watches: alert when an exchange stops ticking or a detector stops finding opportunities
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::clock::SharedClock;
+
+/// Detectors a watch can follow
+pub const DETECTORS: [&str; 3] = [
+    "cycles",     // Tradable opportunities, after clustering and suppression
+    "structural", // Spreads that persisted long enough to be blacklisted
+    "statarb",    // Mean-reversion signals on z-scored spreads
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SilenceSource {
+    Exchange, // Ticks the engine applied from one venue
+    Detector, // Opportunities one detector reported
+}
+
+impl SilenceSource {
+    pub fn events(self) -> &'static str {
+        match self {
+            SilenceSource::Exchange => "ticks",
+            SilenceSource::Detector => "opportunities",
+        }
+    }
+}
+
+/// Something that should never go quiet for longer than `after`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SilenceWatch {
+    pub source: SilenceSource,
+    /// Exchange name, or one of `DETECTORS`
+    pub name: String,
+    #[serde(with = "humantime_serde")]
+    pub after: Duration,
+}
+
+/// Off while `watches` is empty
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SilenceConfig {
+    pub watches: Vec<SilenceWatch>,
+    #[serde(with = "humantime_serde")]
+    pub check_interval: Duration,
+}
+
+impl Default for SilenceConfig {
+    fn default() -> Self {
+        Self {
+            watches: Vec::new(),
+            check_interval: Duration::from_secs(10),
+        }
+    }
+}
+
+/// A watch that just went quiet; raised once until its next event
+#[derive(Debug, Clone, PartialEq)]
+pub struct Silence {
+    pub source: SilenceSource,
+    pub name: String,
+    pub silent_for: Duration,
+    pub after: Duration,
+}
+
+impl fmt::Display for Silence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "no {} {} for {}s (expected within {}s)",
+            self.name,
+            self.source.events(),
+            self.silent_for.as_secs(),
+            self.after.as_secs()
+        )
+    }
+}
+
+/// One watch's event rate since startup, for `/api/stats/silence`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SilenceStatus {
+    pub source: SilenceSource,
+    pub name: String,
+    pub after_secs: u64,
+    pub events: u64,
+    pub events_per_hour: f64,
+    /// Since the last event, or since startup if there was none
+    pub silent_secs: u64,
+    pub silent: bool,
+}
+
+struct WatchState {
+    watch: SilenceWatch,
+    events: u64,
+    last_event: Option<Instant>,
+    alerted: bool,
+}
+
+/// Counts ticks and opportunities per watch and reports the ones that have
+/// been quiet for longer than they're allowed. A watch that has seen nothing
+/// since startup counts as quiet since startup
+pub struct SilenceMonitor {
+    check_interval: Duration,
+    clock: SharedClock,
+    started: Instant,
+    watches: Mutex<Vec<WatchState>>,
+}
+
+impl SilenceMonitor {
+    pub fn new(config: &SilenceConfig, clock: SharedClock) -> Self {
+        let watches = config
+            .watches
+            .iter()
+            .map(|watch| WatchState {
+                watch: watch.clone(),
+                events: 0,
+                last_event: None,
+                alerted: false,
+            })
+            .collect();
+        Self {
+            check_interval: config.check_interval,
+            started: clock.now(),
+            clock,
+            watches: Mutex::new(watches),
+        }
+    }
+    
+    pub fn is_enabled(&self) -> bool {
+        !self.watches.lock().unwrap().is_empty()
+    }
+    
+    pub fn check_interval(&self) -> Duration {
+        self.check_interval
+    }
+    
+    pub fn observe(&self, source: SilenceSource, name: &str) {
+        let now = self.clock.now();
+        let mut watches = self.watches.lock().unwrap();
+        for state in watches.iter_mut().filter(|state| state.watch.source == source && state.watch.name == name) {
+            if state.alerted {
+                let quiet = now.saturating_duration_since(state.last_event.unwrap_or(self.started));
+                info!("{} {} resumed after {}s of silence", name, source.events(), quiet.as_secs());
+                state.alerted = false;
+            }
+            state.events += 1;
+            state.last_event = Some(now);
+        }
+    }
+    
+    /// Watches that went quiet since the last check
+    pub fn check(&self) -> Vec<Silence> {
+        let now = self.clock.now();
+        let mut watches = self.watches.lock().unwrap();
+        let mut silences = Vec::new();
+        for state in watches.iter_mut().filter(|state| !state.alerted) {
+            let silent_for = now.saturating_duration_since(state.last_event.unwrap_or(self.started));
+            if silent_for >= state.watch.after {
+                state.alerted = true;
+                silences.push(Silence {
+                    source: state.watch.source,
+                    name: state.watch.name.clone(),
+                    silent_for,
+                    after: state.watch.after,
+                });
+            }
+        }
+        silences
+    }
+    
+    pub fn report(&self) -> Vec<SilenceStatus> {
+        let now = self.clock.now();
+        let hours = now.saturating_duration_since(self.started).as_secs_f64() / 3600.0;
+        self.watches
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|state| {
+                let silent_for = now.saturating_duration_since(state.last_event.unwrap_or(self.started));
+                SilenceStatus {
+                    source: state.watch.source,
+                    name: state.watch.name.clone(),
+                    after_secs: state.watch.after.as_secs(),
+                    events: state.events,
+                    events_per_hour: if hours > 0.0 { state.events as f64 / hours } else { 0.0 },
+                    silent_secs: silent_for.as_secs(),
+                    silent: silent_for >= state.watch.after,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::clock::ManualClock;
+    
+    fn watch(source: SilenceSource, name: &str, after_secs: u64) -> SilenceWatch {
+        SilenceWatch {
+            source,
+            name: name.to_string(),
+            after: Duration::from_secs(after_secs),
+        }
+    }
+    
+    #[test]
+    fn test_quiet_watches_alert_once_until_they_resume() {
+        let clock = Arc::new(ManualClock::new(1_700_000_000_000));
+        let config = SilenceConfig {
+            watches: vec![watch(SilenceSource::Exchange, "binance", 60), watch(SilenceSource::Detector, "cycles", 3600)],
+            ..SilenceConfig::default()
+        };
+        let monitor = SilenceMonitor::new(&config, clock.clone());
+        
+        clock.advance(Duration::from_secs(50));
+        monitor.observe(SilenceSource::Exchange, "binance");
+        monitor.observe(SilenceSource::Exchange, "kraken"); // Not watched
+        monitor.observe(SilenceSource::Detector, "binance"); // Same name, other source
+        clock.advance(Duration::from_secs(59));
+        assert!(monitor.check().is_empty());
+        
+        clock.advance(Duration::from_secs(1));
+        let silences = monitor.check();
+        assert_eq!(silences.len(), 1);
+        assert_eq!(silences[0].to_string(), "no binance ticks for 60s (expected within 60s)");
+        assert!(monitor.check().is_empty(), "already raised");
+        
+        // Nothing from the detector since startup counts from startup
+        clock.advance(Duration::from_secs(3600));
+        assert_eq!(monitor.check()[0].name, "cycles");
+        
+        monitor.observe(SilenceSource::Exchange, "binance");
+        let binance = &monitor.report()[0];
+        assert_eq!((binance.events, binance.silent_secs, binance.silent), (2, 0, false));
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(monitor.check()[0].name, "binance", "re-armed by the tick");
+    }
+}
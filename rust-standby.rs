@@ -0,0 +1,307 @@
+// standby.rs - Warm standby: tick relay, primary heartbeat and execution state handoff
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::time;
+use tracing::{debug, info, warn};
+
+use crate::arbitrage::{ArbitrageEngine, PriceUpdate};
+use crate::execution::orders::{OrderHandoff, OrderStore};
+use crate::outbox::now_ms;
+
+/// Messages queued per standby before a slow one starts missing ticks; order
+/// state is resent on every heartbeat, so nothing else is lost for good
+const RELAY_CAPACITY: usize = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StandbyRole {
+    Solo,    // No standby
+    Primary, // Alerts and executes, and serves a standby on `listen`
+    Standby, // Follows the primary on `primary` and takes over when it goes quiet
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandbyConfig {
+    pub role: StandbyRole,
+    /// Primary: address the standby link listens on
+    pub listen: String,
+    /// Standby: the primary's `listen` address
+    pub primary: String,
+    #[serde(with = "humantime_serde")]
+    pub heartbeat_interval: Duration,
+    /// Standby: take over once the primary has sent no heartbeat for this long
+    #[serde(with = "humantime_serde")]
+    pub takeover_after: Duration,
+    /// Standby: stream the exchanges itself rather than the primary's relayed ticks
+    pub direct_feeds: bool,
+}
+
+impl Default for StandbyConfig {
+    fn default() -> Self {
+        Self {
+            role: StandbyRole::Solo,
+            listen: "0.0.0.0:9100".to_string(),
+            primary: "127.0.0.1:9100".to_string(),
+            heartbeat_interval: Duration::from_millis(500),
+            takeover_after: Duration::from_secs(3),
+            direct_feeds: false,
+        }
+    }
+}
+
+/// One line of the primary-to-standby link (newline-delimited JSON)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LinkMessage {
+    Heartbeat { sent_at_ms: u64 },
+    Ticks { ticks: Vec<PriceUpdate> },
+    /// In-flight execution state, resent with every heartbeat
+    Orders(OrderHandoff),
+}
+
+/// Whether this process alerts and executes. Solo scanners and primaries are
+/// active from the start; a standby becomes active when it takes over, and a
+/// scanner restarting by handover once its predecessor lets go
+pub struct Failover {
+    active: AtomicBool,
+}
+
+impl Failover {
+    pub fn new(role: StandbyRole) -> Self {
+        Self {
+            active: AtomicBool::new(role != StandbyRole::Standby),
+        }
+    }
+    
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+    
+    pub fn promote(&self) {
+        self.active.store(true, Ordering::SeqCst);
+    }
+    
+    /// Stop alerting, for a scanner handing over to its successor
+    pub fn demote(&self) {
+        self.active.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Why a standby stopped following
+#[derive(Debug, Clone, PartialEq)]
+pub struct Takeover {
+    /// Since the last heartbeat, or since the standby started if it never got one
+    pub silent_for: Duration,
+    pub in_flight: usize,
+}
+
+/// The primary's side of the link: every connected standby gets the relayed
+/// ticks, a heartbeat and the in-flight order legs
+pub struct StandbyLink {
+    sender: broadcast::Sender<LinkMessage>,
+}
+
+impl StandbyLink {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(RELAY_CAPACITY);
+        Self { sender }
+    }
+    
+    /// Forward ticks to connected standbys; a no-op while none is connected
+    pub fn relay(&self, ticks: Vec<PriceUpdate>) {
+        let _ = self.sender.send(LinkMessage::Ticks { ticks });
+    }
+    
+    /// Accept standbys on `config.listen` and heartbeat every `heartbeat_interval`
+    pub async fn serve(self: Arc<Self>, config: StandbyConfig, orders: Arc<OrderStore>) -> io::Result<()> {
+        let listener = TcpListener::bind(&config.listen).await?;
+        info!("Standby link listening on {}", listener.local_addr()?);
+        self.serve_on(listener, config.heartbeat_interval, orders).await
+    }
+    
+    async fn serve_on(self: Arc<Self>, listener: TcpListener, heartbeat_interval: Duration, orders: Arc<OrderStore>) -> io::Result<()> {
+        let mut heartbeat = time::interval(heartbeat_interval);
+        loop {
+            tokio::select! {
+                _ = heartbeat.tick() => {
+                    // Order state first, so a standby that sees the heartbeat has it
+                    let _ = self.sender.send(LinkMessage::Orders(orders.handoff()));
+                    let _ = self.sender.send(LinkMessage::Heartbeat { sent_at_ms: now_ms() });
+                }
+                accepted = listener.accept() => {
+                    let (stream, peer) = accepted?;
+                    info!("Standby connected from {}", peer);
+                    tokio::spawn(stream_to(stream, self.sender.subscribe(), peer.to_string()));
+                }
+            }
+        }
+    }
+}
+
+async fn stream_to(mut stream: TcpStream, mut messages: broadcast::Receiver<LinkMessage>, peer: String) {
+    loop {
+        let message = match messages.recv().await {
+            Ok(message) => message,
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                warn!("Standby {} fell behind; skipped {} messages", peer, missed);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        let mut line = match serde_json::to_vec(&message) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Failed to encode standby message: {}", e);
+                continue;
+            }
+        };
+        line.push(b'\n');
+        if let Err(e) = stream.write_all(&line).await {
+            info!("Standby {} disconnected: {}", peer, e);
+            return;
+        }
+    }
+}
+
+/// Follow the primary at `config.primary`: apply its ticks (unless the standby
+/// has `direct_feeds`) and adopt its in-flight order legs. Returns once no
+/// heartbeat has arrived for `takeover_after`, with `failover` promoted
+pub async fn follow(
+    config: &StandbyConfig,
+    failover: &Failover,
+    engine: &ArbitrageEngine,
+    orders: &OrderStore,
+) -> Takeover {
+    let mut last_heartbeat = Instant::now();
+    let remaining = |last: Instant| config.takeover_after.saturating_sub(last.elapsed());
+    
+    while !remaining(last_heartbeat).is_zero() {
+        let stream = match time::timeout(remaining(last_heartbeat), TcpStream::connect(&config.primary)).await {
+            Ok(Ok(stream)) => stream,
+            Ok(Err(e)) => {
+                debug!("Primary {} unreachable: {}", config.primary, e);
+                time::sleep(config.heartbeat_interval.min(remaining(last_heartbeat))).await;
+                continue;
+            }
+            Err(_) => break,
+        };
+        info!("Following primary {}", config.primary);
+        
+        let mut lines = BufReader::new(stream).lines();
+        loop {
+            let line = match time::timeout(remaining(last_heartbeat), lines.next_line()).await {
+                Ok(Ok(Some(line))) => line,
+                Ok(Ok(None)) => {
+                    warn!("Primary {} closed the standby link", config.primary);
+                    break;
+                }
+                Ok(Err(e)) => {
+                    warn!("Standby link to {} failed: {}", config.primary, e);
+                    break;
+                }
+                Err(_) => break, // Silent past takeover_after
+            };
+            match serde_json::from_str(&line) {
+                Ok(LinkMessage::Heartbeat { .. }) => last_heartbeat = Instant::now(),
+                Ok(LinkMessage::Ticks { ticks }) => {
+                    if !config.direct_feeds {
+                        if let Err(e) = engine.update_prices(&ticks).await {
+                            warn!("Failed to apply relayed ticks: {}", e);
+                        }
+                    }
+                }
+                Ok(LinkMessage::Orders(handoff)) => orders.adopt(handoff),
+                Err(e) => warn!("Unreadable standby message from {}: {}", config.primary, e),
+            }
+        }
+    }
+    
+    failover.promote();
+    let takeover = Takeover {
+        silent_for: last_heartbeat.elapsed(),
+        in_flight: orders.in_flight().len(),
+    };
+    warn!(
+        "Primary {} silent for {:?}; taking over with {} in-flight legs",
+        config.primary, takeover.silent_for, takeover.in_flight
+    );
+    takeover
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrage::Config;
+    use crate::execution::orders::{NewOrder, OrderEvent};
+    use crate::execution::Side;
+    
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+    
+    #[tokio::test]
+    async fn test_standby_follows_then_takes_over_with_order_state() {
+        let primary_orders = Arc::new(OrderStore::open(temp_path("standby-primary")));
+        let id = primary_orders.create(
+            NewOrder {
+                opportunity_path: "USDT_binance -> BTC_binance -> ETH_binance".to_string(),
+                exchange: "binance".to_string(),
+                symbol: "BTC/USDT".to_string(),
+                side: Side::Buy,
+                quantity: 1.0,
+                limit_price: Some(50_000.0),
+            },
+            1,
+        );
+        primary_orders.apply(id, OrderEvent::Submitted, 2).unwrap();
+        
+        let link = Arc::new(StandbyLink::new());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = StandbyConfig {
+            role: StandbyRole::Standby,
+            primary: listener.local_addr().unwrap().to_string(),
+            heartbeat_interval: Duration::from_millis(20),
+            takeover_after: Duration::from_millis(300),
+            ..StandbyConfig::default()
+        };
+        let primary = tokio::spawn(link.clone().serve_on(listener, config.heartbeat_interval, primary_orders.clone()));
+        
+        let engine = ArbitrageEngine::new(Config::default());
+        engine.start().await;
+        let orders = OrderStore::open(temp_path("standby-follower"));
+        let failover = Failover::new(config.role);
+        assert!(!failover.is_active());
+        
+        let relay = async {
+            time::sleep(Duration::from_millis(100)).await;
+            link.relay(vec![PriceUpdate {
+                exchange: "binance".to_string(),
+                symbol: "BTC/USDT".to_string(),
+                bid: 50_000.0,
+                ask: 50_001.0,
+                volume: 1.0,
+            }]);
+            time::sleep(Duration::from_millis(100)).await;
+            primary.abort(); // The primary dies
+        };
+        let started = Instant::now();
+        let (takeover, ()) = tokio::join!(follow(&config, &failover, &engine, &orders), relay);
+        
+        assert!(failover.is_active());
+        assert!(started.elapsed() >= Duration::from_millis(450), "followed until the heartbeat stopped");
+        assert!(takeover.silent_for >= config.takeover_after);
+        assert_eq!(takeover.in_flight, 1);
+        assert_eq!(orders.handoff(), primary_orders.handoff());
+        assert!(engine.get_market_snapshot("BTC/USDT").await.is_some(), "relayed tick reached the standby's engine");
+        
+        engine.stop().await;
+    }
+}
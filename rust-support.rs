@@ -0,0 +1,231 @@
+// support.rs - Support bundles: recent logs, stats, redacted config and connection states in one zip
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{Cursor, Write};
+use std::sync::{Arc, Mutex};
+use serde::Deserialize;
+use serde_json::json;
+use serde_yaml::Value;
+use zip::write::SimpleFileOptions;
+use zip::result::ZipResult;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::arbitrage::ArbitrageEngine;
+use crate::arbitrage::rollups::{RollupQuery, RETENTION_MINUTES};
+use crate::display::DisplayFormat;
+use crate::exchange::ExchangeManager;
+
+/// Log lines kept for bundles
+pub const LOG_LINES: usize = 2000;
+pub const DEFAULT_OPPORTUNITIES: usize = 100;
+const REDACTED: &str = "<redacted>";
+
+/// The last `capacity` log lines, without terminal colours
+pub struct LogTail {
+    capacity: usize,
+    lines: Mutex<VecDeque<String>>,
+}
+
+impl LogTail {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lines: Mutex::new(VecDeque::new()),
+        }
+    }
+    
+    fn push(&self, output: &[u8]) {
+        let text = strip_ansi(&String::from_utf8_lossy(output));
+        let mut lines = self.lines.lock().unwrap();
+        for line in text.lines().filter(|line| !line.is_empty()) {
+            if lines.len() == self.capacity {
+                lines.pop_front();
+            }
+            lines.push_back(line.to_string());
+        }
+    }
+    
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap().iter().cloned().collect()
+    }
+    
+    /// A log writer for `tracing_subscriber`: stdout as before, plus the tail
+    pub fn writer(self: &Arc<Self>) -> TailWriter {
+        TailWriter { tail: Arc::clone(self) }
+    }
+}
+
+pub struct TailWriter {
+    tail: Arc<LogTail>,
+}
+
+impl Write for TailWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        std::io::stdout().write_all(buf)?;
+        self.tail.push(buf);
+        Ok(buf.len())
+    }
+    
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::stdout().flush()
+    }
+}
+
+/// Drops SGR sequences (ESC [ ... m) the formatter adds on a terminal
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Whether a config field holds a credential: tokens, secrets, keys, and
+/// webhook and node URLs, which embed one
+fn is_secret(field: &str) -> bool {
+    field.ends_with("token")
+        || field.ends_with("secret")
+        || field.ends_with("_key")
+        || field.ends_with("_sid")
+        || field == "webhook_url"
+        || field == "rpc_url"
+        || field == "subgraph_url"
+}
+
+/// Replace every credential in a serialized config, at any depth
+fn redact(value: &mut Value) {
+    match value {
+        Value::Mapping(fields) => {
+            for (field, value) in fields.iter_mut() {
+                if field.as_str().is_some_and(is_secret) && !value.is_null() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Sequence(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BundleQuery {
+    /// How many of the most recent opportunities to include
+    pub opportunities: Option<usize>,
+}
+
+/// What a user attaches to a bug report, gathered from the running scanner
+pub struct SupportBundler {
+    engine: Arc<ArbitrageEngine>,
+    exchanges: Arc<ExchangeManager>,
+    logs: Arc<LogTail>,
+    display: Arc<DisplayFormat>,
+}
+
+impl SupportBundler {
+    pub fn new(
+        engine: Arc<ArbitrageEngine>,
+        exchanges: Arc<ExchangeManager>,
+        logs: Arc<LogTail>,
+        display: Arc<DisplayFormat>,
+    ) -> Self {
+        Self { engine, exchanges, logs, display }
+    }
+    
+    /// A zip of logs.txt, config.yaml (credentials redacted), stats.json,
+    /// stats-history.json (per-minute rollups), connections.json and
+    /// opportunities.json
+    pub async fn build(&self, query: &BundleQuery) -> ZipResult<Vec<u8>> {
+        let current = self.engine.current_config();
+        let mut config = serde_yaml::to_value(&current).unwrap_or(Value::Null);
+        redact(&mut config);
+        let history = self
+            .engine
+            .get_rollups(&RollupQuery { since_ms: None, limit: Some(RETENTION_MINUTES as usize) })
+            .await;
+        let tracker = self.exchanges.subscriptions();
+        let subscriptions: BTreeMap<&String, _> = current
+            .exchanges
+            .iter()
+            .filter_map(|exchange| Some((exchange, tracker.snapshot(exchange)?)))
+            .collect();
+        let connections = json!({
+            "connections": self.exchanges.connection_states(),
+            "subscriptions": subscriptions,
+        });
+        let limit = query.opportunities.unwrap_or(DEFAULT_OPPORTUNITIES);
+        let opportunities: Vec<_> = self
+            .engine
+            .get_recent_opportunities(limit)
+            .await
+            .iter()
+            .map(|opportunity| self.display.opportunity_json(opportunity))
+            .collect();
+        
+        let files = [
+            ("logs.txt", self.logs.lines().join("\n")),
+            ("config.yaml", serde_yaml::to_string(&config).unwrap_or_default()),
+            ("stats.json", pretty(&self.engine.get_performance_stats().await)),
+            ("stats-history.json", pretty(&history)),
+            ("connections.json", pretty(&connections)),
+            ("opportunities.json", pretty(&opportunities)),
+        ];
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for (name, contents) in files {
+            zip.start_file(name, options)?;
+            zip.write_all(contents.as_bytes())?;
+        }
+        Ok(zip.finish()?.into_inner())
+    }
+}
+
+fn pretty<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_redacts_credentials_and_keeps_the_rest() {
+        let mut config: Value = serde_yaml::from_str(
+            r#"
+exchanges: [binance, kraken]
+telegram_bot:
+  bot_token: "123:abc"
+  allowed_chats: [42]
+notifications:
+  ntfy: { server: "https://ntfy.sh", topic: scanner, token: tk_secret }
+  slack: { webhook_url: "https://hooks.slack.com/services/T0/B0/x", signing_secret: null }
+  pushover: { user_key: u1, app_token: a1 }
+"#,
+        )
+        .unwrap();
+        redact(&mut config);
+        let text = serde_yaml::to_string(&config).unwrap();
+        
+        for secret in ["123:abc", "tk_secret", "hooks.slack.com", "u1", "a1"] {
+            assert!(!text.contains(secret), "{} leaked into\n{}", secret, text);
+        }
+        assert_eq!(config["notifications"]["ntfy"]["topic"], Value::from("scanner"));
+        assert_eq!(config["notifications"]["slack"]["signing_secret"], Value::Null);
+        assert_eq!(config["exchanges"][1], Value::from("kraken"));
+    }
+    
+    #[test]
+    fn test_log_tail_keeps_the_last_lines_without_colours() {
+        let tail = Arc::new(LogTail::new(2));
+        let mut writer = tail.writer();
+        writer.write_all(b"\x1b[32m INFO\x1b[0m one\n").unwrap();
+        writer.write_all(b"two\nthree\n").unwrap();
+        assert_eq!(tail.lines(), vec!["two", "three"]);
+        assert_eq!(strip_ansi("\x1b[2m2026\x1b[0m \x1b[32m INFO\x1b[0m up"), "2026  INFO up");
+    }
+}
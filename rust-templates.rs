@@ -0,0 +1,267 @@
+// templates.rs - User-customizable alert message templates (Handlebars)
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::RwLock;
+use handlebars::Handlebars;
+use serde_json::{json, Value};
+use tracing::warn;
+
+use crate::arbitrage::quotes::CompositeDeviation;
+use crate::arbitrage::types::ArbitrageOpportunity;
+use crate::display::DisplayFormat;
+
+/// Template key used for channels without their own template
+pub const DEFAULT_CHANNEL: &str = "default";
+
+/// Path of the opportunity templates are checked against
+const SAMPLE_PATH: &str = "BTC_binance -> ETH_binance -> USDT_binance -> BTC_binance";
+
+pub const DEFAULT_TEMPLATE: &str =
+    "ARBITRAGE {{path}} | profit {{profit_display}} | volume {{max_volume_display}} | confidence {{confidence}} | {{exchanges_list}}";
+
+/// Built-in alert text by language, used for channels without their own template
+pub const LANGUAGE_TEMPLATES: &[(&str, &str)] = &[
+    ("en", DEFAULT_TEMPLATE),
+    (
+        "zh",
+        "套利机会 {{path}} | 利润 {{profit_display}} | 数量 {{max_volume_display}} | 置信度 {{confidence}} | {{exchanges_list}}",
+    ),
+    (
+        "es",
+        "ARBITRAJE {{path}} | beneficio {{profit_display}} | volumen {{max_volume_display}} | confianza {{confidence}} | {{exchanges_list}}",
+    ),
+];
+
+#[derive(Debug)]
+pub struct TemplateError {
+    pub setting: &'static str, // Config map the bad entry came from
+    pub channel: String,
+    pub reason: String,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}: {}", self.setting, self.channel, self.reason)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// Per-channel alert bodies. Templates see every opportunity field plus a few
+/// preformatted extras (`profit_display`, `gross_profit_display`, `max_volume_display`,
+/// `profit_percent`, `exchanges_list`, `age_ms`), its `kind` (cycle or basis), and each leg's venue against the composite
+/// quote (`composite`, `composite_summary`)
+pub struct AlertTemplates {
+    compiled: RwLock<Compiled>,
+}
+
+struct Compiled {
+    registry: Handlebars<'static>,
+    languages: HashMap<String, String>, // Channel -> language
+    display: DisplayFormat,
+}
+
+impl AlertTemplates {
+    /// Compile `templates` (channel -> template source) and resolve `languages`
+    /// (channel -> language). Unknown fields are rejected here by rendering a
+    /// sample, not when the first alert goes out
+    pub fn from_config(
+        templates: &HashMap<String, String>,
+        languages: &HashMap<String, String>,
+        display: &DisplayFormat,
+    ) -> Result<Self, TemplateError> {
+        Ok(Self {
+            compiled: RwLock::new(Compiled::new(templates, languages, display)?),
+        })
+    }
+    
+    /// Swap in recompiled templates on config reload; on error the current
+    /// ones stay in use
+    pub fn reload(
+        &self,
+        templates: &HashMap<String, String>,
+        languages: &HashMap<String, String>,
+        display: &DisplayFormat,
+    ) -> Result<(), TemplateError> {
+        let compiled = Compiled::new(templates, languages, display)?;
+        *self.compiled.write().unwrap() = compiled;
+        Ok(())
+    }
+    
+    /// Alert body for `channel`: its own template, else the built-in text in
+    /// its language, else the default template
+    pub fn render(&self, channel: &str, opportunity: &ArbitrageOpportunity, composite: &[CompositeDeviation]) -> String {
+        let compiled = self.compiled.read().unwrap();
+        let language_template = compiled.languages.get(channel).map(|lang| language_key(lang));
+        let name = if compiled.registry.has_template(channel) {
+            channel
+        } else {
+            language_template.as_deref().unwrap_or(DEFAULT_CHANNEL)
+        };
+        let data = context(opportunity, composite, &compiled.display);
+        
+        compiled.registry.render(name, &data).unwrap_or_else(|e| {
+            warn!("Alert template {:?} failed, using default: {}", name, e);
+            compiled.registry.render(DEFAULT_CHANNEL, &data).unwrap_or_default()
+        })
+    }
+}
+
+impl Compiled {
+    fn new(
+        templates: &HashMap<String, String>,
+        languages: &HashMap<String, String>,
+        display: &DisplayFormat,
+    ) -> Result<Self, TemplateError> {
+        let mut registry = Handlebars::new();
+        registry.set_strict_mode(true);
+        registry.register_escape_fn(handlebars::no_escape); // Chat bodies, not HTML
+        
+        for (channel, language) in languages {
+            if !LANGUAGE_TEMPLATES.iter().any(|(lang, _)| lang == language) {
+                let supported: Vec<&str> = LANGUAGE_TEMPLATES.iter().map(|(lang, _)| *lang).collect();
+                return Err(TemplateError {
+                    setting: "alert_languages",
+                    channel: channel.clone(),
+                    reason: format!("no {:?} alert text (supported: {})", language, supported.join(", ")),
+                });
+            }
+        }
+        
+        let language_names: Vec<(String, &str)> = LANGUAGE_TEMPLATES
+            .iter()
+            .map(|(lang, source)| (language_key(lang), *source))
+            .collect();
+        let mut sources: Vec<(&str, &str)> = vec![(DEFAULT_CHANNEL, DEFAULT_TEMPLATE)];
+        sources.extend(language_names.iter().map(|(name, source)| (name.as_str(), *source)));
+        sources.extend(templates.iter().map(|(channel, source)| (channel.as_str(), source.as_str())));
+        
+        let sample = context(&ArbitrageOpportunity::sample(SAMPLE_PATH, 0.0012), &[], display);
+        for (channel, source) in sources {
+            let error = |reason: String| TemplateError {
+                setting: "alert_templates",
+                channel: channel.to_string(),
+                reason,
+            };
+            registry
+                .register_template_string(channel, source)
+                .map_err(|e| error(e.to_string()))?;
+            registry.render(channel, &sample).map_err(|e| error(e.to_string()))?;
+        }
+        
+        Ok(Self {
+            registry,
+            languages: languages.clone(),
+            display: display.clone(),
+        })
+    }
+}
+
+/// Registry name of a built-in language template; can't clash with channel names
+fn language_key(language: &str) -> String {
+    format!("lang:{}", language)
+}
+
+/// Template variables for one opportunity
+fn context(opportunity: &ArbitrageOpportunity, composite: &[CompositeDeviation], display: &DisplayFormat) -> Value {
+    let mut data = display.opportunity_json(opportunity);
+    if let Value::Object(fields) = &mut data {
+        let summary: Vec<String> = composite
+            .iter()
+            .map(|leg| format!("{} on {} {:+.1} bps vs composite", leg.symbol, leg.exchange, leg.deviation_bps))
+            .collect();
+        fields.insert("composite".to_string(), json!(composite));
+        fields.insert("composite_summary".to_string(), json!(summary.join(", ")));
+        fields.insert("profit_percent".to_string(), json!(format!("{:.4}", opportunity.profit_percentage * 100.0)));
+        fields.insert("exchanges_list".to_string(), json!(opportunity.exchanges.join(", ")));
+        fields.insert("age_ms".to_string(), json!(opportunity.detected_at.elapsed().as_millis() as u64));
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn pairs(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+    
+    fn templates(templates: &[(&str, &str)]) -> Result<AlertTemplates, TemplateError> {
+        AlertTemplates::from_config(&pairs(templates), &HashMap::new(), &DisplayFormat::default())
+    }
+    
+    #[test]
+    fn test_channel_template_sees_opportunity_fields() {
+        let templates = templates(&[("telegram", "*{{path}}* {{profit_percent}}% ({{confidence}}) on {{exchanges_list}}")]).unwrap();
+        
+        let body = templates.render("telegram", &ArbitrageOpportunity::sample(SAMPLE_PATH, 0.0012), &[]);
+        assert_eq!(
+            body,
+            "*BTC_binance -> ETH_binance -> USDT_binance -> BTC_binance* 0.1200% (80) on binance"
+        );
+    }
+    
+    #[test]
+    fn test_composite_summary_names_each_legs_deviation() {
+        let templates = templates(&[("slack", "{{path}} [{{composite_summary}}]")]).unwrap();
+        let leg = |symbol: &str, deviation_bps| CompositeDeviation {
+            symbol: symbol.to_string(),
+            exchange: "binance".to_string(),
+            venue_mid: 1.0,
+            composite_mid: 1.0,
+            deviation_bps,
+        };
+        
+        let body = templates.render("slack", &ArbitrageOpportunity::sample(SAMPLE_PATH, 0.0012), &[leg("BTC/USDT", 12.34), leg("ETH/USDT", -0.5)]);
+        assert!(body.ends_with("[BTC/USDT on binance +12.3 bps vs composite, ETH/USDT on binance -0.5 bps vs composite]"));
+        assert!(templates.render("slack", &ArbitrageOpportunity::sample(SAMPLE_PATH, 0.0012), &[]).ends_with("[]"));
+    }
+    
+    #[test]
+    fn test_unknown_channel_uses_default_template() {
+        let templates = templates(&[]).unwrap();
+        
+        let body = templates.render("discord", &ArbitrageOpportunity::sample(SAMPLE_PATH, 0.0012), &[]);
+        assert!(body.starts_with("ARBITRAGE BTC_binance"));
+        assert!(body.contains("profit 0.12% | volume 100.00 USD"));
+    }
+    
+    #[test]
+    fn test_unknown_fields_and_bad_syntax_rejected() {
+        let err = templates(&[("webhook", "{{proffit}}")]).err().unwrap();
+        assert_eq!(err.channel, "webhook");
+        
+        assert!(templates(&[("discord", "{{#if path}}unclosed")]).is_err());
+    }
+    
+    #[test]
+    fn test_language_selected_per_channel() {
+        let languages = pairs(&[("telegram", "zh"), ("discord", "es"), ("webhook", "es")]);
+        let custom = pairs(&[("webhook", "{{path}}")]);
+        let templates = AlertTemplates::from_config(&custom, &languages, &DisplayFormat::default()).unwrap();
+        let opportunity = ArbitrageOpportunity::sample(SAMPLE_PATH, 0.0012);
+        
+        assert!(templates.render("telegram", &opportunity, &[]).starts_with("套利机会 BTC_binance"));
+        assert!(templates.render("discord", &opportunity, &[]).contains("beneficio 0.12%"));
+        assert!(templates.render("slack", &opportunity, &[]).starts_with("ARBITRAGE"));
+        // A channel's own template wins over its language
+        assert!(templates.render("webhook", &opportunity, &[]).starts_with("BTC_binance"));
+        
+        let err = AlertTemplates::from_config(&HashMap::new(), &pairs(&[("telegram", "fr")]), &DisplayFormat::default());
+        assert_eq!(err.err().unwrap().channel, "telegram");
+    }
+    
+    #[test]
+    fn test_reload_swaps_templates_and_keeps_them_on_error() {
+        let templates = templates(&[("telegram", "{{path}}")]).unwrap();
+        let opportunity = ArbitrageOpportunity::sample(SAMPLE_PATH, 0.0012);
+        let none = HashMap::new();
+        
+        templates.reload(&pairs(&[("telegram", "{{confidence}}")]), &none, &DisplayFormat::default()).unwrap();
+        assert_eq!(templates.render("telegram", &opportunity, &[]), "80");
+        
+        assert!(templates.reload(&pairs(&[("telegram", "{{proffit}}")]), &none, &DisplayFormat::default()).is_err());
+        assert_eq!(templates.render("telegram", &opportunity, &[]), "80");
+    }
+}